@@ -0,0 +1,160 @@
+use std::{
+    error::Error,
+    thread,
+    time::{Duration, Instant},
+};
+
+use clap::Args;
+use coapium::{
+    client::url::Url,
+    codec::{message::GetOptions, ResponseCode},
+    protocol::{get::Get, new_request::NewRequest, response},
+    synchronous::{client::ResponseHandle, default_reliability},
+};
+
+use crate::capture::Capture;
+use crate::common::parse_url;
+
+/// Sends GET requests against a URL and reports how the server kept up.
+#[derive(Clone, Args, Debug)]
+pub struct Bench {
+    #[arg(long, value_parser = parse_url)]
+    url: Url,
+
+    /// Number of requests to send
+    #[arg(long, default_value_t = 100)]
+    requests: u32,
+
+    /// Requests sent per second, as fast as possible when left out
+    #[arg(long)]
+    rate: Option<f64>,
+
+    /// Most requests waiting for a response at any time
+    #[arg(long, default_value_t = 1)]
+    concurrency: usize,
+}
+
+#[derive(Debug, Default)]
+struct Report {
+    client_errors: u32,
+    errors: u32,
+    latencies: Vec<Duration>,
+    retransmissions: u32,
+    server_errors: u32,
+    successes: u32,
+    timeouts: u32,
+}
+
+impl Bench {
+    pub fn url(&self) -> &Url {
+        &self.url
+    }
+
+    pub fn run(self, capture: &Capture) -> Result<(), Box<dyn Error>> {
+        if self.concurrency == 0 {
+            return Err("concurrency must be at least 1".into());
+        }
+        if self.rate.is_some_and(|rate| rate <= 0.0) {
+            return Err("rate must be larger than 0".into());
+        }
+
+        let client = capture.client(&self.url)?;
+        let interval = self
+            .rate
+            .map_or(Duration::ZERO, |rate| Duration::from_secs_f64(1.0 / rate));
+
+        let mut report = Report::default();
+        let mut in_flight: Vec<ResponseHandle> = vec![];
+        let mut sent = 0;
+        let started_at = Instant::now();
+
+        while sent < self.requests || !in_flight.is_empty() {
+            let next_send_at = started_at + interval * sent;
+            if sent < self.requests
+                && in_flight.len() < self.concurrency
+                && Instant::now() >= next_send_at
+            {
+                in_flight.push(client.execute(self.request()));
+                sent += 1;
+                continue;
+            }
+
+            let before = in_flight.len();
+            in_flight.retain(|handle| match handle.wait_timeout(Duration::ZERO) {
+                Some(result) => {
+                    report.add(result);
+                    false
+                }
+                None => true,
+            });
+
+            if in_flight.len() == before {
+                thread::sleep(Duration::from_millis(1));
+            }
+        }
+
+        report.print(started_at.elapsed());
+
+        Ok(())
+    }
+
+    fn request(&self) -> NewRequest {
+        NewRequest::Get(Get {
+            options: GetOptions::from(&self.url),
+            reliability: default_reliability(),
+        })
+    }
+}
+
+impl Report {
+    fn add(&mut self, result: Result<response::Response, response::Error>) {
+        match result {
+            Ok(response) => {
+                self.latencies.push(response.metrics.round_trip_time);
+                self.retransmissions += response.metrics.retransmissions as u32;
+                match response.response_code {
+                    ResponseCode::Success(_) => self.successes += 1,
+                    ResponseCode::ClientError(_) => self.client_errors += 1,
+                    ResponseCode::ServerError(_) => self.server_errors += 1,
+                }
+            }
+            Err(response::Error::Timeout | response::Error::AcknowledgementTimeout) => {
+                self.timeouts += 1
+            }
+            Err(_) => self.errors += 1,
+        }
+    }
+
+    fn print(mut self, elapsed: Duration) {
+        self.latencies.sort();
+        let responses = self.latencies.len();
+
+        println!("-- Bench --");
+        println!(
+            "{responses} responses in {:.2}s ({:.1} req/s)",
+            elapsed.as_secs_f64(),
+            responses as f64 / elapsed.as_secs_f64().max(f64::EPSILON)
+        );
+        println!("-- Latency --");
+        for (name, percentile) in [("p50", 50), ("p90", 90), ("p99", 99), ("max", 100)] {
+            match percentile_of(&self.latencies, percentile) {
+                Some(latency) => println!("{name}: {latency:?}"),
+                None => println!("{name}: -"),
+            }
+        }
+        println!("-- Responses --");
+        println!("2.xx: {}", self.successes);
+        println!("4.xx: {}", self.client_errors);
+        println!("5.xx: {}", self.server_errors);
+        println!("-- Failures --");
+        println!("timeouts: {}", self.timeouts);
+        println!("errors: {}", self.errors);
+        println!("retransmissions: {}", self.retransmissions);
+    }
+}
+
+/// Nearest-rank percentile of sorted latencies.
+fn percentile_of(latencies: &[Duration], percentile: usize) -> Option<Duration> {
+    let rank = (latencies.len() * percentile).div_ceil(100).max(1);
+    latencies.get(rank - 1).copied()
+}
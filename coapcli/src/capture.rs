@@ -0,0 +1,270 @@
+use std::{
+    error::Error,
+    fs::File,
+    io::Write,
+    net::{IpAddr, Ipv6Addr, SocketAddr},
+    path::PathBuf,
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+
+use clap::Args;
+use coapium::{
+    client::{
+        capture::{self, Direction},
+        resolve::SystemResolver,
+        socket_config::SocketConfig,
+        url::Url,
+    },
+    protocol::profile::TransmissionProfile,
+    synchronous::client::Client,
+};
+
+/// Raw IP packets without a link layer header, either IPv4 or IPv6.
+const LINKTYPE_RAW: u32 = 101;
+const UDP: u8 = 17;
+const HOP_LIMIT: u8 = 64;
+/// Largest record, an IPv6 header followed by the largest UDP datagram.
+const SNAPLEN: u32 = 40 + u16::MAX as u32;
+
+#[derive(Clone, Args, Debug)]
+pub struct Capture {
+    /// Write every datagram sent and received to a pcap file, with made up IP and UDP headers,
+    /// for Wireshark
+    #[arg(long, global = true)]
+    capture: Option<PathBuf>,
+}
+
+impl Capture {
+    /// Client for `url` that writes every datagram to the capture file, if one was asked for.
+    pub fn client(&self, url: &Url) -> Result<Client, Box<dyn Error>> {
+        let Some(path) = &self.capture else {
+            return Ok(Client::new(url.clone().into(), SocketConfig::default())?);
+        };
+
+        Ok(Client::with_capture(
+            vec![url.clone().into()],
+            SocketConfig::default(),
+            vec![],
+            SystemResolver,
+            TransmissionProfile::default(),
+            Box::new(PcapFile::create(path)?),
+        )?)
+    }
+}
+
+/// Capture file in the classic pcap format. Each record is written out right away, so the file
+/// is complete even if the CLI exits while the client still runs.
+#[derive(Debug)]
+struct PcapFile {
+    file: File,
+}
+
+impl PcapFile {
+    fn create(path: &PathBuf) -> Result<Self, Box<dyn Error>> {
+        let mut file =
+            File::create(path).map_err(|e| format!("failed to create {}: {e}", path.display()))?;
+        file.write_all(&global_header())?;
+
+        Ok(Self { file })
+    }
+}
+
+impl capture::Capture for PcapFile {
+    fn datagram(&mut self, direction: Direction, local: SocketAddr, peer: SocketAddr, data: &[u8]) {
+        let (source, destination) = match direction {
+            Direction::Sent => (local, peer),
+            Direction::Received => (peer, local),
+        };
+        let timestamp = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap_or_default();
+
+        let result = record(timestamp, source, destination, data)
+            .and_then(|record| self.file.write_all(&record).map_err(|e| e.to_string()));
+        if let Err(e) = result {
+            eprintln!("Failed to write capture: {e}");
+        }
+    }
+}
+
+/// Header of a classic pcap file with microsecond timestamps.
+fn global_header() -> Vec<u8> {
+    let mut header = vec![];
+    header.extend_from_slice(&0xa1b2_c3d4_u32.to_le_bytes());
+    header.extend_from_slice(&2_u16.to_le_bytes());
+    header.extend_from_slice(&4_u16.to_le_bytes());
+    header.extend_from_slice(&0_i32.to_le_bytes());
+    header.extend_from_slice(&0_u32.to_le_bytes());
+    header.extend_from_slice(&SNAPLEN.to_le_bytes());
+    header.extend_from_slice(&LINKTYPE_RAW.to_le_bytes());
+    header
+}
+
+/// Record of `data` sent from `source` to `destination` at `timestamp` since the Unix epoch.
+fn record(
+    timestamp: Duration,
+    source: SocketAddr,
+    destination: SocketAddr,
+    data: &[u8],
+) -> Result<Vec<u8>, String> {
+    let packet = packet(source, destination, data)?;
+    let length = u32::try_from(packet.len())
+        .map_err(|_| format!("packet of {} bytes is too large", packet.len()))?;
+
+    let mut record = vec![];
+    record.extend_from_slice(&(timestamp.as_secs() as u32).to_le_bytes());
+    record.extend_from_slice(&timestamp.subsec_micros().to_le_bytes());
+    record.extend_from_slice(&length.to_le_bytes());
+    record.extend_from_slice(&length.to_le_bytes());
+    record.extend_from_slice(&packet);
+
+    Ok(record)
+}
+
+/// `data` in a UDP datagram in an IP packet from `source` to `destination`. An IPv4 address
+/// talking to an IPv6 one is written as an IPv4-mapped IPv6 address. Fails when `data` doesn't
+/// fit the 16 bit length fields of the headers.
+fn packet(source: SocketAddr, destination: SocketAddr, data: &[u8]) -> Result<Vec<u8>, String> {
+    let too_large = |packet| format!("datagram of {} bytes is too large for {packet}", data.len());
+    let udp_length = u32::try_from(data.len())
+        .ok()
+        .and_then(|length| length.checked_add(8))
+        .and_then(|length| u16::try_from(length).ok())
+        .ok_or_else(|| too_large("UDP"))?;
+
+    let mut udp = vec![];
+    udp.extend_from_slice(&source.port().to_be_bytes());
+    udp.extend_from_slice(&destination.port().to_be_bytes());
+    udp.extend_from_slice(&udp_length.to_be_bytes());
+    udp.extend_from_slice(&[0, 0]);
+    udp.extend_from_slice(data);
+
+    let mut packet = vec![];
+    match (source.ip(), destination.ip()) {
+        (IpAddr::V4(source), IpAddr::V4(destination)) => {
+            let total_length =
+                u16::try_from(20 + u32::from(udp_length)).map_err(|_| too_large("IPv4"))?;
+            packet.extend_from_slice(&[0x45, 0]);
+            packet.extend_from_slice(&total_length.to_be_bytes());
+            packet.extend_from_slice(&[0, 0, 0x40, 0, HOP_LIMIT, UDP, 0, 0]);
+            packet.extend_from_slice(&source.octets());
+            packet.extend_from_slice(&destination.octets());
+            let checksum = checksum(&packet);
+            packet[10..12].copy_from_slice(&checksum.to_be_bytes());
+            // The UDP checksum is optional over IPv4 and left at 0.
+        }
+        (source, destination) => {
+            let source = to_ipv6(source).octets();
+            let destination = to_ipv6(destination).octets();
+
+            let mut pseudo_header = vec![];
+            pseudo_header.extend_from_slice(&source);
+            pseudo_header.extend_from_slice(&destination);
+            pseudo_header.extend_from_slice(&u32::from(udp_length).to_be_bytes());
+            pseudo_header.extend_from_slice(&[0, 0, 0, UDP]);
+            pseudo_header.extend_from_slice(&udp);
+            let checksum = match checksum(&pseudo_header) {
+                0 => 0xffff,
+                checksum => checksum,
+            };
+            udp[6..8].copy_from_slice(&checksum.to_be_bytes());
+
+            packet.extend_from_slice(&[0x60, 0, 0, 0]);
+            packet.extend_from_slice(&udp_length.to_be_bytes());
+            packet.extend_from_slice(&[UDP, HOP_LIMIT]);
+            packet.extend_from_slice(&source);
+            packet.extend_from_slice(&destination);
+        }
+    }
+    packet.extend_from_slice(&udp);
+
+    Ok(packet)
+}
+
+fn to_ipv6(address: IpAddr) -> Ipv6Addr {
+    match address {
+        IpAddr::V4(address) => address.to_ipv6_mapped(),
+        IpAddr::V6(address) => address,
+    }
+}
+
+/// Internet checksum, RFC 1071.
+fn checksum(bytes: &[u8]) -> u16 {
+    let mut sum = bytes
+        .chunks(2)
+        .map(|chunk| u32::from(u16::from_be_bytes([chunk[0], *chunk.get(1).unwrap_or(&0)])))
+        .sum::<u32>();
+    while sum > 0xffff {
+        sum = (sum & 0xffff) + (sum >> 16);
+    }
+
+    !(sum as u16)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{net::SocketAddr, time::Duration};
+
+    use pretty_assertions::assert_eq;
+    use rstest::rstest;
+
+    use super::{global_header, packet, record};
+
+    #[test]
+    fn global_header_is_little_endian_raw_ip() {
+        assert_eq!(
+            vec![
+                0xd4, 0xc3, 0xb2, 0xa1, 0x02, 0x00, 0x04, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+                0x00, 0x00, 0x27, 0x00, 0x01, 0x00, 0x65, 0x00, 0x00, 0x00,
+            ],
+            global_header()
+        );
+    }
+
+    #[rstest]
+    #[case(
+        "127.0.0.1:40000",
+        "127.0.0.1:5683",
+        vec![
+            0x00, 0xf1, 0x53, 0x65, 0x40, 0xe2, 0x01, 0x00, 0x20, 0x00, 0x00, 0x00, 0x20, 0x00,
+            0x00, 0x00, 0x45, 0x00, 0x00, 0x20, 0x00, 0x00, 0x40, 0x00, 0x40, 0x11, 0x3c, 0xcb,
+            0x7f, 0x00, 0x00, 0x01, 0x7f, 0x00, 0x00, 0x01, 0x9c, 0x40, 0x16, 0x33, 0x00, 0x0c,
+            0x00, 0x00, 0x40, 0x01, 0x12, 0x34,
+        ]
+    )]
+    #[case(
+        "[::1]:40000",
+        "[2001:db8::1]:5683",
+        vec![
+            0x00, 0xf1, 0x53, 0x65, 0x40, 0xe2, 0x01, 0x00, 0x34, 0x00, 0x00, 0x00, 0x34, 0x00,
+            0x00, 0x00, 0x60, 0x00, 0x00, 0x00, 0x00, 0x0c, 0x11, 0x40, 0x00, 0x00, 0x00, 0x00,
+            0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x01, 0x20, 0x01,
+            0x0d, 0xb8, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x01,
+            0x9c, 0x40, 0x16, 0x33, 0x00, 0x0c, 0xcd, 0x72, 0x40, 0x01, 0x12, 0x34,
+        ]
+    )]
+    fn record_of_datagram(
+        #[case] source: SocketAddr,
+        #[case] destination: SocketAddr,
+        #[case] expected: Vec<u8>,
+    ) {
+        let timestamp = Duration::new(1_700_000_000, 123_456_000);
+
+        assert_eq!(
+            Ok(expected),
+            record(timestamp, source, destination, &[0x40, 0x01, 0x12, 0x34])
+        );
+    }
+
+    #[rstest]
+    #[case("127.0.0.1:40000", "127.0.0.1:5683", 65_508)]
+    #[case("[::1]:40000", "[::1]:5683", 65_528)]
+    fn packet_rejects_datagram_too_large(
+        #[case] source: SocketAddr,
+        #[case] destination: SocketAddr,
+        #[case] length: usize,
+    ) {
+        assert!(packet(source, destination, &vec![0; length]).is_err());
+        assert!(packet(source, destination, &vec![0; length - 1]).is_ok());
+    }
+}
@@ -2,10 +2,17 @@ use std::error::Error;
 
 use clap::{command, Parser, Subcommand};
 
-use crate::{delete::Delete, get::Get, ping::Ping, post::Post, put::Put};
+use coapium::{client::url::Url, codec::url::Scheme};
+
+use crate::{
+    bench::Bench, capture::Capture, decode::Decode, delete::Delete, get::Get, output::Output,
+    ping::Ping, post::Post, put::Put,
+};
 
 #[derive(Debug, Clone, Subcommand)]
 enum Commands {
+    Bench(Bench),
+    Decode(Decode),
     Delete(Delete),
     Get(Get),
     Ping(Ping),
@@ -18,18 +25,50 @@ enum Commands {
 pub struct Cli {
     #[command(subcommand)]
     commands: Commands,
+
+    #[command(flatten)]
+    capture: Capture,
+
+    #[command(flatten)]
+    output: Output,
+}
+
+impl Commands {
+    /// URL the command talks to, `None` for commands that work offline.
+    fn url(&self) -> Option<&Url> {
+        match self {
+            Commands::Bench(command) => Some(command.url()),
+            Commands::Decode(_) => None,
+            Commands::Delete(command) => Some(command.url()),
+            Commands::Get(command) => Some(command.url()),
+            Commands::Ping(command) => Some(command.url()),
+            Commands::Post(command) => Some(command.url()),
+            Commands::Put(command) => Some(command.url()),
+        }
+    }
 }
 
 impl Cli {
     pub fn run() -> Result<(), Box<dyn Error>> {
         let cli = Cli::parse();
+        // coapium only talks plain CoAP for now, so a `coaps` URL is an error instead of
+        // silently sending in the clear.
+        if cli
+            .commands
+            .url()
+            .is_some_and(|url| url.scheme == Scheme::Coaps)
+        {
+            return Err("DTLS is not supported yet, use a coap URL".into());
+        }
 
         match cli.commands {
-            Commands::Delete(command) => command.run(),
-            Commands::Get(command) => command.run(),
-            Commands::Ping(command) => command.run(),
-            Commands::Post(command) => command.run(),
-            Commands::Put(command) => command.run(),
+            Commands::Bench(command) => command.run(&cli.capture),
+            Commands::Decode(command) => command.run(),
+            Commands::Delete(command) => command.run(&cli.capture, &cli.output),
+            Commands::Get(command) => command.run(&cli.capture, &cli.output),
+            Commands::Ping(command) => command.run(&cli.capture),
+            Commands::Post(command) => command.run(&cli.capture, &cli.output),
+            Commands::Put(command) => command.run(&cli.capture, &cli.output),
         }
     }
 }
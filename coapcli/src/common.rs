@@ -1,10 +1,27 @@
+use ciborium::value::Value as Cbor;
 use coapium::{
-    client::url::Url,
-    codec::{option::ContentFormat, MediaType},
+    client::url::{self, Url},
+    codec::{option::ContentFormat, MediaType, Payload},
 };
+use serde_json::Value as Json;
 
 pub fn parse_url(s: &str) -> Result<Url, String> {
-    Ok(s.try_into().map_err(|e| format!("{:?}", e))?)
+    s.try_into().map_err(|e: url::Error| e.to_string())
+}
+
+pub fn parse_hex(s: &str) -> Result<Vec<u8>, String> {
+    if s.len() % 2 != 0 {
+        return Err("hex must have an even number of digits".to_owned());
+    }
+
+    (0..s.len())
+        .step_by(2)
+        .map(|i| {
+            s.get(i..i + 2)
+                .and_then(|digits| u8::from_str_radix(digits, 16).ok())
+                .ok_or_else(|| format!("invalid hex digits at {i}"))
+        })
+        .collect()
 }
 
 pub fn parse_content_format(s: &str) -> Result<ContentFormat, String> {
@@ -18,3 +35,78 @@ pub fn parse_content_format(s: &str) -> Result<ContentFormat, String> {
 
     Ok(MediaType::from_value(number).into())
 }
+
+/// Checks that the payload is valid for the content format, for the formats that can be checked.
+pub fn validate_payload(content_format: &ContentFormat, payload: &Payload) -> Result<(), String> {
+    let payload = payload.value();
+
+    let result = match content_format.media_type() {
+        MediaType::ApplicationJson => serde_json::from_slice::<Json>(payload)
+            .map(|_| ())
+            .map_err(|e| e.to_string()),
+        MediaType::ApplicationCbor => ciborium::de::from_reader::<Cbor, _>(payload)
+            .map(|_| ())
+            .map_err(|e| e.to_string()),
+        MediaType::ApplicationLinkFormat => validate_link_format(payload),
+        _ => Ok(()),
+    };
+
+    result.map_err(|e| {
+        format!(
+            "payload is not valid {}: {e}",
+            name(content_format.media_type())
+        )
+    })
+}
+
+fn name(media_type: &MediaType) -> &'static str {
+    match media_type {
+        MediaType::ApplicationCbor => "application/cbor",
+        MediaType::ApplicationJson => "application/json",
+        MediaType::ApplicationLinkFormat => "application/link-format",
+        _ => "payload",
+    }
+}
+
+/// Checks the outline of each link in a link-format document, `<target>` followed by `;`
+/// separated parameters.
+fn validate_link_format(payload: &[u8]) -> Result<(), String> {
+    let links = std::str::from_utf8(payload).map_err(|e| e.to_string())?;
+
+    for (index, link) in split_links(links).into_iter().enumerate() {
+        let link = link.trim();
+        let valid = link
+            .strip_prefix('<')
+            .and_then(|link| link.split_once('>'))
+            .is_some_and(|(_, parameters)| parameters.is_empty() || parameters.starts_with(';'));
+
+        if !valid {
+            return Err(format!(
+                "link {} `{link}` is not of the form <target>;params",
+                index + 1
+            ));
+        }
+    }
+
+    Ok(())
+}
+
+fn split_links(links: &str) -> Vec<&str> {
+    let mut result = vec![];
+    let mut quoted = false;
+    let mut start = 0;
+
+    for (i, c) in links.char_indices() {
+        match c {
+            '"' => quoted = !quoted,
+            ',' if !quoted => {
+                result.push(&links[start..i]);
+                start = i + 1;
+            }
+            _ => {}
+        }
+    }
+    result.push(&links[start..]);
+
+    result
+}
@@ -0,0 +1,45 @@
+use std::{error::Error, fs, path::Path};
+
+use clap::Args;
+use coapium::codec::Message;
+
+use crate::common::parse_hex;
+
+/// Takes a raw message apart and prints it field by field, with the bytes of each field.
+#[derive(Clone, Args, Debug)]
+pub struct Decode {
+    /// Message as hex digits, spaces and colons allowed, or the path of a file holding it
+    input: String,
+}
+
+impl Decode {
+    pub fn run(self) -> Result<(), Box<dyn Error>> {
+        let bytes = self.bytes()?;
+        let dissection = Message::dissect(&bytes);
+
+        print!("{dissection}");
+
+        if dissection.is_valid() {
+            Ok(())
+        } else {
+            Err("message is malformed".into())
+        }
+    }
+
+    fn bytes(&self) -> Result<Vec<u8>, Box<dyn Error>> {
+        let path = Path::new(&self.input);
+        if path.is_file() {
+            return fs::read(path)
+                .map_err(|e| format!("failed to read {}: {e}", path.display()).into());
+        }
+
+        let digits: String = self
+            .input
+            .trim_start_matches("0x")
+            .chars()
+            .filter(|c| !c.is_whitespace() && *c != ':')
+            .collect();
+
+        Ok(parse_hex(&digits)?)
+    }
+}
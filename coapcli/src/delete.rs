@@ -1,9 +1,16 @@
 use std::error::Error;
 
 use clap::Args;
-use coapium::{client::url::Url, synchronous::delete};
+use coapium::{
+    client::url::Url,
+    codec::message::DeleteOptions,
+    protocol::{delete::Delete as DeleteRequest, new_request::NewRequest},
+    synchronous::default_reliability,
+};
 
+use crate::capture::Capture;
 use crate::common::parse_url;
+use crate::output::Output;
 
 #[derive(Clone, Args, Debug)]
 pub struct Delete {
@@ -12,16 +19,17 @@ pub struct Delete {
 }
 
 impl Delete {
-    pub fn run(self) -> Result<(), Box<dyn Error>> {
-        let response = delete(self.url).unwrap();
+    pub fn url(&self) -> &Url {
+        &self.url
+    }
 
-        println!("-- Response code --\n{:?}", response.response_code);
-        if let Ok(payload) = String::from_utf8(response.payload.value().to_vec()) {
-            println!("-- Payload -- \n{payload}");
-        } else {
-            println!("-- Payload -- \n{:?}", response.payload.value());
-        }
+    pub fn run(self, capture: &Capture, output: &Output) -> Result<(), Box<dyn Error>> {
+        let request = NewRequest::Delete(DeleteRequest {
+            options: DeleteOptions::from(&self.url),
+            reliability: default_reliability(),
+        });
+        let response = capture.client(&self.url)?.execute(request).wait()?;
 
-        Ok(())
+        output.print(&response)
     }
 }
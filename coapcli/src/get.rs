@@ -1,9 +1,16 @@
 use std::error::Error;
 
 use clap::Args;
-use coapium::{client::url::Url, synchronous::get};
-
+use coapium::{
+    client::url::Url,
+    codec::message::GetOptions,
+    protocol::{get::Get as GetRequest, new_request::NewRequest},
+    synchronous::default_reliability,
+};
+
+use crate::capture::Capture;
 use crate::common::parse_url;
+use crate::output::Output;
 
 // TODO: There are two main ways of doing requests.
 // Either assume that all the values are urlencoded already or not.
@@ -37,16 +44,17 @@ pub struct Get {
 }
 
 impl Get {
-    pub fn run(self) -> Result<(), Box<dyn Error>> {
-        let response = get(self.url).unwrap();
+    pub fn url(&self) -> &Url {
+        &self.url
+    }
 
-        println!("-- Response code --\n{:?}", response.response_code);
-        if let Ok(payload) = String::from_utf8(response.payload.value().to_vec()) {
-            println!("-- Payload -- \n{payload}");
-        } else {
-            println!("-- Payload -- \n{:?}", response.payload.value());
-        }
+    pub fn run(self, capture: &Capture, output: &Output) -> Result<(), Box<dyn Error>> {
+        let request = NewRequest::Get(GetRequest {
+            options: GetOptions::from(&self.url),
+            reliability: default_reliability(),
+        });
+        let response = capture.client(&self.url)?.execute(request).wait()?;
 
-        Ok(())
+        output.print(&response)
     }
 }
@@ -1,7 +1,11 @@
+mod bench;
+mod capture;
 mod cli;
 mod common;
+mod decode;
 mod delete;
 mod get;
+mod output;
 mod ping;
 mod post;
 mod put;
@@ -0,0 +1,294 @@
+use std::{
+    error::Error,
+    io::{stdout, Write},
+};
+
+use ciborium::value::Value as Cbor;
+use clap::{Args, ValueEnum};
+use coapium::{
+    codec::{
+        option::{number::registry::Format as OptionFormat, value::Value},
+        Code,
+    },
+    protocol::response::Response,
+};
+use serde_json::{json, Value as Json};
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, ValueEnum)]
+pub enum Format {
+    /// Response code and payload, the payload as text when it is valid UTF-8
+    #[default]
+    Text,
+    /// Payload bytes as is
+    Raw,
+    /// Hexdump of the payload
+    Hex,
+    /// Payload as JSON
+    Json,
+    /// Payload decoded as CBOR and printed in diagnostic notation
+    CborDiag,
+}
+
+#[derive(Clone, Args, Debug)]
+pub struct Output {
+    /// How the response payload is printed
+    #[arg(long = "output", global = true, value_enum, default_value_t)]
+    format: Format,
+
+    /// Print response code, options and payload as a JSON envelope
+    #[arg(long, global = true)]
+    include_meta: bool,
+}
+
+impl Output {
+    pub fn print(&self, response: &Response) -> Result<(), Box<dyn Error>> {
+        if self.include_meta {
+            println!("{}", self.envelope(response)?);
+            return Ok(());
+        }
+
+        let payload = response.payload.value();
+        match self.format {
+            Format::Text => {
+                println!("-- Response code --\n{:?}", response.response_code);
+                if let Ok(payload) = String::from_utf8(payload.to_vec()) {
+                    println!("-- Payload -- \n{payload}");
+                } else {
+                    println!("-- Payload -- \n{:?}", payload);
+                }
+            }
+            Format::Raw => stdout().write_all(payload)?,
+            Format::Hex => print!("{}", hexdump(payload)),
+            Format::Json => println!("{}", serde_json::to_string_pretty(&json(payload)?)?),
+            Format::CborDiag => println!("{}", diagnostic(&cbor(payload)?)),
+        }
+
+        Ok(())
+    }
+
+    fn envelope(&self, response: &Response) -> Result<String, Box<dyn Error>> {
+        let payload = response.payload.value();
+        let rendered_payload = match self.format {
+            Format::Text | Format::Raw => Json::String(String::from_utf8_lossy(payload).into()),
+            Format::Hex => Json::String(hex(payload)),
+            Format::Json => json(payload)?,
+            Format::CborDiag => Json::String(diagnostic(&cbor(payload)?)),
+        };
+
+        let options = response
+            .options
+            .options()
+            .iter()
+            .map(|option| {
+                let option = option.clone().into_decoded();
+                let format = option
+                    .number
+                    .registration()
+                    .map(|registration| registration.format);
+                let mut values = option
+                    .values
+                    .into_iter()
+                    .map(|value| option_value(format, value))
+                    .collect::<Vec<_>>();
+                let value = match (option.number.is_repeatable(), values.len()) {
+                    (false, 1) => values.remove(0),
+                    _ => Json::Array(values),
+                };
+
+                json!({
+                    "number": option.number.value.value(),
+                    "name": option.number.name(),
+                    "value": value,
+                })
+            })
+            .collect::<Vec<_>>();
+
+        Ok(serde_json::to_string_pretty(&json!({
+            "code": Code::Response(response.response_code).to_string(),
+            "options": options,
+            "payload": rendered_payload,
+        }))?)
+    }
+}
+
+/// Value of an option as the registered format says, opaque values and those of options that
+/// aren't registered as hex.
+fn option_value(format: Option<OptionFormat>, value: Value) -> Json {
+    match format {
+        Some(OptionFormat::Empty) => Json::Null,
+        Some(OptionFormat::Uint) => match value.u32() {
+            Ok(uint) => Json::from(uint),
+            Err(()) => Json::String(hex(&value.opaque())),
+        },
+        Some(OptionFormat::String) if value.valid_as_string() => {
+            Json::String(value.string().unwrap_or_default())
+        }
+        _ => Json::String(hex(&value.opaque())),
+    }
+}
+
+fn json(payload: &[u8]) -> Result<Json, Box<dyn Error>> {
+    serde_json::from_slice(payload).map_err(|e| format!("payload is not valid JSON: {e}").into())
+}
+
+fn cbor(payload: &[u8]) -> Result<Cbor, Box<dyn Error>> {
+    ciborium::de::from_reader(payload).map_err(|e| format!("payload is not valid CBOR: {e}").into())
+}
+
+fn hex(payload: &[u8]) -> String {
+    payload.iter().map(|byte| format!("{byte:02x}")).collect()
+}
+
+fn hexdump(payload: &[u8]) -> String {
+    payload
+        .chunks(16)
+        .enumerate()
+        .map(|(line, chunk)| {
+            let bytes = chunk
+                .iter()
+                .map(|byte| format!("{byte:02x}"))
+                .collect::<Vec<_>>()
+                .join(" ");
+            let text = chunk
+                .iter()
+                .map(|byte| match byte {
+                    0x20..=0x7e => *byte as char,
+                    _ => '.',
+                })
+                .collect::<String>();
+
+            format!("{:08x}  {bytes:<47}  |{text}|\n", line * 16)
+        })
+        .collect()
+}
+
+/// Renders a CBOR value in the diagnostic notation of
+/// [RFC 8949](https://datatracker.ietf.org/doc/html/rfc8949#section-8).
+fn diagnostic(value: &Cbor) -> String {
+    match value {
+        Cbor::Integer(integer) => i128::from(*integer).to_string(),
+        Cbor::Bytes(bytes) => format!("h'{}'", hex(bytes)),
+        Cbor::Float(float) if float.is_nan() => "NaN".to_owned(),
+        Cbor::Float(float) if float.is_infinite() => {
+            if float.is_sign_positive() {
+                "Infinity".to_owned()
+            } else {
+                "-Infinity".to_owned()
+            }
+        }
+        Cbor::Float(float) => format!("{float:?}"),
+        // Text strings are written as JSON strings, with the same escapes.
+        Cbor::Text(text) => Json::String(text.clone()).to_string(),
+        Cbor::Bool(bool) => bool.to_string(),
+        Cbor::Null => "null".to_owned(),
+        Cbor::Tag(tag, value) => format!("{tag}({})", diagnostic(value)),
+        Cbor::Array(values) => format!(
+            "[{}]",
+            values.iter().map(diagnostic).collect::<Vec<_>>().join(", ")
+        ),
+        Cbor::Map(entries) => format!(
+            "{{{}}}",
+            entries
+                .iter()
+                .map(|(key, value)| format!("{}: {}", diagnostic(key), diagnostic(value)))
+                .collect::<Vec<_>>()
+                .join(", ")
+        ),
+        _ => "undefined".to_owned(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use ciborium::value::Value as Cbor;
+    use coapium::{
+        codec::{
+            self,
+            message::reliability::Reliability,
+            option::{ContentFormat, ETag, LocationPath, MaxAge},
+            MessageId, Options, Payload, ResponseCode, Token,
+        },
+        protocol::response::Response,
+    };
+    use pretty_assertions::assert_eq;
+    use rstest::rstest;
+    use serde_json::json;
+
+    use super::{diagnostic, hexdump, Format, Output};
+
+    #[rstest]
+    #[case(vec![], "")]
+    #[case(
+        b"Hello, CoAP!\x00\x01\x7f\xff".to_vec(),
+        "00000000  48 65 6c 6c 6f 2c 20 43 6f 41 50 21 00 01 7f ff  |Hello, CoAP!....|\n"
+    )]
+    #[case(
+        (0x41..0x53).collect(),
+        "00000000  41 42 43 44 45 46 47 48 49 4a 4b 4c 4d 4e 4f 50  |ABCDEFGHIJKLMNOP|\n\
+         00000010  51 52                                            |QR|\n"
+    )]
+    fn hexdump_of_payload(#[case] payload: Vec<u8>, #[case] expected: &str) {
+        assert_eq!(expected, hexdump(&payload));
+    }
+
+    #[rstest]
+    #[case(Cbor::Integer((-5).into()), "-5")]
+    #[case(Cbor::Bytes(vec![0x01, 0xab]), "h'01ab'")]
+    #[case(Cbor::Float(1.5), "1.5")]
+    #[case(Cbor::Float(f64::INFINITY), "Infinity")]
+    #[case(Cbor::Float(f64::NEG_INFINITY), "-Infinity")]
+    #[case(Cbor::Float(f64::NAN), "NaN")]
+    #[case(Cbor::Text("a\"b\\c\n\u{1}é".to_owned()), r#""a\"b\\c\n\u0001é""#)]
+    #[case(Cbor::Bool(true), "true")]
+    #[case(Cbor::Null, "null")]
+    #[case(Cbor::Tag(1, Box::new(Cbor::Integer(0.into()))), "1(0)")]
+    #[case(
+        Cbor::Array(vec![Cbor::Integer(1.into()), Cbor::Text("x".to_owned())]),
+        r#"[1, "x"]"#
+    )]
+    #[case(
+        Cbor::Map(vec![(Cbor::Integer(1.into()), Cbor::Array(vec![]))]),
+        "{1: []}"
+    )]
+    fn diagnostic_of_value(#[case] value: Cbor, #[case] expected: &str) {
+        assert_eq!(expected, diagnostic(&value));
+    }
+
+    #[rstest]
+    fn envelope_has_typed_option_values() {
+        let mut options = Options::new();
+        options.set_content_format(ContentFormat::from(50));
+        options.set_etag(ETag::from_values(vec![vec![0x01, 0x02]]).unwrap());
+        options.set_location_path(LocationPath::from_value("/rd/4521").unwrap());
+        options.set_max_age(MaxAge::default());
+        let response = Response::from(codec::Response::new(
+            Reliability::Confirmable,
+            Token::empty(),
+            ResponseCode::CONTENT,
+            MessageId::from_value(1),
+            options,
+            Payload::from_value(br#"{"on":true}"#.to_vec()),
+        ));
+        let output = Output {
+            format: Format::Json,
+            include_meta: true,
+        };
+
+        let envelope: serde_json::Value =
+            serde_json::from_str(&output.envelope(&response).unwrap()).unwrap();
+
+        assert_eq!(
+            json!({
+                "code": "2.05",
+                "options": [
+                    {"number": 12, "name": "Content-Format", "value": 50},
+                    {"number": 4, "name": "ETag", "value": ["0102"]},
+                    {"number": 8, "name": "Location-Path", "value": ["rd", "4521"]},
+                    {"number": 14, "name": "Max-Age", "value": 60},
+                ],
+                "payload": {"on": true},
+            }),
+            envelope
+        );
+    }
+}
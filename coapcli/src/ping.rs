@@ -1,22 +1,128 @@
-use std::error::Error;
+use std::{
+    error::Error,
+    sync::mpsc::channel,
+    thread,
+    time::{Duration, Instant},
+};
 
 use clap::Args;
-use coapium::{client::url::Url, synchronous::ping};
+use coapium::{
+    client::url::Url,
+    protocol::ping::{self, Ping as PingRequest},
+    synchronous::{client::Client, default_parameters},
+};
 
+use crate::capture::Capture;
 use crate::common::parse_url;
 
+/// Sends empty confirmable messages and reports how long the server took to reset them.
 #[derive(Clone, Args, Debug)]
 pub struct Ping {
     #[arg(long, value_parser = parse_url)]
     url: Url,
+
+    /// Number of pings to send
+    #[arg(long, default_value_t = 4)]
+    count: u32,
+
+    /// Seconds between the start of each ping
+    #[arg(long, default_value_t = 1.0)]
+    interval: f64,
+
+    /// Seconds to wait for a reset before counting a ping as lost
+    #[arg(long, default_value_t = 5.0)]
+    timeout: f64,
+}
+
+#[derive(Debug, Default)]
+struct Report {
+    round_trip_times: Vec<Duration>,
+    sent: u32,
 }
 
 impl Ping {
-    pub fn run(self) -> Result<(), Box<dyn Error>> {
-        ping(self.url).unwrap();
+    pub fn url(&self) -> &Url {
+        &self.url
+    }
+
+    pub fn run(self, capture: &Capture) -> Result<(), Box<dyn Error>> {
+        if self.count == 0 {
+            return Err("count must be at least 1".into());
+        }
+        let interval = Duration::try_from_secs_f64(self.interval)
+            .map_err(|_| "interval must be a positive number of seconds")?;
+        let timeout = Duration::try_from_secs_f64(self.timeout)
+            .ok()
+            .filter(|timeout| !timeout.is_zero())
+            .ok_or("timeout must be larger than 0")?;
+
+        let client = capture.client(&self.url)?;
+        let mut report = Report::default();
+
+        println!("-- Ping {} --", self.url.host);
+        for sequence in 1..=self.count {
+            let started_at = Instant::now();
+
+            report.sent += 1;
+            match ping(&client, timeout) {
+                Some(Ok(())) => {
+                    let round_trip_time = started_at.elapsed();
+                    println!("seq={sequence} time={}", millis(round_trip_time));
+                    report.round_trip_times.push(round_trip_time);
+                }
+                Some(Err(e)) => println!("seq={sequence} error: {e}"),
+                None => println!("seq={sequence} timeout"),
+            }
+
+            if sequence < self.count {
+                thread::sleep(interval.saturating_sub(started_at.elapsed()));
+            }
+        }
 
-        println!("-- Ping response --\n");
+        report.print();
 
         Ok(())
     }
 }
+
+/// Pings with the client, `None` when no reset arrived within `timeout`. The ping carries on in
+/// the background until the client gives up on it.
+fn ping(client: &Client, timeout: Duration) -> Option<Result<(), ping::Error>> {
+    let (sender, receiver) = channel();
+    let client = client.clone();
+    thread::spawn(move || {
+        let _ = sender.send(client.ping(PingRequest {
+            confirmable_parameters: default_parameters(),
+        }));
+    });
+
+    receiver.recv_timeout(timeout).ok()
+}
+
+impl Report {
+    fn print(&self) {
+        let received = self.round_trip_times.len() as u32;
+        let loss = 100.0 * (self.sent - received) as f64 / self.sent as f64;
+
+        println!("-- Statistics --");
+        println!("{} sent, {received} received, {loss:.1}% loss", self.sent);
+
+        let (Some(min), Some(max)) = (
+            self.round_trip_times.iter().min(),
+            self.round_trip_times.iter().max(),
+        ) else {
+            return;
+        };
+        let avg = self.round_trip_times.iter().sum::<Duration>() / received;
+        println!(
+            "rtt min/avg/max: {}/{}/{}",
+            millis(*min),
+            millis(avg),
+            millis(*max)
+        );
+    }
+}
+
+fn millis(duration: Duration) -> String {
+    format!("{:.3}ms", duration.as_secs_f64() * 1000.0)
+}
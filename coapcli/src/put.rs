@@ -6,11 +6,14 @@ use std::{
 use clap::Args;
 use coapium::{
     client::url::Url,
-    codec::{option::ContentFormat, MediaType, Payload},
-    synchronous::{put, put_payload},
+    codec::{message::PutOptions, option::ContentFormat, MediaType, Payload},
+    protocol::{new_request::NewRequest, put::Put as PutRequest},
+    synchronous::default_reliability,
 };
 
-use crate::common::{parse_content_format, parse_url};
+use crate::capture::Capture;
+use crate::common::{parse_content_format, parse_url, validate_payload};
+use crate::output::Output;
 
 #[derive(Clone, Args, Debug)]
 pub struct Put {
@@ -25,24 +28,26 @@ pub struct Put {
 }
 
 impl Put {
-    pub fn run(self) -> Result<(), Box<dyn Error>> {
-        let payload = self.payload()?;
+    pub fn url(&self) -> &Url {
+        &self.url
+    }
 
-        let response = if payload.is_empty() {
-            put(self.url)
-        } else {
-            put_payload(self.url.clone(), self.content_format(), payload)
-        }
-        .map_err(|e| format!("{:?}", e))?;
+    pub fn run(self, capture: &Capture, output: &Output) -> Result<(), Box<dyn Error>> {
+        let payload = self.payload()?;
 
-        println!("-- Response code --\n{:?}", response.response_code);
-        if let Ok(payload) = String::from_utf8(response.payload.value().to_vec()) {
-            println!("-- Payload -- \n{payload}");
-        } else {
-            println!("-- Payload -- \n{:?}", response.payload.value());
+        let mut options = PutOptions::from(&self.url);
+        if !payload.is_empty() {
+            validate_payload(&self.content_format(), &payload)?;
+            options.set_content_format(self.content_format());
         }
+        let request = NewRequest::Put(PutRequest {
+            options,
+            reliability: default_reliability(),
+            payload,
+        });
+        let response = capture.client(&self.url)?.execute(request).wait()?;
 
-        Ok(())
+        output.print(&response)
     }
 
     fn stdin_payload() -> Result<Payload, Box<dyn Error>> {
@@ -0,0 +1,69 @@
+//! Lookups in a [`TransactionStore`] holding 10k in-flight NON transactions.
+//!
+//! Run with `cargo bench --bench transaction_store`.
+
+use std::{hint::black_box, time::Instant};
+
+use coapium::{
+    codec::{message::GetOptions, MessageId, Token},
+    protocol::{
+        get::Get, new_request::NewRequest, reliability::Reliability, transaction::Transaction,
+        transaction_store::TransactionStore, transmission_parameters::NonConfirmableParameters,
+    },
+};
+
+const IN_FLIGHT: u16 = 10_000;
+const ROUNDS: u32 = 20;
+
+fn token(index: u16) -> Token {
+    let [high, low] = index.to_be_bytes();
+    Token::from_slice(&[0xc0, 0xa9, high, low]).unwrap()
+}
+
+fn store() -> TransactionStore {
+    let mut store = TransactionStore::new(usize::from(IN_FLIGHT));
+    let now = Instant::now();
+    for index in 0..IN_FLIGHT {
+        let request = NewRequest::Get(Get {
+            options: GetOptions::new(),
+            reliability: Reliability::NonConfirmable(NonConfirmableParameters::default()),
+        });
+        store.add(Transaction::new(
+            MessageId::from_value(index),
+            token(index),
+            request,
+            now,
+        ));
+    }
+    store
+}
+
+fn measure(name: &str, mut lookup: impl FnMut(u16) -> bool) {
+    let start = Instant::now();
+    let mut found = 0;
+    for _ in 0..ROUNDS {
+        for index in 0..IN_FLIGHT {
+            found += usize::from(lookup(black_box(index)));
+        }
+    }
+    let lookups = u32::from(IN_FLIGHT) * ROUNDS;
+    assert_eq!(lookups as usize, found);
+
+    println!(
+        "{name:<20} {lookups} lookups in {:?} ({:?} per lookup)",
+        start.elapsed(),
+        start.elapsed() / lookups
+    );
+}
+
+fn main() {
+    let mut store = store();
+    measure("find_by_token", |index| {
+        store.find_by_token(&token(index)).is_some()
+    });
+    measure("find_by_message_id", |index| {
+        store
+            .find_by_message_id(&MessageId::from_value(index))
+            .is_some()
+    });
+}
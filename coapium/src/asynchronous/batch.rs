@@ -0,0 +1,96 @@
+//! Sending many datagrams with a single `sendmmsg` call, behind the `net-batch` feature on Linux.
+
+use std::{io, mem, os::fd::RawFd};
+
+/// Most datagrams handed to one call, `sendmmsg` takes at most `UIO_MAXIOV` of them.
+const MAX_BATCH: usize = 1024;
+
+/// Sends the datagrams in order on the connected socket `fd`, stopping at the first one that
+/// can't be sent. Returns the number of bytes sent of each datagram that was sent, the error is
+/// for the first datagram when none was.
+pub(crate) fn send(fd: RawFd, datagrams: &[Vec<u8>]) -> io::Result<Vec<usize>> {
+    let datagrams = &datagrams[..datagrams.len().min(MAX_BATCH)];
+    let mut iovecs = datagrams
+        .iter()
+        .map(|data| libc::iovec {
+            iov_base: data.as_ptr() as *mut libc::c_void,
+            iov_len: data.len(),
+        })
+        .collect::<Vec<_>>();
+    let mut messages = iovecs
+        .iter_mut()
+        .map(|iovec| {
+            // SAFETY: msghdr is plain old data, all zeroes is an empty header without an address.
+            let mut header: libc::msghdr = unsafe { mem::zeroed() };
+            header.msg_iov = iovec;
+            header.msg_iovlen = 1;
+            libc::mmsghdr {
+                msg_hdr: header,
+                msg_len: 0,
+            }
+        })
+        .collect::<Vec<_>>();
+
+    // SAFETY: every header points to one iovec, which points to a datagram, all of them outlive
+    // the call. The kernel only writes `msg_len` of the headers.
+    let sent =
+        unsafe { libc::sendmmsg(fd, messages.as_mut_ptr(), messages.len() as libc::c_uint, 0) };
+    if sent < 0 {
+        return Err(io::Error::last_os_error());
+    }
+
+    Ok(messages[..sent as usize]
+        .iter()
+        .map(|message| message.msg_len as usize)
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        net::{Ipv4Addr, UdpSocket},
+        os::fd::AsRawFd,
+        time::Duration,
+    };
+
+    use pretty_assertions::assert_eq;
+    use rstest::rstest;
+
+    use super::send;
+
+    fn receiver() -> UdpSocket {
+        let receiver = UdpSocket::bind((Ipv4Addr::LOCALHOST, 0)).unwrap();
+        receiver
+            .set_read_timeout(Some(Duration::from_secs(5)))
+            .unwrap();
+        receiver
+    }
+
+    #[rstest]
+    fn sends_datagrams_in_order() {
+        let receiver = receiver();
+        let sender = UdpSocket::bind((Ipv4Addr::LOCALHOST, 0)).unwrap();
+        sender.connect(receiver.local_addr().unwrap()).unwrap();
+        let datagrams = (0..8u8)
+            .map(|i| vec![i; usize::from(i) + 1])
+            .collect::<Vec<_>>();
+
+        let sent = send(sender.as_raw_fd(), &datagrams).unwrap();
+
+        assert_eq!((1..=8).collect::<Vec<_>>(), sent);
+        let mut buffer = [0; 16];
+        for data in &datagrams {
+            let read = receiver.recv(&mut buffer).unwrap();
+            assert_eq!(data.as_slice(), &buffer[..read]);
+        }
+    }
+
+    #[rstest]
+    fn fails_on_unconnected_socket() {
+        let sender = UdpSocket::bind((Ipv4Addr::LOCALHOST, 0)).unwrap();
+
+        let error = send(sender.as_raw_fd(), &[vec![1], vec![2]]).unwrap_err();
+
+        assert_eq!(Some(libc::EDESTADDRREQ), error.raw_os_error());
+    }
+}
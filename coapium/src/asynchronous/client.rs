@@ -1,99 +1,1031 @@
-use tokio::sync::mpsc::channel;
-use tokio::{net::UdpSocket, sync::mpsc::UnboundedSender};
+use std::io;
+use std::net::SocketAddr;
+use std::pin::Pin;
+use std::sync::{Arc, Mutex};
+use std::task::{Context, Poll};
+use std::time::{Duration, Instant};
 
+use futures::channel::mpsc::{
+    channel, unbounded, Receiver, Sender, UnboundedReceiver, UnboundedSender,
+};
+use futures::future::{abortable, select, AbortHandle, Either};
+use futures::{AsyncWrite, AsyncWriteExt, SinkExt, Stream, StreamExt};
+use log::{debug, warn};
+
+use crate::client::progress::{Progress, Step};
+use crate::client::resolve::{Resolve, SystemResolver};
+use crate::client::session_stats::SessionStats;
+use crate::client::socket_config::{self, SocketConfig};
+use crate::client::url::Url;
+use crate::client::Error;
+use crate::codec::message::GetOptions;
+use crate::codec::option::{Block2, ContentFormat, ETag, Observe};
+use crate::codec::ResponseCode;
+use crate::protocol::body::Body;
+use crate::protocol::empty_message::EmptyMessage;
+use crate::protocol::get::Get;
+use crate::protocol::interceptor::RequestInterceptor;
+use crate::protocol::keep_alive::{ConnectionState, KeepAlive, Liveness};
 use crate::protocol::new_request::NewRequest;
+use crate::protocol::observation::{retry_after_unavailable, Freshness};
 use crate::protocol::ping::Ping;
+use crate::protocol::profile::TransmissionProfile;
+use crate::protocol::status::Status;
 use crate::protocol::{ping, response};
 use crate::{
     asynchronous::system,
-    codec::{message_id::MessageId, url::Endpoint},
-    protocol::{message_id_store::MessageIdStore, processor::Processor},
+    codec::{message_id::MessageId, url::Endpoint, Token},
+    protocol::{
+        message_id_store::MessageIdStore,
+        processor::{Processor, ReservedPolicy},
+    },
 };
 
 use super::response::Response;
-use super::system::{Command, System};
+#[cfg(any(feature = "tokio", feature = "smol"))]
+use super::runtime::DefaultRuntime;
+use super::runtime::{Runtime, UdpSocket};
+use super::system::{Command, Rejection, System};
 
 // TODO: Try this for diagnostics: https://github.com/tokio-rs/console
 
+/// Handle to a running client system.
+///
+/// Cloning is cheap and every clone talks to the same socket and system, which makes it possible
+/// to share one client between tasks. The client is `Send + Sync`.
 #[derive(Debug, Clone)]
 pub struct Client {
-    request_sender: UnboundedSender<Command>,
+    profile: TransmissionProfile,
+    progress: Arc<Mutex<Progress>>,
+    request_sender: Arc<UnboundedSender<Command>>,
+    runtime: Arc<dyn Runtime>,
+    stats: Arc<Mutex<SessionStats>>,
+    unknown_messages: Arc<Mutex<Option<UnboundedReceiver<Vec<u8>>>>>,
+}
+
+const _: () = {
+    const fn assert_send_sync<T: Send + Sync>() {}
+    assert_send_sync::<Client>();
+};
+
+/// Stream of the responses belonging to one exchange.
+///
+/// The stream ends once the system has resolved the exchange and will not deliver any more
+/// responses for its token.
+#[derive(Debug)]
+pub struct ResponseStream {
+    token: Token,
+    receiver: Receiver<Result<Response, response::Error>>,
+}
+
+/// Notification of an observed resource, with how long it's fresh for.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Notification {
+    pub response: Response,
+    pub freshness: Freshness,
+}
+
+/// The server answered 5.03 (Service Unavailable) during an observation, the client registers
+/// again at `retry_at`.
+#[derive(Clone, Debug, PartialEq)]
+pub struct ObservationInterrupted {
+    pub response: Response,
+    pub retry_at: Instant,
+}
+
+/// Item of an [`Observation`].
+#[derive(Clone, Debug, PartialEq)]
+pub enum ObservationEvent {
+    Notification(Notification),
+    Interrupted(ObservationInterrupted),
+}
+
+/// How [`Client::observe_with`] keeps an observation going.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct ObserveConfig {
+    /// Registers again once the last notification went stale without a newer one, the server
+    /// may have forgotten about the observation.
+    pub auto_refresh: bool,
+    /// Registers again once the Max-Age of a 5.03 (Service Unavailable) answered during the
+    /// observation passed, the stream ends with the 5.03 otherwise.
+    pub reregister_on_unavailable: bool,
+    /// Longest wait before registering again after a 5.03, whatever its Max-Age.
+    pub max_unavailable_delay: Duration,
+}
+
+impl Default for ObserveConfig {
+    fn default() -> Self {
+        Self {
+            auto_refresh: false,
+            reregister_on_unavailable: true,
+            max_unavailable_delay: Duration::from_secs(300),
+        }
+    }
+}
+
+/// Stream of the notifications of an observed resource, see [`Client::observe`]. The first item
+/// is the response to the registration.
+///
+/// The stream ends with a notification that doesn't
+/// [continue the observation](Response::continues_observation) or after an error. A 5.03 (Service
+/// Unavailable) once the observation is established comes as an
+/// [`Interrupted`](ObservationEvent::Interrupted) item instead, if the client registers again.
+/// The observation is canceled once the stream is dropped.
+#[derive(Debug)]
+pub struct Observation {
+    abort_handle: AbortHandle,
+    receiver: Receiver<Result<ObservationEvent, response::Error>>,
+}
+
+impl Drop for Observation {
+    fn drop(&mut self) {
+        self.abort_handle.abort();
+    }
+}
+
+impl Stream for Observation {
+    type Item = Result<ObservationEvent, response::Error>;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        self.receiver.poll_next_unpin(cx)
+    }
+}
+
+/// Representation written out by [`Client::get_into`].
+#[derive(Clone, Debug, PartialEq)]
+pub struct Download {
+    /// Bytes written, over all blocks.
+    pub size: usize,
+    pub content_format: Option<ContentFormat>,
+    pub etag: Option<ETag>,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum DownloadError {
+    Request(response::Error),
+    /// The server answered a block with a response code other than success.
+    Response(ResponseCode),
+    /// A block carried another ETag than the first one, the representation changed during the
+    /// download.
+    ETagChanged,
+    /// The server sent a block other than the one asked for.
+    UnexpectedBlock {
+        num: u32,
+    },
+    Write(io::ErrorKind),
+}
+
+impl From<response::Error> for DownloadError {
+    fn from(error: response::Error) -> Self {
+        Self::Request(error)
+    }
+}
+
+impl std::fmt::Display for DownloadError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            DownloadError::Request(error) => error.fmt(f),
+            DownloadError::Response(response_code) => write!(
+                f,
+                "block was answered with {}",
+                crate::codec::Code::Response(*response_code)
+            ),
+            DownloadError::ETagChanged => write!(f, "representation changed during the download"),
+            DownloadError::UnexpectedBlock { num } => {
+                write!(f, "server sent block {num} instead of the one asked for")
+            }
+            DownloadError::Write(kind) => write!(f, "failed to write block: {kind}"),
+        }
+    }
+}
+
+impl std::error::Error for DownloadError {}
+
+/// Handle to the keep-alive pings of a client, the pings stop once the handle is dropped.
+#[derive(Debug)]
+pub struct KeepAliveHandle {
+    abort_handle: AbortHandle,
+    liveness: Arc<Mutex<Liveness>>,
+}
+
+impl KeepAliveHandle {
+    pub fn missed_pongs(&self) -> u32 {
+        self.liveness.lock().unwrap().missed_pongs()
+    }
+
+    pub fn state(&self) -> ConnectionState {
+        self.liveness.lock().unwrap().state()
+    }
+}
+
+impl Drop for KeepAliveHandle {
+    fn drop(&mut self) {
+        self.abort_handle.abort();
+    }
+}
+
+impl ResponseStream {
+    pub fn token(&self) -> &Token {
+        &self.token
+    }
+}
+
+impl Stream for ResponseStream {
+    type Item = Result<Response, response::Error>;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        self.receiver.poll_next_unpin(cx)
+    }
 }
 
-async fn run_loop(mut system: System, message_id_store: MessageIdStore) -> Result<(), ()> {
-    let mut processor = Processor::new(message_id_store);
+async fn run_loop(
+    mut system: System,
+    mut processor: Processor,
+    stats: Arc<Mutex<SessionStats>>,
+    progress: Arc<Mutex<Progress>>,
+) -> Result<(), ()> {
     loop {
         let event = system.poll().await?;
-        let effects = processor.tick(event).map_err(|_| ())?;
+        let effects = stats
+            .lock()
+            .unwrap()
+            .tick(&mut processor, event)
+            .map_err(|_| ())?;
         system.dispatch(effects).await?;
+        for upload in processor.uploads_mut() {
+            upload.prefetch().await;
+        }
+        progress.lock().unwrap().update(&processor);
     }
 }
 
 impl Client {
-    pub async fn new(endpoint: Endpoint) -> Self {
-        let socket = UdpSocket::bind("0.0.0.0:0").await.unwrap();
-        let connect_address = format!(
-            "{}:{}",
-            endpoint.host,
-            endpoint
-                .port
-                .map(|p| p.value())
-                .unwrap_or(Default::default())
-        );
-        println!("{:?}", connect_address);
-        socket.connect(&connect_address).await.unwrap();
+    /// Creates a client on the [`DefaultRuntime`], which fails when the socket can't be bound as
+    /// `socket_config` says or `endpoint` has no address the socket can send to.
+    #[cfg(any(feature = "tokio", feature = "smol"))]
+    pub async fn new(endpoint: Endpoint, socket_config: SocketConfig) -> Result<Self, Error> {
+        Self::with_interceptors(endpoint, socket_config, vec![]).await
+    }
+
+    /// Creates a client on the [`DefaultRuntime`] where every request and response passes through
+    /// the interceptors, in order.
+    #[cfg(any(feature = "tokio", feature = "smol"))]
+    pub async fn with_interceptors(
+        endpoint: Endpoint,
+        socket_config: SocketConfig,
+        interceptors: Vec<Box<dyn RequestInterceptor>>,
+    ) -> Result<Self, Error> {
+        Self::with_runtime(
+            DefaultRuntime::default(),
+            endpoint,
+            socket_config,
+            interceptors,
+        )
+    }
+
+    /// Creates a client that runs on `runtime`, where every request and response passes through
+    /// the interceptors, in order.
+    pub fn with_runtime(
+        runtime: impl Runtime,
+        endpoint: Endpoint,
+        socket_config: SocketConfig,
+        interceptors: Vec<Box<dyn RequestInterceptor>>,
+    ) -> Result<Self, Error> {
+        Self::with_resolver(
+            runtime,
+            endpoint,
+            socket_config,
+            interceptors,
+            SystemResolver,
+        )
+    }
+
+    /// Creates a client that runs on `runtime`, where `resolver` turns the endpoint into
+    /// addresses. The client sends to the first address of the socket's address family it can
+    /// connect to.
+    pub fn with_resolver(
+        runtime: impl Runtime,
+        endpoint: Endpoint,
+        socket_config: SocketConfig,
+        interceptors: Vec<Box<dyn RequestInterceptor>>,
+        resolver: impl Resolve,
+    ) -> Result<Self, Error> {
+        Self::with_profile(
+            runtime,
+            endpoint,
+            socket_config,
+            interceptors,
+            resolver,
+            TransmissionProfile::default(),
+        )
+    }
+
+    /// Creates a client like [`with_resolver`](Client::with_resolver) that applies `profile`,
+    /// see [`Processor::with_profile`].
+    pub fn with_profile(
+        runtime: impl Runtime,
+        endpoint: Endpoint,
+        socket_config: SocketConfig,
+        interceptors: Vec<Box<dyn RequestInterceptor>>,
+        resolver: impl Resolve,
+        profile: TransmissionProfile,
+    ) -> Result<Self, Error> {
+        if socket_config.accepts_any_source() {
+            return Err(Error::AnySourceUnsupported);
+        }
+
+        let socket = socket_config.bind()?;
+        let local_address = socket
+            .local_addr()
+            .map_err(|e| socket_config::Error::Bind(e.kind()))?;
+        let addresses: Vec<SocketAddr> = resolver
+            .resolve(&endpoint)
+            .map_err(|e| Error::Resolve(e.kind()))?
+            .into_iter()
+            .filter(|address| address.is_ipv4() == local_address.is_ipv4())
+            .collect();
+        if addresses.is_empty() {
+            return Err(Error::NoAddress);
+        }
+        socket
+            .connect(&addresses[..])
+            .map_err(|e| Error::Connect(e.kind()))?;
+        socket
+            .set_nonblocking(true)
+            .map_err(|e| socket_config::Error::Option(e.kind()))?;
+        let socket = runtime
+            .udp_socket(socket)
+            .map_err(|e| socket_config::Error::Bind(e.kind()))?;
+
+        Ok(Self::start(
+            runtime,
+            socket,
+            vec![],
+            socket_config,
+            interceptors,
+            profile,
+        ))
+    }
+
+    /// Runs the client on any transport that exchanges whole datagrams, e.g. the WebSocket of the
+    /// `wasm` feature.
+    pub fn with_socket(
+        runtime: impl Runtime,
+        socket: Box<dyn UdpSocket>,
+        interceptors: Vec<Box<dyn RequestInterceptor>>,
+    ) -> Self {
+        Self::with_sockets(runtime, socket, vec![], interceptors)
+    }
+
+    /// Runs the client on `socket` and `more_sockets` sharing one transaction state, like an IPv4
+    /// and an IPv6 socket of a dual-stack gateway, see [`System::with_socket`]. Requests go out on
+    /// `socket` unless sent with [`execute_to`](Client::execute_to).
+    pub fn with_sockets(
+        runtime: impl Runtime,
+        socket: Box<dyn UdpSocket>,
+        more_sockets: Vec<Box<dyn UdpSocket>>,
+        interceptors: Vec<Box<dyn RequestInterceptor>>,
+    ) -> Self {
+        Self::start(
+            runtime,
+            socket,
+            more_sockets,
+            SocketConfig::default(),
+            interceptors,
+            TransmissionProfile::default(),
+        )
+    }
+
+    fn start(
+        runtime: impl Runtime,
+        socket: Box<dyn UdpSocket>,
+        more_sockets: Vec<Box<dyn UdpSocket>>,
+        socket_config: SocketConfig,
+        interceptors: Vec<Box<dyn RequestInterceptor>>,
+        profile: TransmissionProfile,
+    ) -> Self {
+        let runtime: Arc<dyn Runtime> = Arc::new(runtime);
 
         let initial_message_id = MessageId::from_value(rand::random());
         let message_id_store = MessageIdStore::new(initial_message_id);
+        let processor = interceptors.into_iter().fold(
+            Processor::new(message_id_store)
+                .with_profile(profile)
+                .with_entity_too_large_policy(socket_config.entity_too_large())
+                .with_reserved_policy(socket_config.reserved()),
+            Processor::with_boxed_interceptor,
+        );
 
-        let system = System::new(socket);
-        let request_sender = system.get_sender();
+        let mut system = more_sockets.into_iter().fold(
+            System::new(runtime.clone(), socket)
+                .with_max_datagram_size(socket_config.receive_size())
+                .with_token_length(socket_config.tokens()),
+            System::with_socket,
+        );
+        let mut unknown_messages = None;
+        if socket_config.reserved() == ReservedPolicy::Deliver {
+            let (sender, receiver) = unbounded();
+            system = system.with_unknown_messages(sender);
+            unknown_messages = Some(receiver);
+        }
+        let request_sender = Arc::new(system.get_sender());
+        let progress = system.progress();
+        let stats = Arc::new(Mutex::new(SessionStats::new()));
 
-        tokio::spawn(async { run_loop(system, message_id_store).await });
+        let loop_stats = stats.clone();
+        let loop_progress = progress.clone();
+        runtime.spawn(Box::pin(async {
+            let _ = run_loop(system, processor, loop_stats, loop_progress).await;
+        }));
 
-        Self { request_sender }
+        Self {
+            profile,
+            progress,
+            request_sender,
+            runtime,
+            stats,
+            unknown_messages: Arc::new(Mutex::new(unknown_messages)),
+        }
+    }
+
+    /// Transmission parameters the client was created with, to build the parameters of requests
+    /// from.
+    pub fn profile(&self) -> &TransmissionProfile {
+        &self.profile
+    }
+
+    /// Stream of the messages with a reserved code, as whole datagrams, when the client was
+    /// created with [`ReservedPolicy::Deliver`], see [`SocketConfig::reserved_policy`]. Only the
+    /// first call gets it, the messages are kept until they are received.
+    pub fn unknown_messages(&self) -> Option<UnboundedReceiver<Vec<u8>>> {
+        self.unknown_messages.lock().unwrap().take()
+    }
+
+    /// Snapshot of the counters since the client was created.
+    pub fn stats(&self) -> SessionStats {
+        *self.stats.lock().unwrap()
+    }
+
+    /// Where the request of `token` stands, see [`ResponseStream::token`]. The tokens of resolved
+    /// requests and unknown tokens are [`State::Resolved`](crate::protocol::status::State).
+    pub fn status(&self, token: &Token) -> Status {
+        self.progress.lock().unwrap().status(token)
     }
 
     pub async fn ping(&self, ping: Ping) -> Result<(), ping::Error> {
         let (sender, mut receiver) = channel(2);
+        if self
+            .request_sender
+            .unbounded_send(Command::Ping(ping, sender))
+            .is_err()
+        {
+            return Err(Rejection::Shutdown.into());
+        }
+
+        let (_token, mut receiver) = receiver.next().await.unwrap_or(Err(Rejection::Shutdown))?;
+
+        receiver.next().await.unwrap_or(Err(ping::Error::Shutdown))
+    }
+
+    /// Pings the peer every `interval` until the returned handle is dropped, the handle flips to
+    /// [`ConnectionState::Disconnected`] once too many pings in a row went unanswered.
+    pub fn keep_alive(&self, keep_alive: KeepAlive) -> KeepAliveHandle {
+        let liveness = Arc::new(Mutex::new(Liveness::new(keep_alive.max_missed_pongs)));
+
+        let client = self.clone();
+        let task_liveness = liveness.clone();
+        let runtime = self.runtime.clone();
+        let (task, abort_handle) = abortable(async move {
+            loop {
+                runtime.sleep(keep_alive.interval).await;
+                let result = client.ping(keep_alive.ping.clone()).await;
+
+                let mut liveness = task_liveness.lock().unwrap();
+                let previous = liveness.state();
+                let state = liveness.on_ping_result(&result);
+                if state != previous {
+                    warn!("Keep-alive connection state changed to {state:?}");
+                }
+            }
+        });
+        self.runtime.spawn(Box::pin(async {
+            let _ = task.await;
+        }));
+
+        KeepAliveHandle {
+            abort_handle,
+            liveness,
+        }
+    }
+
+    /// Observes the resource of `get`, registering with an Observe option, RFC 7641.
+    ///
+    /// With `auto_refresh`, the resource is registered for again once the last notification went
+    /// stale without a newer one, the server may have forgotten about the observation.
+    pub fn observe(&self, get: Get, auto_refresh: bool) -> Observation {
+        self.observe_with(
+            get,
+            ObserveConfig {
+                auto_refresh,
+                ..Default::default()
+            },
+        )
+    }
+
+    /// Observes the resource of `get` like [`observe`](Client::observe), registering again as
+    /// `config` asks for.
+    pub fn observe_with(&self, mut get: Get, config: ObserveConfig) -> Observation {
+        get.options.set_observe(Observe::register());
+
+        let (mut sender, receiver) = channel(8);
+        let client = self.clone();
+        let runtime = self.runtime.clone();
+        let (task, abort_handle) = abortable(async move {
+            let mut established = false;
+            loop {
+                let mut responses =
+                    match client.execute_streaming(NewRequest::Get(get.clone())).await {
+                        Ok(responses) => responses,
+                        Err(e) => {
+                            let _ = sender.send(Err(e)).await;
+                            return;
+                        }
+                    };
+
+                let mut stale_at: Option<Instant> = None;
+                let retry_after = loop {
+                    let next = match stale_at.filter(|_| config.auto_refresh) {
+                        Some(stale_at) => {
+                            let until_stale = stale_at.saturating_duration_since(Instant::now());
+                            match select(responses.next(), runtime.sleep(until_stale)).await {
+                                Either::Left((next, _)) => next,
+                                Either::Right(_) => break None,
+                            }
+                        }
+                        None => responses.next().await,
+                    };
+
+                    match next {
+                        Some(Ok(response)) => {
+                            let retry_after = retry_after_unavailable(
+                                response.response_code,
+                                &response.options,
+                                config.max_unavailable_delay,
+                            )
+                            .filter(|_| established && config.reregister_on_unavailable);
+                            if let Some(retry_after) = retry_after {
+                                let interrupted = ObservationInterrupted {
+                                    response,
+                                    retry_at: Instant::now() + retry_after,
+                                };
+                                if sender
+                                    .send(Ok(ObservationEvent::Interrupted(interrupted)))
+                                    .await
+                                    .is_err()
+                                {
+                                    return;
+                                }
+                                break Some(retry_after);
+                            }
+
+                            let freshness = Freshness::new(&response.options, Instant::now());
+                            let continues = response.continues_observation();
+                            established |= continues;
+                            stale_at = Some(freshness.stale_at());
+
+                            let notification = Notification {
+                                response,
+                                freshness,
+                            };
+                            if sender
+                                .send(Ok(ObservationEvent::Notification(notification)))
+                                .await
+                                .is_err()
+                                || !continues
+                            {
+                                return;
+                            }
+                        }
+                        Some(Err(e)) => {
+                            let _ = sender.send(Err(e)).await;
+                            return;
+                        }
+                        None => return,
+                    }
+                };
+
+                match retry_after {
+                    Some(retry_after) => {
+                        debug!("Observed resource is unavailable, registering again in {retry_after:?}");
+                        runtime.sleep(retry_after).await;
+                    }
+                    None => {
+                        debug!("Observation went stale, registering again");
+                        let _ = client
+                            .request_sender
+                            .unbounded_send(Command::Cancel(*responses.token()));
+                    }
+                }
+            }
+        });
+        self.runtime.spawn(Box::pin(async {
+            let _ = task.await;
+        }));
+
+        Observation {
+            abort_handle,
+            receiver,
+        }
+    }
+
+    /// Sends an empty acknowledgement for `message_id` without waiting for anything in return.
+    pub fn send_acknowledgement(&self, message_id: MessageId) {
+        self.send(EmptyMessage::Acknowledgement(message_id))
+    }
+
+    /// Sends a reset for `message_id`, e.g. to reject a notification, without waiting for anything
+    /// in return.
+    pub fn send_reset(&self, message_id: MessageId) {
+        self.send(EmptyMessage::Reset(message_id))
+    }
+
+    fn send(&self, empty_message: EmptyMessage) {
         self.request_sender
-            .send(Command::Ping(ping, sender))
+            .unbounded_send(Command::Send(empty_message))
             .expect("Failed to send to system");
+    }
 
-        let (_token, mut receiver) = match receiver
-            .recv()
+    pub async fn execute(&self, request: NewRequest) -> Result<Response, response::Error> {
+        self.execute_streaming(request)
+            .await?
+            .receiver
+            .next()
             .await
-            .expect("Failed to receive request accepted from system")
+            .unwrap_or(Err(response::Error::Shutdown))
+    }
+
+    /// GETs the resource of `url` block-wise, RFC 7959 §2.4, and writes each block to `writer` as
+    /// it arrives instead of putting the representation together in memory.
+    ///
+    /// The first request leaves the block size to the server, the blocks after it are asked for
+    /// at the size of the first. Every block has to carry the ETag of the first one.
+    pub async fn get_into<W: AsyncWrite + Unpin>(
+        &self,
+        url: &Url,
+        mut writer: W,
+    ) -> Result<Download, DownloadError> {
+        let mut download: Option<Download> = None;
+        let mut next = None;
+
+        loop {
+            let mut options = GetOptions::from(url);
+            if let Some(block2) = next.take() {
+                options.set_block2(block2);
+            }
+            let response = self
+                .execute(NewRequest::Get(Get {
+                    options,
+                    reliability: super::default_reliability(),
+                }))
+                .await?;
+            if !response.response_code.is_success() {
+                return Err(DownloadError::Response(response.response_code));
+            }
+
+            let download = download.get_or_insert_with(|| Download {
+                size: 0,
+                content_format: response.options.content_format().cloned(),
+                etag: response.options.etag().cloned(),
+            });
+            if response.options.etag() != download.etag.as_ref() {
+                return Err(DownloadError::ETagChanged);
+            }
+            let block2 = response.options.block2();
+            if let Some(block2) = block2.filter(|block2| block2.offset() != download.size) {
+                return Err(DownloadError::UnexpectedBlock { num: block2.num() });
+            }
+
+            writer
+                .write_all(response.payload.value())
+                .await
+                .map_err(|e| DownloadError::Write(e.kind()))?;
+            download.size += response.payload.value().len();
+
+            let Some(block2) = block2.filter(|block2| block2.more()) else {
+                break;
+            };
+            next = Some(
+                Block2::new(block2.num() + 1, false, block2.szx())
+                    .map_err(|_| DownloadError::UnexpectedBlock { num: block2.num() })?,
+            );
+        }
+
+        writer
+            .flush()
+            .await
+            .map_err(|e| DownloadError::Write(e.kind()))?;
+
+        Ok(download.expect("At least one block should have been received"))
+    }
+
+    /// Sends the POST or PUT `request` with `body` as its payload, uploaded block-wise so only the
+    /// blocks being sent are in memory. The payload of `request` itself is left out.
+    pub async fn upload(
+        &self,
+        request: NewRequest,
+        mut body: Body,
+    ) -> Result<Response, response::Error> {
+        body.prefetch(1 << (Block2::MAX_SZX + 4)).await;
+
+        let (sender, mut receiver) = System::new_request_channel();
+        if self
+            .request_sender
+            .unbounded_send(Command::Upload(request, body, sender))
+            .is_err()
         {
-            Ok((token, receiver)) => (token, receiver),
-            _ => unreachable!(),
-        };
+            return Err(Rejection::Shutdown.into());
+        }
 
-        receiver
-            .recv()
+        match receiver
+            .next()
             .await
-            .expect("Failed to receive from response from system")
+            .unwrap_or(system::Request::Rejected(Rejection::Shutdown))
+        {
+            system::Request::Accepted(_, mut receiver) => receiver
+                .next()
+                .await
+                .unwrap_or(Err(response::Error::Shutdown)),
+            system::Request::Rejected(rejection) => Err(rejection.into()),
+        }
     }
 
-    pub async fn execute(&self, request: NewRequest) -> Result<Response, response::Error> {
+    /// Sends `request` on the socket connected to `destination`, see
+    /// [`with_sockets`](Client::with_sockets). Fails with [`io::ErrorKind::AddrNotAvailable`] when
+    /// none is.
+    pub async fn execute_to(
+        &self,
+        request: NewRequest,
+        destination: SocketAddr,
+    ) -> Result<Response, response::Error> {
+        self.submit(|sender| Command::RequestTo(request, destination, sender))
+            .await?
+            .receiver
+            .next()
+            .await
+            .unwrap_or(Err(response::Error::Shutdown))
+    }
+
+    /// Sends `request` like [`execute`](Client::execute) and notifies `progress` of each step it
+    /// takes until it's resolved, like its retransmissions.
+    pub async fn execute_with_progress(
+        &self,
+        request: NewRequest,
+        progress: UnboundedSender<Step>,
+    ) -> Result<Response, response::Error> {
+        let mut stream = self.execute_streaming(request).await?;
+        self.progress
+            .lock()
+            .unwrap()
+            .subscribe(stream.token, progress);
+
+        stream
+            .receiver
+            .next()
+            .await
+            .unwrap_or(Err(response::Error::Shutdown))
+    }
+
+    /// Sends `request` and streams its responses, fails right away when the system rejects the
+    /// request.
+    pub async fn execute_streaming(
+        &self,
+        request: NewRequest,
+    ) -> Result<ResponseStream, response::Error> {
+        self.submit(|sender| Command::Request(request, sender))
+            .await
+    }
+
+    async fn submit(
+        &self,
+        command: impl FnOnce(Sender<system::Request>) -> Command,
+    ) -> Result<ResponseStream, response::Error> {
         let (sender, mut receiver) = System::new_request_channel();
-        self.request_sender
-            .send(Command::Request(request, sender))
-            .expect("Failed to send to system");
+        if self.request_sender.unbounded_send(command(sender)).is_err() {
+            return Err(Rejection::Shutdown.into());
+        }
 
-        use system::Request::*;
-        let (_token, mut receiver) = match receiver
-            .recv()
+        match receiver
+            .next()
             .await
-            .expect("Failed to receive request accepted from system")
+            .unwrap_or(system::Request::Rejected(Rejection::Shutdown))
         {
-            Accepted(token, receiver) => (token, receiver),
-            _ => unreachable!(),
+            system::Request::Accepted(token, receiver) => Ok(ResponseStream { token, receiver }),
+            system::Request::Rejected(rejection) => Err(rejection.into()),
+        }
+    }
+}
+
+#[cfg(all(test, any(feature = "tokio", feature = "smol")))]
+mod tests {
+    use std::{
+        io,
+        net::{Ipv4Addr, SocketAddr},
+        time::Duration,
+    };
+
+    use futures::{
+        channel::mpsc::{unbounded, UnboundedReceiver, UnboundedSender},
+        future::BoxFuture,
+        lock::Mutex,
+        StreamExt,
+    };
+    use pretty_assertions::assert_eq;
+    use rstest::rstest;
+
+    use crate::{
+        client::{socket_config::SocketConfig, Error},
+        codec::{
+            message::{reliability::Reliability, GetOptions, Ping},
+            url::Endpoint,
+            Acknowledgement, Message, MessageId, Options, Payload, Request, Reset, Response,
+            ResponseCode,
+        },
+        protocol::{
+            get::Get, new_request::NewRequest, reliability,
+            transmission_parameters::NonConfirmableParameters,
+        },
+    };
+
+    use super::{response, Client, DefaultRuntime, UdpSocket};
+
+    /// Socket connected to `peer` that receives what's put into `received` and puts what it sends
+    /// into `sent`.
+    #[derive(Debug)]
+    struct Socket {
+        peer: SocketAddr,
+        received: Mutex<UnboundedReceiver<Vec<u8>>>,
+        sent: UnboundedSender<Vec<u8>>,
+    }
+
+    impl UdpSocket for Socket {
+        fn recv<'a>(&'a self, buffer: &'a mut [u8]) -> BoxFuture<'a, io::Result<usize>> {
+            Box::pin(async move {
+                let data = self
+                    .received
+                    .lock()
+                    .await
+                    .next()
+                    .await
+                    .ok_or(io::ErrorKind::NotConnected)?;
+                buffer[..data.len()].copy_from_slice(&data);
+                Ok(data.len())
+            })
+        }
+
+        fn recv_from<'a>(
+            &'a self,
+            buffer: &'a mut [u8],
+        ) -> BoxFuture<'a, io::Result<(usize, Option<SocketAddr>)>> {
+            Box::pin(async move { Ok((self.recv(buffer).await?, Some(self.peer))) })
+        }
+
+        fn send<'a>(&'a self, data: &'a [u8]) -> BoxFuture<'a, io::Result<usize>> {
+            let sent = self.sent.unbounded_send(data.to_vec());
+            Box::pin(async move {
+                sent.map_err(|_| io::ErrorKind::NotConnected)?;
+                Ok(data.len())
+            })
+        }
+
+        fn peer_addr(&self) -> Option<SocketAddr> {
+            Some(self.peer)
+        }
+    }
+
+    /// Other end of a [`Socket`], which sends the datagrams the socket receives and receives the
+    /// datagrams it sends.
+    struct Peer {
+        sender: UnboundedSender<Vec<u8>>,
+        receiver: UnboundedReceiver<Vec<u8>>,
+    }
+
+    fn socket(peer: SocketAddr) -> (Box<dyn UdpSocket>, Peer) {
+        let (sender, received) = unbounded();
+        let (sent, receiver) = unbounded();
+        let socket = Socket {
+            peer,
+            received: Mutex::new(received),
+            sent,
         };
 
-        receiver
-            .recv()
-            .await
-            .expect("Failed to receive from response from system")
+        (Box::new(socket), Peer { sender, receiver })
+    }
+
+    /// Runs `test` on tokio, failing it when it's stuck waiting for a datagram.
+    #[cfg(feature = "tokio")]
+    fn run(test: impl std::future::Future<Output = ()>) {
+        tokio::runtime::Runtime::new()
+            .unwrap()
+            .block_on(async { tokio::time::timeout(Duration::from_secs(5), test).await })
+            .expect("Timed out");
+    }
+
+    fn get() -> NewRequest {
+        NewRequest::Get(Get {
+            options: GetOptions::new(),
+            reliability: reliability::Reliability::NonConfirmable(
+                NonConfirmableParameters::default(),
+            ),
+        })
+    }
+
+    #[rstest]
+    fn with_runtime_rejects_any_source() {
+        let endpoint = Endpoint::from_str("coap://127.0.0.1").unwrap();
+
+        assert_eq!(
+            Some(Error::AnySourceUnsupported),
+            Client::with_runtime(
+                DefaultRuntime::default(),
+                endpoint,
+                SocketConfig::new().any_source(true),
+                vec![]
+            )
+            .err()
+        );
+    }
+
+    #[cfg(feature = "tokio")]
+    #[rstest]
+    fn requests_and_replies_leave_on_their_socket() {
+        let first_peer = SocketAddr::from((Ipv4Addr::new(192, 0, 2, 1), 5683));
+        let second_peer = SocketAddr::from((Ipv4Addr::new(192, 0, 2, 2), 5683));
+        let (first, mut first_end) = socket(first_peer);
+        let (second, mut second_end) = socket(second_peer);
+
+        run(async {
+            let client =
+                Client::with_sockets(DefaultRuntime::default(), first, vec![second], vec![]);
+            let execution = tokio::spawn({
+                let client = client.clone();
+                async move { client.execute_to(get(), second_peer).await }
+            });
+
+            let request = second_end.receiver.next().await.unwrap();
+            let Ok(Message::Request(Request::Get(request))) = Message::decode(&request) else {
+                panic!("expected a GET, got {request:?}");
+            };
+            let response = Response::new(
+                Reliability::Confirmable,
+                *request.token(),
+                ResponseCode::CONTENT,
+                MessageId::from_value(7),
+                Options::new(),
+                Payload::from_value(b"second".to_vec()),
+            );
+            second_end.sender.unbounded_send(response.encode()).unwrap();
+            assert_eq!(
+                Some(Acknowledgement::new(MessageId::from_value(7)).encode()),
+                second_end.receiver.next().await
+            );
+            assert_eq!(
+                Payload::from_value(b"second".to_vec()),
+                execution.await.unwrap().unwrap().payload
+            );
+
+            first_end
+                .sender
+                .unbounded_send(Ping::from_message_id(MessageId::from_value(8)).encode())
+                .unwrap();
+            assert_eq!(
+                Some(Reset::from_message_id(MessageId::from_value(8)).encode()),
+                first_end.receiver.next().await
+            );
+
+            tokio::spawn(async move { client.execute(get()).await });
+            assert!(matches!(
+                Message::decode(&first_end.receiver.next().await.unwrap()),
+                Ok(Message::Request(Request::Get(_)))
+            ));
+            assert!(second_end.receiver.try_next().is_err());
+        });
+    }
+
+    #[cfg(feature = "tokio")]
+    #[rstest]
+    fn execute_to_rejects_unknown_destination() {
+        let peer = SocketAddr::from((Ipv4Addr::new(192, 0, 2, 1), 5683));
+        let unknown = SocketAddr::from((Ipv4Addr::new(192, 0, 2, 9), 5683));
+        let (socket, mut end) = socket(peer);
+
+        run(async {
+            let client = Client::with_socket(DefaultRuntime::default(), socket, vec![]);
+
+            assert_eq!(
+                Err(response::Error::Transport(io::ErrorKind::AddrNotAvailable)),
+                client.execute_to(get(), unknown).await
+            );
+            assert!(end.receiver.try_next().is_err());
+        });
     }
 }
@@ -1,80 +1,121 @@
+#[cfg(all(feature = "net-batch", target_os = "linux"))]
+mod batch;
 pub mod client;
+pub mod runtime;
 pub mod system;
+#[cfg(feature = "wasm")]
+pub mod websocket;
 
-use crate::codec::message::{DeleteOptions, GetOptions, PostOptions, PutOptions};
-use crate::codec::option::ContentFormat;
-use crate::codec::TokenLength;
-use crate::codec::{Payload, Token};
-use crate::protocol::delete::Delete;
-use crate::protocol::get::Get;
-use crate::protocol::new_request::NewRequest;
-use crate::protocol::ping::{self, Ping};
-use crate::protocol::post::Post;
-use crate::protocol::put::Put;
 use crate::protocol::reliability::Reliability;
-use crate::protocol::request::Method;
 pub use crate::protocol::response;
-use crate::protocol::transmission_parameters::{
-    ConfirmableParameters, InitialRetransmissionFactor,
-};
+use crate::protocol::transmission_parameters::ConfirmableParameters;
 pub use client::Client;
-use rand::rngs::StdRng;
-use rand::{thread_rng, Rng, RngCore, SeedableRng};
-
-use crate::client::url::Url;
 
-use self::response::Response;
+#[cfg(any(feature = "tokio", feature = "smol"))]
+use {
+    self::response::{Response, TypedResponse},
+    crate::client::{socket_config::SocketConfig, url::Url},
+    crate::codec::message::{DeleteOptions, GetOptions, PostOptions, PutOptions},
+    crate::codec::option::{self, Accept, ContentFormat},
+    crate::codec::{MediaType, Payload},
+    crate::protocol::{
+        delete::Delete,
+        get::Get,
+        new_request::NewRequest,
+        ping::{self, Ping},
+        post::Post,
+        put::Put,
+        request::Method,
+    },
+};
 
 pub fn default_reliability() -> Reliability {
     Reliability::Confirmable(default_parameters())
 }
 
 pub fn default_parameters() -> ConfirmableParameters {
-    ConfirmableParameters::new(
-        Default::default(),
-        Default::default(),
-        initial_retransmission_factor(),
-        Default::default(),
-    )
+    ConfirmableParameters::builder()
+        .build()
+        .expect("Default parameters should be valid")
 }
 
+#[cfg(any(feature = "tokio", feature = "smol"))]
 pub async fn delete(url: Url) -> Result<Response, response::Error> {
     request(Method::Delete, url).await
 }
 
+#[cfg(any(feature = "tokio", feature = "smol"))]
 pub async fn get(url: Url) -> Result<Response, response::Error> {
     request(Method::Get, url).await
 }
 
-fn initial_retransmission_factor() -> InitialRetransmissionFactor {
-    InitialRetransmissionFactor::new(thread_rng().gen_range(0.0..1.0)).unwrap()
+/// GET with content negotiation, the media types are requested in order of preference until the
+/// server stops answering with 4.06 (Not Acceptable).
+///
+/// When every media type is rejected the last 4.06 response is returned, when no media types are
+/// given the request is sent without an Accept option.
+#[cfg(any(feature = "tokio", feature = "smol"))]
+pub async fn get_as(url: Url, media_types: &[MediaType]) -> Result<TypedResponse, response::Error> {
+    let Some((last, preferred)) = media_types.split_last() else {
+        return get(url).await.map(Into::into);
+    };
+
+    let client = client(&url).await?;
+
+    for media_type in preferred {
+        let response = client.execute(negotiated_get(&url, media_type)?).await?;
+        if !response.is_not_acceptable() {
+            return Ok(response.into());
+        }
+    }
+
+    client
+        .execute(negotiated_get(&url, last)?)
+        .await
+        .map(Into::into)
 }
 
+#[cfg(any(feature = "tokio", feature = "smol"))]
+fn negotiated_get(url: &Url, media_type: &MediaType) -> Result<NewRequest, response::Error> {
+    let accept = Accept::try_from(media_type.clone())
+        .map_err(|error| response::Error::Codec(option::Error::from(error).into()))?;
+
+    let mut options = GetOptions::from(url);
+    options.set_accept(accept);
+
+    Ok(NewRequest::Get(Get {
+        options,
+        reliability: default_reliability(),
+    }))
+}
+
+#[cfg(any(feature = "tokio", feature = "smol"))]
 pub async fn ping(url: Url) -> Result<(), ping::Error> {
-    Client::new(url.clone().into())
+    Client::new(url.clone().into(), SocketConfig::default())
         .await
+        .map_err(|error| ping::Error::Transport(error.kind()))?
         .ping(Ping {
             confirmable_parameters: default_parameters(),
         })
         .await
 }
 
+#[cfg(any(feature = "tokio", feature = "smol"))]
 pub async fn post(url: Url) -> Result<Response, response::Error> {
     request(Method::Post, url).await
 }
 
+#[cfg(any(feature = "tokio", feature = "smol"))]
 pub async fn post_payload(
     url: Url,
     content_format: ContentFormat,
     payload: Payload,
 ) -> Result<Response, response::Error> {
-    let client = Client::new(url.clone().into()).await;
+    let client = client(&url).await?;
 
     let reliability = default_reliability();
 
-    let mut options = PostOptions::new();
-    options.set_uri_path(url.path);
-    options.set_uri_query(url.query);
+    let mut options = PostOptions::from(&url);
     options.set_content_format(content_format);
 
     let request = NewRequest::Post(Post {
@@ -86,22 +127,22 @@ pub async fn post_payload(
     client.execute(request).await
 }
 
+#[cfg(any(feature = "tokio", feature = "smol"))]
 pub async fn put(url: Url) -> Result<Response, response::Error> {
     request(Method::Put, url).await
 }
 
+#[cfg(any(feature = "tokio", feature = "smol"))]
 pub async fn put_payload(
     url: Url,
     content_format: ContentFormat,
     payload: Payload,
 ) -> Result<Response, response::Error> {
-    let client = Client::new(url.clone().into()).await;
+    let client = client(&url).await?;
 
     let reliability = default_reliability();
 
-    let mut options = PutOptions::new();
-    options.set_uri_path(url.path);
-    options.set_uri_query(url.query);
+    let mut options = PutOptions::from(&url);
     options.set_content_format(content_format);
 
     let request = NewRequest::Put(Put {
@@ -113,16 +154,22 @@ pub async fn put_payload(
     client.execute(request).await
 }
 
+#[cfg(any(feature = "tokio", feature = "smol"))]
+async fn client(url: &Url) -> Result<Client, response::Error> {
+    Client::new(url.clone().into(), SocketConfig::default())
+        .await
+        .map_err(|error| response::Error::Transport(error.kind()))
+}
+
+#[cfg(any(feature = "tokio", feature = "smol"))]
 pub async fn request(method: Method, url: Url) -> Result<Response, response::Error> {
-    let client = Client::new(url.clone().into()).await;
+    let client = client(&url).await?;
 
     let reliability = default_reliability();
 
     let request = match method {
         Method::Get => {
-            let mut options = GetOptions::new();
-            options.set_uri_path(url.path);
-            options.set_uri_query(url.query);
+            let options = GetOptions::from(&url);
 
             NewRequest::Get(Get {
                 options,
@@ -130,9 +177,7 @@ pub async fn request(method: Method, url: Url) -> Result<Response, response::Err
             })
         }
         Method::Post => {
-            let mut options = PostOptions::new();
-            options.set_uri_path(url.path);
-            options.set_uri_query(url.query);
+            let options = PostOptions::from(&url);
 
             NewRequest::Post(Post {
                 options,
@@ -141,9 +186,7 @@ pub async fn request(method: Method, url: Url) -> Result<Response, response::Err
             })
         }
         Method::Put => {
-            let mut options = PutOptions::new();
-            options.set_uri_path(url.path);
-            options.set_uri_query(url.query);
+            let options = PutOptions::from(&url);
 
             NewRequest::Put(Put {
                 options,
@@ -152,9 +195,7 @@ pub async fn request(method: Method, url: Url) -> Result<Response, response::Err
             })
         }
         Method::Delete => {
-            let mut options = DeleteOptions::new();
-            options.set_uri_path(url.path);
-            options.set_uri_query(url.query);
+            let options = DeleteOptions::from(&url);
 
             NewRequest::Delete(Delete {
                 options,
@@ -165,11 +206,3 @@ pub async fn request(method: Method, url: Url) -> Result<Response, response::Err
 
     client.execute(request).await
 }
-
-// TODO: Source token from here
-fn token() -> Token {
-    let mut rng = StdRng::from_entropy();
-    let mut bytes = [0; TokenLength::MAX as usize];
-    rng.fill_bytes(&mut bytes);
-    Token::from_value(bytes.to_vec()).unwrap()
-}
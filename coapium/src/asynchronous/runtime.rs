@@ -0,0 +1,273 @@
+//! The parts of an async runtime the asynchronous client needs: spawning tasks, sleeping and a
+//! UDP socket.
+//!
+//! [`Tokio`] and [`Smol`] are available behind the `tokio` and `smol` features, `tokio` is enabled
+//! by default. The smol runtime also works under async-std since both are built on `async-io`.
+//! [`Wasm`] runs in the browser behind the `wasm` feature, where there are no UDP sockets and the
+//! client talks CoAP over WebSocket instead, see [`websocket`](super::websocket).
+//! For any other runtime, e.g. embassy, implement [`Runtime`] and create the client with
+//! [`Client::with_runtime`](super::Client::with_runtime).
+
+use std::{
+    fmt::Debug,
+    io,
+    net::{self, SocketAddr},
+    time::Duration,
+};
+
+use futures::future::BoxFuture;
+
+/// Runtime of [`Client::new`](super::Client::new) and the request functions, tokio when both
+/// runtimes are enabled.
+#[cfg(feature = "tokio")]
+pub type DefaultRuntime = Tokio;
+
+/// Runtime of [`Client::new`](super::Client::new) and the request functions, tokio when both
+/// runtimes are enabled.
+#[cfg(all(feature = "smol", not(feature = "tokio")))]
+pub type DefaultRuntime = Smol;
+
+/// Spawns tasks, sleeps and wraps sockets for the asynchronous client.
+pub trait Runtime: Debug + Send + Sync + 'static {
+    /// Runs `future` to completion in the background.
+    fn spawn(&self, future: BoxFuture<'static, ()>);
+
+    /// Completes once `duration` has passed.
+    fn sleep(&self, duration: Duration) -> BoxFuture<'static, ()>;
+
+    /// Wraps a bound, connected and non-blocking socket.
+    fn udp_socket(&self, socket: net::UdpSocket) -> io::Result<Box<dyn UdpSocket>>;
+}
+
+/// Connected UDP socket of a [`Runtime`].
+pub trait UdpSocket: Debug + Send + Sync {
+    fn recv<'a>(&'a self, buffer: &'a mut [u8]) -> BoxFuture<'a, io::Result<usize>>;
+
+    /// Receives like [`recv`](UdpSocket::recv), along with the address the datagram came from if
+    /// the transport has addresses.
+    fn recv_from<'a>(
+        &'a self,
+        buffer: &'a mut [u8],
+    ) -> BoxFuture<'a, io::Result<(usize, Option<SocketAddr>)>> {
+        Box::pin(async move { self.recv(buffer).await.map(|read| (read, None)) })
+    }
+
+    fn send<'a>(&'a self, data: &'a [u8]) -> BoxFuture<'a, io::Result<usize>>;
+
+    /// Address the socket is connected to, `None` if it isn't or the transport has no addresses.
+    fn peer_addr(&self) -> Option<SocketAddr> {
+        None
+    }
+
+    /// Sends the datagrams in order, with the result of each. One by one unless the socket can do
+    /// better, like the tokio socket with a single `sendmmsg` call behind the `net-batch` feature
+    /// on Linux.
+    fn send_batch<'a>(&'a self, datagrams: &'a [Vec<u8>]) -> BoxFuture<'a, Vec<io::Result<usize>>> {
+        Box::pin(async move {
+            let mut results = Vec::with_capacity(datagrams.len());
+            for data in datagrams {
+                results.push(self.send(data).await);
+            }
+            results
+        })
+    }
+}
+
+/// Runtime backed by tokio, the client has to be created from within a tokio runtime.
+#[cfg(feature = "tokio")]
+#[derive(Clone, Copy, Debug, Default)]
+pub struct Tokio;
+
+#[cfg(feature = "tokio")]
+impl Runtime for Tokio {
+    fn spawn(&self, future: BoxFuture<'static, ()>) {
+        tokio::spawn(future);
+    }
+
+    fn sleep(&self, duration: Duration) -> BoxFuture<'static, ()> {
+        Box::pin(tokio::time::sleep(duration))
+    }
+
+    fn udp_socket(&self, socket: net::UdpSocket) -> io::Result<Box<dyn UdpSocket>> {
+        Ok(Box::new(tokio::net::UdpSocket::from_std(socket)?))
+    }
+}
+
+#[cfg(feature = "tokio")]
+impl UdpSocket for tokio::net::UdpSocket {
+    fn recv<'a>(&'a self, buffer: &'a mut [u8]) -> BoxFuture<'a, io::Result<usize>> {
+        Box::pin(tokio::net::UdpSocket::recv(self, buffer))
+    }
+
+    fn recv_from<'a>(
+        &'a self,
+        buffer: &'a mut [u8],
+    ) -> BoxFuture<'a, io::Result<(usize, Option<SocketAddr>)>> {
+        Box::pin(async move {
+            let (read, source) = tokio::net::UdpSocket::recv_from(self, buffer).await?;
+            Ok((read, Some(source)))
+        })
+    }
+
+    fn send<'a>(&'a self, data: &'a [u8]) -> BoxFuture<'a, io::Result<usize>> {
+        Box::pin(tokio::net::UdpSocket::send(self, data))
+    }
+
+    fn peer_addr(&self) -> Option<SocketAddr> {
+        tokio::net::UdpSocket::peer_addr(self).ok()
+    }
+
+    #[cfg(all(feature = "net-batch", target_os = "linux"))]
+    fn send_batch<'a>(&'a self, datagrams: &'a [Vec<u8>]) -> BoxFuture<'a, Vec<io::Result<usize>>> {
+        use std::os::fd::AsRawFd;
+
+        use tokio::io::Interest;
+
+        Box::pin(async move {
+            let mut results = Vec::with_capacity(datagrams.len());
+            while results.len() < datagrams.len() {
+                let remaining = &datagrams[results.len()..];
+                let sent = match self.writable().await {
+                    Ok(()) => self.try_io(Interest::WRITABLE, || {
+                        super::batch::send(self.as_raw_fd(), remaining)
+                    }),
+                    Err(e) => Err(e),
+                };
+                match sent {
+                    Ok(sent) => results.extend(sent.into_iter().map(Ok)),
+                    Err(e) if e.kind() == io::ErrorKind::WouldBlock => {}
+                    // The datagram that failed is skipped, the ones after it are still sent.
+                    Err(e) => results.push(Err(e)),
+                }
+            }
+            results
+        })
+    }
+}
+
+/// Runtime backed by smol, tasks are spawned on the global smol executor.
+#[cfg(feature = "smol")]
+#[derive(Clone, Copy, Debug, Default)]
+pub struct Smol;
+
+#[cfg(feature = "smol")]
+impl Runtime for Smol {
+    fn spawn(&self, future: BoxFuture<'static, ()>) {
+        smol::spawn(future).detach();
+    }
+
+    fn sleep(&self, duration: Duration) -> BoxFuture<'static, ()> {
+        Box::pin(async move {
+            smol::Timer::after(duration).await;
+        })
+    }
+
+    fn udp_socket(&self, socket: net::UdpSocket) -> io::Result<Box<dyn UdpSocket>> {
+        Ok(Box::new(smol::net::UdpSocket::try_from(socket)?))
+    }
+}
+
+#[cfg(feature = "smol")]
+impl UdpSocket for smol::net::UdpSocket {
+    fn recv<'a>(&'a self, buffer: &'a mut [u8]) -> BoxFuture<'a, io::Result<usize>> {
+        Box::pin(smol::net::UdpSocket::recv(self, buffer))
+    }
+
+    fn recv_from<'a>(
+        &'a self,
+        buffer: &'a mut [u8],
+    ) -> BoxFuture<'a, io::Result<(usize, Option<SocketAddr>)>> {
+        Box::pin(async move {
+            let (read, source) = smol::net::UdpSocket::recv_from(self, buffer).await?;
+            Ok((read, Some(source)))
+        })
+    }
+
+    fn send<'a>(&'a self, data: &'a [u8]) -> BoxFuture<'a, io::Result<usize>> {
+        Box::pin(smol::net::UdpSocket::send(self, data))
+    }
+
+    fn peer_addr(&self) -> Option<SocketAddr> {
+        smol::net::UdpSocket::peer_addr(self).ok()
+    }
+}
+
+/// Runtime of the browser event loop, tasks are spawned as promises and sleeping uses
+/// `setTimeout` of the window.
+#[cfg(feature = "wasm")]
+#[derive(Clone, Copy, Debug, Default)]
+pub struct Wasm;
+
+#[cfg(feature = "wasm")]
+impl Runtime for Wasm {
+    fn spawn(&self, future: BoxFuture<'static, ()>) {
+        wasm_bindgen_futures::spawn_local(future);
+    }
+
+    fn sleep(&self, duration: Duration) -> BoxFuture<'static, ()> {
+        use wasm_bindgen::{closure::Closure, JsCast};
+
+        // The closure isn't Send, so it's handed to the browser right away and the future only
+        // waits for it to fire.
+        let (sender, receiver) = futures::channel::oneshot::channel();
+        let callback = Closure::once_into_js(move || {
+            let _ = sender.send(());
+        });
+        if let Some(window) = web_sys::window() {
+            let _ = window.set_timeout_with_callback_and_timeout_and_arguments_0(
+                callback.unchecked_ref(),
+                duration.as_millis().min(i32::MAX as u128) as i32,
+            );
+        }
+
+        Box::pin(async move {
+            let _ = receiver.await;
+        })
+    }
+
+    fn udp_socket(&self, _socket: net::UdpSocket) -> io::Result<Box<dyn UdpSocket>> {
+        Err(io::ErrorKind::Unsupported.into())
+    }
+}
+
+#[cfg(all(test, feature = "tokio", feature = "net-batch", target_os = "linux"))]
+mod tests {
+    use std::{net::Ipv4Addr, time::Duration};
+
+    use pretty_assertions::assert_eq;
+    use rstest::rstest;
+
+    use super::UdpSocket;
+
+    #[rstest]
+    fn send_batch_skips_datagram_that_fails() {
+        let receiver = std::net::UdpSocket::bind((Ipv4Addr::LOCALHOST, 0)).unwrap();
+        receiver
+            .set_read_timeout(Some(Duration::from_secs(5)))
+            .unwrap();
+        // Larger than any UDP datagram, so it fails with EMSGSIZE.
+        let datagrams = vec![vec![1], vec![2; 70_000], vec![3, 3]];
+
+        let results = tokio::runtime::Runtime::new().unwrap().block_on(async {
+            let sender = tokio::net::UdpSocket::bind((Ipv4Addr::LOCALHOST, 0))
+                .await
+                .unwrap();
+            sender
+                .connect(receiver.local_addr().unwrap())
+                .await
+                .unwrap();
+            UdpSocket::send_batch(&sender, &datagrams).await
+        });
+
+        let results = results
+            .into_iter()
+            .map(|result| result.map_err(|e| e.raw_os_error()))
+            .collect::<Vec<_>>();
+        assert_eq!(vec![Ok(1), Err(Some(libc::EMSGSIZE)), Ok(2)], results);
+        let mut buffer = [0; 16];
+        for data in [vec![1], vec![3, 3]] {
+            let read = receiver.recv(&mut buffer).unwrap();
+            assert_eq!(data.as_slice(), &buffer[..read]);
+        }
+    }
+}
@@ -6,35 +6,79 @@ use crate::protocol::{
     },
     transaction::PATH_MTU,
 };
-use std::sync::Arc;
-
-use log::error;
-use tokio::{
-    net::UdpSocket,
-    pin, select, spawn,
-    sync::{
-        mpsc::{channel, unbounded_channel, Receiver, Sender, UnboundedReceiver, UnboundedSender},
-        Mutex,
-    },
-    time::sleep,
+use std::{
+    collections::HashMap,
+    io::{self, ErrorKind},
+    net::SocketAddr,
+    sync::{self, Arc},
+    time::Duration,
+};
+
+use futures::{
+    channel::mpsc::{channel, unbounded, Receiver, Sender, UnboundedReceiver, UnboundedSender},
+    future::BoxFuture,
+    lock::Mutex,
+    select, SinkExt, StreamExt,
 };
+use log::{debug, error, warn};
 
 use crate::{
-    codec::Token,
+    client::progress::Progress,
+    codec::{Token, TokenLength},
     protocol::{
-        effect::{Effect, Effects, Timeout},
-        event::Event,
+        body::Body,
+        effect::{prioritize, Effect, Effects, Timeout},
+        empty_message::EmptyMessage,
+        event::{Event, Events},
         new_request::NewRequest,
         response,
     },
 };
 
-use super::response::Response;
+use super::{
+    response::Response,
+    runtime::{Runtime, UdpSocket},
+};
 
 #[derive(Debug)]
 pub enum Request {
     Accepted(Token, Receiver<Result<Response, response::Error>>),
-    Rejected(),
+    Rejected(Rejection),
+}
+
+/// Why the system didn't take on a request or ping.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Rejection {
+    /// As many requests as the system keeps track of are waiting to be resolved.
+    QueueFull,
+    /// None of the sockets of the system is connected to the destination of the request.
+    NoRoute(SocketAddr),
+    /// No token could be generated for the request.
+    TokenGenFailure,
+    /// The system stopped running.
+    Shutdown,
+}
+
+impl From<Rejection> for response::Error {
+    fn from(rejection: Rejection) -> Self {
+        match rejection {
+            Rejection::QueueFull => response::Error::QueueFull,
+            Rejection::NoRoute(_) => response::Error::Transport(ErrorKind::AddrNotAvailable),
+            Rejection::TokenGenFailure => response::Error::TokenGeneration,
+            Rejection::Shutdown => response::Error::Shutdown,
+        }
+    }
+}
+
+impl From<Rejection> for ping::Error {
+    fn from(rejection: Rejection) -> Self {
+        match rejection {
+            Rejection::QueueFull => ping::Error::QueueFull,
+            Rejection::NoRoute(_) => ping::Error::Transport(ErrorKind::AddrNotAvailable),
+            Rejection::TokenGenFailure => ping::Error::TokenGeneration,
+            Rejection::Shutdown => ping::Error::Shutdown,
+        }
+    }
 }
 
 #[derive(Debug)]
@@ -46,13 +90,54 @@ pub enum RequestSender {
 #[derive(Debug)]
 pub enum Command {
     Request(NewRequest, Sender<Request>),
+    /// Request sent on the socket connected to the address, see [`System::with_socket`].
+    RequestTo(NewRequest, SocketAddr, Sender<Request>),
+    /// POST or PUT whose payload is uploaded block-wise from the body.
+    Upload(NewRequest, Body, Sender<Request>),
     Cancel(Token),
+    Send(EmptyMessage),
     Ping(
         Ping,
-        Sender<Result<(Token, Receiver<Result<(), ping::Error>>), ()>>,
+        Sender<Result<(Token, Receiver<Result<(), ping::Error>>), Rejection>>,
     ),
 }
 
+/// Datagram event with the index of the socket it was received on and the address it came from,
+/// or why receiving failed.
+type Received = Result<(usize, Option<SocketAddr>, Event), io::ErrorKind>;
+
+/// Pause after a receive failed with an error that isn't known to be transient, doubled for each
+/// failure in a row up to [`MAX_RECEIVE_BACKOFF`].
+const MIN_RECEIVE_BACKOFF: Duration = Duration::from_millis(10);
+const MAX_RECEIVE_BACKOFF: Duration = Duration::from_secs(1);
+
+/// Tokens generated for a request before giving up on finding one that isn't in use.
+const MAX_TOKEN_ATTEMPTS: usize = 16;
+
+/// How receiving carries on after a failed receive.
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum Recovery {
+    Retry,
+    Backoff,
+    Stop,
+}
+
+impl Recovery {
+    fn of(kind: ErrorKind) -> Self {
+        match kind {
+            // A connected socket reports an ICMP error for an earlier datagram on the next
+            // receive, the socket itself is fine and the peer may come back.
+            ErrorKind::ConnectionRefused
+            | ErrorKind::ConnectionReset
+            | ErrorKind::Interrupted
+            | ErrorKind::WouldBlock => Recovery::Retry,
+            // The transport is gone, like a closed WebSocket.
+            ErrorKind::ConnectionAborted | ErrorKind::NotConnected => Recovery::Stop,
+            _ => Recovery::Backoff,
+        }
+    }
+}
+
 #[derive(Debug)]
 pub struct System {
     requests: Vec<(Token, RequestSender)>,
@@ -60,8 +145,31 @@ pub struct System {
     command_sender: UnboundedSender<Command>,
     timeout_receiver: Arc<Mutex<UnboundedReceiver<Timeout>>>,
     timeout_sender: UnboundedSender<Timeout>,
-    incoming_socket_receiver: Arc<Mutex<UnboundedReceiver<Vec<u8>>>>,
-    udp_socket: Arc<UdpSocket>,
+    incoming_socket_receiver: Arc<Mutex<UnboundedReceiver<Received>>>,
+    /// Taken once receiving starts, on the first poll.
+    incoming_socket_sender: Option<UnboundedSender<Received>>,
+    max_datagram_size: usize,
+    max_requests: Option<usize>,
+    /// Requesters of the observations that go on after their request was resolved.
+    observations: Vec<(Token, Sender<Result<Response, response::Error>>)>,
+    /// Events raised while dispatching effects, handed out by the next poll.
+    pending: Events,
+    progress: Arc<sync::Mutex<Progress>>,
+    /// Index of the socket the datagram of the event being handled was received on, acknowledgements
+    /// and resets go out on it.
+    received_on: usize,
+    /// Socket of each request sent to the peer of another socket than the first.
+    routes: HashMap<Token, usize>,
+    runtime: Arc<dyn Runtime>,
+    /// Sockets datagrams are sent and received on. Requests go out on the first one unless they
+    /// are sent to the peer of another.
+    sockets: Vec<Arc<dyn UdpSocket>>,
+    /// Address the datagram of the event being handled came from, filled in as the endpoint of
+    /// the responses it resolves.
+    source: Option<SocketAddr>,
+    token_length: TokenLength,
+    /// Receives the messages with a reserved code the processor hands over.
+    unknown_messages: Option<UnboundedSender<Vec<u8>>>,
 }
 
 impl System {
@@ -69,140 +177,365 @@ impl System {
         channel(2)
     }
 
-    pub fn new(udp_socket: UdpSocket) -> Self {
-        let (incoming_socket_sender, incoming_socket_receiver) = unbounded_channel::<Vec<u8>>();
-
-        let udp_socket = Arc::new(udp_socket);
-        let socket_for_loop = udp_socket.clone();
-
-        spawn(async move {
-            loop {
-                let mut buffer = [0u8; PATH_MTU];
+    pub fn new(runtime: Arc<dyn Runtime>, udp_socket: Box<dyn UdpSocket>) -> Self {
+        let (incoming_socket_sender, incoming_socket_receiver) = unbounded();
 
-                let read = socket_for_loop.recv(&mut buffer).await.unwrap();
-                if let Err(e) = incoming_socket_sender.send(buffer[..read].to_vec()) {
-                    println!("Failed to send data on incoming socket sender: {e:?}");
-                    return;
-                }
-            }
-        });
-
-        let (command_sender, command_receiver) = unbounded_channel();
-        let (timeout_sender, timeout_receiver) = unbounded_channel();
+        let (command_sender, command_receiver) = unbounded();
+        let (timeout_sender, timeout_receiver) = unbounded();
         Self {
-            udp_socket,
+            runtime,
+            sockets: vec![Arc::from(udp_socket)],
             incoming_socket_receiver: Arc::new(Mutex::new(incoming_socket_receiver)),
+            incoming_socket_sender: Some(incoming_socket_sender),
+            max_datagram_size: PATH_MTU,
             timeout_receiver: Arc::new(Mutex::new(timeout_receiver)),
             timeout_sender,
             command_receiver: Arc::new(Mutex::new(command_receiver)),
             command_sender,
+            max_requests: None,
+            observations: vec![],
+            pending: vec![],
+            progress: Default::default(),
+            received_on: 0,
             requests: Default::default(),
+            routes: HashMap::new(),
+            source: None,
+            token_length: TokenLength::decode(TokenLength::DEFAULT),
+            unknown_messages: None,
         }
     }
 
+    /// Largest datagram received in full, [`PATH_MTU`] by default. Larger ones are cut off by the
+    /// socket and handed over as [`Event::TruncatedDataReceived`].
+    pub fn with_max_datagram_size(mut self, max_datagram_size: usize) -> Self {
+        self.max_datagram_size = max_datagram_size;
+        self
+    }
+
+    /// Adds a socket to send and receive on, like an IPv6 socket next to the IPv4 one the system
+    /// was created with, so a dual-stack gateway keeps a single transaction state. Requests go out
+    /// on it when they are sent to the address it's connected to, see [`Command::RequestTo`].
+    pub fn with_socket(mut self, udp_socket: Box<dyn UdpSocket>) -> Self {
+        self.sockets.push(Arc::from(udp_socket));
+        self
+    }
+
+    /// Largest number of requests and pings waiting to be resolved, unbounded by default. Any
+    /// beyond it are rejected with [`Rejection::QueueFull`].
+    pub fn with_max_requests(mut self, max_requests: usize) -> Self {
+        self.max_requests = Some(max_requests);
+        self
+    }
+
+    /// Length of the tokens generated for requests, [`TokenLength::DEFAULT`] by default. A token
+    /// already used by a request or observation in flight is generated again.
+    pub fn with_token_length(mut self, token_length: TokenLength) -> Self {
+        self.token_length = token_length;
+        self
+    }
+
+    /// Sends the datagrams of [`Effect::UnknownMessageReceived`] to `sender` instead of dropping
+    /// them.
+    pub fn with_unknown_messages(mut self, sender: UnboundedSender<Vec<u8>>) -> Self {
+        self.unknown_messages = Some(sender);
+        self
+    }
+
     pub fn get_sender(&self) -> UnboundedSender<Command> {
         self.command_sender.clone()
     }
 
-    async fn on_command(&mut self, command: Command) -> Result<Event, ()> {
+    /// Status of the requests yet to be resolved, to be updated after each tick.
+    pub fn progress(&self) -> Arc<sync::Mutex<Progress>> {
+        self.progress.clone()
+    }
+
+    async fn on_command(&mut self, command: Command) -> Result<Option<Event>, ()> {
         match command {
-            Command::Request(request, sender) => self.handle_request(request, sender).await,
+            Command::Request(request, sender) => Ok(self
+                .handle_request(sender, 0, |token| {
+                    Event::TransactionRequested(request, token)
+                })
+                .await),
+            Command::RequestTo(request, destination, mut sender) => {
+                let Some(socket) = self.socket_to(destination) else {
+                    let rejection = Request::Rejected(Rejection::NoRoute(destination));
+                    if let Err(e) = sender.send(rejection).await {
+                        error!("Failed to send Request::Rejected to client: {e:?}");
+                    }
+                    return Ok(None);
+                };
+                Ok(self
+                    .handle_request(sender, socket, |token| {
+                        Event::TransactionRequested(request, token)
+                    })
+                    .await)
+            }
+            Command::Upload(request, body, sender) => Ok(self
+                .handle_request(sender, 0, |token| {
+                    Event::UploadRequested(request, body, token)
+                })
+                .await),
             Command::Cancel(token) => self.handle_cancel(token),
-            Command::Ping(ping, sender) => self.ping(ping, sender).await,
+            Command::Send(empty_message) => Ok(Some(Event::EmptyMessageRequested(empty_message))),
+            Command::Ping(ping, sender) => Ok(self.ping(ping, sender).await),
+        }
+    }
+
+    /// Index of the socket connected to `destination`.
+    fn socket_to(&self, destination: SocketAddr) -> Option<usize> {
+        self.sockets
+            .iter()
+            .position(|socket| socket.peer_addr() == Some(destination))
+    }
+
+    /// Index of the socket `data` goes out on. Requests go out on the socket of their token,
+    /// empty messages, which answer a received message, on the socket it was received on.
+    fn route(&self, data: &[u8]) -> usize {
+        let token = data.first().and_then(|byte| {
+            let length = usize::from(TokenLength::decode(*byte).value());
+            data.get(4..4 + length)
+        });
+
+        match token.and_then(|token| Token::from_slice(token).ok()) {
+            Some(token) if !token.is_empty() => self.routes.get(&token).copied().unwrap_or(0),
+            _ => self.received_on,
         }
     }
 
-    fn handle_cancel(&mut self, token: Token) -> Result<Event, ()> {
-        self.requests.retain(|(t, _)| *t == token);
-        Ok(Event::TransactionCanceled(token))
+    fn handle_cancel(&mut self, token: Token) -> Result<Option<Event>, ()> {
+        Ok(Some(Event::TransactionCanceled(token)))
+    }
+
+    /// Token for a new request, unless the request has to be rejected.
+    fn accept(&self) -> Result<Token, Rejection> {
+        if self
+            .max_requests
+            .is_some_and(|max_requests| self.requests.len() >= max_requests)
+        {
+            return Err(Rejection::QueueFull);
+        }
+
+        (0..MAX_TOKEN_ATTEMPTS)
+            .map(|_| Token::random(self.token_length))
+            .find(|token| !self.is_in_use(token))
+            .ok_or(Rejection::TokenGenFailure)
+    }
+
+    fn is_in_use(&self, token: &Token) -> bool {
+        self.requests.iter().any(|(used, _)| used == token)
+            || self.observations.iter().any(|(used, _)| used == token)
     }
 
     async fn ping(
         &mut self,
         ping: Ping,
-        sender: Sender<Result<(Token, Receiver<Result<(), ping::Error>>), ()>>,
-    ) -> Result<Event, ()> {
-        let token = Token::new().map_err(|_| ())?;
+        mut sender: Sender<Result<(Token, Receiver<Result<(), ping::Error>>), Rejection>>,
+    ) -> Option<Event> {
+        let token = match self.accept() {
+            Ok(token) => token,
+            Err(rejection) => {
+                if let Err(e) = sender.send(Err(rejection)).await {
+                    error!("Failed to send ping rejection to client: {e:?}");
+                }
+                return None;
+            }
+        };
 
+        self.progress.lock().unwrap().accepted(token);
         let (result_sender, result_receiver) = channel(1);
-        if let Err(e) = sender.send(Ok((token.clone(), result_receiver))).await {
+        if let Err(e) = sender.send(Ok((token, result_receiver))).await {
             error!("Failed to send Request::Accepted to client: {e:?}");
-            return Err(());
+            return None;
         }
 
         self.requests
-            .push((token.clone(), RequestSender::Ping(result_sender)));
+            .push((token, RequestSender::Ping(result_sender)));
 
-        Ok(Event::TransactionRequested(NewRequest::Ping(ping), token))
+        Some(Event::TransactionRequested(NewRequest::Ping(ping), token))
     }
 
+    /// Event of an accepted request, made by `event` from the token of the request, which goes
+    /// out on the socket with index `socket`.
     async fn handle_request(
         &mut self,
-        request: NewRequest,
-        sender: Sender<Request>,
-    ) -> Result<Event, ()> {
-        let token = Token::new().map_err(|_| ())?;
+        mut sender: Sender<Request>,
+        socket: usize,
+        event: impl FnOnce(Token) -> Event,
+    ) -> Option<Event> {
+        let token = match self.accept() {
+            Ok(token) => token,
+            Err(rejection) => {
+                if let Err(e) = sender.send(Request::Rejected(rejection)).await {
+                    error!("Failed to send Request::Rejected to client: {e:?}");
+                }
+                return None;
+            }
+        };
 
+        self.progress.lock().unwrap().accepted(token);
         let (result_sender, result_receiver) = channel(1);
-        if let Err(e) = sender
-            .send(Request::Accepted(token.clone(), result_receiver))
-            .await
-        {
+        if let Err(e) = sender.send(Request::Accepted(token, result_receiver)).await {
             error!("Failed to send Request::Accepted to client: {e:?}");
-            return Err(());
+            return None;
         }
 
         self.requests
-            .push((token.clone(), RequestSender::Request(result_sender)));
+            .push((token, RequestSender::Request(result_sender)));
+        if socket != 0 {
+            self.routes.insert(token, socket);
+        }
 
-        Ok(Event::TransactionRequested(request, token))
+        Some(event(token))
     }
 
     async fn on_timeout(&mut self, timeout: Timeout) -> Result<Event, ()> {
         Ok(Event::TimeoutReached(timeout))
     }
 
-    async fn on_socket_data(&mut self, data: Vec<u8>) -> Result<Event, ()> {
-        Ok(Event::DataReceived(data))
+    /// Spawns a loop receiving from each socket, unless they are running already.
+    ///
+    /// Failed receives are reported, except for interruptions, and receiving carries on. Right
+    /// away for errors caused by an earlier datagram, like an unreachable peer, after a growing
+    /// pause for others. Only a transport that is gone ends receiving.
+    fn start_receiving(&mut self) {
+        let Some(incoming_socket_sender) = self.incoming_socket_sender.take() else {
+            return;
+        };
+        for (index, socket) in self.sockets.iter().enumerate() {
+            self.runtime.spawn(Self::receive(
+                index,
+                socket.clone(),
+                self.runtime.clone(),
+                incoming_socket_sender.clone(),
+                self.max_datagram_size,
+            ));
+        }
     }
 
+    fn receive(
+        index: usize,
+        socket: Arc<dyn UdpSocket>,
+        runtime: Arc<dyn Runtime>,
+        incoming_socket_sender: UnboundedSender<Received>,
+        max_datagram_size: usize,
+    ) -> BoxFuture<'static, ()> {
+        let mut buffer = vec![0u8; max_datagram_size + 1];
+
+        Box::pin(async move {
+            let mut backoff = MIN_RECEIVE_BACKOFF;
+            loop {
+                let received = match socket.recv_from(&mut buffer).await {
+                    Ok((read, source)) if read == buffer.len() => {
+                        warn!("Dropping datagram larger than {} bytes", read - 1);
+                        Ok((index, source, Event::TruncatedDataReceived(buffer.clone())))
+                    }
+                    Ok((read, source)) => {
+                        let data = buffer[..read].to_vec();
+                        let event = match source {
+                            Some(source) => Event::DataReceivedFrom(source, data),
+                            None => Event::DataReceived(data),
+                        };
+                        Ok((index, source, event))
+                    }
+                    Err(e) => Err(e.kind()),
+                };
+
+                let recovery = match &received {
+                    Ok(_) => {
+                        backoff = MIN_RECEIVE_BACKOFF;
+                        Recovery::Retry
+                    }
+                    Err(kind @ (ErrorKind::Interrupted | ErrorKind::WouldBlock)) => {
+                        debug!("Receiving on udp socket was interrupted: {kind}");
+                        continue;
+                    }
+                    Err(kind) => Recovery::of(*kind),
+                };
+                if let Err(e) = incoming_socket_sender.unbounded_send(received) {
+                    error!("Failed to send data on incoming socket sender: {e:?}");
+                    return;
+                }
+
+                match recovery {
+                    Recovery::Retry => {}
+                    Recovery::Backoff => {
+                        runtime.sleep(backoff).await;
+                        backoff = (backoff * 2).min(MAX_RECEIVE_BACKOFF);
+                    }
+                    Recovery::Stop => return,
+                }
+            }
+        })
+    }
+
+    async fn on_socket_data(&mut self, data: Received) -> Result<Event, ()> {
+        Ok(match data {
+            Ok((index, source, event)) => {
+                self.received_on = index;
+                self.source = source;
+                event
+            }
+            Err(kind) => {
+                debug!("Failed to receive on udp socket: {kind}");
+                Event::TransportFailed(kind)
+            }
+        })
+    }
+
+    /// Waits for the next event, commands that don't lead to one, like rejected requests, are
+    /// handled along the way.
     pub async fn poll(&mut self) -> Result<Event, ()> {
+        self.start_receiving();
+        self.received_on = 0;
+        self.source = None;
+
+        if !self.pending.is_empty() {
+            return Ok(self.pending.remove(0));
+        }
+
+        loop {
+            if let Some(event) = self.poll_once().await? {
+                return Ok(event);
+            }
+        }
+    }
+
+    async fn poll_once(&mut self) -> Result<Option<Event>, ()> {
         let command_receiver = self.command_receiver.clone();
         let command_receiver = &mut command_receiver.lock().await;
-        let command_future = command_receiver.recv();
-        pin!(command_future);
+        let mut command_future = command_receiver.next();
 
         let timeouts_receiver = self.timeout_receiver.clone();
         let timeouts_receiver = &mut timeouts_receiver.lock().await;
-        let timeouts_future = timeouts_receiver.recv();
-        pin!(timeouts_future);
+        let mut timeouts_future = timeouts_receiver.next();
 
         let socket_receiver = self.incoming_socket_receiver.clone();
         let socket_receiver = &mut socket_receiver.lock().await;
-        let socket_future = socket_receiver.recv();
-        pin!(socket_future);
+        let mut socket_future = socket_receiver.next();
 
         select! {
-            result = &mut command_future => {
-                return self.on_command(result.ok_or(())?).await
+            result = command_future => {
+                self.on_command(result.ok_or(())?).await
             }
-            result = &mut timeouts_future => {
-                return self.on_timeout(result.ok_or(())?).await
+            result = timeouts_future => {
+                self.on_timeout(result.ok_or(())?).await.map(Some)
             }
-            result = &mut socket_future => {
-                return self.on_socket_data(result.ok_or(())?).await
+            result = socket_future => {
+                self.on_socket_data(result.ok_or(())?).await.map(Some)
             }
-        };
+        }
     }
 
     async fn on_non_lifetime_timeout(&mut self, timeout: NonLifetimeTimeout) {
         let timeout_sender = self.timeout_sender.clone();
-        tokio::spawn(async move {
-            sleep(*timeout.timeout()).await;
-            if let Err(e) = timeout_sender.send(Timeout::NonLifetime(timeout)) {
+        let sleep = self.runtime.sleep(*timeout.timeout());
+        self.runtime.spawn(Box::pin(async move {
+            sleep.await;
+            if let Err(e) = timeout_sender.unbounded_send(Timeout::NonLifetime(timeout)) {
                 error!("Failed to send non lifetime timeout: {e:?}");
             }
-        });
+        }));
     }
 
     async fn on_con_lifetime_timeout(
@@ -210,42 +543,46 @@ impl System {
         exchange_lifetime_timeout: ExchangeLifetimeTimeout,
     ) {
         let timeout_sender = self.timeout_sender.clone();
-        tokio::spawn(async move {
-            sleep(*exchange_lifetime_timeout.timeout()).await;
-            if let Err(e) = timeout_sender.send(exchange_lifetime_timeout.into()) {
+        let sleep = self.runtime.sleep(*exchange_lifetime_timeout.timeout());
+        self.runtime.spawn(Box::pin(async move {
+            sleep.await;
+            if let Err(e) = timeout_sender.unbounded_send(exchange_lifetime_timeout.into()) {
                 error!("Failed to send exchange timeout: {e:?}");
             }
-        });
+        }));
     }
 
     async fn on_retransmission_timeout(&mut self, timeout: RetransmissionTimeout) {
         let timeout_sender = self.timeout_sender.clone();
-        tokio::spawn(async move {
-            sleep(*timeout.timeout()).await;
-            if let Err(e) = timeout_sender.send(timeout.into()) {
+        let sleep = self.runtime.sleep(*timeout.timeout());
+        self.runtime.spawn(Box::pin(async move {
+            sleep.await;
+            if let Err(e) = timeout_sender.unbounded_send(timeout.into()) {
                 error!("Failed to send retransmission timeout: {e:?}");
             }
-        });
+        }));
     }
 
     async fn on_non_retransmission_timeout(&mut self, timeout: NonRetransmissionTimeout) {
         let timeout_sender = self.timeout_sender.clone();
-        tokio::spawn(async move {
-            sleep(*timeout.timeout()).await;
-            if let Err(e) = timeout_sender.send(timeout.into()) {
+        let sleep = self.runtime.sleep(*timeout.timeout());
+        self.runtime.spawn(Box::pin(async move {
+            sleep.await;
+            if let Err(e) = timeout_sender.unbounded_send(timeout.into()) {
                 error!("Failed to send non retransmission timeout: {e:?}");
             }
-        });
+        }));
     }
 
     async fn on_max_transmit_wait(&mut self, timeout: MaxTransmitWaitTimeout) {
         let timeout_sender = self.timeout_sender.clone();
-        tokio::spawn(async move {
-            sleep(*timeout.timeout()).await;
-            if let Err(e) = timeout_sender.send(timeout.into()) {
+        let sleep = self.runtime.sleep(*timeout.timeout());
+        self.runtime.spawn(Box::pin(async move {
+            sleep.await;
+            if let Err(e) = timeout_sender.unbounded_send(timeout.into()) {
                 error!("Failed to send max transmit wait timeout: {e:?}");
             }
-        });
+        }));
     }
 
     async fn on_create_timeout(&mut self, timeout: Timeout) {
@@ -266,6 +603,8 @@ impl System {
     }
 
     fn remove_request_by_token(&mut self, token: &Token) -> Option<RequestSender> {
+        self.progress.lock().unwrap().resolved(token);
+
         let Some(position) = self
             .requests
             .iter()
@@ -280,20 +619,72 @@ impl System {
     async fn on_transaction_resolved(
         &mut self,
         token: Token,
-        result: Result<Response, response::Error>,
+        mut result: Result<Response, response::Error>,
     ) {
+        if let Ok(response) = &mut result {
+            response.metrics.endpoint = self.source;
+        }
+
         let Some(request) = self.remove_request_by_token(&token) else {
             return;
         };
 
         match request {
             RequestSender::Ping(sender) => Self::on_ping_resolved(sender, result).await,
-            RequestSender::Request(sender) => Self::on_request_resolved(sender, result).await,
+            RequestSender::Request(sender) => {
+                let observed = result
+                    .as_ref()
+                    .is_ok_and(|response| response.continues_observation());
+                if observed {
+                    self.observations.push((token, sender.clone()));
+                }
+                Self::on_request_resolved(sender, result).await
+            }
+        }
+    }
+
+    /// Hands a message with a reserved code to the receiver of unknown messages, if there's
+    /// anyone to receive it.
+    fn on_unknown_message(&mut self, data: Vec<u8>) {
+        let Some(sender) = &self.unknown_messages else {
+            debug!("Ignoring message with reserved code: {data:?}");
+            return;
+        };
+        if let Err(e) = sender.unbounded_send(data) {
+            debug!("Ignoring message with reserved code: {:?}", e.into_inner());
+        }
+    }
+
+    /// Hands the notification to the requester of the observation, the observation is canceled
+    /// once the requester is gone.
+    fn on_notification_received(&mut self, token: Token, mut response: Response) {
+        response.metrics.endpoint = self.source;
+
+        let Some(position) = self
+            .observations
+            .iter()
+            .position(|(observation_token, _)| *observation_token == token)
+        else {
+            return;
+        };
+
+        let continues = response.continues_observation();
+        let sender = &mut self.observations[position].1;
+        if let Err(e) = sender.try_send(Ok(response)) {
+            if e.is_disconnected() {
+                self.observations.swap_remove(position);
+                self.pending.push(Event::TransactionCanceled(token));
+                return;
+            }
+            warn!("Dropping notification the requester isn't keeping up with");
+        }
+        if !continues {
+            self.observations.swap_remove(position);
         }
     }
 
     async fn on_request_resolved(
-        sender: Sender<Result<Response, response::Error>>,
+        mut sender: Sender<Result<Response, response::Error>>,
         result: Result<Response, response::Error>,
     ) {
         if let Err(e) = sender.send(result).await {
@@ -302,7 +693,7 @@ impl System {
     }
 
     async fn on_ping_resolved(
-        sender: Sender<Result<(), ping::Error>>,
+        mut sender: Sender<Result<(), ping::Error>>,
         result: Result<Response, response::Error>,
     ) {
         if let Err(e) = sender.send(ping::into_result(result)).await {
@@ -310,22 +701,70 @@ impl System {
         }
     }
 
-    async fn on_transmit(&mut self, data: Vec<u8>) {
-        if let Err(e) = self.udp_socket.send(&data).await {
-            println!("Failed to send on udp socket: {e:?}");
+    /// Sends the datagrams in one go per socket when the socket supports it, see
+    /// [`UdpSocket::send_batch`].
+    async fn on_transmit(&mut self, datagrams: Vec<Vec<u8>>) {
+        let mut batches = vec![vec![]; self.sockets.len()];
+        for data in datagrams {
+            batches[self.route(&data)].push(data);
+        }
+
+        for (socket, datagrams) in self.sockets.clone().iter().zip(batches) {
+            if datagrams.is_empty() {
+                continue;
+            }
+
+            let results = socket.send_batch(&datagrams).await;
+            for (data, result) in datagrams.into_iter().zip(results) {
+                if let Err(e) = result {
+                    debug!("Failed to send on udp socket: {e:?}");
+                    self.pending.push(Event::TransmitFailed(data, e.kind()));
+                }
+            }
         }
     }
 
-    pub async fn dispatch(&mut self, effects: Effects) -> Result<(), ()> {
+    /// Carries out `effects`, acknowledgements and resets first, see [`prioritize`].
+    pub async fn dispatch(&mut self, mut effects: Effects) -> Result<(), ()> {
+        prioritize(&mut effects);
+
+        // Consecutive transmissions are sent together.
+        let mut transmissions = vec![];
         for effect in effects {
+            if !matches!(effect, Effect::Transmit(_)) {
+                self.on_transmit(std::mem::take(&mut transmissions)).await;
+            }
+
             match effect {
                 Effect::CreateTimeout(timeout) => self.on_create_timeout(timeout).await,
-                Effect::Transmit(data) => self.on_transmit(data).await,
+                Effect::TransactionCanceled(token) => {
+                    self.remove_request_by_token(&token);
+                    self.observations
+                        .retain(|(observation_token, _)| *observation_token != token);
+                }
+                Effect::NotificationReceived(token, response) => {
+                    self.on_notification_received(token, response);
+                }
+                Effect::Transmit(data) => transmissions.push(data),
+                Effect::UnknownMessageReceived(data) => self.on_unknown_message(data),
+                Effect::MalformedDatagramReceived(data) => {
+                    debug!("Ignoring malformed datagram: {data:?}");
+                }
                 Effect::TransactionResolved(token, result) => {
                     self.on_transaction_resolved(token, result).await;
                 }
             }
         }
+        self.on_transmit(transmissions).await;
+
+        if !self.routes.is_empty() {
+            let routes = std::mem::take(&mut self.routes);
+            self.routes = routes
+                .into_iter()
+                .filter(|(token, _)| self.is_in_use(token))
+                .collect();
+        }
+
         Ok(())
     }
 }
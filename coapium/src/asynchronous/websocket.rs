@@ -0,0 +1,172 @@
+//! CoAP over WebSocket, [RFC 8323](https://datatracker.ietf.org/doc/html/rfc8323#section-4), for
+//! clients running in the browser.
+//!
+//! The client and its request builders are the same as over UDP, only the socket differs:
+//!
+//! ```ignore
+//! let client = coapium::asynchronous::websocket::connect("wss://example.com/.well-known/coap")?;
+//! let response = client.execute(request).await;
+//! ```
+
+use futures::{
+    channel::mpsc::{unbounded, UnboundedReceiver, UnboundedSender},
+    future::BoxFuture,
+    lock::Mutex,
+    select, FutureExt, StreamExt,
+};
+use std::io;
+use wasm_bindgen::{closure::Closure, JsCast, JsValue};
+use web_sys::{BinaryType, MessageEvent};
+
+use crate::protocol::{interceptor::RequestInterceptor, websocket::WebSocketBridge};
+
+use super::{
+    runtime::{UdpSocket, Wasm},
+    Client,
+};
+
+/// Sub-protocol registered for CoAP in the WebSocket handshake.
+pub const PROTOCOL: &str = "coap";
+
+/// Datagram socket on top of a browser WebSocket.
+///
+/// The WebSocket lives in a task of its own as it can't leave the thread it was created on, the
+/// socket only exchanges datagrams with that task.
+#[derive(Debug)]
+pub struct WebSocket {
+    receiver: Mutex<UnboundedReceiver<Vec<u8>>>,
+    sender: UnboundedSender<Vec<u8>>,
+}
+
+/// Opens a WebSocket to `url` and creates a client on top of it.
+pub fn connect(url: &str) -> Result<Client, JsValue> {
+    connect_with_interceptors(url, vec![])
+}
+
+/// Opens a WebSocket to `url` and creates a client where every request and response passes
+/// through the interceptors, in order.
+pub fn connect_with_interceptors(
+    url: &str,
+    interceptors: Vec<Box<dyn RequestInterceptor>>,
+) -> Result<Client, JsValue> {
+    let socket = WebSocket::connect(url)?;
+    Ok(Client::with_socket(Wasm, Box::new(socket), interceptors))
+}
+
+impl WebSocket {
+    /// Opens a WebSocket to `url`, datagrams sent before it's open are held back until it is.
+    pub fn connect(url: &str) -> Result<Self, JsValue> {
+        let websocket = web_sys::WebSocket::new_with_str(url, PROTOCOL)?;
+        websocket.set_binary_type(BinaryType::Arraybuffer);
+
+        let (opened_sender, opened_receiver) = futures::channel::oneshot::channel::<()>();
+        let (frame_sender, frame_receiver) = unbounded::<Vec<u8>>();
+        let (incoming_sender, incoming_receiver) = unbounded();
+        let (outgoing_sender, outgoing_receiver) = unbounded();
+
+        let on_open = Closure::once(move || {
+            let _ = opened_sender.send(());
+        });
+        let on_message = {
+            let frame_sender = frame_sender.clone();
+            Closure::<dyn FnMut(MessageEvent)>::new(move |event: MessageEvent| {
+                if let Ok(buffer) = event.data().dyn_into::<js_sys::ArrayBuffer>() {
+                    let _ = frame_sender.unbounded_send(js_sys::Uint8Array::new(&buffer).to_vec());
+                }
+            })
+        };
+        let on_close = Closure::<dyn FnMut()>::new(move || frame_sender.close_channel());
+        websocket.set_onopen(Some(on_open.as_ref().unchecked_ref()));
+        websocket.set_onmessage(Some(on_message.as_ref().unchecked_ref()));
+        websocket.set_onclose(Some(on_close.as_ref().unchecked_ref()));
+        websocket.set_onerror(Some(on_close.as_ref().unchecked_ref()));
+
+        wasm_bindgen_futures::spawn_local(async move {
+            // The closures have to outlive every callback of the WebSocket.
+            let _callbacks = (on_open, on_message, on_close);
+
+            if opened_receiver.await.is_ok() {
+                bridge(
+                    &websocket,
+                    frame_receiver,
+                    outgoing_receiver,
+                    incoming_sender,
+                )
+                .await;
+            }
+
+            let _ = websocket.close();
+        });
+
+        Ok(Self {
+            receiver: Mutex::new(incoming_receiver),
+            sender: outgoing_sender,
+        })
+    }
+}
+
+async fn bridge(
+    websocket: &web_sys::WebSocket,
+    mut frames: UnboundedReceiver<Vec<u8>>,
+    mut outgoing: UnboundedReceiver<Vec<u8>>,
+    incoming: UnboundedSender<Vec<u8>>,
+) {
+    let mut bridge = WebSocketBridge::new();
+    if websocket
+        .send_with_u8_array(&WebSocketBridge::csm())
+        .is_err()
+    {
+        return;
+    }
+
+    loop {
+        let bridged = select! {
+            frame = frames.next() => match frame {
+                Some(frame) => bridge.incoming(&frame),
+                None => return,
+            },
+            datagram = outgoing.next() => match datagram {
+                Some(datagram) => bridge.outgoing(&datagram),
+                None => return,
+            },
+        };
+
+        if let Some(frame) = bridged.frame {
+            if websocket.send_with_u8_array(&frame).is_err() {
+                return;
+            }
+        }
+        if let Some(datagram) = bridged.datagram {
+            if incoming.unbounded_send(datagram).is_err() {
+                return;
+            }
+        }
+    }
+}
+
+impl UdpSocket for WebSocket {
+    fn recv<'a>(&'a self, buffer: &'a mut [u8]) -> BoxFuture<'a, io::Result<usize>> {
+        async move {
+            let datagram = self
+                .receiver
+                .lock()
+                .await
+                .next()
+                .await
+                .ok_or(io::ErrorKind::ConnectionAborted)?;
+            let length = datagram.len().min(buffer.len());
+            buffer[..length].copy_from_slice(&datagram[..length]);
+            Ok(length)
+        }
+        .boxed()
+    }
+
+    fn send<'a>(&'a self, data: &'a [u8]) -> BoxFuture<'a, io::Result<usize>> {
+        let result = self
+            .sender
+            .unbounded_send(data.to_vec())
+            .map(|_| data.len())
+            .map_err(|_| io::ErrorKind::ConnectionAborted.into());
+        Box::pin(futures::future::ready(result))
+    }
+}
@@ -0,0 +1,17 @@
+use std::{fmt::Debug, net::SocketAddr};
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Direction {
+    Received,
+    Sent,
+}
+
+/// Hook into the system to see every datagram as it went over the wire, for example to write a
+/// capture file to open in Wireshark.
+///
+/// Datagrams are handed over in the order they were sent or received, including malformed ones
+/// and ones cut off at the receive size.
+pub trait Capture: Debug + Send {
+    /// Called once `data` was sent to or received from `peer` on the `local` address.
+    fn datagram(&mut self, direction: Direction, local: SocketAddr, peer: SocketAddr, data: &[u8]);
+}
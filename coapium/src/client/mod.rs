@@ -1,5 +1,12 @@
+pub mod capture;
+pub mod progress;
+pub mod resolve;
+pub mod session_stats;
+pub mod socket_config;
 pub mod url;
 
+use std::io;
+
 use crate::{
     codec::option::{UriHost, UriPath, UriPort, UriQuery},
     protocol::{
@@ -10,6 +17,55 @@ use crate::{
 
 use self::url::Url;
 
+/// Why a client couldn't be created.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Error {
+    /// [`SocketConfig::any_source`](socket_config::SocketConfig::any_source) is set for a client
+    /// that doesn't support it.
+    AnySourceUnsupported,
+    Connect(io::ErrorKind),
+    /// None of the addresses the endpoints resolved to is of the address family of the socket,
+    /// like those of an IPv6-only host for a socket bound to an IPv4 address.
+    NoAddress,
+    /// The [`Resolve`](resolve::Resolve) implementation failed.
+    Resolve(io::ErrorKind),
+    Socket(socket_config::Error),
+}
+
+impl Error {
+    /// Closest I/O error kind, for callers that report every failure as one.
+    pub fn kind(&self) -> io::ErrorKind {
+        match self {
+            Error::AnySourceUnsupported => io::ErrorKind::Unsupported,
+            Error::Connect(kind) | Error::Resolve(kind) => *kind,
+            Error::NoAddress => io::ErrorKind::AddrNotAvailable,
+            Error::Socket(error) => error.kind(),
+        }
+    }
+}
+
+impl From<socket_config::Error> for Error {
+    fn from(error: socket_config::Error) -> Self {
+        Error::Socket(error)
+    }
+}
+
+impl std::fmt::Display for Error {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Error::AnySourceUnsupported => {
+                write!(f, "taking responses from any source is unsupported")
+            }
+            Error::Connect(kind) => write!(f, "failed to connect socket: {kind}"),
+            Error::NoAddress => write!(f, "no address of the socket's address family"),
+            Error::Resolve(kind) => write!(f, "failed to resolve endpoint: {kind}"),
+            Error::Socket(error) => error.fmt(f),
+        }
+    }
+}
+
+impl std::error::Error for Error {}
+
 pub trait RequestBuilder {
     fn port(self, port: UriPort) -> Self;
     fn host(self, host: UriHost) -> Self;
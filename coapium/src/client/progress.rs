@@ -0,0 +1,209 @@
+use std::collections::HashMap;
+
+use futures::channel::mpsc::UnboundedSender;
+
+use crate::{
+    codec::Token,
+    protocol::{
+        processor::Processor,
+        status::{State, Status},
+    },
+};
+
+/// Step a request took on its way to being resolved, see [`Progress::subscribe`].
+///
+/// A piggybacked response acknowledges the request along with resolving it, so only a request
+/// acknowledged with an empty acknowledgement is notified as acknowledged.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Step {
+    /// Sent for the first time.
+    Transmitted,
+    /// Sent again, for the `n`th time after the first.
+    Retransmitted(u8),
+    /// Acknowledged by the peer.
+    Acknowledged,
+    /// The response follows separately from the acknowledgement, RFC 7252 §5.2.2.
+    SeparateResponsePending,
+}
+
+/// Status of the requests a client has yet to resolve, kept up to date by its system.
+///
+/// A request is tracked from the moment its token is handed out until it's resolved, so a token
+/// that isn't tracked belongs to a resolved request.
+#[derive(Debug, Default)]
+pub struct Progress {
+    statuses: HashMap<Token, Status>,
+    subscribers: HashMap<Token, UnboundedSender<Step>>,
+}
+
+impl Progress {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn status(&self, token: &Token) -> Status {
+        self.statuses
+            .get(token)
+            .copied()
+            .unwrap_or_else(Status::resolved)
+    }
+
+    pub(crate) fn accepted(&mut self, token: Token) {
+        self.statuses.insert(token, Status::queued());
+    }
+
+    /// Notifies `subscriber` of each step the request of `token` takes until it's resolved, when
+    /// `subscriber` is dropped. Steps the request took already are notified right away.
+    pub fn subscribe(&mut self, token: Token, subscriber: UnboundedSender<Step>) {
+        let Some(status) = self.statuses.get(&token) else {
+            return;
+        };
+
+        notify(&subscriber, State::Queued, status.state);
+        self.subscribers.insert(token, subscriber);
+    }
+
+    pub(crate) fn resolved(&mut self, token: &Token) {
+        self.statuses.remove(token);
+        self.subscribers.remove(token);
+    }
+
+    /// Takes the status of each tracked request from `processor`, requests it hasn't been ticked
+    /// with yet stay queued.
+    pub(crate) fn update(&mut self, processor: &Processor) {
+        for (token, status) in &mut self.statuses {
+            let previous = status.state;
+            *status = processor.status(token).unwrap_or_else(Status::queued);
+
+            if let Some(subscriber) = self.subscribers.get(token) {
+                notify(subscriber, previous, status.state);
+            }
+        }
+    }
+}
+
+fn notify(subscriber: &UnboundedSender<Step>, from: State, to: State) {
+    for notification in notifications(from, to) {
+        // A subscriber that is gone just isn't notified anymore.
+        let _ = subscriber.unbounded_send(notification);
+    }
+}
+
+/// Steps between `from` and `to`, a transmission for each attempt in between.
+fn notifications(from: State, to: State) -> Vec<Step> {
+    match (from, to) {
+        (_, State::Transmitted { attempt }) => {
+            let sent = match from {
+                State::Transmitted { attempt } => attempt,
+                _ => 0,
+            };
+            (sent + 1..=attempt)
+                .map(|attempt| match attempt {
+                    1 => Step::Transmitted,
+                    attempt => Step::Retransmitted(attempt - 1),
+                })
+                .collect()
+        }
+        (State::Acknowledged, State::Acknowledged) => vec![],
+        (_, State::Acknowledged) => vec![Step::Acknowledged, Step::SeparateResponsePending],
+        _ => vec![],
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use futures::{channel::mpsc::unbounded, executor::block_on, StreamExt};
+    use pretty_assertions::assert_eq;
+    use rstest::rstest;
+
+    use crate::{
+        codec::{message::GetOptions, MessageId, Token},
+        protocol::{
+            event::Event,
+            get::Get,
+            message_id_store::MessageIdStore,
+            new_request::NewRequest,
+            processor::Processor,
+            reliability::Reliability,
+            status::{State, Status},
+            transmission_parameters::{ConfirmableParameters, InitialRetransmissionFactor},
+        },
+    };
+
+    use super::{notifications, Progress, Step};
+
+    #[rstest]
+    fn follows_processor() {
+        let mut processor = Processor::new(MessageIdStore::new(MessageId::from_value(0)));
+        let mut progress = Progress::new();
+        let token = Token::new().unwrap();
+
+        progress.accepted(token);
+        progress.update(&processor);
+        assert_eq!(Status::queued(), progress.status(&token));
+
+        processor
+            .tick(Event::TransactionRequested(
+                NewRequest::Get(Get {
+                    options: GetOptions::new(),
+                    reliability: Reliability::Confirmable(ConfirmableParameters::default(
+                        InitialRetransmissionFactor::new(0.5).unwrap(),
+                    )),
+                }),
+                token,
+            ))
+            .unwrap();
+        progress.update(&processor);
+        let status = progress.status(&token);
+        assert_eq!(State::Transmitted { attempt: 1 }, status.state);
+        assert!(status.next_retransmission.is_some());
+
+        progress.resolved(&token);
+        assert_eq!(Status::resolved(), progress.status(&token));
+    }
+
+    #[rstest]
+    #[case(State::Queued, State::Queued, vec![])]
+    #[case(State::Queued, State::Transmitted { attempt: 1 }, vec![Step::Transmitted])]
+    #[case(State::Transmitted { attempt: 1 }, State::Transmitted { attempt: 1 }, vec![])]
+    #[case(State::Transmitted { attempt: 1 }, State::Transmitted { attempt: 3 }, vec![Step::Retransmitted(1), Step::Retransmitted(2)])]
+    #[case(State::Queued, State::Transmitted { attempt: 2 }, vec![Step::Transmitted, Step::Retransmitted(1)])]
+    #[case(State::Transmitted { attempt: 2 }, State::Acknowledged, vec![Step::Acknowledged, Step::SeparateResponsePending])]
+    #[case(State::Acknowledged, State::Acknowledged, vec![])]
+    fn notifications_between_states(
+        #[case] from: State,
+        #[case] to: State,
+        #[case] expected: Vec<Step>,
+    ) {
+        assert_eq!(expected, notifications(from, to));
+    }
+
+    #[rstest]
+    fn subscriber_catches_up() {
+        let mut processor = Processor::new(MessageIdStore::new(MessageId::from_value(0)));
+        let mut progress = Progress::new();
+        let token = Token::new().unwrap();
+        progress.accepted(token);
+        processor
+            .tick(Event::TransactionRequested(
+                NewRequest::Get(Get {
+                    options: GetOptions::new(),
+                    reliability: Reliability::Confirmable(ConfirmableParameters::default(
+                        InitialRetransmissionFactor::new(0.5).unwrap(),
+                    )),
+                }),
+                token,
+            ))
+            .unwrap();
+        progress.update(&processor);
+
+        let (sender, mut receiver) = unbounded();
+        progress.subscribe(token, sender);
+        progress.resolved(&token);
+
+        assert_eq!(
+            vec![Step::Transmitted],
+            block_on(receiver.by_ref().collect::<Vec<_>>())
+        );
+    }
+}
@@ -0,0 +1,66 @@
+use std::{
+    io,
+    net::{SocketAddr, ToSocketAddrs},
+};
+
+use crate::codec::url::Endpoint;
+
+/// Turns the host of an endpoint into the socket addresses a client sends to.
+///
+/// Implement it for hosts the operating system can't resolve, like mDNS `.local` names on an
+/// embedded system, or to pin hosts to known addresses. Closures taking an endpoint implement it
+/// as well.
+pub trait Resolve: Send + Sync {
+    /// Addresses of `endpoint` in the order they are tried, the ones after the first are
+    /// candidates for failover.
+    fn resolve(&self, endpoint: &Endpoint) -> io::Result<Vec<SocketAddr>>;
+}
+
+impl<F> Resolve for F
+where
+    F: Fn(&Endpoint) -> io::Result<Vec<SocketAddr>> + Send + Sync,
+{
+    fn resolve(&self, endpoint: &Endpoint) -> io::Result<Vec<SocketAddr>> {
+        self(endpoint)
+    }
+}
+
+/// Resolves with the operating system, see [`ToSocketAddrs`]. Endpoints without a port get the
+/// default CoAP port.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct SystemResolver;
+
+impl Resolve for SystemResolver {
+    fn resolve(&self, endpoint: &Endpoint) -> io::Result<Vec<SocketAddr>> {
+        let port = endpoint.port.clone().unwrap_or_default().value();
+
+        Ok(format!("{}:{}", endpoint.host, port)
+            .to_socket_addrs()?
+            .collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::net::SocketAddr;
+
+    use pretty_assertions::assert_eq;
+    use rstest::rstest;
+
+    use crate::codec::url::Endpoint;
+
+    use super::{Resolve, SystemResolver};
+
+    #[rstest]
+    #[case("coap://127.0.0.1:5684", "127.0.0.1:5684")]
+    #[case("coap://127.0.0.1", "127.0.0.1:5683")]
+    #[case("coap://[::1]:5684", "[::1]:5684")]
+    fn system_resolver(#[case] endpoint: &str, #[case] expected: &str) {
+        let endpoint = Endpoint::from_str(endpoint).unwrap();
+
+        assert_eq!(
+            vec![expected.parse::<SocketAddr>().unwrap()],
+            SystemResolver.resolve(&endpoint).unwrap()
+        );
+    }
+}
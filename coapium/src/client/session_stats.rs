@@ -0,0 +1,259 @@
+use crate::{
+    codec::{Header, MessageType, ResponseCode},
+    protocol::{
+        effect::{Effect, Timeout},
+        event::Event,
+        processor::{self, Processor},
+        response,
+    },
+};
+
+/// Counters of what a client sent and received since it was created, for monitoring agents to
+/// scrape.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct SessionStats {
+    /// 4.xx responses.
+    pub client_errors: u64,
+    pub datagrams_received: u64,
+    pub datagrams_sent: u64,
+    /// Non-confirmable responses dropped as copies of one already received.
+    pub duplicate_non_responses: u64,
+    /// Received datagrams that couldn't be parsed and were dropped.
+    pub malformed_datagrams: u64,
+    pub resets_received: u64,
+    pub resets_sent: u64,
+    pub retransmissions: u64,
+    /// 5.xx responses.
+    pub server_errors: u64,
+    /// 2.xx responses.
+    pub successes: u64,
+    pub timeouts: u64,
+    /// Received datagrams larger than the receive buffer, dropped as they were cut short, see
+    /// [`SocketConfig::max_datagram_size`](super::socket_config::SocketConfig::max_datagram_size).
+    pub truncated_datagrams: u64,
+}
+
+impl SessionStats {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Ticks `processor` with `event` and counts the datagrams it was handed and the effects it
+    /// returned.
+    pub(crate) fn tick(&mut self, processor: &mut Processor, event: Event) -> processor::Result {
+        let retransmission = matches!(
+            event,
+            Event::TimeoutReached(Timeout::Retransmission(_) | Timeout::NonRetransmission(_))
+        );
+        if let Event::DataReceived(data)
+        | Event::DataReceivedFrom(_, data)
+        | Event::TruncatedDataReceived(data) = &event
+        {
+            self.datagrams_received += 1;
+            if is_reset(data) {
+                self.resets_received += 1;
+            }
+        }
+
+        let before = processor.stats();
+        let effects = processor.tick(event)?;
+        let after = processor.stats();
+        self.duplicate_non_responses +=
+            (after.duplicate_non_responses - before.duplicate_non_responses) as u64;
+        self.malformed_datagrams += (after.malformed_datagrams - before.malformed_datagrams) as u64;
+        self.truncated_datagrams += (after.truncated_datagrams - before.truncated_datagrams) as u64;
+
+        for effect in &effects {
+            match effect {
+                Effect::Transmit(data) => {
+                    self.datagrams_sent += 1;
+                    if retransmission {
+                        self.retransmissions += 1;
+                    }
+                    if is_reset(data) {
+                        self.resets_sent += 1;
+                    }
+                }
+                Effect::TransactionResolved(_, Ok(response)) => match response.response_code {
+                    ResponseCode::Success(_) => self.successes += 1,
+                    ResponseCode::ClientError(_) => self.client_errors += 1,
+                    ResponseCode::ServerError(_) => self.server_errors += 1,
+                },
+                Effect::TransactionResolved(
+                    _,
+                    Err(response::Error::Timeout | response::Error::AcknowledgementTimeout),
+                ) => self.timeouts += 1,
+                _ => {}
+            }
+        }
+
+        Ok(effects)
+    }
+}
+
+fn is_reset(data: &[u8]) -> bool {
+    Header::parse(data).is_ok_and(|(_, header)| header.message_type() == MessageType::Reset)
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+    use rstest::rstest;
+
+    use crate::{
+        codec::{
+            code::response_code::{ClientError, Success},
+            message::{self, GetOptions},
+            MessageId, Options, Payload, Piggyback, Reset, Response, ResponseCode, Token,
+        },
+        protocol::{
+            effect::Effects,
+            event::Event,
+            get::Get,
+            message_id_store::MessageIdStore,
+            new_request::NewRequest,
+            processor::Processor,
+            reliability::Reliability,
+            timeout::RetransmissionTimeout,
+            transmission_parameters::{ConfirmableParameters, InitialRetransmissionFactor},
+        },
+    };
+
+    use super::SessionStats;
+
+    fn parameters() -> ConfirmableParameters {
+        ConfirmableParameters::default(InitialRetransmissionFactor::new(0.5).unwrap())
+    }
+
+    fn request(token: Token) -> Event {
+        Event::TransactionRequested(
+            NewRequest::Get(Get {
+                options: GetOptions::new(),
+                reliability: Reliability::Confirmable(parameters()),
+            }),
+            token,
+        )
+    }
+
+    fn piggyback(token: Token, response_code: ResponseCode) -> Event {
+        Event::DataReceived(
+            Piggyback::new(
+                token,
+                response_code,
+                MessageId::from_value(0),
+                Options::new(),
+                Payload::empty(),
+            )
+            .encode(),
+        )
+    }
+
+    fn tick(events: Vec<Event>) -> SessionStats {
+        let mut processor = Processor::new(MessageIdStore::new(MessageId::from_value(0)));
+        let mut stats = SessionStats::new();
+        for event in events {
+            stats.tick(&mut processor, event).unwrap();
+        }
+        stats
+    }
+
+    fn non_response(token: Token) -> Event {
+        Event::DataReceived(
+            Response::new(
+                message::Reliability::NonConfirmable,
+                token,
+                ResponseCode::Success(Success::Content),
+                MessageId::from_value(7),
+                Options::new(),
+                Payload::empty(),
+            )
+            .encode(),
+        )
+    }
+
+    #[rstest]
+    fn duplicate_non_responses_are_counted() {
+        let stats = tick(vec![
+            request(Token::from_value(vec![1]).unwrap()),
+            non_response(Token::from_value(vec![1]).unwrap()),
+            non_response(Token::from_value(vec![1]).unwrap()),
+        ]);
+
+        assert_eq!(
+            SessionStats {
+                datagrams_received: 2,
+                datagrams_sent: 1,
+                duplicate_non_responses: 1,
+                successes: 1,
+                ..Default::default()
+            },
+            stats
+        );
+    }
+
+    #[rstest]
+    fn malformed_datagrams_are_counted_and_dropped() {
+        let mut processor = Processor::new(MessageIdStore::new(MessageId::from_value(0)));
+        let mut stats = SessionStats::new();
+
+        let effects = stats
+            .tick(&mut processor, Event::DataReceived(vec![0xFF]))
+            .map(Effects::into_vec);
+
+        assert_eq!(Ok(vec![]), effects);
+        assert_eq!(
+            SessionStats {
+                datagrams_received: 1,
+                malformed_datagrams: 1,
+                ..Default::default()
+            },
+            stats
+        );
+    }
+
+    #[rstest]
+    fn truncated_datagrams_are_counted_and_dropped() {
+        let mut processor = Processor::new(MessageIdStore::new(MessageId::from_value(0)));
+        let mut stats = SessionStats::new();
+
+        let effects = stats
+            .tick(&mut processor, Event::TruncatedDataReceived(vec![0x50; 5]))
+            .map(Effects::into_vec);
+
+        assert_eq!(Ok(vec![]), effects);
+        assert_eq!(
+            SessionStats {
+                datagrams_received: 1,
+                truncated_datagrams: 1,
+                ..Default::default()
+            },
+            stats
+        );
+    }
+
+    #[rstest]
+    #[case(
+        vec![request(Token::from_value(vec![1]).unwrap()), piggyback(Token::from_value(vec![1]).unwrap(), ResponseCode::Success(Success::Content))],
+        SessionStats { datagrams_received: 1, datagrams_sent: 1, successes: 1, ..Default::default() }
+    )]
+    #[case(
+        vec![request(Token::from_value(vec![1]).unwrap()), piggyback(Token::from_value(vec![1]).unwrap(), ResponseCode::ClientError(ClientError::NotFound))],
+        SessionStats { datagrams_received: 1, datagrams_sent: 1, client_errors: 1, ..Default::default() }
+    )]
+    #[case(
+        vec![request(Token::from_value(vec![1]).unwrap()), RetransmissionTimeout::new(MessageId::from_value(0), &parameters()).into()],
+        SessionStats { datagrams_sent: 2, retransmissions: 1, ..Default::default() }
+    )]
+    #[case(
+        vec![request(Token::from_value(vec![1]).unwrap()), Event::DataReceived(Reset::from_message_id(MessageId::from_value(0)).encode())],
+        SessionStats { datagrams_received: 1, datagrams_sent: 1, resets_received: 1, ..Default::default() }
+    )]
+    // A ping from the peer is answered with a reset
+    #[case(
+        vec![Event::DataReceived(vec![0x40, 0x00, 0x00, 0x01])],
+        SessionStats { datagrams_received: 1, datagrams_sent: 1, resets_sent: 1, ..Default::default() }
+    )]
+    fn counts(#[case] events: Vec<Event>, #[case] expected: SessionStats) {
+        assert_eq!(expected, tick(events));
+    }
+}
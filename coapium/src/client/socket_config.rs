@@ -0,0 +1,301 @@
+use std::{
+    io,
+    net::{Ipv4Addr, SocketAddr, UdpSocket},
+};
+
+#[cfg(not(target_arch = "wasm32"))]
+use socket2::{Domain, Protocol, Socket, Type};
+
+use crate::{
+    codec::TokenLength,
+    protocol::{
+        processor::{EntityTooLargePolicy, ReservedPolicy},
+        transaction::{MAX_DATAGRAM_SIZE, PATH_MTU},
+    },
+};
+
+pub const MAX_DSCP: u8 = 0b0011_1111;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Error {
+    Bind(io::ErrorKind),
+    Dscp(u8),
+    MaxDatagramSize(usize),
+    Option(io::ErrorKind),
+}
+
+/// Configuration of the UDP socket a client binds.
+///
+/// Anything not set is left at the operating system default, the socket is bound to an ephemeral
+/// port on all IPv4 interfaces unless a local address is set.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct SocketConfig {
+    any_source: bool,
+    dscp: Option<u8>,
+    entity_too_large_policy: EntityTooLargePolicy,
+    hop_limit: Option<u32>,
+    local_address: Option<SocketAddr>,
+    max_datagram_size: Option<usize>,
+    only_v6: Option<bool>,
+    receive_buffer_size: Option<usize>,
+    reserved_policy: ReservedPolicy,
+    token_length: Option<TokenLength>,
+}
+
+impl SocketConfig {
+    pub fn new() -> Self {
+        Self {
+            ..Default::default()
+        }
+    }
+
+    /// Takes responses from any address instead of only the one requests are sent to, which
+    /// keeps separate responses coming when a NAT in front of the server rebinds it to another
+    /// port, e.g. on cellular links. Off by default.
+    ///
+    /// Responses are then matched by token and acknowledgements by message ID alone, so anyone
+    /// who can guess them can answer in place of the server. Only the synchronous client supports
+    /// it, the socket isn't connected and unreachable peers aren't reported. The asynchronous
+    /// client fails with [`AnySourceUnsupported`](super::Error::AnySourceUnsupported).
+    pub fn any_source(mut self, any_source: bool) -> Self {
+        self.any_source = any_source;
+        self
+    }
+
+    pub(crate) fn accepts_any_source(&self) -> bool {
+        self.any_source
+    }
+
+    /// Differentiated services code point, written to the TOS field for IPv4 and to the traffic
+    /// class for IPv6.
+    pub fn dscp(mut self, dscp: u8) -> Self {
+        self.dscp = Some(dscp);
+        self
+    }
+
+    /// What the client does when a POST or PUT is answered with 4.13 (Request Entity Too Large),
+    /// see [`EntityTooLargePolicy`].
+    pub fn entity_too_large_policy(
+        mut self,
+        entity_too_large_policy: EntityTooLargePolicy,
+    ) -> Self {
+        self.entity_too_large_policy = entity_too_large_policy;
+        self
+    }
+
+    pub(crate) fn entity_too_large(&self) -> EntityTooLargePolicy {
+        self.entity_too_large_policy
+    }
+
+    /// TTL for IPv4 and unicast hop limit for IPv6.
+    pub fn hop_limit(mut self, hop_limit: u32) -> Self {
+        self.hop_limit = Some(hop_limit);
+        self
+    }
+
+    pub fn local_address(mut self, local_address: SocketAddr) -> Self {
+        self.local_address = Some(local_address);
+        self
+    }
+
+    /// Largest datagram received in full, [`PATH_MTU`] by default and at most
+    /// [`MAX_DATAGRAM_SIZE`]. Larger datagrams are cut off by the socket and dropped, they are
+    /// counted as [`SessionStats::truncated_datagrams`](super::session_stats::SessionStats).
+    ///
+    /// Servers that don't negotiate block-wise transfers may send responses above the path MTU.
+    pub fn max_datagram_size(mut self, max_datagram_size: usize) -> Self {
+        self.max_datagram_size = Some(max_datagram_size);
+        self
+    }
+
+    /// Only has an effect when the local address is an IPv6 address.
+    pub fn only_v6(mut self, only_v6: bool) -> Self {
+        self.only_v6 = Some(only_v6);
+        self
+    }
+
+    pub fn receive_buffer_size(mut self, receive_buffer_size: usize) -> Self {
+        self.receive_buffer_size = Some(receive_buffer_size);
+        self
+    }
+
+    /// What the client does with received messages that have a reserved code, see
+    /// [`Processor::with_reserved_policy`](crate::protocol::processor::Processor::with_reserved_policy).
+    /// With [`ReservedPolicy::Deliver`] they are handed out by the `unknown_messages` of the
+    /// client.
+    pub fn reserved_policy(mut self, reserved_policy: ReservedPolicy) -> Self {
+        self.reserved_policy = reserved_policy;
+        self
+    }
+
+    pub(crate) fn reserved(&self) -> ReservedPolicy {
+        self.reserved_policy
+    }
+
+    pub(crate) fn receive_size(&self) -> usize {
+        self.max_datagram_size.unwrap_or(PATH_MTU)
+    }
+
+    /// Length of the tokens the client generates for requests, [`TokenLength::DEFAULT`] by
+    /// default. Shorter tokens save bytes on every message but leave fewer to tell the requests in
+    /// flight apart, a token still in use is never handed out again.
+    pub fn token_length(mut self, token_length: TokenLength) -> Self {
+        self.token_length = Some(token_length);
+        self
+    }
+
+    pub(crate) fn tokens(&self) -> TokenLength {
+        self.token_length
+            .unwrap_or(TokenLength::decode(TokenLength::DEFAULT))
+    }
+
+    /// Creates a non-blocking socket with the configuration applied and binds it.
+    #[cfg(not(target_arch = "wasm32"))]
+    pub(crate) fn bind(&self) -> Result<UdpSocket, Error> {
+        if let Some(dscp) = self.dscp.filter(|dscp| *dscp > MAX_DSCP) {
+            return Err(Error::Dscp(dscp));
+        }
+        if let Some(size) = self
+            .max_datagram_size
+            .filter(|size| *size == 0 || *size > MAX_DATAGRAM_SIZE)
+        {
+            return Err(Error::MaxDatagramSize(size));
+        }
+
+        let local_address = self
+            .local_address
+            .unwrap_or_else(|| SocketAddr::from((Ipv4Addr::UNSPECIFIED, 0)));
+
+        let socket = Socket::new(
+            Domain::for_address(local_address),
+            Type::DGRAM,
+            Some(Protocol::UDP),
+        )
+        .map_err(|e| Error::Bind(e.kind()))?;
+
+        self.apply(&socket, &local_address)
+            .map_err(|e| Error::Option(e.kind()))?;
+
+        socket
+            .bind(&local_address.into())
+            .map_err(|e| Error::Bind(e.kind()))?;
+
+        Ok(socket.into())
+    }
+
+    /// There are no UDP sockets on wasm, the client talks CoAP over WebSocket there instead.
+    #[cfg(target_arch = "wasm32")]
+    pub(crate) fn bind(&self) -> Result<UdpSocket, Error> {
+        Err(Error::Bind(io::ErrorKind::Unsupported))
+    }
+
+    #[cfg(not(target_arch = "wasm32"))]
+    fn apply(&self, socket: &Socket, local_address: &SocketAddr) -> io::Result<()> {
+        socket.set_nonblocking(true)?;
+
+        if let Some(receive_buffer_size) = self.receive_buffer_size {
+            socket.set_recv_buffer_size(receive_buffer_size)?;
+        }
+
+        match local_address {
+            SocketAddr::V4(_) => {
+                if let Some(hop_limit) = self.hop_limit {
+                    socket.set_ttl(hop_limit)?;
+                }
+                if let Some(dscp) = self.dscp {
+                    socket.set_tos(traffic_class(dscp))?;
+                }
+            }
+            SocketAddr::V6(_) => {
+                if let Some(only_v6) = self.only_v6 {
+                    socket.set_only_v6(only_v6)?;
+                }
+                if let Some(hop_limit) = self.hop_limit {
+                    socket.set_unicast_hops_v6(hop_limit)?;
+                }
+                if let Some(dscp) = self.dscp {
+                    socket.set_tclass_v6(traffic_class(dscp))?;
+                }
+            }
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+fn traffic_class(dscp: u8) -> u32 {
+    (dscp as u32) << 2
+}
+
+impl Error {
+    /// Closest I/O error kind, for callers that report every failure as one.
+    pub fn kind(&self) -> io::ErrorKind {
+        match self {
+            Error::Bind(kind) | Error::Option(kind) => *kind,
+            Error::Dscp(_) | Error::MaxDatagramSize(_) => io::ErrorKind::InvalidInput,
+        }
+    }
+}
+
+impl std::fmt::Display for Error {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Error::Bind(kind) => write!(f, "failed to bind socket: {kind}"),
+            Error::Dscp(dscp) => write!(f, "DSCP {dscp} must be at most {MAX_DSCP}"),
+            Error::MaxDatagramSize(size) => write!(
+                f,
+                "max datagram size {size} must be between 1 and {MAX_DATAGRAM_SIZE}"
+            ),
+            Error::Option(kind) => write!(f, "failed to set socket option: {kind}"),
+        }
+    }
+}
+
+impl std::error::Error for Error {}
+
+#[cfg(test)]
+mod tests {
+    use std::net::{Ipv4Addr, SocketAddr};
+
+    use pretty_assertions::assert_eq;
+    use rstest::rstest;
+
+    use super::{Error, SocketConfig};
+
+    #[rstest]
+    fn bind_applies_options() {
+        let socket = SocketConfig::new()
+            .local_address(SocketAddr::from((Ipv4Addr::LOCALHOST, 0)))
+            .hop_limit(7)
+            .bind()
+            .unwrap();
+
+        assert_eq!(7, socket.ttl().unwrap());
+        assert_eq!(Ipv4Addr::LOCALHOST, socket.local_addr().unwrap().ip());
+    }
+
+    #[rstest]
+    fn bind_rejects_invalid_dscp() {
+        assert_eq!(
+            Some(Error::Dscp(64)),
+            SocketConfig::new().dscp(64).bind().err()
+        );
+    }
+
+    #[rstest]
+    #[case(0)]
+    #[case(65_528)]
+    fn bind_rejects_invalid_max_datagram_size(#[case] size: usize) {
+        assert_eq!(
+            Some(Error::MaxDatagramSize(size)),
+            SocketConfig::new().max_datagram_size(size).bind().err()
+        );
+    }
+
+    #[rstest]
+    fn any_source_is_opt_in() {
+        assert!(!SocketConfig::new().accepts_any_source());
+        assert!(SocketConfig::new().any_source(true).accepts_any_source());
+    }
+}
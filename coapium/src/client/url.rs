@@ -1,4 +1,5 @@
 use crate::codec::{
+    message::{DeleteOptions, GetOptions, PostOptions, PutOptions},
     option::{uri_host, uri_path, uri_port, UriHost, UriPath, UriPort, UriQuery},
     url::{Endpoint, Scheme},
 };
@@ -19,6 +20,37 @@ pub struct Url {
     pub port: Option<UriPort>,
     pub path: UriPath,
     pub query: UriQuery,
+    /// Whether requests built from the url carry Uri-Host, set to `false` for servers that
+    /// reject it.
+    pub send_uri_host: bool,
+    /// Whether requests built from the url carry Uri-Port, set to `false` for servers that
+    /// reject it.
+    pub send_uri_port: bool,
+}
+
+impl Url {
+    /// Uri-Host of a request to the url, see
+    /// [RFC 7252 §6.4](https://datatracker.ietf.org/doc/html/rfc7252#section-6.4). Left out when
+    /// the host is an IP literal, which the destination address already carries.
+    pub fn uri_host(&self) -> Option<UriHost> {
+        if !self.send_uri_host || self.host.ip_addr().is_some() {
+            return None;
+        }
+
+        Some(self.host.clone())
+    }
+
+    /// Uri-Port of a request to the url, left out when it's the default port of the scheme.
+    pub fn uri_port(&self) -> Option<UriPort> {
+        let default_port = match self.scheme {
+            Scheme::Coap => 5683,
+            Scheme::Coaps => 5684,
+        };
+
+        self.port
+            .clone()
+            .filter(|port| self.send_uri_port && port.value() != default_port)
+    }
 }
 
 impl From<Url> for Endpoint {
@@ -31,6 +63,66 @@ impl From<Url> for Endpoint {
     }
 }
 
+impl From<&Url> for DeleteOptions {
+    fn from(url: &Url) -> Self {
+        let mut options = DeleteOptions::new();
+        if let Some(host) = url.uri_host() {
+            options.set_uri_host(host);
+        }
+        if let Some(port) = url.uri_port() {
+            options.set_uri_port(port);
+        }
+        options.set_uri_path(url.path.clone());
+        options.set_uri_query(url.query.clone());
+        options
+    }
+}
+
+impl From<&Url> for GetOptions {
+    fn from(url: &Url) -> Self {
+        let mut options = GetOptions::new();
+        if let Some(host) = url.uri_host() {
+            options.set_uri_host(host);
+        }
+        if let Some(port) = url.uri_port() {
+            options.set_uri_port(port);
+        }
+        options.set_uri_path(url.path.clone());
+        options.set_uri_query(url.query.clone());
+        options
+    }
+}
+
+impl From<&Url> for PostOptions {
+    fn from(url: &Url) -> Self {
+        let mut options = PostOptions::new();
+        if let Some(host) = url.uri_host() {
+            options.set_uri_host(host);
+        }
+        if let Some(port) = url.uri_port() {
+            options.set_uri_port(port);
+        }
+        options.set_uri_path(url.path.clone());
+        options.set_uri_query(url.query.clone());
+        options
+    }
+}
+
+impl From<&Url> for PutOptions {
+    fn from(url: &Url) -> Self {
+        let mut options = PutOptions::new();
+        if let Some(host) = url.uri_host() {
+            options.set_uri_host(host);
+        }
+        if let Some(port) = url.uri_port() {
+            options.set_uri_port(port);
+        }
+        options.set_uri_path(url.path.clone());
+        options.set_uri_query(url.query.clone());
+        options
+    }
+}
+
 impl From<uri_host::ValueError> for Error {
     fn from(value: uri_host::ValueError) -> Self {
         Self::Host(value)
@@ -84,8 +176,64 @@ impl TryFrom<url::Url> for Url {
                 .map_err(|_| Error::Scheme(value.scheme().to_owned()))?,
             host: value.host_str().unwrap_or("").try_into()?, // TODO: This does not handle already url encoded hosts
             port: value.port().map(|p| p.into()),
-            path: value.path().try_into()?, // TODO: This does not handle already url encoded paths
+            path: UriPath::from_url_path(value.path())?,
             query,
+            send_uri_host: true,
+            send_uri_port: true,
         })
     }
 }
+
+impl std::fmt::Display for Error {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Error::Scheme(scheme) => write!(f, "unsupported scheme `{scheme}`"),
+            Error::Path(error) => write!(f, "invalid path: {error}"),
+            Error::Host(error) => write!(f, "invalid host: {error}"),
+            Error::Port(error) => write!(f, "invalid port: {error}"),
+            Error::Other(message) => write!(f, "invalid url: {message}"),
+        }
+    }
+}
+
+impl std::error::Error for Error {}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+    use rstest::rstest;
+
+    use super::Url;
+
+    fn url(url: &str) -> Url {
+        url::Url::parse(url).unwrap().try_into().unwrap()
+    }
+
+    #[rstest]
+    #[case("coap://example.com/a",        Some("example.com".to_owned()))]
+    #[case("coap://127.0.0.1/a", None)]
+    #[case("coap://[::1]/a", None)]
+    fn uri_host(#[case] value: &str, #[case] expected: Option<String>) {
+        assert_eq!(expected, url(value).uri_host().map(|host| host.to_string()));
+    }
+
+    #[rstest]
+    #[case("coap://example.com/a", None)]
+    #[case("coap://example.com:5683/a", None)]
+    #[case("coap://example.com:5684/a", Some(5684))]
+    #[case("coaps://example.com:5684/a", None)]
+    #[case("coaps://example.com:5683/a", Some(5683))]
+    fn uri_port(#[case] value: &str, #[case] expected: Option<u16>) {
+        assert_eq!(expected, url(value).uri_port().map(|port| port.value()));
+    }
+
+    #[rstest]
+    fn suppressed() {
+        let mut url = url("coap://example.com:8000/a");
+        url.send_uri_host = false;
+        url.send_uri_port = false;
+
+        assert_eq!(None, url.uri_host());
+        assert_eq!(None, url.uri_port());
+    }
+}
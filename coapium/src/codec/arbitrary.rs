@@ -0,0 +1,199 @@
+//! [`quickcheck::Arbitrary`] implementations of messages and their parts, for property tests and
+//! fuzzing of downstream stacks with realistic CoAP messages.
+//!
+//! Generated messages are well-formed, they decode to what they were encoded from. Options are
+//! drawn from a handful of common ones and are accepted by every request method.
+
+use quickcheck::{Arbitrary, Gen};
+
+use super::{
+    message::{
+        Delete, DeleteOptions, Get, GetOptions, Ping, Post, PostOptions, Put, PutOptions,
+        Reliability, Request,
+    },
+    option::{ETag, Observe, UriQuery},
+    Acknowledgement, Code, MediaType, Message, MessageId, Options, Payload, Piggyback, Reset,
+    Response, ResponseCode, Token,
+};
+
+const MEDIA_TYPES: [MediaType; 4] = [
+    MediaType::TextPlain,
+    MediaType::ApplicationLinkFormat,
+    MediaType::ApplicationJson,
+    MediaType::ApplicationCbor,
+];
+
+impl Arbitrary for Code {
+    fn arbitrary(g: &mut Gen) -> Self {
+        Code::decode(u8::arbitrary(g))
+    }
+}
+
+impl Arbitrary for Message {
+    fn arbitrary(g: &mut Gen) -> Self {
+        let message_id = MessageId::from_value(u16::arbitrary(g));
+        let reliability = *g
+            .choose(&[Reliability::Confirmable, Reliability::NonConfirmable])
+            .unwrap();
+        let token = Token::arbitrary(g);
+        let options = Options::arbitrary(g);
+
+        match u8::arbitrary(g) % 9 {
+            0 => Message::Acknowledgement(Acknowledgement::new(message_id)),
+            1 => Message::Reset(Reset::from_message_id(message_id)),
+            2 => Message::Ping(Ping::from_message_id(message_id)),
+            3 => Message::Request(Request::Get(Get::new(
+                message_id,
+                reliability,
+                token,
+                GetOptions::from_options(options).expect("options are valid for GET"),
+            ))),
+            4 => Message::Request(Request::Post(Post::new(
+                message_id,
+                reliability,
+                token,
+                PostOptions::from_options(options).expect("options are valid for POST"),
+                payload(g),
+            ))),
+            5 => Message::Request(Request::Put(Put::new(
+                message_id,
+                reliability,
+                token,
+                PutOptions::from_options(options).expect("options are valid for PUT"),
+                payload(g),
+            ))),
+            6 => Message::Request(Request::Delete(Delete::new(
+                message_id,
+                reliability,
+                token,
+                DeleteOptions::from_options(options).expect("options are valid for DELETE"),
+            ))),
+            7 => Message::Piggyback(Piggyback::new(
+                token,
+                response_code(g),
+                message_id,
+                options,
+                payload(g),
+            )),
+            _ => Message::Response(Response::new(
+                reliability,
+                token,
+                response_code(g),
+                message_id,
+                options,
+                payload(g),
+            )),
+        }
+    }
+}
+
+impl Arbitrary for Options {
+    /// Options are set in the order of their numbers, the order they are decoded in.
+    fn arbitrary(g: &mut Gen) -> Self {
+        let mut options = Options::new();
+
+        if bool::arbitrary(g) {
+            let etag = ETag::from_values(vec![bytes(g, 1, 8)]).expect("ETag is 1 to 8 bytes");
+            options.set_etag(etag);
+        }
+        if bool::arbitrary(g) {
+            options.set_observe(Observe::from_value(u32::arbitrary(g) & 0x00ff_ffff));
+        }
+        if bool::arbitrary(g) {
+            let segments = (0..1 + usize::arbitrary(g) % 3)
+                .map(|_| text(g))
+                .collect::<Vec<_>>()
+                .join("/");
+            options.set_uri_path(
+                segments
+                    .as_str()
+                    .try_into()
+                    .expect("segments are alphanumeric"),
+            );
+        }
+        if bool::arbitrary(g) {
+            let media_type = g.choose(&MEDIA_TYPES).unwrap().clone();
+            options.set_content_format(media_type.into());
+        }
+        if bool::arbitrary(g) {
+            options.set_max_age(u32::arbitrary(g).into());
+        }
+        if bool::arbitrary(g) {
+            let mut query = UriQuery::new();
+            for _ in 0..1 + usize::arbitrary(g) % 3 {
+                query
+                    .add_key_value(&text(g), &text(g))
+                    .expect("key and value are alphanumeric");
+            }
+            options.set_uri_query(query);
+        }
+
+        options
+    }
+}
+
+impl Arbitrary for Token {
+    fn arbitrary(g: &mut Gen) -> Self {
+        Token::from_value(bytes(g, 0, 8)).expect("token is at most 8 bytes")
+    }
+}
+
+fn bytes(g: &mut Gen, min: usize, max: usize) -> Vec<u8> {
+    let length = min + usize::arbitrary(g) % (max - min + 1);
+    (0..length).map(|_| u8::arbitrary(g)).collect()
+}
+
+fn payload(g: &mut Gen) -> Payload {
+    Payload::from_value(bytes(g, 0, 32))
+}
+
+/// Response code of any detail, assigned or not.
+fn response_code(g: &mut Gen) -> ResponseCode {
+    let detail = u8::arbitrary(g) & 0b0001_1111;
+    let class = *g.choose(&[2, 4, 5]).unwrap();
+
+    match Code::decode(class << 5 | detail) {
+        Code::Response(response_code) => response_code,
+        code => unreachable!("{code} is a response code"),
+    }
+}
+
+fn text(g: &mut Gen) -> String {
+    const CHARACTERS: &[u8] = b"abcdefghijklmnopqrstuvwxyz0123456789";
+
+    (0..1 + usize::arbitrary(g) % 8)
+        .map(|_| *g.choose(CHARACTERS).unwrap() as char)
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+    use quickcheck_macros::quickcheck;
+
+    use crate::codec::{Code, Message, Options, Token};
+
+    #[quickcheck]
+    fn message_encode_decode(message: Message) {
+        assert_eq!(Ok(message.clone()), Message::decode(&message.encode()));
+    }
+
+    #[quickcheck]
+    fn options_encode_parse(options: Options) {
+        let encoded = options.clone().encode();
+
+        assert_eq!(Ok((&[][..], options)), Options::parse(&encoded));
+    }
+
+    #[quickcheck]
+    fn token_encode_decode(token: Token) {
+        let (_, encoded) = token.clone().encode();
+
+        assert_eq!(Ok(token), Token::decode(encoded));
+    }
+
+    #[quickcheck]
+    fn code_decode_encode(code: Code) {
+        assert_eq!(code, Code::decode(code.encode()));
+    }
+}
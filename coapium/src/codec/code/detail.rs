@@ -70,6 +70,18 @@ impl Detail {
     }
 }
 
+impl std::fmt::Display for Error {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Error::OutOfRange(value) => {
+                write!(f, "code detail {value} is out of range, max is {MAX}")
+            }
+        }
+    }
+}
+
+impl std::error::Error for Error {}
+
 #[cfg(test)]
 mod tests {
     use pretty_assertions::assert_eq;
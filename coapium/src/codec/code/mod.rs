@@ -79,6 +79,18 @@ impl Code {
     }
 }
 
+impl std::fmt::Display for Code {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let byte = self.encode();
+        write!(
+            f,
+            "{}.{:02}",
+            Class::decode(byte).value(),
+            Detail::decode(byte).value()
+        )
+    }
+}
+
 #[cfg(test)]
 mod tests {
 
@@ -148,4 +160,21 @@ mod tests {
     fn is_reserved(#[case] code: Code, #[case] expected: bool) {
         assert_eq!(expected, code.is_reserved())
     }
+
+    #[rstest]
+    #[case(Code::Empty, "0.00")]
+    #[case(Code::Request(MethodCode::Get), "0.01")]
+    #[case(Code::Response(ResponseCode::Success(Success::Content)), "2.05")]
+    #[case(
+        Code::Response(ResponseCode::ClientError(ClientError::NotFound)),
+        "4.04"
+    )]
+    #[case(
+        Code::Response(ResponseCode::ServerError(ServerError::ProxyingNotSupported)),
+        "5.05"
+    )]
+    #[case(Code::Reserved(ReservedCode::new(Class::Reserved { value: 7 }, Detail::from_value_or_panic(31))), "7.31")]
+    fn display(#[case] code: Code, #[case] expected: &str) {
+        assert_eq!(expected, code.to_string())
+    }
 }
@@ -1,10 +1,11 @@
-use super::{Class, Code, Detail};
+use super::{Class, Code, Detail, MethodCode};
 
 const CREATED: Detail = Detail::from_value_or_panic(1);
 const DELETED: Detail = Detail::from_value_or_panic(2);
 const VALID: Detail = Detail::from_value_or_panic(3);
 const CHANGED: Detail = Detail::from_value_or_panic(4);
 const CONTENT: Detail = Detail::from_value_or_panic(5);
+const CONTINUE: Detail = Detail::from_value_or_panic(31);
 
 const BAD_REQUEST: Detail = Detail::from_value_or_panic(0);
 const UNAUTHORIZED: Detail = Detail::from_value_or_panic(1);
@@ -43,6 +44,9 @@ pub enum Success {
     Valid,
     Changed,
     Content,
+    /// 2.31 (Continue) from RFC 7959, a block of a request was received and the next one can
+    /// be sent.
+    Continue,
     Unassigned(Unassigned),
 }
 
@@ -73,6 +77,39 @@ pub enum ServerError {
 }
 
 impl ResponseCode {
+    pub const CREATED: Self = ResponseCode::Success(Success::Created);
+    pub const DELETED: Self = ResponseCode::Success(Success::Deleted);
+    pub const VALID: Self = ResponseCode::Success(Success::Valid);
+    pub const CHANGED: Self = ResponseCode::Success(Success::Changed);
+    pub const CONTENT: Self = ResponseCode::Success(Success::Content);
+    pub const CONTINUE: Self = ResponseCode::Success(Success::Continue);
+    pub const BAD_REQUEST: Self = ResponseCode::ClientError(ClientError::BadRequest);
+    pub const UNAUTHORIZED: Self = ResponseCode::ClientError(ClientError::Unauthorized);
+    pub const BAD_OPTION: Self = ResponseCode::ClientError(ClientError::BadOption);
+    pub const FORBIDDEN: Self = ResponseCode::ClientError(ClientError::Forbidden);
+    pub const NOT_FOUND: Self = ResponseCode::ClientError(ClientError::NotFound);
+    pub const METHOD_NOT_ALLOWED: Self = ResponseCode::ClientError(ClientError::MethodNotAllowed);
+    pub const NOT_ACCEPTABLE: Self = ResponseCode::ClientError(ClientError::NotAcceptable);
+    pub const PRECONDITION_FAILED: Self =
+        ResponseCode::ClientError(ClientError::PreconditionFailed);
+    pub const REQUEST_ENTITY_TOO_LARGE: Self =
+        ResponseCode::ClientError(ClientError::RequestEntityTooLarge);
+    pub const UNSUPPORTED_CONTENT_FORMAT: Self =
+        ResponseCode::ClientError(ClientError::UnsupportedContentFormat);
+    pub const INTERNAL_SERVER_ERROR: Self =
+        ResponseCode::ServerError(ServerError::InternalServerError);
+    pub const NOT_IMPLEMENTED: Self = ResponseCode::ServerError(ServerError::NotImplemented);
+    pub const BAD_GATEWAY: Self = ResponseCode::ServerError(ServerError::BadGateway);
+    pub const SERVICE_UNAVAILABLE: Self =
+        ResponseCode::ServerError(ServerError::ServiceUnavailable);
+    pub const GATEWAY_TIMEOUT: Self = ResponseCode::ServerError(ServerError::GatewayTimeout);
+    pub const PROXYING_NOT_SUPPORTED: Self =
+        ResponseCode::ServerError(ServerError::ProxyingNotSupported);
+
+    pub const fn class(&self) -> Class {
+        self.encode().0
+    }
+
     pub const fn encode(self) -> (Class, Detail) {
         match self {
             ResponseCode::Success(success) => (Class::Success, success.encode()),
@@ -93,12 +130,26 @@ impl ResponseCode {
         ResponseCode::ServerError(ServerError::decode(detail))
     }
 
+    pub const fn is_client_error(&self) -> bool {
+        matches!(self, ResponseCode::ClientError(_))
+    }
+
+    pub const fn is_server_error(&self) -> bool {
+        matches!(self, ResponseCode::ServerError(_))
+    }
+
     pub const fn is_success(&self) -> bool {
         match self {
             ResponseCode::Success(_) => true,
             _ => false,
         }
     }
+
+    /// Class and detail as numbers, e.g. `(4, 4)` for 4.04 (Not Found).
+    pub const fn numeric(&self) -> (u8, u8) {
+        let (class, detail) = self.encode();
+        (class.value(), detail.value())
+    }
 }
 
 impl Success {
@@ -109,6 +160,7 @@ impl Success {
             VALID => Success::Valid,
             CHANGED => Success::Changed,
             CONTENT => Success::Content,
+            CONTINUE => Success::Continue,
             detail => Success::Unassigned(Unassigned { value: detail }),
         }
     }
@@ -120,9 +172,23 @@ impl Success {
             Success::Valid => VALID,
             Success::Changed => CHANGED,
             Success::Content => CONTENT,
+            Success::Continue => CONTINUE,
             Success::Unassigned(Unassigned { value }) => value,
         }
     }
+
+    /// Whether the code is defined as a response to `method` by RFC 7252 §5.9, e.g. 2.01 (Created)
+    /// only to POST and PUT. Unassigned codes and methods are taken to be allowed.
+    pub const fn is_allowed_for(&self, method: MethodCode) -> bool {
+        match (self, method) {
+            (Success::Unassigned(_), _) | (_, MethodCode::Unassigned(_)) => true,
+            (Success::Created | Success::Changed | Success::Continue, method) => {
+                matches!(method, MethodCode::Post | MethodCode::Put)
+            }
+            (Success::Deleted, method) => matches!(method, MethodCode::Delete | MethodCode::Post),
+            (Success::Valid | Success::Content, method) => matches!(method, MethodCode::Get),
+        }
+    }
 }
 
 impl ClientError {
@@ -133,6 +199,9 @@ impl ClientError {
             BAD_OPTION => ClientError::BadOption,
             FORBIDDEN => ClientError::Forbidden,
             NOT_FOUND => ClientError::NotFound,
+            METHOD_NOT_ALLOWED => ClientError::MethodNotAllowed,
+            NOT_ACCEPTABLE => ClientError::NotAcceptable,
+            PRECONDITION_FAILED => ClientError::PreconditionFailed,
             REQUEST_ENTITY_TOO_LARGE => ClientError::RequestEntityTooLarge,
             UNSUPPORTED_CONTENT_FORMAT => ClientError::UnsupportedContentFormat,
             detail => ClientError::Unassigned(Unassigned { value: detail }),
@@ -194,10 +263,18 @@ mod tests {
     use rstest::rstest;
 
     use super::{
-        Class, ClientError, Detail, ResponseCode, ServerError, Success, BAD_REQUEST, CREATED,
-        INTERNAL_SERVER_ERROR,
+        Class, ClientError, Detail, MethodCode, ResponseCode, ServerError, Success, BAD_REQUEST,
+        CREATED, INTERNAL_SERVER_ERROR, METHOD_NOT_ALLOWED, NOT_ACCEPTABLE, PRECONDITION_FAILED,
     };
 
+    #[rstest]
+    #[case(METHOD_NOT_ALLOWED, ClientError::MethodNotAllowed)]
+    #[case(NOT_ACCEPTABLE, ClientError::NotAcceptable)]
+    #[case(PRECONDITION_FAILED, ClientError::PreconditionFailed)]
+    fn client_error_decode(#[case] detail: Detail, #[case] expected: ClientError) {
+        assert_eq!(expected, ClientError::decode(detail))
+    }
+
     #[rstest]
     #[case(ResponseCode::Success(Success::Created), (Class::Success, CREATED))]
     #[case(ResponseCode::ClientError(ClientError::BadRequest), (Class::ClientError, BAD_REQUEST))]
@@ -205,4 +282,67 @@ mod tests {
     fn encode(#[case] response_code: ResponseCode, #[case] expected: (Class, Detail)) {
         assert_eq!(expected, response_code.encode())
     }
+
+    #[rstest]
+    #[case(Success::Created, MethodCode::Post, true)]
+    #[case(Success::Created, MethodCode::Get, false)]
+    #[case(Success::Deleted, MethodCode::Delete, true)]
+    #[case(Success::Deleted, MethodCode::Put, false)]
+    #[case(Success::Valid, MethodCode::Get, true)]
+    #[case(Success::Changed, MethodCode::Put, true)]
+    #[case(Success::Changed, MethodCode::Delete, false)]
+    #[case(Success::Content, MethodCode::Get, true)]
+    #[case(Success::Content, MethodCode::Post, false)]
+    #[case(Success::Continue, MethodCode::Put, true)]
+    #[case(Success::Continue, MethodCode::Get, false)]
+    #[case(
+        Success::decode(Detail::from_value_or_panic(30)),
+        MethodCode::Get,
+        true
+    )]
+    #[case(
+        Success::Created,
+        MethodCode::decode(Detail::from_value_or_panic(5)),
+        true
+    )]
+    fn is_allowed_for(
+        #[case] success: Success,
+        #[case] method: MethodCode,
+        #[case] expected: bool,
+    ) {
+        assert_eq!(expected, success.is_allowed_for(method))
+    }
+
+    #[rstest]
+    #[case(ResponseCode::CREATED, ResponseCode::Success(Success::Created))]
+    #[case(ResponseCode::CONTENT, ResponseCode::Success(Success::Content))]
+    #[case(
+        ResponseCode::NOT_FOUND,
+        ResponseCode::ClientError(ClientError::NotFound)
+    )]
+    #[case(
+        ResponseCode::PROXYING_NOT_SUPPORTED,
+        ResponseCode::ServerError(ServerError::ProxyingNotSupported)
+    )]
+    fn constants(#[case] constant: ResponseCode, #[case] expected: ResponseCode) {
+        assert_eq!(expected, constant)
+    }
+
+    #[rstest]
+    #[case(ResponseCode::CONTENT, (2, 5), Class::Success, false, false)]
+    #[case(ResponseCode::UNSUPPORTED_CONTENT_FORMAT, (4, 15), Class::ClientError, true, false)]
+    #[case(ResponseCode::GATEWAY_TIMEOUT, (5, 4), Class::ServerError, false, true)]
+    #[case(ResponseCode::decode_client_error(Detail::from_value_or_panic(29)), (4, 29), Class::ClientError, true, false)]
+    fn numeric_and_class(
+        #[case] response_code: ResponseCode,
+        #[case] numeric: (u8, u8),
+        #[case] class: Class,
+        #[case] is_client_error: bool,
+        #[case] is_server_error: bool,
+    ) {
+        assert_eq!(numeric, response_code.numeric());
+        assert_eq!(class, response_code.class());
+        assert_eq!(is_client_error, response_code.is_client_error());
+        assert_eq!(is_server_error, response_code.is_server_error());
+    }
 }
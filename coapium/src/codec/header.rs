@@ -1,6 +1,8 @@
 use version::Version;
 
-use crate::codec::{parsing::take, version, Code, MessageId, MessageType, TokenLength};
+use crate::codec::{
+    parsing::take, token_length, version, Code, MessageId, MessageType, TokenLength,
+};
 
 #[derive(Clone, Copy, Debug, PartialEq)]
 pub struct Header {
@@ -13,6 +15,8 @@ pub struct Header {
 #[derive(Clone, Copy, Debug, PartialEq)]
 pub enum Error {
     DataLength,
+    /// Token length 9-15, which RFC 7252 §3 requires to be processed as a message format error.
+    TokenLength(token_length::Error),
     Version(version::Error),
 }
 
@@ -52,15 +56,14 @@ impl Header {
     }
 
     pub fn parse(bytes: &[u8]) -> Result<(&[u8], Self), Error> {
-        let Ok((_, header_bytes)) = take::<4>(bytes) else {
+        let Ok((rest, header_bytes)) = take::<4>(bytes) else {
             return Err(Error::DataLength);
         };
-        let rest = &bytes[4..];
 
         Version::decode(header_bytes[0])?;
 
         let message_type = MessageType::decode(header_bytes[0]);
-        let token_length = TokenLength::decode(header_bytes[0]);
+        let token_length = TokenLength::from_value(TokenLength::decode(header_bytes[0]).value())?;
         let code = Code::decode(header_bytes[1]);
         let message_id = MessageId::decode([header_bytes[2], header_bytes[3]]);
 
@@ -80,12 +83,30 @@ impl Header {
     }
 }
 
+impl From<token_length::Error> for Error {
+    fn from(value: token_length::Error) -> Self {
+        Self::TokenLength(value)
+    }
+}
+
 impl From<version::Error> for Error {
     fn from(value: version::Error) -> Self {
         Self::Version(value)
     }
 }
 
+impl std::fmt::Display for Error {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Error::DataLength => write!(f, "header requires 4 bytes"),
+            Error::TokenLength(error) => write!(f, "invalid header: {error}"),
+            Error::Version(error) => write!(f, "invalid header: {error}"),
+        }
+    }
+}
+
+impl std::error::Error for Error {}
+
 #[cfg(test)]
 mod tests {
 
@@ -94,8 +115,8 @@ mod tests {
 
     use super::{
         super::code::response_code::{ClientError, Success},
-        super::ResponseCode,
-        version, Code, Error, Header, MessageId, MessageType, TokenLength,
+        super::{MethodCode, ResponseCode},
+        token_length, version, Code, Error, Header, MessageId, MessageType, TokenLength,
     };
 
     #[rstest]
@@ -180,6 +201,31 @@ mod tests {
 
     #[rstest]
     #[case(&[], &[], Err(Error::DataLength))]
+    #[case(&[0b01_00_0000], &[], Err(Error::DataLength))]
+    #[case(&[0b01_00_0000, 0b000_00001], &[], Err(Error::DataLength))]
+    #[case(&[0b01_00_0000, 0b000_00001, 0], &[], Err(Error::DataLength))]
+    #[case(
+        &[0b01_00_0000, 0b000_00001, 0, 2],
+        &[],
+        Ok(Header {
+            message_type: MessageType::Confirmable,
+            token_length: TokenLength::from_value(0).unwrap(),
+            code: Code::Request(MethodCode::Get),
+            message_id: MessageId::from_value(2),
+        }),
+    )]
+    #[case(
+        &[0b01_00_1000, 0b000_00001, 0, 2, 1, 2, 3, 4, 5, 6, 7, 8],
+        &[1, 2, 3, 4, 5, 6, 7, 8],
+        Ok(Header {
+            message_type: MessageType::Confirmable,
+            token_length: TokenLength::from_value(8).unwrap(),
+            code: Code::Request(MethodCode::Get),
+            message_id: MessageId::from_value(2),
+        }),
+    )]
+    #[case(&[0b01_00_1001, 0b000_00001, 0, 2], &[], Err(Error::TokenLength(token_length::Error::OutOfRange(9))))]
+    #[case(&[0b01_00_1111, 0b000_00001, 0, 2], &[], Err(Error::TokenLength(token_length::Error::OutOfRange(15))))]
     #[case(
         &[0b10_10_0001, 0b010_00001, 0, 2, 3, 4],
         &[],
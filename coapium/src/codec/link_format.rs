@@ -0,0 +1,167 @@
+/// Link of a CoRE Link Format (RFC 6690) document, like the resources a server lists under
+/// `/.well-known/core`.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Link {
+    pub target: String,
+    /// Parameters in the order they were given, with the quotes around quoted values removed.
+    pub parameters: Vec<(String, Option<String>)>,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Error {
+    /// The link at `index` isn't of the form `<target>;params`.
+    Malformed { index: usize },
+}
+
+impl Link {
+    /// Value of the first parameter called `name`, `None` if there is none or it has no value.
+    pub fn parameter(&self, name: &str) -> Option<&str> {
+        self.parameters
+            .iter()
+            .find(|(parameter, _)| parameter == name)
+            .and_then(|(_, value)| value.as_deref())
+    }
+
+    pub fn has_parameter(&self, name: &str) -> bool {
+        self.parameters
+            .iter()
+            .any(|(parameter, _)| parameter == name)
+    }
+
+    fn parse(link: &str) -> Option<Self> {
+        let (target, parameters) = link.trim().strip_prefix('<')?.split_once('>')?;
+        if !parameters.is_empty() && !parameters.starts_with(';') {
+            return None;
+        }
+
+        let parameters = split_outside_quotes(parameters, ';')
+            .into_iter()
+            .skip(1)
+            .map(|parameter| match parameter.split_once('=') {
+                Some((name, value)) => (name.trim().to_owned(), Some(unquote(value.trim()))),
+                None => (parameter.trim().to_owned(), None),
+            })
+            .collect();
+
+        Some(Self {
+            target: target.to_owned(),
+            parameters,
+        })
+    }
+}
+
+/// Links of a link-format document, an empty document has no links.
+pub fn parse(links: &str) -> Result<Vec<Link>, Error> {
+    if links.trim().is_empty() {
+        return Ok(vec![]);
+    }
+
+    split_outside_quotes(links, ',')
+        .into_iter()
+        .enumerate()
+        .map(|(index, link)| Link::parse(link).ok_or(Error::Malformed { index }))
+        .collect()
+}
+
+fn split_outside_quotes(value: &str, separator: char) -> Vec<&str> {
+    let mut result = vec![];
+    let mut quoted = false;
+    let mut escaped = false;
+    let mut start = 0;
+
+    for (i, c) in value.char_indices() {
+        match c {
+            _ if escaped => escaped = false,
+            '\\' if quoted => escaped = true,
+            '"' => quoted = !quoted,
+            c if c == separator && !quoted => {
+                result.push(&value[start..i]);
+                start = i + c.len_utf8();
+            }
+            _ => {}
+        }
+    }
+    result.push(&value[start..]);
+
+    result
+}
+
+fn unquote(value: &str) -> String {
+    let Some(value) = value
+        .strip_prefix('"')
+        .and_then(|value| value.strip_suffix('"'))
+    else {
+        return value.to_owned();
+    };
+
+    let mut result = String::with_capacity(value.len());
+    let mut chars = value.chars();
+    while let Some(c) = chars.next() {
+        match c {
+            '\\' => result.extend(chars.next()),
+            c => result.push(c),
+        }
+    }
+
+    result
+}
+
+impl std::fmt::Display for Error {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Error::Malformed { index } => {
+                write!(f, "link {} is not of the form <target>;params", index + 1)
+            }
+        }
+    }
+}
+
+impl std::error::Error for Error {}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+    use rstest::rstest;
+
+    use super::{parse, Error, Link};
+
+    fn link(target: &str, parameters: &[(&str, Option<&str>)]) -> Link {
+        Link {
+            target: target.to_owned(),
+            parameters: parameters
+                .iter()
+                .map(|(name, value)| ((*name).to_owned(), value.map(ToOwned::to_owned)))
+                .collect(),
+        }
+    }
+
+    #[rstest]
+    #[case("", Ok(vec![]))]
+    #[case("</sensors>", Ok(vec![link("/sensors", &[])]))]
+    #[case(
+        "</sensors/temp>;rt=\"temperature-c\";if=sensor;obs,\n</sensors/light>;ct=0",
+        Ok(vec![
+            link("/sensors/temp", &[("rt", Some("temperature-c")), ("if", Some("sensor")), ("obs", None)]),
+            link("/sensors/light", &[("ct", Some("0"))]),
+        ])
+    )]
+    #[case(
+        "</a>;title=\"a, \\\"b\\\"; c\"",
+        Ok(vec![link("/a", &[("title", Some("a, \"b\"; c"))])])
+    )]
+    #[case("</a>,/b", Err(Error::Malformed { index: 1 }))]
+    #[case("</a>rt=x", Err(Error::Malformed { index: 0 }))]
+    fn parse_links(#[case] links: &str, #[case] expected: Result<Vec<Link>, Error>) {
+        assert_eq!(expected, parse(links));
+    }
+
+    #[rstest]
+    fn parameter() {
+        let link = link("/a", &[("obs", None), ("rt", Some("x")), ("rt", Some("y"))]);
+
+        assert_eq!(Some("x"), link.parameter("rt"));
+        assert_eq!(None, link.parameter("obs"));
+        assert!(link.has_parameter("obs"));
+        assert!(!link.has_parameter("ct"));
+    }
+}
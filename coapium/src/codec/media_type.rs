@@ -64,6 +64,7 @@ pub enum MediaType {
     ApplicationOctetStream,
     ApplicationExi,
     ApplicationJson,
+    ApplicationCbor,
     ExpertReview(ExpertReview),
     IetfOrIesg(IetfOrIesg),
     FirstComeFirstServe(FirstComeFirstServe),
@@ -83,6 +84,7 @@ impl MediaType {
     pub const APPLICATION_OCTET_STREAM: u16 = 42;
     pub const APPLICATION_EXI: u16 = 47;
     pub const APPLICATION_JSON: u16 = 50;
+    pub const APPLICATION_CBOR: u16 = 60;
 
     pub fn decode(values: Vec<Value>) -> Result<Self, Error> {
         let value = single(values).map_err(|_| Error::SingleValue)?;
@@ -102,6 +104,7 @@ impl MediaType {
             Self::APPLICATION_OCTET_STREAM => Self::ApplicationOctetStream,
             Self::APPLICATION_EXI => Self::ApplicationExi,
             Self::APPLICATION_JSON => Self::ApplicationJson,
+            Self::APPLICATION_CBOR => Self::ApplicationCbor,
             0..=255 => Self::ExpertReview(ExpertReview(value)),
             256..=9999 => Self::IetfOrIesg(IetfOrIesg(value)),
             10000..=64999 => Self::FirstComeFirstServe(FirstComeFirstServe(value)),
@@ -118,6 +121,7 @@ impl MediaType {
             MediaType::ApplicationOctetStream => Some(Self::APPLICATION_OCTET_STREAM),
             MediaType::ApplicationExi => Some(Self::APPLICATION_EXI),
             MediaType::ApplicationJson => Some(Self::APPLICATION_JSON),
+            MediaType::ApplicationCbor => Some(Self::APPLICATION_CBOR),
             MediaType::ExpertReview(ExpertReview(value)) => Some(*value),
             MediaType::IetfOrIesg(IetfOrIesg(value)) => Some(*value),
             MediaType::FirstComeFirstServe(FirstComeFirstServe(value)) => Some(*value),
@@ -139,7 +143,19 @@ impl TryFrom<&str> for MediaType {
             "application/octet-stream " => Ok(MediaType::ApplicationOctetStream),
             "application/exi" => Ok(MediaType::ApplicationExi),
             "application/json" => Ok(MediaType::ApplicationJson),
+            "application/cbor" => Ok(MediaType::ApplicationCbor),
             _ => Err(()),
         }
     }
 }
+
+impl std::fmt::Display for Error {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Error::SingleValue => write!(f, "media type requires exactly one value"),
+            Error::Number => write!(f, "media type is not a valid number"),
+        }
+    }
+}
+
+impl std::error::Error for Error {}
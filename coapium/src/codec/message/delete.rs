@@ -1,7 +1,7 @@
-use crate::codec::{token, Header, MessageId, Token, TokenLength};
+use crate::codec::{token, Header, MessageId, Payload, Token, TokenLength};
 
 use super::delete_options::{self, DeleteOptions};
-use super::{Method, Reliability};
+use super::{Leniency, Method, Reliability};
 
 #[derive(Clone, Debug, PartialEq)]
 pub struct Delete {
@@ -9,6 +9,7 @@ pub struct Delete {
     reliability: Reliability,
     token: Token,
     options: DeleteOptions,
+    ignored_payload: bool,
 }
 
 #[derive(Clone, Copy, Debug, PartialEq)]
@@ -24,16 +25,22 @@ impl Delete {
         token_length: TokenLength,
         reliability: Reliability,
         remaining_bytes: &[u8],
+        leniency: Leniency,
     ) -> Result<Self, Error> {
         let (remaining_bytes, token) = Token::parse(token_length, remaining_bytes)?;
 
         let (remaining_bytes, options) = DeleteOptions::parse(remaining_bytes)?;
 
-        if !remaining_bytes.is_empty() {
-            return Err(Error::ResidualData);
-        }
+        let ignored_payload = match (remaining_bytes.is_empty(), leniency) {
+            (true, _) => false,
+            (false, Leniency::Tolerant) if Payload::decode(remaining_bytes).is_ok() => true,
+            _ => return Err(Error::ResidualData),
+        };
 
-        Ok(Self::new(message_id, reliability, token, options))
+        Ok(Self {
+            ignored_payload,
+            ..Self::new(message_id, reliability, token, options)
+        })
     }
 
     pub fn encode(self) -> Vec<u8> {
@@ -71,9 +78,16 @@ impl Delete {
             reliability,
             token,
             options,
+            ignored_payload: false,
         }
     }
 
+    /// Whether the DELETE came with a payload that was dropped while decoding with
+    /// [`Leniency::Tolerant`].
+    pub fn has_ignored_payload(&self) -> bool {
+        self.ignored_payload
+    }
+
     pub fn options(&self) -> &DeleteOptions {
         &self.options
     }
@@ -99,6 +113,18 @@ impl From<delete_options::Error> for Error {
     }
 }
 
+impl std::fmt::Display for Error {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Error::Token(error) => write!(f, "invalid DELETE token: {error}"),
+            Error::Options(error) => write!(f, "invalid DELETE options: {error}"),
+            Error::ResidualData => write!(f, "DELETE must not contain a payload"),
+        }
+    }
+}
+
+impl std::error::Error for Error {}
+
 #[cfg(test)]
 mod tests {
     use pretty_assertions::assert_eq;
@@ -106,7 +132,7 @@ mod tests {
 
     use crate::codec::{
         self,
-        message::{Delete, Reliability},
+        message::{Delete, Leniency, Reliability},
         option::{DecodedOption, Delta, Number},
         MessageId,
     };
@@ -127,6 +153,7 @@ mod tests {
                 message_id: MessageId::from_value(1), 
                 reliability: Reliability::Confirmable, 
                 token: Token::from_value(vec![1]).unwrap(),
+                ignored_payload: false,
                 options: DeleteOptions::new(),
             }
         )
@@ -174,6 +201,7 @@ mod tests {
             message_id: MessageId::from_value(4),
             reliability: Reliability::Confirmable,
             token: Token::from_value(vec![1]).unwrap(),
+            ignored_payload: false,
             options: {  DeleteOptions::new() }
         })
     )]
@@ -186,16 +214,39 @@ mod tests {
     ) {
         assert_eq!(
             expected,
-            Delete::decode(message_id, token_length, reliability, &remaining_bytes)
+            Delete::decode(
+                message_id,
+                token_length,
+                reliability,
+                &remaining_bytes,
+                Leniency::Strict
+            )
         )
     }
 
+    #[rstest]
+    #[case(&[1, 0xff, 1, 2], Ok(true))]
+    #[case(&[1], Ok(false))]
+    #[case(&[1, 0xff], Err(Error::ResidualData))]
+    fn decode_tolerant(#[case] remaining_bytes: &[u8], #[case] expected: Result<bool, Error>) {
+        let decoded = Delete::decode(
+            MessageId::from_value(1),
+            TokenLength::from_value(1).unwrap(),
+            Reliability::Confirmable,
+            remaining_bytes,
+            Leniency::Tolerant,
+        );
+
+        assert_eq!(expected, decoded.map(|delete| delete.has_ignored_payload()))
+    }
+
     #[rstest]
     #[case(
         Delete {
             message_id: MessageId::from_value(4),
             reliability: Reliability::Confirmable,
             token: Token::from_value(vec![1]).unwrap(),
+            ignored_payload: false,
             options: { DeleteOptions::new() }
         },
         &[0b01_00_0001, 0b000_00100, 0, 4, 1]
@@ -210,6 +261,7 @@ mod tests {
             message_id: MessageId::from_value(4),
             reliability: Reliability::Confirmable,
             token: Token::from_value(vec![1]).unwrap(),
+            ignored_payload: false,
             options: { DeleteOptions::new() }
         },
        MessageId::from_value(4) 
@@ -229,6 +281,7 @@ mod tests {
             message_id: MessageId::from_value(4),
             reliability: Reliability::Confirmable,
             token: Token::from_value(vec![1]).unwrap(),
+            ignored_payload: false,
             options: { DeleteOptions::new() }
         },
     )]
@@ -251,6 +304,7 @@ mod tests {
             message_id: MessageId::from_value(4),
             reliability: Reliability::Confirmable,
             token: Token::from_value(vec![1]).unwrap(),
+            ignored_payload: false,
             options: { DeleteOptions::new() }
         },
         {  DeleteOptions::new() }
@@ -265,6 +319,7 @@ mod tests {
             message_id: MessageId::from_value(4),
             reliability: Reliability::Confirmable,
             token: Token::from_value(vec![1]).unwrap(),
+            ignored_payload: false,
             options: { DeleteOptions::new() }
         },
              Reliability::Confirmable,
@@ -280,6 +335,7 @@ mod tests {
             message_id: MessageId::from_value(4),
             reliability: Reliability::Confirmable,
             token: Token::from_value(vec![1]).unwrap(),
+            ignored_payload: false,
             options: { DeleteOptions::new() }
         },
              Token::from_value(vec![1]).unwrap(),
@@ -1,8 +1,9 @@
 use crate::codec::{
-    option::{Number, UriHost, UriPath, UriPort, UriQuery},
+    option::{Echo, Number, RequestTag, UriHost, UriPath, UriPort, UriQuery},
     options, Options,
 };
 
+/// Options of a DELETE request, only options valid for DELETE can be set.
 #[derive(Clone, Debug, PartialEq)]
 pub struct DeleteOptions {
     options: Options,
@@ -52,6 +53,22 @@ impl DeleteOptions {
         ]
     }
 
+    pub fn echo(&self) -> std::option::Option<&Echo> {
+        self.options.echo()
+    }
+
+    pub fn request_tag(&self) -> std::option::Option<&RequestTag> {
+        self.options.request_tag()
+    }
+
+    pub fn set_echo(&mut self, echo: Echo) {
+        self.options.set_echo(echo)
+    }
+
+    pub fn set_request_tag(&mut self, request_tag: RequestTag) {
+        self.options.set_request_tag(request_tag)
+    }
+
     pub fn set_uri_host(&mut self, host: UriHost) {
         self.options.set_uri_host(host)
     }
@@ -67,6 +84,27 @@ impl DeleteOptions {
     pub fn set_uri_query(&mut self, path: UriQuery) {
         self.options.set_uri_query(path)
     }
+
+    /// Removes every option with `number`.
+    pub fn remove(&mut self, number: Number) {
+        self.options.remove(number)
+    }
+
+    pub fn remove_uri_query(&mut self) {
+        self.options.remove_uri_query()
+    }
+
+    pub fn clear(&mut self) {
+        self.options.clear()
+    }
+
+    pub fn uri_path(&self) -> std::option::Option<&UriPath> {
+        self.options.uri_path()
+    }
+
+    pub fn uri_query(&self) -> std::option::Option<&UriQuery> {
+        self.options.uri_query()
+    }
 }
 
 impl From<options::Error> for Error {
@@ -75,6 +113,19 @@ impl From<options::Error> for Error {
     }
 }
 
+impl std::fmt::Display for Error {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Error::Options(error) => error.fmt(f),
+            Error::Unrecognized(number) => {
+                write!(f, "critical option {number} is not recognized for DELETE")
+            }
+        }
+    }
+}
+
+impl std::error::Error for Error {}
+
 #[cfg(test)]
 mod tests {
 
@@ -0,0 +1,201 @@
+use std::ops::Range;
+
+use crate::codec::{
+    option::{number::registry::Format, EncodedOption, Number},
+    payload, Header, Payload, Token,
+};
+
+use super::{Error, Message};
+
+/// Field of a message and the bytes it was taken from.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Field {
+    pub range: Range<usize>,
+    pub name: String,
+    pub value: String,
+}
+
+/// Message taken apart field by field along with the bytes of each field, like Wireshark shows
+/// it, for debugging messages captured from other implementations.
+///
+/// The fields go as far as the message could be taken apart. `error` is why the message doesn't
+/// [decode](Message::decode), with the offset of the byte where taking it apart stopped if it
+/// did.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Dissection {
+    pub fields: Vec<Field>,
+    pub error: Option<(Option<usize>, Error)>,
+}
+
+impl Dissection {
+    pub fn dissect(bytes: &[u8]) -> Self {
+        let mut fields = vec![];
+        let offset = Self::dissect_fields(bytes, &mut fields).err();
+
+        Self {
+            fields,
+            error: Message::decode(bytes).err().map(|error| (offset, error)),
+        }
+    }
+
+    pub fn is_valid(&self) -> bool {
+        self.error.is_none()
+    }
+
+    /// Pushes the fields of `bytes`, failing with the offset of the first byte that can't be
+    /// taken apart.
+    fn dissect_fields(bytes: &[u8], fields: &mut Vec<Field>) -> Result<(), usize> {
+        let offset = |rest: &[u8]| bytes.len() - rest.len();
+        let mut push = |range: Range<usize>, name: &str, value: String| {
+            fields.push(Field {
+                range,
+                name: name.to_owned(),
+                value,
+            })
+        };
+
+        let (rest, header) = Header::parse(bytes).map_err(|_| 0_usize)?;
+        push(0..1, "Version", "1".to_owned());
+        push(0..1, "Type", header.message_type().to_string());
+        push(
+            0..1,
+            "Token Length",
+            header.token_length().value().to_string(),
+        );
+        push(1..2, "Code", header.code().to_string());
+        push(2..4, "Message ID", header.message_id().value().to_string());
+
+        let (mut rest, token) =
+            Token::parse(header.token_length(), rest).map_err(|_| offset(rest))?;
+        if !token.is_empty() {
+            push(4..offset(rest), "Token", hex(token.as_bytes()));
+        }
+
+        let mut number = 0_u32;
+        while rest.first().is_some_and(|&byte| byte != payload::MARKER) {
+            let start = offset(rest);
+            let (next, option) = EncodedOption::parse(rest).map_err(|_| start)?;
+            number += u32::from(option.delta().value());
+            rest = next;
+
+            let registration = u16::try_from(number)
+                .ok()
+                .and_then(|number| Number::from_value(number).ok())
+                .and_then(|number| number.registration());
+            let name = registration.map_or("Unknown", |registration| registration.name);
+            let value = option.value().clone();
+            let value = match registration.map(|registration| registration.format) {
+                Some(Format::Empty) if value.is_empty() => "(empty)".to_owned(),
+                Some(Format::Uint) if value.u32().is_ok() => {
+                    value.u32().unwrap_or_default().to_string()
+                }
+                Some(Format::String) if value.valid_as_string() => {
+                    format!("{:?}", value.string().unwrap_or_default())
+                }
+                _ => hex(&value.opaque()),
+            };
+            push(
+                start..offset(rest),
+                &format!("Option #{number} {name}"),
+                value,
+            );
+        }
+
+        if !rest.is_empty() {
+            let start = offset(rest);
+            let payload = Payload::decode(rest).map_err(|_| start)?;
+            let payload = payload.value();
+            let value = match std::str::from_utf8(payload) {
+                Ok(text) => format!("length {}, {text:?}", payload.len()),
+                Err(_) => format!("length {}, {}", payload.len(), hex(payload)),
+            };
+            push(start..bytes.len(), "Payload", value);
+        }
+
+        Ok(())
+    }
+}
+
+fn hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|byte| format!("{byte:02x}")).collect()
+}
+
+impl std::fmt::Display for Dissection {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for field in &self.fields {
+            let range = format!("{}..{}", field.range.start, field.range.end);
+            writeln!(f, "{range:>9}  {}: {}", field.name, field.value)?;
+        }
+
+        match &self.error {
+            Some((Some(offset), error)) => writeln!(f, "Malformed at byte {offset}: {error}"),
+            Some((None, error)) => writeln!(f, "Malformed: {error}"),
+            None => Ok(()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+    use rstest::rstest;
+
+    use super::{Dissection, Field};
+
+    fn field(range: std::ops::Range<usize>, name: &str, value: &str) -> Field {
+        Field {
+            range,
+            name: name.to_owned(),
+            value: value.to_owned(),
+        }
+    }
+
+    #[rstest]
+    fn dissect_request() {
+        // CON GET with token 0xab, Uri-Path "a" and payload "x"
+        let bytes = [0x41, 0x01, 0x00, 0x01, 0xab, 0xb1, b'a', 0xff, b'x'];
+
+        let dissection = Dissection::dissect(&bytes);
+
+        assert_eq!(
+            vec![
+                field(0..1, "Version", "1"),
+                field(0..1, "Type", "CON"),
+                field(0..1, "Token Length", "1"),
+                field(1..2, "Code", "0.01"),
+                field(2..4, "Message ID", "1"),
+                field(4..5, "Token", "ab"),
+                field(5..7, "Option #11 Uri-Path", "\"a\""),
+                field(7..9, "Payload", "length 1, \"x\""),
+            ],
+            dissection.fields
+        );
+        // A GET with a payload only decodes leniently, there's no byte to blame.
+        assert_eq!(Some(None), dissection.error.map(|(offset, _)| offset));
+    }
+
+    #[rstest]
+    fn dissect_response() {
+        // ACK 2.05 with Content-Format 0
+        let bytes = [0x60, 0x45, 0x12, 0x34, 0xc0];
+
+        let dissection = Dissection::dissect(&bytes);
+
+        assert!(dissection.is_valid());
+        assert_eq!(
+            Some(&field(4..5, "Option #12 Content-Format", "0")),
+            dissection.fields.last()
+        );
+    }
+
+    #[rstest]
+    #[case(&[0x40, 0x01], Some(0))]
+    #[case(&[0x42, 0x01, 0x00, 0x01, 0xab], Some(4))]
+    #[case(&[0x40, 0x01, 0x00, 0x01, 0xb5, b'a'], Some(4))]
+    #[case(&[0x40, 0x01, 0x00, 0x01, 0xff], Some(4))]
+    fn dissect_locates_error(#[case] bytes: &[u8], #[case] offset: Option<usize>) {
+        let dissection = Dissection::dissect(bytes);
+
+        assert_eq!(Some(offset), dissection.error.map(|(offset, _)| offset));
+    }
+}
@@ -1,7 +1,7 @@
-use crate::codec::{token, Header, MessageId, Token, TokenLength};
+use crate::codec::{token, Header, MessageId, Payload, Token, TokenLength};
 
 use super::get_options::{self, GetOptions};
-use super::{Method, Reliability};
+use super::{Leniency, Method, Reliability};
 
 #[derive(Clone, Debug, PartialEq)]
 pub struct Get {
@@ -9,6 +9,7 @@ pub struct Get {
     reliability: Reliability,
     token: Token,
     options: GetOptions,
+    ignored_payload: bool,
 }
 
 // Option notes regarding GET-requests
@@ -33,16 +34,22 @@ impl Get {
         token_length: TokenLength,
         reliability: Reliability,
         remaining_bytes: &[u8],
+        leniency: Leniency,
     ) -> Result<Self, Error> {
         let (remaining_bytes, token) = Token::parse(token_length, remaining_bytes)?;
 
         let (remaining_bytes, options) = GetOptions::parse(remaining_bytes)?;
 
-        if !remaining_bytes.is_empty() {
-            return Err(Error::ResidualData);
-        }
+        let ignored_payload = match (remaining_bytes.is_empty(), leniency) {
+            (true, _) => false,
+            (false, Leniency::Tolerant) if Payload::decode(remaining_bytes).is_ok() => true,
+            _ => return Err(Error::ResidualData),
+        };
 
-        Ok(Self::new(message_id, reliability, token, options))
+        Ok(Self {
+            ignored_payload,
+            ..Self::new(message_id, reliability, token, options)
+        })
     }
 
     pub fn encode(self) -> Vec<u8> {
@@ -80,9 +87,16 @@ impl Get {
             reliability,
             token,
             options,
+            ignored_payload: false,
         }
     }
 
+    /// Whether the GET came with a payload that was dropped while decoding with
+    /// [`Leniency::Tolerant`].
+    pub fn has_ignored_payload(&self) -> bool {
+        self.ignored_payload
+    }
+
     pub fn options(&self) -> &GetOptions {
         &self.options
     }
@@ -108,6 +122,18 @@ impl From<get_options::Error> for Error {
     }
 }
 
+impl std::fmt::Display for Error {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Error::Token(error) => write!(f, "invalid GET token: {error}"),
+            Error::Options(error) => write!(f, "invalid GET options: {error}"),
+            Error::ResidualData => write!(f, "GET must not contain a payload"),
+        }
+    }
+}
+
+impl std::error::Error for Error {}
+
 #[cfg(test)]
 mod tests {
     use pretty_assertions::assert_eq;
@@ -115,7 +141,7 @@ mod tests {
 
     use crate::codec::{
         self,
-        message::{Get, Reliability},
+        message::{Get, Leniency, Reliability},
         option::{DecodedOption, Delta, Number},
         MessageId,
     };
@@ -136,6 +162,7 @@ mod tests {
                 message_id: MessageId::from_value(1), 
                 reliability: Reliability::Confirmable, 
                 token: Token::from_value(vec![1]).unwrap(),
+                ignored_payload: false,
                 options: GetOptions::new(),
             }
         )
@@ -186,6 +213,7 @@ mod tests {
             message_id: MessageId::from_value(4),
             reliability: Reliability::Confirmable,
             token: Token::from_value(vec![1]).unwrap(),
+            ignored_payload: false,
             options: {
                 let mut options = GetOptions::new();
                 options.set_uri_path("a/b/c".try_into().unwrap());
@@ -202,16 +230,39 @@ mod tests {
     ) {
         assert_eq!(
             expected,
-            Get::decode(message_id, token_length, reliability, &remaining_bytes)
+            Get::decode(
+                message_id,
+                token_length,
+                reliability,
+                &remaining_bytes,
+                Leniency::Strict
+            )
         )
     }
 
+    #[rstest]
+    #[case(&[1, 0xff, 1, 2], Ok(true))]
+    #[case(&[1], Ok(false))]
+    #[case(&[1, 0xff], Err(Error::ResidualData))]
+    fn decode_tolerant(#[case] remaining_bytes: &[u8], #[case] expected: Result<bool, Error>) {
+        let decoded = Get::decode(
+            MessageId::from_value(1),
+            TokenLength::from_value(1).unwrap(),
+            Reliability::Confirmable,
+            remaining_bytes,
+            Leniency::Tolerant,
+        );
+
+        assert_eq!(expected, decoded.map(|get| get.has_ignored_payload()))
+    }
+
     #[rstest]
     #[case(
         Get {
             message_id: MessageId::from_value(4),
             reliability: Reliability::Confirmable,
             token: Token::from_value(vec![1]).unwrap(),
+            ignored_payload: false,
             options: {
                 let mut options = GetOptions::new();
                 options.set_uri_path("a/b/c".try_into().unwrap());
@@ -230,6 +281,7 @@ mod tests {
             message_id: MessageId::from_value(4),
             reliability: Reliability::Confirmable,
             token: Token::from_value(vec![1]).unwrap(),
+            ignored_payload: false,
             options: {
                 let mut options = GetOptions::new();
                 options.set_uri_path("a/b/c".try_into().unwrap());
@@ -258,6 +310,7 @@ mod tests {
             message_id: MessageId::from_value(4),
             reliability: Reliability::Confirmable,
             token: Token::from_value(vec![1]).unwrap(),
+            ignored_payload: false,
             options: {
                 let mut options = GetOptions::new();
                 
@@ -283,6 +336,7 @@ mod tests {
             message_id: MessageId::from_value(4),
             reliability: Reliability::Confirmable,
             token: Token::from_value(vec![1]).unwrap(),
+            ignored_payload: false,
             options: {
                 let mut options = GetOptions::new();
                 options.set_uri_path("a/b/c".try_into().unwrap());
@@ -308,6 +362,7 @@ mod tests {
             message_id: MessageId::from_value(4),
             reliability: Reliability::Confirmable,
             token: Token::from_value(vec![1]).unwrap(),
+            ignored_payload: false,
             options: {
                 let mut options = GetOptions::new();
                 
@@ -329,6 +384,7 @@ mod tests {
             message_id: MessageId::from_value(4),
             reliability: Reliability::Confirmable,
             token: Token::from_value(vec![1]).unwrap(),
+            ignored_payload: false,
             options: {
                 let mut options = GetOptions::new();
                 
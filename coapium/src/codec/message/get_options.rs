@@ -1,12 +1,14 @@
 use crate::codec::{option::Number, Options};
 use crate::codec::{
     option::{
-        accept::Accept, proxy_scheme::ProxyScheme, proxy_uri::ProxyUri, uri_host::UriHost,
-        uri_path::UriPath, uri_port::UriPort, uri_query::UriQuery, ETag,
+        accept::Accept, block2::Block2, observe::Observe, proxy_scheme::ProxyScheme,
+        proxy_uri::ProxyUri, uri_host::UriHost, uri_path::UriPath, uri_port::UriPort,
+        uri_query::UriQuery, ETag, Echo, QBlock2, RequestTag,
     },
     options,
 };
 
+/// Options of a GET request, only options valid for GET can be set.
 #[derive(Clone, Debug, PartialEq)]
 pub struct GetOptions {
     options: Options,
@@ -30,6 +32,69 @@ impl GetOptions {
         }
     }
 
+    pub fn block2(&self) -> std::option::Option<&Block2> {
+        self.options.block2()
+    }
+
+    pub fn etag(&self) -> std::option::Option<&ETag> {
+        self.options.etag()
+    }
+
+    pub fn observe(&self) -> std::option::Option<&Observe> {
+        self.options.observe()
+    }
+
+    pub fn echo(&self) -> std::option::Option<&Echo> {
+        self.options.echo()
+    }
+
+    /// Quick blocks asked for, see [`QBlock2`].
+    pub fn q_block2(&self) -> std::option::Option<&QBlock2> {
+        self.options.q_block2()
+    }
+
+    pub fn request_tag(&self) -> std::option::Option<&RequestTag> {
+        self.options.request_tag()
+    }
+
+    pub fn set_echo(&mut self, echo: Echo) {
+        self.options.set_echo(echo)
+    }
+
+    pub fn set_request_tag(&mut self, request_tag: RequestTag) {
+        self.options.set_request_tag(request_tag)
+    }
+
+    pub fn accept(&self) -> std::option::Option<&Accept> {
+        self.options.accept()
+    }
+
+    pub fn set_accept(&mut self, accept: Accept) {
+        self.options.set_accept(accept)
+    }
+
+    pub fn set_block2(&mut self, block2: Block2) {
+        self.options.set_block2(block2)
+    }
+
+    pub fn set_q_block2(&mut self, q_block2: QBlock2) {
+        self.options.set_q_block2(q_block2)
+    }
+
+    pub fn set_etag(&mut self, etag: ETag) {
+        self.options.set_etag(etag)
+    }
+
+    /// Adds ETags to validate, a server answers 2.03 (Valid) when any of them is current, see
+    /// [RFC 7252 §5.10.6.2](https://datatracker.ietf.org/doc/html/rfc7252#section-5.10.6.2).
+    pub fn add_etag(&mut self, etag: ETag) {
+        self.options.add_etag(etag)
+    }
+
+    pub fn set_observe(&mut self, observe: Observe) {
+        self.options.set_observe(observe)
+    }
+
     pub fn set_uri_host(&mut self, host: UriHost) {
         self.options.set_uri_host(host)
     }
@@ -46,6 +111,27 @@ impl GetOptions {
         self.options.set_uri_query(path)
     }
 
+    /// Removes every option with `number`.
+    pub fn remove(&mut self, number: Number) {
+        self.options.remove(number)
+    }
+
+    pub fn remove_uri_query(&mut self) {
+        self.options.remove_uri_query()
+    }
+
+    pub fn clear(&mut self) {
+        self.options.clear()
+    }
+
+    pub fn uri_path(&self) -> std::option::Option<&UriPath> {
+        self.options.uri_path()
+    }
+
+    pub fn uri_query(&self) -> std::option::Option<&UriQuery> {
+        self.options.uri_query()
+    }
+
     pub fn from_options(options: Options) -> Result<Self, Error> {
         if let Some(option) = options
             .options()
@@ -62,9 +148,12 @@ impl GetOptions {
     fn recognized_options() -> Vec<Number> {
         vec![
             Accept::number(),
+            Block2::number(),
             ETag::number(),
+            Observe::number(),
             ProxyScheme::number(),
             ProxyUri::number(),
+            QBlock2::number(),
             UriHost::number(),
             UriPath::number(),
             UriPort::number(),
@@ -83,13 +172,83 @@ impl From<options::Error> for Error {
     }
 }
 
+impl std::fmt::Display for Error {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Error::Options(error) => error.fmt(f),
+            Error::Unrecognized(number) => {
+                write!(f, "critical option {number} is not recognized for GET")
+            }
+        }
+    }
+}
+
+impl std::error::Error for Error {}
+
 #[cfg(test)]
 mod tests {
 
     use pretty_assertions::assert_eq;
     use rstest::rstest;
 
-    use super::{GetOptions, Options, UriHost, UriPath, UriPort, UriQuery};
+    use super::{Accept, ETag, GetOptions, Options, UriHost, UriPath, UriPort, UriQuery};
+    use crate::codec::MediaType;
+
+    #[rstest]
+    #[case(
+        GetOptions { options: Options::new() },
+        Accept::from(MediaType::APPLICATION_JSON),
+        GetOptions {
+            options: {
+                let mut options = Options::new();
+                options.set_accept(MediaType::APPLICATION_JSON.into());
+                options
+           }
+        }
+    )]
+    fn set_accept(
+        #[case] mut get_options: GetOptions,
+        #[case] accept: Accept,
+        #[case] expected: GetOptions,
+    ) {
+        get_options.set_accept(accept);
+        assert_eq!(expected, get_options)
+    }
+
+    #[rstest]
+    #[case(
+        GetOptions { options: Options::new() },
+        ETag::from_values(vec![vec![1, 2]]).unwrap(),
+        GetOptions {
+            options: {
+                let mut options = Options::new();
+                options.set_etag(vec![vec![1, 2]].try_into().unwrap());
+                options
+           }
+        }
+    )]
+    fn set_etag(
+        #[case] mut get_options: GetOptions,
+        #[case] etag: ETag,
+        #[case] expected: GetOptions,
+    ) {
+        get_options.set_etag(etag);
+        assert_eq!(expected, get_options)
+    }
+
+    #[rstest]
+    fn add_etag() {
+        let mut get_options = GetOptions::new();
+        get_options.set_uri_path("a".try_into().unwrap());
+        get_options.add_etag(ETag::from_values(vec![vec![1, 2]]).unwrap());
+        get_options.set_accept(MediaType::APPLICATION_JSON.into());
+        get_options.add_etag(ETag::from_values(vec![vec![3]]).unwrap());
+
+        assert_eq!(
+            vec![0x42, 1, 2, 0x01, 3, 0x71, b'a', 0x61, 50],
+            get_options.encode()
+        );
+    }
 
     #[rstest]
     #[case(
@@ -0,0 +1,172 @@
+use crate::codec::{
+    option::{
+        self,
+        decoded_option::{self, DecodedOption},
+        encoded_option, Delta, EncodedOption, Number,
+    },
+    parsing::many0,
+    payload, Header, Options, Payload, Token,
+};
+
+use super::Error;
+
+/// Message decoded as far as possible, for tools that need to look at messages this crate would
+/// otherwise reject.
+#[derive(Clone, Debug, PartialEq)]
+pub struct LossyMessage {
+    pub header: Header,
+    pub token: Token,
+    pub options: Options,
+    /// Options that are unrecognized or have an invalid value.
+    pub raw_options: Vec<RawOption>,
+    pub payload: Payload,
+    pub diagnostics: Vec<Diagnostic>,
+}
+
+/// Value of an option that was not decoded, repeated options result in one per value.
+#[derive(Clone, Debug, PartialEq)]
+pub struct RawOption {
+    pub number: u16,
+    pub bytes: Vec<u8>,
+}
+
+/// Why part of a message was not decoded.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Diagnostic {
+    /// The options could not be parsed from this point, the rest of the message is ignored.
+    EncodedOption(encoded_option::Error),
+    /// The number of an option could not be decoded, it and the options after it are ignored.
+    DecodedOption(decoded_option::Error),
+    /// The option was kept as a [`RawOption`].
+    Option(Number, option::Error),
+    Payload(payload::Error),
+}
+
+impl LossyMessage {
+    /// Fails only if the header or token can't be decoded.
+    pub fn decode(bytes: &[u8]) -> Result<Self, Error> {
+        let (bytes, header) = Header::parse(bytes)?;
+        let (bytes, token) = Token::parse(header.token_length(), bytes)?;
+        let (bytes, encoded_options) =
+            many0(EncodedOption::parse)(bytes).unwrap_or((bytes, vec![]));
+
+        let mut diagnostics = vec![];
+
+        let (decoded_options, error) = Self::decode_options(&encoded_options);
+        diagnostics.extend(error.map(Diagnostic::DecodedOption));
+
+        let (options, rest) = Options::decode_lossy(decoded_options);
+        let mut raw_options = vec![];
+        for (option, error) in rest {
+            if let Some(error) = error {
+                diagnostics.push(Diagnostic::Option(option.number, error));
+            }
+
+            raw_options.extend(option.values.into_iter().map(|value| RawOption {
+                number: option.number.value.value(),
+                bytes: value.encode(),
+            }));
+        }
+
+        let payload = match bytes.first() {
+            Some(&payload::MARKER) | None => Payload::decode(bytes).unwrap_or_else(|error| {
+                diagnostics.push(Diagnostic::Payload(error));
+                Payload::empty()
+            }),
+            Some(_) => {
+                if let Err(error) = EncodedOption::parse(bytes) {
+                    diagnostics.push(Diagnostic::EncodedOption(error));
+                }
+                Payload::empty()
+            }
+        };
+
+        Ok(Self {
+            header,
+            token,
+            options,
+            raw_options,
+            payload,
+            diagnostics,
+        })
+    }
+
+    fn decode_options(
+        mut input: &[EncodedOption],
+    ) -> (Vec<DecodedOption>, Option<decoded_option::Error>) {
+        let mut options = vec![];
+        let mut delta_sum = Delta::from_value(0);
+
+        while !input.is_empty() {
+            match DecodedOption::parse(delta_sum, input) {
+                Ok((rest, option)) => {
+                    input = rest;
+                    delta_sum = option.number.value;
+                    options.push(option);
+                }
+                Err(error) => return (options, Some(error)),
+            }
+        }
+
+        (options, None)
+    }
+}
+
+impl std::fmt::Display for Diagnostic {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Diagnostic::EncodedOption(error) => write!(f, "invalid encoded option: {error}"),
+            Diagnostic::DecodedOption(error) => write!(f, "invalid option number: {error}"),
+            Diagnostic::Option(number, error) => write!(f, "invalid option {number}: {error}"),
+            Diagnostic::Payload(error) => write!(f, "invalid payload: {error}"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+    use rstest::rstest;
+
+    use crate::codec::{
+        option::{self, Number, UriPath},
+        Payload,
+    };
+
+    use super::{Diagnostic, LossyMessage, RawOption};
+
+    #[rstest]
+    fn decode_keeps_unknown_critical_option() {
+        // GET with Uri-Path "a", critical option 2049 and a payload
+        let bytes = [
+            0x40, 0x01, 0x00, 0x01, 0xb1, b'a', 0xe1, 0x06, 0xe9, 0x7f, 0xff, b'x',
+        ];
+
+        let message = LossyMessage::decode(&bytes).unwrap();
+
+        assert_eq!(
+            Some(&UriPath::from_value("a").unwrap()),
+            message.options.uri_path()
+        );
+        assert_eq!(
+            vec![RawOption {
+                number: 2049,
+                bytes: vec![0x7f]
+            }],
+            message.raw_options
+        );
+        assert_eq!(Payload::from_value(b"x".to_vec()), message.payload);
+        assert_eq!(
+            vec![Diagnostic::Option(
+                Number::from_value_or_panic(2049),
+                option::Error::Unrecognized(Number::from_value_or_panic(2049))
+            )],
+            message.diagnostics
+        );
+    }
+
+    #[rstest]
+    fn decode_fails_on_header() {
+        assert!(LossyMessage::decode(&[0x40, 0x01]).is_err());
+    }
+}
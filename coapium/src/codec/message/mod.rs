@@ -1,10 +1,13 @@
 pub mod acknowledgement;
 pub mod delete;
 pub mod delete_options;
+pub mod dissection;
 pub mod get;
 pub mod get_options;
+pub mod lossy;
 pub mod method;
 pub mod piggyback;
+pub mod ping;
 pub mod post;
 pub mod post_options;
 pub mod put;
@@ -18,10 +21,13 @@ pub mod response;
 pub use acknowledgement::Acknowledgement;
 pub use delete::Delete;
 pub use delete_options::DeleteOptions;
+pub use dissection::Dissection;
 pub use get::Get;
 pub use get_options::GetOptions;
+pub use lossy::LossyMessage;
 pub use method::Method;
 pub use piggyback::Piggyback;
+pub use ping::Ping;
 pub use post::Post;
 pub use post_options::PostOptions;
 pub use put::Put;
@@ -42,12 +48,25 @@ use crate::codec::{
 pub enum Message {
     Acknowledgement(Acknowledgement),
     Piggyback(Piggyback),
+    Ping(Ping),
     Request(Request),
     Reset(Reset),
     Response(Response),
     Reserved(Reserved),
 }
 
+/// How to decode messages that deviate from RFC 7252 in ways that leave their meaning intact.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub enum Leniency {
+    /// Reject such messages, e.g. a GET with a payload fails with
+    /// [`get::Error::ResidualData`].
+    #[default]
+    Strict,
+    /// Accept such messages, e.g. a GET with a payload decodes with the payload dropped and
+    /// [`Get::has_ignored_payload`] set.
+    Tolerant,
+}
+
 #[derive(Clone, Copy, Debug, PartialEq)]
 pub enum FormatError {
     TokenLengthNonZero,
@@ -68,16 +87,43 @@ pub enum Error {
     Token(token::Error),
     TokenLength(token_length::Error),
     Header(header::Error),
+    Request(request::Error),
 }
 
 impl Message {
     pub fn decode(bytes: &[u8]) -> Result<Self, Error> {
+        Self::decode_with_leniency(bytes, Leniency::Strict)
+    }
+
+    /// Decodes as much of the message as possible, see [`LossyMessage`].
+    pub fn decode_lossy(bytes: &[u8]) -> Result<LossyMessage, Error> {
+        LossyMessage::decode(bytes)
+    }
+
+    /// Takes the message apart field by field, however malformed it is, see [`Dissection`].
+    pub fn dissect(bytes: &[u8]) -> Dissection {
+        Dissection::dissect(bytes)
+    }
+
+    pub fn encode(self) -> Vec<u8> {
+        match self {
+            Message::Acknowledgement(acknowledgement) => acknowledgement.encode(),
+            Message::Piggyback(piggyback) => piggyback.encode(),
+            Message::Ping(ping) => ping.encode(),
+            Message::Request(request) => request.encode(),
+            Message::Reset(reset) => reset.encode(),
+            Message::Response(response) => response.encode(),
+            Message::Reserved(reserved) => reserved.encode(),
+        }
+    }
+
+    pub fn decode_with_leniency(bytes: &[u8], leniency: Leniency) -> Result<Self, Error> {
         let (bytes, header) = Header::parse(bytes)?;
 
         match header.message_type() {
             MessageType::Acknowledgement => Self::decode_acknowledgement(header, bytes),
-            MessageType::Confirmable => Self::decode_confirmable(header, bytes),
-            MessageType::NonConfirmable => Self::decode_non_confirmable(header, bytes),
+            MessageType::Confirmable => Self::decode_confirmable(header, bytes, leniency),
+            MessageType::NonConfirmable => Self::decode_non_confirmable(header, bytes, leniency),
             MessageType::Reset => Self::decode_reset(header, bytes),
         }
     }
@@ -98,12 +144,17 @@ impl Message {
         }
     }
 
-    fn decode_confirmable(header: Header, bytes: &[u8]) -> Result<Self, Error> {
+    fn decode_confirmable(header: Header, bytes: &[u8], leniency: Leniency) -> Result<Self, Error> {
         match header.code() {
-            Code::Request(method_code) => {
-                Request::decode(header, method_code, Reliability::Confirmable, bytes)
-                    .map(Self::Request)
-            }
+            Code::Request(method_code) => Request::decode(
+                header,
+                method_code,
+                Reliability::Confirmable,
+                bytes,
+                leniency,
+            )
+            .map(Self::Request)
+            .map_err(Into::into),
             Code::Response(response_code) => Response::decode(
                 Reliability::Confirmable,
                 header.token_length(),
@@ -120,19 +171,27 @@ impl Message {
                 bytes,
             )
             .map(Self::Reserved),
-            code => Err(Error::Format(FormatError::InvalidTypeAndCode(
-                MessageType::Confirmable,
-                code,
-            ))),
+            Code::Empty => {
+                Ping::decode(header.message_id(), header.token_length(), bytes).map(Self::Ping)
+            }
         }
     }
 
-    fn decode_non_confirmable(header: Header, bytes: &[u8]) -> Result<Self, Error> {
+    fn decode_non_confirmable(
+        header: Header,
+        bytes: &[u8],
+        leniency: Leniency,
+    ) -> Result<Self, Error> {
         match header.code() {
-            Code::Request(method_code) => {
-                Request::decode(header, method_code, Reliability::NonConfirmable, bytes)
-                    .map(Self::Request)
-            }
+            Code::Request(method_code) => Request::decode(
+                header,
+                method_code,
+                Reliability::NonConfirmable,
+                bytes,
+                leniency,
+            )
+            .map(Self::Request)
+            .map_err(Into::into),
             Code::Response(response_code) => Response::decode(
                 Reliability::NonConfirmable,
                 header.token_length(),
@@ -211,6 +270,12 @@ impl From<payload::Error> for Error {
     }
 }
 
+impl From<request::Error> for Error {
+    fn from(value: request::Error) -> Self {
+        Self::Request(value)
+    }
+}
+
 impl From<token::Error> for Error {
     fn from(value: token::Error) -> Self {
         Self::Token(value)
@@ -222,3 +287,58 @@ impl From<token_length::Error> for Error {
         Self::TokenLength(value)
     }
 }
+
+impl std::fmt::Display for FormatError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            FormatError::TokenLengthNonZero => {
+                write!(f, "empty message must have a zero length token")
+            }
+            FormatError::ExcessiveData => {
+                write!(f, "empty message must not contain data after the header")
+            }
+            FormatError::InvalidTypeAndCode(message_type, code) => {
+                write!(f, "code {code} is not valid in a {message_type} message")
+            }
+        }
+    }
+}
+
+impl std::error::Error for FormatError {}
+
+impl std::fmt::Display for Error {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Error::Version(error) => write!(f, "invalid message: {error}"),
+            Error::Format(error) => write!(f, "invalid message format: {error}"),
+            Error::HeaderMissing => write!(f, "message header is missing"),
+            Error::DataLength => write!(f, "message is too short"),
+            Error::EncodedOption(error) => write!(f, "invalid encoded option: {error}"),
+            Error::Option(error) => write!(f, "invalid option: {error}"),
+            Error::Options(error) => write!(f, "invalid options: {error}"),
+            Error::Payload(error) => write!(f, "invalid payload: {error}"),
+            Error::Token(error) => write!(f, "invalid token: {error}"),
+            Error::TokenLength(error) => write!(f, "invalid token length: {error}"),
+            Error::Header(error) => write!(f, "invalid header: {error}"),
+            Error::Request(error) => write!(f, "invalid request: {error}"),
+        }
+    }
+}
+
+impl std::error::Error for Error {}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+    use rstest::rstest;
+
+    use super::Message;
+
+    #[rstest]
+    #[case(&[0b01_11_0000, 0b000_00001, 0, 1], "invalid message format: code 0.01 is not valid in a RST message")]
+    #[case(&[0b01_10_0001, 0b000_00000, 0, 1, 9], "invalid message format: empty message must have a zero length token")]
+    #[case(&[0b01_00_0000, 0b010_00101], "invalid header: header requires 4 bytes")]
+    fn decode_error_display(#[case] bytes: &[u8], #[case] expected: &str) {
+        assert_eq!(expected, Message::decode(bytes).unwrap_err().to_string())
+    }
+}
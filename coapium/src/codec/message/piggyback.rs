@@ -45,6 +45,10 @@ impl Piggyback {
         .collect()
     }
 
+    pub fn message_id(&self) -> MessageId {
+        self.message_id
+    }
+
     pub fn new(
         token: Token,
         response_code: ResponseCode,
@@ -60,6 +64,10 @@ impl Piggyback {
             payload,
         }
     }
+
+    pub fn token(&self) -> &Token {
+        &self.token
+    }
 }
 
 impl From<Piggyback> for Response {
@@ -0,0 +1,78 @@
+use crate::codec::{Code, Header, MessageId, MessageType, Token, TokenLength};
+
+use super::{Error, FormatError};
+
+/// Empty confirmable message, which a peer answers with a reset to show it is alive.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Ping {
+    message_id: MessageId,
+}
+
+impl Ping {
+    pub fn decode(
+        message_id: MessageId,
+        token_length: TokenLength,
+        remaining_bytes: &[u8],
+    ) -> Result<Self, Error> {
+        if !token_length.is_zero_length() {
+            return Err(Error::Format(FormatError::TokenLengthNonZero));
+        }
+
+        if !remaining_bytes.is_empty() {
+            return Err(Error::Format(FormatError::ExcessiveData));
+        }
+
+        Ok(Self { message_id })
+    }
+
+    pub fn encode(self) -> Vec<u8> {
+        let (token_length, _) = Token::empty().encode();
+        Header::new(
+            MessageType::Confirmable,
+            token_length,
+            Code::Empty,
+            self.message_id,
+        )
+        .encode()
+    }
+
+    pub fn from_message_id(message_id: MessageId) -> Self {
+        Self { message_id }
+    }
+
+    pub fn message_id(&self) -> MessageId {
+        self.message_id
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+    use rstest::rstest;
+
+    use super::{Error, FormatError, MessageId, Ping, TokenLength};
+
+    #[rstest]
+    #[case(MessageId::from_value(10), TokenLength::from_value(0).unwrap(), &[], Ok(Ping {message_id: MessageId::from_value(10)}))]
+    #[case(MessageId::from_value(10), TokenLength::from_value(1).unwrap(), &[3], Err(Error::Format(FormatError::TokenLengthNonZero)))]
+    #[case(MessageId::from_value(10), TokenLength::from_value(0).unwrap(), &[0xff, 1, 2, 3 ], Err(Error::Format(FormatError::ExcessiveData)))]
+    fn decode(
+        #[case] message_id: MessageId,
+        #[case] token_length: TokenLength,
+        #[case] remaining_bytes: &[u8],
+        #[case] expected: Result<Ping, Error>,
+    ) {
+        assert_eq!(
+            expected,
+            Ping::decode(message_id, token_length, remaining_bytes)
+        )
+    }
+
+    #[rstest]
+    fn encode() {
+        assert_eq!(
+            vec![0x40, 0x00, 0x7d, 0x34],
+            Ping::from_message_id(MessageId::from_value(0x7d34)).encode()
+        )
+    }
+}
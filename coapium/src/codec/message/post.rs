@@ -78,6 +78,10 @@ impl Post {
         &self.options
     }
 
+    pub fn payload(&self) -> &Payload {
+        &self.payload
+    }
+
     pub fn reliability(&self) -> Reliability {
         self.reliability
     }
@@ -105,6 +109,18 @@ impl From<post_options::Error> for Error {
     }
 }
 
+impl std::fmt::Display for Error {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Error::Token(error) => write!(f, "invalid POST token: {error}"),
+            Error::Options(error) => write!(f, "invalid POST options: {error}"),
+            Error::Payload(error) => write!(f, "invalid POST payload: {error}"),
+        }
+    }
+}
+
+impl std::error::Error for Error {}
+
 #[cfg(test)]
 mod tests {
     use pretty_assertions::assert_eq;
@@ -1,7 +1,10 @@
-use crate::codec::option::{ContentFormat, UriHost, UriPath, UriPort, UriQuery};
+use crate::codec::option::{
+    Accept, Block1, ContentFormat, Echo, QBlock1, RequestTag, UriHost, UriPath, UriPort, UriQuery,
+};
 use crate::codec::options;
 use crate::codec::{option::Number, Options};
 
+/// Options of a POST request, only options valid for POST can be set.
 #[derive(Clone, Debug, PartialEq)]
 pub struct PostOptions {
     options: Options,
@@ -18,6 +21,10 @@ impl PostOptions {
         self.options.encode()
     }
 
+    pub fn content_format(&self) -> std::option::Option<&ContentFormat> {
+        self.options.content_format()
+    }
+
     pub fn from_options(options: Options) -> Result<Self, Error> {
         if let Some(option) = options
             .options()
@@ -44,7 +51,10 @@ impl PostOptions {
 
     fn recognized_options() -> Vec<Number> {
         vec![
+            Accept::number(),
+            Block1::number(),
             ContentFormat::number(),
+            QBlock1::number(),
             UriHost::number(),
             UriPath::number(),
             UriPort::number(),
@@ -52,6 +62,46 @@ impl PostOptions {
         ]
     }
 
+    pub fn block1(&self) -> std::option::Option<&Block1> {
+        self.options.block1()
+    }
+
+    pub fn echo(&self) -> std::option::Option<&Echo> {
+        self.options.echo()
+    }
+
+    pub fn q_block1(&self) -> std::option::Option<&QBlock1> {
+        self.options.q_block1()
+    }
+
+    pub fn request_tag(&self) -> std::option::Option<&RequestTag> {
+        self.options.request_tag()
+    }
+
+    pub fn set_block1(&mut self, block1: Block1) {
+        self.options.set_block1(block1)
+    }
+
+    pub fn set_echo(&mut self, echo: Echo) {
+        self.options.set_echo(echo)
+    }
+
+    pub fn set_q_block1(&mut self, q_block1: QBlock1) {
+        self.options.set_q_block1(q_block1)
+    }
+
+    pub fn set_request_tag(&mut self, request_tag: RequestTag) {
+        self.options.set_request_tag(request_tag)
+    }
+
+    pub fn accept(&self) -> std::option::Option<&Accept> {
+        self.options.accept()
+    }
+
+    pub fn set_accept(&mut self, accept: Accept) {
+        self.options.set_accept(accept)
+    }
+
     pub fn set_content_format(&mut self, host: ContentFormat) {
         self.options.set_content_format(host)
     }
@@ -71,6 +121,31 @@ impl PostOptions {
     pub fn set_uri_query(&mut self, path: UriQuery) {
         self.options.set_uri_query(path)
     }
+
+    pub fn remove_content_format(&mut self) {
+        self.options.remove_content_format()
+    }
+
+    /// Removes every option with `number`.
+    pub fn remove(&mut self, number: Number) {
+        self.options.remove(number)
+    }
+
+    pub fn remove_uri_query(&mut self) {
+        self.options.remove_uri_query()
+    }
+
+    pub fn clear(&mut self) {
+        self.options.clear()
+    }
+
+    pub fn uri_path(&self) -> std::option::Option<&UriPath> {
+        self.options.uri_path()
+    }
+
+    pub fn uri_query(&self) -> std::option::Option<&UriQuery> {
+        self.options.uri_query()
+    }
 }
 
 impl From<options::Error> for Error {
@@ -79,6 +154,19 @@ impl From<options::Error> for Error {
     }
 }
 
+impl std::fmt::Display for Error {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Error::Options(error) => error.fmt(f),
+            Error::Unrecognized(number) => {
+                write!(f, "critical option {number} is not recognized for POST")
+            }
+        }
+    }
+}
+
+impl std::error::Error for Error {}
+
 #[cfg(test)]
 mod tests {
 
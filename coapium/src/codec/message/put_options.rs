@@ -1,7 +1,11 @@
-use crate::codec::option::{ContentFormat, UriHost, UriPath, UriPort, UriQuery};
+use crate::codec::option::{
+    Accept, Block1, ContentFormat, Echo, IfMatch, IfNoneMatch, QBlock1, RequestTag, UriHost,
+    UriPath, UriPort, UriQuery,
+};
 use crate::codec::options;
 use crate::codec::{option::Number, Options};
 
+/// Options of a PUT request, only options valid for PUT can be set.
 #[derive(Clone, Debug, PartialEq)]
 pub struct PutOptions {
     options: Options,
@@ -18,6 +22,10 @@ impl PutOptions {
         self.options.encode()
     }
 
+    pub fn content_format(&self) -> std::option::Option<&ContentFormat> {
+        self.options.content_format()
+    }
+
     pub fn from_options(options: Options) -> Result<Self, Error> {
         if let Some(option) = options
             .options()
@@ -44,7 +52,12 @@ impl PutOptions {
 
     fn recognized_options() -> Vec<Number> {
         vec![
+            Accept::number(),
+            Block1::number(),
             ContentFormat::number(),
+            IfMatch::number(),
+            IfNoneMatch::number(),
+            QBlock1::number(),
             UriHost::number(),
             UriPath::number(),
             UriPort::number(),
@@ -52,10 +65,58 @@ impl PutOptions {
         ]
     }
 
+    pub fn block1(&self) -> std::option::Option<&Block1> {
+        self.options.block1()
+    }
+
+    pub fn echo(&self) -> std::option::Option<&Echo> {
+        self.options.echo()
+    }
+
+    pub fn q_block1(&self) -> std::option::Option<&QBlock1> {
+        self.options.q_block1()
+    }
+
+    pub fn request_tag(&self) -> std::option::Option<&RequestTag> {
+        self.options.request_tag()
+    }
+
+    pub fn set_block1(&mut self, block1: Block1) {
+        self.options.set_block1(block1)
+    }
+
+    pub fn set_echo(&mut self, echo: Echo) {
+        self.options.set_echo(echo)
+    }
+
+    pub fn set_q_block1(&mut self, q_block1: QBlock1) {
+        self.options.set_q_block1(q_block1)
+    }
+
+    pub fn set_request_tag(&mut self, request_tag: RequestTag) {
+        self.options.set_request_tag(request_tag)
+    }
+
+    pub fn accept(&self) -> std::option::Option<&Accept> {
+        self.options.accept()
+    }
+
+    pub fn set_accept(&mut self, accept: Accept) {
+        self.options.set_accept(accept)
+    }
+
     pub fn set_content_format(&mut self, host: ContentFormat) {
         self.options.set_content_format(host)
     }
 
+    pub fn set_if_match(&mut self, if_match: IfMatch) {
+        self.options.set_if_match(if_match)
+    }
+
+    pub fn set_if_none_match(&mut self, if_none_match: IfNoneMatch) {
+        self.options.set_if_none_match(if_none_match)
+    }
+
     pub fn set_uri_host(&mut self, host: UriHost) {
         self.options.set_uri_host(host)
     }
@@ -71,6 +132,31 @@ impl PutOptions {
     pub fn set_uri_query(&mut self, path: UriQuery) {
         self.options.set_uri_query(path)
     }
+
+    pub fn remove_content_format(&mut self) {
+        self.options.remove_content_format()
+    }
+
+    /// Removes every option with `number`.
+    pub fn remove(&mut self, number: Number) {
+        self.options.remove(number)
+    }
+
+    pub fn remove_uri_query(&mut self) {
+        self.options.remove_uri_query()
+    }
+
+    pub fn clear(&mut self) {
+        self.options.clear()
+    }
+
+    pub fn uri_path(&self) -> std::option::Option<&UriPath> {
+        self.options.uri_path()
+    }
+
+    pub fn uri_query(&self) -> std::option::Option<&UriQuery> {
+        self.options.uri_query()
+    }
 }
 
 impl From<options::Error> for Error {
@@ -79,15 +165,52 @@ impl From<options::Error> for Error {
     }
 }
 
+impl std::fmt::Display for Error {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Error::Options(error) => error.fmt(f),
+            Error::Unrecognized(number) => {
+                write!(f, "critical option {number} is not recognized for PUT")
+            }
+        }
+    }
+}
+
+impl std::error::Error for Error {}
+
 #[cfg(test)]
 mod tests {
 
     use pretty_assertions::assert_eq;
     use rstest::rstest;
 
-    use super::{ContentFormat, Options, PutOptions, UriHost, UriPath, UriPort, UriQuery};
+    use super::{
+        ContentFormat, IfNoneMatch, Options, PutOptions, UriHost, UriPath, UriPort, UriQuery,
+    };
     use crate::codec::MediaType;
 
+    #[rstest]
+    fn set_if_none_match() {
+        let mut put_options = PutOptions::new();
+        put_options.set_if_none_match(IfNoneMatch);
+
+        assert_eq!(vec![0b0101_0000], put_options.encode());
+    }
+
+    #[rstest]
+    fn parse_recognizes_if_match() {
+        let bytes = [0b0001_0001, 7];
+
+        assert_eq!(
+            Ok((&[][..], {
+                let mut put_options = PutOptions::new();
+                put_options.set_if_match(vec![vec![7]].try_into().unwrap());
+                put_options
+            })),
+            PutOptions::parse(&bytes)
+        );
+    }
+
     #[rstest]
     #[case(
         PutOptions { options: Options::new() }, 
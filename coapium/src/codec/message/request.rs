@@ -1,44 +1,161 @@
-use crate::codec::{Header, MethodCode};
+use crate::codec::{
+    option::{Accept, UriPath},
+    Code, Header, MessageId, MethodCode, Token,
+};
 
-use super::{get::Get, Error, Reliability};
+use super::{delete, get, post, put, Delete, Get, Leniency, Post, Put, Reliability};
 
 #[derive(Clone, Debug, PartialEq)]
 pub enum Request {
     Get(Get),
-    Post(()),
-    Put(()),
-    Delete(()),
+    Post(Post),
+    Put(Put),
+    Delete(Delete),
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Error {
+    Get(get::Error),
+    Post(post::Error),
+    Put(put::Error),
+    Delete(delete::Error),
+    UnassignedMethod(MethodCode),
 }
 
 impl Request {
     pub fn encode(self) -> Vec<u8> {
         match self {
             Request::Get(get) => get.encode(),
-            Request::Post(_) => todo!(),
-            Request::Put(_) => todo!(),
-            Request::Delete(_) => todo!(),
+            Request::Post(post) => post.encode(),
+            Request::Put(put) => put.encode(),
+            Request::Delete(delete) => delete.encode(),
         }
     }
 
     pub fn decode(
-        _header: Header,
-        _method_code: MethodCode,
-        _reliability: Reliability,
-        _remaining_bytes: &[u8],
+        header: Header,
+        method_code: MethodCode,
+        reliability: Reliability,
+        remaining_bytes: &[u8],
+        leniency: Leniency,
     ) -> Result<Self, Error> {
-        todo!()
+        let message_id = header.message_id();
+        let token_length = header.token_length();
+
+        match method_code {
+            MethodCode::Get => Get::decode(
+                message_id,
+                token_length,
+                reliability,
+                remaining_bytes,
+                leniency,
+            )
+            .map(Self::Get)
+            .map_err(Error::Get),
+            MethodCode::Post => {
+                Post::decode(message_id, token_length, reliability, remaining_bytes)
+                    .map(Self::Post)
+                    .map_err(Error::Post)
+            }
+            MethodCode::Put => Put::decode(message_id, token_length, reliability, remaining_bytes)
+                .map(Self::Put)
+                .map_err(Error::Put),
+            MethodCode::Delete => Delete::decode(
+                message_id,
+                token_length,
+                reliability,
+                remaining_bytes,
+                leniency,
+            )
+            .map(Self::Delete)
+            .map_err(Error::Delete),
+            MethodCode::Unassigned(_) => Err(Error::UnassignedMethod(method_code)),
+        }
+    }
+
+    pub fn message_id(&self) -> MessageId {
+        match self {
+            Request::Get(get) => get.message_id(),
+            Request::Post(post) => post.message_id(),
+            Request::Put(put) => put.message_id(),
+            Request::Delete(delete) => delete.message_id(),
+        }
+    }
+
+    pub fn method_code(&self) -> MethodCode {
+        match self {
+            Request::Get(_) => MethodCode::Get,
+            Request::Post(_) => MethodCode::Post,
+            Request::Put(_) => MethodCode::Put,
+            Request::Delete(_) => MethodCode::Delete,
+        }
+    }
+
+    pub fn reliability(&self) -> Reliability {
+        match self {
+            Request::Get(get) => get.reliability(),
+            Request::Post(post) => post.reliability(),
+            Request::Put(put) => put.reliability(),
+            Request::Delete(delete) => delete.reliability(),
+        }
+    }
+
+    pub fn token(&self) -> &Token {
+        match self {
+            Request::Get(get) => get.token(),
+            Request::Post(post) => post.token(),
+            Request::Put(put) => put.token(),
+            Request::Delete(delete) => delete.token(),
+        }
+    }
+
+    /// Content format the request asked for, DELETE can't ask for one.
+    pub fn accept(&self) -> Option<&Accept> {
+        match self {
+            Request::Get(get) => get.options().accept(),
+            Request::Post(post) => post.options().accept(),
+            Request::Put(put) => put.options().accept(),
+            Request::Delete(_) => None,
+        }
+    }
+
+    pub fn uri_path(&self) -> Option<&UriPath> {
+        match self {
+            Request::Get(get) => get.options().uri_path(),
+            Request::Post(post) => post.options().uri_path(),
+            Request::Put(put) => put.options().uri_path(),
+            Request::Delete(delete) => delete.options().uri_path(),
+        }
+    }
+}
+
+impl std::fmt::Display for Error {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Error::Get(error) => error.fmt(f),
+            Error::Post(error) => error.fmt(f),
+            Error::Put(error) => error.fmt(f),
+            Error::Delete(error) => error.fmt(f),
+            Error::UnassignedMethod(method_code) => {
+                write!(f, "unassigned method {}", Code::Request(*method_code))
+            }
+        }
     }
 }
 
+impl std::error::Error for Error {}
+
 #[cfg(test)]
 mod tests {
 
     use pretty_assertions::assert_eq;
     use rstest::rstest;
 
-    use crate::codec::{message::get_options::GetOptions, MessageId, Token};
+    use crate::codec::{
+        message::get_options::GetOptions, Code, Header, MessageId, MessageType, Token,
+    };
 
-    use super::{Get, Reliability, Request};
+    use super::{Error, Get, Leniency, Reliability, Request};
 
     #[rstest]
     #[case(
@@ -59,4 +176,51 @@ mod tests {
     fn encode(#[case] request: Request, #[case] expected: &[u8]) {
         assert_eq!(expected, request.encode())
     }
+
+    #[rstest]
+    #[case(&[0b01_00_0011, 0b000_00001, 0, 3, 1, 2, 3, 0b1011_0011, 97, 98, 99])]
+    #[case(&[0b01_01_0000, 0b000_00010, 0, 4, 0xff, 1])]
+    #[case(&[0b01_00_0001, 0b000_00011, 0, 5, 9, 0xff, 1, 2])]
+    #[case(&[0b01_01_0000, 0b000_00100, 0, 6])]
+    fn decode_then_encode(#[case] bytes: &[u8]) {
+        let (remaining_bytes, header) = Header::parse(bytes).unwrap();
+        let Code::Request(method_code) = header.code() else {
+            panic!("Expected a request code");
+        };
+        let reliability = match header.message_type() {
+            MessageType::Confirmable => Reliability::Confirmable,
+            _ => Reliability::NonConfirmable,
+        };
+
+        let request = Request::decode(
+            header,
+            method_code,
+            reliability,
+            remaining_bytes,
+            Leniency::Strict,
+        )
+        .unwrap();
+
+        assert_eq!(bytes, request.encode())
+    }
+
+    #[rstest]
+    fn decode_unassigned_method() {
+        let bytes = [0b01_00_0000, 0b000_11111, 0, 1];
+        let (_, header) = Header::parse(&bytes).unwrap();
+        let Code::Request(method_code) = header.code() else {
+            panic!("Expected a request code");
+        };
+
+        assert_eq!(
+            Err(Error::UnassignedMethod(method_code)),
+            Request::decode(
+                header,
+                method_code,
+                Reliability::Confirmable,
+                &[],
+                Leniency::Strict
+            )
+        );
+    }
 }
@@ -1,5 +1,6 @@
 use crate::codec::{
-    code::reserved_code::ReservedCode, MessageId, Options, Payload, Token, TokenLength,
+    code::reserved_code::ReservedCode, Code, Header, MessageId, Options, Payload, Token,
+    TokenLength,
 };
 
 use super::{Error, Reliability};
@@ -15,6 +16,10 @@ pub struct Reserved {
 }
 
 impl Reserved {
+    pub fn code(&self) -> ReservedCode {
+        self.code
+    }
+
     pub fn decode(
         reliability: Reliability,
         token_length: TokenLength,
@@ -37,6 +42,43 @@ impl Reserved {
             payload,
         })
     }
+
+    pub fn encode(self) -> Vec<u8> {
+        let (token_length, encoded_token) = self.token.encode();
+
+        Header::new(
+            self.reliability.into(),
+            token_length,
+            Code::Reserved(self.code),
+            self.message_id,
+        )
+        .encode()
+        .into_iter()
+        .chain(encoded_token)
+        .chain(self.options.encode())
+        .chain(self.payload.encode())
+        .collect()
+    }
+
+    pub fn message_id(&self) -> MessageId {
+        self.message_id
+    }
+
+    pub fn options(&self) -> &Options {
+        &self.options
+    }
+
+    pub fn payload(&self) -> &Payload {
+        &self.payload
+    }
+
+    pub fn reliability(&self) -> Reliability {
+        self.reliability
+    }
+
+    pub fn token(&self) -> &Token {
+        &self.token
+    }
 }
 
 #[cfg(test)]
@@ -1,4 +1,8 @@
-use crate::codec::{Header, MessageId, Options, Payload, ResponseCode, Token, TokenLength};
+use crate::codec::{
+    code::response_code::{ClientError, ServerError, Success},
+    option::{ContentFormat, LocationPath, Number},
+    Header, MediaType, MessageId, Options, Payload, ResponseCode, Token, TokenLength,
+};
 
 use super::{Error, Reliability};
 
@@ -13,6 +17,42 @@ pub struct Response {
 }
 
 impl Response {
+    /// 4.02 (Bad Option) with a diagnostic payload listing the options of the request the server
+    /// didn't recognize, e.g. `Unrecognized options: 11 (Uri-Path), 65001`.
+    pub fn bad_option(numbers: &[Number]) -> Self {
+        let numbers = numbers
+            .iter()
+            .map(|number| match number.name() {
+                Some(name) => format!("{} ({name})", number.value.value()),
+                None => number.value.value().to_string(),
+            })
+            .collect::<Vec<_>>()
+            .join(", ");
+
+        Self::from_response_code(ResponseCode::ClientError(ClientError::BadOption)).with_payload(
+            Payload::from_value(format!("Unrecognized options: {numbers}").into_bytes()),
+        )
+    }
+
+    pub fn bad_request() -> Self {
+        Self::from_response_code(ResponseCode::ClientError(ClientError::BadRequest))
+    }
+
+    pub fn changed() -> Self {
+        Self::from_response_code(ResponseCode::Success(Success::Changed))
+    }
+
+    pub fn content(payload: Payload) -> Self {
+        Self::from_response_code(ResponseCode::Success(Success::Content)).with_payload(payload)
+    }
+
+    pub fn created(location: LocationPath) -> Self {
+        let mut options = Options::new();
+        options.set_location_path(location);
+
+        Self::from_response_code(ResponseCode::Success(Success::Created)).with_options(options)
+    }
+
     pub fn decode(
         reliability: Reliability,
         token_length: TokenLength,
@@ -36,6 +76,17 @@ impl Response {
         })
     }
 
+    pub fn deleted() -> Self {
+        Self::from_response_code(ResponseCode::Success(Success::Deleted))
+    }
+
+    /// Human-readable text carried by a client or server error response, `None` for successful
+    /// responses, empty payloads, payloads that aren't UTF-8 and payloads tagged with a
+    /// Content-Format other than `text/plain`.
+    pub fn diagnostic(&self) -> Option<&str> {
+        diagnostic(self.response_code, &self.options, &self.payload)
+    }
+
     pub fn encode(self) -> Vec<u8> {
         let (token_length, encoded_token) = self.token.encode();
 
@@ -53,6 +104,28 @@ impl Response {
         .collect()
     }
 
+    /// Confirmable response without options or payload, with an empty token and message id 0 as
+    /// placeholders to be replaced with [`with_token`](Self::with_token) and
+    /// [`with_message_id`](Self::with_message_id) before it is sent.
+    pub fn from_response_code(response_code: ResponseCode) -> Self {
+        Self::new(
+            Reliability::Confirmable,
+            Token::empty(),
+            response_code,
+            MessageId::from_value(0),
+            Options::new(),
+            Payload::empty(),
+        )
+    }
+
+    pub fn internal_server_error() -> Self {
+        Self::from_response_code(ResponseCode::ServerError(ServerError::InternalServerError))
+    }
+
+    pub fn method_not_allowed() -> Self {
+        Self::from_response_code(ResponseCode::ClientError(ClientError::MethodNotAllowed))
+    }
+
     pub fn new(
         reliability: Reliability,
         token: Token,
@@ -75,6 +148,49 @@ impl Response {
         self.message_id
     }
 
+    pub fn not_found() -> Self {
+        Self::from_response_code(ResponseCode::ClientError(ClientError::NotFound))
+    }
+
+    pub fn not_implemented() -> Self {
+        Self::from_response_code(ResponseCode::ServerError(ServerError::NotImplemented))
+    }
+
+    pub fn valid() -> Self {
+        Self::from_response_code(ResponseCode::Success(Success::Valid))
+    }
+
+    pub fn with_content_format(mut self, content_format: ContentFormat) -> Self {
+        self.options.set_content_format(content_format);
+        self
+    }
+
+    pub fn with_message_id(mut self, message_id: MessageId) -> Self {
+        self.message_id = message_id;
+        self
+    }
+
+    /// Replaces all options, including any set by the constructor.
+    pub fn with_options(mut self, options: Options) -> Self {
+        self.options = options;
+        self
+    }
+
+    pub fn with_payload(mut self, payload: Payload) -> Self {
+        self.payload = payload;
+        self
+    }
+
+    pub fn with_reliability(mut self, reliability: Reliability) -> Self {
+        self.reliability = reliability;
+        self
+    }
+
+    pub fn with_token(mut self, token: Token) -> Self {
+        self.token = token;
+        self
+    }
+
     pub fn token(&self) -> &Token {
         &self.token
     }
@@ -96,14 +212,38 @@ impl Response {
     }
 }
 
+/// Diagnostic payload shared by the codec and the protocol responses.
+pub(crate) fn diagnostic<'a>(
+    response_code: ResponseCode,
+    options: &Options,
+    payload: &'a Payload,
+) -> Option<&'a str> {
+    if response_code.is_success() || payload.is_empty() {
+        return None;
+    }
+    if !matches!(
+        options.content_media_type(),
+        None | Some(MediaType::TextPlain)
+    ) {
+        return None;
+    }
+
+    std::str::from_utf8(payload.value()).ok()
+}
+
 #[cfg(test)]
 mod tests {
 
     use pretty_assertions::assert_eq;
     use rstest::rstest;
 
-    use super::super::super::code::response_code::{ResponseCode, Success};
-    use super::{Error, MessageId, Options, Payload, Reliability, Response, Token, TokenLength};
+    use super::super::super::code::response_code::{
+        ClientError, ResponseCode, ServerError, Success,
+    };
+    use super::{
+        Error, LocationPath, MessageId, Options, Payload, Reliability, Response, Token, TokenLength,
+    };
+    use crate::codec::{option::Number, MediaType};
 
     #[rstest]
     #[case(
@@ -331,4 +471,110 @@ mod tests {
     fn payload(#[case] response: Response, #[case] expected: Payload) {
         assert_eq!(&expected, response.payload())
     }
+
+    #[rstest]
+    fn content() {
+        let response = Response::content(Payload::from_value(vec![1, 2]))
+            .with_content_format(MediaType::ApplicationJson.into())
+            .with_token(Token::from_value(vec![7]).unwrap())
+            .with_message_id(MessageId::from_value(3))
+            .with_reliability(Reliability::NonConfirmable);
+
+        let mut options = Options::new();
+        options.set_content_format(MediaType::ApplicationJson.into());
+        assert_eq!(
+            Response::new(
+                Reliability::NonConfirmable,
+                Token::from_value(vec![7]).unwrap(),
+                ResponseCode::Success(Success::Content),
+                MessageId::from_value(3),
+                options,
+                Payload::from_value(vec![1, 2]),
+            ),
+            response
+        );
+    }
+
+    #[rstest]
+    fn created() {
+        let location = LocationPath::from_value("/rd/4521").unwrap();
+
+        let response = Response::created(location.clone());
+
+        assert_eq!(
+            ResponseCode::Success(Success::Created),
+            response.response_code()
+        );
+        assert_eq!(Some(&location), response.options().location_path());
+        assert_eq!(Some("/rd/4521".to_owned()), response.options().location());
+    }
+
+    #[rstest]
+    #[case(
+        Response::bad_request(),
+        ResponseCode::ClientError(ClientError::BadRequest)
+    )]
+    #[case(Response::changed(), ResponseCode::Success(Success::Changed))]
+    #[case(Response::deleted(), ResponseCode::Success(Success::Deleted))]
+    #[case(
+        Response::internal_server_error(),
+        ResponseCode::ServerError(ServerError::InternalServerError)
+    )]
+    #[case(
+        Response::method_not_allowed(),
+        ResponseCode::ClientError(ClientError::MethodNotAllowed)
+    )]
+    #[case(
+        Response::not_found(),
+        ResponseCode::ClientError(ClientError::NotFound)
+    )]
+    #[case(
+        Response::not_implemented(),
+        ResponseCode::ServerError(ServerError::NotImplemented)
+    )]
+    #[case(Response::valid(), ResponseCode::Success(Success::Valid))]
+    fn without_payload(#[case] response: Response, #[case] expected: ResponseCode) {
+        assert_eq!(expected, response.response_code());
+        assert_eq!(Reliability::Confirmable, response.reliability());
+        assert!(response.token().is_empty());
+        assert!(response.payload().is_empty());
+    }
+
+    #[rstest]
+    fn bad_option() {
+        let response = Response::bad_option(&[
+            Number::from_value_or_panic(11),
+            Number::from_value_or_panic(65001),
+        ]);
+
+        assert_eq!(
+            ResponseCode::ClientError(ClientError::BadOption),
+            response.response_code()
+        );
+        assert_eq!(
+            Some("Unrecognized options: 11 (Uri-Path), 65001"),
+            response.diagnostic()
+        );
+    }
+
+    #[rstest]
+    #[case(Response::not_found().with_payload(Payload::from_value(b"no /a".to_vec())), Some("no /a"))]
+    #[case(
+        Response::not_found()
+            .with_payload(Payload::from_value(b"no /a".to_vec()))
+            .with_content_format(MediaType::TextPlain.into()),
+        Some("no /a")
+    )]
+    #[case(
+        Response::not_found()
+            .with_payload(Payload::from_value(b"{}".to_vec()))
+            .with_content_format(MediaType::ApplicationJson.into()),
+        None
+    )]
+    #[case(Response::not_found().with_payload(Payload::from_value(vec![0xff])), None)]
+    #[case(Response::not_found(), None)]
+    #[case(Response::content(Payload::from_value(b"a".to_vec())), None)]
+    fn diagnostic(#[case] response: Response, #[case] expected: Option<&str>) {
+        assert_eq!(expected, response.diagnostic());
+    }
 }
@@ -114,6 +114,17 @@ impl MessageType {
     }
 }
 
+impl std::fmt::Display for MessageType {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Acknowledgement => f.write_str("ACK"),
+            Confirmable => f.write_str("CON"),
+            NonConfirmable => f.write_str("NON"),
+            Reset => f.write_str("RST"),
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
 
@@ -1,7 +1,10 @@
 mod parsing;
 
+#[cfg(any(test, feature = "test-util"))]
+pub mod arbitrary;
 pub mod code;
 pub mod header;
+pub mod link_format;
 pub mod media_type;
 pub mod message;
 pub mod message_id;
@@ -9,8 +12,14 @@ pub mod message_type;
 pub mod option;
 pub mod options;
 pub mod payload;
+mod percent;
+#[cfg(feature = "senml")]
+pub mod senml;
+#[cfg(any(test, feature = "test-util"))]
+pub mod test_vectors;
 pub mod token;
 pub mod token_length;
+#[cfg(feature = "url")]
 pub mod url;
 pub mod version;
 
@@ -1,6 +1,11 @@
 use crate::codec::{parsing::single, MediaType};
 
-use super::{decoded_option::DecodedOption, number::Number, value::Value, Delta};
+use super::{
+    decoded_option::{self, DecodedOption},
+    number::Number,
+    value::Value,
+    Delta,
+};
 
 #[derive(Clone, Debug, PartialEq)]
 pub struct Accept {
@@ -11,6 +16,8 @@ pub struct Accept {
 pub enum Error {
     SingleValue,
     Format,
+    /// The media type has no Content-Format number to ask for, like [`MediaType::CharsetUtf8`].
+    Unnumbered,
 }
 
 impl Accept {
@@ -34,11 +41,61 @@ impl Accept {
         .encode(delta_sum)
     }
 
+    /// Size of the option once encoded, see [`Accept::encode`].
+    pub fn encoded_size(&self, delta_sum: Delta) -> usize {
+        decoded_option::encoded_size(Self::number(), delta_sum, [&self.value])
+    }
+
     pub fn number() -> Number {
         Number::from_value_or_panic(17)
     }
+
+    pub fn media_type(&self) -> MediaType {
+        MediaType::from_value(self.value())
+    }
+
+    /// Content-Format number asked for, also for formats without a [`MediaType`] variant of their
+    /// own.
+    pub fn value(&self) -> u16 {
+        self.value
+            .clone()
+            .u16()
+            .expect("Value should have been validated as a u16")
+    }
+}
+
+impl From<u16> for Accept {
+    fn from(value: u16) -> Self {
+        Self {
+            value: Value::from_u16(value),
+        }
+    }
+}
+
+impl TryFrom<MediaType> for Accept {
+    type Error = Error;
+
+    fn try_from(media_type: MediaType) -> Result<Self, Self::Error> {
+        let value = media_type.value().ok_or(Error::Unnumbered)?;
+
+        Ok(Self {
+            value: Value::from_u16(value),
+        })
+    }
+}
+
+impl std::fmt::Display for Error {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Error::SingleValue => write!(f, "Accept(17) requires exactly one value"),
+            Error::Format => write!(f, "Accept(17) value is not a valid uint"),
+            Error::Unnumbered => write!(f, "Accept(17) requires a media type with a number"),
+        }
+    }
 }
 
+impl std::error::Error for Error {}
+
 #[cfg(test)]
 mod tests {
     use pretty_assertions::assert_eq;
@@ -70,4 +127,25 @@ mod tests {
     fn decode(#[case] values: Vec<Value>, #[case] expected: Result<Accept, Error>) {
         assert_eq!(expected, Accept::decode(values));
     }
+
+    #[rstest]
+    #[case(
+        MediaType::ApplicationJson,
+        Ok(Accept::from(MediaType::APPLICATION_JSON))
+    )]
+    #[case(MediaType::from_value(65001), Ok(Accept::from(65001)))]
+    #[case(MediaType::CharsetUtf8, Err(Error::Unnumbered))]
+    fn try_from_media_type(#[case] media_type: MediaType, #[case] expected: Result<Accept, Error>) {
+        assert_eq!(expected, Accept::try_from(media_type));
+    }
+
+    #[rstest]
+    #[case(MediaType::APPLICATION_JSON, MediaType::ApplicationJson)]
+    #[case(65001, MediaType::from_value(65001))]
+    fn from_value(#[case] value: u16, #[case] media_type: MediaType) {
+        let accept = Accept::from(value);
+
+        assert_eq!(media_type, accept.media_type());
+        assert_eq!(value, accept.value());
+    }
 }
@@ -0,0 +1,172 @@
+use crate::codec::parsing::single;
+
+use super::{
+    decoded_option::{self, DecodedOption},
+    number::Number,
+    value::Value,
+    Delta,
+};
+
+/// Block2 option from [RFC 7959](https://datatracker.ietf.org/doc/html/rfc7959#section-2.2).
+///
+/// The value packs the block number, whether more blocks follow and the block size exponent
+/// (SZX), where the block size is `2^(SZX + 4)` bytes.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Block2 {
+    more: bool,
+    num: u32,
+    szx: u8,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Error {
+    SingleValue,
+    Format,
+    Num(u32),
+    Szx(u8),
+}
+
+impl Block2 {
+    pub const MAX_NUM: u32 = 0x000f_ffff;
+    pub const MAX_SZX: u8 = 6;
+    const NUMBER: u16 = 23;
+
+    pub fn decode(values: Vec<Value>) -> Result<Self, Error> {
+        single(values)
+            .map_err(|_| Error::SingleValue)
+            .and_then(Self::decode_value)
+    }
+
+    /// Decodes a single packed block value, shared with the Q-Block options which pack their
+    /// values the same way.
+    pub(crate) fn decode_value(value: Value) -> Result<Self, Error> {
+        if value.len() > 3 {
+            return Err(Error::Format);
+        }
+        let value = value.u32().map_err(|_| Error::Format)?;
+
+        Self::new(value >> 4, value & 0b1000 != 0, (value & 0b0111) as u8)
+    }
+
+    pub fn encode(self, delta_sum: Delta) -> Vec<u8> {
+        DecodedOption {
+            number: Self::number(),
+            values: vec![self.encode_value()],
+        }
+        .encode(delta_sum)
+    }
+
+    /// Size of the option once encoded, see [`Block2::encode`].
+    pub fn encoded_size(&self, delta_sum: Delta) -> usize {
+        decoded_option::encoded_size(Self::number(), delta_sum, [&self.encode_value()])
+    }
+
+    pub(crate) fn encode_value(&self) -> Value {
+        let value = self.num << 4 | (self.more as u32) << 3 | self.szx as u32;
+        let leading_zeros = (value.leading_zeros() / 8) as usize;
+
+        Value::from_opaque(value.to_be_bytes()[leading_zeros..].to_vec())
+            .expect("Block2 value should be at most 3 bytes")
+    }
+
+    /// Whether more blocks follow this one.
+    pub fn more(&self) -> bool {
+        self.more
+    }
+
+    pub fn new(num: u32, more: bool, szx: u8) -> Result<Self, Error> {
+        if num > Self::MAX_NUM {
+            return Err(Error::Num(num));
+        }
+
+        if szx > Self::MAX_SZX {
+            return Err(Error::Szx(szx));
+        }
+
+        Ok(Self { more, num, szx })
+    }
+
+    pub fn num(&self) -> u32 {
+        self.num
+    }
+
+    pub fn number() -> Number {
+        Number::from_value_or_panic(Self::NUMBER)
+    }
+
+    /// Offset of the block in the full representation.
+    pub fn offset(&self) -> usize {
+        self.num as usize * self.size()
+    }
+
+    pub fn size(&self) -> usize {
+        1 << (self.szx + 4)
+    }
+
+    pub fn szx(&self) -> u8 {
+        self.szx
+    }
+}
+
+impl std::fmt::Display for Error {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Error::SingleValue => write!(f, "Block2(23) requires exactly one value"),
+            Error::Format => write!(f, "Block2(23) value is not a valid 24 bit uint"),
+            Error::Num(num) => write!(
+                f,
+                "Block2(23) block number {num} is larger than {}",
+                Block2::MAX_NUM
+            ),
+            Error::Szx(szx) => write!(f, "Block2(23) SZX {szx} is larger than {}", Block2::MAX_SZX),
+        }
+    }
+}
+
+impl std::error::Error for Error {}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+    use rstest::rstest;
+
+    use super::{Block2, Delta, Error, Number, Value};
+
+    #[rstest]
+    #[case(vec![Value::from_opaque(vec![]).unwrap()],             Ok(Block2::new(0, false, 0).unwrap()))]
+    #[case(vec![Value::from_opaque(vec![0b0001_1010]).unwrap()],  Ok(Block2::new(1, true, 2).unwrap()))]
+    #[case(vec![Value::from_opaque(vec![1, 2, 0b0011]).unwrap()], Ok(Block2::new(0x1020, false, 3).unwrap()))]
+    #[case(vec![Value::from_opaque(vec![0b0000_0111]).unwrap()],  Err(Error::Szx(7)))]
+    #[case(vec![Value::from_opaque(vec![1, 2, 3, 4]).unwrap()],   Err(Error::Format))]
+    #[case(vec![],                                                Err(Error::SingleValue))]
+    fn decode(#[case] values: Vec<Value>, #[case] expected: Result<Block2, Error>) {
+        assert_eq!(expected, Block2::decode(values));
+    }
+
+    #[rstest]
+    #[case(Block2::new(0, false, 0).unwrap(),      vec![0b1101_0000, 10])]
+    #[case(Block2::new(1, true, 2).unwrap(),       vec![0b1101_0001, 10, 0b0001_1010])]
+    #[case(Block2::new(0x1020, false, 3).unwrap(), vec![0b1101_0011, 10, 1, 2, 0b0011])]
+    fn encode(#[case] block2: Block2, #[case] expected: Vec<u8>) {
+        assert_eq!(expected, block2.encode(Delta::from_value(0)))
+    }
+
+    #[rstest]
+    #[case(Block2::MAX_NUM + 1, 0, Error::Num(Block2::MAX_NUM + 1))]
+    #[case(0, 7, Error::Szx(7))]
+    fn new_rejects_out_of_range(#[case] num: u32, #[case] szx: u8, #[case] expected: Error) {
+        assert_eq!(Err(expected), Block2::new(num, false, szx));
+    }
+
+    #[rstest]
+    #[case(Block2::new(0, false, 0).unwrap(), 16, 0)]
+    #[case(Block2::new(3, false, 6).unwrap(), 1024, 3072)]
+    fn size_and_offset(#[case] block2: Block2, #[case] size: usize, #[case] offset: usize) {
+        assert_eq!((size, offset), (block2.size(), block2.offset()));
+    }
+
+    #[rstest]
+    fn number() {
+        assert_eq!(Number::from_value(23).unwrap(), Block2::number())
+    }
+}
@@ -1,6 +1,11 @@
 use crate::codec::{media_type, MediaType};
 
-use super::{decoded_option::DecodedOption, number::Number, value::Value, Delta};
+use super::{
+    decoded_option::{self, DecodedOption},
+    number::Number,
+    value::Value,
+    Delta,
+};
 
 #[derive(Clone, Debug, PartialEq)]
 pub struct ContentFormat {
@@ -33,9 +38,26 @@ impl ContentFormat {
         }
     }
 
+    /// Size of the option once encoded, see [`ContentFormat::encode`].
+    pub fn encoded_size(&self, delta_sum: Delta) -> usize {
+        self.media_type.value().map_or(0, |value| {
+            decoded_option::encoded_size(Self::number(), delta_sum, [&Value::from_u16(value)])
+        })
+    }
+
+    pub fn media_type(&self) -> &MediaType {
+        &self.media_type
+    }
+
     pub fn number() -> Number {
         Number::from_value_or_panic(Self::NUMBER)
     }
+
+    /// Content-Format number of the media type, also for formats without a [`MediaType`] variant
+    /// of their own. `None` for [`MediaType::CharsetUtf8`], which has no number.
+    pub fn value(&self) -> Option<u16> {
+        self.media_type.value()
+    }
 }
 
 impl From<media_type::Error> for Error {
@@ -50,6 +72,12 @@ impl From<MediaType> for ContentFormat {
     }
 }
 
+impl From<u16> for ContentFormat {
+    fn from(value: u16) -> Self {
+        MediaType::from_value(value).into()
+    }
+}
+
 impl TryFrom<&str> for ContentFormat {
     type Error = ();
 
@@ -58,6 +86,16 @@ impl TryFrom<&str> for ContentFormat {
     }
 }
 
+impl std::fmt::Display for Error {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Error::MediaType(error) => write!(f, "invalid Content-Format(12): {error}"),
+        }
+    }
+}
+
+impl std::error::Error for Error {}
+
 #[cfg(test)]
 mod tests {
     use pretty_assertions::assert_eq;
@@ -117,4 +155,14 @@ mod tests {
     fn number() {
         assert_eq!(Number::from_value(12).unwrap(), ContentFormat::number())
     }
+
+    #[rstest]
+    #[case(MediaType::APPLICATION_CBOR, MediaType::ApplicationCbor)]
+    #[case(270, MediaType::IetfOrIesg(IetfOrIesg::from_value(270).unwrap()))]
+    fn from_value(#[case] value: u16, #[case] media_type: MediaType) {
+        let content_format = ContentFormat::from(value);
+
+        assert_eq!(&media_type, content_format.media_type());
+        assert_eq!(Some(value), content_format.value());
+    }
 }
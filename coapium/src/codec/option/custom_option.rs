@@ -0,0 +1,318 @@
+//! Typed options this crate doesn't know, declared with [`define_option!`](crate::define_option)
+//! instead of writing a module like [`size1`](super::size1) by hand.
+//!
+//! ```
+//! use coapium::{codec::Options, define_option};
+//!
+//! define_option! {
+//!     /// Name of the gateway that relayed the request.
+//!     pub struct Relay(65004, string, 1..=64);
+//! }
+//!
+//! let mut options = Options::new();
+//! options.set_custom_option(Relay::new("gw-1").unwrap());
+//!
+//! assert_eq!("gw-1", options.custom_option::<Relay>().unwrap().unwrap().value());
+//! ```
+//!
+//! The class and forwarding behaviour of an option are part of its number, see
+//! [RFC 7252 §5.4.6](https://datatracker.ietf.org/doc/html/rfc7252#section-5.4.6), and are
+//! available from [`CustomOption::number`]. The number must be one this crate doesn't decode
+//! into an option of its own.
+
+use super::{decoded_option::DecodedOption, number::Number, value::Value};
+
+/// How the value of an option is formatted, see
+/// [RFC 7252 §3.2](https://datatracker.ietf.org/doc/html/rfc7252#section-3.2).
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Format {
+    Empty,
+    Opaque,
+    String,
+    Uint,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Error {
+    Format(Number),
+    Length { number: Number, length: usize },
+    SingleValue(Number),
+}
+
+/// Option with a single value of a fixed format and length, implemented by
+/// [`define_option!`](crate::define_option).
+pub trait CustomOption: Sized {
+    const NUMBER: u16;
+    const FORMAT: Format;
+    const MIN_LENGTH: usize;
+    const MAX_LENGTH: usize;
+
+    /// Wraps a value that already passed [`CustomOption::validate`].
+    fn from_value(value: Value) -> Self;
+
+    fn into_value(self) -> Value;
+
+    fn number() -> Number {
+        Number::from_value_or_panic(Self::NUMBER)
+    }
+
+    fn decode(values: Vec<Value>) -> Result<Self, Error> {
+        let [value] = &*values else {
+            return Err(Error::SingleValue(Self::number()));
+        };
+
+        Self::validate(value)?;
+
+        Ok(Self::from_value(value.clone()))
+    }
+
+    fn into_decoded(self) -> DecodedOption {
+        DecodedOption::new(Self::number(), vec![self.into_value()])
+    }
+
+    fn validate(value: &Value) -> Result<(), Error> {
+        let valid_format = match Self::FORMAT {
+            Format::Empty => value.is_empty(),
+            Format::Opaque => true,
+            Format::String => value.valid_as_string(),
+            Format::Uint => value.u32().is_ok(),
+        };
+        if !valid_format {
+            return Err(Error::Format(Self::number()));
+        }
+
+        if value.len() < Self::MIN_LENGTH || value.len() > Self::MAX_LENGTH {
+            return Err(Error::Length {
+                number: Self::number(),
+                length: value.len(),
+            });
+        }
+
+        Ok(())
+    }
+}
+
+/// Declares a [`CustomOption`] with a typed constructor and accessor for its value.
+///
+/// The format is one of `empty`, `opaque`, `string` or `uint`, followed by the inclusive length
+/// bounds of the value in bytes for all but `empty`.
+#[macro_export]
+macro_rules! define_option {
+    ($(#[$meta:meta])* $vis:vis struct $name:ident($number:literal, empty);) => {
+        $crate::define_option!(@struct $(#[$meta])* $vis $name, $number, Empty, 0, 0);
+
+        impl $name {
+            #[allow(dead_code)]
+            pub fn new() -> Self {
+                Self {
+                    value: $crate::codec::option::value::Value::empty(),
+                }
+            }
+        }
+
+        impl Default for $name {
+            fn default() -> Self {
+                Self::new()
+            }
+        }
+    };
+    ($(#[$meta:meta])* $vis:vis struct $name:ident($number:literal, opaque, $min:literal..=$max:literal);) => {
+        $crate::define_option!(@struct $(#[$meta])* $vis $name, $number, Opaque, $min, $max);
+
+        impl $name {
+            #[allow(dead_code)]
+            pub fn new(value: Vec<u8>) -> Result<Self, $crate::codec::option::custom_option::Error> {
+                let length = value.len();
+                Self::checked($crate::codec::option::value::Value::from_opaque(value), length)
+            }
+
+            #[allow(dead_code)]
+            pub fn value(&self) -> Vec<u8> {
+                self.value.clone().opaque()
+            }
+        }
+    };
+    ($(#[$meta:meta])* $vis:vis struct $name:ident($number:literal, string, $min:literal..=$max:literal);) => {
+        $crate::define_option!(@struct $(#[$meta])* $vis $name, $number, String, $min, $max);
+
+        impl $name {
+            #[allow(dead_code)]
+            pub fn new(value: &str) -> Result<Self, $crate::codec::option::custom_option::Error> {
+                Self::checked($crate::codec::option::value::Value::from_str(value), value.len())
+            }
+
+            #[allow(dead_code)]
+            pub fn value(&self) -> String {
+                self.value
+                    .clone()
+                    .string()
+                    .expect("Value should have been validated as a string")
+            }
+        }
+    };
+    ($(#[$meta:meta])* $vis:vis struct $name:ident($number:literal, uint, $min:literal..=$max:literal);) => {
+        $crate::define_option!(@struct $(#[$meta])* $vis $name, $number, Uint, $min, $max);
+
+        impl $name {
+            #[allow(dead_code)]
+            pub fn new(value: u32) -> Result<Self, $crate::codec::option::custom_option::Error> {
+                let value = $crate::codec::option::value::Value::from_u32(value);
+                let length = value.len();
+                Self::checked(Ok(value), length)
+            }
+
+            #[allow(dead_code)]
+            pub fn value(&self) -> u32 {
+                self.value
+                    .u32()
+                    .expect("Value should have been validated as a uint")
+            }
+        }
+    };
+    (@struct $(#[$meta:meta])* $vis:vis $name:ident, $number:literal, $format:ident, $min:literal, $max:literal) => {
+        $(#[$meta])*
+        #[derive(Clone, Debug, PartialEq)]
+        $vis struct $name {
+            value: $crate::codec::option::value::Value,
+        }
+
+        impl $crate::codec::option::custom_option::CustomOption for $name {
+            const NUMBER: u16 = $number;
+            const FORMAT: $crate::codec::option::custom_option::Format =
+                $crate::codec::option::custom_option::Format::$format;
+            const MIN_LENGTH: usize = $min;
+            const MAX_LENGTH: usize = $max;
+
+            fn from_value(value: $crate::codec::option::value::Value) -> Self {
+                Self { value }
+            }
+
+            fn into_value(self) -> $crate::codec::option::value::Value {
+                self.value
+            }
+        }
+
+        impl $name {
+            #[allow(dead_code)]
+            fn checked(
+                value: Result<
+                    $crate::codec::option::value::Value,
+                    $crate::codec::option::value::Error,
+                >,
+                length: usize,
+            ) -> Result<Self, $crate::codec::option::custom_option::Error> {
+                use $crate::codec::option::custom_option::{CustomOption, Error};
+
+                let value = value.map_err(|_| Error::Length {
+                    number: Self::number(),
+                    length,
+                })?;
+                Self::validate(&value)?;
+
+                Ok(Self { value })
+            }
+        }
+    };
+}
+
+impl std::fmt::Display for Error {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Error::Format(number) => write!(
+                f,
+                "Option({}) value doesn't have the expected format",
+                number.value.value()
+            ),
+            Error::Length { number, length } => write!(
+                f,
+                "Option({}) length {length} is out of bounds",
+                number.value.value()
+            ),
+            Error::SingleValue(number) => write!(
+                f,
+                "Option({}) requires exactly one value",
+                number.value.value()
+            ),
+        }
+    }
+}
+
+impl std::error::Error for Error {}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+    use rstest::rstest;
+
+    use crate::codec::option::{number::Number, value::Value};
+
+    use super::{CustomOption, Error};
+
+    crate::define_option! {
+        struct Flag(65000, empty);
+    }
+
+    crate::define_option! {
+        struct Key(65008, opaque, 1..=8);
+    }
+
+    crate::define_option! {
+        struct Relay(65004, string, 1..=4);
+    }
+
+    crate::define_option! {
+        struct Hops(65012, uint, 0..=1);
+    }
+
+    fn number(value: u16) -> Number {
+        Number::from_value_or_panic(value)
+    }
+
+    #[rstest]
+    #[case(vec![Value::from_str("gw").unwrap()],                                    Ok(Relay::new("gw").unwrap()))]
+    #[case(vec![],                                                                  Err(Error::SingleValue(number(65004))))]
+    #[case(vec![Value::from_str("a").unwrap(), Value::from_str("b").unwrap()],      Err(Error::SingleValue(number(65004))))]
+    #[case(vec![Value::empty()],                                                    Err(Error::Length { number: number(65004), length: 0 }))]
+    #[case(vec![Value::from_str("gw-12").unwrap()],                                 Err(Error::Length { number: number(65004), length: 5 }))]
+    #[case(vec![Value::from_opaque(vec![0xbf]).unwrap()],                           Err(Error::Format(number(65004))))]
+    fn decode_string(#[case] values: Vec<Value>, #[case] expected: Result<Relay, Error>) {
+        assert_eq!(expected, Relay::decode(values));
+    }
+
+    #[rstest]
+    #[case("gw", Ok("gw".to_string()))]
+    #[case("",   Err(Error::Length { number: number(65004), length: 0 }))]
+    fn string(#[case] value: &str, #[case] expected: Result<String, Error>) {
+        assert_eq!(expected, Relay::new(value).map(|relay| relay.value()));
+    }
+
+    #[rstest]
+    #[case(0, Ok(0))]
+    #[case(255, Ok(255))]
+    #[case(256, Err(Error::Length { number: number(65012), length: 2 }))]
+    fn uint(#[case] value: u32, #[case] expected: Result<u32, Error>) {
+        assert_eq!(expected, Hops::new(value).map(|hops| hops.value()));
+    }
+
+    #[rstest]
+    #[case(vec![1, 2], Ok(vec![1, 2]))]
+    #[case(vec![],     Err(Error::Length { number: number(65008), length: 0 }))]
+    fn opaque(#[case] value: Vec<u8>, #[case] expected: Result<Vec<u8>, Error>) {
+        assert_eq!(expected, Key::new(value).map(|key| key.value()));
+    }
+
+    #[rstest]
+    #[case(vec![Value::empty()],                     Ok(Flag::new()))]
+    #[case(vec![Value::from_opaque(vec![1]).unwrap()], Err(Error::Format(number(65000))))]
+    fn decode_empty(#[case] values: Vec<Value>, #[case] expected: Result<Flag, Error>) {
+        assert_eq!(expected, Flag::decode(values));
+    }
+
+    #[rstest]
+    fn into_decoded() {
+        let decoded = Relay::new("gw").unwrap().into_decoded();
+
+        assert_eq!(number(65004), decoded.number);
+        assert_eq!(Ok(Relay::new("gw").unwrap()), Relay::decode(decoded.values));
+    }
+}
@@ -5,6 +5,13 @@ use super::{
     EncodedOption,
 };
 
+/// Option with its absolute number and every value of it, repeats included, but with the values
+/// left uninterpreted.
+///
+/// Together with [`EncodedOption`] and [`DecodedOptions`](super::DecodedOptions) this is the
+/// supported way to work with options this crate doesn't know, e.g. OSCORE or Echo: decode them
+/// from the raw options of [`Message::decode_lossy`](crate::codec::Message::decode_lossy) and send
+/// them with [`Options::set_custom`](crate::codec::Options::set_custom).
 #[derive(Clone, Debug, PartialEq)]
 pub struct DecodedOption {
     pub number: Number,
@@ -15,9 +22,16 @@ pub struct DecodedOption {
 pub enum Error {
     EmptyOptions,
     Number(number::Error),
+    NumberOverflow,
+    /// More than one value of an option that isn't repeatable.
+    Repeated(Number),
+    /// Value of a length outside the registered length of the option.
+    ValueLength(Number, usize),
 }
 
 impl DecodedOption {
+    /// Encodes the option as one encoded option per value, `delta_sum` is the number of the
+    /// option encoded before it.
     pub fn encode(self, delta_sum: Delta) -> Vec<u8> {
         let number = self.number;
         let mut values = self.values.into_iter().filter(Value::is_bytes);
@@ -35,14 +49,53 @@ impl DecodedOption {
         }
     }
 
-    pub fn parse(input: &[EncodedOption]) -> Result<(&[EncodedOption], Self), Error> {
+    /// Size of the option once encoded, see [`DecodedOption::encode`].
+    pub fn encoded_size(&self, delta_sum: Delta) -> usize {
+        encoded_size(self.number, delta_sum, &self.values)
+    }
+
+    pub fn new(number: Number, values: Vec<Value>) -> Self {
+        Self { number, values }
+    }
+
+    /// Checks the values against the IANA registration of the option, options that aren't
+    /// registered are always valid. Options this crate doesn't know are otherwise taken as they
+    /// are, see [`Number::registration`].
+    pub fn validate(&self) -> Result<(), Error> {
+        if self.number.registration().is_none() {
+            return Ok(());
+        }
+        if self.values.len() > 1 && !self.number.is_repeatable() {
+            return Err(Error::Repeated(self.number));
+        }
+        if let Some(value) = self
+            .values
+            .iter()
+            .find(|value| !self.number.is_valid_length(value.len()))
+        {
+            return Err(Error::ValueLength(self.number, value.len()));
+        }
+
+        Ok(())
+    }
+
+    /// Parses the next option, its number is the delta of the first encoded option added to the
+    /// number of the option before it.
+    pub fn parse(
+        delta_sum: Delta,
+        input: &[EncodedOption],
+    ) -> Result<(&[EncodedOption], Self), Error> {
         let mut options = input.iter();
 
         let Some(head) = options.next() else {
             return Err(Error::EmptyOptions);
         };
 
-        let number = Number::decode(*head.delta())?;
+        let number = delta_sum
+            .value()
+            .checked_add(head.delta().value())
+            .ok_or(Error::NumberOverflow)?;
+        let number = Number::decode(Delta::from_value(number))?;
         let mut values = vec![head.value().clone()];
 
         values.extend(
@@ -61,6 +114,42 @@ impl From<number::Error> for Error {
     }
 }
 
+impl std::fmt::Display for Error {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Error::EmptyOptions => write!(f, "no encoded options to decode"),
+            Error::Number(error) => error.fmt(f),
+            Error::NumberOverflow => write!(f, "option number exceeds {}", u16::MAX),
+            Error::Repeated(number) => write!(f, "option {number} is not repeatable"),
+            Error::ValueLength(number, length) => {
+                write!(f, "option {number} can't have a value of {length} bytes")
+            }
+        }
+    }
+}
+
+impl std::error::Error for Error {}
+
+/// Size of option `number` with `values` once encoded, worked out from the lengths of the values
+/// the way [`DecodedOption::encode`] encodes them.
+pub(crate) fn encoded_size<'a>(
+    number: Number,
+    delta_sum: Delta,
+    values: impl IntoIterator<Item = &'a Value>,
+) -> usize {
+    let mut values = values.into_iter().filter(|value| value.is_bytes());
+
+    match values.next() {
+        Some(first_value) => {
+            EncodedOption::encoded_size(number.encode(delta_sum), first_value)
+                + values
+                    .map(|value| EncodedOption::encoded_size(Delta::repeating(), value))
+                    .sum::<usize>()
+        }
+        None => EncodedOption::encoded_size(number.encode(delta_sum), &Value::Empty),
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use pretty_assertions::assert_eq;
@@ -181,7 +270,48 @@ mod tests {
     ) {
         assert_eq!(
             expected.map(|value| (expected_rest, value)),
-            DecodedOption::parse(input)
+            DecodedOption::parse(Delta::from_value(0), input)
+        );
+    }
+
+    #[rstest]
+    #[case(Delta::from_value(11), Delta::from_value(1), Ok(Number::from_value(12).unwrap()))]
+    #[case(Delta::from_value(11), Delta::from_value(0), Ok(Number::from_value(11).unwrap()))]
+    #[case(
+        Delta::from_value(u16::MAX),
+        Delta::from_value(1),
+        Err(Error::NumberOverflow)
+    )]
+    fn parse_after_previous_option(
+        #[case] delta_sum: Delta,
+        #[case] delta: Delta,
+        #[case] expected: Result<Number, Error>,
+    ) {
+        let input = [EncodedOption::new(delta, Value::from_str("a").unwrap())];
+
+        assert_eq!(
+            expected,
+            DecodedOption::parse(delta_sum, &input).map(|(_, option)| option.number)
+        );
+    }
+
+    #[rstest]
+    #[case(16, vec![Value::from_opaque(vec![1]).unwrap()], Ok(()))]
+    #[case(16, vec![Value::empty()], Err(Error::ValueLength(Number::from_value(16).unwrap(), 0)))]
+    #[case(
+        258,
+        vec![Value::empty(), Value::empty()],
+        Err(Error::Repeated(Number::from_value(258).unwrap()))
+    )]
+    #[case(65000, vec![Value::empty(), Value::empty()], Ok(()))]
+    fn validate(
+        #[case] number: u16,
+        #[case] values: Vec<Value>,
+        #[case] expected: Result<(), Error>,
+    ) {
+        assert_eq!(
+            expected,
+            DecodedOption::new(Number::from_value(number).unwrap(), values).validate()
         );
     }
 }
@@ -1,10 +1,11 @@
-use crate::codec::parsing::many0;
+use crate::codec::payload;
 
 use super::{
     decoded_option::{self, DecodedOption},
-    encoded_option, EncodedOption,
+    encoded_option, Delta, EncodedOption,
 };
 
+/// Options of a message in the order they were encoded, see [`DecodedOption`].
 #[derive(Clone, Debug, PartialEq)]
 pub struct DecodedOptions {
     options: Vec<DecodedOption>,
@@ -21,11 +22,13 @@ impl DecodedOptions {
         let mut input: &[EncodedOption] = &encoded_options;
 
         let mut options = vec![];
+        let mut delta_sum = Delta::from_value(0);
 
         while !input.is_empty() {
-            let (rest, option) = DecodedOption::parse(&input)?;
+            let (rest, option) = DecodedOption::parse(delta_sum, input)?;
 
             input = rest;
+            delta_sum = option.number.value;
             options.push(option);
         }
 
@@ -36,8 +39,28 @@ impl DecodedOptions {
         self.options.into_iter()
     }
 
-    pub fn parse(bytes: &[u8]) -> Result<(&[u8], Self), Error> {
-        let (bytes, options) = many0(EncodedOption::parse)(bytes)?;
+    pub fn new(options: Vec<DecodedOption>) -> Self {
+        Self { options }
+    }
+
+    pub fn options(&self) -> &[DecodedOption] {
+        &self.options
+    }
+
+    /// Parses options up to the payload marker or the end of `bytes`.
+    ///
+    /// Anything else that isn't an option is a message format error, like a delta or length
+    /// nibble of 15 outside the payload marker, see
+    /// [RFC 7252 §3.1](https://datatracker.ietf.org/doc/html/rfc7252#section-3.1).
+    pub fn parse(mut bytes: &[u8]) -> Result<(&[u8], Self), Error> {
+        let mut options = vec![];
+
+        while bytes.first().is_some_and(|&byte| byte != payload::MARKER) {
+            let (rest, option) = EncodedOption::parse(bytes)?;
+            bytes = rest;
+            options.push(option);
+        }
+
         Self::decode(options).map(|options| (bytes, options))
     }
 }
@@ -54,6 +77,17 @@ impl From<decoded_option::Error> for Error {
     }
 }
 
+impl std::fmt::Display for Error {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Error::EncodedOption(error) => error.fmt(f),
+            Error::DecodedOption(error) => error.fmt(f),
+        }
+    }
+}
+
+impl std::error::Error for Error {}
+
 #[cfg(test)]
 mod tests {
     use pretty_assertions::assert_eq;
@@ -62,9 +96,11 @@ mod tests {
     use super::{
         super::decoded_option,
         super::number::{self, Number},
+        super::value,
         super::Delta,
         super::Value,
-        DecodedOption, DecodedOptions, EncodedOption, Error,
+        super::{delta, delta_header, length, length_header},
+        encoded_option, DecodedOption, DecodedOptions, EncodedOption, Error,
     };
 
     #[rstest]
@@ -83,7 +119,7 @@ mod tests {
     #[case(
         vec![
             EncodedOption::new(Delta::from_value(11), Value::from_str("a").unwrap()),
-            EncodedOption::new(Delta::from_value(15), Value::from_str("b").unwrap()),
+            EncodedOption::new(Delta::from_value(4), Value::from_str("b").unwrap()),
         ],
         Ok(DecodedOptions { 
             options: vec![
@@ -104,10 +140,12 @@ mod tests {
 
     #[rstest]
     #[case(&[], &[], Ok(DecodedOptions{ options: vec![] }))]
-    #[case(&[0b1111_0001, 97], &[0b1111_0001, 97], Ok(DecodedOptions { options: vec![] }))]
-    #[case(&[0b0001_1111, 97], &[0b0001_1111, 97], Ok(DecodedOptions { options: vec![] }))]
+    #[case(&[0b1111_0001, 97], &[], Err(Error::EncodedOption(encoded_option::Error::Delta(delta::DecodeError::Header(delta_header::Error::Reserved)))))]
+    #[case(&[0b0001_1111, 97], &[], Err(Error::EncodedOption(encoded_option::Error::Value(value::Error::Length(length::DecodeError::Header(length_header::Error::Reserved))))))]
+    #[case(&[0b1011_0001, 97, 0b1111_0000], &[], Err(Error::EncodedOption(encoded_option::Error::Delta(delta::DecodeError::Header(delta_header::Error::Reserved)))))]
     #[case(&[0b1111_1111, 97], &[0b1111_1111, 97], Ok(DecodedOptions { options: vec![] }))]
-    #[case(&[0b1011_0001, 97, 98], &[98], Ok(DecodedOptions { options: vec![DecodedOption{ number: Number::from_value(11).unwrap(), values: vec![Value::from_str("a").unwrap()] }] }))]
+    #[case(&[0b1011_0001, 97, 0b1111_1111, 98], &[0b1111_1111, 98], Ok(DecodedOptions { options: vec![DecodedOption{ number: Number::from_value(11).unwrap(), values: vec![Value::from_str("a").unwrap()] }] }))]
+    #[case(&[0b1011_0001, 97, 98], &[], Err(Error::EncodedOption(encoded_option::Error::Value(value::Error::Value(value::ValueError::LengthOutOfBounds)))))]
     fn parse(
         #[case] bytes: &[u8],
         #[case] expected_rest: &[u8],
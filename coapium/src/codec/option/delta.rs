@@ -25,6 +25,11 @@ pub enum Delta {
 pub enum DecodeError {
     Combination(DeltaHeader, usize),
     Header(delta_header::Error),
+    /// The datagram ends before the extended bytes the header asks for.
+    Insufficient {
+        needed: usize,
+        available: usize,
+    },
     OutOfRange(u16),
 }
 
@@ -95,10 +100,20 @@ impl Delta {
         match DeltaHeader::decode(header_byte)? {
             header @ DeltaHeader::Length(_) => Ok((bytes, Self::decode(header, &[])?)),
             header @ DeltaHeader::Extended8Bit => {
-                Ok((&bytes[1..], Self::decode(header, &bytes[..1])?))
+                let (extended, rest) =
+                    bytes.split_at_checked(1).ok_or(DecodeError::Insufficient {
+                        needed: 1,
+                        available: bytes.len(),
+                    })?;
+                Ok((rest, Self::decode(header, extended)?))
             }
             header @ DeltaHeader::Extended16Bit => {
-                Ok((&bytes[2..], Self::decode(header, &bytes[..2])?))
+                let (extended, rest) =
+                    bytes.split_at_checked(2).ok_or(DecodeError::Insufficient {
+                        needed: 2,
+                        available: bytes.len(),
+                    })?;
+                Ok((rest, Self::decode(header, extended)?))
             }
         }
     }
@@ -111,6 +126,15 @@ impl Delta {
         Self::from_value(self.value() - other.value())
     }
 
+    /// Number of extended bytes the value is encoded with after the header.
+    pub const fn extended_len(&self) -> usize {
+        match self {
+            Self::Length(_) => 0,
+            Self::Extended8Bit(_) => 1,
+            Self::Extended16Bit(_) => 2,
+        }
+    }
+
     pub const fn value(&self) -> u16 {
         match *self {
             Self::Length(length) => length.value() as u16,
@@ -146,6 +170,26 @@ impl From<delta_header::Error> for DecodeError {
     }
 }
 
+impl std::fmt::Display for DecodeError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            DecodeError::Combination(header, length) => write!(
+                f,
+                "delta header {} requires more extended bytes, got {length}",
+                header.value()
+            ),
+            DecodeError::Header(error) => write!(f, "invalid delta: {error}"),
+            DecodeError::Insufficient { needed, available } => write!(
+                f,
+                "delta requires {needed} extended bytes, only {available} left"
+            ),
+            DecodeError::OutOfRange(value) => write!(f, "delta {value} is out of range"),
+        }
+    }
+}
+
+impl std::error::Error for DecodeError {}
+
 #[cfg(test)]
 mod tests {
     use std::cmp::Ordering;
@@ -279,6 +323,9 @@ mod tests {
     #[case(3 << 4, &[1, 2], Ok(([1, 2].as_ref(), Delta::Length(delta_header::Value::from_value_or_panic(3)))))]
     #[case(13 << 4, &[1, 2], Ok(([2].as_ref(), Delta::Extended8Bit(Extended8Bit(1)))))]
     #[case(14 << 4, &[1, 2, 3], Ok(([3].as_ref(), Delta::Extended16Bit(Extended16Bit(258)))))]
+    #[case(13 << 4, &[], Err(DecodeError::Insufficient { needed: 1, available: 0 }))]
+    #[case(14 << 4, &[], Err(DecodeError::Insufficient { needed: 2, available: 0 }))]
+    #[case(14 << 4, &[1], Err(DecodeError::Insufficient { needed: 2, available: 1 }))]
     fn parse(
         #[case] header: u8,
         #[case] rest: &[u8],
@@ -85,6 +85,17 @@ impl Value {
     }
 }
 
+impl std::fmt::Display for Error {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Error::Range(value) => write!(f, "delta header {value} is out of range"),
+            Error::Reserved => write!(f, "delta header 15 is reserved for the payload marker"),
+        }
+    }
+}
+
+impl std::error::Error for Error {}
+
 #[cfg(test)]
 mod tests {
     use pretty_assertions::assert_eq;
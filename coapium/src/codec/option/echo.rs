@@ -0,0 +1,125 @@
+use crate::codec::parsing::single;
+
+use super::{
+    decoded_option::{self, DecodedOption},
+    number::Number,
+    value::Value,
+    Delta,
+};
+
+/// Echo option from [RFC 9175](https://datatracker.ietf.org/doc/html/rfc9175#section-2), a value
+/// chosen by the server that the client repeats to show that its request is fresh.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Echo {
+    value: Value,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Error {
+    SingleValue,
+    Length(usize),
+}
+
+impl Echo {
+    const MIN_LENGTH: usize = 1;
+    const MAX_LENGTH: usize = 40;
+    const NUMBER: u16 = 252;
+
+    pub fn decode(values: Vec<Value>) -> Result<Self, Error> {
+        let value = single(values).map_err(|_| Error::SingleValue)?;
+
+        if value.len() < Self::MIN_LENGTH || value.len() > Self::MAX_LENGTH {
+            return Err(Error::Length(value.len()));
+        }
+
+        Ok(Self { value })
+    }
+
+    pub fn encode(self, delta_sum: Delta) -> Vec<u8> {
+        DecodedOption {
+            number: Self::number(),
+            values: vec![self.value],
+        }
+        .encode(delta_sum)
+    }
+
+    /// Size of the option once encoded, see [`Echo::encode`].
+    pub fn encoded_size(&self, delta_sum: Delta) -> usize {
+        decoded_option::encoded_size(Self::number(), delta_sum, [&self.value])
+    }
+
+    pub fn from_value(value: Vec<u8>) -> Result<Self, Error> {
+        let length = value.len();
+        let value = Value::from_opaque(value).map_err(|_| Error::Length(length))?;
+        Self::decode(vec![value])
+    }
+
+    pub fn number() -> Number {
+        Number::from_value_or_panic(Self::NUMBER)
+    }
+
+    pub fn value(&self) -> Vec<u8> {
+        self.value.clone().opaque()
+    }
+}
+
+impl TryFrom<Vec<u8>> for Echo {
+    type Error = Error;
+
+    fn try_from(value: Vec<u8>) -> Result<Self, Self::Error> {
+        Self::from_value(value)
+    }
+}
+
+impl std::fmt::Display for Error {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Error::SingleValue => write!(f, "Echo(252) requires exactly one value"),
+            Error::Length(length) => write!(
+                f,
+                "Echo(252) value length {length} is outside {}..={}",
+                Echo::MIN_LENGTH,
+                Echo::MAX_LENGTH
+            ),
+        }
+    }
+}
+
+impl std::error::Error for Error {}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+    use rstest::rstest;
+
+    use super::{Delta, Echo, Error, Number, Value};
+
+    #[rstest]
+    #[case(vec![Value::from_opaque(vec![1, 2]).unwrap()], Ok(Echo { value: Value::from_opaque(vec![1, 2]).unwrap() }))]
+    #[case(vec![], Err(Error::SingleValue))]
+    #[case(vec![Value::from_opaque(vec![]).unwrap()], Err(Error::Length(0)))]
+    #[case(vec![Value::from_opaque(vec![1; 41]).unwrap()], Err(Error::Length(41)))]
+    #[case(vec![Value::from_opaque(vec![1]).unwrap(), Value::from_opaque(vec![2]).unwrap()], Err(Error::SingleValue))]
+    fn decode(#[case] values: Vec<Value>, #[case] expected: Result<Echo, Error>) {
+        assert_eq!(expected, Echo::decode(values));
+    }
+
+    #[rstest]
+    #[case(Echo::from_value(vec![0xab]).unwrap(), vec![0b1101_0001, 239, 0xab])]
+    fn encode(#[case] echo: Echo, #[case] expected: Vec<u8>) {
+        assert_eq!(expected, echo.encode(Delta::from_value(0)))
+    }
+
+    #[rstest]
+    fn number() {
+        assert_eq!(Number::from_value(252).unwrap(), Echo::number())
+    }
+
+    #[rstest]
+    fn value() {
+        assert_eq!(
+            vec![1, 2, 3],
+            Echo::from_value(vec![1, 2, 3]).unwrap().value()
+        )
+    }
+}
@@ -3,6 +3,8 @@ use super::{
     value::{self, Value},
 };
 
+/// Single option as it appears on the wire, a delta to the number of the option before it and one
+/// value. A repeated option is one encoded option per value, see [`DecodedOption`](super::DecodedOption).
 #[derive(Debug, PartialEq, Clone)]
 pub struct EncodedOption {
     delta: Delta,
@@ -40,6 +42,11 @@ impl EncodedOption {
         bytes
     }
 
+    /// Size of the option once encoded, without encoding it.
+    pub fn encoded_size(delta: Delta, value: &Value) -> usize {
+        1 + delta.extended_len() + value.length().extended_len() + value.len()
+    }
+
     pub fn new(delta: Delta, value: Value) -> Self {
         Self::decode(delta, value)
     }
@@ -80,6 +87,18 @@ impl From<value::Error> for Error {
     }
 }
 
+impl std::fmt::Display for Error {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Error::HeaderMissing => write!(f, "option header is missing"),
+            Error::Delta(error) => error.fmt(f),
+            Error::Value(error) => error.fmt(f),
+        }
+    }
+}
+
+impl std::error::Error for Error {}
+
 #[cfg(test)]
 mod tests {
     use pretty_assertions::assert_eq;
@@ -1,4 +1,9 @@
-use super::{decoded_option::DecodedOption, number::Number, value::Value, Delta};
+use super::{
+    decoded_option::{self, DecodedOption},
+    number::Number,
+    value::Value,
+    Delta,
+};
 
 #[derive(Clone, Debug, PartialEq)]
 pub struct ETag {
@@ -34,6 +39,17 @@ impl ETag {
             .map(|values| Self { values })
     }
 
+    pub fn from_values(values: Vec<Vec<u8>>) -> Result<Self, Error> {
+        let values = values
+            .into_iter()
+            .map(|value| {
+                let length = value.len();
+                Value::from_opaque(value).map_err(|_| Error::Length(length))
+            })
+            .collect::<Result<_, _>>()?;
+        Self::decode(values)
+    }
+
     pub fn encode(self, delta_sum: Delta) -> Vec<u8> {
         DecodedOption {
             number: Self::number(),
@@ -42,11 +58,44 @@ impl ETag {
         .encode(delta_sum)
     }
 
+    /// Size of the option once encoded, see [`ETag::encode`].
+    pub fn encoded_size(&self, delta_sum: Delta) -> usize {
+        decoded_option::encoded_size(Self::number(), delta_sum, &self.values)
+    }
+
     pub fn number() -> Number {
         Number::from_value_or_panic(4)
     }
+
+    /// Appends the values of `etag`, each is encoded as a repeat of the option.
+    pub fn extend(&mut self, etag: ETag) {
+        self.values.extend(etag.values);
+    }
 }
 
+impl TryFrom<Vec<Vec<u8>>> for ETag {
+    type Error = Error;
+
+    fn try_from(values: Vec<Vec<u8>>) -> Result<Self, Self::Error> {
+        Self::from_values(values)
+    }
+}
+
+impl std::fmt::Display for Error {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Error::Length(length) => write!(
+                f,
+                "ETag(4) value length {length} is outside {}..={}",
+                ETag::MIN_LENGTH,
+                ETag::MAX_LENGTH
+            ),
+        }
+    }
+}
+
+impl std::error::Error for Error {}
+
 #[cfg(test)]
 mod tests {
     use rstest::rstest;
@@ -64,4 +113,12 @@ mod tests {
     fn decode(#[case] values: Vec<Value>, #[case] expected: Result<ETag, Error>) {
         assert_eq!(expected, ETag::decode(values));
     }
+
+    #[rstest]
+    #[case(vec![vec![1, 2]], Ok(ETag{ values: vec![Value::from_opaque(vec![1, 2]).unwrap()] }))]
+    #[case(vec![vec![]], Err(Error::Length(0)))]
+    #[case(vec![vec![1; 9]], Err(Error::Length(9)))]
+    fn from_values(#[case] values: Vec<Vec<u8>>, #[case] expected: Result<ETag, Error>) {
+        assert_eq!(expected, ETag::from_values(values));
+    }
 }
@@ -1,5 +1,5 @@
 use super::{
-    decoded_option::DecodedOption,
+    decoded_option::{self, DecodedOption},
     number::Number,
     value::{self, Value},
     Delta,
@@ -51,6 +51,11 @@ impl IfMatch {
         .encode(delta_sum)
     }
 
+    /// Size of the option once encoded, see [`IfMatch::encode`].
+    pub fn encoded_size(&self, delta_sum: Delta) -> usize {
+        decoded_option::encoded_size(Self::number(), delta_sum, &self.values)
+    }
+
     pub fn number() -> Number {
         Number::from_value_or_panic(1)
     }
@@ -69,6 +74,16 @@ impl TryFrom<Vec<Vec<u8>>> for IfMatch {
     }
 }
 
+impl std::fmt::Display for Error {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Error::Length => write!(f, "If-Match(1) value exceeds {} bytes", IfMatch::MAX_LENGTH),
+        }
+    }
+}
+
+impl std::error::Error for Error {}
+
 #[cfg(test)]
 mod tests {
     use super::Error;
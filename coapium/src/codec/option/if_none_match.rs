@@ -1,4 +1,9 @@
-use super::{decoded_option::DecodedOption, number::Number, value::Value, Delta};
+use super::{
+    decoded_option::{self, DecodedOption},
+    number::Number,
+    value::Value,
+    Delta,
+};
 
 #[derive(Clone, Debug, PartialEq)]
 pub struct IfNoneMatch;
@@ -29,11 +34,27 @@ impl IfNoneMatch {
         }
         .encode(delta_sum)
     }
+
+    /// Size of the option once encoded, see [`IfNoneMatch::encode`].
+    pub fn encoded_size(&self, delta_sum: Delta) -> usize {
+        decoded_option::encoded_size(Self::number(), delta_sum, [&Value::empty()])
+    }
     pub fn number() -> Number {
         Number::from_value_or_panic(5)
     }
 }
 
+impl std::fmt::Display for Error {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Error::SingleValue => write!(f, "If-None-Match(5) requires exactly one value"),
+            Error::NotEmpty => write!(f, "If-None-Match(5) value must be empty"),
+        }
+    }
+}
+
+impl std::error::Error for Error {}
+
 #[cfg(test)]
 mod tests {
     use super::Error;
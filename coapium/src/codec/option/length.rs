@@ -25,6 +25,11 @@ pub enum Length {
 pub enum DecodeError {
     Combination(LengthHeader, usize),
     Header(length_header::Error),
+    /// The datagram ends before the extended bytes the header asks for.
+    Insufficient {
+        needed: usize,
+        available: usize,
+    },
     OutOfRange(u16),
 }
 
@@ -91,14 +96,33 @@ impl Length {
         match LengthHeader::decode(header_byte)? {
             header @ LengthHeader::Length(_) => Ok((bytes, Self::decode(header, &[])?)),
             header @ LengthHeader::Extended8Bit => {
-                Ok((&bytes[1..], Self::decode(header, &bytes[..1])?))
+                let (extended, rest) =
+                    bytes.split_at_checked(1).ok_or(DecodeError::Insufficient {
+                        needed: 1,
+                        available: bytes.len(),
+                    })?;
+                Ok((rest, Self::decode(header, extended)?))
             }
             header @ LengthHeader::Extended16Bit => {
-                Ok((&bytes[2..], Self::decode(header, &bytes[..2])?))
+                let (extended, rest) =
+                    bytes.split_at_checked(2).ok_or(DecodeError::Insufficient {
+                        needed: 2,
+                        available: bytes.len(),
+                    })?;
+                Ok((rest, Self::decode(header, extended)?))
             }
         }
     }
 
+    /// Number of extended bytes the value is encoded with after the header.
+    pub const fn extended_len(&self) -> usize {
+        match self {
+            Self::Length(_) => 0,
+            Self::Extended8Bit(_) => 1,
+            Self::Extended16Bit(_) => 2,
+        }
+    }
+
     pub const fn value(&self) -> u16 {
         match *self {
             Self::Length(length) => length.value() as u16,
@@ -120,6 +144,26 @@ impl PartialOrd for Length {
     }
 }
 
+impl std::fmt::Display for DecodeError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            DecodeError::Combination(header, length) => write!(
+                f,
+                "length header {} requires more extended bytes, got {length}",
+                header.value()
+            ),
+            DecodeError::Header(error) => write!(f, "invalid length: {error}"),
+            DecodeError::Insufficient { needed, available } => write!(
+                f,
+                "length requires {needed} extended bytes, only {available} left"
+            ),
+            DecodeError::OutOfRange(value) => write!(f, "length {value} is out of range"),
+        }
+    }
+}
+
+impl std::error::Error for DecodeError {}
+
 #[cfg(test)]
 mod tests {
     use std::cmp::Ordering;
@@ -222,6 +266,9 @@ mod tests {
     #[case(3, &[1, 2], Ok(([1, 2].as_ref(), Length::Length(length_header::Value::from_value_or_panic(3)))))]
     #[case(13, &[1, 2], Ok(([2].as_ref(), Length::Extended8Bit(Extended8Bit(1)))))]
     #[case(14, &[1, 2, 3], Ok(([3].as_ref(), Length::Extended16Bit(Extended16Bit(258)))))]
+    #[case(13, &[], Err(DecodeError::Insufficient { needed: 1, available: 0 }))]
+    #[case(14, &[], Err(DecodeError::Insufficient { needed: 2, available: 0 }))]
+    #[case(14, &[1], Err(DecodeError::Insufficient { needed: 2, available: 1 }))]
     fn parse(
         #[case] header: u8,
         #[case] rest: &[u8],
@@ -1,4 +1,9 @@
-use super::{decoded_option::DecodedOption, number::Number, value::Value, Delta};
+use super::{
+    decoded_option::{self, DecodedOption},
+    number::Number,
+    value::Value,
+    Delta,
+};
 
 #[derive(Clone, Debug, PartialEq)]
 pub struct LocationPath {
@@ -42,11 +47,57 @@ impl LocationPath {
         .encode(delta_sum)
     }
 
+    /// Size of the option once encoded, see [`LocationPath::encode`].
+    pub fn encoded_size(&self, delta_sum: Delta) -> usize {
+        decoded_option::encoded_size(Self::number(), delta_sum, &self.values)
+    }
+
+    /// Location-Path from a path such as `/rd/4521`, split into segments on `/`.
+    pub fn from_value<S: AsRef<str>>(value: S) -> Result<Self, Error> {
+        value
+            .as_ref()
+            .trim_start_matches('/')
+            .split('/')
+            .filter(|segment| !segment.is_empty())
+            .map(|segment| Value::from_str(segment).map_err(|_| Error::Format))
+            .collect::<Result<Vec<_>, _>>()
+            .and_then(Self::decode)
+    }
+
     pub fn number() -> Number {
         Number::from_value_or_panic(8)
     }
+
+    pub fn segments(&self) -> Vec<String> {
+        self.values
+            .iter()
+            .cloned()
+            .map(|value| {
+                value
+                    .string()
+                    .expect("Location-Path value should be a string")
+            })
+            .collect()
+    }
 }
 
+impl std::fmt::Display for Error {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Error::Format => write!(f, "Location-Path(8) value is not a valid string"),
+            Error::Length(length) => {
+                write!(
+                    f,
+                    "Location-Path(8) segment length {length} exceeds {} bytes",
+                    LocationPath::MAX_LENGTH
+                )
+            }
+        }
+    }
+}
+
+impl std::error::Error for Error {}
+
 #[cfg(test)]
 mod tests {
     use rstest::rstest;
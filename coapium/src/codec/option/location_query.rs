@@ -1,4 +1,9 @@
-use super::{decoded_option::DecodedOption, number::Number, value::Value, Delta};
+use super::{
+    decoded_option::{self, DecodedOption},
+    number::Number,
+    value::Value,
+    Delta,
+};
 
 #[derive(Clone, Debug, PartialEq)]
 pub struct LocationQuery {
@@ -26,6 +31,18 @@ impl LocationQuery {
         }
     }
 
+    pub fn arguments(&self) -> Vec<String> {
+        self.values
+            .iter()
+            .cloned()
+            .map(|value| {
+                value
+                    .string()
+                    .expect("Location-Query value should be a string")
+            })
+            .collect()
+    }
+
     pub fn decode(values: Vec<Value>) -> Result<Self, Error> {
         values
             .into_iter()
@@ -42,11 +59,33 @@ impl LocationQuery {
         .encode(delta_sum)
     }
 
+    /// Size of the option once encoded, see [`LocationQuery::encode`].
+    pub fn encoded_size(&self, delta_sum: Delta) -> usize {
+        decoded_option::encoded_size(Self::number(), delta_sum, &self.values)
+    }
+
     pub fn number() -> Number {
         Number::from_value_or_panic(20)
     }
 }
 
+impl std::fmt::Display for Error {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Error::Format => write!(f, "Location-Query(20) value is not a valid string"),
+            Error::Length(length) => {
+                write!(
+                    f,
+                    "Location-Query(20) length {length} exceeds {} bytes",
+                    LocationQuery::MAX_LENGTH
+                )
+            }
+        }
+    }
+}
+
+impl std::error::Error for Error {}
+
 #[cfg(test)]
 mod tests {
     use rstest::rstest;
@@ -1,6 +1,11 @@
 use crate::codec::parsing::single;
 
-use super::{decoded_option::DecodedOption, number::Number, value::Value, Delta};
+use super::{
+    decoded_option::{self, DecodedOption},
+    number::Number,
+    value::Value,
+    Delta,
+};
 
 #[derive(Clone, Debug, PartialEq)]
 pub struct MaxAge {
@@ -41,9 +46,18 @@ impl MaxAge {
         .encode(delta_sum)
     }
 
+    /// Size of the option once encoded, see [`MaxAge::encode`].
+    pub fn encoded_size(&self, delta_sum: Delta) -> usize {
+        decoded_option::encoded_size(Self::number(), delta_sum, [&self.value])
+    }
+
     pub fn number() -> Number {
         Number::from_value_or_panic(Self::NUMBER)
     }
+
+    pub fn seconds(&self) -> u32 {
+        self.value.u32().expect("Max-Age value should be a uint")
+    }
 }
 
 impl Default for MaxAge {
@@ -60,6 +74,17 @@ impl From<u32> for MaxAge {
     }
 }
 
+impl std::fmt::Display for DecodeError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            DecodeError::SingleValue => write!(f, "Max-Age(14) requires exactly one value"),
+            DecodeError::Format => write!(f, "Max-Age(14) value is not a valid uint"),
+        }
+    }
+}
+
+impl std::error::Error for DecodeError {}
+
 #[cfg(test)]
 mod tests {
 
@@ -1,9 +1,13 @@
 pub mod accept;
+pub mod block1;
+pub mod block2;
 pub mod content_format;
+pub mod custom_option;
 pub mod decoded_option;
 pub mod decoded_options;
 pub mod delta;
 pub mod delta_header;
+pub mod echo;
 pub mod encoded_option;
 pub mod etag;
 pub mod if_match;
@@ -14,8 +18,12 @@ pub mod location_path;
 pub mod location_query;
 pub mod max_age;
 pub mod number;
+pub mod observe;
 pub mod proxy_scheme;
 pub mod proxy_uri;
+pub mod q_block1;
+pub mod q_block2;
+pub mod request_tag;
 pub mod size1;
 pub mod uri_host;
 pub mod uri_path;
@@ -24,11 +32,14 @@ pub mod uri_query;
 pub mod value;
 
 pub use accept::Accept;
+pub use block1::Block1;
+pub use block2::Block2;
 pub use content_format::ContentFormat;
 pub use decoded_option::DecodedOption;
 pub use decoded_options::DecodedOptions;
 pub use delta::Delta;
 pub use delta_header::DeltaHeader;
+pub use echo::Echo;
 pub use encoded_option::EncodedOption;
 pub use etag::ETag;
 pub use if_match::IfMatch;
@@ -39,8 +50,12 @@ pub use location_path::LocationPath;
 pub use location_query::LocationQuery;
 pub use max_age::MaxAge;
 pub use number::Number;
+pub use observe::Observe;
 pub use proxy_scheme::ProxyScheme;
 pub use proxy_uri::ProxyUri;
+pub use q_block1::QBlock1;
+pub use q_block2::QBlock2;
+pub use request_tag::RequestTag;
 pub use size1::Size1;
 pub use uri_host::UriHost;
 pub use uri_path::UriPath;
@@ -87,34 +102,50 @@ pub use value::Value;
 #[derive(Clone, Debug, PartialEq)]
 pub enum Option {
     Accept(Accept),
+    Block1(Block1),
+    Block2(Block2),
     ContentFormat(ContentFormat),
+    Echo(Echo),
     ETag(ETag),
     IfMatch(IfMatch),
     IfNoneMatch(IfNoneMatch),
     LocationPath(LocationPath),
     LocationQuery(LocationQuery),
     MaxAge(MaxAge),
+    Observe(Observe),
     ProxyScheme(ProxyScheme),
     ProxyUri(ProxyUri),
+    QBlock1(QBlock1),
+    QBlock2(QBlock2),
+    RequestTag(RequestTag),
     Size1(Size1),
     UriHost(UriHost),
     UriPath(UriPath),
     UriPort(UriPort),
     UriQuery(UriQuery),
+    /// Option this crate doesn't know, never the result of [`Option::decode`].
+    Custom(DecodedOption),
 }
 
 #[derive(Clone, Copy, Debug, PartialEq)]
 pub enum Error {
     Accept(accept::Error),
+    Block1(block1::Error),
+    Block2(block2::Error),
     ContentFormat(content_format::Error),
+    Echo(echo::Error),
     ETag(etag::Error),
     IfMatch(if_match::Error),
     IfNoneMatch(if_none_match::Error),
     LocationPath(location_path::Error),
     LocationQuery(location_query::Error),
     MaxAge(max_age::DecodeError),
+    Observe(observe::Error),
     ProxyScheme(proxy_scheme::Error),
     ProxyUri(proxy_uri::Error),
+    QBlock1(q_block1::Error),
+    QBlock2(q_block2::Error),
+    RequestTag(request_tag::Error),
     Size1(size1::Error),
     UriHost(uri_host::DecodeError),
     UriPath(uri_path::Error),
@@ -128,6 +159,27 @@ pub enum Error {
 }
 
 impl Option {
+    pub fn block1(&self) -> std::option::Option<&Block1> {
+        match self {
+            Option::Block1(block1) => Some(block1),
+            _ => None,
+        }
+    }
+
+    pub fn block2(&self) -> std::option::Option<&Block2> {
+        match self {
+            Option::Block2(block2) => Some(block2),
+            _ => None,
+        }
+    }
+
+    pub fn accept(&self) -> std::option::Option<&Accept> {
+        match self {
+            Option::Accept(accept) => Some(accept),
+            _ => None,
+        }
+    }
+
     pub fn content_format(&self) -> std::option::Option<&ContentFormat> {
         match self {
             Option::ContentFormat(content_format) => Some(content_format),
@@ -138,9 +190,12 @@ impl Option {
     pub fn decode(option: DecodedOption) -> Result<std::option::Option<Self>, Error> {
         let option = match option.number {
             n if n == Accept::number() => Accept::decode(option.values).map(Self::Accept)?,
+            n if n == Block1::number() => Block1::decode(option.values).map(Self::Block1)?,
+            n if n == Block2::number() => Block2::decode(option.values).map(Self::Block2)?,
             n if n == ContentFormat::number() => {
                 ContentFormat::decode(option.values).map(Self::ContentFormat)?
             }
+            n if n == Echo::number() => Echo::decode(option.values).map(Self::Echo)?,
             n if n == ETag::number() => ETag::decode(option.values).map(Self::ETag)?,
             n if n == IfMatch::number() => IfMatch::decode(option.values).map(Self::IfMatch)?,
             n if n == IfNoneMatch::number() => {
@@ -153,10 +208,16 @@ impl Option {
                 LocationQuery::decode(option.values).map(Self::LocationQuery)?
             }
             n if n == MaxAge::number() => MaxAge::decode(option.values).map(Self::MaxAge)?,
+            n if n == Observe::number() => Observe::decode(option.values).map(Self::Observe)?,
             n if n == ProxyScheme::number() => {
                 ProxyScheme::decode(option.values).map(Self::ProxyScheme)?
             }
             n if n == ProxyUri::number() => ProxyUri::decode(option.values).map(Self::ProxyUri)?,
+            n if n == QBlock1::number() => QBlock1::decode(option.values).map(Self::QBlock1)?,
+            n if n == QBlock2::number() => QBlock2::decode(option.values).map(Self::QBlock2)?,
+            n if n == RequestTag::number() => {
+                RequestTag::decode(option.values).map(Self::RequestTag)?
+            }
             n if n == Size1::number() => Size1::decode(option.values).map(Self::Size1)?,
             n if n == UriHost::number() => UriHost::decode(option.values).map(Self::UriHost)?,
             n if n == UriPath::number() => UriPath::decode(option.values).map(Self::UriPath)?,
@@ -176,23 +237,81 @@ impl Option {
         }
     }
 
+    pub fn custom(&self) -> std::option::Option<&DecodedOption> {
+        match self {
+            Option::Custom(custom) => Some(custom),
+            _ => None,
+        }
+    }
+
     pub fn encode(self, delta_sum: Delta) -> Vec<u8> {
         match self {
             Option::Accept(o) => o.encode(delta_sum),
+            Option::Block1(o) => o.encode(delta_sum),
+            Option::Block2(o) => o.encode(delta_sum),
             Option::ContentFormat(o) => o.encode(delta_sum),
+            Option::Echo(o) => o.encode(delta_sum),
             Option::ETag(o) => o.encode(delta_sum),
             Option::IfMatch(o) => o.encode(delta_sum),
             Option::IfNoneMatch(o) => o.encode(delta_sum),
             Option::LocationPath(o) => o.encode(delta_sum),
             Option::LocationQuery(o) => o.encode(delta_sum),
             Option::MaxAge(o) => o.encode(delta_sum),
+            Option::Observe(o) => o.encode(delta_sum),
             Option::ProxyScheme(o) => o.encode(delta_sum),
             Option::ProxyUri(o) => o.encode(delta_sum),
+            Option::QBlock1(o) => o.encode(delta_sum),
+            Option::QBlock2(o) => o.encode(delta_sum),
+            Option::RequestTag(o) => o.encode(delta_sum),
             Option::Size1(o) => o.encode(delta_sum),
             Option::UriHost(o) => o.encode(delta_sum),
             Option::UriPath(o) => o.encode(delta_sum),
             Option::UriPort(o) => o.encode(delta_sum),
             Option::UriQuery(o) => o.encode(delta_sum),
+            Option::Custom(o) => o.encode(delta_sum),
+        }
+    }
+
+    /// Size of the option once encoded, without encoding it, see [`Option::encode`].
+    pub fn encoded_size(&self, delta_sum: Delta) -> usize {
+        match self {
+            Option::Accept(o) => o.encoded_size(delta_sum),
+            Option::Block1(o) => o.encoded_size(delta_sum),
+            Option::Block2(o) => o.encoded_size(delta_sum),
+            Option::ContentFormat(o) => o.encoded_size(delta_sum),
+            Option::Echo(o) => o.encoded_size(delta_sum),
+            Option::ETag(o) => o.encoded_size(delta_sum),
+            Option::IfMatch(o) => o.encoded_size(delta_sum),
+            Option::IfNoneMatch(o) => o.encoded_size(delta_sum),
+            Option::LocationPath(o) => o.encoded_size(delta_sum),
+            Option::LocationQuery(o) => o.encoded_size(delta_sum),
+            Option::MaxAge(o) => o.encoded_size(delta_sum),
+            Option::Observe(o) => o.encoded_size(delta_sum),
+            Option::ProxyScheme(o) => o.encoded_size(delta_sum),
+            Option::ProxyUri(o) => o.encoded_size(delta_sum),
+            Option::QBlock1(o) => o.encoded_size(delta_sum),
+            Option::QBlock2(o) => o.encoded_size(delta_sum),
+            Option::RequestTag(o) => o.encoded_size(delta_sum),
+            Option::Size1(o) => o.encoded_size(delta_sum),
+            Option::UriHost(o) => o.encoded_size(delta_sum),
+            Option::UriPath(o) => o.encoded_size(delta_sum),
+            Option::UriPort(o) => o.encoded_size(delta_sum),
+            Option::UriQuery(o) => o.encoded_size(delta_sum),
+            Option::Custom(o) => o.encoded_size(delta_sum),
+        }
+    }
+
+    pub fn echo(&self) -> std::option::Option<&Echo> {
+        match self {
+            Option::Echo(echo) => Some(echo),
+            _ => None,
+        }
+    }
+
+    pub fn etag(&self) -> std::option::Option<&ETag> {
+        match self {
+            Option::ETag(etag) => Some(etag),
+            _ => None,
         }
     }
 
@@ -203,6 +322,14 @@ impl Option {
         }
     }
 
+    pub fn is_accept(&self) -> bool {
+        matches!(self, Option::Accept(_))
+    }
+
+    pub fn is_block2(&self) -> bool {
+        matches!(self, Option::Block2(_))
+    }
+
     pub fn is_content_format(&self) -> bool {
         match self {
             Option::ContentFormat(_) => true,
@@ -210,6 +337,14 @@ impl Option {
         }
     }
 
+    pub fn is_echo(&self) -> bool {
+        matches!(self, Option::Echo(_))
+    }
+
+    pub fn is_etag(&self) -> bool {
+        matches!(self, Option::ETag(_))
+    }
+
     pub fn is_if_match(&self) -> bool {
         match self {
             Option::IfMatch(_) => true,
@@ -217,6 +352,14 @@ impl Option {
         }
     }
 
+    pub fn is_if_none_match(&self) -> bool {
+        matches!(self, Option::IfNoneMatch(_))
+    }
+
+    pub fn is_location_path(&self) -> bool {
+        matches!(self, Option::LocationPath(_))
+    }
+
     pub fn is_max_age(&self) -> bool {
         match self {
             Option::MaxAge(_) => true,
@@ -224,6 +367,26 @@ impl Option {
         }
     }
 
+    pub fn is_observe(&self) -> bool {
+        matches!(self, Option::Observe(_))
+    }
+
+    pub fn is_block1(&self) -> bool {
+        matches!(self, Option::Block1(_))
+    }
+
+    pub fn is_q_block1(&self) -> bool {
+        matches!(self, Option::QBlock1(_))
+    }
+
+    pub fn is_q_block2(&self) -> bool {
+        matches!(self, Option::QBlock2(_))
+    }
+
+    pub fn is_request_tag(&self) -> bool {
+        matches!(self, Option::RequestTag(_))
+    }
+
     pub fn is_uri_host(&self) -> bool {
         match self {
             Option::UriHost(_) => true,
@@ -255,20 +418,55 @@ impl Option {
     pub fn number(&self) -> Number {
         match self {
             Option::Accept(_) => Accept::number(),
+            Option::Block1(_) => Block1::number(),
+            Option::Block2(_) => Block2::number(),
             Option::ContentFormat(_) => ContentFormat::number(),
+            Option::Echo(_) => Echo::number(),
             Option::ETag(_) => ETag::number(),
             Option::IfMatch(_) => IfMatch::number(),
             Option::IfNoneMatch(_) => IfNoneMatch::number(),
             Option::LocationPath(_) => LocationPath::number(),
             Option::LocationQuery(_) => LocationQuery::number(),
             Option::MaxAge(_) => MaxAge::number(),
+            Option::Observe(_) => Observe::number(),
             Option::ProxyScheme(_) => ProxyScheme::number(),
             Option::ProxyUri(_) => ProxyUri::number(),
+            Option::QBlock1(_) => QBlock1::number(),
+            Option::QBlock2(_) => QBlock2::number(),
+            Option::RequestTag(_) => RequestTag::number(),
             Option::Size1(_) => Size1::number(),
             Option::UriHost(_) => UriHost::number(),
             Option::UriPath(_) => UriPath::number(),
             Option::UriPort(_) => UriPort::number(),
             Option::UriQuery(_) => UriQuery::number(),
+            Option::Custom(o) => o.number,
+        }
+    }
+
+    /// The raw form of the option, which [`Option::decode`] turns back into the typed option.
+    pub fn into_decoded(self) -> DecodedOption {
+        if let Option::Custom(option) = self {
+            return option;
+        }
+
+        let number = self.number();
+        DecodedOptions::parse(&self.encode(Delta::from_value(0)))
+            .ok()
+            .and_then(|(_, options)| options.decoded_options().next())
+            .unwrap_or_else(|| DecodedOption::new(number, vec![]))
+    }
+
+    pub fn location_path(&self) -> std::option::Option<&LocationPath> {
+        match self {
+            Option::LocationPath(location_path) => Some(location_path),
+            _ => None,
+        }
+    }
+
+    pub fn location_query(&self) -> std::option::Option<&LocationQuery> {
+        match self {
+            Option::LocationQuery(location_query) => Some(location_query),
+            _ => None,
         }
     }
 
@@ -279,6 +477,41 @@ impl Option {
         }
     }
 
+    pub fn observe(&self) -> std::option::Option<&Observe> {
+        match self {
+            Option::Observe(observe) => Some(observe),
+            _ => None,
+        }
+    }
+
+    pub fn q_block1(&self) -> std::option::Option<&QBlock1> {
+        match self {
+            Option::QBlock1(q_block1) => Some(q_block1),
+            _ => None,
+        }
+    }
+
+    pub fn q_block2(&self) -> std::option::Option<&QBlock2> {
+        match self {
+            Option::QBlock2(q_block2) => Some(q_block2),
+            _ => None,
+        }
+    }
+
+    pub fn request_tag(&self) -> std::option::Option<&RequestTag> {
+        match self {
+            Option::RequestTag(request_tag) => Some(request_tag),
+            _ => None,
+        }
+    }
+
+    pub fn size1(&self) -> std::option::Option<&Size1> {
+        match self {
+            Option::Size1(size1) => Some(size1),
+            _ => None,
+        }
+    }
+
     pub fn uri_host(&self) -> std::option::Option<&UriHost> {
         match self {
             Option::UriHost(uri_host) => Some(uri_host),
@@ -308,12 +541,48 @@ impl Option {
     }
 }
 
+impl From<block1::Error> for Error {
+    fn from(value: block1::Error) -> Self {
+        Self::Block1(value)
+    }
+}
+
+impl From<echo::Error> for Error {
+    fn from(value: echo::Error) -> Self {
+        Self::Echo(value)
+    }
+}
+
+impl From<q_block1::Error> for Error {
+    fn from(value: q_block1::Error) -> Self {
+        Self::QBlock1(value)
+    }
+}
+
+impl From<q_block2::Error> for Error {
+    fn from(value: q_block2::Error) -> Self {
+        Self::QBlock2(value)
+    }
+}
+
+impl From<request_tag::Error> for Error {
+    fn from(value: request_tag::Error) -> Self {
+        Self::RequestTag(value)
+    }
+}
+
 impl From<accept::Error> for Error {
     fn from(value: accept::Error) -> Self {
         Self::Accept(value)
     }
 }
 
+impl From<block2::Error> for Error {
+    fn from(value: block2::Error) -> Self {
+        Self::Block2(value)
+    }
+}
+
 impl From<content_format::Error> for Error {
     fn from(value: content_format::Error) -> Self {
         Self::ContentFormat(value)
@@ -356,6 +625,12 @@ impl From<max_age::DecodeError> for Error {
     }
 }
 
+impl From<observe::Error> for Error {
+    fn from(value: observe::Error) -> Self {
+        Self::Observe(value)
+    }
+}
+
 impl From<proxy_scheme::Error> for Error {
     fn from(value: proxy_scheme::Error) -> Self {
         Self::ProxyScheme(value)
@@ -416,16 +691,75 @@ impl From<value::Error> for Error {
     }
 }
 
+impl std::fmt::Display for Error {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Error::Accept(error) => error.fmt(f),
+            Error::Block1(error) => error.fmt(f),
+            Error::Block2(error) => error.fmt(f),
+            Error::ContentFormat(error) => error.fmt(f),
+            Error::Echo(error) => error.fmt(f),
+            Error::ETag(error) => error.fmt(f),
+            Error::IfMatch(error) => error.fmt(f),
+            Error::IfNoneMatch(error) => error.fmt(f),
+            Error::LocationPath(error) => error.fmt(f),
+            Error::LocationQuery(error) => error.fmt(f),
+            Error::MaxAge(error) => error.fmt(f),
+            Error::Observe(error) => error.fmt(f),
+            Error::ProxyScheme(error) => error.fmt(f),
+            Error::ProxyUri(error) => error.fmt(f),
+            Error::QBlock1(error) => error.fmt(f),
+            Error::QBlock2(error) => error.fmt(f),
+            Error::RequestTag(error) => error.fmt(f),
+            Error::Size1(error) => error.fmt(f),
+            Error::UriHost(error) => error.fmt(f),
+            Error::UriPath(error) => error.fmt(f),
+            Error::UriPort(error) => error.fmt(f),
+            Error::UriQuery(error) => error.fmt(f),
+            Error::Unrecognized(number) => write!(f, "critical option {number} is not recognized"),
+            Error::Delta(error) => error.fmt(f),
+            Error::HeaderMissing => write!(f, "option header is missing"),
+            Error::Length(error) => error.fmt(f),
+            Error::Value(error) => error.fmt(f),
+        }
+    }
+}
+
+impl std::error::Error for Error {}
+
 #[cfg(test)]
 mod tests {
     use pretty_assertions::assert_eq;
     use rstest::rstest;
 
     use super::{
-        ContentFormat, Delta, EncodedOption, Option, UriHost, UriPath, UriPort, UriQuery, Value,
+        ContentFormat, DecodedOption, Delta, EncodedOption, Number, Option, UriHost, UriPath,
+        UriPort, UriQuery, Value,
     };
     use crate::codec::MediaType;
 
+    #[rstest]
+    #[case(Option::ContentFormat(MediaType::ApplicationJson.into()))]
+    #[case(Option::UriHost(UriHost::try_from("a").unwrap()))]
+    #[case(Option::UriPath(UriPath::from_value("a/b").unwrap()))]
+    #[case(Option::UriPort(5683.into()))]
+    fn into_decoded_decode(#[case] option: Option) {
+        assert_eq!(
+            Ok(Some(option.clone())),
+            Option::decode(option.into_decoded())
+        );
+    }
+
+    #[rstest]
+    fn into_decoded_custom() {
+        let echo = DecodedOption::new(
+            Number::from_value(252).unwrap(),
+            vec![Value::from_opaque(vec![1, 2]).unwrap()],
+        );
+
+        assert_eq!(echo.clone(), Option::Custom(echo).into_decoded());
+    }
+
     #[rstest]
     #[case(Option::ContentFormat(MediaType::ApplicationXml.into()), Some(ContentFormat::from(MediaType::ApplicationXml)))]
     #[case(Option::MaxAge(4567.into()), None)]
@@ -1,8 +1,9 @@
 pub mod cache_key;
 pub mod class;
 pub mod forward;
+pub mod registry;
 
-use self::{class::Class, forward::Forward};
+use self::{cache_key::CacheKey, class::Class, forward::Forward, registry::Registration};
 
 use super::delta::Delta;
 
@@ -48,6 +49,12 @@ impl Number {
         self.value - delta_sum
     }
 
+    /// Whether the option is left out of the cache key, i.e. it's safe to forward and its
+    /// NoCacheKey bits are all set.
+    pub const fn is_no_cache_key(&self) -> bool {
+        matches!(self.forward, Forward::Safe(CacheKey::NotSet))
+    }
+
     pub fn from_value(value: u16) -> Result<Self, Error> {
         Self::decode(Delta::from_value(value))
     }
@@ -58,6 +65,31 @@ impl Number {
             Err(_) => panic!("Invalid Number value"),
         }
     }
+
+    /// Name of the option as registered with IANA, e.g. `Uri-Path`, or `None` if the number is
+    /// unknown.
+    pub fn name(&self) -> std::option::Option<&'static str> {
+        self.registration().map(|registration| registration.name)
+    }
+
+    /// Entry of the option in the IANA registry, see [`registry::REGISTRY`].
+    pub fn registration(&self) -> std::option::Option<&'static Registration> {
+        registry::lookup(self.value.value())
+    }
+
+    /// Whether the option may occur more than once in a message, options that aren't registered
+    /// may not.
+    pub fn is_repeatable(&self) -> bool {
+        self.registration()
+            .is_some_and(|registration| registration.repeatable)
+    }
+
+    /// Whether a value of `length` bytes is within the registered length of the option, any
+    /// length is for options that aren't registered.
+    pub fn is_valid_length(&self, length: usize) -> bool {
+        self.registration()
+            .is_none_or(|registration| registration.is_valid_length(length))
+    }
 }
 
 impl PartialOrd for Number {
@@ -72,6 +104,22 @@ impl Ord for Number {
     }
 }
 
+impl std::fmt::Display for Number {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.value.value())
+    }
+}
+
+impl std::fmt::Display for Error {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Error::Reserved(delta) => write!(f, "option number {} is reserved", delta.value()),
+        }
+    }
+}
+
+impl std::error::Error for Error {}
+
 #[cfg(test)]
 mod tests {
     use std::cmp::Ordering;
@@ -92,6 +140,54 @@ mod tests {
         assert_eq!(expected, a.cmp(&b))
     }
 
+    #[rstest]
+    #[case(11, Some("Uri-Path"))]
+    #[case(14, Some("Max-Age"))]
+    #[case(31, Some("Q-Block2"))]
+    #[case(60, Some("Size1"))]
+    #[case(252, Some("Echo"))]
+    #[case(292, Some("Request-Tag"))]
+    #[case(258, Some("No-Response"))]
+    #[case(65000, None)]
+    fn name(#[case] value: u16, #[case] expected: Option<&str>) {
+        assert_eq!(expected, Number::from_value(value).unwrap().name())
+    }
+
+    #[rstest]
+    #[case(11, true)]
+    #[case(14, false)]
+    #[case(31, true)]
+    #[case(65000, false)]
+    fn is_repeatable(#[case] value: u16, #[case] expected: bool) {
+        assert_eq!(expected, Number::from_value_or_panic(value).is_repeatable())
+    }
+
+    #[rstest]
+    #[case(16, 1, true)]
+    #[case(16, 2, false)]
+    #[case(252, 0, false)]
+    #[case(65000, 300, true)]
+    fn is_valid_length(#[case] value: u16, #[case] length: usize, #[case] expected: bool) {
+        assert_eq!(
+            expected,
+            Number::from_value_or_panic(value).is_valid_length(length)
+        )
+    }
+
+    #[rstest]
+    #[case(11, false)]
+    #[case(14, false)]
+    #[case(28, true)]
+    #[case(60, true)]
+    #[case(252, true)]
+    #[case(292, false)]
+    fn is_no_cache_key(#[case] value: u16, #[case] expected: bool) {
+        assert_eq!(
+            expected,
+            Number::from_value_or_panic(value).is_no_cache_key()
+        )
+    }
+
     #[rstest]
     fn sort() {
         let mut numbers = vec![
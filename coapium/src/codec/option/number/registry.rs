@@ -0,0 +1,127 @@
+//! Options of the IANA [CoAP Option Numbers](https://www.iana.org/assignments/core-parameters/core-parameters.xhtml#option-numbers)
+//! registry, with the properties their specifications define.
+
+/// Format of the values of an option, RFC 7252 §3.2.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Format {
+    Empty,
+    Opaque,
+    Uint,
+    String,
+}
+
+/// Entry of the registry. Lengths are of a single value in bytes.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct Registration {
+    pub number: u16,
+    pub name: &'static str,
+    pub repeatable: bool,
+    pub min_length: usize,
+    pub max_length: usize,
+    pub format: Format,
+}
+
+impl Registration {
+    const fn new(
+        number: u16,
+        name: &'static str,
+        repeatable: bool,
+        min_length: usize,
+        max_length: usize,
+        format: Format,
+    ) -> Self {
+        Self {
+            number,
+            name,
+            repeatable,
+            min_length,
+            max_length,
+            format,
+        }
+    }
+
+    pub fn is_valid_length(&self, length: usize) -> bool {
+        (self.min_length..=self.max_length).contains(&length)
+    }
+}
+
+/// The registered options, ordered by number.
+pub const REGISTRY: [Registration; 29] = [
+    Registration::new(1, "If-Match", true, 0, 8, Format::Opaque),
+    Registration::new(3, "Uri-Host", false, 1, 255, Format::String),
+    Registration::new(4, "ETag", true, 1, 8, Format::Opaque),
+    Registration::new(5, "If-None-Match", false, 0, 0, Format::Empty),
+    Registration::new(6, "Observe", false, 0, 3, Format::Uint),
+    Registration::new(7, "Uri-Port", false, 0, 2, Format::Uint),
+    Registration::new(8, "Location-Path", true, 0, 255, Format::String),
+    Registration::new(9, "OSCORE", false, 0, 255, Format::Opaque),
+    Registration::new(11, "Uri-Path", true, 0, 255, Format::String),
+    Registration::new(12, "Content-Format", false, 0, 2, Format::Uint),
+    Registration::new(14, "Max-Age", false, 0, 4, Format::Uint),
+    Registration::new(15, "Uri-Query", true, 0, 255, Format::String),
+    Registration::new(16, "Hop-Limit", false, 1, 1, Format::Uint),
+    Registration::new(17, "Accept", false, 0, 2, Format::Uint),
+    Registration::new(19, "Q-Block1", false, 0, 3, Format::Uint),
+    Registration::new(20, "Location-Query", true, 0, 255, Format::String),
+    Registration::new(21, "EDHOC", false, 0, 0, Format::Empty),
+    Registration::new(23, "Block2", false, 0, 3, Format::Uint),
+    Registration::new(27, "Block1", false, 0, 3, Format::Uint),
+    Registration::new(28, "Size2", false, 0, 4, Format::Uint),
+    Registration::new(31, "Q-Block2", true, 0, 3, Format::Uint),
+    Registration::new(35, "Proxy-Uri", false, 1, 1034, Format::String),
+    Registration::new(39, "Proxy-Scheme", false, 1, 255, Format::String),
+    Registration::new(60, "Size1", false, 0, 4, Format::Uint),
+    Registration::new(252, "Echo", false, 1, 40, Format::Opaque),
+    Registration::new(258, "No-Response", false, 0, 1, Format::Uint),
+    Registration::new(292, "Request-Tag", true, 0, 8, Format::Opaque),
+    Registration::new(
+        2049,
+        "OCF-Accept-Content-Format-Version",
+        false,
+        2,
+        2,
+        Format::Uint,
+    ),
+    Registration::new(
+        2053,
+        "OCF-Content-Format-Version",
+        false,
+        2,
+        2,
+        Format::Uint,
+    ),
+];
+
+/// Registration of option `number`, `None` if it isn't registered.
+pub fn lookup(number: u16) -> Option<&'static Registration> {
+    REGISTRY
+        .binary_search_by_key(&number, |registration| registration.number)
+        .ok()
+        .map(|index| &REGISTRY[index])
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+    use rstest::rstest;
+
+    use super::{lookup, REGISTRY};
+
+    #[rstest]
+    fn ordered_by_number() {
+        assert!(REGISTRY
+            .windows(2)
+            .all(|pair| pair[0].number < pair[1].number));
+    }
+
+    #[rstest]
+    #[case(16, Some("Hop-Limit"))]
+    #[case(258, Some("No-Response"))]
+    #[case(2, None)]
+    fn lookup_name(#[case] number: u16, #[case] expected: Option<&str>) {
+        assert_eq!(
+            expected,
+            lookup(number).map(|registration| registration.name)
+        );
+    }
+}
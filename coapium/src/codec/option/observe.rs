@@ -0,0 +1,140 @@
+use crate::codec::parsing::single;
+
+use super::{
+    decoded_option::{self, DecodedOption},
+    number::Number,
+    value::Value,
+    Delta,
+};
+
+/// Observe option from [RFC 7641](https://datatracker.ietf.org/doc/html/rfc7641#section-2).
+///
+/// In a GET request the value registers (0) or deregisters (1) an observer, in a notification it
+/// is a 24 bit sequence number.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Observe {
+    value: Value,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Error {
+    SingleValue,
+    Format,
+}
+
+impl Observe {
+    pub const DEREGISTER: u32 = 1;
+    pub const MAX: u32 = 0x00ff_ffff;
+    pub const REGISTER: u32 = 0;
+    const NUMBER: u16 = 6;
+
+    pub fn decode(values: Vec<Value>) -> Result<Self, Error> {
+        let value = single(values).map_err(|_| Error::SingleValue)?;
+
+        let value = value.u32().map_err(|_| Error::Format)?;
+        if value > Self::MAX {
+            return Err(Error::Format);
+        }
+
+        Ok(Self::from_value(value))
+    }
+
+    pub fn deregister() -> Self {
+        Self::from_value(Self::DEREGISTER)
+    }
+
+    pub fn encode(self, delta_sum: Delta) -> Vec<u8> {
+        DecodedOption {
+            number: Self::number(),
+            values: vec![self.value],
+        }
+        .encode(delta_sum)
+    }
+
+    /// Size of the option once encoded, see [`Observe::encode`].
+    pub fn encoded_size(&self, delta_sum: Delta) -> usize {
+        decoded_option::encoded_size(Self::number(), delta_sum, [&self.value])
+    }
+
+    /// Creates the option from the lower 24 bits of the value.
+    pub fn from_value(value: u32) -> Self {
+        let value = value & Self::MAX;
+        let leading_zeros = (value.leading_zeros() / 8) as usize;
+
+        Self {
+            value: Value::from_opaque(value.to_be_bytes()[leading_zeros..].to_vec())
+                .expect("Observe value should be at most 3 bytes"),
+        }
+    }
+
+    pub fn is_deregister(&self) -> bool {
+        self.value() == Self::DEREGISTER
+    }
+
+    pub fn is_register(&self) -> bool {
+        self.value() == Self::REGISTER
+    }
+
+    pub fn number() -> Number {
+        Number::from_value_or_panic(Self::NUMBER)
+    }
+
+    pub fn register() -> Self {
+        Self::from_value(Self::REGISTER)
+    }
+
+    pub fn value(&self) -> u32 {
+        self.value
+            .u32()
+            .expect("Observe value should be a valid uint")
+    }
+}
+
+impl std::fmt::Display for Error {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Error::SingleValue => write!(f, "Observe(6) requires exactly one value"),
+            Error::Format => write!(f, "Observe(6) value is not a valid 24 bit uint"),
+        }
+    }
+}
+
+impl std::error::Error for Error {}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+    use rstest::rstest;
+
+    use super::{Delta, Error, Number, Observe, Value};
+
+    #[rstest]
+    #[case(vec![Value::from_opaque(vec![]).unwrap()],                                        Ok(Observe::register()))]
+    #[case(vec![Value::from_opaque(vec![1]).unwrap()],                                       Ok(Observe::deregister()))]
+    #[case(vec![Value::from_opaque(vec![1, 2, 3]).unwrap()],                                 Ok(Observe::from_value(0x01_02_03)))]
+    #[case(vec![],                                                                           Err(Error::SingleValue))]
+    #[case(vec![Value::from_opaque(vec![1, 2, 3, 4]).unwrap()],                              Err(Error::Format))]
+    #[case(vec![Value::from_opaque(vec![1]).unwrap(), Value::from_opaque(vec![2]).unwrap()], Err(Error::SingleValue))]
+    fn decode(#[case] values: Vec<Value>, #[case] expected: Result<Observe, Error>) {
+        assert_eq!(expected, Observe::decode(values));
+    }
+
+    #[rstest]
+    #[case(Observe::register(),            vec![0b0110_0000])]
+    #[case(Observe::from_value(0x01_0203), vec![0b0110_0011, 1, 2, 3])]
+    fn encode(#[case] observe: Observe, #[case] expected: Vec<u8>) {
+        assert_eq!(expected, observe.encode(Delta::from_value(0)))
+    }
+
+    #[rstest]
+    #[case(0x0100_0001, 1)]
+    #[case(0x00ff_ffff, 0x00ff_ffff)]
+    fn from_value(#[case] value: u32, #[case] expected: u32) {
+        assert_eq!(expected, Observe::from_value(value).value())
+    }
+
+    #[rstest]
+    fn number() {
+        assert_eq!(Number::from_value(6).unwrap(), Observe::number())
+    }
+}
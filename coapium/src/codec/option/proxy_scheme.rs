@@ -1,4 +1,9 @@
-use super::{decoded_option::DecodedOption, number::Number, value::Value, Delta};
+use super::{
+    decoded_option::{self, DecodedOption},
+    number::Number,
+    value::Value,
+    Delta,
+};
 
 #[derive(Clone, Debug, PartialEq)]
 pub struct ProxyScheme {
@@ -42,11 +47,33 @@ impl ProxyScheme {
         .encode(delta_sum)
     }
 
+    /// Size of the option once encoded, see [`ProxyScheme::encode`].
+    pub fn encoded_size(&self, delta_sum: Delta) -> usize {
+        decoded_option::encoded_size(Self::number(), delta_sum, [&self.value])
+    }
+
     pub fn number() -> Number {
         Number::from_value_or_panic(39)
     }
 }
 
+impl std::fmt::Display for Error {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Error::Format => write!(f, "Proxy-Scheme(39) value is not a valid string"),
+            Error::SingleValue => write!(f, "Proxy-Scheme(39) requires exactly one value"),
+            Error::Length(length) => write!(
+                f,
+                "Proxy-Scheme(39) length {length} is outside {}..={}",
+                ProxyScheme::MIN_LENGTH,
+                ProxyScheme::MAX_LENGTH
+            ),
+        }
+    }
+}
+
+impl std::error::Error for Error {}
+
 #[cfg(test)]
 mod tests {
     use super::Error;
@@ -0,0 +1,126 @@
+use crate::codec::parsing::single;
+
+use super::{
+    block2,
+    decoded_option::{self, DecodedOption},
+    number::Number,
+    value::Value,
+    Block2, Delta,
+};
+
+/// Q-Block1 option from [RFC 9177](https://datatracker.ietf.org/doc/html/rfc9177#section-4),
+/// the Block1 variant of quick block transfers where request blocks are sent without waiting for
+/// each one to be acknowledged.
+///
+/// The value is packed the same way as [`Block2`].
+#[derive(Clone, Debug, PartialEq)]
+pub struct QBlock1 {
+    block: Block2,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Error {
+    SingleValue,
+    Format,
+    Num(u32),
+    Szx(u8),
+}
+
+impl QBlock1 {
+    const NUMBER: u16 = 19;
+
+    /// The block number, whether more blocks follow and the block size of this block.
+    pub fn block(&self) -> &Block2 {
+        &self.block
+    }
+
+    pub fn decode(values: Vec<Value>) -> Result<Self, Error> {
+        let value = single(values).map_err(|_| Error::SingleValue)?;
+
+        Block2::decode_value(value)
+            .map(|block| Self { block })
+            .map_err(Error::from)
+    }
+
+    pub fn encode(self, delta_sum: Delta) -> Vec<u8> {
+        DecodedOption {
+            number: Self::number(),
+            values: vec![self.block.encode_value()],
+        }
+        .encode(delta_sum)
+    }
+
+    /// Size of the option once encoded, see [`QBlock1::encode`].
+    pub fn encoded_size(&self, delta_sum: Delta) -> usize {
+        decoded_option::encoded_size(Self::number(), delta_sum, [&self.block.encode_value()])
+    }
+
+    pub fn new(num: u32, more: bool, szx: u8) -> Result<Self, Error> {
+        Block2::new(num, more, szx)
+            .map(|block| Self { block })
+            .map_err(Error::from)
+    }
+
+    pub fn number() -> Number {
+        Number::from_value_or_panic(Self::NUMBER)
+    }
+}
+
+impl From<block2::Error> for Error {
+    fn from(error: block2::Error) -> Self {
+        match error {
+            block2::Error::SingleValue => Error::SingleValue,
+            block2::Error::Format => Error::Format,
+            block2::Error::Num(num) => Error::Num(num),
+            block2::Error::Szx(szx) => Error::Szx(szx),
+        }
+    }
+}
+
+impl std::fmt::Display for Error {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Error::SingleValue => write!(f, "Q-Block1(19) requires exactly one value"),
+            Error::Format => write!(f, "Q-Block1(19) value is not a valid 24 bit uint"),
+            Error::Num(num) => write!(
+                f,
+                "Q-Block1(19) block number {num} is larger than {}",
+                Block2::MAX_NUM
+            ),
+            Error::Szx(szx) => write!(
+                f,
+                "Q-Block1(19) SZX {szx} is larger than {}",
+                Block2::MAX_SZX
+            ),
+        }
+    }
+}
+
+impl std::error::Error for Error {}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+    use rstest::rstest;
+
+    use super::{Delta, Error, Number, QBlock1, Value};
+
+    #[rstest]
+    #[case(vec![Value::from_opaque(vec![0b0001_1010]).unwrap()], Ok(QBlock1::new(1, true, 2).unwrap()))]
+    #[case(vec![Value::from_opaque(vec![0b0000_0111]).unwrap()], Err(Error::Szx(7)))]
+    #[case(vec![],                                               Err(Error::SingleValue))]
+    fn decode(#[case] values: Vec<Value>, #[case] expected: Result<QBlock1, Error>) {
+        assert_eq!(expected, QBlock1::decode(values));
+    }
+
+    #[rstest]
+    #[case(QBlock1::new(1, true, 2).unwrap(), vec![0b1101_0001, 6, 0b0001_1010])]
+    fn encode(#[case] q_block1: QBlock1, #[case] expected: Vec<u8>) {
+        assert_eq!(expected, q_block1.encode(Delta::from_value(0)))
+    }
+
+    #[rstest]
+    fn number() {
+        assert_eq!(Number::from_value(19).unwrap(), QBlock1::number())
+    }
+}
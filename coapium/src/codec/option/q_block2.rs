@@ -0,0 +1,159 @@
+use super::{
+    block2,
+    decoded_option::{self, DecodedOption},
+    number::Number,
+    value::Value,
+    Block2, Delta,
+};
+
+/// Q-Block2 option from [RFC 9177](https://datatracker.ietf.org/doc/html/rfc9177#section-4),
+/// the Block2 variant of quick block transfers where all response blocks are sent as a burst of
+/// non-confirmable messages.
+///
+/// Each value is packed the same way as [`Block2`]. The option is repeatable so a client can ask
+/// for all blocks it is missing in a single request.
+#[derive(Clone, Debug, PartialEq)]
+pub struct QBlock2 {
+    blocks: Vec<Block2>,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Error {
+    Empty,
+    Format,
+    Num(u32),
+    Szx(u8),
+}
+
+impl QBlock2 {
+    const NUMBER: u16 = 31;
+
+    pub fn blocks(&self) -> &[Block2] {
+        &self.blocks
+    }
+
+    pub fn decode(values: Vec<Value>) -> Result<Self, Error> {
+        if values.is_empty() {
+            return Err(Error::Empty);
+        }
+
+        values
+            .into_iter()
+            .map(|value| Block2::decode_value(value).map_err(Error::from))
+            .collect::<Result<_, _>>()
+            .map(|blocks| Self { blocks })
+    }
+
+    pub fn encode(self, delta_sum: Delta) -> Vec<u8> {
+        DecodedOption {
+            number: Self::number(),
+            values: self.blocks.iter().map(Block2::encode_value).collect(),
+        }
+        .encode(delta_sum)
+    }
+
+    /// Size of the option once encoded, see [`QBlock2::encode`].
+    pub fn encoded_size(&self, delta_sum: Delta) -> usize {
+        decoded_option::encoded_size(
+            Self::number(),
+            delta_sum,
+            &self
+                .blocks
+                .iter()
+                .map(Block2::encode_value)
+                .collect::<Vec<_>>(),
+        )
+    }
+
+    pub fn from_blocks(blocks: Vec<Block2>) -> Result<Self, Error> {
+        if blocks.is_empty() {
+            return Err(Error::Empty);
+        }
+
+        Ok(Self { blocks })
+    }
+
+    pub fn new(num: u32, more: bool, szx: u8) -> Result<Self, Error> {
+        Block2::new(num, more, szx)
+            .map(|block| Self {
+                blocks: vec![block],
+            })
+            .map_err(Error::from)
+    }
+
+    pub fn number() -> Number {
+        Number::from_value_or_panic(Self::NUMBER)
+    }
+}
+
+impl From<block2::Error> for Error {
+    fn from(error: block2::Error) -> Self {
+        match error {
+            block2::Error::SingleValue => Error::Empty,
+            block2::Error::Format => Error::Format,
+            block2::Error::Num(num) => Error::Num(num),
+            block2::Error::Szx(szx) => Error::Szx(szx),
+        }
+    }
+}
+
+impl std::fmt::Display for Error {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Error::Empty => write!(f, "Q-Block2(31) requires at least one value"),
+            Error::Format => write!(f, "Q-Block2(31) value is not a valid 24 bit uint"),
+            Error::Num(num) => write!(
+                f,
+                "Q-Block2(31) block number {num} is larger than {}",
+                Block2::MAX_NUM
+            ),
+            Error::Szx(szx) => write!(
+                f,
+                "Q-Block2(31) SZX {szx} is larger than {}",
+                Block2::MAX_SZX
+            ),
+        }
+    }
+}
+
+impl std::error::Error for Error {}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+    use rstest::rstest;
+
+    use super::{Block2, Delta, Error, Number, QBlock2, Value};
+
+    #[rstest]
+    #[case(vec![Value::from_opaque(vec![0b0001_1010]).unwrap()], Ok(QBlock2::new(1, true, 2).unwrap()))]
+    #[case(
+        vec![Value::from_opaque(vec![0b0001_0000]).unwrap(), Value::from_opaque(vec![0b0011_0000]).unwrap()],
+        Ok(QBlock2::from_blocks(vec![Block2::new(1, false, 0).unwrap(), Block2::new(3, false, 0).unwrap()]).unwrap())
+    )]
+    #[case(vec![Value::from_opaque(vec![0b0000_0111]).unwrap()], Err(Error::Szx(7)))]
+    #[case(vec![],                                               Err(Error::Empty))]
+    fn decode(#[case] values: Vec<Value>, #[case] expected: Result<QBlock2, Error>) {
+        assert_eq!(expected, QBlock2::decode(values));
+    }
+
+    #[rstest]
+    #[case(QBlock2::new(1, true, 2).unwrap(), vec![0b1101_0001, 18, 0b0001_1010])]
+    #[case(
+        QBlock2::from_blocks(vec![Block2::new(1, false, 0).unwrap(), Block2::new(3, false, 0).unwrap()]).unwrap(),
+        vec![0b1101_0001, 18, 0b0001_0000, 0b0000_0001, 0b0011_0000]
+    )]
+    fn encode(#[case] q_block2: QBlock2, #[case] expected: Vec<u8>) {
+        assert_eq!(expected, q_block2.encode(Delta::from_value(0)))
+    }
+
+    #[rstest]
+    fn from_blocks_rejects_empty() {
+        assert_eq!(Err(Error::Empty), QBlock2::from_blocks(vec![]))
+    }
+
+    #[rstest]
+    fn number() {
+        assert_eq!(Number::from_value(31).unwrap(), QBlock2::number())
+    }
+}
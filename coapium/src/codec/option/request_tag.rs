@@ -0,0 +1,127 @@
+use super::{
+    decoded_option::{self, DecodedOption},
+    number::Number,
+    value::Value,
+    Delta,
+};
+
+/// Request-Tag option from [RFC 9175](https://datatracker.ietf.org/doc/html/rfc9175#section-3),
+/// tells the blocks of one block-wise operation apart from those of another on the same resource.
+#[derive(Clone, Debug, PartialEq)]
+pub struct RequestTag {
+    values: Vec<Value>,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Error {
+    Length(usize),
+}
+
+impl RequestTag {
+    const MAX_LENGTH: usize = 8;
+    const NUMBER: u16 = 292;
+
+    fn decode_value(value: Value) -> Result<Value, Error> {
+        if value.len() > Self::MAX_LENGTH {
+            return Err(Error::Length(value.len()));
+        }
+
+        Ok(value)
+    }
+
+    pub fn decode(values: Vec<Value>) -> Result<Self, Error> {
+        values
+            .into_iter()
+            .map(Self::decode_value)
+            .collect::<Result<_, _>>()
+            .map(|values| Self { values })
+    }
+
+    pub fn encode(self, delta_sum: Delta) -> Vec<u8> {
+        DecodedOption {
+            number: Self::number(),
+            values: self.values,
+        }
+        .encode(delta_sum)
+    }
+
+    /// Size of the option once encoded, see [`RequestTag::encode`].
+    pub fn encoded_size(&self, delta_sum: Delta) -> usize {
+        decoded_option::encoded_size(Self::number(), delta_sum, &self.values)
+    }
+
+    pub fn from_value(value: Vec<u8>) -> Result<Self, Error> {
+        let length = value.len();
+        let value = Value::from_opaque(value).map_err(|_| Error::Length(length))?;
+        Self::decode(vec![value])
+    }
+
+    pub fn number() -> Number {
+        Number::from_value_or_panic(Self::NUMBER)
+    }
+
+    pub fn values(&self) -> Vec<Vec<u8>> {
+        self.values
+            .iter()
+            .cloned()
+            .map(|value| value.opaque())
+            .collect()
+    }
+}
+
+impl TryFrom<Vec<u8>> for RequestTag {
+    type Error = Error;
+
+    fn try_from(value: Vec<u8>) -> Result<Self, Self::Error> {
+        Self::from_value(value)
+    }
+}
+
+impl std::fmt::Display for Error {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Error::Length(length) => write!(
+                f,
+                "Request-Tag(292) value length {length} exceeds {} bytes",
+                RequestTag::MAX_LENGTH
+            ),
+        }
+    }
+}
+
+impl std::error::Error for Error {}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+    use rstest::rstest;
+
+    use super::{Delta, Error, Number, RequestTag, Value};
+
+    #[rstest]
+    #[case(vec![Value::from_opaque(vec![]).unwrap()], Ok(RequestTag { values: vec![Value::from_opaque(vec![]).unwrap()] }))]
+    #[case(vec![Value::from_opaque(vec![1, 2]).unwrap()], Ok(RequestTag { values: vec![Value::from_opaque(vec![1, 2]).unwrap()] }))]
+    #[case(vec![Value::from_opaque(vec![1; 9]).unwrap()], Err(Error::Length(9)))]
+    fn decode(#[case] values: Vec<Value>, #[case] expected: Result<RequestTag, Error>) {
+        assert_eq!(expected, RequestTag::decode(values));
+    }
+
+    #[rstest]
+    #[case(RequestTag::from_value(vec![0x01, 0x02]).unwrap(), vec![0b1110_0010, 0, 23, 0x01, 0x02])]
+    fn encode(#[case] request_tag: RequestTag, #[case] expected: Vec<u8>) {
+        assert_eq!(expected, request_tag.encode(Delta::from_value(0)))
+    }
+
+    #[rstest]
+    fn number() {
+        assert_eq!(Number::from_value(292).unwrap(), RequestTag::number())
+    }
+
+    #[rstest]
+    fn values() {
+        assert_eq!(
+            vec![vec![7, 8]],
+            RequestTag::from_value(vec![7, 8]).unwrap().values()
+        )
+    }
+}
@@ -1,6 +1,11 @@
 use crate::codec::parsing::single;
 
-use super::{decoded_option::DecodedOption, number::Number, value::Value, Delta};
+use super::{
+    decoded_option::{self, DecodedOption},
+    number::Number,
+    value::Value,
+    Delta,
+};
 
 #[derive(Clone, Debug, PartialEq)]
 pub struct Size1 {
@@ -32,11 +37,39 @@ impl Size1 {
         .encode(delta_sum)
     }
 
+    /// Size of the option once encoded, see [`Size1::encode`].
+    pub fn encoded_size(&self, delta_sum: Delta) -> usize {
+        decoded_option::encoded_size(Self::number(), delta_sum, [&self.value])
+    }
+
+    pub fn new(value: u32) -> Self {
+        Self {
+            value: Value::from_u32(value),
+        }
+    }
+
     pub fn number() -> Number {
         Number::from_value_or_panic(60)
     }
+
+    pub fn value(&self) -> u32 {
+        self.value
+            .u32()
+            .expect("Size1 value should be a valid uint")
+    }
 }
 
+impl std::fmt::Display for Error {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Error::SingleValue => write!(f, "Size1(60) requires exactly one value"),
+            Error::Format => write!(f, "Size1(60) value is not a valid uint"),
+        }
+    }
+}
+
+impl std::error::Error for Error {}
+
 #[cfg(test)]
 mod tests {
     use pretty_assertions::assert_eq;
@@ -54,4 +87,11 @@ mod tests {
     fn decode(#[case] values: Vec<Value>, #[case] expected: Result<Size1, Error>) {
         assert_eq!(expected, Size1::decode(values));
     }
+
+    #[rstest]
+    #[case(vec![Value::from_opaque(vec![]).unwrap()],     0)]
+    #[case(vec![Value::from_opaque(vec![1, 2]).unwrap()], 258)]
+    fn value(#[case] values: Vec<Value>, #[case] expected: u32) {
+        assert_eq!(expected, Size1::decode(values).unwrap().value());
+    }
 }
@@ -1,10 +1,16 @@
-use std::fmt::Display;
-
-use url::Url;
+use std::{
+    fmt::Display,
+    net::{IpAddr, Ipv6Addr},
+};
 
 use crate::codec::parsing::single_or_err;
 
-use super::{decoded_option::DecodedOption, number::Number, value::Value, Delta};
+use super::{
+    decoded_option::{self, DecodedOption},
+    number::Number,
+    value::Value,
+    Delta,
+};
 
 #[derive(Clone, Debug, PartialEq)]
 pub struct UriHost {
@@ -20,7 +26,10 @@ pub enum DecodeError {
 #[derive(Clone, Copy, Debug, PartialEq)]
 pub enum ValueError {
     Format,
+    /// A character that can't be part of a host, like the `:` of a port.
+    Character(char),
     Length(usize),
+    Whitespace,
 }
 
 impl UriHost {
@@ -40,32 +49,68 @@ impl UriHost {
         .encode(delta_sum)
     }
 
+    /// Size of the option once encoded, see [`UriHost::encode`].
+    pub fn encoded_size(&self, delta_sum: Delta) -> usize {
+        decoded_option::encoded_size(Self::number(), delta_sum, [&self.host])
+    }
+
+    /// Uri-Host from a host as it appears in a URI. Registered names are lowercased and IPv6
+    /// addresses are kept in brackets in their canonical form, with or without brackets given, so
+    /// equal hosts compare equal however they were written or received.
     pub fn from_value<S: Into<String>>(value: S) -> Result<Self, ValueError> {
-        to_url(value.into())
-            //.and_then(validate_length) TODO: validate that only host was given
-            .and_then(to_host)
+        normalize(value.into())
             .and_then(validate_length)
             .and_then(to_value)
             .map(to_uri_host)
     }
 
+    /// Address of a host that is an IP literal, `None` for a registered name.
+    pub fn ip_addr(&self) -> Option<IpAddr> {
+        let host = self.to_string();
+        host.trim_start_matches('[')
+            .trim_end_matches(']')
+            .parse()
+            .ok()
+    }
+
     pub fn number() -> Number {
         Number::from_value_or_panic(Self::NUMBER)
     }
 }
 
-fn to_host(url: Url) -> Result<String, ValueError> {
-    url.host_str()
-        .map(ToOwned::to_owned)
-        .ok_or(ValueError::Format)
+/// Characters of a registered name, RFC 3986 §3.2.2, the `%` of percent-encoded octets included.
+fn is_host_char(c: char) -> bool {
+    c.is_ascii_alphanumeric() || "-._~!$&'()*+,;=%".contains(c)
 }
 
-fn to_string(value: Value) -> Result<String, ValueError> {
-    value.string().map_err(|_| ValueError::Format)
+/// Host in its normalized form, RFC 3986 §6.2.2.1.
+fn normalize(host: String) -> Result<String, ValueError> {
+    if host.is_empty() {
+        return Err(ValueError::Format);
+    }
+    if host.chars().any(char::is_whitespace) {
+        return Err(ValueError::Whitespace);
+    }
+
+    if let Some(literal) = host.strip_prefix('[') {
+        return literal
+            .strip_suffix(']')
+            .and_then(|address| address.parse::<Ipv6Addr>().ok())
+            .map(|address| format!("[{address}]"))
+            .ok_or(ValueError::Format);
+    }
+    if let Ok(address) = host.parse::<Ipv6Addr>() {
+        return Ok(format!("[{address}]"));
+    }
+
+    match host.chars().find(|c| !is_host_char(*c)) {
+        Some(c) => Err(ValueError::Character(c)),
+        None => Ok(host.to_ascii_lowercase()),
+    }
 }
 
-fn to_url(value: String) -> Result<Url, ValueError> {
-    Url::parse(&format!("coap://{}", value)).map_err(|_| ValueError::Format)
+fn to_string(value: Value) -> Result<String, ValueError> {
+    value.string().map_err(|_| ValueError::Format)
 }
 
 fn to_uri_host(value: Value) -> UriHost {
@@ -104,8 +149,38 @@ impl TryFrom<&str> for UriHost {
     }
 }
 
+impl std::fmt::Display for DecodeError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            DecodeError::SingleValue => write!(f, "Uri-Host(3) requires exactly one value"),
+            DecodeError::Value(error) => error.fmt(f),
+        }
+    }
+}
+
+impl std::error::Error for DecodeError {}
+
+impl std::fmt::Display for ValueError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ValueError::Format => write!(f, "Uri-Host(3) value is not a valid host"),
+            ValueError::Character(c) => write!(f, "Uri-Host(3) can't contain `{c}`"),
+            ValueError::Length(length) => write!(
+                f,
+                "Uri-Host(3) length {length} is outside 1..={}",
+                UriHost::MAX_LENGTH
+            ),
+            ValueError::Whitespace => write!(f, "Uri-Host(3) can't contain whitespace"),
+        }
+    }
+}
+
+impl std::error::Error for ValueError {}
+
 #[cfg(test)]
 mod tests {
+    use std::net::IpAddr;
+
     use pretty_assertions::assert_eq;
     use rstest::rstest;
 
@@ -161,8 +236,26 @@ mod tests {
         "[2001:db8:aaaa:bbbb:cccc:dddd:eeee:aaaa]", 
         Ok(UriHost { host: Value::from_str("[2001:db8:aaaa:bbbb:cccc:dddd:eeee:aaaa]").unwrap() })
     )]
+    #[case(
+        "[2001:DB8::0:1]",
+        Ok(UriHost { host: Value::from_str("[2001:db8::1]").unwrap() })
+    )]
+    #[case(
+        "::1",
+        Ok(UriHost { host: Value::from_str("[::1]").unwrap() })
+    )]
+    #[case(
+        "Coap.Example.ORG",
+        Ok(UriHost { host: Value::from_str("coap.example.org").unwrap() })
+    )]
     #[case("", Err(ValueError::Format))]
-    #[case("this is not a host", Err(ValueError::Format))]
+    #[case("[::1", Err(ValueError::Format))]
+    #[case("[example.com]", Err(ValueError::Format))]
+    #[case("this is not a host", Err(ValueError::Whitespace))]
+    #[case("example.com\t", Err(ValueError::Whitespace))]
+    #[case("example.com:5683", Err(ValueError::Character(':')))]
+    #[case("example.com/a", Err(ValueError::Character('/')))]
+    #[case("bücher.example", Err(ValueError::Character('ü')))]
     #[case(
         &format!("{}.com", "a".repeat(255)), 
         Err(ValueError::Length(259))
@@ -171,6 +264,25 @@ mod tests {
         assert_eq!(expected, UriHost::from_value(value));
     }
 
+    #[rstest]
+    #[case("[::1]")]
+    #[case("::1")]
+    #[case("[0:0:0:0:0:0:0:1]")]
+    fn ipv6_literal_forms_agree(#[case] value: &str) {
+        let decoded = UriHost::decode(vec![Value::from_str(value).unwrap()]).unwrap();
+
+        assert_eq!(UriHost::try_from("[::1]").unwrap(), decoded);
+        assert_eq!("[::1]", decoded.to_string());
+    }
+
+    #[rstest]
+    #[case("[::1]", Some("::1".parse().unwrap()))]
+    #[case("127.0.0.1", Some("127.0.0.1".parse().unwrap()))]
+    #[case("example.com", None)]
+    fn ip_addr(#[case] value: &str, #[case] expected: Option<IpAddr>) {
+        assert_eq!(expected, UriHost::from_value(value).unwrap().ip_addr());
+    }
+
     #[rstest]
     fn number() {
         assert_eq!(Number::from_value(3).unwrap(), UriHost::number())
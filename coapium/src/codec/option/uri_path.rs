@@ -1,6 +1,11 @@
-use url::Url;
+use crate::codec::percent;
 
-use super::{decoded_option::DecodedOption, number::Number, value::Value, Delta};
+use super::{
+    decoded_option::{self, DecodedOption},
+    number::Number,
+    value::Value,
+    Delta,
+};
 
 #[derive(Clone, Debug, PartialEq)]
 pub struct UriPath {
@@ -9,6 +14,8 @@ pub struct UriPath {
 
 #[derive(Clone, Copy, Debug, PartialEq)]
 pub enum Error {
+    /// A segment is `.` or `..`, which RFC 7252 doesn't allow in a Uri-Path option.
+    DotSegment,
     Format,
     Length(usize),
 }
@@ -18,16 +25,25 @@ impl UriPath {
     const NUMBER: u16 = 11;
 
     pub fn decode(encoded_options: Vec<Value>) -> Result<Self, Error> {
+        // The root path is sent without any Uri-Path option, but accept a single empty one too
+        if let [Value::Empty] = encoded_options.as_slice() {
+            return Ok(UriPath { segments: vec![] });
+        }
+
         let segments = encoded_options
             .into_iter()
-            .map(|v| v.string())
-            .collect::<Result<Vec<_>, _>>()
-            .map_err(|_| Error::Format)?;
+            .map(validate)
+            .collect::<Result<Vec<_>, _>>()?;
 
-        Self::from_value(segments.join("/"))
+        Ok(UriPath { segments })
     }
 
+    /// Encodes one option per segment, the root path is encoded without any option.
     pub fn encode(self, delta_sum: Delta) -> Vec<u8> {
+        if !self.segments.iter().any(Value::is_bytes) {
+            return vec![];
+        }
+
         DecodedOption {
             number: Self::number(),
             values: self.segments,
@@ -35,32 +51,65 @@ impl UriPath {
         .encode(delta_sum)
     }
 
-    pub fn from_value<S: AsRef<str>>(value: S) -> Result<Self, Error> {
-        let value = value.as_ref();
+    /// Size of the option once encoded, see [`UriPath::encode`].
+    pub fn encoded_size(&self, delta_sum: Delta) -> usize {
+        if !self.segments.iter().any(Value::is_bytes) {
+            return 0;
+        }
 
-        let url = if value.starts_with("/") {
-            format!("coap://127.0.0.1{}", value)
-        } else {
-            format!("coap://127.0.0.1/{}", value)
-        };
+        decoded_option::encoded_size(Self::number(), delta_sum, &self.segments)
+    }
 
-        let url = Url::parse(&url).map_err(|_| Error::Format)?;
+    pub fn from_value<S: AsRef<str>>(value: S) -> Result<Self, Error> {
+        let value = value.as_ref();
 
-        if url.fragment().is_some() {
-            return Err(Error::Format);
+        if value.split('/').any(is_dot_segment) {
+            return Err(Error::DotSegment);
         }
 
-        if url.query().is_some() {
+        // Parsed the way a URL parser parses a path: tabs and newlines are dropped, trailing
+        // controls and spaces trimmed, and a query or fragment isn't a path.
+        let absolute = value.starts_with('/');
+        let value: String = value
+            .trim_end_matches(|c: char| c <= ' ')
+            .chars()
+            .filter(|c| !matches!(c, '\t' | '\n' | '\r'))
+            .collect();
+        if value.contains(['?', '#']) {
             return Err(Error::Format);
         }
 
-        let Some(path_segments) = url.path_segments() else {
-            return Ok(UriPath { segments: vec![] });
+        let path = match absolute {
+            true => &value[1..],
+            false => &value,
         };
+        let path_segments: Vec<&str> = path.split('/').collect();
+        let mut encoded: Vec<String> = vec![];
+        for (index, segment) in path_segments.iter().enumerate() {
+            let is_last = index == path_segments.len() - 1;
+            let segment = percent::encode_path_segment(segment);
 
-        let segments = path_segments
-            .into_iter()
-            .map(to_value)
+            // Percent-encoded dot segments, and ones split by dropped tabs, get past the check
+            // above.
+            match segment.to_ascii_lowercase().as_str() {
+                ".." | "%2e%2e" | "%2e." | ".%2e" => {
+                    encoded.pop();
+                    if is_last {
+                        encoded.push(String::new());
+                    }
+                }
+                "." | "%2e" if is_last => encoded.push(String::new()),
+                "." | "%2e" => {}
+                _ => encoded.push(segment),
+            }
+        }
+        if encoded.is_empty() {
+            encoded.push(String::new());
+        }
+
+        let segments = encoded
+            .iter()
+            .map(|segment| to_value(segment))
             .collect::<Result<Vec<_>, _>>()?
             .into_iter()
             .enumerate()
@@ -71,9 +120,52 @@ impl UriPath {
         Ok(UriPath { segments })
     }
 
+    /// Creates the path from the path of a URL, removing `.` and `..` segments the way RFC 3986
+    /// does and percent-decoding each segment.
+    pub fn from_url_path<S: AsRef<str>>(path: S) -> Result<Self, Error> {
+        let path = path.as_ref();
+        let path = path.strip_prefix('/').unwrap_or(path);
+
+        let mut segments: Vec<&str> = vec![];
+        for segment in path.split('/') {
+            match segment {
+                "." => {}
+                ".." => {
+                    segments.pop();
+                }
+                segment => segments.push(segment),
+            }
+        }
+        if let [""] = segments.as_slice() {
+            segments.clear();
+        }
+
+        let segments = segments
+            .into_iter()
+            .map(|segment| {
+                let segment = percent::decode(segment).map_err(|_| Error::Format)?;
+                validate(Value::from_str(&segment).map_err(|_| Error::Format)?)
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+
+        Ok(UriPath { segments })
+    }
+
     pub fn number() -> Number {
         Number::from_value_or_panic(Self::NUMBER)
     }
+
+    pub fn segments(&self) -> Vec<String> {
+        self.segments
+            .iter()
+            .cloned()
+            .map(|segment| {
+                segment
+                    .string()
+                    .expect("Uri-Path segment should be a string")
+            })
+            .collect()
+    }
 }
 
 fn is_tail_segment(element: &(usize, Value)) -> bool {
@@ -83,6 +175,24 @@ fn is_tail_segment(element: &(usize, Value)) -> bool {
     }
 }
 
+fn is_dot_segment(segment: &str) -> bool {
+    segment == "." || segment == ".."
+}
+
+fn validate(segment: Value) -> Result<Value, Error> {
+    let length = segment.length().value() as usize;
+    if length > UriPath::MAX_LENGTH {
+        return Err(Error::Length(length));
+    }
+
+    let string = segment.clone().string().map_err(|_| Error::Format)?;
+    if is_dot_segment(&string) {
+        return Err(Error::DotSegment);
+    }
+
+    Ok(segment)
+}
+
 fn to_value(path_segment: &str) -> Result<Value, Error> {
     if path_segment.len() > UriPath::MAX_LENGTH {
         Err(Error::Length(path_segment.len()))
@@ -107,6 +217,22 @@ impl TryFrom<String> for UriPath {
     }
 }
 
+impl std::fmt::Display for Error {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Error::DotSegment => write!(f, "Uri-Path(11) segment must not be `.` or `..`"),
+            Error::Format => write!(f, "Uri-Path(11) value is not a valid path"),
+            Error::Length(length) => write!(
+                f,
+                "Uri-Path(11) segment length {length} exceeds {} bytes",
+                UriPath::MAX_LENGTH
+            ),
+        }
+    }
+}
+
+impl std::error::Error for Error {}
+
 #[cfg(test)]
 mod tests {
     use pretty_assertions::assert_eq;
@@ -120,6 +246,9 @@ mod tests {
     #[case(vec![Value::from_str("").unwrap()], Ok(UriPath { segments: vec![] } ))]
     #[case(vec![Value::from_str("a").unwrap()], Ok(UriPath { segments: vec![Value::from_str("a").unwrap()] } ))]
     #[case(vec![Value::from_str(&format!("{}", "a".repeat(256))).unwrap()], Err(Error::Length(256)))]
+    #[case(vec![Value::from_str("a").unwrap(), Value::from_str("..").unwrap()], Err(Error::DotSegment))]
+    #[case(vec![Value::from_str(".").unwrap()], Err(Error::DotSegment))]
+    #[case(vec![Value::from_str("a/b").unwrap()], Ok(UriPath { segments: vec![Value::from_str("a/b").unwrap()] } ))]
     fn decode(#[case] values: Vec<Value>, #[case] expected: Result<UriPath, Error>) {
         assert_eq!(expected, UriPath::decode(values))
     }
@@ -127,14 +256,14 @@ mod tests {
     #[rstest]
     #[case(
         UriPath { segments: vec![] },
-        vec![0b1011_0000]
+        vec![]
     )]
     #[case(
         UriPath { segments: vec![Value::Empty] }, 
-        vec![0b1011_0000])]
+        vec![])]
     #[case(
         UriPath { segments: vec![Value::Empty, Value::Empty] }, 
-        vec![0b1011_0000]
+        vec![]
     )]
     #[case(
         UriPath { segments: vec![Value::Empty, Value::from_str("a").unwrap(), Value::Empty, Value::Empty] }, 
@@ -159,10 +288,27 @@ mod tests {
     #[case("a/#ac", Err(Error::Format))]
     #[case("a/?b=c", Err(Error::Format))]
     #[case(&format!("/a/{}", "c".repeat(256)),  Err(Error::Length(256)))]
+    #[case("/a/../b", Err(Error::DotSegment))]
+    #[case("./a", Err(Error::DotSegment))]
     fn from_value(#[case] value: &str, #[case] expected: Result<UriPath, Error>) {
         assert_eq!(expected, UriPath::from_value(value))
     }
 
+    #[rstest]
+    #[case("", Ok(vec![]))]
+    #[case("/", Ok(vec![]))]
+    #[case("/a/./b/../c", Ok(vec!["a", "c"]))]
+    #[case("/../a", Ok(vec!["a"]))]
+    #[case("/a%20b/c%2Fd", Ok(vec!["a b", "c/d"]))]
+    #[case("/a/", Ok(vec!["a", ""]))]
+    #[case(&format!("/{}", "c".repeat(256)), Err(Error::Length(256)))]
+    fn from_url_path(#[case] path: &str, #[case] expected: Result<Vec<&str>, Error>) {
+        assert_eq!(
+            expected.map(|segments| segments.into_iter().map(String::from).collect::<Vec<_>>()),
+            UriPath::from_url_path(path).map(|uri_path| uri_path.segments())
+        )
+    }
+
     #[rstest]
     fn number() {
         assert_eq!(Number::from_value(11).unwrap(), UriPath::number())
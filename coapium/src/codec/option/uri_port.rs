@@ -1,6 +1,11 @@
 use crate::codec::parsing::single;
 
-use super::{decoded_option::DecodedOption, number::Number, value::Value, Delta};
+use super::{
+    decoded_option::{self, DecodedOption},
+    number::Number,
+    value::Value,
+    Delta,
+};
 
 #[derive(Clone, Debug, PartialEq)]
 pub struct UriPort {
@@ -34,6 +39,11 @@ impl UriPort {
         .encode(delta_sum)
     }
 
+    /// Size of the option once encoded, see [`UriPort::encode`].
+    pub fn encoded_size(&self, delta_sum: Delta) -> usize {
+        decoded_option::encoded_size(Self::number(), delta_sum, [&self.value])
+    }
+
     pub fn from_u16(value: u16) -> Self {
         Self {
             value: Value::from_u16(value),
@@ -61,6 +71,17 @@ impl From<u16> for UriPort {
     }
 }
 
+impl std::fmt::Display for DecodeError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            DecodeError::SingleValue => write!(f, "Uri-Port(7) requires exactly one value"),
+            DecodeError::Format => write!(f, "Uri-Port(7) value is not a valid port"),
+        }
+    }
+}
+
+impl std::error::Error for DecodeError {}
+
 #[cfg(test)]
 mod tests {
     use pretty_assertions::assert_eq;
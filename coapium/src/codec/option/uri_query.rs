@@ -1,5 +1,7 @@
+use crate::codec::percent;
+
 use super::{
-    decoded_option::DecodedOption,
+    decoded_option::{self, DecodedOption},
     number::Number,
     value::{self, Value},
     Delta,
@@ -24,28 +26,54 @@ impl UriQuery {
     const NUMBER: u16 = 15;
 
     fn add<S: AsRef<str>>(&mut self, value: S) -> Result<(), Error> {
+        let value = Self::value(value)?;
+
+        self.queries.push(value);
+
+        Ok(())
+    }
+
+    fn value<S: AsRef<str>>(value: S) -> Result<Value, Error> {
         let value = Value::from_str(value.as_ref())?;
 
         if value.len() > Self::MAX_LENGTH {
             return Err(Error::Length(value.len()));
         }
 
-        self.queries.push(value);
-
-        Ok(())
+        Ok(value)
     }
 
     pub fn add_key_value<S: AsRef<str>>(&mut self, key: S, value: S) -> Result<(), Error> {
         let value = format!(
             "{}={}",
-            urlencoding::encode(key.as_ref()),
-            urlencoding::encode(value.as_ref())
+            percent::encode(key.as_ref()),
+            percent::encode(value.as_ref())
         );
         self.add(value)
     }
 
+    /// Replaces the value of the first `key=` query, or adds one if there is none. Other queries
+    /// with the same key are removed.
+    pub fn set_key_value<S: AsRef<str>>(&mut self, key: S, value: S) -> Result<(), Error> {
+        let prefix = format!("{}=", percent::encode(key.as_ref()));
+        let value = Self::value(format!("{prefix}{}", percent::encode(value.as_ref())))?;
+
+        let has_key = |query: &Value| query.clone().opaque().starts_with(prefix.as_bytes());
+        match self.queries.iter().position(has_key) {
+            Some(position) => {
+                self.queries[position] = value;
+                let mut first = true;
+                self.queries
+                    .retain(|query| !has_key(query) || std::mem::take(&mut first));
+            }
+            None => self.queries.push(value),
+        }
+
+        Ok(())
+    }
+
     pub fn add_value<S: AsRef<str>>(&mut self, value: S) -> Result<(), Error> {
-        self.add(urlencoding::encode(value.as_ref()))
+        self.add(percent::encode(value.as_ref()))
     }
 
     pub fn decode(values: Vec<Value>) -> Result<Self, Error> {
@@ -76,6 +104,11 @@ impl UriQuery {
         .encode(delta_sum)
     }
 
+    /// Size of the option once encoded, see [`UriQuery::encode`].
+    pub fn encoded_size(&self, delta_sum: Delta) -> usize {
+        decoded_option::encoded_size(Self::number(), delta_sum, &self.queries)
+    }
+
     pub fn new() -> Self {
         Self { queries: vec![] }
     }
@@ -115,6 +148,22 @@ impl From<value::Error> for Error {
     }
 }
 
+impl std::fmt::Display for Error {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Error::Length(length) => write!(
+                f,
+                "Uri-Query(15) length {length} exceeds {} bytes",
+                UriQuery::MAX_LENGTH
+            ),
+            Error::String => write!(f, "Uri-Query(15) value is not a valid string"),
+            Error::Value(error) => write!(f, "invalid Uri-Query(15): {error}"),
+        }
+    }
+}
+
+impl std::error::Error for Error {}
+
 #[cfg(test)]
 mod tests {
     use pretty_assertions::assert_eq;
@@ -146,6 +195,31 @@ mod tests {
         }
     }
 
+    #[rstest]
+    #[case(vec![],                     vec!["a=1"])]
+    #[case(vec!["b=2"],                vec!["b=2", "a=1"])]
+    #[case(vec!["a=0", "b=2"],         vec!["a=1", "b=2"])]
+    #[case(vec!["b=2", "a=0", "a=3"],  vec!["b=2", "a=1"])]
+    #[case(vec!["ab=0", "a"],          vec!["ab=0", "a", "a=1"])]
+    fn set_key_value(#[case] queries: Vec<&str>, #[case] expected: Vec<&str>) {
+        let mut uri_query = UriQuery {
+            queries: queries
+                .into_iter()
+                .map(|query| Value::from_str(query).unwrap())
+                .collect(),
+        };
+
+        uri_query.set_key_value("a", "1").unwrap();
+
+        assert_eq!(
+            expected
+                .into_iter()
+                .map(|query| Value::from_str(query).unwrap())
+                .collect::<Vec<_>>(),
+            uri_query.queries
+        );
+    }
+
     #[rstest]
     #[case("", Ok(()), Some(vec![Value::Empty]))]
     #[case("a", Ok(()), Some(vec![Value::from_str("a").unwrap()]))]
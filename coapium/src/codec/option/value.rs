@@ -196,6 +196,27 @@ impl From<length::DecodeError> for Error {
     }
 }
 
+impl std::fmt::Display for Error {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Error::Length(error) => error.fmt(f),
+            Error::Value(error) => error.fmt(f),
+        }
+    }
+}
+
+impl std::error::Error for Error {}
+
+impl std::fmt::Display for ValueError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ValueError::LengthOutOfBounds => write!(f, "option value length is out of bounds"),
+        }
+    }
+}
+
+impl std::error::Error for ValueError {}
+
 #[cfg(test)]
 mod tests {
     use pretty_assertions::assert_eq;
@@ -1,12 +1,29 @@
 use std::convert::identity;
+use std::time::Duration;
 
 use crate::codec::option;
+use crate::codec::option::custom_option::{self, CustomOption};
+use crate::codec::option::Accept;
+use crate::codec::option::Block1;
+use crate::codec::option::Block2;
 use crate::codec::option::Delta;
+use crate::codec::option::ETag;
+use crate::codec::option::Echo;
 use crate::codec::option::IfMatch;
+use crate::codec::option::IfNoneMatch;
+use crate::codec::option::LocationPath;
+use crate::codec::option::LocationQuery;
 use crate::codec::option::MaxAge;
+use crate::codec::option::Number;
+use crate::codec::option::Observe;
 use crate::codec::option::Option;
+use crate::codec::option::QBlock1;
+use crate::codec::option::QBlock2;
+use crate::codec::option::RequestTag;
+use crate::codec::option::Size1;
 use crate::codec::option::UriPath;
 
+use super::media_type::MediaType;
 use super::option::ContentFormat;
 use super::option::UriHost;
 use super::option::UriPort;
@@ -28,10 +45,61 @@ pub enum Error {
 }
 
 impl Options {
+    pub fn block1(&self) -> std::option::Option<&Block1> {
+        self.options.iter().find_map(|o| o.block1())
+    }
+
+    pub fn block2(&self) -> std::option::Option<&Block2> {
+        self.options.iter().find_map(|o| o.block2())
+    }
+
+    pub fn accept(&self) -> std::option::Option<&Accept> {
+        self.options.iter().find_map(|o| o.accept())
+    }
+
+    pub fn accept_media_type(&self) -> std::option::Option<MediaType> {
+        self.accept().map(Accept::media_type)
+    }
+
+    /// Options that are part of the cache key, encoded in the order of their numbers, to tell
+    /// apart requests a cache or proxy must not answer with the same response. NoCacheKey options
+    /// like Size1 are left out, see [`Number::is_no_cache_key`].
+    pub fn cache_key(&self) -> Vec<u8> {
+        let mut options = self
+            .options
+            .iter()
+            .filter(|option| !option.number().is_no_cache_key())
+            .cloned()
+            .collect::<Vec<_>>();
+        options.sort_by_key(Option::number);
+
+        Self::encode_sorted(options)
+    }
+
     pub fn content_format(&self) -> std::option::Option<&ContentFormat> {
         self.options.iter().find_map(|o| o.content_format())
     }
 
+    pub fn content_media_type(&self) -> std::option::Option<MediaType> {
+        self.content_format()
+            .map(|content_format| content_format.media_type().clone())
+    }
+
+    /// Option declared with [`define_option!`](crate::define_option), or `None` if it isn't set.
+    pub fn custom_option<O: CustomOption>(
+        &self,
+    ) -> std::option::Option<Result<O, custom_option::Error>> {
+        self.custom(O::number())
+            .map(|option| O::decode(option.values.clone()))
+    }
+
+    pub fn custom(&self, number: Number) -> std::option::Option<&DecodedOption> {
+        self.options
+            .iter()
+            .filter_map(Option::custom)
+            .find(|o| o.number == number)
+    }
+
     pub fn decode(options: DecodedOptions) -> Result<Self, Error> {
         Ok(Self {
             options: options
@@ -44,33 +112,135 @@ impl Options {
         })
     }
 
+    /// Decodes the options that can be decoded and hands back the others. Options that failed to
+    /// decode come back with the error, unrecognized elective options without one.
+    pub fn decode_lossy(
+        options: impl IntoIterator<Item = DecodedOption>,
+    ) -> (
+        Self,
+        Vec<(DecodedOption, std::option::Option<option::Error>)>,
+    ) {
+        let mut decoded = vec![];
+        let mut rest = vec![];
+
+        for option in options {
+            match Option::decode(option.clone()) {
+                Ok(Some(decoded_option)) => decoded.push(decoded_option),
+                Ok(None) => rest.push((option, None)),
+                Err(error) => rest.push((option, Some(error))),
+            }
+        }
+
+        (Self { options: decoded }, rest)
+    }
+
     fn decode_option(option: DecodedOption) -> Result<std::option::Option<Option>, Error> {
         Option::decode(option).map_err(Into::into)
     }
 
+    /// Encodes the options in the order of their numbers. Options with the same number keep the
+    /// order they were added in, and every option after the first is encoded as a repeat.
     pub fn encode(mut self) -> Vec<u8> {
         self.options.sort_by_key(Option::number);
-        self.options
-            .into_iter()
-            .fold(
-                (Delta::from_value(0), vec![]),
-                |(delta_sum, mut encoded_options), o| {
-                    let number = o.number();
-                    let encoded_option = o.encode(delta_sum);
-                    encoded_options.push(encoded_option);
-                    (number.value, encoded_options)
-                },
-            )
-            .1
-            .into_iter()
-            .flatten()
-            .collect()
+        Self::encode_sorted(self.options)
+    }
+
+    /// Encodes options already sorted by number, the sort is stable so repeats stay in order.
+    fn encode_sorted(options: Vec<Option>) -> Vec<u8> {
+        let mut delta_sum = Delta::from_value(0);
+        let mut bytes = vec![];
+
+        for option in options {
+            let number = option.number().value;
+
+            // Options like the root Uri-Path encode to nothing, the next delta can't count them
+            let encoded = option.encode(delta_sum);
+            if !encoded.is_empty() {
+                bytes.extend(encoded);
+                delta_sum = number;
+            }
+        }
+
+        bytes
+    }
+
+    /// Size of the options once encoded, worked out without encoding them, e.g. to check a message
+    /// against the MTU or to pick a block size before it's serialized.
+    pub fn encoded_size_hint(&self) -> usize {
+        let mut options = self.options.iter().collect::<Vec<_>>();
+        options.sort_by_key(|option| option.number());
+
+        let mut delta_sum = Delta::from_value(0);
+        let mut size = 0;
+        for option in options {
+            let option_size = option.encoded_size(delta_sum);
+            if option_size > 0 {
+                size += option_size;
+                delta_sum = option.number().value;
+            }
+        }
+
+        size
+    }
+
+    pub fn echo(&self) -> std::option::Option<&Echo> {
+        self.options.iter().find_map(|o| o.echo())
+    }
+
+    pub fn etag(&self) -> std::option::Option<&ETag> {
+        self.options.iter().find_map(|o| o.etag())
     }
 
     pub fn if_match(&self) -> std::option::Option<&IfMatch> {
         self.options.iter().find_map(|o| o.if_match())
     }
 
+    /// Whether there are no options, a root Uri-Path counts as one even though it encodes to
+    /// nothing.
+    pub fn is_empty(&self) -> bool {
+        self.options.is_empty()
+    }
+
+    /// Options in the order they are encoded in, see [`Options::encode`].
+    pub fn iter(&self) -> impl Iterator<Item = (Number, &Option)> {
+        let mut options = self
+            .options
+            .iter()
+            .map(|option| (option.number(), option))
+            .collect::<Vec<_>>();
+        options.sort_by_key(|(number, _)| *number);
+        options.into_iter()
+    }
+
+    /// Number of options, an option with several values like a Uri-Path counts once.
+    pub fn len(&self) -> usize {
+        self.options.len()
+    }
+
+    /// Location-Path and Location-Query joined into a relative URI, e.g. `/a/b?c=d`.
+    pub fn location(&self) -> std::option::Option<String> {
+        let path = self.location_path().map(LocationPath::segments);
+        let query = self.location_query().map(LocationQuery::arguments);
+        if path.is_none() && query.is_none() {
+            return None;
+        }
+
+        let mut location = format!("/{}", path.unwrap_or_default().join("/"));
+        if let Some(query) = query {
+            location.push('?');
+            location.push_str(&query.join("&"));
+        }
+        Some(location)
+    }
+
+    pub fn location_path(&self) -> std::option::Option<&LocationPath> {
+        self.options.iter().find_map(|o| o.location_path())
+    }
+
+    pub fn location_query(&self) -> std::option::Option<&LocationQuery> {
+        self.options.iter().find_map(|o| o.location_query())
+    }
+
     pub fn new() -> Self {
         Self { options: vec![] }
     }
@@ -79,6 +249,20 @@ impl Options {
         self.options.iter().find_map(|o| o.max_age())
     }
 
+    /// Max-Age as a duration, `None` when the option is absent even though its default is 60 s.
+    pub fn max_age_duration(&self) -> std::option::Option<Duration> {
+        self.max_age()
+            .map(|max_age| Duration::from_secs(max_age.seconds().into()))
+    }
+
+    pub fn observe(&self) -> std::option::Option<&Observe> {
+        self.options.iter().find_map(|o| o.observe())
+    }
+
+    pub fn clear(&mut self) {
+        self.options.clear();
+    }
+
     pub fn options(&self) -> &[Option] {
         &self.options
     }
@@ -89,6 +273,44 @@ impl Options {
         Ok((bytes, Self::decode(options)?))
     }
 
+    /// Removes every option with `number`, known or custom.
+    pub fn remove(&mut self, number: Number) {
+        self.options.retain(|o| o.number() != number);
+    }
+
+    pub fn remove_content_format(&mut self) {
+        self.options.retain(|o| !o.is_content_format());
+    }
+
+    pub fn remove_uri_query(&mut self) {
+        self.options.retain(|o| !o.is_uri_query());
+    }
+
+    pub fn set_accept(&mut self, accept: Accept) {
+        match self.options.iter().position(|x| x.is_accept()) {
+            Some(position) => {
+                self.options.swap_remove(position);
+                self.options.push(Option::Accept(accept))
+            }
+            None => self.options.push(Option::Accept(accept)),
+        }
+    }
+
+    pub fn set_block1(&mut self, block1: Block1) {
+        self.options.retain(|o| !o.is_block1());
+        self.options.push(Option::Block1(block1));
+    }
+
+    pub fn set_block2(&mut self, block2: Block2) {
+        match self.options.iter().position(|x| x.is_block2()) {
+            Some(position) => {
+                self.options.swap_remove(position);
+                self.options.push(Option::Block2(block2))
+            }
+            None => self.options.push(Option::Block2(block2)),
+        }
+    }
+
     pub fn set_content_format(&mut self, content_format: ContentFormat) {
         match self.options.iter().position(|x| x.is_content_format()) {
             Some(position) => {
@@ -99,6 +321,47 @@ impl Options {
         }
     }
 
+    /// Sets an option this crate doesn't know, replacing a custom option with the same number.
+    pub fn set_custom(&mut self, option: DecodedOption) {
+        self.options.retain(|o| {
+            o.custom()
+                .is_none_or(|custom| custom.number != option.number)
+        });
+        self.options.push(Option::Custom(option));
+    }
+
+    /// Sets an option declared with [`define_option!`](crate::define_option), replacing one with
+    /// the same number.
+    pub fn set_custom_option<O: CustomOption>(&mut self, option: O) {
+        self.set_custom(option.into_decoded())
+    }
+
+    pub fn set_echo(&mut self, echo: Echo) {
+        self.options.retain(|o| !o.is_echo());
+        self.options.push(Option::Echo(echo));
+    }
+
+    pub fn set_etag(&mut self, etag: ETag) {
+        match self.options.iter().position(|x| x.is_etag()) {
+            Some(position) => {
+                self.options.swap_remove(position);
+                self.options.push(Option::ETag(etag))
+            }
+            None => self.options.push(Option::ETag(etag)),
+        }
+    }
+
+    /// Adds the values of `etag` to the ETag option, setting it if there is none yet.
+    pub fn add_etag(&mut self, etag: ETag) {
+        match self.options.iter_mut().find_map(|o| match o {
+            Option::ETag(existing) => Some(existing),
+            _ => None,
+        }) {
+            Some(existing) => existing.extend(etag),
+            None => self.options.push(Option::ETag(etag)),
+        }
+    }
+
     pub fn set_if_match(&mut self, if_match: IfMatch) {
         match self.options.iter().position(|x| x.is_if_match()) {
             Some(position) => {
@@ -109,6 +372,26 @@ impl Options {
         }
     }
 
+    pub fn set_if_none_match(&mut self, if_none_match: IfNoneMatch) {
+        match self.options.iter().position(|x| x.is_if_none_match()) {
+            Some(position) => {
+                self.options.swap_remove(position);
+                self.options.push(Option::IfNoneMatch(if_none_match))
+            }
+            None => self.options.push(Option::IfNoneMatch(if_none_match)),
+        }
+    }
+
+    pub fn set_location_path(&mut self, location_path: LocationPath) {
+        match self.options.iter().position(|x| x.is_location_path()) {
+            Some(position) => {
+                self.options.swap_remove(position);
+                self.options.push(Option::LocationPath(location_path))
+            }
+            None => self.options.push(Option::LocationPath(location_path)),
+        }
+    }
+
     pub fn set_max_age(&mut self, max_age: MaxAge) {
         match self.options.iter().position(|x| x.is_max_age()) {
             Some(position) => {
@@ -119,6 +402,36 @@ impl Options {
         }
     }
 
+    pub fn set_observe(&mut self, observe: Observe) {
+        match self.options.iter().position(|x| x.is_observe()) {
+            Some(position) => {
+                self.options.swap_remove(position);
+                self.options.push(Option::Observe(observe))
+            }
+            None => self.options.push(Option::Observe(observe)),
+        }
+    }
+
+    pub fn set_q_block1(&mut self, q_block1: QBlock1) {
+        self.options.retain(|o| !o.is_q_block1());
+        self.options.push(Option::QBlock1(q_block1));
+    }
+
+    pub fn set_q_block2(&mut self, q_block2: QBlock2) {
+        self.options.retain(|o| !o.is_q_block2());
+        self.options.push(Option::QBlock2(q_block2));
+    }
+
+    pub fn set_request_tag(&mut self, request_tag: RequestTag) {
+        self.options.retain(|o| !o.is_request_tag());
+        self.options.push(Option::RequestTag(request_tag));
+    }
+
+    pub fn set_size1(&mut self, size1: Size1) {
+        self.options.retain(|o| o.size1().is_none());
+        self.options.push(Option::Size1(size1));
+    }
+
     pub fn set_uri_host(&mut self, host: UriHost) {
         match self.options.iter().position(|x| x.is_uri_host()) {
             Some(position) => {
@@ -159,6 +472,22 @@ impl Options {
         }
     }
 
+    pub fn q_block1(&self) -> std::option::Option<&QBlock1> {
+        self.options.iter().find_map(|o| o.q_block1())
+    }
+
+    pub fn q_block2(&self) -> std::option::Option<&QBlock2> {
+        self.options.iter().find_map(|o| o.q_block2())
+    }
+
+    pub fn request_tag(&self) -> std::option::Option<&RequestTag> {
+        self.options.iter().find_map(|o| o.request_tag())
+    }
+
+    pub fn size1(&self) -> std::option::Option<&Size1> {
+        self.options.iter().find_map(|o| o.size1())
+    }
+
     pub fn uri_host(&self) -> std::option::Option<&UriHost> {
         self.options.iter().find_map(|o| o.uri_host())
     }
@@ -188,18 +517,35 @@ impl From<option::Error> for Error {
     }
 }
 
+impl std::fmt::Display for Error {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Error::Option(error) => error.fmt(f),
+            Error::DecodedOptions(error) => error.fmt(f),
+        }
+    }
+}
+
+impl std::error::Error for Error {}
+
 #[cfg(test)]
 mod tests {
 
     use pretty_assertions::assert_eq;
     use rstest::rstest;
 
+    use std::time::Duration;
+
     use crate::codec::option::{
-        uri_host, ContentFormat, Delta, IfMatch, MaxAge, UriHost, UriPath, UriQuery, Value,
+        uri_host, Block2, ContentFormat, Delta, ETag, Echo, IfMatch, LocationPath, LocationQuery,
+        MaxAge, ProxyUri, Size1, UriHost, UriPath, UriQuery, Value,
     };
     use crate::codec::MediaType;
 
-    use super::{super::option, super::EncodedOption, DecodedOptions, Error, Option, Options};
+    use super::{
+        super::option, super::EncodedOption, DecodedOption, DecodedOptions, Error,
+        Number, Option, Options,
+    };
 
     #[rstest]
     #[case(DecodedOptions::decode(vec![]).unwrap(), Ok(Options { options: vec![] }))]
@@ -266,6 +612,112 @@ mod tests {
         assert_eq!(expected, options.encode())
     }
 
+    #[rstest]
+    #[case(Options::new())]
+    #[case(Options { options: vec![Option::UriPath(UriPath::from_value("/").unwrap())] })]
+    #[case(Options { options: vec![Option::ContentFormat(MediaType::CharsetUtf8.into())] })]
+    #[case(
+        {
+            let mut options = Options::new();
+            options.set_uri_host("coap.example.org".try_into().unwrap());
+            options.set_uri_path("sensors/temperature".try_into().unwrap());
+            options.set_etag(ETag::from_values(vec![vec![1], vec![2, 3]]).unwrap());
+            options.set_block2(Block2::new(300, true, 6).unwrap());
+            options.set_size1(Size1::new(70000));
+            options.set_echo(Echo::from_value(vec![7; 40]).unwrap());
+            options.set_custom(DecodedOption::new(
+                Number::from_value_or_panic(2049),
+                vec![Value::from_u16(1)],
+            ));
+            options
+        }
+    )]
+    #[case(
+        Options {
+            options: vec![Option::ProxyUri(
+                ProxyUri::decode(vec![Value::from_string("a".repeat(300)).unwrap()]).unwrap(),
+            )],
+        }
+    )]
+    fn encoded_size_hint(#[case] options: Options) {
+        assert_eq!(options.clone().encode().len(), options.encoded_size_hint())
+    }
+
+    #[rstest]
+    fn len() {
+        let mut options = Options::new();
+        assert!(options.is_empty());
+
+        options.set_uri_path("a/b".try_into().unwrap());
+        options.set_uri_port(5432.into());
+
+        assert!(!options.is_empty());
+        assert_eq!(2, options.len());
+    }
+
+    #[rstest]
+    fn cache_key() {
+        let mut options = Options::new();
+        options.set_echo(Echo::from_value(vec![1]).unwrap());
+        options.set_uri_path("a".try_into().unwrap());
+        let mut other = options.clone();
+        other.set_echo(Echo::from_value(vec![2]).unwrap());
+
+        assert_eq!(vec![0xb1, b'a'], options.cache_key());
+        assert_eq!(options.cache_key(), other.cache_key());
+    }
+
+    #[rstest]
+    fn encode_keeps_insertion_order_of_same_number() {
+        let options = Options {
+            options: vec![
+                Option::UriPath(UriPath::from_value("b").unwrap()),
+                Option::ContentFormat(MediaType::ApplicationJson.into()),
+                Option::UriPath(UriPath::from_value("a").unwrap()),
+            ],
+        };
+
+        assert_eq!(
+            vec![0b1011_0001, 98, 0b0000_0001, 97, 0b0001_0001, 50],
+            options.encode()
+        );
+    }
+
+    #[rstest]
+    fn encode_root_uri_path() {
+        let options = Options {
+            options: vec![
+                Option::UriPath(UriPath::from_value("/").unwrap()),
+                Option::ContentFormat(MediaType::ApplicationJson.into()),
+            ],
+        };
+
+        assert_eq!(vec![0b1100_0001, 50], options.encode());
+    }
+
+    #[rstest]
+    fn iter() {
+        let options = Options {
+            options: vec![
+                Option::UriPath(UriPath::from_value("b").unwrap()),
+                Option::ContentFormat(MediaType::ApplicationJson.into()),
+                Option::UriPath(UriPath::from_value("a").unwrap()),
+            ],
+        };
+
+        assert_eq!(
+            vec![
+                (Some("Uri-Path"), &options.options()[0]),
+                (Some("Uri-Path"), &options.options()[2]),
+                (Some("Content-Format"), &options.options()[1]),
+            ],
+            options
+                .iter()
+                .map(|(number, option)| (number.name(), option))
+                .collect::<Vec<_>>()
+        );
+    }
+
     #[rstest]
     fn new() {
         assert_eq!(Vec::<u8>::new(), Options::new().encode());
@@ -290,8 +742,8 @@ mod tests {
     #[case(&[0xff], &[0xff], Ok(Options { options: vec![] }))]
     #[case(&[0xff], &[0xff], Ok(Options { options: vec![] }))]
     #[case(
-        &[0b1011_0001, 97, 98], 
-        &[98], 
+        &[0b1011_0001, 97, 0xff, 98], 
+        &[0xff, 98], 
         Ok({
             let mut options = Options::new();
             options.set_uri_path("a".try_into().unwrap()); 
@@ -374,4 +826,99 @@ mod tests {
         assert_eq!(Some(&uri_query), options.uri_query());
         assert_eq!(expected, options.options());
     }
+
+    #[rstest]
+    #[case(vec![], None)]
+    #[case(vec![Option::MaxAge(MaxAge::from(120))], Some(Duration::from_secs(120)))]
+    fn max_age_duration(
+        #[case] options: Vec<Option>,
+        #[case] expected: std::option::Option<Duration>,
+    ) {
+        assert_eq!(expected, Options { options }.max_age_duration());
+    }
+
+    #[rstest]
+    #[case(vec![], None)]
+    #[case(
+        vec![Option::ContentFormat(ContentFormat::from(MediaType::ApplicationJson))],
+        Some(MediaType::ApplicationJson)
+    )]
+    fn content_media_type(
+        #[case] options: Vec<Option>,
+        #[case] expected: std::option::Option<MediaType>,
+    ) {
+        assert_eq!(expected, Options { options }.content_media_type());
+    }
+
+    fn strings(values: &[&str]) -> Vec<Value> {
+        values
+            .iter()
+            .map(|value| Value::from_str(value).unwrap())
+            .collect()
+    }
+
+    #[rstest]
+    #[case(vec![], None)]
+    #[case(
+        vec![Option::LocationPath(LocationPath::decode(strings(&["rd", "4521"])).unwrap())],
+        Some("/rd/4521")
+    )]
+    #[case(
+        vec![
+            Option::LocationPath(LocationPath::decode(strings(&["rd"])).unwrap()),
+            Option::LocationQuery(LocationQuery::decode(strings(&["a=1", "b"])).unwrap()),
+        ],
+        Some("/rd?a=1&b")
+    )]
+    #[case(
+        vec![Option::LocationQuery(LocationQuery::decode(strings(&["a=1"])).unwrap())],
+        Some("/?a=1")
+    )]
+    fn location(#[case] options: Vec<Option>, #[case] expected: std::option::Option<&str>) {
+        assert_eq!(expected.map(str::to_owned), Options { options }.location());
+    }
+
+    #[rstest]
+    #[case(|o: &mut Options| o.remove_content_format(),                vec![0xb1, b'a', 0x41, b'b', 0xd1, 0xe0, 1])]
+    #[case(|o: &mut Options| o.remove_uri_query(),                     vec![0xb1, b'a', 0x10, 0xd1, 0xe3, 1])]
+    #[case(|o: &mut Options| o.remove(UriPath::number()),              vec![0xc0, 0x31, b'b', 0xd1, 0xe0, 1])]
+    #[case(|o: &mut Options| o.remove(Number::from_value(252).unwrap()), vec![0xb1, b'a', 0x10, 0x31, b'b'])]
+    #[case(|o: &mut Options| o.clear(),                                vec![])]
+    fn remove(#[case] apply: fn(&mut Options), #[case] expected: Vec<u8>) {
+        let mut options = Options::new();
+        options.set_uri_path(UriPath::from_value("a").unwrap());
+        options.set_content_format(ContentFormat::from(MediaType::TextPlain));
+        options.set_uri_query(UriQuery::try_from("b").unwrap());
+        options.set_custom(DecodedOption::new(
+            Number::from_value(252).unwrap(),
+            vec![Value::from_opaque(vec![1]).unwrap()],
+        ));
+
+        apply(&mut options);
+
+        assert_eq!(expected, options.encode());
+    }
+
+    #[rstest]
+    fn set_custom() {
+        let echo = |value: u8| {
+            DecodedOption::new(
+                Number::from_value(252).unwrap(),
+                vec![Value::from_opaque(vec![value]).unwrap()],
+            )
+        };
+        let mut options = Options::new();
+        options.set_custom(echo(1));
+        options.set_uri_port(5683.into());
+        options.set_custom(echo(2));
+
+        assert_eq!(
+            Some(&echo(2)),
+            options.custom(Number::from_value(252).unwrap())
+        );
+        assert_eq!(
+            vec![0b0111_0010, 0x16, 0x33, 0b1101_0001, 232, 2],
+            options.encode()
+        );
+    }
 }
@@ -1,4 +1,4 @@
-const MARKER: u8 = 0xff;
+pub const MARKER: u8 = 0xff;
 
 #[derive(Clone, Debug, PartialEq)]
 pub struct Payload {
@@ -7,6 +7,8 @@ pub struct Payload {
 
 #[derive(Clone, Copy, Debug, PartialEq)]
 pub enum Error {
+    #[cfg(feature = "cbor")]
+    Cbor,
     Format,
 }
 
@@ -22,6 +24,14 @@ impl Payload {
         }
     }
 
+    /// Serializes `value` as CBOR, to be sent with the `application/cbor` content format.
+    #[cfg(feature = "cbor")]
+    pub fn from_cbor<T: serde::Serialize>(value: &T) -> Result<Self, Error> {
+        let mut bytes = vec![];
+        ciborium::ser::into_writer(value, &mut bytes).map_err(|_| Error::Cbor)?;
+        Ok(Self::from_value(bytes))
+    }
+
     pub fn empty() -> Self {
         Self { value: None }
     }
@@ -51,8 +61,25 @@ impl Payload {
     pub fn is_empty(&self) -> bool {
         self.value.is_none()
     }
+
+    #[cfg(feature = "cbor")]
+    pub fn to_cbor<T: serde::de::DeserializeOwned>(&self) -> Result<T, Error> {
+        ciborium::de::from_reader(self.value()).map_err(|_| Error::Cbor)
+    }
 }
 
+impl std::fmt::Display for Error {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            #[cfg(feature = "cbor")]
+            Error::Cbor => write!(f, "payload is not valid CBOR for the expected type"),
+            Error::Format => write!(f, "payload marker must be followed by a non-empty payload"),
+        }
+    }
+}
+
+impl std::error::Error for Error {}
+
 #[cfg(test)]
 mod tests {
     use rstest::rstest;
@@ -108,4 +135,14 @@ mod tests {
     fn is_empty() {
         assert!(Payload::empty().is_empty())
     }
+
+    #[cfg(feature = "cbor")]
+    #[rstest]
+    fn cbor() {
+        let payload = Payload::from_cbor(&(21.5, "kitchen".to_owned())).unwrap();
+
+        assert_eq!(&[0x82, 0xf9, 0x4d, 0x60][..], &payload.value()[..4]);
+        assert_eq!(Ok((21.5, "kitchen".to_owned())), payload.to_cbor());
+        assert_eq!(Err(Error::Cbor), payload.to_cbor::<u8>());
+    }
 }
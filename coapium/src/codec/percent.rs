@@ -0,0 +1,97 @@
+//! Percent-encoding of URI components, RFC 3986 §2.1, so the codec doesn't need a URL crate.
+
+use std::string::FromUtf8Error;
+
+/// Encodes every byte except the unreserved characters, RFC 3986 §2.3.
+pub fn encode(value: &str) -> String {
+    encode_bytes(value, |byte| {
+        !(byte.is_ascii_alphanumeric() || matches!(byte, b'-' | b'.' | b'_' | b'~'))
+    })
+}
+
+/// Encodes the bytes a URL parser encodes in a path: controls, space, `"`, `<`, `>`, `` ` ``,
+/// `{`, `}` and anything that isn't ASCII. `#` and `?` end a path, so they're never encoded.
+pub fn encode_path_segment(segment: &str) -> String {
+    encode_bytes(segment, |byte| {
+        byte <= 0x20 || byte >= 0x7f || matches!(byte, b'"' | b'<' | b'>' | b'`' | b'{' | b'}')
+    })
+}
+
+/// Decodes `%XX` escapes, leaving any `%` that isn't followed by two hex digits as it is. Fails if
+/// the decoded bytes aren't UTF-8.
+pub fn decode(value: &str) -> Result<String, FromUtf8Error> {
+    let bytes = value.as_bytes();
+    let mut decoded = Vec::with_capacity(bytes.len());
+
+    let mut i = 0;
+    while i < bytes.len() {
+        let escaped = match (bytes[i], bytes.get(i + 1), bytes.get(i + 2)) {
+            (b'%', Some(&high), Some(&low)) => hex_digit(high).zip(hex_digit(low)),
+            _ => None,
+        };
+        match escaped {
+            Some((high, low)) => {
+                decoded.push(high << 4 | low);
+                i += 3;
+            }
+            None => {
+                decoded.push(bytes[i]);
+                i += 1;
+            }
+        }
+    }
+
+    String::from_utf8(decoded)
+}
+
+fn encode_bytes(value: &str, is_encoded: impl Fn(u8) -> bool) -> String {
+    let mut encoded = String::with_capacity(value.len());
+    for &byte in value.as_bytes() {
+        if is_encoded(byte) {
+            encoded.push_str(&format!("%{byte:02X}"));
+        } else {
+            encoded.push(char::from(byte));
+        }
+    }
+
+    encoded
+}
+
+fn hex_digit(digit: u8) -> Option<u8> {
+    char::from(digit).to_digit(16).map(|digit| digit as u8)
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+    use rstest::rstest;
+
+    use super::{decode, encode, encode_path_segment};
+
+    #[rstest]
+    #[case("abc-._~", "abc-._~")]
+    #[case("a b=c&d", "a%20b%3Dc%26d")]
+    #[case("ä", "%C3%A4")]
+    fn encodes(#[case] value: &str, #[case] expected: &str) {
+        assert_eq!(expected, encode(value))
+    }
+
+    #[rstest]
+    #[case("a=b&c", "a=b&c")]
+    #[case("a b{}", "a%20b%7B%7D")]
+    #[case("ä%20", "%C3%A4%20")]
+    fn encodes_path_segment(#[case] segment: &str, #[case] expected: &str) {
+        assert_eq!(expected, encode_path_segment(segment))
+    }
+
+    #[rstest]
+    #[case("a%20b", Some("a b"))]
+    #[case("c%2Fd%2f", Some("c/d/"))]
+    #[case("100%", Some("100%"))]
+    #[case("%zz%2", Some("%zz%2"))]
+    #[case("%C3%A4", Some("ä"))]
+    #[case("%C3", None)]
+    fn decodes(#[case] value: &str, #[case] expected: Option<&str>) {
+        assert_eq!(expected.map(String::from), decode(value).ok())
+    }
+}
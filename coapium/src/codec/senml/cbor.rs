@@ -0,0 +1,145 @@
+use ciborium::value::{Integer, Value as Cbor};
+
+use super::{Error, Record, Value};
+
+const BASE_VERSION: i8 = -1;
+const BASE_NAME: i8 = -2;
+const BASE_TIME: i8 = -3;
+const BASE_UNIT: i8 = -4;
+const BASE_VALUE: i8 = -5;
+const BASE_SUM: i8 = -6;
+const NAME: i8 = 0;
+const UNIT: i8 = 1;
+const VALUE: i8 = 2;
+const STRING_VALUE: i8 = 3;
+const BOOLEAN_VALUE: i8 = 4;
+const SUM: i8 = 5;
+const TIME: i8 = 6;
+const UPDATE_TIME: i8 = 7;
+const DATA_VALUE: i8 = 8;
+
+pub(super) fn decode(bytes: &[u8]) -> Result<Vec<Record>, Error> {
+    let Cbor::Array(records) = ciborium::de::from_reader(bytes).map_err(|_| Error::Cbor)? else {
+        return Err(Error::Cbor);
+    };
+
+    records.into_iter().map(decode_record).collect()
+}
+
+pub(super) fn encode(records: &[Record]) -> Result<Vec<u8>, Error> {
+    let records = Cbor::Array(records.iter().map(encode_record).collect());
+
+    let mut bytes = vec![];
+    ciborium::ser::into_writer(&records, &mut bytes).map_err(|_| Error::Cbor)?;
+    Ok(bytes)
+}
+
+fn decode_record(record: Cbor) -> Result<Record, Error> {
+    let Cbor::Map(fields) = record else {
+        return Err(Error::Cbor);
+    };
+
+    let mut record = Record::new();
+    for (label, value) in fields {
+        let label = label
+            .as_integer()
+            .and_then(|label| i8::try_from(label).ok());
+
+        match label {
+            Some(BASE_VERSION) => {
+                record.base_version = Some(
+                    value
+                        .as_integer()
+                        .and_then(|version| u64::try_from(version).ok())
+                        .ok_or(Error::Field("bver"))?,
+                )
+            }
+            Some(BASE_NAME) => record.base_name = Some(string(value, "bn")?),
+            Some(BASE_TIME) => record.base_time = Some(number(value, "bt")?),
+            Some(BASE_UNIT) => record.base_unit = Some(string(value, "bu")?),
+            Some(BASE_VALUE) => record.base_value = Some(number(value, "bv")?),
+            Some(BASE_SUM) => record.base_sum = Some(number(value, "bs")?),
+            Some(NAME) => record.name = Some(string(value, "n")?),
+            Some(UNIT) => record.unit = Some(string(value, "u")?),
+            Some(VALUE) => record.value = Some(Value::Number(number(value, "v")?)),
+            Some(STRING_VALUE) => record.value = Some(Value::String(string(value, "vs")?)),
+            Some(BOOLEAN_VALUE) => {
+                record.value = Some(Value::Boolean(value.as_bool().ok_or(Error::Field("vb"))?))
+            }
+            Some(SUM) => record.sum = Some(number(value, "s")?),
+            Some(TIME) => record.time = Some(number(value, "t")?),
+            Some(UPDATE_TIME) => record.update_time = Some(number(value, "ut")?),
+            Some(DATA_VALUE) => match value {
+                Cbor::Bytes(data) => record.value = Some(Value::Data(data)),
+                _ => return Err(Error::Field("vd")),
+            },
+            // Negative labels below -127 and labels above 127 are not defined by the registry.
+            _ => {}
+        }
+    }
+
+    Ok(record)
+}
+
+fn encode_record(record: &Record) -> Cbor {
+    let mut fields = vec![];
+
+    if let Some(version) = record.base_version {
+        fields.push((label(BASE_VERSION), Cbor::Integer(version.into())));
+    }
+
+    let strings = [
+        (BASE_NAME, &record.base_name),
+        (BASE_UNIT, &record.base_unit),
+        (NAME, &record.name),
+        (UNIT, &record.unit),
+    ];
+    fields.extend(strings.into_iter().filter_map(|(key, value)| {
+        value
+            .as_ref()
+            .map(|value| (label(key), Cbor::Text(value.clone())))
+    }));
+
+    let numbers = [
+        (BASE_TIME, record.base_time),
+        (BASE_VALUE, record.base_value),
+        (BASE_SUM, record.base_sum),
+        (SUM, record.sum),
+        (TIME, record.time),
+        (UPDATE_TIME, record.update_time),
+    ];
+    fields.extend(
+        numbers
+            .into_iter()
+            .filter_map(|(key, value)| value.map(|value| (label(key), Cbor::Float(value)))),
+    );
+
+    match &record.value {
+        Some(Value::Boolean(value)) => fields.push((label(BOOLEAN_VALUE), Cbor::Bool(*value))),
+        Some(Value::Data(value)) => fields.push((label(DATA_VALUE), Cbor::Bytes(value.clone()))),
+        Some(Value::Number(value)) => fields.push((label(VALUE), Cbor::Float(*value))),
+        Some(Value::String(value)) => fields.push((label(STRING_VALUE), Cbor::Text(value.clone()))),
+        None => {}
+    }
+
+    Cbor::Map(fields)
+}
+
+fn label(label: i8) -> Cbor {
+    Cbor::Integer(Integer::from(label))
+}
+
+fn number(value: Cbor, label: &'static str) -> Result<f64, Error> {
+    match value {
+        Cbor::Float(value) => Ok(value),
+        Cbor::Integer(value) => Ok(i128::from(value) as f64),
+        _ => Err(Error::Field(label)),
+    }
+}
+
+fn string(value: Cbor, label: &'static str) -> Result<String, Error> {
+    match value {
+        Cbor::Text(value) => Ok(value),
+        _ => Err(Error::Field(label)),
+    }
+}
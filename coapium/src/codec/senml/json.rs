@@ -0,0 +1,178 @@
+use serde_json::{Map, Number, Value as Json};
+
+use super::{Error, Record, Value};
+
+const BASE64_URL: &[u8; 64] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789-_";
+
+pub(super) fn decode(bytes: &[u8]) -> Result<Vec<Record>, Error> {
+    let Json::Array(records) = serde_json::from_slice(bytes).map_err(|_| Error::Json)? else {
+        return Err(Error::Json);
+    };
+
+    records.into_iter().map(decode_record).collect()
+}
+
+pub(super) fn encode(records: &[Record]) -> Result<Vec<u8>, Error> {
+    let records = records
+        .iter()
+        .map(encode_record)
+        .collect::<Result<Vec<_>, _>>()?;
+
+    serde_json::to_vec(&records).map_err(|_| Error::Json)
+}
+
+fn decode_record(record: Json) -> Result<Record, Error> {
+    let Json::Object(fields) = record else {
+        return Err(Error::Json);
+    };
+
+    let mut record = Record::new();
+    for (label, value) in fields {
+        match label.as_str() {
+            "bn" => record.base_name = Some(string(value, "bn")?),
+            "bs" => record.base_sum = Some(number(value, "bs")?),
+            "bt" => record.base_time = Some(number(value, "bt")?),
+            "bu" => record.base_unit = Some(string(value, "bu")?),
+            "bv" => record.base_value = Some(number(value, "bv")?),
+            "bver" => record.base_version = Some(value.as_u64().ok_or(Error::Field("bver"))?),
+            "n" => record.name = Some(string(value, "n")?),
+            "s" => record.sum = Some(number(value, "s")?),
+            "t" => record.time = Some(number(value, "t")?),
+            "u" => record.unit = Some(string(value, "u")?),
+            "ut" => record.update_time = Some(number(value, "ut")?),
+            "v" => record.value = Some(Value::Number(number(value, "v")?)),
+            "vb" => record.value = Some(Value::Boolean(value.as_bool().ok_or(Error::Field("vb"))?)),
+            "vd" => {
+                let data = base64_decode(&string(value, "vd")?).ok_or(Error::Field("vd"))?;
+                record.value = Some(Value::Data(data))
+            }
+            "vs" => record.value = Some(Value::String(string(value, "vs")?)),
+            label if label.ends_with('_') => return Err(Error::MustUnderstand),
+            _ => {}
+        }
+    }
+
+    Ok(record)
+}
+
+fn encode_record(record: &Record) -> Result<Json, Error> {
+    let mut fields = Map::new();
+
+    let strings = [
+        ("bn", &record.base_name),
+        ("bu", &record.base_unit),
+        ("n", &record.name),
+        ("u", &record.unit),
+    ];
+    for (label, value) in strings {
+        if let Some(value) = value {
+            fields.insert(label.to_owned(), Json::String(value.clone()));
+        }
+    }
+
+    let numbers = [
+        ("bs", record.base_sum),
+        ("bt", record.base_time),
+        ("bv", record.base_value),
+        ("s", record.sum),
+        ("t", record.time),
+        ("ut", record.update_time),
+    ];
+    for (label, value) in numbers {
+        if let Some(value) = value {
+            fields.insert(label.to_owned(), to_number(value, label)?);
+        }
+    }
+
+    if let Some(version) = record.base_version {
+        fields.insert("bver".to_owned(), Json::Number(version.into()));
+    }
+
+    match &record.value {
+        Some(Value::Boolean(value)) => fields.insert("vb".to_owned(), Json::Bool(*value)),
+        Some(Value::Data(value)) => {
+            fields.insert("vd".to_owned(), Json::String(base64_encode(value)))
+        }
+        Some(Value::Number(value)) => fields.insert("v".to_owned(), to_number(*value, "v")?),
+        Some(Value::String(value)) => fields.insert("vs".to_owned(), Json::String(value.clone())),
+        None => None,
+    };
+
+    Ok(Json::Object(fields))
+}
+
+fn number(value: Json, label: &'static str) -> Result<f64, Error> {
+    value.as_f64().ok_or(Error::Field(label))
+}
+
+fn string(value: Json, label: &'static str) -> Result<String, Error> {
+    match value {
+        Json::String(value) => Ok(value),
+        _ => Err(Error::Field(label)),
+    }
+}
+
+fn to_number(value: f64, label: &'static str) -> Result<Json, Error> {
+    Number::from_f64(value)
+        .map(Json::Number)
+        .ok_or(Error::Field(label))
+}
+
+/// Base64 with the URL safe alphabet and without padding, as SenML requires for data values.
+fn base64_encode(data: &[u8]) -> String {
+    data.chunks(3)
+        .flat_map(|chunk| {
+            let bits = chunk.iter().enumerate().fold(0u32, |bits, (i, byte)| {
+                bits | (*byte as u32) << (16 - 8 * i)
+            });
+
+            (0..=chunk.len())
+                .map(move |i| BASE64_URL[(bits >> (18 - 6 * i) & 0b11_1111) as usize] as char)
+        })
+        .collect()
+}
+
+fn base64_decode(text: &str) -> Option<Vec<u8>> {
+    let text = text.trim_end_matches('=');
+    let sextets = text
+        .bytes()
+        .map(|c| BASE64_URL.iter().position(|b| *b == c).map(|p| p as u32))
+        .collect::<Option<Vec<_>>>()?;
+
+    if sextets.len() % 4 == 1 {
+        return None;
+    }
+
+    Some(
+        sextets
+            .chunks(4)
+            .flat_map(|chunk| {
+                let bits = chunk
+                    .iter()
+                    .enumerate()
+                    .fold(0u32, |bits, (i, sextet)| bits | sextet << (18 - 6 * i));
+
+                (0..chunk.len() - 1).map(move |i| (bits >> (16 - 8 * i)) as u8)
+            })
+            .collect(),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+    use rstest::rstest;
+
+    use super::{base64_decode, base64_encode};
+
+    #[rstest]
+    #[case(b"", "")]
+    #[case(b"f", "Zg")]
+    #[case(b"fo", "Zm8")]
+    #[case(b"foo", "Zm9v")]
+    #[case(&[0xfb, 0xff], "-_8")]
+    fn base64(#[case] data: &[u8], #[case] text: &str) {
+        assert_eq!(text, base64_encode(data));
+        assert_eq!(Some(data.to_vec()), base64_decode(text));
+    }
+}
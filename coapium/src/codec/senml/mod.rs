@@ -0,0 +1,307 @@
+//! SenML payloads as described in [RFC 8428](https://datatracker.ietf.org/doc/html/rfc8428).
+
+mod cbor;
+mod json;
+pub mod record;
+
+pub use record::{Record, ResolvedRecord, Value};
+
+use super::{option::ContentFormat, MediaType, Payload};
+
+/// Highest SenML version this implementation understands.
+pub const VERSION: u64 = 10;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Format {
+    Cbor,
+    Json,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Error {
+    Cbor,
+    /// A field is missing or has the wrong type, named by its JSON label.
+    Field(&'static str),
+    Json,
+    /// A label ending in `_` that must be understood by the recipient is not known.
+    MustUnderstand,
+    Name,
+    UnsupportedContentFormat,
+    Version(u64),
+}
+
+/// List of SenML records.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Pack {
+    pub records: Vec<Record>,
+}
+
+impl Format {
+    pub const CBOR: u16 = 112;
+    pub const JSON: u16 = 110;
+
+    pub fn from_content_format(content_format: &ContentFormat) -> Option<Self> {
+        match content_format.media_type().value()? {
+            Self::CBOR => Some(Self::Cbor),
+            Self::JSON => Some(Self::Json),
+            _ => None,
+        }
+    }
+
+    pub fn media_type(&self) -> MediaType {
+        match self {
+            Format::Cbor => MediaType::from_value(Self::CBOR),
+            Format::Json => MediaType::from_value(Self::JSON),
+        }
+    }
+}
+
+impl Pack {
+    pub fn new(records: Vec<Record>) -> Self {
+        Self { records }
+    }
+
+    pub fn decode(format: Format, payload: &Payload) -> Result<Self, Error> {
+        let records = match format {
+            Format::Cbor => cbor::decode(payload.value())?,
+            Format::Json => json::decode(payload.value())?,
+        };
+
+        Ok(Self { records })
+    }
+
+    pub fn encode(&self, format: Format) -> Result<Payload, Error> {
+        let bytes = match format {
+            Format::Cbor => cbor::encode(&self.records)?,
+            Format::Json => json::encode(&self.records)?,
+        };
+
+        Ok(Payload::from_value(bytes))
+    }
+
+    /// Decodes a payload in the format named by its Content-Format option.
+    pub fn from_payload(content_format: &ContentFormat, payload: &Payload) -> Result<Self, Error> {
+        let format =
+            Format::from_content_format(content_format).ok_or(Error::UnsupportedContentFormat)?;
+        Self::decode(format, payload)
+    }
+
+    /// Applies the base fields of every record to the records that follow it.
+    pub fn resolve(&self) -> Result<Vec<ResolvedRecord>, Error> {
+        let mut base = Record::new();
+
+        self.records
+            .iter()
+            .map(|record| {
+                if let Some(version) = record.base_version.filter(|version| *version > VERSION) {
+                    return Err(Error::Version(version));
+                }
+
+                base.base_name = record.base_name.clone().or(base.base_name.take());
+                base.base_sum = record.base_sum.or(base.base_sum);
+                base.base_time = record.base_time.or(base.base_time);
+                base.base_unit = record.base_unit.clone().or(base.base_unit.take());
+                base.base_value = record.base_value.or(base.base_value);
+
+                let name = format!(
+                    "{}{}",
+                    base.base_name.as_deref().unwrap_or_default(),
+                    record.name.as_deref().unwrap_or_default()
+                );
+                if !is_valid_name(&name) {
+                    return Err(Error::Name);
+                }
+
+                let value = match (&record.value, base.base_value) {
+                    (Some(Value::Number(value)), Some(base_value)) => {
+                        Some(Value::Number(base_value + value))
+                    }
+                    (value, _) => value.clone(),
+                };
+                let sum = match (record.sum, base.base_sum) {
+                    (Some(sum), Some(base_sum)) => Some(base_sum + sum),
+                    (sum, _) => sum,
+                };
+
+                Ok(ResolvedRecord {
+                    name,
+                    sum,
+                    time: base.base_time.unwrap_or_default() + record.time.unwrap_or_default(),
+                    unit: record.unit.clone().or(base.base_unit.clone()),
+                    update_time: record.update_time,
+                    value,
+                })
+            })
+            .collect()
+    }
+
+    /// Encodes the pack and tags it with the matching Content-Format.
+    pub fn to_payload(&self, format: Format) -> Result<(ContentFormat, Payload), Error> {
+        Ok((format.into(), self.encode(format)?))
+    }
+}
+
+impl From<Format> for ContentFormat {
+    fn from(value: Format) -> Self {
+        value.media_type().into()
+    }
+}
+
+/// Names must start with a letter or digit and only contain letters, digits, `-`, `:`, `.`, `/`
+/// and `_`.
+fn is_valid_name(name: &str) -> bool {
+    let mut characters = name.chars();
+    let Some(first) = characters.next() else {
+        return false;
+    };
+
+    first.is_ascii_alphanumeric()
+        && characters.all(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | ':' | '.' | '/' | '_'))
+}
+
+impl std::fmt::Display for Error {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Error::Cbor => write!(f, "SenML payload is not valid CBOR"),
+            Error::Field(label) => write!(f, "SenML field {label} is invalid"),
+            Error::Json => write!(f, "SenML payload is not valid JSON"),
+            Error::MustUnderstand => write!(f, "SenML record has an unknown must understand field"),
+            Error::Name => write!(f, "SenML record has an invalid name"),
+            Error::UnsupportedContentFormat => {
+                write!(f, "content format is not a SenML content format")
+            }
+            Error::Version(version) => {
+                write!(f, "SenML version {version} is newer than {VERSION}")
+            }
+        }
+    }
+}
+
+impl std::error::Error for Error {}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+    use rstest::rstest;
+
+    use crate::codec::{option::ContentFormat, MediaType, Payload};
+
+    use super::{Error, Format, Pack, Record, ResolvedRecord, Value};
+
+    fn pack() -> Pack {
+        Pack::new(vec![
+            Record::new()
+                .base_name("urn:dev:ow:10e2073a01080063:")
+                .base_time(1.276020076001e9)
+                .base_unit("A")
+                .name("voltage")
+                .unit("V")
+                .value(Value::Number(120.1)),
+            Record::new()
+                .name("current")
+                .time(-5.0)
+                .value(Value::Number(1.2)),
+            Record::new()
+                .name("label")
+                .value(Value::String("kitchen".to_owned())),
+            Record::new().name("open").value(Value::Boolean(true)),
+            Record::new()
+                .name("raw")
+                .value(Value::Data(vec![0, 1, 254, 255])),
+        ])
+    }
+
+    #[rstest]
+    #[case(Format::Cbor)]
+    #[case(Format::Json)]
+    fn round_trip(#[case] format: Format) {
+        let (content_format, payload) = pack().to_payload(format).unwrap();
+
+        assert_eq!(Ok(pack()), Pack::from_payload(&content_format, &payload));
+    }
+
+    #[rstest]
+    fn decode_json() {
+        let payload = Payload::from_value(
+            br#"[{"bn":"urn:dev:ow:10e2073a01080063:","bt":1.276020076001e9,"bu":"A","n":"voltage","u":"V","v":120.1},{"n":"current","t":-5,"v":1.2}]"#
+                .to_vec(),
+        );
+
+        assert_eq!(
+            Ok(Pack::new(pack().records[..2].to_vec())),
+            Pack::decode(Format::Json, &payload)
+        );
+    }
+
+    #[rstest]
+    fn resolve() {
+        let resolved = pack().resolve().unwrap();
+
+        assert_eq!(
+            ResolvedRecord {
+                name: "urn:dev:ow:10e2073a01080063:current".to_owned(),
+                sum: None,
+                time: 1.276020071001e9,
+                unit: Some("A".to_owned()),
+                update_time: None,
+                value: Some(Value::Number(1.2)),
+            },
+            resolved[1]
+        );
+        assert_eq!(Some("V".to_owned()), resolved[0].unit);
+    }
+
+    #[rstest]
+    fn resolve_adds_base_value() {
+        let pack = Pack::new(vec![
+            Record::new()
+                .base_name("temp")
+                .base_value(20.0)
+                .value(Value::Number(1.5)),
+            Record::new().value(Value::Number(-0.5)),
+        ]);
+
+        assert_eq!(
+            vec![Some(Value::Number(21.5)), Some(Value::Number(19.5))],
+            pack.resolve()
+                .unwrap()
+                .into_iter()
+                .map(|record| record.value)
+                .collect::<Vec<_>>()
+        );
+    }
+
+    #[rstest]
+    #[case(Pack::new(vec![Record::new().value(Value::Number(1.0))]), Error::Name)]
+    #[case(Pack::new(vec![Record::new().name("-a").value(Value::Number(1.0))]), Error::Name)]
+    #[case(Pack::new(vec![Record { base_version: Some(11), ..Record::new().name("a") }]), Error::Version(11))]
+    fn resolve_rejects_invalid_records(#[case] pack: Pack, #[case] expected: Error) {
+        assert_eq!(Err(expected), pack.resolve());
+    }
+
+    #[rstest]
+    #[case(MediaType::from_value(110), Some(Format::Json))]
+    #[case(MediaType::from_value(112), Some(Format::Cbor))]
+    #[case(MediaType::ApplicationJson, None)]
+    fn from_content_format(#[case] media_type: MediaType, #[case] expected: Option<Format>) {
+        assert_eq!(
+            expected,
+            Format::from_content_format(&ContentFormat::from(media_type))
+        );
+    }
+
+    #[rstest]
+    #[case(Format::Cbor, b"\x01".to_vec(), Error::Cbor)]
+    #[case(Format::Json, b"{}".to_vec(), Error::Json)]
+    #[case(Format::Json, br#"[{"v":"1"}]"#.to_vec(), Error::Field("v"))]
+    fn decode_rejects_invalid_payload(
+        #[case] format: Format,
+        #[case] payload: Vec<u8>,
+        #[case] expected: Error,
+    ) {
+        assert_eq!(
+            Err(expected),
+            Pack::decode(format, &Payload::from_value(payload))
+        );
+    }
+}
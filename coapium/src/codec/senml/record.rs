@@ -0,0 +1,95 @@
+/// Value of a SenML record, only one of the value fields can be set in a record.
+#[derive(Clone, Debug, PartialEq)]
+pub enum Value {
+    Boolean(bool),
+    Data(Vec<u8>),
+    Number(f64),
+    String(String),
+}
+
+/// Single record of a SenML pack as it appears on the wire, see
+/// [RFC 8428](https://datatracker.ietf.org/doc/html/rfc8428#section-4).
+///
+/// Base fields apply to the record itself and every record that follows it in the pack.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Record {
+    pub base_name: Option<String>,
+    pub base_sum: Option<f64>,
+    pub base_time: Option<f64>,
+    pub base_unit: Option<String>,
+    pub base_value: Option<f64>,
+    pub base_version: Option<u64>,
+    pub name: Option<String>,
+    pub sum: Option<f64>,
+    pub time: Option<f64>,
+    pub unit: Option<String>,
+    pub update_time: Option<f64>,
+    pub value: Option<Value>,
+}
+
+/// Record with the base fields applied, as described in
+/// [RFC 8428](https://datatracker.ietf.org/doc/html/rfc8428#section-4.6).
+#[derive(Clone, Debug, PartialEq)]
+pub struct ResolvedRecord {
+    pub name: String,
+    pub sum: Option<f64>,
+    /// Absolute time in seconds since the epoch, or when less than 2^28 relative to the time the
+    /// pack was received.
+    pub time: f64,
+    pub unit: Option<String>,
+    pub update_time: Option<f64>,
+    pub value: Option<Value>,
+}
+
+impl Record {
+    pub fn new() -> Self {
+        Self {
+            ..Default::default()
+        }
+    }
+
+    pub fn base_name<S: Into<String>>(mut self, base_name: S) -> Self {
+        self.base_name = Some(base_name.into());
+        self
+    }
+
+    pub fn base_time(mut self, base_time: f64) -> Self {
+        self.base_time = Some(base_time);
+        self
+    }
+
+    pub fn base_unit<S: Into<String>>(mut self, base_unit: S) -> Self {
+        self.base_unit = Some(base_unit.into());
+        self
+    }
+
+    pub fn base_value(mut self, base_value: f64) -> Self {
+        self.base_value = Some(base_value);
+        self
+    }
+
+    pub fn name<S: Into<String>>(mut self, name: S) -> Self {
+        self.name = Some(name.into());
+        self
+    }
+
+    pub fn sum(mut self, sum: f64) -> Self {
+        self.sum = Some(sum);
+        self
+    }
+
+    pub fn time(mut self, time: f64) -> Self {
+        self.time = Some(time);
+        self
+    }
+
+    pub fn unit<S: Into<String>>(mut self, unit: S) -> Self {
+        self.unit = Some(unit.into());
+        self
+    }
+
+    pub fn value(mut self, value: Value) -> Self {
+        self.value = Some(value);
+        self
+    }
+}
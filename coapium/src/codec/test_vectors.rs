@@ -0,0 +1,325 @@
+//! Corpus of CoAP datagrams together with the result of decoding them, for downstream
+//! implementations and fuzz targets to check compatibility against.
+//!
+//! Valid vectors are taken from the examples in
+//! [RFC 7252](https://datatracker.ietf.org/doc/html/rfc7252#appendix-A) and from exchanges typical
+//! of deployed devices, such as LwM2M registration and Observe notifications. Invalid vectors cover
+//! each way a datagram can be rejected by [`Message::decode`].
+
+use super::{
+    code::response_code::Success,
+    header,
+    message::{get, get_options, request, FormatError, Message},
+    message_type::MessageType,
+    option::{decoded_options, delta, delta_header, encoded_option, length, length_header, value},
+    options, token, token_length, version, Code, Error, MethodCode, ResponseCode,
+};
+
+/// Kind of message a valid datagram decodes to.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Kind {
+    Acknowledgement,
+    Piggyback,
+    Ping,
+    Request(MethodCode),
+    Reserved,
+    Reset,
+    Response(ResponseCode),
+}
+
+/// Where a vector comes from.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Source {
+    /// Datagram constructed to exercise an edge case of the decoder.
+    Constructed,
+    /// Exchange typical of a deployed device.
+    Device,
+    /// Example from RFC 7252.
+    Rfc7252,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct TestVector {
+    pub name: &'static str,
+    pub source: Source,
+    pub bytes: &'static [u8],
+    pub expected: Result<Kind, Error>,
+}
+
+/// Error of a GET whose options can't be parsed.
+const fn get_option_error(error: encoded_option::Error) -> Error {
+    Error::Request(request::Error::Get(get::Error::Options(
+        get_options::Error::Options(options::Error::DecodedOptions(
+            decoded_options::Error::EncodedOption(error),
+        )),
+    )))
+}
+
+pub const VECTORS: &[TestVector] = &[
+    TestVector {
+        name: "confirmable get with uri path",
+        source: Source::Rfc7252,
+        bytes: b"\x40\x01\x7d\x34\xbbtemperature",
+        expected: Ok(Kind::Request(MethodCode::Get)),
+    },
+    TestVector {
+        name: "piggybacked content response",
+        source: Source::Rfc7252,
+        bytes: b"\x60\x45\x7d\x34\xff22.3 C",
+        expected: Ok(Kind::Piggyback),
+    },
+    TestVector {
+        name: "confirmable get with token",
+        source: Source::Rfc7252,
+        bytes: b"\x41\x01\x7d\x34\x20\xbbtemperature",
+        expected: Ok(Kind::Request(MethodCode::Get)),
+    },
+    TestVector {
+        name: "piggybacked content response with token",
+        source: Source::Rfc7252,
+        bytes: b"\x61\x45\x7d\x34\x20\xff22.3 C",
+        expected: Ok(Kind::Piggyback),
+    },
+    TestVector {
+        name: "empty acknowledgement",
+        source: Source::Rfc7252,
+        bytes: &[0x60, 0x00, 0x7d, 0x34],
+        expected: Ok(Kind::Acknowledgement),
+    },
+    TestVector {
+        name: "separate confirmable response",
+        source: Source::Rfc7252,
+        bytes: b"\x41\x45\x23\xbb\x20\xff22.3 C",
+        expected: Ok(Kind::Response(ResponseCode::Success(Success::Content))),
+    },
+    TestVector {
+        name: "non-confirmable get",
+        source: Source::Rfc7252,
+        bytes: b"\x51\x01\x7d\x35\x21\xbbtemperature",
+        expected: Ok(Kind::Request(MethodCode::Get)),
+    },
+    TestVector {
+        name: "reset",
+        source: Source::Rfc7252,
+        bytes: &[0x70, 0x00, 0x7d, 0x35],
+        expected: Ok(Kind::Reset),
+    },
+    TestVector {
+        name: "ping",
+        source: Source::Constructed,
+        bytes: &[0x40, 0x00, 0x7d, 0x36],
+        expected: Ok(Kind::Ping),
+    },
+    TestVector {
+        name: "observe notification",
+        source: Source::Device,
+        bytes: b"\x42\x45\x7d\x36\xbe\xef\x61\x0c\x60\xff21.5",
+        expected: Ok(Kind::Response(ResponseCode::Success(Success::Content))),
+    },
+    TestVector {
+        name: "lwm2m register",
+        source: Source::Device,
+        bytes: b"\x44\x02\x00\x2a\x01\x02\x03\x04\xb2rd\x11\x28\x38ep=node1\xff</1/0>,</3/0>",
+        expected: Ok(Kind::Request(MethodCode::Post)),
+    },
+    TestVector {
+        name: "block2 request for second block",
+        source: Source::Device,
+        bytes: b"\x42\x01\x00\x2b\xca\xfe\xb8firmware\xc1\x12",
+        expected: Ok(Kind::Request(MethodCode::Get)),
+    },
+    TestVector {
+        name: "reserved code",
+        source: Source::Constructed,
+        bytes: &[0x40, 0xe0, 0x00, 0x01],
+        expected: Ok(Kind::Reserved),
+    },
+    TestVector {
+        name: "empty datagram",
+        source: Source::Constructed,
+        bytes: &[],
+        expected: Err(Error::Header(header::Error::DataLength)),
+    },
+    TestVector {
+        name: "single byte datagram",
+        source: Source::Constructed,
+        bytes: &[0x40],
+        expected: Err(Error::Header(header::Error::DataLength)),
+    },
+    TestVector {
+        name: "truncated header",
+        source: Source::Constructed,
+        bytes: &[0x40, 0x01],
+        expected: Err(Error::Header(header::Error::DataLength)),
+    },
+    TestVector {
+        name: "unsupported version",
+        source: Source::Constructed,
+        bytes: &[0x80, 0x01, 0x00, 0x01],
+        expected: Err(Error::Header(header::Error::Version(
+            version::Error::Unsupported(2),
+        ))),
+    },
+    TestVector {
+        name: "token length 9",
+        source: Source::Constructed,
+        bytes: &[0x49, 0x01, 0x00, 0x01, 1, 2, 3, 4, 5, 6, 7, 8, 9],
+        expected: Err(Error::Header(header::Error::TokenLength(
+            token_length::Error::OutOfRange(9),
+        ))),
+    },
+    TestVector {
+        name: "truncated token",
+        source: Source::Constructed,
+        bytes: &[0x42, 0x01, 0x00, 0x01, 0x01],
+        expected: Err(Error::Request(request::Error::Get(get::Error::Token(
+            token::Error::LengthOutOfRange,
+        )))),
+    },
+    TestVector {
+        name: "payload marker without payload",
+        source: Source::Constructed,
+        bytes: &[0x40, 0x01, 0x00, 0x01, 0xff],
+        expected: Err(Error::Request(request::Error::Get(
+            get::Error::ResidualData,
+        ))),
+    },
+    TestVector {
+        name: "truncated extended option length",
+        source: Source::Constructed,
+        bytes: &[0x40, 0x01, 0x00, 0x01, 0x0d],
+        expected: Err(get_option_error(encoded_option::Error::Value(
+            value::Error::Length(length::DecodeError::Insufficient {
+                needed: 1,
+                available: 0,
+            }),
+        ))),
+    },
+    TestVector {
+        name: "truncated 16 bit extended option length",
+        source: Source::Constructed,
+        bytes: &[0x40, 0x01, 0x00, 0x01, 0x0e, 0x01],
+        expected: Err(get_option_error(encoded_option::Error::Value(
+            value::Error::Length(length::DecodeError::Insufficient {
+                needed: 2,
+                available: 1,
+            }),
+        ))),
+    },
+    TestVector {
+        name: "truncated extended option delta",
+        source: Source::Constructed,
+        bytes: &[0x40, 0x01, 0x00, 0x01, 0xd0],
+        expected: Err(get_option_error(encoded_option::Error::Delta(
+            delta::DecodeError::Insufficient {
+                needed: 1,
+                available: 0,
+            },
+        ))),
+    },
+    TestVector {
+        name: "truncated 16 bit extended option delta",
+        source: Source::Constructed,
+        bytes: &[0x40, 0x01, 0x00, 0x01, 0xe0, 0x01],
+        expected: Err(get_option_error(encoded_option::Error::Delta(
+            delta::DecodeError::Insufficient {
+                needed: 2,
+                available: 1,
+            },
+        ))),
+    },
+    TestVector {
+        name: "option delta 15 outside the payload marker",
+        source: Source::Constructed,
+        bytes: &[0x40, 0x01, 0x00, 0x01, 0xb1, b'a', 0xf0],
+        expected: Err(get_option_error(encoded_option::Error::Delta(
+            delta::DecodeError::Header(delta_header::Error::Reserved),
+        ))),
+    },
+    TestVector {
+        name: "option length 15",
+        source: Source::Constructed,
+        bytes: &[0x40, 0x01, 0x00, 0x01, 0x1f],
+        expected: Err(get_option_error(encoded_option::Error::Value(
+            value::Error::Length(length::DecodeError::Header(length_header::Error::Reserved)),
+        ))),
+    },
+    TestVector {
+        name: "empty acknowledgement with token",
+        source: Source::Constructed,
+        bytes: &[0x61, 0x00, 0x00, 0x01, 0x01],
+        expected: Err(Error::Format(FormatError::TokenLengthNonZero)),
+    },
+    TestVector {
+        name: "empty acknowledgement with trailing data",
+        source: Source::Constructed,
+        bytes: &[0x60, 0x00, 0x00, 0x01, 0x01],
+        expected: Err(Error::Format(FormatError::ExcessiveData)),
+    },
+    TestVector {
+        name: "reset with request code",
+        source: Source::Constructed,
+        bytes: &[0x70, 0x01, 0x00, 0x01],
+        expected: Err(Error::Format(FormatError::InvalidTypeAndCode(
+            MessageType::Reset,
+            Code::Request(MethodCode::Get),
+        ))),
+    },
+];
+
+impl Kind {
+    pub fn of(message: &Message) -> Self {
+        match message {
+            Message::Acknowledgement(_) => Kind::Acknowledgement,
+            Message::Piggyback(_) => Kind::Piggyback,
+            Message::Ping(_) => Kind::Ping,
+            Message::Request(request) => Kind::Request(request.method_code()),
+            Message::Reserved(_) => Kind::Reserved,
+            Message::Reset(_) => Kind::Reset,
+            Message::Response(response) => Kind::Response(response.response_code()),
+        }
+    }
+}
+
+impl TestVector {
+    pub fn decode(&self) -> Result<Message, Error> {
+        Message::decode(self.bytes)
+    }
+
+    /// Whether decoding the datagram gives the expected result.
+    pub fn is_satisfied(&self) -> bool {
+        self.kind() == self.expected
+    }
+
+    pub fn kind(&self) -> Result<Kind, Error> {
+        self.decode().map(|message| Kind::of(&message))
+    }
+}
+
+pub fn invalid() -> impl Iterator<Item = &'static TestVector> {
+    VECTORS.iter().filter(|vector| vector.expected.is_err())
+}
+
+pub fn valid() -> impl Iterator<Item = &'static TestVector> {
+    VECTORS.iter().filter(|vector| vector.expected.is_ok())
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+    use rstest::rstest;
+
+    use super::{invalid, valid, VECTORS};
+
+    #[rstest]
+    fn vectors() {
+        for vector in VECTORS {
+            assert_eq!((vector.name, vector.expected), (vector.name, vector.kind()));
+        }
+    }
+
+    #[rstest]
+    fn valid_and_invalid_cover_all_vectors() {
+        assert_eq!(VECTORS.len(), valid().count() + invalid().count());
+    }
+}
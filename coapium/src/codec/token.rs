@@ -1,13 +1,16 @@
+#[cfg(feature = "rand")]
 use rand::{rngs::StdRng, RngCore, SeedableRng};
 
 use crate::codec::TokenLength;
 
 use super::token_length;
 
-#[derive(Clone, Debug, PartialEq)]
+/// Token stored inline, bytes past `length` are always zero so equality and hashing only depend
+/// on the token itself.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
 pub struct Token {
     length: TokenLength,
-    value: Vec<u8>,
+    value: [u8; TokenLength::MAX as usize],
 }
 
 #[derive(Clone, Copy, Debug, PartialEq)]
@@ -16,15 +19,12 @@ pub enum Error {
 }
 
 impl Token {
-    pub fn decode(bytes: Vec<u8>) -> Result<Self, Error> {
-        let token_length = u8::try_from(bytes.len())
-            .map(TokenLength::from_value)
-            .map_err(|_| Error::LengthOutOfRange)??;
+    pub fn as_bytes(&self) -> &[u8] {
+        &self.value[..usize::from(self.length.value())]
+    }
 
-        Ok(Self {
-            length: token_length,
-            value: bytes,
-        })
+    pub fn decode(bytes: Vec<u8>) -> Result<Self, Error> {
+        Self::from_slice(&bytes)
     }
 
     pub fn empty() -> Self {
@@ -32,7 +32,21 @@ impl Token {
     }
 
     pub fn encode(self) -> (TokenLength, Vec<u8>) {
-        (self.length, self.value)
+        (self.length, self.as_bytes().to_vec())
+    }
+
+    pub fn from_slice(bytes: &[u8]) -> Result<Self, Error> {
+        let token_length = u8::try_from(bytes.len())
+            .map(TokenLength::from_value)
+            .map_err(|_| Error::LengthOutOfRange)??;
+
+        let mut value = [0; TokenLength::MAX as usize];
+        value[..bytes.len()].copy_from_slice(bytes);
+
+        Ok(Self {
+            length: token_length,
+            value,
+        })
     }
 
     pub fn from_value(value: Vec<u8>) -> Result<Self, Error> {
@@ -40,19 +54,26 @@ impl Token {
     }
 
     pub fn is_empty(&self) -> bool {
-        self.value.is_empty()
+        self.length.value() == 0
     }
 
     pub const fn length(&self) -> u8 {
         self.length.value()
     }
 
+    #[cfg(feature = "rand")]
     pub fn new() -> Result<Self, Error> {
-        let mut rng = StdRng::from_entropy();
-        let mut bytes = [0; TokenLength::MAX as usize];
-        rng.fill_bytes(&mut bytes);
+        Ok(Self::random(TokenLength::decode(TokenLength::MAX)))
+    }
 
-        Self::decode(bytes.to_vec())
+    /// Token of `length` random bytes. Shorter tokens save bytes on every message but are more
+    /// likely to collide, so they have to be checked against the tokens in use.
+    #[cfg(feature = "rand")]
+    pub fn random(length: TokenLength) -> Self {
+        let mut value = [0; TokenLength::MAX as usize];
+        StdRng::from_entropy().fill_bytes(&mut value[..usize::from(length.value())]);
+
+        Self { length, value }
     }
 
     pub fn parse<'a>(
@@ -65,13 +86,21 @@ impl Token {
             return Err(Error::LengthOutOfRange);
         }
 
-        let token = Self::decode(bytes[..length].to_vec())?;
+        let token = Self::from_slice(&bytes[..length])?;
 
         Ok((&bytes[length..], token))
     }
 
     pub fn value(&self) -> Vec<u8> {
-        self.value.clone()
+        self.as_bytes().to_vec()
+    }
+}
+
+impl std::fmt::Display for Token {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        self.as_bytes()
+            .iter()
+            .try_for_each(|byte| write!(f, "{byte:02x}"))
     }
 }
 
@@ -80,6 +109,23 @@ impl From<token_length::Error> for Error {
         Self::LengthOutOfRange
     }
 }
+
+impl std::fmt::Display for Error {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Error::LengthOutOfRange => {
+                write!(
+                    f,
+                    "token length is out of range, max is {}",
+                    TokenLength::MAX
+                )
+            }
+        }
+    }
+}
+
+impl std::error::Error for Error {}
+
 #[cfg(test)]
 mod tests {
     use pretty_assertions::assert_eq;
@@ -88,19 +134,19 @@ mod tests {
     use super::{Error, Token, TokenLength};
 
     #[rstest]
-    #[case(vec![], Token { length: TokenLength::from_value(0).unwrap(), value: vec![] })]
-    #[case(vec![1], Token { length: TokenLength::from_value(1).unwrap(), value: vec![1] })]
-    #[case(vec![1, 2], Token { length: TokenLength::from_value(2).unwrap(), value: vec![1, 2] })]
-    #[case(vec![1, 2, 3], Token { length: TokenLength::from_value(3).unwrap(), value: vec![1, 2, 3] })]
-    #[case(vec![1, 2, 3, 4], Token { length: TokenLength::from_value(4).unwrap(), value: vec![1, 2, 3, 4] })]
-    #[case(vec![1, 2, 3, 4, 5], Token { length: TokenLength::from_value(5).unwrap(), value: vec![1, 2, 3, 4, 5] })]
-    #[case(vec![1, 2, 3, 4, 5, 6], Token { length: TokenLength::from_value(6).unwrap(), value: vec![1, 2, 3, 4, 5, 6] })]
-    #[case(vec![1, 2, 3, 4, 5, 6, 7], Token { length: TokenLength::from_value(7).unwrap(), value: vec![1, 2, 3, 4, 5, 6, 7] })]
-    #[case(vec![1, 2, 3, 4, 5, 6, 7, 8], Token { length: TokenLength::from_value(8).unwrap(), value: vec![1, 2, 3, 4, 5, 6, 7, 8] })]
+    #[case(vec![], Token { length: TokenLength::from_value(0).unwrap(), value: [0, 0, 0, 0, 0, 0, 0, 0] })]
+    #[case(vec![1], Token { length: TokenLength::from_value(1).unwrap(), value: [1, 0, 0, 0, 0, 0, 0, 0] })]
+    #[case(vec![1, 2], Token { length: TokenLength::from_value(2).unwrap(), value: [1, 2, 0, 0, 0, 0, 0, 0] })]
+    #[case(vec![1, 2, 3], Token { length: TokenLength::from_value(3).unwrap(), value: [1, 2, 3, 0, 0, 0, 0, 0] })]
+    #[case(vec![1, 2, 3, 4], Token { length: TokenLength::from_value(4).unwrap(), value: [1, 2, 3, 4, 0, 0, 0, 0] })]
+    #[case(vec![1, 2, 3, 4, 5], Token { length: TokenLength::from_value(5).unwrap(), value: [1, 2, 3, 4, 5, 0, 0, 0] })]
+    #[case(vec![1, 2, 3, 4, 5, 6], Token { length: TokenLength::from_value(6).unwrap(), value: [1, 2, 3, 4, 5, 6, 0, 0] })]
+    #[case(vec![1, 2, 3, 4, 5, 6, 7], Token { length: TokenLength::from_value(7).unwrap(), value: [1, 2, 3, 4, 5, 6, 7, 0] })]
+    #[case(vec![1, 2, 3, 4, 5, 6, 7, 8], Token { length: TokenLength::from_value(8).unwrap(), value: [1, 2, 3, 4, 5, 6, 7, 8] })]
     fn decode_value_encode(#[case] bytes: Vec<u8>, #[case] expected: Token) {
         let decoded = Token::decode(bytes.clone()).unwrap();
         let value = decoded.value();
-        let (encoded_length, encoded_bytes) = decoded.clone().encode();
+        let (encoded_length, encoded_bytes) = decoded.encode();
 
         assert_eq!(expected, decoded);
         assert_eq!(bytes, value);
@@ -140,6 +186,15 @@ mod tests {
         assert_eq!(expected, Token::from_value(value).unwrap().is_empty())
     }
 
+    #[rstest]
+    #[case(vec![], "")]
+    #[case(vec![0x0a, 0xff], "0aff")]
+    #[case(vec![1, 2, 3, 4, 5, 6, 7, 8], "0102030405060708")]
+    fn display(#[case] value: Vec<u8>, #[case] expected: &str) {
+        assert_eq!(expected, Token::from_value(value).unwrap().to_string())
+    }
+
+    #[cfg(feature = "rand")]
     #[rstest]
     fn new() {
         let token = Token::new().unwrap();
@@ -147,6 +202,22 @@ mod tests {
         assert_eq!(8, token.length())
     }
 
+    #[cfg(feature = "rand")]
+    #[rstest]
+    #[case(0)]
+    #[case(4)]
+    #[case(8)]
+    fn random(#[case] length: u8) {
+        let token = Token::random(TokenLength::from_value(length).unwrap());
+
+        assert_eq!(length, token.length());
+        assert_eq!(
+            Token::from_slice(token.as_bytes()).unwrap(),
+            token,
+            "bytes past the length should be zero"
+        );
+    }
+
     #[rstest]
     #[case(0, &[1,2,3,4,5,6,7,8], &[1,2,3,4,5,6,7,8], &[0; 0])]
     #[case(1, &[1,2,3,4,5,6,7,8], &[2,3,4,5,6,7,8], &[1])]
@@ -168,7 +239,7 @@ mod tests {
         let (actual_rest, actual_token) = Token::parse(token_length, input).unwrap();
 
         assert_eq!(expected_rest, actual_rest);
-        assert_eq!(expected_token, actual_token.value);
+        assert_eq!(expected_token, actual_token.as_bytes());
     }
 
     #[rstest]
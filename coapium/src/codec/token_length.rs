@@ -17,7 +17,7 @@ const MASK: u8 = 0b0000_1111;
 ///
 /// A reserved value will treated as a parsing error and will result in [`OutOfBounds`](`Error::OutOfBounds`).
 ///
-#[derive(Clone, Copy, Debug, PartialEq)]
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
 pub struct TokenLength {
     value: u8,
 }
@@ -35,6 +35,10 @@ impl TokenLength {
     /// Max token length value.
     pub const MAX: u8 = 8;
 
+    /// Length of the tokens generated for requests unless configured otherwise. Four random bytes
+    /// keep tokens apart among the requests in flight while saving four bytes on every message.
+    pub const DEFAULT: u8 = 4;
+
     /// Parse the byte from the [message header](https://datatracker.ietf.org/doc/html/rfc7252#section-3).
     pub const fn decode(byte: u8) -> Self {
         Self {
@@ -72,6 +76,22 @@ impl TokenLength {
     }
 }
 
+impl std::fmt::Display for Error {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Error::OutOfRange(value) => {
+                write!(
+                    f,
+                    "token length {value} is out of range, max is {}",
+                    TokenLength::MAX
+                )
+            }
+        }
+    }
+}
+
+impl std::error::Error for Error {}
+
 #[cfg(test)]
 mod tests {
     use pretty_assertions::assert_eq;
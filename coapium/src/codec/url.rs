@@ -161,3 +161,16 @@ impl TryFrom<&str> for Endpoint {
         Ok(Self { scheme, host, port })
     }
 }
+
+impl std::fmt::Display for Error {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Error::Scheme => write!(f, "unsupported scheme"),
+            Error::Host => write!(f, "invalid host"),
+            Error::Port => write!(f, "invalid port"),
+            Error::Format => write!(f, "invalid url format"),
+        }
+    }
+}
+
+impl std::error::Error for Error {}
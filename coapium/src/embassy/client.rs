@@ -0,0 +1,298 @@
+use std::pin::pin;
+
+use embassy_time::{Instant, Timer};
+use embedded_nal_async::ConnectedUdp;
+use futures::future::{select, Either};
+use log::debug;
+
+use crate::{
+    codec::{message_id::MessageId, Token},
+    protocol::{
+        effect::{prioritize, Effect, Timeout},
+        event::Event,
+        message_id_store::MessageIdStore,
+        new_request::NewRequest,
+        ping::{self, Ping},
+        processor::{self, Processor},
+        response::{self, Response},
+        transaction::PATH_MTU,
+    },
+};
+
+use super::EmbassyClock;
+
+/// Client that drives the [`Processor`] over a connected `embedded-nal-async` UDP socket.
+///
+/// The socket is only read while a request is executed, there is one request at a time and
+/// responses that belong to no request are dropped.
+#[derive(Debug)]
+pub struct Client<S> {
+    processor: Processor,
+    socket: S,
+    timeouts: Vec<(Instant, Timeout)>,
+    unknown_messages: Vec<Vec<u8>>,
+}
+
+#[derive(Debug)]
+pub enum Error<E> {
+    Ping(ping::Error),
+    Processor(processor::Error),
+    Response(response::Error),
+    Socket(E),
+    Token,
+}
+
+impl<S: ConnectedUdp> Client<S> {
+    /// Creates a client with a processor that uses the [`EmbassyClock`].
+    pub fn new(socket: S) -> Self {
+        let initial_message_id = MessageId::from_value(rand::random());
+        let processor =
+            Processor::new(MessageIdStore::new(initial_message_id)).with_clock(EmbassyClock::new());
+
+        Self::from_processor(socket, processor)
+    }
+
+    pub fn from_processor(socket: S, processor: Processor) -> Self {
+        Self {
+            processor,
+            socket,
+            timeouts: vec![],
+            unknown_messages: vec![],
+        }
+    }
+
+    /// Messages with a reserved code received since the last call, when the processor was created
+    /// with [`ReservedPolicy::Deliver`](processor::ReservedPolicy::Deliver).
+    pub fn take_unknown_messages(&mut self) -> Vec<Vec<u8>> {
+        std::mem::take(&mut self.unknown_messages)
+    }
+
+    pub async fn execute(&mut self, request: NewRequest) -> Result<Response, Error<S::Error>> {
+        self.resolve(request).await?.map_err(Error::Response)
+    }
+
+    pub async fn ping(&mut self, ping: Ping) -> Result<(), Error<S::Error>> {
+        let result = self.resolve(NewRequest::Ping(ping)).await?;
+
+        ping::into_result(result).map_err(Error::Ping)
+    }
+
+    /// Feeds the processor until the transaction of `request` is resolved.
+    async fn resolve(
+        &mut self,
+        request: NewRequest,
+    ) -> Result<Result<Response, response::Error>, Error<S::Error>> {
+        let token = Token::new().map_err(|_| Error::Token)?;
+
+        let mut event = Event::TransactionRequested(request, token);
+        loop {
+            if let Some(result) = self.tick(event, token).await? {
+                return Ok(result);
+            }
+
+            event = self.next_event().await?;
+        }
+    }
+
+    async fn next_event(&mut self) -> Result<Event, Error<S::Error>> {
+        let mut buffer = [0; PATH_MTU];
+
+        let earliest = self
+            .timeouts
+            .iter()
+            .enumerate()
+            .min_by_key(|(_, (deadline, _))| *deadline)
+            .map(|(index, (deadline, _))| (index, *deadline));
+
+        let Some((index, deadline)) = earliest else {
+            let read = self
+                .socket
+                .receive_into(&mut buffer)
+                .await
+                .map_err(Error::Socket)?;
+            return Ok(Event::DataReceived(buffer[..read].to_vec()));
+        };
+
+        let read = {
+            let receive = pin!(self.socket.receive_into(&mut buffer));
+            match select(receive, pin!(Timer::at(deadline))).await {
+                Either::Left((read, _)) => Some(read.map_err(Error::Socket)?),
+                Either::Right(_) => None,
+            }
+        };
+
+        match read {
+            Some(read) => Ok(Event::DataReceived(buffer[..read].to_vec())),
+            None => Ok(Event::TimeoutReached(self.timeouts.swap_remove(index).1)),
+        }
+    }
+
+    /// Hands the event to the processor and carries out the effects, returns the result once the
+    /// transaction of `token` is resolved.
+    async fn tick(
+        &mut self,
+        event: Event,
+        token: Token,
+    ) -> Result<Option<Result<Response, response::Error>>, Error<S::Error>> {
+        let mut effects = self.processor.tick(event).map_err(Error::Processor)?;
+        prioritize(&mut effects);
+
+        let mut resolved = None;
+        for effect in effects {
+            match effect {
+                Effect::CreateTimeout(timeout) => {
+                    let duration =
+                        embassy_time::Duration::from_micros(timeout.duration().as_micros() as u64);
+                    self.timeouts.push((Instant::now() + duration, timeout));
+                }
+                Effect::Transmit(data) => self.socket.send(&data).await.map_err(Error::Socket)?,
+                Effect::TransactionResolved(resolved_token, result) if resolved_token == token => {
+                    resolved = Some(result)
+                }
+                Effect::TransactionResolved(resolved_token, _) => {
+                    debug!("Dropping result of transaction {resolved_token}");
+                }
+                Effect::UnknownMessageReceived(data) => self.unknown_messages.push(data),
+                Effect::MalformedDatagramReceived(_)
+                | Effect::NotificationReceived(..)
+                | Effect::TransactionCanceled(_) => {}
+            }
+        }
+
+        Ok(resolved)
+    }
+}
+
+impl<E: std::fmt::Debug> std::fmt::Display for Error<E> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Error::Ping(error) => error.fmt(f),
+            Error::Processor(error) => error.fmt(f),
+            Error::Response(error) => error.fmt(f),
+            Error::Socket(error) => write!(f, "socket failed: {error:?}"),
+            Error::Token => write!(f, "failed to create a token"),
+        }
+    }
+}
+
+impl<E: std::fmt::Debug> std::error::Error for Error<E> {}
+
+#[cfg(test)]
+mod tests {
+    use std::{convert::Infallible, time::Duration};
+
+    use futures::executor::block_on;
+    use pretty_assertions::assert_eq;
+    use rstest::rstest;
+
+    use crate::{
+        codec::{
+            code::response_code::Success, message::GetOptions, MessageId, Payload, ResponseCode,
+        },
+        protocol::{
+            get::Get,
+            message_id_store::MessageIdStore,
+            new_request::NewRequest,
+            processor::{Processor, ReservedPolicy},
+            reliability::Reliability,
+            transmission_parameters::ConfirmableParameters,
+        },
+    };
+
+    use super::{Client, ConnectedUdp};
+
+    /// Peer that answers every request with a piggybacked 2.05, after ignoring the first
+    /// `ignored` transmissions, and sends a message with a reserved code ahead of it if
+    /// `reserved`.
+    #[derive(Debug, Default)]
+    struct Peer {
+        ignored: usize,
+        pending: Vec<Vec<u8>>,
+        received: usize,
+        reserved: bool,
+    }
+
+    impl ConnectedUdp for Peer {
+        type Error = Infallible;
+
+        async fn send(&mut self, data: &[u8]) -> Result<(), Self::Error> {
+            self.received += 1;
+            if self.received <= self.ignored {
+                return Ok(());
+            }
+
+            let token_length = (data[0] & 0x0f) as usize;
+            let mut response = vec![0x60 | data[0] & 0x0f, 0x45, data[2], data[3]];
+            response.extend(&data[4..4 + token_length]);
+            response.extend([0xff, b'o', b'k']);
+            self.pending.push(response);
+            if self.reserved {
+                self.pending.push(vec![0x40, 0xe0, 0x00, 0x07]);
+            }
+
+            Ok(())
+        }
+
+        async fn receive_into(&mut self, buffer: &mut [u8]) -> Result<usize, Self::Error> {
+            let Some(response) = self.pending.pop() else {
+                return futures::future::pending().await;
+            };
+
+            buffer[..response.len()].copy_from_slice(&response);
+            Ok(response.len())
+        }
+    }
+
+    fn request() -> NewRequest {
+        NewRequest::Get(Get {
+            options: GetOptions::new(),
+            reliability: Reliability::Confirmable(
+                ConfirmableParameters::builder()
+                    .ack_timeout(Duration::from_secs(1))
+                    .ack_random_factor(1.0)
+                    .initial_retransmission_factor(0.0)
+                    .build()
+                    .unwrap(),
+            ),
+        })
+    }
+
+    #[rstest]
+    #[case(0, 1)]
+    #[case(1, 2)]
+    fn execute_resolves_with_response(#[case] ignored: usize, #[case] transmissions: usize) {
+        let mut client = Client::new(Peer {
+            ignored,
+            ..Default::default()
+        });
+
+        let response = block_on(client.execute(request())).unwrap();
+
+        assert_eq!(
+            ResponseCode::Success(Success::Content),
+            response.response_code
+        );
+        assert_eq!(Payload::from_value(b"ok".to_vec()), response.payload);
+        assert_eq!(transmissions, client.socket.received);
+    }
+
+    #[rstest]
+    #[case(ReservedPolicy::Ignore, vec![])]
+    #[case(ReservedPolicy::Deliver, vec![vec![0x40, 0xe0, 0x00, 0x07]])]
+    fn keeps_unknown_messages(#[case] policy: ReservedPolicy, #[case] expected: Vec<Vec<u8>>) {
+        let processor = Processor::new(MessageIdStore::new(MessageId::from_value(1)))
+            .with_reserved_policy(policy);
+        let mut client = Client::from_processor(
+            Peer {
+                reserved: true,
+                ..Default::default()
+            },
+            processor,
+        );
+
+        block_on(client.execute(request())).unwrap();
+
+        assert_eq!(expected, client.take_unknown_messages());
+        assert!(client.take_unknown_messages().is_empty());
+    }
+}
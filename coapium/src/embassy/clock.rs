@@ -0,0 +1,33 @@
+use std::time::{Duration, Instant};
+
+use crate::protocol::clock::Clock;
+
+/// Clock that follows the embassy time driver, so the processor sees the same time as the timers
+/// of the [`Client`](super::Client).
+#[derive(Clone, Copy, Debug)]
+pub struct EmbassyClock {
+    embassy_origin: embassy_time::Instant,
+    origin: Instant,
+}
+
+impl EmbassyClock {
+    pub fn new() -> Self {
+        Self {
+            embassy_origin: embassy_time::Instant::now(),
+            origin: Instant::now(),
+        }
+    }
+}
+
+impl Default for EmbassyClock {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Clock for EmbassyClock {
+    fn now(&self) -> Instant {
+        let elapsed = embassy_time::Instant::now() - self.embassy_origin;
+        self.origin + Duration::from_micros(elapsed.as_micros())
+    }
+}
@@ -0,0 +1,13 @@
+//! Client for devices running embassy, on top of the `embedded-nal-async` UDP traits and
+//! `embassy-time`, behind the `embassy` feature.
+//!
+//! The processor needs `std`, so this only builds for targets with a `std` port such as the ESP32
+//! family under esp-idf. Bare-metal targets like the RP2040 or nRF52 aren't supported until the
+//! processor, its stores and its clock work without `std`. The client doesn't spawn anything and
+//! drives the processor from the task that executes the request.
+
+pub mod client;
+pub mod clock;
+
+pub use client::Client;
+pub use clock::EmbassyClock;
@@ -1,5 +1,20 @@
+// The processor, its stores and its clock need `std`, bare-metal targets get a clear error instead
+// of a missing `std` crate.
+#[cfg(all(feature = "embassy", target_os = "none"))]
+compile_error!("the `embassy` feature needs `std`, bare-metal targets like the RP2040 or nRF52 aren't supported yet");
+
+#[cfg(feature = "client")]
 pub mod asynchronous;
+#[cfg(feature = "client")]
 pub mod client;
 pub mod codec;
+#[cfg(feature = "embassy")]
+pub mod embassy;
+#[cfg(feature = "protocol")]
+pub mod lwm2m;
+#[cfg(feature = "protocol")]
 pub mod protocol;
+#[cfg(feature = "protocol")]
+pub mod server;
+#[cfg(feature = "client")]
 pub mod synchronous;
@@ -0,0 +1,87 @@
+use crate::codec::{option, MediaType};
+
+/// Content formats an LwM2M client exchanges with a server, the LwM2M specific formats are
+/// registered in the CoAP content format registry.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum ContentFormat {
+    LinkFormat,
+    Lwm2mJson,
+    Opaque,
+    SenmlCbor,
+    SenmlJson,
+    TextPlain,
+    Tlv,
+}
+
+impl ContentFormat {
+    pub const LWM2M_JSON: u16 = 11543;
+    pub const SENML_CBOR: u16 = 112;
+    pub const SENML_JSON: u16 = 110;
+    pub const TLV: u16 = 11542;
+
+    pub fn from_media_type(media_type: &MediaType) -> Option<Self> {
+        match media_type {
+            MediaType::TextPlain => Some(Self::TextPlain),
+            MediaType::ApplicationLinkFormat => Some(Self::LinkFormat),
+            MediaType::ApplicationOctetStream => Some(Self::Opaque),
+            media_type => match media_type.value()? {
+                Self::LWM2M_JSON => Some(Self::Lwm2mJson),
+                Self::SENML_CBOR => Some(Self::SenmlCbor),
+                Self::SENML_JSON => Some(Self::SenmlJson),
+                Self::TLV => Some(Self::Tlv),
+                _ => None,
+            },
+        }
+    }
+
+    pub fn media_type(&self) -> MediaType {
+        match self {
+            ContentFormat::LinkFormat => MediaType::ApplicationLinkFormat,
+            ContentFormat::Lwm2mJson => MediaType::from_value(Self::LWM2M_JSON),
+            ContentFormat::Opaque => MediaType::ApplicationOctetStream,
+            ContentFormat::SenmlCbor => MediaType::from_value(Self::SENML_CBOR),
+            ContentFormat::SenmlJson => MediaType::from_value(Self::SENML_JSON),
+            ContentFormat::TextPlain => MediaType::TextPlain,
+            ContentFormat::Tlv => MediaType::from_value(Self::TLV),
+        }
+    }
+}
+
+impl From<ContentFormat> for option::ContentFormat {
+    fn from(value: ContentFormat) -> Self {
+        value.media_type().into()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+    use rstest::rstest;
+
+    use crate::codec::MediaType;
+
+    use super::ContentFormat;
+
+    #[rstest]
+    #[case(ContentFormat::LinkFormat)]
+    #[case(ContentFormat::Lwm2mJson)]
+    #[case(ContentFormat::Opaque)]
+    #[case(ContentFormat::SenmlCbor)]
+    #[case(ContentFormat::SenmlJson)]
+    #[case(ContentFormat::TextPlain)]
+    #[case(ContentFormat::Tlv)]
+    fn media_type_round_trip(#[case] content_format: ContentFormat) {
+        assert_eq!(
+            Some(content_format),
+            ContentFormat::from_media_type(&content_format.media_type())
+        );
+    }
+
+    #[rstest]
+    fn unknown_media_type() {
+        assert_eq!(
+            None,
+            ContentFormat::from_media_type(&MediaType::ApplicationXml)
+        );
+    }
+}
@@ -0,0 +1,488 @@
+use std::{fmt::Debug, net::SocketAddr};
+
+use crate::{
+    codec::{
+        code::response_code::{ClientError, Success},
+        message::{Reliability, Request},
+        option::Observe,
+        MessageId, Options, Payload, Piggyback, Response, ResponseCode, Token,
+    },
+    server::observable::Notification,
+};
+
+use super::{ContentFormat, Path, Registration};
+
+/// Value of an object, object instance or resource together with its content format.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Content {
+    pub content_format: ContentFormat,
+    pub payload: Payload,
+}
+
+/// Handler of a single LwM2M object and all of its instances.
+///
+/// Operations that are not implemented answer with 4.05 (Method Not Allowed).
+pub trait ObjectHandler: Debug + Send {
+    /// Instances of the object, announced to the server when registering.
+    fn instances(&self) -> Vec<u16>;
+
+    fn read(&mut self, path: &Path) -> Result<Content, ResponseCode>;
+
+    fn write(&mut self, _path: &Path, _content: Content) -> Result<(), ResponseCode> {
+        Err(method_not_allowed())
+    }
+
+    fn execute(&mut self, _path: &Path, _arguments: &Payload) -> Result<(), ResponseCode> {
+        Err(method_not_allowed())
+    }
+
+    fn delete(&mut self, _path: &Path) -> Result<(), ResponseCode> {
+        Err(method_not_allowed())
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+struct Observation {
+    endpoint: SocketAddr,
+    last_message_id: Option<MessageId>,
+    path: Path,
+    token: Token,
+}
+
+/// LwM2M endpoint client, dispatches requests from a server to object handlers and keeps track
+/// of the observations the server made.
+///
+/// The endpoint is sans-IO, requests are handed to [Endpoint::handle] and the returned messages
+/// are expected to be sent by the caller.
+#[derive(Debug)]
+pub struct Endpoint {
+    bootstrapped: bool,
+    next_message_id: MessageId,
+    objects: Vec<(u16, Box<dyn ObjectHandler>)>,
+    observations: Vec<Observation>,
+    registration: Registration,
+    sequence: u32,
+}
+
+impl Endpoint {
+    pub fn new(registration: Registration, initial_message_id: MessageId) -> Self {
+        Self {
+            bootstrapped: false,
+            next_message_id: initial_message_id,
+            objects: vec![],
+            observations: vec![],
+            registration,
+            sequence: 0,
+        }
+    }
+
+    pub fn with_object(mut self, object: u16, handler: Box<dyn ObjectHandler>) -> Self {
+        self.objects.retain(|(id, _)| *id != object);
+        self.objects.push((object, handler));
+        self
+    }
+
+    /// Handles a request from a server and returns the encoded response.
+    pub fn handle(&mut self, endpoint: SocketAddr, request: &Request) -> Notification {
+        let (response_code, options, payload) = self.dispatch(endpoint, request);
+
+        match request.reliability() {
+            Reliability::Confirmable => Notification {
+                data: Piggyback::new(
+                    *request.token(),
+                    response_code,
+                    request.message_id(),
+                    options,
+                    payload,
+                )
+                .encode(),
+                endpoint,
+                message_id: request.message_id(),
+                reliability: Reliability::Confirmable,
+            },
+            Reliability::NonConfirmable => {
+                let message_id = self.claim_message_id();
+                Notification {
+                    data: Response::new(
+                        Reliability::NonConfirmable,
+                        *request.token(),
+                        response_code,
+                        message_id,
+                        options,
+                        payload,
+                    )
+                    .encode(),
+                    endpoint,
+                    message_id,
+                    reliability: Reliability::NonConfirmable,
+                }
+            }
+        }
+    }
+
+    /// Whether the bootstrap server has finished bootstrapping the client.
+    pub fn is_bootstrapped(&self) -> bool {
+        self.bootstrapped
+    }
+
+    /// Creates a confirmable notification for every observation of the changed path or of one
+    /// of its parents, so servers that are gone can be detected by the missing acknowledgement.
+    pub fn notify(&mut self, changed: &Path) -> Vec<Notification> {
+        self.sequence = (self.sequence + 1) & Observe::MAX;
+
+        let mut observations = std::mem::take(&mut self.observations);
+        let notifications = observations
+            .iter_mut()
+            .filter(|observation| {
+                observation.path.contains(changed) || changed.contains(&observation.path)
+            })
+            .filter_map(|observation| self.notification(observation))
+            .collect();
+        self.observations = observations;
+
+        notifications
+    }
+
+    /// Cancels the observation a rejected notification was sent for, returns `false` if no
+    /// notification matches.
+    pub fn on_reset(&mut self, endpoint: &SocketAddr, message_id: MessageId) -> bool {
+        let Some(position) = self.observations.iter().position(|observation| {
+            observation.endpoint == *endpoint && observation.last_message_id == Some(message_id)
+        }) else {
+            return false;
+        };
+
+        self.observations.swap_remove(position);
+        true
+    }
+
+    /// Registration with every object instance of the registered handlers.
+    pub fn registration(&self) -> Registration {
+        self.objects
+            .iter()
+            .flat_map(|(object, handler)| match handler.instances()[..] {
+                [] => vec![Path::object(*object)],
+                ref instances => instances
+                    .iter()
+                    .map(|instance| Path::instance(*object, *instance))
+                    .collect(),
+            })
+            .fold(self.registration.clone(), Registration::object)
+    }
+
+    fn claim_message_id(&mut self) -> MessageId {
+        let message_id = self.next_message_id;
+        self.next_message_id = message_id.next();
+        message_id
+    }
+
+    fn dispatch(
+        &mut self,
+        endpoint: SocketAddr,
+        request: &Request,
+    ) -> (ResponseCode, Options, Payload) {
+        let segments = request
+            .uri_path()
+            .map(|uri_path| uri_path.segments())
+            .unwrap_or_default();
+
+        if matches!(request, Request::Post(_)) && segments == ["bs"] {
+            self.bootstrapped = true;
+            return empty(ResponseCode::Success(Success::Changed));
+        }
+
+        let Ok(path) = Path::from_segments(&segments) else {
+            return empty(ResponseCode::ClientError(ClientError::NotFound));
+        };
+
+        let Some(handler) = self
+            .objects
+            .iter_mut()
+            .find(|(object, _)| *object == path.object_id())
+            .map(|(_, handler)| handler)
+        else {
+            return empty(ResponseCode::ClientError(ClientError::NotFound));
+        };
+
+        let result = match request {
+            Request::Get(get) => match handler.read(&path) {
+                Ok(content) => {
+                    let observe = get.options().observe().cloned();
+                    let token = *get.token();
+                    return self.read(endpoint, path, token, observe, content);
+                }
+                Err(response_code) => Err(response_code),
+            },
+            Request::Put(put) => {
+                let content_format = match put.options().content_format() {
+                    Some(content_format) => {
+                        ContentFormat::from_media_type(content_format.media_type())
+                    }
+                    None => Some(ContentFormat::Opaque),
+                };
+                match content_format {
+                    Some(content_format) => handler
+                        .write(
+                            &path,
+                            Content {
+                                content_format,
+                                payload: put.payload().clone(),
+                            },
+                        )
+                        .map(|_| ResponseCode::Success(Success::Changed)),
+                    None => Err(ResponseCode::ClientError(
+                        ClientError::UnsupportedContentFormat,
+                    )),
+                }
+            }
+            Request::Post(post) => handler
+                .execute(&path, post.payload())
+                .map(|_| ResponseCode::Success(Success::Changed)),
+            Request::Delete(_) => handler
+                .delete(&path)
+                .map(|_| ResponseCode::Success(Success::Deleted)),
+        };
+
+        empty(result.unwrap_or_else(|response_code| response_code))
+    }
+
+    fn notification(&mut self, observation: &mut Observation) -> Option<Notification> {
+        let handler = self
+            .objects
+            .iter_mut()
+            .find(|(object, _)| *object == observation.path.object_id())
+            .map(|(_, handler)| handler)?;
+        let content = handler.read(&observation.path).ok()?;
+
+        let message_id = self.claim_message_id();
+        observation.last_message_id = Some(message_id);
+
+        let mut options = Options::new();
+        options.set_observe(Observe::from_value(self.sequence));
+        options.set_content_format(content.content_format.into());
+
+        Some(Notification {
+            data: Response::new(
+                Reliability::Confirmable,
+                observation.token,
+                ResponseCode::Success(Success::Content),
+                message_id,
+                options,
+                content.payload,
+            )
+            .encode(),
+            endpoint: observation.endpoint,
+            message_id,
+            reliability: Reliability::Confirmable,
+        })
+    }
+
+    fn read(
+        &mut self,
+        endpoint: SocketAddr,
+        path: Path,
+        token: Token,
+        observe: Option<Observe>,
+        content: Content,
+    ) -> (ResponseCode, Options, Payload) {
+        let mut options = Options::new();
+        options.set_content_format(content.content_format.into());
+
+        if let Some(observe) = observe {
+            self.observations.retain(|observation| {
+                observation.endpoint != endpoint || observation.token != token
+            });
+
+            if observe.is_register() {
+                self.observations.push(Observation {
+                    endpoint,
+                    last_message_id: None,
+                    path,
+                    token,
+                });
+                options.set_observe(Observe::from_value(self.sequence));
+            }
+        }
+
+        (
+            ResponseCode::Success(Success::Content),
+            options,
+            content.payload,
+        )
+    }
+}
+
+fn empty(response_code: ResponseCode) -> (ResponseCode, Options, Payload) {
+    (response_code, Options::new(), Payload::empty())
+}
+
+fn method_not_allowed() -> ResponseCode {
+    ResponseCode::ClientError(ClientError::MethodNotAllowed)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::net::{Ipv4Addr, SocketAddr};
+
+    use pretty_assertions::assert_eq;
+    use rstest::rstest;
+
+    use crate::{
+        codec::{
+            code::response_code::{ClientError, Success},
+            message::{
+                Delete, DeleteOptions, Get, GetOptions, Put, PutOptions, Reliability, Request,
+            },
+            option::{Observe, UriPath},
+            Message, MessageId, Payload, ResponseCode, Token,
+        },
+        lwm2m::{ContentFormat, Path, Registration},
+    };
+
+    use super::{Content, Endpoint, ObjectHandler};
+
+    #[derive(Debug)]
+    struct Device {
+        manufacturer: String,
+    }
+
+    impl ObjectHandler for Device {
+        fn instances(&self) -> Vec<u16> {
+            vec![0]
+        }
+
+        fn read(&mut self, path: &Path) -> Result<Content, ResponseCode> {
+            match (path.instance_id(), path.resource_id()) {
+                (Some(0), Some(0)) => Ok(Content {
+                    content_format: ContentFormat::TextPlain,
+                    payload: Payload::from_value(self.manufacturer.clone().into_bytes()),
+                }),
+                _ => Err(ResponseCode::ClientError(ClientError::NotFound)),
+            }
+        }
+
+        fn write(&mut self, _path: &Path, content: Content) -> Result<(), ResponseCode> {
+            self.manufacturer = String::from_utf8(content.payload.value().to_vec()).unwrap();
+            Ok(())
+        }
+    }
+
+    fn server() -> SocketAddr {
+        SocketAddr::from((Ipv4Addr::LOCALHOST, 5683))
+    }
+
+    fn endpoint() -> Endpoint {
+        Endpoint::new(Registration::new("sensor-1"), MessageId::from_value(0)).with_object(
+            3,
+            Box::new(Device {
+                manufacturer: "coapium".to_owned(),
+            }),
+        )
+    }
+
+    fn get(path: &str, observe: Option<Observe>) -> Request {
+        let mut options = GetOptions::new();
+        options.set_uri_path(UriPath::from_value(path).unwrap());
+        if let Some(observe) = observe {
+            options.set_observe(observe);
+        }
+        Request::Get(Get::new(
+            MessageId::from_value(100),
+            Reliability::Confirmable,
+            Token::from_value(vec![1]).unwrap(),
+            options,
+        ))
+    }
+
+    fn response(data: &[u8]) -> crate::codec::Response {
+        match Message::decode(data) {
+            Ok(Message::Piggyback(piggyback)) => piggyback.into(),
+            Ok(Message::Response(response)) => response,
+            message => panic!("Expected a response, got {message:?}"),
+        }
+    }
+
+    #[rstest]
+    #[case(get("/3/0/0", None), ResponseCode::Success(Success::Content), b"coapium".to_vec())]
+    #[case(get("/3/0/9", None), ResponseCode::ClientError(ClientError::NotFound), vec![])]
+    #[case(get("/5/0/1", None), ResponseCode::ClientError(ClientError::NotFound), vec![])]
+    #[case(get("/rd", None),    ResponseCode::ClientError(ClientError::NotFound), vec![])]
+    #[case(
+        Request::Delete(Delete::new(
+            MessageId::from_value(100),
+            Reliability::Confirmable,
+            Token::from_value(vec![1]).unwrap(),
+            { let mut options = DeleteOptions::new(); options.set_uri_path(UriPath::from_value("/3/0").unwrap()); options },
+        )),
+        ResponseCode::ClientError(ClientError::MethodNotAllowed),
+        vec![]
+    )]
+    fn handle_dispatches_to_object(
+        #[case] request: Request,
+        #[case] expected_code: ResponseCode,
+        #[case] expected_payload: Vec<u8>,
+    ) {
+        let response = response(&endpoint().handle(server(), &request).data);
+
+        assert_eq!(
+            (expected_code, Payload::from_value(expected_payload)),
+            (response.response_code(), response.payload().clone())
+        );
+    }
+
+    #[rstest]
+    fn write_then_read() {
+        let mut endpoint = endpoint();
+        let mut options = PutOptions::new();
+        options.set_uri_path(UriPath::from_value("/3/0/0").unwrap());
+        options.set_content_format(ContentFormat::TextPlain.into());
+        let put = Request::Put(Put::new(
+            MessageId::from_value(100),
+            Reliability::Confirmable,
+            Token::from_value(vec![1]).unwrap(),
+            options,
+            Payload::from_value(b"acme".to_vec()),
+        ));
+
+        let changed = response(&endpoint.handle(server(), &put).data);
+        let read = response(&endpoint.handle(server(), &get("/3/0/0", None)).data);
+
+        assert_eq!(
+            ResponseCode::Success(Success::Changed),
+            changed.response_code()
+        );
+        assert_eq!(&Payload::from_value(b"acme".to_vec()), read.payload());
+    }
+
+    #[rstest]
+    fn observed_resource_is_notified() {
+        let mut endpoint = endpoint();
+        endpoint.handle(server(), &get("/3/0/0", Some(Observe::register())));
+
+        assert_eq!(0, endpoint.notify(&Path::resource(3, 0, 1)).len());
+
+        let notifications = endpoint.notify(&Path::resource(3, 0, 0));
+        let notification = response(&notifications[0].data);
+
+        assert_eq!(1, notifications.len());
+        assert_eq!(Reliability::Confirmable, notification.reliability());
+        assert_eq!(
+            Some(&Observe::from_value(2)),
+            notification.options().observe()
+        );
+
+        assert_eq!(
+            true,
+            endpoint.on_reset(&server(), notifications[0].message_id)
+        );
+        assert_eq!(0, endpoint.notify(&Path::resource(3, 0, 0)).len());
+    }
+
+    #[rstest]
+    fn registration_announces_object_instances() {
+        assert_eq!(
+            Registration::new("sensor-1").object(Path::instance(3, 0)),
+            endpoint().registration()
+        );
+    }
+}
@@ -0,0 +1,9 @@
+pub mod content_format;
+pub mod endpoint;
+pub mod path;
+pub mod registration;
+
+pub use content_format::ContentFormat;
+pub use endpoint::{Content, Endpoint, ObjectHandler};
+pub use path::Path;
+pub use registration::{Location, Registration};
@@ -0,0 +1,158 @@
+use crate::codec::option::{uri_path, UriPath};
+
+/// Path to an object, object instance or resource in the LwM2M data model, like `/3/0/1`.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Path {
+    instance: Option<u16>,
+    object: u16,
+    resource: Option<u16>,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Error {
+    Format,
+    Id(u32),
+    Length(usize),
+}
+
+impl Path {
+    /// 65535 is reserved by LwM2M and can not be used as an id.
+    pub const MAX_ID: u16 = 65534;
+
+    /// Whether the path is the same as or a parent of the other path.
+    pub fn contains(&self, other: &Path) -> bool {
+        self.object == other.object
+            && (self.instance.is_none() || self.instance == other.instance)
+            && (self.resource.is_none() || self.resource == other.resource)
+    }
+
+    pub fn from_segments<S: AsRef<str>>(segments: &[S]) -> Result<Self, Error> {
+        let ids = segments
+            .iter()
+            .map(|segment| parse_id(segment.as_ref()))
+            .collect::<Result<Vec<_>, _>>()?;
+
+        match ids[..] {
+            [object] => Ok(Self::object(object)),
+            [object, instance] => Ok(Self::instance(object, instance)),
+            [object, instance, resource] => Ok(Self::resource(object, instance, resource)),
+            _ => Err(Error::Length(ids.len())),
+        }
+    }
+
+    pub fn from_uri_path(uri_path: &UriPath) -> Result<Self, Error> {
+        Self::from_segments(&uri_path.segments())
+    }
+
+    pub fn instance(object: u16, instance: u16) -> Self {
+        Self {
+            instance: Some(instance),
+            object,
+            resource: None,
+        }
+    }
+
+    pub fn instance_id(&self) -> Option<u16> {
+        self.instance
+    }
+
+    pub fn object(object: u16) -> Self {
+        Self {
+            instance: None,
+            object,
+            resource: None,
+        }
+    }
+
+    pub fn object_id(&self) -> u16 {
+        self.object
+    }
+
+    pub fn resource(object: u16, instance: u16, resource: u16) -> Self {
+        Self {
+            instance: Some(instance),
+            object,
+            resource: Some(resource),
+        }
+    }
+
+    pub fn resource_id(&self) -> Option<u16> {
+        self.resource
+    }
+
+    pub fn uri_path(&self) -> Result<UriPath, uri_path::Error> {
+        UriPath::from_value(self.to_string())
+    }
+}
+
+fn parse_id(segment: &str) -> Result<u16, Error> {
+    let id = segment.parse::<u32>().map_err(|_| Error::Format)?;
+
+    match u16::try_from(id) {
+        Ok(id) if id <= Path::MAX_ID => Ok(id),
+        _ => Err(Error::Id(id)),
+    }
+}
+
+impl std::fmt::Display for Path {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "/{}", self.object)?;
+        if let Some(instance) = self.instance {
+            write!(f, "/{instance}")?;
+        }
+        if let Some(resource) = self.resource {
+            write!(f, "/{resource}")?;
+        }
+        Ok(())
+    }
+}
+
+impl std::fmt::Display for Error {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Error::Format => write!(f, "LwM2M path segment is not a number"),
+            Error::Id(id) => write!(f, "LwM2M id {id} is larger than {}", Path::MAX_ID),
+            Error::Length(length) => {
+                write!(f, "LwM2M path has {length} segments, expected 1 to 3")
+            }
+        }
+    }
+}
+
+impl std::error::Error for Error {}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+    use rstest::rstest;
+
+    use super::{Error, Path};
+
+    #[rstest]
+    #[case(&["3"],           Ok(Path::object(3)))]
+    #[case(&["3", "0"],      Ok(Path::instance(3, 0)))]
+    #[case(&["3", "0", "1"], Ok(Path::resource(3, 0, 1)))]
+    #[case(&[],              Err(Error::Length(0)))]
+    #[case(&["3", "0", "1", "2"], Err(Error::Length(4)))]
+    #[case(&["rd"],          Err(Error::Format))]
+    #[case(&["65535"],       Err(Error::Id(65535)))]
+    fn from_segments(#[case] segments: &[&str], #[case] expected: Result<Path, Error>) {
+        assert_eq!(expected, Path::from_segments(segments));
+    }
+
+    #[rstest]
+    #[case(Path::object(3), Path::resource(3, 0, 1), true)]
+    #[case(Path::instance(3, 0), Path::resource(3, 0, 1), true)]
+    #[case(Path::instance(3, 1), Path::resource(3, 0, 1), false)]
+    #[case(Path::resource(3, 0, 1), Path::instance(3, 0), false)]
+    fn contains(#[case] path: Path, #[case] other: Path, #[case] expected: bool) {
+        assert_eq!(expected, path.contains(&other));
+    }
+
+    #[rstest]
+    #[case(Path::object(1), "/1")]
+    #[case(Path::resource(3, 0, 13), "/3/0/13")]
+    fn display(#[case] path: Path, #[case] expected: &str) {
+        assert_eq!(expected, path.to_string());
+    }
+}
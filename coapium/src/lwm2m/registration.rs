@@ -0,0 +1,298 @@
+use std::time::Duration;
+
+use crate::{
+    codec::{
+        code::response_code::Success,
+        message::{DeleteOptions, PostOptions},
+        option::{uri_path, uri_query, UriPath, UriQuery},
+        MediaType, Payload, ResponseCode,
+    },
+    protocol::{
+        delete::Delete, new_request::NewRequest, post::Post, reliability::Reliability,
+        response::Response,
+    },
+};
+
+use super::Path;
+
+/// Registration of an LwM2M client with a server's registration interface, as described in the
+/// LwM2M transport binding.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Registration {
+    binding: String,
+    endpoint_name: String,
+    lifetime: Duration,
+    objects: Vec<Path>,
+    version: String,
+}
+
+/// Location of a registration, returned by the server when registering.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Location {
+    segments: Vec<String>,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Error {
+    LocationMissing,
+    Path(uri_path::Error),
+    Query(uri_query::Error),
+    ResponseCode(ResponseCode),
+}
+
+impl Registration {
+    pub const DEFAULT_BINDING: &'static str = "U";
+    pub const DEFAULT_LIFETIME: Duration = Duration::from_secs(86400);
+    pub const DEFAULT_VERSION: &'static str = "1.1";
+
+    pub fn new<S: Into<String>>(endpoint_name: S) -> Self {
+        Self {
+            binding: Self::DEFAULT_BINDING.to_owned(),
+            endpoint_name: endpoint_name.into(),
+            lifetime: Self::DEFAULT_LIFETIME,
+            objects: vec![],
+            version: Self::DEFAULT_VERSION.to_owned(),
+        }
+    }
+
+    pub fn binding<S: Into<String>>(mut self, binding: S) -> Self {
+        self.binding = binding.into();
+        self
+    }
+
+    pub fn lifetime(mut self, lifetime: Duration) -> Self {
+        self.lifetime = lifetime;
+        self
+    }
+
+    /// Object or object instance announced to the server when registering.
+    pub fn object(mut self, path: Path) -> Self {
+        self.objects.push(path);
+        self
+    }
+
+    /// Request sent to the bootstrap server to ask for bootstrap information.
+    pub fn bootstrap_request(&self, reliability: Reliability) -> Result<NewRequest, Error> {
+        let mut query = UriQuery::new();
+        query.add_key_value("ep", self.endpoint_name.as_str())?;
+
+        let mut options = PostOptions::new();
+        options.set_uri_path(UriPath::from_value("bs")?);
+        options.set_uri_query(query);
+
+        Ok(NewRequest::Post(Post {
+            options,
+            reliability,
+            payload: Payload::empty(),
+        }))
+    }
+
+    pub fn deregister_request(
+        &self,
+        location: &Location,
+        reliability: Reliability,
+    ) -> Result<NewRequest, Error> {
+        let mut options = DeleteOptions::new();
+        options.set_uri_path(location.uri_path()?);
+
+        Ok(NewRequest::Delete(Delete {
+            options,
+            reliability,
+        }))
+    }
+
+    pub fn register_request(&self, reliability: Reliability) -> Result<NewRequest, Error> {
+        let mut query = UriQuery::new();
+        query.add_key_value("ep", self.endpoint_name.as_str())?;
+        query.add_key_value("lt", self.lifetime.as_secs().to_string().as_str())?;
+        query.add_key_value("lwm2m", self.version.as_str())?;
+        query.add_key_value("b", self.binding.as_str())?;
+
+        let mut options = PostOptions::new();
+        options.set_uri_path(UriPath::from_value("rd")?);
+        options.set_uri_query(query);
+        options.set_content_format(MediaType::ApplicationLinkFormat.into());
+
+        Ok(NewRequest::Post(Post {
+            options,
+            reliability,
+            payload: Payload::from_value(self.links().into_bytes()),
+        }))
+    }
+
+    /// Request that refreshes the lifetime of a registration.
+    pub fn update_request(
+        &self,
+        location: &Location,
+        reliability: Reliability,
+    ) -> Result<NewRequest, Error> {
+        let mut query = UriQuery::new();
+        query.add_key_value("lt", self.lifetime.as_secs().to_string().as_str())?;
+
+        let mut options = PostOptions::new();
+        options.set_uri_path(location.uri_path()?);
+        options.set_uri_query(query);
+
+        Ok(NewRequest::Post(Post {
+            options,
+            reliability,
+            payload: Payload::empty(),
+        }))
+    }
+
+    fn links(&self) -> String {
+        self.objects
+            .iter()
+            .map(|path| format!("<{path}>"))
+            .collect::<Vec<_>>()
+            .join(",")
+    }
+}
+
+impl Location {
+    /// Location of the registration from the response to a register request.
+    pub fn from_response(response: &Response) -> Result<Self, Error> {
+        if response.response_code != ResponseCode::Success(Success::Created) {
+            return Err(Error::ResponseCode(response.response_code));
+        }
+
+        response
+            .options
+            .location_path()
+            .map(|location_path| Self {
+                segments: location_path.segments(),
+            })
+            .ok_or(Error::LocationMissing)
+    }
+
+    pub fn segments(&self) -> &[String] {
+        &self.segments
+    }
+
+    fn uri_path(&self) -> Result<UriPath, uri_path::Error> {
+        UriPath::from_value(self.segments.join("/"))
+    }
+}
+
+impl From<uri_path::Error> for Error {
+    fn from(value: uri_path::Error) -> Self {
+        Self::Path(value)
+    }
+}
+
+impl From<uri_query::Error> for Error {
+    fn from(value: uri_query::Error) -> Self {
+        Self::Query(value)
+    }
+}
+
+impl std::fmt::Display for Error {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Error::LocationMissing => write!(f, "registration response has no Location-Path"),
+            Error::Path(error) => error.fmt(f),
+            Error::Query(error) => error.fmt(f),
+            Error::ResponseCode(response_code) => write!(
+                f,
+                "registration was rejected with {}",
+                crate::codec::Code::Response(*response_code)
+            ),
+        }
+    }
+}
+
+impl std::error::Error for Error {}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use pretty_assertions::assert_eq;
+    use rstest::rstest;
+
+    use crate::{
+        codec::{
+            code::response_code::{ClientError, Success},
+            message::PostOptions,
+            option::{LocationPath, UriPath, UriQuery, Value},
+            MediaType, Options, Payload, ResponseCode,
+        },
+        protocol::{
+            new_request::NewRequest,
+            post::Post,
+            reliability::Reliability,
+            response::{Metrics, Response},
+            transmission_parameters::NonConfirmableParameters,
+        },
+    };
+
+    use super::{Error, Location, Path, Registration};
+
+    fn reliability() -> Reliability {
+        Reliability::NonConfirmable(NonConfirmableParameters::default())
+    }
+
+    fn response(response_code: ResponseCode, location: &[&str]) -> Response {
+        let mut options = Options::new();
+        if !location.is_empty() {
+            options.set_location_path(
+                LocationPath::decode(
+                    location
+                        .iter()
+                        .map(|segment| Value::from_str(segment).unwrap())
+                        .collect(),
+                )
+                .unwrap(),
+            );
+        }
+
+        Response {
+            response_code,
+            options,
+            payload: Payload::empty(),
+            metrics: Metrics::default(),
+            warnings: vec![],
+        }
+    }
+
+    #[rstest]
+    fn register_request() {
+        let registration = Registration::new("sensor-1")
+            .lifetime(Duration::from_secs(300))
+            .object(Path::instance(1, 0))
+            .object(Path::instance(3, 0));
+
+        let mut query = UriQuery::new();
+        query.add_key_value("ep", "sensor-1").unwrap();
+        query.add_key_value("lt", "300").unwrap();
+        query.add_key_value("lwm2m", "1.1").unwrap();
+        query.add_key_value("b", "U").unwrap();
+        let mut options = PostOptions::new();
+        options.set_uri_path(UriPath::from_value("rd").unwrap());
+        options.set_uri_query(query);
+        options.set_content_format(MediaType::ApplicationLinkFormat.into());
+
+        assert_eq!(
+            Ok(NewRequest::Post(Post {
+                options,
+                reliability: reliability(),
+                payload: Payload::from_value(b"</1/0>,</3/0>".to_vec()),
+            })),
+            registration.register_request(reliability())
+        );
+    }
+
+    #[rstest]
+    #[case(response(ResponseCode::Success(Success::Created), &["rd", "5a3f"]), Ok(vec!["rd".to_owned(), "5a3f".to_owned()]))]
+    #[case(response(ResponseCode::Success(Success::Created), &[]),             Err(Error::LocationMissing))]
+    #[case(response(ResponseCode::ClientError(ClientError::Forbidden), &[]),   Err(Error::ResponseCode(ResponseCode::ClientError(ClientError::Forbidden))))]
+    fn location_from_response(
+        #[case] response: Response,
+        #[case] expected: Result<Vec<String>, Error>,
+    ) {
+        assert_eq!(
+            expected,
+            Location::from_response(&response).map(|location| location.segments().to_vec())
+        );
+    }
+}
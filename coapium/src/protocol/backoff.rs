@@ -0,0 +1,71 @@
+use std::{fmt::Debug, time::Duration};
+
+use super::transmission_parameters::ConfirmableParameters;
+
+/// Decides how long to wait for an acknowledgement before a confirmable message is retransmitted.
+///
+/// The number of retransmissions is still bounded by `MAX_RETRANSMIT` of the
+/// [ConfirmableParameters], a strategy only decides the timeouts in between.
+pub trait BackoffStrategy: Debug + Send {
+    /// Timeout before the first retransmission.
+    fn initial(&self, parameters: &ConfirmableParameters) -> Duration;
+
+    /// Timeout before the next retransmission, where `attempt` is the number of retransmissions
+    /// sent so far.
+    fn next(&self, timeout: Duration, attempt: u8) -> Duration;
+}
+
+/// Binary exponential backoff as described in
+/// [RFC 7252](https://datatracker.ietf.org/doc/html/rfc7252#section-4.2).
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct ExponentialBackoff;
+
+impl BackoffStrategy for ExponentialBackoff {
+    fn initial(&self, parameters: &ConfirmableParameters) -> Duration {
+        let variable_range = parameters.max_ack_timeout() - parameters.min_ack_timeout();
+
+        let range = variable_range.mul_f32(parameters.initial_retransmission_factor());
+
+        parameters.min_ack_timeout() + range
+    }
+
+    fn next(&self, timeout: Duration, _attempt: u8) -> Duration {
+        timeout * 2
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use pretty_assertions::assert_eq;
+    use rstest::rstest;
+
+    use crate::protocol::transmission_parameters::{
+        ConfirmableParameters, InitialRetransmissionFactor,
+    };
+
+    use super::{BackoffStrategy, ExponentialBackoff};
+
+    #[rstest]
+    #[case(0.0, Duration::from_secs(2))]
+    #[case(0.5, Duration::from_millis(2500))]
+    #[case(1.0, Duration::from_secs(3))]
+    fn exponential_initial(#[case] factor: f32, #[case] expected: Duration) {
+        let parameters =
+            ConfirmableParameters::default(InitialRetransmissionFactor::new(factor).unwrap());
+
+        assert_eq!(expected, ExponentialBackoff.initial(&parameters));
+    }
+
+    #[rstest]
+    #[case(Duration::from_secs(2), 0, Duration::from_secs(4))]
+    #[case(Duration::from_secs(4), 1, Duration::from_secs(8))]
+    fn exponential_next(
+        #[case] timeout: Duration,
+        #[case] attempt: u8,
+        #[case] expected: Duration,
+    ) {
+        assert_eq!(expected, ExponentialBackoff.next(timeout, attempt));
+    }
+}
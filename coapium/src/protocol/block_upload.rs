@@ -0,0 +1,324 @@
+use std::io;
+
+use crate::codec::{
+    option::{Block1, Block2, RequestTag},
+    MessageId, Options, Payload, Token,
+};
+
+use super::{body::Body, new_request::NewRequest};
+
+/// Sends the payload of a POST or PUT one Block1 block at a time, see
+/// [RFC 7959 §2.5](https://datatracker.ietf.org/doc/html/rfc7959#section-2.5).
+///
+/// The next block goes out once the server answered the previous one with 2.31 (Continue), the
+/// response to the last block is the response to the whole request. Blocks are taken from the
+/// [`Body`] as they are sent and all carry the same Request-Tag, if the upload has one.
+#[derive(Debug)]
+pub struct BlockUpload {
+    body: Body,
+    /// Bytes of the block sent last, kept to start over from the first block.
+    current: Vec<u8>,
+    more: bool,
+    num: u32,
+    request: NewRequest,
+    request_tag: Option<RequestTag>,
+    szx: u8,
+}
+
+impl BlockUpload {
+    /// Upload of the payload of `request` in blocks of `2^(szx + 4)` bytes, `None` if the request
+    /// isn't a POST or PUT or already carries a block of its own.
+    pub fn new(mut request: NewRequest, szx: u8) -> Option<Self> {
+        let payload = match &mut request {
+            NewRequest::Post(post) => std::mem::replace(&mut post.payload, Payload::empty()),
+            NewRequest::Put(put) => std::mem::replace(&mut put.payload, Payload::empty()),
+            _ => return None,
+        };
+
+        Self::with_body(request, payload.into(), szx)
+    }
+
+    /// Upload of `body` with the method and options of `request`, whose own payload is left out.
+    pub fn with_body(mut request: NewRequest, body: Body, szx: u8) -> Option<Self> {
+        let block1 = match &mut request {
+            NewRequest::Post(post) => {
+                post.payload = Payload::empty();
+                post.options.block1()
+            }
+            NewRequest::Put(put) => {
+                put.payload = Payload::empty();
+                put.options.block1()
+            }
+            _ => return None,
+        };
+        if block1.is_some() {
+            return None;
+        }
+
+        Some(Self {
+            body,
+            current: vec![],
+            more: true,
+            num: 0,
+            request,
+            request_tag: None,
+            szx: szx.min(Block2::MAX_SZX),
+        })
+    }
+
+    /// Tags every block with `request_tag`, RFC 9175 §3, unless the request has a Request-Tag of
+    /// its own.
+    pub fn with_request_tag(mut self, request_tag: RequestTag) -> Self {
+        if self.request.request_tag().is_none() {
+            self.request_tag = Some(request_tag);
+        }
+        self
+    }
+
+    /// Block size exponent a server asks for in a 2.31 (Continue) or 4.13 (Request Entity Too
+    /// Large) response. The Block1 option is taken over Size1, which is turned into the largest
+    /// block size that isn't above it.
+    pub fn szx_hint(options: &Options) -> Option<u8> {
+        if let Some(block1) = options.block1() {
+            return Some(block1.block().szx());
+        }
+
+        let size1 = options.size1()?.value();
+        (0..=Block2::MAX_SZX)
+            .rev()
+            .find(|szx| 1u32 << (szx + 4) <= size1)
+    }
+
+    /// Shrinks the block size until a block sent with `token` fits in `max_message_size` bytes,
+    /// down to the smallest block size.
+    pub fn fit(&mut self, max_message_size: usize, token: Token) {
+        let mut request = self.request.clone();
+        if let Some(request_tag) = &self.request_tag {
+            request.set_request_tag(request_tag.clone());
+        }
+        // Block1 option with an extended delta and a 3 byte value, and the payload marker.
+        let overhead = request.encode(MessageId::from_value(0), token).len() + 6;
+
+        while self.szx > 0 && overhead + (1 << (self.szx + 4)) > max_message_size {
+            self.szx -= 1;
+        }
+    }
+
+    /// Request carrying the first block.
+    pub fn start(&mut self) -> io::Result<NewRequest> {
+        self.num = 0;
+        self.block()
+    }
+
+    /// Moves on to the block after the current one, at the smaller block size the server asks for
+    /// in `options` if any. `None` once the current block was the last.
+    pub fn next(&mut self, options: &Options) -> Option<io::Result<NewRequest>> {
+        if !self.more {
+            return None;
+        }
+
+        let offset = (self.num as usize + 1) << (self.szx + 4);
+        if let Some(szx) = Self::szx_hint(options).filter(|szx| *szx < self.szx) {
+            self.szx = szx;
+        }
+        self.num = u32::try_from(offset >> (self.szx + 4))
+            .ok()
+            .filter(|num| *num <= Block2::MAX_NUM)?;
+
+        Some(self.block())
+    }
+
+    /// Starts over from the first block at the smaller block size `szx`. Only an upload still at
+    /// its first block can, the blocks after it are gone from the body.
+    pub fn restart(&mut self, szx: u8) -> Option<io::Result<NewRequest>> {
+        if self.num != 0 || szx >= self.szx {
+            return None;
+        }
+
+        self.body.unread(std::mem::take(&mut self.current));
+        self.szx = szx;
+        Some(self.start())
+    }
+
+    /// Reads ahead the block after the current one, see [`Body::prefetch`].
+    pub async fn prefetch(&mut self) {
+        self.body.prefetch(1 << (self.szx + 4)).await
+    }
+
+    pub fn szx(&self) -> u8 {
+        self.szx
+    }
+
+    fn block(&mut self) -> io::Result<NewRequest> {
+        let (payload, more) = self.body.next_block(1 << (self.szx + 4))?;
+        let block1 = Block1::new(self.num, more, self.szx)
+            .expect("Block number should stay within the Block1 range");
+        self.current = payload.clone();
+        self.more = more;
+
+        let mut request = self.request.clone();
+        match &mut request {
+            NewRequest::Post(post) => {
+                post.options.set_block1(block1);
+                post.payload = Payload::from_value(payload);
+            }
+            NewRequest::Put(put) => {
+                put.options.set_block1(block1);
+                put.payload = Payload::from_value(payload);
+            }
+            _ => {}
+        }
+        if let Some(request_tag) = &self.request_tag {
+            request.set_request_tag(request_tag.clone());
+        }
+
+        Ok(request)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+    use rstest::rstest;
+
+    use crate::{
+        codec::{
+            message::PutOptions,
+            option::{Block1, RequestTag, Size1},
+            Options, Payload,
+        },
+        protocol::{
+            put::Put, reliability::Reliability, transmission_parameters::NonConfirmableParameters,
+        },
+    };
+
+    use super::{BlockUpload, NewRequest};
+
+    fn put(payload: Vec<u8>) -> NewRequest {
+        NewRequest::Put(Put {
+            options: PutOptions::new(),
+            reliability: Reliability::NonConfirmable(NonConfirmableParameters::default()),
+            payload: Payload::from_value(payload),
+        })
+    }
+
+    fn block(request: NewRequest) -> (Option<Block1>, Vec<u8>) {
+        let NewRequest::Put(put) = request else {
+            panic!("Block of a PUT should be a PUT");
+        };
+        (put.options.block1().cloned(), put.payload.value().to_vec())
+    }
+
+    #[rstest]
+    #[case(Some(2), None, Some(2))]
+    #[case(Some(2), Some(1024), Some(2))]
+    #[case(None, Some(100), Some(2))]
+    #[case(None, Some(5000), Some(6))]
+    #[case(None, Some(15), None)]
+    #[case(None, None, None)]
+    fn szx_hint(
+        #[case] block1_szx: Option<u8>,
+        #[case] size1: Option<u32>,
+        #[case] expected: Option<u8>,
+    ) {
+        let mut options = Options::new();
+        if let Some(szx) = block1_szx {
+            options.set_block1(Block1::new(0, false, szx).unwrap());
+        }
+        if let Some(size1) = size1 {
+            options.set_size1(Size1::new(size1));
+        }
+
+        assert_eq!(expected, BlockUpload::szx_hint(&options));
+    }
+
+    #[rstest]
+    fn blocks_follow_each_other() {
+        let payload = (0..40).collect::<Vec<u8>>();
+        let mut upload = BlockUpload::new(put(payload.clone()), 0).unwrap();
+
+        assert_eq!(
+            (
+                Some(Block1::new(0, true, 0).unwrap()),
+                payload[..16].to_vec()
+            ),
+            block(upload.start().unwrap())
+        );
+        assert_eq!(
+            (
+                Some(Block1::new(1, true, 0).unwrap()),
+                payload[16..32].to_vec()
+            ),
+            block(upload.next(&Options::new()).unwrap().unwrap())
+        );
+        assert_eq!(
+            (
+                Some(Block1::new(2, false, 0).unwrap()),
+                payload[32..].to_vec()
+            ),
+            block(upload.next(&Options::new()).unwrap().unwrap())
+        );
+        assert!(upload.next(&Options::new()).is_none());
+    }
+
+    #[rstest]
+    fn server_shrinks_block_size() {
+        let payload = (0..100).collect::<Vec<u8>>();
+        let mut upload = BlockUpload::new(put(payload.clone()), 1).unwrap();
+        upload.start().unwrap();
+        let mut options = Options::new();
+        options.set_block1(Block1::new(0, true, 0).unwrap());
+
+        assert_eq!(
+            (
+                Some(Block1::new(2, true, 0).unwrap()),
+                payload[32..48].to_vec()
+            ),
+            block(upload.next(&options).unwrap().unwrap())
+        );
+        assert_eq!(0, upload.szx());
+    }
+
+    #[rstest]
+    fn restart_at_smaller_block_size() {
+        let payload = (0..100).collect::<Vec<u8>>();
+        let mut upload = BlockUpload::new(put(payload.clone()), 2).unwrap();
+        upload.start().unwrap();
+
+        assert_eq!(
+            (
+                Some(Block1::new(0, true, 0).unwrap()),
+                payload[..16].to_vec()
+            ),
+            block(upload.restart(0).unwrap().unwrap())
+        );
+        upload.next(&Options::new()).unwrap().unwrap();
+        assert!(upload.restart(0).is_none());
+    }
+
+    #[rstest]
+    fn blocks_carry_request_tag() {
+        let tag = RequestTag::from_value(vec![7]).unwrap();
+        let mut upload = BlockUpload::new(put(vec![0; 20]), 0)
+            .unwrap()
+            .with_request_tag(tag.clone());
+
+        assert_eq!(Some(&tag), upload.start().unwrap().request_tag());
+        assert_eq!(
+            Some(&tag),
+            upload.next(&Options::new()).unwrap().unwrap().request_tag()
+        );
+    }
+
+    #[rstest]
+    fn own_request_tag_is_kept() {
+        let own = RequestTag::from_value(vec![1]).unwrap();
+        let mut request = put(vec![0; 20]);
+        request.set_request_tag(own.clone());
+        let mut upload = BlockUpload::new(request, 0)
+            .unwrap()
+            .with_request_tag(RequestTag::from_value(vec![7]).unwrap());
+
+        assert_eq!(Some(&own), upload.start().unwrap().request_tag());
+    }
+}
@@ -0,0 +1,222 @@
+use std::{
+    collections::VecDeque,
+    io::{self, Read},
+    pin::Pin,
+};
+
+use futures::{io::AsyncRead, AsyncReadExt};
+
+use crate::codec::Payload;
+
+enum Source {
+    /// Everything is read ahead already.
+    Empty,
+    Reader(Box<dyn Read + Send>),
+    Stream(Pin<Box<dyn AsyncRead + Send>>),
+    Generator(Box<dyn FnMut(usize) -> io::Result<Vec<u8>> + Send>),
+}
+
+/// Payload of a request sent with a block-wise upload, see
+/// [`BlockUpload`](super::block_upload::BlockUpload). Only the blocks about to be sent are read,
+/// so a large payload like a firmware image is never in memory as a whole.
+///
+/// The processor takes blocks without waiting, a body read from an async stream has to be read
+/// ahead with [`prefetch`](Body::prefetch) before each block is taken.
+pub struct Body {
+    ahead: VecDeque<u8>,
+    ended: bool,
+    error: Option<io::ErrorKind>,
+    source: Source,
+}
+
+impl Body {
+    pub fn from_payload(payload: Payload) -> Self {
+        Self {
+            ahead: payload.value().iter().copied().collect(),
+            ended: true,
+            error: None,
+            source: Source::Empty,
+        }
+    }
+
+    pub fn from_reader<R: Read + Send + 'static>(reader: R) -> Self {
+        Self::from_source(Source::Reader(Box::new(reader)))
+    }
+
+    pub fn from_stream<S: AsyncRead + Send + 'static>(stream: S) -> Self {
+        Self::from_source(Source::Stream(Box::pin(stream)))
+    }
+
+    /// Body produced on demand by `generator`, which is called with the largest number of bytes
+    /// to produce. An empty chunk ends the body.
+    pub fn from_generator<G>(generator: G) -> Self
+    where
+        G: FnMut(usize) -> io::Result<Vec<u8>> + Send + 'static,
+    {
+        Self::from_source(Source::Generator(Box::new(generator)))
+    }
+
+    fn from_source(source: Source) -> Self {
+        Self {
+            ahead: VecDeque::new(),
+            ended: false,
+            error: None,
+            source,
+        }
+    }
+
+    /// Takes the next block of at most `size` bytes, along with whether more follow. A stream that
+    /// wasn't read far enough ahead fails with [`io::ErrorKind::WouldBlock`].
+    pub fn next_block(&mut self, size: usize) -> io::Result<(Vec<u8>, bool)> {
+        if let Some(kind) = self.error {
+            return Err(kind.into());
+        }
+
+        while !self.ended && self.ahead.len() <= size {
+            let chunk = match &mut self.source {
+                Source::Empty => vec![],
+                Source::Reader(reader) => {
+                    let mut chunk = vec![0; size + 1 - self.ahead.len()];
+                    let read = reader.read(&mut chunk)?;
+                    chunk.truncate(read);
+                    chunk
+                }
+                Source::Stream(_) => return Err(io::ErrorKind::WouldBlock.into()),
+                Source::Generator(generator) => generator(size + 1 - self.ahead.len())?,
+            };
+            self.ended = chunk.is_empty();
+            self.ahead.extend(chunk);
+        }
+
+        let block = self
+            .ahead
+            .drain(..size.min(self.ahead.len()))
+            .collect::<Vec<_>>();
+
+        Ok((block, !self.ahead.is_empty()))
+    }
+
+    /// Reads a stream ahead until a block of `size` bytes can be taken and it's known whether
+    /// more follow. Other bodies are read as blocks are taken and are left as they are. A failed
+    /// read is kept and returned by the next [`next_block`](Body::next_block).
+    pub async fn prefetch(&mut self, size: usize) {
+        let Source::Stream(stream) = &mut self.source else {
+            return;
+        };
+
+        let mut chunk = vec![0; size + 1];
+        while !self.ended && self.error.is_none() && self.ahead.len() <= size {
+            match stream.read(&mut chunk[..size + 1 - self.ahead.len()]).await {
+                Ok(read) => {
+                    self.ended = read == 0;
+                    self.ahead.extend(&chunk[..read]);
+                }
+                Err(e) if e.kind() == io::ErrorKind::Interrupted => {}
+                Err(e) => self.error = Some(e.kind()),
+            }
+        }
+    }
+
+    /// Puts a block taken from the body back in front, to be taken again.
+    pub fn unread(&mut self, block: Vec<u8>) {
+        for byte in block.into_iter().rev() {
+            self.ahead.push_front(byte);
+        }
+    }
+}
+
+impl From<Payload> for Body {
+    fn from(payload: Payload) -> Self {
+        Self::from_payload(payload)
+    }
+}
+
+impl std::fmt::Debug for Body {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let source = match self.source {
+            Source::Empty => "Empty",
+            Source::Reader(_) => "Reader",
+            Source::Stream(_) => "Stream",
+            Source::Generator(_) => "Generator",
+        };
+
+        f.debug_struct("Body")
+            .field("ahead", &self.ahead.len())
+            .field("ended", &self.ended)
+            .field("error", &self.error)
+            .field("source", &source)
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io;
+
+    use futures::executor::block_on;
+    use pretty_assertions::assert_eq;
+    use rstest::rstest;
+
+    use crate::codec::Payload;
+
+    use super::Body;
+
+    fn blocks(mut body: Body, size: usize) -> Vec<(Vec<u8>, bool)> {
+        let mut blocks = vec![];
+        loop {
+            let (block, more) = body.next_block(size).unwrap();
+            blocks.push((block, more));
+            if !more {
+                return blocks;
+            }
+        }
+    }
+
+    #[rstest]
+    #[case(Body::from_payload(Payload::from_value((0..40).collect())))]
+    #[case(Body::from_reader(io::Cursor::new((0..40).collect::<Vec<u8>>())))]
+    #[case(Body::from_generator({
+        let mut next = 0u8;
+        move |size| {
+            let chunk = (next..40).take(size.min(7)).collect::<Vec<_>>();
+            next += chunk.len() as u8;
+            Ok(chunk)
+        }
+    }))]
+    fn blocks_of_body(#[case] body: Body) {
+        assert_eq!(
+            vec![
+                ((0..16).collect(), true),
+                ((16..32).collect(), true),
+                ((32..40).collect(), false),
+            ],
+            blocks(body, 16)
+        );
+    }
+
+    #[rstest]
+    fn stream_is_read_ahead() {
+        let mut body = Body::from_stream(futures::io::Cursor::new((0..20).collect::<Vec<u8>>()));
+
+        assert_eq!(
+            io::ErrorKind::WouldBlock,
+            body.next_block(16).unwrap_err().kind()
+        );
+
+        block_on(body.prefetch(16));
+        assert_eq!(((0..16).collect(), true), body.next_block(16).unwrap());
+
+        block_on(body.prefetch(16));
+        assert_eq!(((16..20).collect(), false), body.next_block(16).unwrap());
+    }
+
+    #[rstest]
+    fn unread_block_is_taken_again() {
+        let mut body = Body::from_payload(Payload::from_value((0..20).collect()));
+
+        let (block, _) = body.next_block(16).unwrap();
+        body.unread(block);
+
+        assert_eq!(((0..8).collect(), true), body.next_block(8).unwrap());
+    }
+}
@@ -0,0 +1,71 @@
+use std::{
+    fmt::Debug,
+    sync::{Arc, Mutex},
+    time::{Duration, Instant},
+};
+
+/// Source of the current time for the [Processor](super::processor::Processor), replace it to
+/// control time in tests or on targets without a system clock.
+pub trait Clock: Debug + Send {
+    fn now(&self) -> Instant;
+}
+
+/// Time as given by [`Instant::now`].
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct SystemClock;
+
+/// Clock that only moves when told to, clones share the same time.
+#[derive(Clone, Debug)]
+pub struct ManualClock {
+    now: Arc<Mutex<Instant>>,
+}
+
+impl Clock for SystemClock {
+    fn now(&self) -> Instant {
+        Instant::now()
+    }
+}
+
+impl ManualClock {
+    pub fn new(now: Instant) -> Self {
+        Self {
+            now: Arc::new(Mutex::new(now)),
+        }
+    }
+
+    pub fn advance(&self, duration: Duration) {
+        *self.now.lock().unwrap() += duration;
+    }
+}
+
+impl Default for ManualClock {
+    fn default() -> Self {
+        Self::new(Instant::now())
+    }
+}
+
+impl Clock for ManualClock {
+    fn now(&self) -> Instant {
+        *self.now.lock().unwrap()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use pretty_assertions::assert_eq;
+    use rstest::rstest;
+
+    use super::{Clock, ManualClock};
+
+    #[rstest]
+    fn manual_clock_clones_share_time() {
+        let clock = ManualClock::default();
+        let start = clock.now();
+
+        clock.clone().advance(Duration::from_secs(45));
+
+        assert_eq!(Duration::from_secs(45), clock.now() - start);
+    }
+}
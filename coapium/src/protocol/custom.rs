@@ -0,0 +1,67 @@
+use crate::codec::{message, Code, Header, MessageId, MethodCode, Options, Payload, Token};
+
+use super::reliability::Reliability;
+
+/// Request with any method code and any mix of options, for exercising devices under test with
+/// unassigned methods or option combinations the typed requests don't allow.
+///
+/// Nothing about the request is checked, it's sent as it is.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Custom {
+    pub method_code: MethodCode,
+    pub options: Options,
+    pub payload: Payload,
+    pub reliability: Reliability,
+}
+
+impl Custom {
+    pub fn encode(self, message_id: MessageId, token: Token) -> Vec<u8> {
+        let (token_length, token) = token.encode();
+
+        Header::new(
+            message::Reliability::from(&self.reliability).into(),
+            token_length,
+            Code::Request(self.method_code),
+            message_id,
+        )
+        .encode()
+        .into_iter()
+        .chain(token)
+        .chain(self.options.encode())
+        .chain(self.payload.encode())
+        .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+    use rstest::rstest;
+
+    use crate::{
+        codec::{code::Detail, MessageId, MethodCode, Options, Payload, Token},
+        protocol::{reliability::Reliability, transmission_parameters::NonConfirmableParameters},
+    };
+
+    use super::Custom;
+
+    #[rstest]
+    fn encode() {
+        let mut options = Options::new();
+        options.set_uri_path("a".try_into().unwrap());
+        let custom = Custom {
+            method_code: MethodCode::decode(Detail::from_value_or_panic(8)),
+            options,
+            payload: Payload::from_value(vec![1]),
+            reliability: Reliability::NonConfirmable(NonConfirmableParameters::default()),
+        };
+
+        assert_eq!(
+            vec![0x51, 0x08, 0x00, 0x07, 0xAB, 0xB1, b'a', 0xFF, 1],
+            custom.encode(
+                MessageId::from_value(7),
+                Token::from_value(vec![0xAB]).unwrap()
+            )
+        );
+    }
+}
@@ -5,6 +5,7 @@ use crate::codec::{
 
 use super::reliability::Reliability;
 
+/// DELETE request, which has no payload since RFC 7252 gives a payload in a DELETE no meaning.
 #[derive(Clone, Debug, PartialEq)]
 pub struct Delete {
     pub options: DeleteOptions,
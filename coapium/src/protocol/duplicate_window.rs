@@ -0,0 +1,179 @@
+use std::{
+    collections::VecDeque,
+    net::SocketAddr,
+    time::{Duration, Instant},
+};
+
+use crate::codec::MessageId;
+
+use super::profile::TransmissionProfile;
+
+/// Message ids of recently received non-confirmable messages, to recognize the duplicates a lossy
+/// network delivers, see [RFC 7252 §4.5](https://datatracker.ietf.org/doc/html/rfc7252#section-4.5).
+///
+/// A message id only identifies a message together with the endpoint that sent it, so ids are
+/// remembered along with their source. Without a source, for a transport with a single peer, the
+/// window has to be cleared when the peer changes. Ids are remembered for `NON_LIFETIME`, after
+/// which the peer may reuse them, and the least recently received id is forgotten first when the
+/// window is full.
+#[derive(Clone, Debug, PartialEq)]
+pub struct DuplicateWindow {
+    capacity: usize,
+    lifetime: Duration,
+    received: VecDeque<(Option<SocketAddr>, MessageId, Instant)>,
+}
+
+impl DuplicateWindow {
+    pub const DEFAULT_CAPACITY: usize = 64;
+
+    /// Window of at most `capacity` message ids, `0` turns off duplicate detection.
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            capacity,
+            lifetime: TransmissionProfile::default().non_lifetime(),
+            received: VecDeque::new(),
+        }
+    }
+
+    pub fn with_lifetime(mut self, lifetime: Duration) -> Self {
+        self.lifetime = lifetime;
+        self
+    }
+
+    /// Whether `message_id` was already received from `source` within the lifetime, it's
+    /// remembered as received at `now` either way.
+    pub fn is_duplicate(
+        &mut self,
+        source: Option<SocketAddr>,
+        message_id: MessageId,
+        now: Instant,
+    ) -> bool {
+        if self.capacity == 0 {
+            return false;
+        }
+
+        self.received
+            .retain(|(_, _, received_at)| now.duration_since(*received_at) < self.lifetime);
+
+        let duplicate = match self
+            .received
+            .iter()
+            .position(|(from, received, _)| *from == source && *received == message_id)
+        {
+            Some(position) => {
+                self.received.remove(position);
+                true
+            }
+            None => false,
+        };
+
+        if self.received.len() >= self.capacity {
+            self.received.pop_front();
+        }
+        self.received.push_back((source, message_id, now));
+
+        duplicate
+    }
+
+    pub fn clear(&mut self) {
+        self.received.clear();
+    }
+
+    pub fn len(&self) -> usize {
+        self.received.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.received.is_empty()
+    }
+}
+
+impl Default for DuplicateWindow {
+    fn default() -> Self {
+        Self::new(Self::DEFAULT_CAPACITY)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::{Duration, Instant};
+
+    use pretty_assertions::assert_eq;
+    use rstest::rstest;
+
+    use crate::codec::MessageId;
+
+    use super::DuplicateWindow;
+
+    #[rstest]
+    #[case(vec![1, 2, 3],    vec![false, false, false])]
+    #[case(vec![1, 1, 1],    vec![false, true, true])]
+    #[case(vec![1, 2, 1],    vec![false, false, true])]
+    // The least recently received id is forgotten once the window is full
+    #[case(vec![1, 2, 3, 1], vec![false, false, false, false])]
+    // A duplicate counts as received again
+    #[case(vec![1, 2, 1, 3, 1], vec![false, false, true, false, true])]
+    fn is_duplicate(#[case] message_ids: Vec<u16>, #[case] expected: Vec<bool>) {
+        let mut window = DuplicateWindow::new(2);
+        let now = Instant::now();
+
+        let duplicates = message_ids
+            .into_iter()
+            .map(|message_id| window.is_duplicate(None, MessageId::from_value(message_id), now))
+            .collect::<Vec<_>>();
+
+        assert_eq!(expected, duplicates);
+    }
+
+    #[rstest]
+    fn message_ids_are_per_source() {
+        let mut window = DuplicateWindow::new(4);
+        let now = Instant::now();
+        let first = Some("192.0.2.1:5683".parse().unwrap());
+        let second = Some("192.0.2.2:5683".parse().unwrap());
+
+        assert_eq!(
+            false,
+            window.is_duplicate(first, MessageId::from_value(1), now)
+        );
+        assert_eq!(
+            false,
+            window.is_duplicate(second, MessageId::from_value(1), now)
+        );
+        assert_eq!(
+            true,
+            window.is_duplicate(first, MessageId::from_value(1), now)
+        );
+    }
+
+    #[rstest]
+    fn forgets_after_lifetime() {
+        let mut window = DuplicateWindow::new(2).with_lifetime(Duration::from_secs(10));
+        let now = Instant::now();
+
+        window.is_duplicate(None, MessageId::from_value(1), now);
+
+        assert_eq!(
+            false,
+            window.is_duplicate(
+                None,
+                MessageId::from_value(1),
+                now + Duration::from_secs(10)
+            )
+        );
+    }
+
+    #[rstest]
+    fn disabled() {
+        let mut window = DuplicateWindow::new(0);
+        let now = Instant::now();
+
+        window.is_duplicate(None, MessageId::from_value(1), now);
+
+        assert_eq!(
+            false,
+            window.is_duplicate(None, MessageId::from_value(1), now)
+        );
+        assert_eq!(true, window.is_empty());
+    }
+}
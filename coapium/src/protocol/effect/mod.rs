@@ -2,19 +2,67 @@ pub mod timeout;
 
 pub use timeout::Timeout;
 
+use smallvec::SmallVec;
+
 use crate::{
-    codec::Token,
+    codec::{Header, MessageType, Token},
     protocol::response::{self, Response},
 };
 
 #[derive(Clone, Debug, PartialEq)]
 pub enum Effect {
     CreateTimeout(Timeout),
+    /// Datagram that couldn't be parsed, emitted when the processor is configured with
+    /// [`MalformedPolicy::Deliver`](crate::protocol::processor::MalformedPolicy::Deliver).
+    MalformedDatagramReceived(Vec<u8>),
+    /// Notification of a resource observed with the token, after the response that resolved the
+    /// transaction. The last one doesn't
+    /// [continue the observation](crate::protocol::response::Response::continues_observation).
+    NotificationReceived(Token, Response),
+    /// The transaction or observation of the token was canceled, nothing more is delivered for it.
+    TransactionCanceled(Token),
     TransactionResolved(Token, Result<Response, response::Error>),
     Transmit(Vec<u8>),
+    /// Datagram with a reserved code, emitted when the processor is configured with
+    /// [`ReservedPolicy::Deliver`](crate::protocol::processor::ReservedPolicy::Deliver).
+    UnknownMessageReceived(Vec<u8>),
+}
+
+/// Order effects are dispatched in, see [`prioritize`].
+#[derive(Clone, Copy, Debug, Eq, Ord, PartialEq, PartialOrd)]
+pub enum Priority {
+    /// Acknowledgements and resets, the peer retransmits when they are late.
+    Urgent,
+    Normal,
+}
+
+impl Effect {
+    pub fn priority(&self) -> Priority {
+        match self {
+            Effect::Transmit(data) if is_acknowledgement_or_reset(data) => Priority::Urgent,
+            _ => Priority::Normal,
+        }
+    }
+}
+
+/// Moves the transmissions of acknowledgements and resets ahead of the other effects, the order
+/// is kept otherwise. Under load this keeps the peer from retransmitting while requests are sent.
+pub fn prioritize(effects: &mut Effects) {
+    effects.sort_by_key(Effect::priority);
+}
+
+fn is_acknowledgement_or_reset(data: &[u8]) -> bool {
+    Header::parse(data).is_ok_and(|(_, header)| {
+        matches!(
+            header.message_type(),
+            MessageType::Acknowledgement | MessageType::Reset
+        )
+    })
 }
 
-pub type Effects = Vec<Effect>;
+/// Effects of a single tick, kept inline for the few a tick usually has so the list itself isn't
+/// allocated. The datagrams of [`Effect::Transmit`] still are.
+pub type Effects = SmallVec<[Effect; 4]>;
 
 impl<T> From<T> for Effect
 where
@@ -24,3 +72,48 @@ where
         Self::CreateTimeout(value.into())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+    use rstest::rstest;
+    use smallvec::smallvec;
+
+    use crate::{
+        codec::{Acknowledgement, MessageId, Reset, Token},
+        protocol::{
+            response,
+            timeout::RetransmissionTimeout,
+            transmission_parameters::{ConfirmableParameters, InitialRetransmissionFactor},
+        },
+    };
+
+    use super::{prioritize, Effect, Effects};
+
+    #[rstest]
+    fn acknowledgements_and_resets_go_first() {
+        let parameters =
+            ConfirmableParameters::default(InitialRetransmissionFactor::new(0.5).unwrap());
+        let token = Token::from_value(vec![1]).unwrap();
+        let request = Effect::Transmit(vec![0x40, 0x01, 0x00, 0x02]);
+        let timeout: Effect = RetransmissionTimeout::new(2.into(), &parameters).into();
+        let acknowledgement =
+            Effect::Transmit(Acknowledgement::new(MessageId::from_value(1)).encode());
+        let reset = Effect::Transmit(Reset::from_message_id(MessageId::from_value(3)).encode());
+        let resolved = Effect::TransactionResolved(token, Err(response::Error::Timeout));
+        let mut effects: Effects = smallvec![
+            timeout.clone(),
+            request.clone(),
+            resolved.clone(),
+            acknowledgement.clone(),
+            reset.clone(),
+        ];
+
+        prioritize(&mut effects);
+
+        assert_eq!(
+            vec![acknowledgement, reset, timeout, request, resolved],
+            effects.into_vec()
+        );
+    }
+}
@@ -0,0 +1,37 @@
+use crate::codec::{message_id::MessageId, Acknowledgement, Reset};
+
+/// Empty message sent on its own, outside of any exchange the processor keeps track of.
+///
+/// Useful to acknowledge or reject a message by hand, e.g. to see how a device behaves when its
+/// notifications are reset.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum EmptyMessage {
+    Acknowledgement(MessageId),
+    Reset(MessageId),
+}
+
+impl EmptyMessage {
+    pub fn encode(self) -> Vec<u8> {
+        match self {
+            EmptyMessage::Acknowledgement(message_id) => Acknowledgement::new(message_id).encode(),
+            EmptyMessage::Reset(message_id) => Reset::from_message_id(message_id).encode(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+    use rstest::rstest;
+
+    use crate::codec::message_id::MessageId;
+
+    use super::EmptyMessage;
+
+    #[rstest]
+    #[case(EmptyMessage::Acknowledgement(MessageId::from_value(0x7d34)), vec![0x60, 0x00, 0x7d, 0x34])]
+    #[case(EmptyMessage::Reset(MessageId::from_value(0x7d34)), vec![0x70, 0x00, 0x7d, 0x34])]
+    fn encode(#[case] empty_message: EmptyMessage, #[case] expected: Vec<u8>) {
+        assert_eq!(expected, empty_message.encode());
+    }
+}
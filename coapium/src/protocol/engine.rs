@@ -0,0 +1,116 @@
+use super::{
+    effect::Effect,
+    event::Event,
+    processor::{Error, Processor},
+};
+
+/// Pull-based front of the [`Processor`] for embedders with an event loop of their own instead of
+/// one of the systems.
+///
+/// Each event is handed in and its effects pulled out one by one. The effects of a tick are kept
+/// inline, see [`Effects`](super::effect::Effects), so the list of them isn't allocated for the
+/// common tick. What still allocates: the datagrams of [`Effect::Transmit`] and the responses
+/// handed out, and the processor's own stores of transactions, message ids and observations.
+/// The processor also needs `std` for those stores and its clock, so this doesn't run on
+/// bare-metal targets yet.
+#[derive(Debug)]
+pub struct Engine {
+    processor: Processor,
+}
+
+impl Engine {
+    pub fn new(processor: Processor) -> Self {
+        Self { processor }
+    }
+
+    /// Handles `event`, returning the effects to carry out in order.
+    pub fn handle(&mut self, event: Event) -> Result<impl Iterator<Item = Effect>, Error> {
+        Ok(self.processor.tick(event)?.into_iter())
+    }
+
+    pub fn processor(&self) -> &Processor {
+        &self.processor
+    }
+
+    pub fn processor_mut(&mut self) -> &mut Processor {
+        &mut self.processor
+    }
+
+    pub fn into_processor(self) -> Processor {
+        self.processor
+    }
+}
+
+impl From<Processor> for Engine {
+    fn from(processor: Processor) -> Self {
+        Self::new(processor)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+    use rstest::rstest;
+
+    use crate::{
+        codec::{message::GetOptions, MessageId, Reset, Token},
+        protocol::{
+            effect::Effect,
+            event::Event,
+            get::Get,
+            message_id_store::MessageIdStore,
+            new_request::NewRequest,
+            processor::Processor,
+            reliability::Reliability,
+            response,
+            timeout::{ExchangeLifetimeTimeout, RetransmissionTimeout},
+            transmission_parameters::{ConfirmableParameters, InitialRetransmissionFactor},
+        },
+    };
+
+    use super::Engine;
+
+    #[rstest]
+    fn handle() {
+        let parameters =
+            ConfirmableParameters::default(InitialRetransmissionFactor::new(0.5).unwrap());
+        let mut engine = Engine::new(Processor::new(MessageIdStore::new(MessageId::from_value(
+            0,
+        ))));
+        let token = Token::new().unwrap();
+        let request = NewRequest::Get(Get {
+            options: GetOptions::new(),
+            reliability: Reliability::Confirmable(parameters),
+        });
+
+        let effects = engine
+            .handle(Event::TransactionRequested(request.clone(), token))
+            .unwrap()
+            .collect::<Vec<_>>();
+
+        assert_eq!(
+            vec![
+                ExchangeLifetimeTimeout::new(0.into(), &parameters).into(),
+                RetransmissionTimeout::new(0.into(), &parameters).into(),
+                Effect::Transmit(request.encode(0.into(), token)),
+            ],
+            effects
+        );
+
+        let mut effects = engine
+            .handle(Event::DataReceived(
+                Reset::from_message_id(0.into()).encode(),
+            ))
+            .unwrap();
+
+        assert_eq!(
+            Some(Effect::TransactionResolved(
+                token,
+                Err(response::Error::Reset)
+            )),
+            effects.next()
+        );
+        assert_eq!(None, effects.next());
+        assert_eq!(0, engine.processor().stats().in_flight);
+    }
+}
@@ -1,13 +1,38 @@
+use std::{io, net::SocketAddr};
+
 use crate::codec::token::Token;
 
-use super::{effect::Timeout, new_request::NewRequest};
+use super::{
+    body::Body, effect::Timeout, empty_message::EmptyMessage, new_request::NewRequest,
+    request_queue::Priority,
+};
 
 #[derive(Debug)]
 pub enum Event {
     TransactionRequested(NewRequest, Token),
+    /// Same as `TransactionRequested`, but sent ahead of or after other requests waiting for
+    /// capacity depending on the priority.
+    PrioritizedTransactionRequested(NewRequest, Token, Priority),
+    /// POST or PUT whose payload is taken from the body block by block, see
+    /// [`BlockUpload`](super::block_upload::BlockUpload).
+    UploadRequested(NewRequest, Body, Token),
     TransactionCanceled(Token),
     TimeoutReached(Timeout),
+    /// Datagram from the only peer of the transport, like a connected socket.
     DataReceived(Vec<u8>),
+    /// Datagram from `source`, for transports that receive from more than one peer. Message ids
+    /// are only unique per endpoint, so duplicates are recognized per source.
+    DataReceivedFrom(SocketAddr, Vec<u8>),
+    /// A datagram larger than the receive buffer, cut off at the size of the buffer. It's dropped
+    /// instead of parsed, as its payload would be silently cut short.
+    TruncatedDataReceived(Vec<u8>),
+    EmptyMessageRequested(EmptyMessage),
+    /// The datagram of a [`Effect::Transmit`](super::effect::Effect::Transmit) couldn't be sent,
+    /// its transaction fails.
+    TransmitFailed(Vec<u8>, io::ErrorKind),
+    /// The transport reported the peer as unreachable, e.g. an ICMP port unreachable on a
+    /// connected socket, every transaction in flight fails.
+    TransportFailed(io::ErrorKind),
 }
 
 pub type Events = Vec<Event>;
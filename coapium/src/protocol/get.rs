@@ -5,6 +5,7 @@ use crate::codec::{
 
 use super::reliability::Reliability;
 
+/// GET request, which has no payload since RFC 7252 gives a payload in a GET no meaning.
 #[derive(Clone, Debug, PartialEq)]
 pub struct Get {
     pub options: GetOptions,
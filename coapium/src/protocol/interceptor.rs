@@ -0,0 +1,15 @@
+use std::fmt::Debug;
+
+use super::{new_request::NewRequest, response::Response};
+
+/// Hook into the processor to inspect or rewrite every request and response, for example to add
+/// an authentication option or to rewrite paths.
+///
+/// Interceptors run in the order they were added for both requests and responses.
+pub trait RequestInterceptor: Debug + Send {
+    /// Called right before a request is encoded and transmitted for the first time.
+    fn before_encode(&mut self, _request: &mut NewRequest) {}
+
+    /// Called after a response has been decoded, before it is handed to the requester.
+    fn after_decode(&mut self, _response: &mut Response) {}
+}
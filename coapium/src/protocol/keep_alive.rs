@@ -0,0 +1,106 @@
+use std::time::Duration;
+
+use super::ping::{self, Ping};
+
+/// Pings sent to the peer at a fixed interval, e.g. to keep a NAT binding open.
+#[derive(Clone, Debug, PartialEq)]
+pub struct KeepAlive {
+    pub interval: Duration,
+    pub max_missed_pongs: u32,
+    pub ping: Ping,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum ConnectionState {
+    Connected,
+    Disconnected,
+}
+
+/// Keeps count of unanswered keep-alive pings.
+///
+/// The connection is considered disconnected once more than `max_missed_pongs` pings in a row
+/// went unanswered, and connected again as soon as a ping is answered.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Liveness {
+    max_missed_pongs: u32,
+    missed_pongs: u32,
+}
+
+impl KeepAlive {
+    pub const DEFAULT_MAX_MISSED_PONGS: u32 = 3;
+
+    pub fn new(interval: Duration, ping: Ping) -> Self {
+        Self {
+            interval,
+            max_missed_pongs: Self::DEFAULT_MAX_MISSED_PONGS,
+            ping,
+        }
+    }
+
+    pub fn max_missed_pongs(mut self, max_missed_pongs: u32) -> Self {
+        self.max_missed_pongs = max_missed_pongs;
+        self
+    }
+}
+
+impl Liveness {
+    pub fn new(max_missed_pongs: u32) -> Self {
+        Self {
+            max_missed_pongs,
+            missed_pongs: 0,
+        }
+    }
+
+    pub fn missed_pongs(&self) -> u32 {
+        self.missed_pongs
+    }
+
+    /// Records the outcome of a keep-alive ping and returns the resulting state.
+    pub fn on_ping_result(&mut self, result: &ping::Result) -> ConnectionState {
+        match result {
+            Ok(()) => self.missed_pongs = 0,
+            Err(_) => self.missed_pongs = self.missed_pongs.saturating_add(1),
+        }
+
+        self.state()
+    }
+
+    pub fn state(&self) -> ConnectionState {
+        if self.missed_pongs > self.max_missed_pongs {
+            ConnectionState::Disconnected
+        } else {
+            ConnectionState::Connected
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+    use rstest::rstest;
+
+    use crate::protocol::ping;
+
+    use super::{ConnectionState, Liveness};
+
+    #[rstest]
+    #[case(&[], ConnectionState::Connected)]
+    #[case(&[Err(ping::Error::Timeout), Err(ping::Error::Timeout)], ConnectionState::Connected)]
+    #[case(
+        &[Err(ping::Error::Timeout), Err(ping::Error::Timeout), Err(ping::Error::AcknowledgementTimeout)],
+        ConnectionState::Disconnected
+    )]
+    #[case(
+        &[Err(ping::Error::Timeout), Err(ping::Error::Timeout), Err(ping::Error::Timeout), Ok(())],
+        ConnectionState::Connected
+    )]
+    fn on_ping_result(#[case] results: &[ping::Result], #[case] expected: ConnectionState) {
+        let mut liveness = Liveness::new(2);
+
+        for result in results {
+            liveness.on_ping_result(result);
+        }
+
+        assert_eq!(expected, liveness.state());
+    }
+}
@@ -1,9 +1,52 @@
 use crate::codec::message_id::MessageId;
 
+/// Number of message ids available per endpoint.
+pub const CAPACITY: usize = u16::MAX as usize + 1;
+
 #[derive(Debug)]
 pub struct MessageIdStore {
-    claimed: Vec<MessageId>,
+    claimed: Vec<(MessageId, MessageIdState)>,
     next: Option<MessageId>,
+    claims: u64,
+    exhaustions: u64,
+    releases: u64,
+}
+
+/// Where a message id is in its life cycle.
+///
+/// An id goes from free to in flight when a transaction claims it. However the transaction ends,
+/// with a response, a reset, a cancellation or by giving up, the id is quarantined until the
+/// lifetime timeout of the transaction releases it, see
+/// [RFC 7252 §4.4](https://datatracker.ietf.org/doc/html/rfc7252#section-4.4). That way a late
+/// acknowledgement or reset can't be matched to a new request.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub enum MessageIdState {
+    #[default]
+    Free,
+    InFlight,
+    Quarantined,
+}
+
+/// What to do with a new request when every message id is claimed.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub enum ExhaustionPolicy {
+    /// Resolve the request with [`response::Error::MessageIdExhausted`](super::response::Error).
+    FailFast,
+    /// Hold on to the request until a message id is released.
+    #[default]
+    Queue,
+}
+
+/// Counters of a [`MessageIdStore`], a `claimed` count that keeps growing while `releases` stays
+/// behind `claims` points at message ids that are never released.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct Usage {
+    pub claimed: usize,
+    pub claims: u64,
+    pub exhaustions: u64,
+    /// Claimed message ids waiting for their lifetime to end.
+    pub quarantined: usize,
+    pub releases: u64,
 }
 
 impl MessageIdStore {
@@ -11,6 +54,18 @@ impl MessageIdStore {
         Self {
             claimed: Default::default(),
             next: Some(initial_value),
+            claims: 0,
+            exhaustions: 0,
+            releases: 0,
+        }
+    }
+
+    /// Store without any message id left to claim.
+    #[cfg(test)]
+    pub(crate) fn exhausted() -> Self {
+        Self {
+            next: None,
+            ..Self::new(MessageId::from_value(0))
         }
     }
 
@@ -21,7 +76,10 @@ impl MessageIdStore {
     pub fn claim(&mut self) -> Option<MessageId> {
         let claimed = match self.next {
             Some(next) => next,
-            None => return None,
+            None => {
+                self.exhaustions += 1;
+                return None;
+            }
         };
 
         let next = claimed.next();
@@ -31,24 +89,131 @@ impl MessageIdStore {
             self.next = Some(next);
         }
 
-        self.claimed.push(claimed);
+        self.claimed.push((claimed, MessageIdState::InFlight));
+        self.claims += 1;
 
         Some(claimed)
     }
 
+    /// Holds on to the message id of a transaction that ended until it is released.
+    pub fn quarantine(&mut self, message_id: MessageId) {
+        if let Some((_, state)) = self.claimed.iter_mut().find(|(m, _)| *m == message_id) {
+            *state = MessageIdState::Quarantined;
+        }
+    }
+
     pub fn release(&mut self, message_id: MessageId) {
-        let position = match self.claimed.iter().position(|m| *m == message_id) {
+        let position = match self.claimed.iter().position(|(m, _)| *m == message_id) {
             Some(position) => position,
             None => return,
         };
 
         self.claimed.swap_remove(position);
+        self.releases += 1;
         if self.next.is_none() {
             self.next = Some(message_id)
         }
     }
 
+    /// Gives memory held on to after a burst of traffic back to the allocator.
+    pub fn compact(&mut self) {
+        self.claimed.shrink_to_fit();
+    }
+
     pub fn is_claimed(&self, message_id: &MessageId) -> bool {
-        self.claimed.contains(message_id)
+        self.state(message_id) != MessageIdState::Free
+    }
+
+    pub fn state(&self, message_id: &MessageId) -> MessageIdState {
+        self.claimed
+            .iter()
+            .find(|(m, _)| m == message_id)
+            .map_or(MessageIdState::Free, |(_, state)| *state)
+    }
+
+    pub fn usage(&self) -> Usage {
+        Usage {
+            claimed: self.claimed.len(),
+            claims: self.claims,
+            exhaustions: self.exhaustions,
+            quarantined: self
+                .claimed
+                .iter()
+                .filter(|(_, state)| *state == MessageIdState::Quarantined)
+                .count(),
+            releases: self.releases,
+        }
+    }
+
+    /// Share of the message ids that are claimed, from 0 to 1.
+    pub fn utilization(&self) -> f64 {
+        self.claimed.len() as f64 / CAPACITY as f64
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+    use rstest::rstest;
+
+    use crate::codec::message_id::MessageId;
+
+    use super::{MessageIdState, MessageIdStore, Usage, CAPACITY};
+
+    #[rstest]
+    fn usage() {
+        let mut store = MessageIdStore::new(MessageId::from_value(0));
+
+        let first = store.claim().unwrap();
+        let second = store.claim().unwrap();
+        store.release(first);
+        store.release(first);
+        store.quarantine(second);
+
+        assert_eq!(
+            Usage {
+                claimed: 1,
+                claims: 2,
+                exhaustions: 0,
+                quarantined: 1,
+                releases: 1,
+            },
+            store.usage()
+        );
+        assert_eq!(1.0 / CAPACITY as f64, store.utilization());
+    }
+
+    #[rstest]
+    fn claim_when_exhausted() {
+        let mut store = MessageIdStore::exhausted();
+
+        assert_eq!(None, store.claim());
+        assert_eq!(1, store.usage().exhaustions);
+    }
+
+    #[rstest]
+    fn life_cycle() {
+        let mut store = MessageIdStore::new(MessageId::from_value(0));
+        let message_id = MessageId::from_value(0);
+        assert_eq!(MessageIdState::Free, store.state(&message_id));
+
+        store.claim().unwrap();
+        assert_eq!(MessageIdState::InFlight, store.state(&message_id));
+
+        store.quarantine(message_id);
+        assert_eq!(MessageIdState::Quarantined, store.state(&message_id));
+        assert_eq!(true, store.is_claimed(&message_id));
+
+        store.release(message_id);
+        assert_eq!(MessageIdState::Free, store.state(&message_id));
+    }
+
+    #[rstest]
+    fn quarantine_of_free_message_id_is_ignored() {
+        let mut store = MessageIdStore::new(MessageId::from_value(0));
+
+        store.quarantine(MessageId::from_value(3));
+
+        assert_eq!(MessageIdState::Free, store.state(&MessageId::from_value(3)));
     }
 }
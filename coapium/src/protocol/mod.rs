@@ -1,17 +1,35 @@
+pub mod backoff;
+pub mod block_upload;
+pub mod body;
+pub mod clock;
+pub mod custom;
 pub mod delete;
+pub mod duplicate_window;
 pub mod effect;
+pub mod empty_message;
+pub mod engine;
 pub mod event;
 pub mod get;
+pub mod interceptor;
+pub mod keep_alive;
 pub mod message_id_store;
 pub mod new_request;
+pub mod observation;
 pub mod ping;
 pub mod post;
 pub mod processor;
+pub mod profile;
 pub mod put;
+pub mod quick_block;
 pub mod reliability;
 pub mod request;
+pub mod request_queue;
+pub mod request_tagging;
+pub mod request_template;
 pub mod response;
+pub mod status;
 pub mod timeout;
 pub mod transaction;
 pub mod transaction_store;
 pub mod transmission_parameters;
+pub mod websocket;
@@ -1,10 +1,25 @@
-use crate::codec::{MessageId, Token};
+use crate::codec::{
+    message,
+    option::{Echo, RequestTag},
+    MessageId, Token,
+};
 
-use super::{delete::Delete, get::Get, ping::Ping, post::Post, put::Put, reliability::Reliability};
+use super::{
+    custom::Custom,
+    delete::Delete,
+    get::Get,
+    ping::Ping,
+    post::Post,
+    put::Put,
+    reliability::Reliability,
+    transmission_parameters::{ConfirmableParameters, NonConfirmableParameters},
+};
 
 // TODO: Fix this, weird naming, what do you mean "new request", are there old requests? :P
 #[derive(Clone, Debug, PartialEq)]
 pub enum NewRequest {
+    /// Escape hatch for requests the other variants can't express.
+    Custom(Custom),
     Delete(Delete),
     Get(Get),
     Ping(Ping),
@@ -13,8 +28,24 @@ pub enum NewRequest {
 }
 
 impl NewRequest {
+    pub fn echo(&self) -> Option<&Echo> {
+        match self {
+            NewRequest::Custom(custom) => custom.options.echo(),
+            NewRequest::Delete(delete) => delete.options.echo(),
+            NewRequest::Get(get) => get.options.echo(),
+            NewRequest::Ping(_) => None,
+            NewRequest::Post(post) => post.options.echo(),
+            NewRequest::Put(put) => put.options.echo(),
+        }
+    }
+
+    /// Encodes the request into the bytes sent on the wire.
+    ///
+    /// The encoding is deterministic, the same request, message id and token always result in the
+    /// same bytes.
     pub fn encode(self, message_id: MessageId, token: Token) -> Vec<u8> {
         match self {
+            NewRequest::Custom(request) => request.encode(message_id, token),
             NewRequest::Delete(request) => request.encode(message_id, token),
             NewRequest::Get(request) => request.encode(message_id, token),
             NewRequest::Ping(request) => request.encode(message_id, token),
@@ -23,8 +54,74 @@ impl NewRequest {
         }
     }
 
+    /// Creates a request from a decoded request message, together with the message id and token
+    /// it was sent with.
+    ///
+    /// Transmission parameters are not part of the wire format, the parameters matching the
+    /// reliability of the message are used.
+    pub fn from_request(
+        request: message::Request,
+        confirmable_parameters: ConfirmableParameters,
+        non_confirmable_parameters: NonConfirmableParameters,
+    ) -> (MessageId, Token, Self) {
+        let message_id = request.message_id();
+        let token = *request.token();
+        let reliability = match request.reliability() {
+            message::Reliability::Confirmable => Reliability::Confirmable(confirmable_parameters),
+            message::Reliability::NonConfirmable => {
+                Reliability::NonConfirmable(non_confirmable_parameters)
+            }
+        };
+
+        (
+            message_id,
+            token,
+            Self::from_request_with_reliability(request, reliability),
+        )
+    }
+
+    /// Same as [`from_request`](Self::from_request), with the reliability given instead of taken
+    /// from the message.
+    pub fn from_request_with_reliability(
+        request: message::Request,
+        reliability: Reliability,
+    ) -> Self {
+        match request {
+            message::Request::Delete(delete) => NewRequest::Delete(Delete {
+                options: delete.options().clone(),
+                reliability,
+            }),
+            message::Request::Get(get) => NewRequest::Get(Get {
+                options: get.options().clone(),
+                reliability,
+            }),
+            message::Request::Post(post) => NewRequest::Post(Post {
+                options: post.options().clone(),
+                reliability,
+                payload: post.payload().clone(),
+            }),
+            message::Request::Put(put) => NewRequest::Put(Put {
+                options: put.options().clone(),
+                reliability,
+                payload: put.payload().clone(),
+            }),
+        }
+    }
+
+    pub fn request_tag(&self) -> Option<&RequestTag> {
+        match self {
+            NewRequest::Custom(custom) => custom.options.request_tag(),
+            NewRequest::Delete(delete) => delete.options.request_tag(),
+            NewRequest::Get(get) => get.options.request_tag(),
+            NewRequest::Ping(_) => None,
+            NewRequest::Post(post) => post.options.request_tag(),
+            NewRequest::Put(put) => put.options.request_tag(),
+        }
+    }
+
     pub fn reliability(&self) -> Reliability {
         match self {
+            NewRequest::Custom(custom) => custom.reliability,
             NewRequest::Delete(delete) => delete.reliability,
             NewRequest::Get(get) => get.reliability,
             NewRequest::Ping(ping) => Reliability::Confirmable(ping.confirmable_parameters),
@@ -32,4 +129,88 @@ impl NewRequest {
             NewRequest::Put(put) => put.reliability,
         }
     }
+
+    /// Sets the Echo option, pings have no options and are left as they are.
+    pub fn set_echo(&mut self, echo: Echo) {
+        match self {
+            NewRequest::Custom(custom) => custom.options.set_echo(echo),
+            NewRequest::Delete(delete) => delete.options.set_echo(echo),
+            NewRequest::Get(get) => get.options.set_echo(echo),
+            NewRequest::Ping(_) => {}
+            NewRequest::Post(post) => post.options.set_echo(echo),
+            NewRequest::Put(put) => put.options.set_echo(echo),
+        }
+    }
+
+    /// Sets the Request-Tag option, pings have no options and are left as they are.
+    pub fn set_request_tag(&mut self, request_tag: RequestTag) {
+        match self {
+            NewRequest::Custom(custom) => custom.options.set_request_tag(request_tag),
+            NewRequest::Delete(delete) => delete.options.set_request_tag(request_tag),
+            NewRequest::Get(get) => get.options.set_request_tag(request_tag),
+            NewRequest::Ping(_) => {}
+            NewRequest::Post(post) => post.options.set_request_tag(request_tag),
+            NewRequest::Put(put) => put.options.set_request_tag(request_tag),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+    use rstest::rstest;
+
+    use crate::{
+        codec::{
+            message::{GetOptions, Message, PutOptions},
+            MessageId, Payload, Token,
+        },
+        protocol::{
+            get::Get,
+            put::Put,
+            reliability::Reliability,
+            transmission_parameters::{
+                ConfirmableParameters, InitialRetransmissionFactor, NonConfirmableParameters,
+            },
+        },
+    };
+
+    use super::NewRequest;
+
+    fn confirmable_parameters() -> ConfirmableParameters {
+        ConfirmableParameters::default(InitialRetransmissionFactor::new(0.5).unwrap())
+    }
+
+    #[rstest]
+    #[case(NewRequest::Get(Get {
+        options: {
+            let mut options = GetOptions::new();
+            options.set_uri_path("abc".try_into().unwrap());
+            options
+        },
+        reliability: Reliability::Confirmable(confirmable_parameters()),
+    }))]
+    #[case(NewRequest::Put(Put {
+        options: PutOptions::new(),
+        reliability: Reliability::NonConfirmable(NonConfirmableParameters::default()),
+        payload: Payload::from_value(vec![1, 2, 3]),
+    }))]
+    fn encode_then_from_request(#[case] request: NewRequest) {
+        let message_id = MessageId::from_value(7);
+        let token = Token::from_value(vec![1, 2]).unwrap();
+
+        let bytes = request.clone().encode(message_id, token);
+        let Ok(Message::Request(decoded)) = Message::decode(&bytes) else {
+            panic!("Expected a request message");
+        };
+
+        assert_eq!(
+            (message_id, token, request),
+            NewRequest::from_request(
+                decoded,
+                confirmable_parameters(),
+                NonConfirmableParameters::default()
+            )
+        );
+    }
 }
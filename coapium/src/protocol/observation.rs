@@ -0,0 +1,172 @@
+use std::time::{Duration, Instant};
+
+use crate::codec::{option::MaxAge, Options, ResponseCode};
+
+/// Largest distance between two Observe values for the larger one to be the newer, RFC 7641 §3.4.
+const SEQUENCE_WINDOW: u32 = 1 << 23;
+/// Time after which a notification is newer than the previous one whatever its Observe value.
+const SEQUENCE_TIMEOUT: Duration = Duration::from_secs(128);
+
+/// Observation of a resource the processor receives notifications for, RFC 7641.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Observation {
+    received_at: Instant,
+    sequence: u32,
+}
+
+impl Observation {
+    pub fn new(sequence: u32, now: Instant) -> Self {
+        Self {
+            received_at: now,
+            sequence,
+        }
+    }
+
+    /// Takes the notification with Observe value `sequence` if it's newer than the last one, a
+    /// notification that was overtaken by a newer one is stale and left out.
+    pub fn accept(&mut self, sequence: u32, now: Instant) -> bool {
+        let newer = (self.sequence < sequence && sequence - self.sequence < SEQUENCE_WINDOW)
+            || (self.sequence > sequence && self.sequence - sequence > SEQUENCE_WINDOW)
+            || now > self.received_at + SEQUENCE_TIMEOUT;
+        if newer {
+            *self = Self::new(sequence, now);
+        }
+
+        newer
+    }
+}
+
+/// How long a response or notification is fresh for by its Max-Age, RFC 7252 §5.10.5. An
+/// observer that got no notification by [`stale_at`](Freshness::stale_at) can't tell if the
+/// resource changed and should register again.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Freshness {
+    pub received_at: Instant,
+    /// Max-Age of the response, 60 seconds if it has none.
+    pub max_age: Duration,
+}
+
+impl Freshness {
+    pub fn new(options: &Options, received_at: Instant) -> Self {
+        Self {
+            received_at,
+            max_age: options
+                .max_age_duration()
+                .unwrap_or_else(|| Duration::from_secs(MaxAge::default().seconds().into())),
+        }
+    }
+
+    pub fn age(&self, now: Instant) -> Duration {
+        now.saturating_duration_since(self.received_at)
+    }
+
+    pub fn stale_at(&self) -> Instant {
+        self.received_at + self.max_age
+    }
+
+    pub fn is_stale(&self, now: Instant) -> bool {
+        now >= self.stale_at()
+    }
+}
+
+/// Wait before registering again with a server that answered 5.03 (Service Unavailable), its
+/// Max-Age capped at `max_delay`, RFC 7252 §5.9.3.4. `None` for any other response code.
+pub fn retry_after_unavailable(
+    response_code: ResponseCode,
+    options: &Options,
+    max_delay: Duration,
+) -> Option<Duration> {
+    if response_code != ResponseCode::SERVICE_UNAVAILABLE {
+        return None;
+    }
+
+    let max_age = options
+        .max_age_duration()
+        .unwrap_or_else(|| Duration::from_secs(MaxAge::default().seconds().into()));
+    Some(max_age.min(max_delay))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::{Duration, Instant};
+
+    use pretty_assertions::assert_eq;
+    use rstest::rstest;
+
+    use crate::codec::{Options, ResponseCode};
+
+    use super::{retry_after_unavailable, Freshness, Observation};
+
+    #[rstest]
+    #[case(5, 6, 0, true)]
+    #[case(5, 5, 0, false)]
+    #[case(6, 5, 0, false)]
+    #[case(0x00ff_fffe, 1, 0, true)]
+    #[case(1, 0x00ff_fffe, 0, false)]
+    #[case(6, 5, 129, true)]
+    fn accept(
+        #[case] last: u32,
+        #[case] sequence: u32,
+        #[case] seconds_later: u64,
+        #[case] expected: bool,
+    ) {
+        let now = Instant::now();
+        let mut observation = Observation::new(last, now);
+
+        assert_eq!(
+            expected,
+            observation.accept(sequence, now + Duration::from_secs(seconds_later))
+        );
+    }
+
+    #[rstest]
+    #[case(None, Duration::from_secs(60))]
+    #[case(Some(5), Duration::from_secs(5))]
+    fn freshness(#[case] max_age: Option<u32>, #[case] expected: Duration) {
+        let now = Instant::now();
+        let mut options = Options::new();
+        if let Some(max_age) = max_age {
+            options.set_max_age(max_age.into());
+        }
+
+        let freshness = Freshness::new(&options, now);
+
+        assert_eq!(expected, freshness.max_age);
+        assert_eq!(now + expected, freshness.stale_at());
+        assert_eq!(
+            Duration::from_secs(2),
+            freshness.age(now + Duration::from_secs(2))
+        );
+        assert!(!freshness.is_stale(now + expected - Duration::from_millis(1)));
+        assert!(freshness.is_stale(now + expected));
+    }
+
+    #[rstest]
+    #[case(
+        ResponseCode::SERVICE_UNAVAILABLE,
+        Some(5),
+        Some(Duration::from_secs(5))
+    )]
+    #[case(ResponseCode::SERVICE_UNAVAILABLE, None, Some(Duration::from_secs(60)))]
+    #[case(
+        ResponseCode::SERVICE_UNAVAILABLE,
+        Some(600),
+        Some(Duration::from_secs(120))
+    )]
+    #[case(ResponseCode::CONTENT, Some(5), None)]
+    fn retry_after_unavailable_uses_max_age(
+        #[case] response_code: ResponseCode,
+        #[case] max_age: Option<u32>,
+        #[case] expected: Option<Duration>,
+    ) {
+        let mut options = Options::new();
+        if let Some(max_age) = max_age {
+            options.set_max_age(max_age.into());
+        }
+
+        assert_eq!(
+            expected,
+            retry_after_unavailable(response_code, &options, Duration::from_secs(120))
+        );
+    }
+}
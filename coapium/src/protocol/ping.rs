@@ -1,6 +1,8 @@
-use std::result;
+use std::{io, result};
 
-use crate::codec::{self, message::Reliability, Code, Header, MessageId, Token};
+use crate::codec::{
+    self, message::Reliability, Code, Header, MessageId, MethodCode, ResponseCode, Token,
+};
 
 use super::{
     response::{self, Response},
@@ -33,20 +35,91 @@ impl Ping {
 
 #[derive(Clone, Debug)]
 pub enum Error {
-    UnexpectedResponse(Response),
+    UnexpectedResponse(Box<Response>),
     AcknowledgementTimeout,
     Codec(codec::Error),
+    Internal(&'static str),
+    MessageIdExhausted,
+    MessageTooLarge {
+        size: usize,
+        limit: usize,
+    },
+    ProtocolViolation {
+        method: MethodCode,
+        response_code: ResponseCode,
+    },
+    QueueFull,
+    Shutdown,
     Timeout,
+    TokenGeneration,
+    TransactionLimit,
+    Transport(io::ErrorKind),
 }
 
 pub fn into_result(result: result::Result<Response, response::Error>) -> result::Result<(), Error> {
     match result {
-        Ok(response) => Err(Error::UnexpectedResponse(response)),
+        Ok(response) => Err(Error::UnexpectedResponse(Box::new(response))),
         Err(error) => match error {
             response::Error::AcknowledgementTimeout => Err(Error::AcknowledgementTimeout),
+            response::Error::Body(_) => Err(Error::Internal("a ping has no body")),
             response::Error::Codec(error) => Err(Error::Codec(error)),
+            response::Error::Internal(reason) => Err(Error::Internal(reason)),
+            response::Error::MessageIdExhausted => Err(Error::MessageIdExhausted),
+            response::Error::MessageTooLarge { size, limit } => {
+                Err(Error::MessageTooLarge { size, limit })
+            }
+            response::Error::ProtocolViolation {
+                method,
+                response_code,
+            } => Err(Error::ProtocolViolation {
+                method,
+                response_code,
+            }),
+            response::Error::QueueFull => Err(Error::QueueFull),
             response::Error::Reset => Ok(()),
+            response::Error::Shutdown => Err(Error::Shutdown),
             response::Error::Timeout => Err(Error::Timeout),
+            response::Error::TokenGeneration => Err(Error::TokenGeneration),
+            response::Error::TransactionLimit => Err(Error::TransactionLimit),
+            response::Error::Transport(kind) => Err(Error::Transport(kind)),
         },
     }
 }
+
+impl std::fmt::Display for Error {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Error::UnexpectedResponse(response) => write!(
+                f,
+                "ping expected a reset but got a {} response",
+                Code::Response(response.response_code)
+            ),
+            Error::AcknowledgementTimeout => {
+                write!(f, "ping timed out waiting for acknowledgement")
+            }
+            Error::Codec(error) => write!(f, "ping failed: {error}"),
+            Error::Internal(reason) => write!(f, "ping failed, internal error: {reason}"),
+            Error::MessageIdExhausted => write!(f, "ping failed, every message id is in use"),
+            Error::MessageTooLarge { size, limit } => {
+                write!(f, "ping of {size} bytes exceeds the limit of {limit} bytes")
+            }
+            Error::ProtocolViolation {
+                method,
+                response_code,
+            } => write!(
+                f,
+                "ping failed, {} response is not defined for a {} request",
+                Code::Response(*response_code),
+                Code::Request(*method)
+            ),
+            Error::QueueFull => write!(f, "ping failed, too many requests are waiting to be sent"),
+            Error::Shutdown => write!(f, "ping failed, client has shut down"),
+            Error::Timeout => write!(f, "ping timed out"),
+            Error::TokenGeneration => write!(f, "ping failed to generate a token"),
+            Error::TransactionLimit => write!(f, "ping failed, too many requests are in flight"),
+            Error::Transport(kind) => write!(f, "ping failed, transport failed: {kind}"),
+        }
+    }
+}
+
+impl std::error::Error for Error {}
@@ -1,24 +1,45 @@
-use std::collections::VecDeque;
+use std::{collections::HashMap, io, net::SocketAddr};
+
+use smallvec::smallvec;
 
 use crate::codec::{
-    self, message::Message, message_id::MessageId, token::Token, Acknowledgement, Piggyback, Reset,
+    self,
+    code::response_code::ClientError,
+    message::{Message, Reserved},
+    message_id::MessageId,
+    option::Block2,
+    token::Token,
+    Acknowledgement, Header, MessageType, MethodCode, Piggyback, Reset, ResponseCode,
 };
 
 use super::{
+    backoff::{BackoffStrategy, ExponentialBackoff},
+    block_upload::BlockUpload,
+    body::Body,
+    clock::{Clock, SystemClock},
+    duplicate_window::DuplicateWindow,
     effect::{Effect, Effects, Timeout},
     event::Event,
-    message_id_store::MessageIdStore,
+    interceptor::RequestInterceptor,
+    message_id_store::{self, ExhaustionPolicy, MessageIdStore},
     new_request::NewRequest,
+    observation::Observation,
+    profile::TransmissionProfile,
+    request_queue::{Priority, RequestQueue},
+    request_tagging::RequestTagging,
     response,
+    status::Status,
     timeout::{
-        ExchangeLifetimeTimeout, MaxTransmitWaitTimeout, NonLifetimeTimeout, RetransmissionTimeout,
+        ExchangeLifetimeTimeout, MaxTransmitWaitTimeout, NonLifetimeTimeout,
+        NonRetransmissionTimeout, RetransmissionTimeout,
     },
-    transaction::Transaction,
-    transaction_store::TransactionStore,
+    transaction::{next_retransmission, Transaction, PATH_MTU},
+    transaction_store::{EvictionHook, TransactionStore},
 };
 
 #[derive(Debug, PartialEq)]
 pub enum Error {
+    MessageIdExhausted,
     Other(String),
 }
 
@@ -30,31 +51,307 @@ impl Error {
 
 pub type Result = std::result::Result<Effects, Error>;
 
+/// Snapshot of what a [`Processor`] is holding on to.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct Stats {
+    pub claimed_message_ids: usize,
+    /// Non-confirmable responses dropped as duplicates, since the processor was created.
+    pub duplicate_non_responses: usize,
+    pub in_flight: usize,
+    /// Received datagrams that couldn't be parsed, since the processor was created.
+    pub malformed_datagrams: usize,
+    pub queued: usize,
+    /// Received datagrams larger than the receive buffer, since the processor was created.
+    pub truncated_datagrams: usize,
+}
+
+/// What to do with received datagrams that can't be parsed, they are counted either way.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub enum MalformedPolicy {
+    #[default]
+    Drop,
+    /// Hand the datagram over as [`Effect::MalformedDatagramReceived`].
+    Deliver,
+}
+
+/// What to do with received messages that have a reserved code.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub enum ReservedPolicy {
+    #[default]
+    Ignore,
+    /// Reject the message with a reset.
+    Reset,
+    /// Hand the datagram over as [`Effect::UnknownMessageReceived`].
+    Deliver,
+}
+
+/// What to do with a success response code that isn't defined for the method of the request by
+/// RFC 7252 §5.9, like 2.01 (Created) in reply to a GET.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub enum ResponseCodePolicy {
+    #[default]
+    Accept,
+    /// Resolve with the response, carrying a [`Warning::ProtocolViolation`](response::Warning).
+    Warn,
+    /// Resolve with [`response::Error::ProtocolViolation`] instead of the response.
+    Reject,
+}
+
+/// What to do when a POST or PUT is answered with 4.13 (Request Entity Too Large).
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub enum EntityTooLargePolicy {
+    /// Resolve with the response.
+    #[default]
+    Fail,
+    /// Send the payload again block-wise, at the block size the Block1 or Size1 option of the
+    /// response asks for. A response with neither is resolved as it is.
+    AutoBlockwise,
+}
+
 #[derive(Debug)]
 pub struct Processor {
-    queued: VecDeque<(NewRequest, Token)>,
+    backoff_strategy: Box<dyn BackoffStrategy>,
+    clock: Box<dyn Clock>,
+    duplicate_non_responses: usize,
+    entity_too_large_policy: EntityTooLargePolicy,
+    exhaustion_policy: ExhaustionPolicy,
+    interceptors: Vec<Box<dyn RequestInterceptor>>,
+    malformed_datagrams: usize,
+    malformed_policy: MalformedPolicy,
+    max_message_size: usize,
+    max_transactions: Option<usize>,
+    non_duplicates: DuplicateWindow,
+    observations: HashMap<Token, Observation>,
+    profile: TransmissionProfile,
+    queued: RequestQueue,
+    request_tagging: RequestTagging,
+    reserved_policy: ReservedPolicy,
+    response_code_policy: ResponseCodePolicy,
     transaction_store: TransactionStore,
+    truncated_datagrams: usize,
+    uploads: HashMap<Token, BlockUpload>,
     message_id_store: MessageIdStore,
 }
 
 impl Processor {
     pub fn new(message_id_store: MessageIdStore) -> Self {
         Self {
+            backoff_strategy: Box::new(ExponentialBackoff),
+            clock: Box::new(SystemClock),
+            duplicate_non_responses: 0,
+            entity_too_large_policy: Default::default(),
+            exhaustion_policy: Default::default(),
+            interceptors: vec![],
+            malformed_datagrams: 0,
+            malformed_policy: Default::default(),
+            max_message_size: PATH_MTU,
+            max_transactions: None,
+            non_duplicates: Default::default(),
+            observations: HashMap::new(),
+            profile: Default::default(),
             queued: Default::default(),
+            request_tagging: Default::default(),
+            reserved_policy: Default::default(),
+            response_code_policy: Default::default(),
             transaction_store: Default::default(),
+            truncated_datagrams: 0,
+            uploads: HashMap::new(),
             message_id_store,
         }
     }
 
+    /// Replaces the default exponential backoff between retransmissions of confirmable messages.
+    pub fn with_backoff_strategy<S: BackoffStrategy + 'static>(mut self, strategy: S) -> Self {
+        self.backoff_strategy = Box::new(strategy);
+        self
+    }
+
+    /// Replaces the system clock the start of transactions and their metrics are measured with.
+    pub fn with_clock<C: Clock + 'static>(mut self, clock: C) -> Self {
+        self.clock = Box::new(clock);
+        self
+    }
+
+    /// Tells `hook` about every transaction evicted as leaked, see
+    /// [`TransactionStore::evict_leaked`].
+    pub fn with_eviction_hook<H: EvictionHook + 'static>(mut self, hook: H) -> Self {
+        self.transaction_store.add_eviction_hook(Box::new(hook));
+        self
+    }
+
+    /// What to do when a POST or PUT is too large for the server, resolves with the 4.13 (Request
+    /// Entity Too Large) response by default.
+    pub fn with_entity_too_large_policy(
+        mut self,
+        entity_too_large_policy: EntityTooLargePolicy,
+    ) -> Self {
+        self.entity_too_large_policy = entity_too_large_policy;
+        self
+    }
+
+    /// What to do with new requests while every message id is claimed, queues them by default.
+    pub fn with_exhaustion_policy(mut self, exhaustion_policy: ExhaustionPolicy) -> Self {
+        self.exhaustion_policy = exhaustion_policy;
+        self
+    }
+
+    pub fn with_interceptor<I: RequestInterceptor + 'static>(self, interceptor: I) -> Self {
+        self.with_boxed_interceptor(Box::new(interceptor))
+    }
+
+    pub fn with_boxed_interceptor(mut self, interceptor: Box<dyn RequestInterceptor>) -> Self {
+        self.interceptors.push(interceptor);
+        self
+    }
+
+    /// Largest encoded request that is sent, defaults to `PATH_MTU`. Larger requests are resolved
+    /// with [response::Error::MessageTooLarge] without being transmitted.
+    pub fn with_max_message_size(mut self, max_message_size: usize) -> Self {
+        self.max_message_size = max_message_size;
+        self
+    }
+
+    /// Largest number of requests waiting for capacity, unbounded by default. Requests beyond it
+    /// are resolved with [response::Error::QueueFull].
+    pub fn with_max_queue_depth(mut self, max_queue_depth: usize) -> Self {
+        self.queued = RequestQueue::new(Some(max_queue_depth));
+        self
+    }
+
+    /// Largest number of transactions kept track of at once, unbounded by default. Requests
+    /// beyond it are resolved with [response::Error::TransactionLimit] instead of being queued.
+    pub fn with_max_transactions(mut self, max_transactions: usize) -> Self {
+        self.max_transactions = Some(max_transactions);
+        self
+    }
+
+    /// Number of non-confirmable message ids remembered to drop duplicate responses,
+    /// `DuplicateWindow::DEFAULT_CAPACITY` by default and `0` to deliver every copy.
+    pub fn with_non_duplicate_window(mut self, capacity: usize) -> Self {
+        self.non_duplicates =
+            DuplicateWindow::new(capacity).with_lifetime(self.profile.non_lifetime());
+        self
+    }
+
+    /// Transmission parameters of the peer, RFC 7252 by default. Sets how many exchanges are in
+    /// flight at once and how long duplicate responses are recognized, requests carry their own
+    /// retransmission parameters, see [`TransmissionProfile::confirmable_parameters`].
+    pub fn with_profile(mut self, profile: TransmissionProfile) -> Self {
+        self.profile = profile;
+        self.transaction_store.set_nstart(profile.nstart());
+        self.non_duplicates = self.non_duplicates.with_lifetime(profile.non_lifetime());
+        self
+    }
+
+    pub fn profile(&self) -> &TransmissionProfile {
+        &self.profile
+    }
+
+    /// What to do with datagrams that can't be parsed, drops them by default.
+    pub fn with_malformed_policy(mut self, malformed_policy: MalformedPolicy) -> Self {
+        self.malformed_policy = malformed_policy;
+        self
+    }
+
+    /// What to do with messages that have a reserved code, ignores them by default.
+    pub fn with_reserved_policy(mut self, reserved_policy: ReservedPolicy) -> Self {
+        self.reserved_policy = reserved_policy;
+        self
+    }
+
+    /// What to do with success responses that don't fit the method of the request, accepts them
+    /// by default.
+    pub fn with_response_code_policy(mut self, response_code_policy: ResponseCodePolicy) -> Self {
+        self.response_code_policy = response_code_policy;
+        self
+    }
+
+    pub fn stats(&self) -> Stats {
+        Stats {
+            claimed_message_ids: self.message_id_store.usage().claimed,
+            duplicate_non_responses: self.duplicate_non_responses,
+            in_flight: self.transaction_store.count(),
+            malformed_datagrams: self.malformed_datagrams,
+            queued: self.queued.len(),
+            truncated_datagrams: self.truncated_datagrams,
+        }
+    }
+
+    /// Where the request of `token` stands, `None` once it's resolved or if it was never
+    /// requested.
+    pub fn status(&self, token: &Token) -> Option<Status> {
+        if self.queued.contains(token) {
+            return Some(Status::queued());
+        }
+
+        self.transaction_store
+            .find_by_token(token)
+            .map(Transaction::status)
+    }
+
+    /// Forgets the message ids of received non-confirmable responses. Datagrams handed in with
+    /// [`Event::DataReceived`] have no source, call it when the peer behind them changes.
+    pub fn clear_duplicate_window(&mut self) {
+        self.non_duplicates.clear();
+    }
+
+    /// Handles `event`. Transactions leaked past their lifetime are resolved with
+    /// [`response::Error::Internal`] first, as the processor is only ever ticked.
     pub fn tick(&mut self, event: Event) -> Result {
+        let mut effects = self.evict_leaked()?;
+        effects.extend(self.on_event(event)?);
+
+        Ok(effects)
+    }
+
+    fn on_event(&mut self, event: Event) -> Result {
         match event {
             Event::TransactionRequested(request, token) => {
-                self.on_transaction_requested(request, token)
+                self.on_transaction_requested(request, token, Priority::Normal)
+            }
+            Event::PrioritizedTransactionRequested(request, token, priority) => {
+                self.on_transaction_requested(request, token, priority)
+            }
+            Event::UploadRequested(request, body, token) => {
+                self.on_upload_requested(request, body, token)
             }
-            Event::TransactionCanceled(_) => Ok(vec![]),
+            Event::TransactionCanceled(token) => self.on_transaction_canceled(token),
             Event::TimeoutReached(timeout) => self.on_timeout_reached(timeout),
-            Event::DataReceived(data) => self.on_data_received(data),
+            Event::DataReceived(data) => self.on_data_received(data, None),
+            Event::DataReceivedFrom(source, data) => self.on_data_received(data, Some(source)),
+            Event::TruncatedDataReceived(_) => {
+                self.truncated_datagrams += 1;
+                Ok(smallvec![])
+            }
+            Event::EmptyMessageRequested(empty_message) => {
+                Ok(smallvec![Effect::Transmit(empty_message.encode())])
+            }
+            Event::TransmitFailed(data, kind) => self.on_transmit_failed(&data, kind),
+            Event::TransportFailed(kind) => self.on_transport_failed(kind),
+        }
+    }
+
+    fn evict_leaked(&mut self) -> Result {
+        if self.transaction_store.count() == 0 {
+            return Ok(smallvec![]);
+        }
+
+        let leaked = self.transaction_store.evict_leaked(self.clock.now());
+        if leaked.is_empty() {
+            return Ok(smallvec![]);
+        }
+
+        let mut effects = Effects::new();
+        for transaction in leaked {
+            self.message_id_store.release(transaction.message_id());
+            effects.push(Effect::TransactionResolved(
+                *transaction.token(),
+                Err(response::Error::Internal("leaked")),
+            ));
         }
+        effects.extend(self.dequeue_request()?);
+
+        Ok(effects)
     }
 
     fn at_capacity(&self) -> bool {
@@ -63,36 +360,110 @@ impl Processor {
     }
 
     fn claim_message_id(&mut self) -> std::result::Result<MessageId, Error> {
-        let Some(message_id) = self.message_id_store.claim() else {
-            return Err(Error::other("Failed to claim message id"));
-        };
+        self.message_id_store
+            .claim()
+            .ok_or(Error::MessageIdExhausted)
+    }
+
+    pub fn message_id_usage(&self) -> message_id_store::Usage {
+        self.message_id_store.usage()
+    }
+
+    fn on_message_id_exhausted(
+        &mut self,
+        request: NewRequest,
+        token: Token,
+        priority: Priority,
+    ) -> Effects {
+        match self.exhaustion_policy {
+            ExhaustionPolicy::FailFast => smallvec![Effect::TransactionResolved(
+                token,
+                Err(response::Error::MessageIdExhausted),
+            )],
+            ExhaustionPolicy::Queue => self.queue(request, token, priority),
+        }
+    }
 
-        Ok(message_id)
+    fn queue(&mut self, request: NewRequest, token: Token, priority: Priority) -> Effects {
+        match self.queued.push(priority, request, token) {
+            Ok(()) => smallvec![],
+            Err(token) => smallvec![Effect::TransactionResolved(
+                token,
+                Err(response::Error::QueueFull),
+            )],
+        }
     }
 
-    fn on_data_received(&mut self, data: Vec<u8>) -> Result {
-        let message = Message::decode(&data)
-            .map_err(|e| Error::other(format!("Failed to parse message => {e:?}")))?;
+    fn on_data_received(&mut self, data: Vec<u8>, source: Option<SocketAddr>) -> Result {
+        let message = match Message::decode(&data) {
+            Ok(message) => message,
+            Err(_) => return Ok(self.on_malformed(data)),
+        };
 
         match message {
             Message::Acknowledgement(acknowledgement) => self.on_acknowledgement(acknowledgement),
-            Message::Piggyback(piggyback) => self.on_piggyback(piggyback),
-            Message::Request(_) => Ok(vec![]),
+            Message::Piggyback(piggyback) => self.on_piggyback(piggyback, data.len()),
+            Message::Ping(ping) => Ok(smallvec![Effect::Transmit(
+                Reset::from_message_id(ping.message_id()).encode(),
+            )]),
+            Message::Request(_) => Ok(smallvec![]),
             Message::Reset(reset) => self.on_reset(reset),
-            Message::Response(response) => self.on_response(response),
-            Message::Reserved(_) => Ok(vec![]),
+            Message::Response(response) => {
+                self.on_non_duplicate_response(response, source, data.len())
+            }
+            Message::Reserved(reserved) => Ok(self.on_reserved(reserved, data)),
+        }
+    }
+
+    /// Copies of a non-confirmable response from the same source are dropped, a confirmable one is
+    /// acknowledged again by [`Processor::on_response`] if it's a copy.
+    fn on_non_duplicate_response(
+        &mut self,
+        response: codec::Response,
+        source: Option<SocketAddr>,
+        bytes_received: usize,
+    ) -> Result {
+        if response.reliability().is_non_confirmable()
+            && self
+                .non_duplicates
+                .is_duplicate(source, response.message_id(), self.clock.now())
+        {
+            self.duplicate_non_responses += 1;
+            return Ok(smallvec![]);
+        }
+
+        self.on_response(response, bytes_received)
+    }
+
+    /// Garbage from the network is no reason to stop, the datagram is counted and left at that.
+    fn on_malformed(&mut self, data: Vec<u8>) -> Effects {
+        self.malformed_datagrams += 1;
+
+        match self.malformed_policy {
+            MalformedPolicy::Drop => smallvec![],
+            MalformedPolicy::Deliver => smallvec![Effect::MalformedDatagramReceived(data)],
+        }
+    }
+
+    fn on_reserved(&mut self, reserved: Reserved, data: Vec<u8>) -> Effects {
+        match self.reserved_policy {
+            ReservedPolicy::Ignore => smallvec![],
+            ReservedPolicy::Reset => smallvec![Effect::Transmit(
+                Reset::from_message_id(reserved.message_id()).encode(),
+            )],
+            ReservedPolicy::Deliver => smallvec![Effect::UnknownMessageReceived(data)],
         }
     }
 
     fn dequeue_request(&mut self) -> Result {
         if self.at_capacity() {
-            return Ok(vec![]);
+            return Ok(smallvec![]);
         }
 
-        let Some((request, token)) = self.queued.pop_front() else {
-            return Ok(vec![]);
+        let Some((priority, request, token)) = self.queued.pop() else {
+            return Ok(smallvec![]);
         };
-        self.on_transaction_requested(request, token)
+        self.on_transaction_requested(request, token, priority)
     }
 
     fn on_timeout_reached(&mut self, timeout: Timeout) -> Result {
@@ -101,7 +472,7 @@ impl Processor {
             Timeout::Retransmission(timeout) => self.on_retransmission(timeout),
             Timeout::ExchangeLifetime(timeout) => self.on_exchange_lifetime(timeout),
             Timeout::MaxTransmitWait(timeout) => self.on_max_transmit_wait(timeout),
-            Timeout::NonRetransmission(_) => todo!(),
+            Timeout::NonRetransmission(timeout) => self.on_non_retransmission(timeout),
         }
     }
 
@@ -110,20 +481,31 @@ impl Processor {
             .transaction_store
             .find_by_message_id(timeout.message_id())
         else {
-            return Ok(vec![]);
+            return Ok(smallvec![]);
         };
 
         match transaction.on_max_transmit_wait() {
             Ok(effects) => Ok(effects),
-            Err(effects) => {
-                self.transaction_store
-                    .remove_by_message_id(timeout.message_id());
+            Err(error) => {
+                let token = transaction.token;
+                self.end_transaction_by_message_id(timeout.message_id());
 
-                Ok(effects)
+                Ok(smallvec![Effect::TransactionResolved(token, Err(error))])
             }
         }
     }
 
+    /// Removes a transaction that gave up, its message id is released by the lifetime timeout.
+    fn end_transaction_by_message_id(&mut self, message_id: &MessageId) {
+        if self
+            .transaction_store
+            .remove_by_message_id(message_id)
+            .is_some()
+        {
+            self.message_id_store.quarantine(*message_id);
+        }
+    }
+
     fn on_exchange_lifetime(&mut self, timeout: ExchangeLifetimeTimeout) -> Result {
         self.on_lifetime(*timeout.message_id())
     }
@@ -133,7 +515,7 @@ impl Processor {
     }
 
     fn on_lifetime(&mut self, message_id: MessageId) -> Result {
-        let mut effects = vec![];
+        let mut effects = smallvec![];
 
         if let Some(transaction) = self.transaction_store.remove_by_message_id(&message_id) {
             effects.push(transaction.timeout());
@@ -143,6 +525,11 @@ impl Processor {
 
         effects.extend(self.dequeue_request()?);
 
+        if self.transaction_store.count() == 0 {
+            self.transaction_store.compact();
+            self.message_id_store.compact();
+        }
+
         Ok(effects)
     }
 
@@ -151,44 +538,126 @@ impl Processor {
             .transaction_store
             .find_mut_by_message_id(timeout.message_id())
         else {
-            return Ok(vec![]);
+            return Ok(smallvec![]);
         };
 
-        match transaction.retransmit(timeout) {
-            Ok(effects) => Ok(effects),
-            Err(effects) => {
-                self.transaction_store
-                    .remove_by_message_id(timeout.message_id());
+        match transaction.retransmit(timeout, self.backoff_strategy.as_ref()) {
+            Ok(effects) => {
+                transaction.next_retransmission = next_retransmission(&effects, self.clock.now());
+                Ok(effects)
+            }
+            Err(error) => {
+                let token = transaction.token;
+                self.end_transaction_by_message_id(timeout.message_id());
+                Ok(smallvec![Effect::TransactionResolved(token, Err(error))])
+            }
+        }
+    }
+
+    /// Forgets the transaction of the token. Its message id is quarantined until the lifetime
+    /// timeout of the transaction, so a late response can't be matched to a new request.
+    fn on_transaction_canceled(&mut self, token: Token) -> Result {
+        self.uploads.remove(&token);
+        if self.queued.remove(&token) || self.observations.remove(&token).is_some() {
+            return Ok(smallvec![Effect::TransactionCanceled(token)]);
+        }
+
+        let Some(transaction) = self.transaction_store.remove_by_token(&token) else {
+            return Ok(smallvec![]);
+        };
+        self.message_id_store.quarantine(transaction.message_id());
+
+        let mut effects = smallvec![Effect::TransactionCanceled(token)];
+        effects.extend(self.dequeue_request()?);
+
+        Ok(effects)
+    }
+
+    fn on_non_retransmission(&mut self, timeout: NonRetransmissionTimeout) -> Result {
+        let Some(Transaction::NonConfirmable(transaction)) = self
+            .transaction_store
+            .find_mut_by_message_id(timeout.message_id())
+        else {
+            return Ok(smallvec![]);
+        };
+
+        match transaction.retransmit() {
+            Ok(effects) => {
+                transaction.next_retransmission = next_retransmission(&effects, self.clock.now());
                 Ok(effects)
             }
+            Err(error) => {
+                let token = transaction.token;
+                self.end_transaction_by_message_id(timeout.message_id());
+                Ok(smallvec![Effect::TransactionResolved(token, Err(error))])
+            }
         }
     }
 
-    fn on_transaction_requested(&mut self, request: NewRequest, token: Token) -> Result {
+    fn on_transaction_requested(
+        &mut self,
+        mut request: NewRequest,
+        token: Token,
+        priority: Priority,
+    ) -> Result {
         if self.transaction_store.exists_by_token(&token) {
             return Err(Error::other("Token already exists"));
         }
 
-        if self.at_capacity() {
-            self.queued.push_back((request, token));
-            return Ok(vec![]);
+        if self
+            .max_transactions
+            .is_some_and(|max_transactions| self.transaction_store.count() >= max_transactions)
+        {
+            return Ok(smallvec![Effect::TransactionResolved(
+                token,
+                Err(response::Error::TransactionLimit),
+            )]);
+        }
+
+        if self.transaction_store.at_max_inflight_capacity() {
+            return Ok(self.queue(request, token, priority));
         }
 
-        let transaction = Transaction::new(self.claim_message_id()?, token, request);
+        let message_id = match self.claim_message_id() {
+            Ok(message_id) => message_id,
+            Err(Error::MessageIdExhausted) => {
+                return Ok(self.on_message_id_exhausted(request, token, priority))
+            }
+            Err(error) => return Err(error),
+        };
+
+        for interceptor in &mut self.interceptors {
+            interceptor.before_encode(&mut request);
+        }
+        let mut transaction = Transaction::new(message_id, token, request, self.clock.now());
+
+        let size = transaction.request_data().len();
+        if size > self.max_message_size {
+            self.message_id_store.release(message_id);
+            return Ok(smallvec![Effect::TransactionResolved(
+                *transaction.token(),
+                Err(response::Error::MessageTooLarge {
+                    size,
+                    limit: self.max_message_size,
+                }),
+            )]);
+        }
 
-        let effects = transaction.initial_effects();
+        let effects = transaction.initial_effects(self.backoff_strategy.as_ref());
+        transaction.schedule_retransmission(&effects, self.clock.now());
 
         self.transaction_store.add(transaction);
 
         Ok(effects)
     }
 
-    fn on_response(&mut self, response: codec::Response) -> Result {
+    fn on_response(&mut self, response: codec::Response, bytes_received: usize) -> Result {
         let Some(transaction) = self.transaction_store.remove_by_token(&response.token()) else {
-            return Ok(vec![]);
+            return Ok(self.on_notification(response, bytes_received));
         };
+        self.message_id_store.quarantine(transaction.message_id());
 
-        let mut effects = vec![];
+        let mut effects = smallvec![];
 
         if response.reliability().is_confirmable() {
             effects.push(Effect::Transmit(
@@ -196,16 +665,250 @@ impl Processor {
             ))
         }
 
+        if let Some(request) = Self::echo_retry(&transaction, &response) {
+            effects.extend(self.on_transaction_requested(
+                request,
+                *transaction.token(),
+                Priority::High,
+            )?);
+            return Ok(effects);
+        }
+
+        match self.upload_block(&transaction, &response) {
+            Some(Ok(request)) => {
+                effects.extend(self.on_transaction_requested(
+                    request,
+                    *transaction.token(),
+                    Priority::High,
+                )?);
+                return Ok(effects);
+            }
+            Some(Err(e)) => {
+                effects.push(Effect::TransactionResolved(
+                    *transaction.token(),
+                    Err(response::Error::Body(e.kind())),
+                ));
+                return Ok(effects);
+            }
+            None => {}
+        }
+
+        let response_code = response.response_code();
+        let violation = Self::protocol_violation(&transaction, response_code);
+        if let (Some(method), ResponseCodePolicy::Reject) = (violation, self.response_code_policy) {
+            effects.push(Effect::TransactionResolved(
+                *transaction.token(),
+                Err(response::Error::ProtocolViolation {
+                    method,
+                    response_code,
+                }),
+            ));
+            return Ok(effects);
+        }
+
+        let mut response = response::Response {
+            metrics: transaction.metrics(bytes_received, self.clock.now()),
+            ..response.into()
+        };
+        if let Some(accept) = transaction.accept() {
+            response.check_accept(accept.media_type());
+        }
+        if let (Some(method), ResponseCodePolicy::Warn) = (violation, self.response_code_policy) {
+            response
+                .warnings
+                .push(response::Warning::ProtocolViolation {
+                    method,
+                    response_code,
+                });
+        }
+        for interceptor in &mut self.interceptors {
+            interceptor.after_decode(&mut response);
+        }
+        if response.continues_observation() {
+            if let Some(observe) = response.options.observe() {
+                self.observations.insert(
+                    *transaction.token(),
+                    Observation::new(observe.value(), self.clock.now()),
+                );
+            }
+        }
+
         effects.push(Effect::TransactionResolved(
-            transaction.token().clone(),
-            Ok(response.into()),
+            *transaction.token(),
+            Ok(response),
         ));
 
         Ok(effects)
     }
 
-    fn on_piggyback(&mut self, piggyback: Piggyback) -> Result {
-        self.on_response(piggyback.into())
+    /// Response to no transaction, a notification if it's for an observation. Notifications
+    /// overtaken by a newer one are acknowledged and left out. Others that ask to be observed
+    /// further are rejected with a reset, so the server stops sending them, RFC 7641 §3.6.
+    fn on_notification(&mut self, response: codec::Response, bytes_received: usize) -> Effects {
+        let token = *response.token();
+        let Some(observation) = self.observations.get_mut(&token) else {
+            if response.reliability().is_confirmable() && response.options().observe().is_some() {
+                return smallvec![Effect::Transmit(
+                    Reset::from_message_id(response.message_id()).encode(),
+                )];
+            }
+            return smallvec![];
+        };
+
+        let mut effects = smallvec![];
+        if response.reliability().is_confirmable() {
+            effects.push(Effect::Transmit(
+                Acknowledgement::new(response.message_id()).encode(),
+            ));
+        }
+
+        let mut response = response::Response {
+            metrics: response::Metrics {
+                bytes_received,
+                ..Default::default()
+            },
+            ..response.into()
+        };
+        if let Some(observe) = response.options.observe() {
+            if !observation.accept(observe.value(), self.clock.now()) {
+                return effects;
+            }
+        }
+        if !response.continues_observation() {
+            self.observations.remove(&token);
+        }
+        for interceptor in &mut self.interceptors {
+            interceptor.after_decode(&mut response);
+        }
+
+        effects.push(Effect::NotificationReceived(token, response));
+        effects
+    }
+
+    /// Method of the request when `response_code` is a success code that isn't defined for it.
+    fn protocol_violation(
+        transaction: &Transaction,
+        response_code: ResponseCode,
+    ) -> Option<MethodCode> {
+        let ResponseCode::Success(success) = response_code else {
+            return None;
+        };
+
+        transaction
+            .method()
+            .filter(|method| !success.is_allowed_for(*method))
+    }
+
+    /// Request to send again with the Echo value of a 4.01 (Unauthorized) response, as described
+    /// in RFC 9175 §2.4. Requests that already had an Echo value are not sent again, so a server
+    /// can't keep the client going back and forth.
+    fn echo_retry(transaction: &Transaction, response: &codec::Response) -> Option<NewRequest> {
+        if response.response_code() != ResponseCode::ClientError(ClientError::Unauthorized) {
+            return None;
+        }
+
+        let echo = response.options().echo()?;
+        let mut request = transaction.request()?;
+        if request.echo().is_some() {
+            return None;
+        }
+
+        request.set_echo(echo.clone());
+        Some(request)
+    }
+
+    /// Block to send next of a block-wise upload, RFC 7959 §2.5. A 2.31 (Continue) moves the
+    /// upload on and a 4.13 (Request Entity Too Large) starts it over with the smaller block size
+    /// it asks for. A 4.13 to a request that isn't uploaded yet starts the upload when the policy
+    /// allows. Any other response, or a block that can't be read, ends the upload.
+    fn upload_block(
+        &mut self,
+        transaction: &Transaction,
+        response: &codec::Response,
+    ) -> Option<io::Result<NewRequest>> {
+        let token = *transaction.token();
+        let options = response.options();
+        let szx = BlockUpload::szx_hint(options);
+
+        let block = match (response.response_code(), self.uploads.get_mut(&token)) {
+            (ResponseCode::CONTINUE, Some(upload)) => upload.next(options),
+            (ResponseCode::REQUEST_ENTITY_TOO_LARGE, Some(upload)) => {
+                szx.and_then(|szx| upload.restart(szx))
+            }
+            (ResponseCode::REQUEST_ENTITY_TOO_LARGE, None)
+                if self.entity_too_large_policy == EntityTooLargePolicy::AutoBlockwise =>
+            {
+                let mut upload = BlockUpload::new(transaction.request()?, szx?)?
+                    .with_request_tag(self.request_tagging.next_tag());
+                let block = upload.start();
+                self.uploads.insert(token, upload);
+                Some(block)
+            }
+            _ => None,
+        };
+        if !matches!(block, Some(Ok(_))) {
+            self.uploads.remove(&token);
+        }
+
+        block
+    }
+
+    /// Uploads `body` block-wise right away, at the largest block size that fits in a message.
+    fn on_upload_requested(&mut self, request: NewRequest, body: Body, token: Token) -> Result {
+        let Some(upload) = BlockUpload::with_body(request, body, Block2::MAX_SZX) else {
+            return Err(Error::other(
+                "Only a POST or PUT without a Block1 option can be uploaded",
+            ));
+        };
+        let mut upload = upload.with_request_tag(self.request_tagging.next_tag());
+        upload.fit(self.max_message_size, token);
+
+        let block = match upload.start() {
+            Ok(block) => block,
+            Err(e) => {
+                return Ok(smallvec![Effect::TransactionResolved(
+                    token,
+                    Err(response::Error::Body(e.kind())),
+                )])
+            }
+        };
+
+        let effects = self.on_transaction_requested(block, token, Priority::Normal)?;
+        if !effects
+            .iter()
+            .any(|effect| matches!(effect, Effect::TransactionResolved(..)))
+        {
+            self.uploads.insert(token, upload);
+        }
+
+        Ok(effects)
+    }
+
+    /// Uploads in progress, for bodies read from a stream to be read ahead with
+    /// [`BlockUpload::prefetch`] after each tick.
+    pub fn uploads_mut(&mut self) -> impl Iterator<Item = &mut BlockUpload> {
+        self.uploads.values_mut()
+    }
+
+    /// A piggybacked response has to carry the token of the request it acknowledges. One that
+    /// doesn't is rejected with a reset instead of resolving whichever transaction has its token.
+    fn on_piggyback(&mut self, piggyback: Piggyback, bytes_received: usize) -> Result {
+        let Some(transaction) = self
+            .transaction_store
+            .find_mut_by_message_id(&piggyback.message_id())
+        else {
+            return Ok(smallvec![]);
+        };
+
+        if transaction.token() != piggyback.token() {
+            return Ok(smallvec![Effect::Transmit(
+                Reset::from_message_id(piggyback.message_id()).encode(),
+            )]);
+        }
+
+        transaction.acknowledged(self.clock.now());
+
+        self.on_response(piggyback.into(), bytes_received)
     }
 
     fn on_acknowledgement(&mut self, acknowledgement: Acknowledgement) -> Result {
@@ -213,24 +916,65 @@ impl Processor {
             .transaction_store
             .find_mut_by_message_id(&acknowledgement.message_id())
         else {
-            return Ok(vec![]);
+            return Ok(smallvec![]);
         };
 
-        transaction.acknowledged();
+        transaction.acknowledged(self.clock.now());
 
         self.dequeue_request()
     }
 
+    /// Only requests and pings have a transaction, the message id of an acknowledgement or reset
+    /// belongs to the peer.
+    fn on_transmit_failed(&mut self, data: &[u8], kind: io::ErrorKind) -> Result {
+        let Ok((_, header)) = Header::parse(data) else {
+            return Ok(smallvec![]);
+        };
+        if !matches!(
+            header.message_type(),
+            MessageType::Confirmable | MessageType::NonConfirmable
+        ) {
+            return Ok(smallvec![]);
+        }
+
+        self.fail_transactions(&[header.message_id()], kind)
+    }
+
+    fn on_transport_failed(&mut self, kind: io::ErrorKind) -> Result {
+        let message_ids = self.transaction_store.message_ids();
+        self.fail_transactions(&message_ids, kind)
+    }
+
+    fn fail_transactions(&mut self, message_ids: &[MessageId], kind: io::ErrorKind) -> Result {
+        let mut effects = smallvec![];
+
+        for message_id in message_ids {
+            let Some(transaction) = self.transaction_store.remove_by_message_id(message_id) else {
+                continue;
+            };
+            self.message_id_store.quarantine(transaction.message_id());
+
+            effects.push(Effect::TransactionResolved(
+                *transaction.token(),
+                Err(response::Error::Transport(kind)),
+            ));
+            effects.extend(self.dequeue_request()?);
+        }
+
+        Ok(effects)
+    }
+
     fn on_reset(&mut self, reset: Reset) -> Result {
         let Some(transaction) = self
             .transaction_store
             .remove_by_message_id(&reset.message_id())
         else {
-            return Ok(vec![]);
+            return Ok(smallvec![]);
         };
+        self.message_id_store.quarantine(transaction.message_id());
 
-        let mut effects = vec![Effect::TransactionResolved(
-            transaction.token().clone(),
+        let mut effects = smallvec![Effect::TransactionResolved(
+            *transaction.token(),
             Err(response::Error::Reset),
         )];
 
@@ -240,12 +984,37 @@ impl Processor {
     }
 }
 
+impl std::fmt::Display for Error {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Error::MessageIdExhausted => write!(f, "every message id is in use"),
+            Error::Other(message) => f.write_str(message),
+        }
+    }
+}
+
+impl std::error::Error for Error {}
+
 #[cfg(test)]
 mod tests {
 
-    use crate::codec::message::GetOptions;
+    use std::io;
+    use std::time::{Duration, Instant};
+
+    use crate::codec::message::{GetOptions, PostOptions, PutOptions};
     use crate::codec::Payload;
+    use crate::protocol::backoff::BackoffStrategy;
+    use crate::protocol::block_upload::BlockUpload;
+    use crate::protocol::body::Body;
+    use crate::protocol::clock::{Clock, ManualClock};
+    use crate::protocol::effect::Effects;
+    use crate::protocol::empty_message::EmptyMessage;
     use crate::protocol::get::Get;
+    use crate::protocol::interceptor::RequestInterceptor;
+    use crate::protocol::message_id_store::{ExhaustionPolicy, MessageIdState};
+    use crate::protocol::post::Post;
+    use crate::protocol::put::Put;
+    use crate::protocol::request_queue::Priority;
     use crate::protocol::timeout::{
         ExchangeLifetimeTimeout, MaxTransmitWaitTimeout, NonLifetimeTimeout,
         NonRetransmissionTimeout, RetransmissionTimeout,
@@ -260,17 +1029,31 @@ mod tests {
     use rstest::rstest;
 
     use crate::protocol::get;
+    use crate::protocol::profile::TransmissionProfile;
     use crate::protocol::reliability::Reliability;
     use crate::protocol::transaction::con::ConfirmableTransaction;
     use crate::protocol::transaction::non_con::NonConfirmableTransacation;
+    use crate::protocol::transaction::LEAK_MARGIN;
     use crate::{
         codec::{
-            code::response_code::Success, message, message_id::MessageId, token::Token,
-            Acknowledgement, Options, Response, ResponseCode,
+            code::response_code::{ClientError, Success},
+            message,
+            message_id::MessageId,
+            option::{Block1, Echo, Observe, RequestTag, Size1},
+            token::Token,
+            Acknowledgement, MediaType, MethodCode, Options, Response, ResponseCode,
         },
         protocol::{
-            effect::Effect, event::Event, message_id_store::MessageIdStore,
-            new_request::NewRequest, processor::Processor, response,
+            effect::Effect,
+            event::Event,
+            message_id_store::MessageIdStore,
+            new_request::NewRequest,
+            processor::{
+                EntityTooLargePolicy, MalformedPolicy, Processor, ReservedPolicy,
+                ResponseCodePolicy, Stats,
+            },
+            response::{self, Warning},
+            status::{State, Status},
         },
     };
 
@@ -279,6 +1062,22 @@ mod tests {
         Processor::new(message_id_store)
     }
 
+    fn without_metrics(effects: Vec<Effect>) -> Vec<Effect> {
+        effects
+            .into_iter()
+            .map(|effect| match effect {
+                Effect::TransactionResolved(token, Ok(response)) => Effect::TransactionResolved(
+                    token,
+                    Ok(response::Response {
+                        metrics: Default::default(),
+                        ..response
+                    }),
+                ),
+                effect => effect,
+            })
+            .collect()
+    }
+
     #[rstest]
     #[case(Reliability::NonConfirmable(NonConfirmableParameters::default()))]
     fn non_get_requested_without_retransmission(#[case] reliability: Reliability) {
@@ -293,9 +1092,10 @@ mod tests {
 
         let expected_message = NonConfirmableTransacation::new(
             MessageId::from_value(0),
-            token.clone(),
+            token,
             request.clone(),
             NonConfirmableParameters::default(),
+            Instant::now(),
         )
         .request_data;
 
@@ -304,7 +1104,7 @@ mod tests {
         let event = Event::TransactionRequested(request, token);
 
         // Act
-        let effects = processor.tick(event);
+        let effects = processor.tick(event).map(Effects::into_vec);
 
         // Assert
         let expected = Ok(vec![
@@ -336,16 +1136,17 @@ mod tests {
 
         let expected_message = NonConfirmableTransacation::new(
             MessageId::from_value(0),
-            token.clone(),
+            token,
             request.clone(),
             NonConfirmableParameters::default(),
+            Instant::now(),
         )
         .request_data;
 
         let event = Event::TransactionRequested(request, token);
 
         // Act
-        let effects = processor.tick(event);
+        let effects = processor.tick(event).map(Effects::into_vec);
 
         // Assert
         let expected = Ok(vec![
@@ -365,6 +1166,50 @@ mod tests {
         assert_eq!(expected, effects)
     }
 
+    #[rstest]
+    fn non_get_retransmitted_then_times_out() {
+        let mut processor = new_proccessor();
+        let parameters = NonConfirmableParameters::builder()
+            .max_retransmit(2)
+            .probing_rate_per_second(10.0)
+            .build()
+            .unwrap();
+
+        let token = Token::from_value(vec![1]).unwrap();
+        let request = NewRequest::Get(Get {
+            options: GetOptions::new(),
+            reliability: Reliability::NonConfirmable(parameters),
+        });
+        let effects = processor
+            .tick(Event::TransactionRequested(request, token))
+            .unwrap()
+            .into_vec();
+        let Some(Effect::Transmit(request_data)) = effects.last().cloned() else {
+            panic!("Expected the request to be transmitted, got {effects:?}");
+        };
+        let timeout = NonRetransmissionTimeout::new(
+            &MessageId::from_value(0),
+            request_data.len(),
+            &ProbingRatePerSecond::new(10.0),
+        );
+
+        for _ in 0..2 {
+            assert_eq!(
+                Ok(vec![timeout.into(), Effect::Transmit(request_data.clone())]),
+                processor.tick(timeout.into()).map(Effects::into_vec)
+            );
+        }
+
+        assert_eq!(
+            Ok(vec![Effect::TransactionResolved(
+                token,
+                Err(response::Error::Timeout)
+            )]),
+            processor.tick(timeout.into()).map(Effects::into_vec)
+        );
+        assert!(!processor.transaction_store.exists_by_token(&token));
+    }
+
     #[rstest]
     fn con_get_requested() {
         // Arrange
@@ -381,9 +1226,10 @@ mod tests {
 
         let transaction = ConfirmableTransaction::new(
             MessageId::from_value(0),
-            token.clone(),
+            token,
             request.clone(),
             ConfirmableParameters::default(InitialRetransmissionFactor::new(0.5).unwrap()),
+            Instant::now(),
         );
 
         let expected_message = transaction.clone().request_data;
@@ -391,7 +1237,7 @@ mod tests {
         let event = Event::TransactionRequested(request, token);
 
         // Act
-        let effects = processor.tick(event);
+        let effects = processor.tick(event).map(Effects::into_vec);
 
         // Assert
         let expected = Ok(vec![
@@ -425,14 +1271,16 @@ mod tests {
             reliability,
         });
 
-        let event = Event::TransactionRequested(request, token.clone());
+        let event = Event::TransactionRequested(request, token);
         processor.tick(event).unwrap();
 
         let response_message = Acknowledgement::new(message_id);
 
         // Act
         let response_bytes = response_message.encode();
-        let effects = processor.tick(Event::DataReceived(response_bytes));
+        let effects = processor
+            .tick(Event::DataReceived(response_bytes))
+            .map(Effects::into_vec);
 
         // Assert
         let transcation = processor.transaction_store.find_by_token(&token).unwrap();
@@ -462,7 +1310,7 @@ mod tests {
             let Event::TransactionRequested(_, token) = &event else {
                 panic!("Should be requested")
             };
-            token.clone()
+            *token
         };
 
         processor.tick(event).unwrap();
@@ -471,11 +1319,12 @@ mod tests {
 
         let _effects = processor
             .tick(Event::DataReceived(acknowledge_message.encode()))
-            .unwrap();
+            .unwrap()
+            .into_vec();
 
         let response_message = Response::new(
             message::Reliability::Confirmable,
-            token.clone(),
+            token,
             ResponseCode::Success(Success::Content),
             MessageId::from_value(1234),
             Options::new(),
@@ -488,11 +1337,14 @@ mod tests {
         let _payload = response_message.payload().clone().encode();
 
         let expected_response = response_message.clone();
-        let effects = processor
-            .tick(Event::DataReceived(response_message.encode()))
-            .unwrap();
-
-        // Act
+        let effects = without_metrics(
+            processor
+                .tick(Event::DataReceived(response_message.encode()))
+                .unwrap()
+                .into_vec(),
+        );
+
+        // Act
         assert_eq!(
             vec![
                 Effect::Transmit(Acknowledgement::new(message_id).encode()),
@@ -516,8 +1368,8 @@ mod tests {
             )),
         });
 
-        let event = Event::TransactionRequested(request.clone(), token.clone());
-        let effects = processor.tick(event).unwrap();
+        let event = Event::TransactionRequested(request.clone(), token);
+        let effects = processor.tick(event).unwrap().into_vec();
 
         let retransmission_timeout = RetransmissionTimeout::new(
             0.into(),
@@ -532,66 +1384,81 @@ mod tests {
                 )
                 .into(),
                 retransmission_timeout.into(),
-                Effect::Transmit(request.clone().encode(0.into(), token.clone()))
+                Effect::Transmit(request.clone().encode(0.into(), token))
             ],
             effects
         );
 
         // second transmission
 
-        let effects = processor.tick(retransmission_timeout.into()).unwrap();
+        let effects = processor
+            .tick(retransmission_timeout.into())
+            .unwrap()
+            .into_vec();
         let retransmission_timeout = retransmission_timeout.next();
 
         assert_eq!(
             vec![
                 retransmission_timeout.into(),
-                Effect::Transmit(request.clone().encode(0.into(), token.clone()))
+                Effect::Transmit(request.clone().encode(0.into(), token))
             ],
             effects
         );
 
         // third transmission
 
-        let effects = processor.tick(retransmission_timeout.into()).unwrap();
+        let effects = processor
+            .tick(retransmission_timeout.into())
+            .unwrap()
+            .into_vec();
         let retransmission_timeout = retransmission_timeout.next();
 
         assert_eq!(
             vec![
                 retransmission_timeout.into(),
-                Effect::Transmit(request.clone().encode(0.into(), token.clone()))
+                Effect::Transmit(request.clone().encode(0.into(), token))
             ],
             effects
         );
 
         // fourth transmission
 
-        let effects = processor.tick(retransmission_timeout.into()).unwrap();
+        let effects = processor
+            .tick(retransmission_timeout.into())
+            .unwrap()
+            .into_vec();
         let retransmission_timeout = retransmission_timeout.next();
 
         assert_eq!(
             vec![
                 retransmission_timeout.into(),
-                Effect::Transmit(request.clone().encode(0.into(), token.clone()))
+                Effect::Transmit(request.clone().encode(0.into(), token))
             ],
             effects
         );
 
         // fifth transmission
 
-        let effects = processor.tick(retransmission_timeout.into()).unwrap();
-        let retransmission_timeout = retransmission_timeout.clone().next();
+        let effects = processor
+            .tick(retransmission_timeout.into())
+            .unwrap()
+            .into_vec();
+        let retransmission_timeout = retransmission_timeout.next();
 
         assert_eq!(
             vec![
                 retransmission_timeout.into(),
-                Effect::Transmit(request.clone().encode(0.into(), token.clone()))
+                Effect::Transmit(request.clone().encode(0.into(), token))
             ],
             effects
         );
 
         // attempt transmission but timeout due to `MAX_RETRANSMIT` reached
 
-        let effects = processor.tick(retransmission_timeout.into()).unwrap();
+        let effects = processor
+            .tick(retransmission_timeout.into())
+            .unwrap()
+            .into_vec();
 
         assert_eq!(
             vec![Effect::TransactionResolved(
@@ -618,14 +1485,14 @@ mod tests {
             )),
         });
 
-        let event = Event::TransactionRequested(request.clone(), token.clone());
-        let effects = processor.tick(event).unwrap();
+        let event = Event::TransactionRequested(request.clone(), token);
+        let effects = processor.tick(event).unwrap().into_vec();
 
         assert_eq!(
             vec![
                 ExchangeLifetimeTimeout::new(0.into(), &confirmable_parameters).into(),
                 RetransmissionTimeout::new(0.into(), &confirmable_parameters).into(),
-                Effect::Transmit(request.clone().encode(0.into(), token.clone()))
+                Effect::Transmit(request.clone().encode(0.into(), token))
             ],
             effects
         );
@@ -634,7 +1501,7 @@ mod tests {
 
         let reset = Reset::from_message_id(0.into());
         let event = Event::DataReceived(reset.encode());
-        let effects = processor.tick(event).unwrap();
+        let effects = processor.tick(event).unwrap().into_vec();
 
         assert_eq!(
             vec![Effect::TransactionResolved(
@@ -646,10 +1513,9 @@ mod tests {
     }
 
     #[rstest]
-    fn confirmable_message_sent_then_receives_acknowledgement() {
+    fn transmit_failed() {
         let mut processor = new_proccessor();
 
-        let message_id = MessageId::from_value(0);
         let token = Token::new().unwrap();
         let request = NewRequest::Get(Get {
             options: GetOptions::new(),
@@ -657,27 +1523,32 @@ mod tests {
                 InitialRetransmissionFactor::new(0.5).unwrap(),
             )),
         });
+        processor
+            .tick(Event::TransactionRequested(request.clone(), token))
+            .unwrap();
 
-        let event = Event::TransactionRequested(request.clone(), token.clone());
-        processor.tick(event).unwrap();
-
-        let transaction = processor.transaction_store.find_by_token(&token).unwrap();
-        assert_eq!(false, transaction.is_acknowledged());
-
-        let acknowledgement = Acknowledgement::new(message_id);
-        let event = Event::DataReceived(acknowledgement.encode());
-        let effects = processor.tick(event).unwrap();
+        let event = Event::TransmitFailed(
+            request.encode(0.into(), token),
+            io::ErrorKind::NetworkUnreachable,
+        );
+        let effects = processor.tick(event).unwrap().into_vec();
 
-        let transaction = processor.transaction_store.find_by_token(&token).unwrap();
-        assert_eq!(Vec::<Effect>::new(), effects);
-        assert_eq!(true, transaction.is_acknowledged());
+        assert_eq!(
+            vec![Effect::TransactionResolved(
+                token,
+                Err(response::Error::Transport(
+                    io::ErrorKind::NetworkUnreachable
+                ))
+            )],
+            effects
+        );
+        assert_eq!(0, processor.stats().in_flight);
     }
 
     #[rstest]
-    fn confirmable_message_acknowledged_then_receives_response() {
+    fn transmit_failed_for_acknowledgement_is_ignored() {
         let mut processor = new_proccessor();
 
-        let message_id = MessageId::from_value(0);
         let token = Token::new().unwrap();
         let request = NewRequest::Get(Get {
             options: GetOptions::new(),
@@ -685,136 +1556,103 @@ mod tests {
                 InitialRetransmissionFactor::new(0.5).unwrap(),
             )),
         });
+        processor
+            .tick(Event::TransactionRequested(request, token))
+            .unwrap();
 
-        let event = Event::TransactionRequested(request.clone(), token.clone());
-        processor.tick(event).unwrap();
-
-        let acknowledgement = Acknowledgement::new(message_id);
-        let event = Event::DataReceived(acknowledgement.encode());
-        processor.tick(event).unwrap();
-
-        let response = Response::new(
-            message::Reliability::Confirmable,
-            token.clone(),
-            ResponseCode::Success(Success::Content),
-            MessageId::from_value(5),
-            Options::new(),
-            Payload::empty(),
+        let event = Event::TransmitFailed(
+            Acknowledgement::new(0.into()).encode(),
+            io::ErrorKind::NetworkUnreachable,
         );
-        let event = Event::DataReceived(response.clone().encode());
-        let effects = processor.tick(event).unwrap();
 
-        let response = self::response::Response {
-            options: Options::new(),
-            response_code: response.response_code(),
-            payload: response.payload().clone(),
-        };
-        let acknowledgement = Acknowledgement::new(MessageId::from_value(5));
-        let expected_effects = vec![
-            Effect::Transmit(acknowledgement.encode()),
-            Effect::TransactionResolved(token, Ok(response)),
-        ];
-        assert_eq!(0, processor.transaction_store.count());
-        assert_eq!(expected_effects, effects);
+        assert_eq!(Ok(vec![]), processor.tick(event).map(Effects::into_vec));
+        assert_eq!(1, processor.stats().in_flight);
     }
 
     #[rstest]
-    fn confirmable_message_sent_then_receives_reset() {
+    fn transport_failed_resolves_transactions() {
         let mut processor = new_proccessor();
 
-        let message_id = MessageId::from_value(0);
-        let token = Token::new().unwrap();
-        let request = NewRequest::Get(Get {
-            options: GetOptions::new(),
-            reliability: Reliability::Confirmable(ConfirmableParameters::default(
-                InitialRetransmissionFactor::new(0.5).unwrap(),
-            )),
-        });
-
-        let event = Event::TransactionRequested(request.clone(), token.clone());
-        processor.tick(event).unwrap();
+        let tokens = [Token::new().unwrap(), Token::new().unwrap()];
+        for token in tokens {
+            let request = NewRequest::Get(Get {
+                options: GetOptions::new(),
+                reliability: Reliability::NonConfirmable(NonConfirmableParameters::default()),
+            });
+            processor
+                .tick(Event::TransactionRequested(request, token))
+                .unwrap();
+        }
 
-        let reset = Reset::from_message_id(message_id);
-        let event = Event::DataReceived(reset.encode());
-        let effects = processor.tick(event).unwrap();
-        let expected_effects = vec![Effect::TransactionResolved(
-            token,
-            Err(response::Error::Reset),
-        )];
-        assert_eq!(expected_effects, effects);
+        // The queued request takes the place of the failed one, until the transport fails again
+        for token in tokens {
+            let resolved = processor
+                .tick(Event::TransportFailed(io::ErrorKind::ConnectionRefused))
+                .unwrap()
+                .into_vec()
+                .into_iter()
+                .filter(|effect| matches!(effect, Effect::TransactionResolved(..)))
+                .collect::<Vec<_>>();
+
+            assert_eq!(
+                vec![Effect::TransactionResolved(
+                    token,
+                    Err(response::Error::Transport(io::ErrorKind::ConnectionRefused))
+                )],
+                resolved
+            );
+        }
+        assert_eq!(0, processor.stats().in_flight);
     }
 
     #[rstest]
-    fn confirmable_message_sent_then_receives_piggyback_response() {
-        let mut processor = new_proccessor();
-
-        let message_id = MessageId::from_value(0);
-        let token = Token::new().unwrap();
+    #[case(ExhaustionPolicy::FailFast, vec![Effect::TransactionResolved(Token::from_value(vec![1]).unwrap(), Err(response::Error::MessageIdExhausted))], 0)]
+    #[case(ExhaustionPolicy::Queue, vec![], 1)]
+    fn message_id_exhausted(
+        #[case] exhaustion_policy: ExhaustionPolicy,
+        #[case] expected: Vec<Effect>,
+        #[case] expected_queued: usize,
+    ) {
+        let mut processor =
+            Processor::new(MessageIdStore::exhausted()).with_exhaustion_policy(exhaustion_policy);
         let request = NewRequest::Get(Get {
             options: GetOptions::new(),
-            reliability: Reliability::Confirmable(ConfirmableParameters::default(
-                InitialRetransmissionFactor::new(0.5).unwrap(),
-            )),
+            reliability: Reliability::NonConfirmable(NonConfirmableParameters::default()),
         });
 
-        let event = Event::TransactionRequested(request.clone(), token.clone());
-        processor.tick(event).unwrap();
+        let event = Event::TransactionRequested(request, Token::from_value(vec![1]).unwrap());
 
-        let piggyback = Piggyback::new(
-            token.clone(),
-            ResponseCode::Success(Success::Content),
-            message_id,
-            Options::new(),
-            Payload::empty(),
-        );
-        let event = Event::DataReceived(piggyback.encode());
-        let effects = processor.tick(event).unwrap();
-        let response = self::response::Response {
-            response_code: ResponseCode::Success(Success::Content),
-            options: Options::new(),
-            payload: Payload::empty(),
-        };
-        let expected_effects = vec![Effect::TransactionResolved(token, Ok(response))];
-        assert_eq!(0, processor.transaction_store.count());
-        assert_eq!(expected_effects, effects);
+        assert_eq!(Ok(expected), processor.tick(event).map(Effects::into_vec));
+        assert_eq!(expected_queued, processor.queued.len());
+        assert_eq!(1, processor.message_id_usage().exhaustions);
     }
 
     #[rstest]
-    fn confirmable_message_sent_then_is_timed_out_based_on_max_transmit_wait() {
-        let mut processor = new_proccessor();
-
-        let message_id = MessageId::from_value(0);
-        let token = Token::new().unwrap();
+    fn queue_full() {
+        let mut processor = Processor::new(MessageIdStore::exhausted()).with_max_queue_depth(1);
         let request = NewRequest::Get(Get {
             options: GetOptions::new(),
-            reliability: Reliability::Confirmable(ConfirmableParameters::default(
-                InitialRetransmissionFactor::new(0.5).unwrap(),
-            )),
+            reliability: Reliability::NonConfirmable(NonConfirmableParameters::default()),
         });
 
-        let event = Event::TransactionRequested(request.clone(), token.clone());
-        processor.tick(event).unwrap();
+        let event =
+            Event::TransactionRequested(request.clone(), Token::from_value(vec![1]).unwrap());
+        assert_eq!(Ok(vec![]), processor.tick(event).map(Effects::into_vec));
 
-        let event = MaxTransmitWaitTimeout::new(
-            &message_id,
-            &ConfirmableParameters::default(InitialRetransmissionFactor::new(0.5).unwrap()),
-        )
-        .into();
-        let effects = processor.tick(event).unwrap();
-        let expected_effects = vec![Effect::TransactionResolved(
-            token,
-            Err(response::Error::Timeout),
-        )];
-        assert_eq!(0, processor.transaction_store.count());
-        assert_eq!(expected_effects, effects);
+        let event = Event::TransactionRequested(request, Token::from_value(vec![2]).unwrap());
+        assert_eq!(
+            Ok(vec![Effect::TransactionResolved(
+                Token::from_value(vec![2]).unwrap(),
+                Err(response::Error::QueueFull)
+            )]),
+            processor.tick(event).map(Effects::into_vec)
+        );
+        assert_eq!(1, processor.queued.len());
     }
 
     #[rstest]
-    fn confirmable_message_acknowledged_then_is_timed_out_based_on_max_transmit_wait() {
-        let mut processor = new_proccessor();
-
-        let message_id = MessageId::from_value(0);
-        let token = Token::new().unwrap();
+    fn stats_and_transaction_limit() {
+        let mut processor = new_proccessor().with_max_transactions(1);
         let request = NewRequest::Get(Get {
             options: GetOptions::new(),
             reliability: Reliability::Confirmable(ConfirmableParameters::default(
@@ -822,149 +1660,837 @@ mod tests {
             )),
         });
 
-        let event = Event::TransactionRequested(request.clone(), token.clone());
+        let event =
+            Event::TransactionRequested(request.clone(), Token::from_value(vec![1]).unwrap());
         processor.tick(event).unwrap();
 
-        let event = Event::DataReceived(Acknowledgement::new(message_id).encode());
-        processor.tick(event).unwrap();
+        let event = Event::TransactionRequested(request, Token::from_value(vec![2]).unwrap());
         assert_eq!(
-            true,
-            processor
-                .transaction_store
-                .find_by_token(&token)
-                .unwrap()
-                .is_acknowledged()
+            Ok(vec![Effect::TransactionResolved(
+                Token::from_value(vec![2]).unwrap(),
+                Err(response::Error::TransactionLimit)
+            )]),
+            processor.tick(event).map(Effects::into_vec)
+        );
+        assert_eq!(
+            Stats {
+                claimed_message_ids: 1,
+                duplicate_non_responses: 0,
+                in_flight: 1,
+                malformed_datagrams: 0,
+                queued: 0,
+                truncated_datagrams: 0,
+            },
+            processor.stats()
         );
-
-        let event = MaxTransmitWaitTimeout::new(
-            &message_id,
-            &ConfirmableParameters::default(InitialRetransmissionFactor::new(0.5).unwrap()),
-        )
-        .into();
-        let effects = processor.tick(event).unwrap();
-        assert_eq!(1, processor.transaction_store.count());
-        assert_eq!(Vec::<Effect>::new(), effects);
     }
 
     #[rstest]
-    fn confirmable_message_sent_then_is_timed_out_based_on_exchange_lifetime() {
+    fn queued_requests_are_sent_by_priority() {
         let mut processor = new_proccessor();
-
-        let message_id = MessageId::from_value(0);
-        let token = Token::new().unwrap();
         let request = NewRequest::Get(Get {
             options: GetOptions::new(),
-            reliability: Reliability::Confirmable(ConfirmableParameters::default(
-                InitialRetransmissionFactor::new(0.5).unwrap(),
-            )),
+            reliability: Reliability::NonConfirmable(NonConfirmableParameters::default()),
         });
+        let low = Token::from_value(vec![2]).unwrap();
+        let high = Token::from_value(vec![3]).unwrap();
 
-        let event = Event::TransactionRequested(request.clone(), token.clone());
-        processor.tick(event).unwrap();
-
-        let event = Event::TimeoutReached(
-            ExchangeLifetimeTimeout::new(
-                message_id,
-                &ConfirmableParameters::default(InitialRetransmissionFactor::new(0.0).unwrap()),
+        let events = [
+            Event::TransactionRequested(request.clone(), Token::from_value(vec![1]).unwrap()),
+            Event::PrioritizedTransactionRequested(request.clone(), low, Priority::Low),
+            Event::PrioritizedTransactionRequested(request, high, Priority::High),
+            NonLifetimeTimeout::new(
+                &MessageId::from_value(0),
+                &NonConfirmableParameters::default(),
             )
             .into(),
-        );
-        let effects = processor.tick(event).unwrap();
-        let expected_effects = vec![Effect::TransactionResolved(
-            token,
-            Err(response::Error::Timeout),
-        )];
-        assert_eq!(0, processor.transaction_store.count());
-        assert_eq!(expected_effects, effects);
+        ];
+        for event in events {
+            processor.tick(event).unwrap();
+        }
+
+        assert!(processor.transaction_store.exists_by_token(&high));
+        assert!(!processor.transaction_store.exists_by_token(&low));
+        assert_eq!(1, processor.queued.len());
     }
 
     #[rstest]
-    fn confirmable_message_acknowledged_then_is_timed_out_based_on_exchange_lifetime() {
-        let mut processor = new_proccessor();
+    fn metrics_measured_with_clock() {
+        let clock = ManualClock::default();
+        let mut processor = new_proccessor().with_clock(clock.clone());
 
-        let message_id = MessageId::from_value(0);
-        let token = Token::new().unwrap();
+        let token = Token::from_value(vec![1]).unwrap();
         let request = NewRequest::Get(Get {
             options: GetOptions::new(),
             reliability: Reliability::Confirmable(ConfirmableParameters::default(
                 InitialRetransmissionFactor::new(0.5).unwrap(),
             )),
         });
+        processor
+            .tick(Event::TransactionRequested(request, token))
+            .unwrap();
 
-        let event = Event::TransactionRequested(request.clone(), token.clone());
-        processor.tick(event).unwrap();
+        clock.advance(Duration::from_secs(2));
+        processor
+            .tick(Event::DataReceived(
+                Acknowledgement::new(MessageId::from_value(0)).encode(),
+            ))
+            .unwrap();
 
-        let event = Event::DataReceived(Acknowledgement::new(message_id).encode());
-        processor.tick(event).unwrap();
-        assert_eq!(
-            true,
-            processor
-                .transaction_store
-                .find_by_token(&token)
-                .unwrap()
-                .is_acknowledged()
+        clock.advance(Duration::from_secs(3));
+        let response = Response::new(
+            message::Reliability::NonConfirmable,
+            token,
+            ResponseCode::Success(Success::Content),
+            MessageId::from_value(1),
+            Options::new(),
+            Payload::empty(),
         );
+        let effects = processor
+            .tick(Event::DataReceived(response.encode()))
+            .unwrap()
+            .into_vec();
 
-        let event = Event::TimeoutReached(
-            ExchangeLifetimeTimeout::new(
-                message_id,
-                &ConfirmableParameters::default(InitialRetransmissionFactor::new(0.0).unwrap()),
-            )
-            .into(),
+        let [Effect::TransactionResolved(_, Ok(response))] = effects.as_slice() else {
+            panic!("Expected a resolved transaction, got {effects:?}");
+        };
+        assert_eq!(Duration::from_secs(5), response.metrics.round_trip_time);
+        assert_eq!(
+            Some(Duration::from_secs(2)),
+            response.metrics.time_to_acknowledgement
         );
-        let effects = processor.tick(event).unwrap();
-        let expected_effects = vec![Effect::TransactionResolved(
-            token,
-            Err(response::Error::Timeout),
-        )];
-        assert_eq!(0, processor.transaction_store.count());
-        assert_eq!(expected_effects, effects);
     }
 
     #[rstest]
-    fn confirmable_message_sent_then_is_timed_out_based_on_transmission_counter() {
-        let mut processor = new_proccessor();
+    fn status_follows_transaction() {
+        let clock = ManualClock::default();
+        let mut processor = new_proccessor().with_clock(clock.clone());
+        let parameters =
+            ConfirmableParameters::default(InitialRetransmissionFactor::new(0.5).unwrap());
+        let token = Token::from_value(vec![1]).unwrap();
+        let retransmission_timeout = RetransmissionTimeout::new(0.into(), &parameters);
+        processor
+            .tick(Event::TransactionRequested(
+                NewRequest::Get(Get {
+                    options: GetOptions::new(),
+                    reliability: Reliability::Confirmable(parameters),
+                }),
+                token,
+            ))
+            .unwrap();
 
-        let message_id = MessageId::from_value(0);
-        let token = Token::new().unwrap();
-        let request = NewRequest::Get(Get {
-            options: GetOptions::new(),
-            reliability: Reliability::Confirmable(ConfirmableParameters::default(
-                InitialRetransmissionFactor::new(0.5).unwrap(),
-            )),
-        });
+        assert_eq!(
+            Some(Status {
+                state: State::Transmitted { attempt: 1 },
+                next_retransmission: Some(clock.now() + *retransmission_timeout.timeout()),
+            }),
+            processor.status(&token)
+        );
 
-        let event = Event::TransactionRequested(request.clone(), token.clone());
-        processor.tick(event).unwrap();
+        clock.advance(*retransmission_timeout.timeout());
+        processor
+            .tick(Event::TimeoutReached(retransmission_timeout.into()))
+            .unwrap();
 
-        let confirmable_parameters =
-            ConfirmableParameters::default(InitialRetransmissionFactor::new(0.0).unwrap());
-        let initial_retransmission_timeout =
-            RetransmissionTimeout::new(message_id, &confirmable_parameters);
+        assert_eq!(
+            Some(Status {
+                state: State::Transmitted { attempt: 2 },
+                next_retransmission: Some(clock.now() + *retransmission_timeout.timeout() * 2),
+            }),
+            processor.status(&token)
+        );
 
-        let event = Event::TimeoutReached(initial_retransmission_timeout.clone().into());
-        processor.tick(event).unwrap();
+        processor
+            .tick(Event::DataReceived(
+                Acknowledgement::new(MessageId::from_value(0)).encode(),
+            ))
+            .unwrap();
 
-        let next_retranmission_timeout =
-            RetransmissionTimeout::from_previous(initial_retransmission_timeout);
-        let event = Event::TimeoutReached(next_retranmission_timeout.clone().into());
-        processor.tick(event).unwrap();
+        assert_eq!(
+            Some(Status {
+                state: State::Acknowledged,
+                next_retransmission: None,
+            }),
+            processor.status(&token)
+        );
 
-        let next_retranmission_timeout =
-            RetransmissionTimeout::from_previous(next_retranmission_timeout);
-        let event = Event::TimeoutReached(next_retranmission_timeout.clone().into());
-        processor.tick(event).unwrap();
+        processor.tick(Event::TransactionCanceled(token)).unwrap();
 
-        let next_retranmission_timeout =
-            RetransmissionTimeout::from_previous(next_retranmission_timeout);
-        let event = Event::TimeoutReached(next_retranmission_timeout.clone().into());
-        processor.tick(event).unwrap();
+        assert_eq!(None, processor.status(&token));
+    }
 
-        let next_retranmission_timeout =
-            RetransmissionTimeout::from_previous(next_retranmission_timeout);
-        let event = Event::TimeoutReached(next_retranmission_timeout.clone().into());
-        let effects = processor.tick(event).unwrap();
-        let expected_effects = vec![Effect::TransactionResolved(
+    #[rstest]
+    fn leaked_transaction_is_evicted() {
+        let clock = ManualClock::default();
+        let mut processor = new_proccessor().with_clock(clock.clone());
+        let token = Token::from_value(vec![1]).unwrap();
+        processor
+            .tick(Event::TransactionRequested(
+                NewRequest::Get(Get {
+                    options: GetOptions::new(),
+                    reliability: Reliability::NonConfirmable(NonConfirmableParameters::default()),
+                }),
+                token,
+            ))
+            .unwrap();
+
+        clock.advance(
+            TransmissionProfile::default().non_lifetime() + LEAK_MARGIN + Duration::from_secs(1),
+        );
+        let effects = processor
+            .tick(Event::TransactionCanceled(
+                Token::from_value(vec![2]).unwrap(),
+            ))
+            .unwrap()
+            .into_vec();
+
+        assert_eq!(
+            vec![Effect::TransactionResolved(
+                token,
+                Err(response::Error::Internal("leaked"))
+            )],
+            effects
+        );
+        assert_eq!(0, processor.stats().in_flight);
+        assert_eq!(0, processor.stats().claimed_message_ids);
+    }
+
+    #[rstest]
+    fn status_of_queued_request() {
+        let mut processor = Processor::new(MessageIdStore::exhausted())
+            .with_exhaustion_policy(ExhaustionPolicy::Queue);
+        let token = Token::from_value(vec![1]).unwrap();
+        processor
+            .tick(Event::TransactionRequested(
+                NewRequest::Get(Get {
+                    options: GetOptions::new(),
+                    reliability: Reliability::NonConfirmable(NonConfirmableParameters::default()),
+                }),
+                token,
+            ))
+            .unwrap();
+
+        assert_eq!(Some(Status::queued()), processor.status(&token));
+    }
+
+    fn notification(token: Token, message_id: u16, observe: Option<u32>) -> Vec<u8> {
+        let mut options = Options::new();
+        if let Some(observe) = observe {
+            options.set_observe(Observe::from_value(observe));
+        }
+
+        Response::new(
+            message::Reliability::Confirmable,
+            token,
+            ResponseCode::Success(Success::Content),
+            MessageId::from_value(message_id),
+            options,
+            Payload::empty(),
+        )
+        .encode()
+    }
+
+    fn notified(effects: Vec<Effect>) -> Vec<Effect> {
+        effects
+            .into_iter()
+            .filter(|effect| matches!(effect, Effect::NotificationReceived(..)))
+            .collect()
+    }
+
+    #[rstest]
+    fn observation_delivers_newer_notifications() {
+        let mut processor = new_proccessor();
+        let token = Token::from_value(vec![1]).unwrap();
+        let mut options = GetOptions::new();
+        options.set_observe(Observe::register());
+        processor
+            .tick(Event::TransactionRequested(
+                NewRequest::Get(Get {
+                    options,
+                    reliability: Reliability::NonConfirmable(NonConfirmableParameters::default()),
+                }),
+                token,
+            ))
+            .unwrap();
+        processor
+            .tick(Event::DataReceived(notification(token, 10, Some(5))))
+            .unwrap();
+
+        let effects = processor
+            .tick(Event::DataReceived(notification(token, 11, Some(7))))
+            .unwrap()
+            .into_vec();
+        assert_eq!(
+            Effect::Transmit(Acknowledgement::new(MessageId::from_value(11)).encode()),
+            effects[0]
+        );
+        assert_eq!(1, notified(effects).len());
+
+        let effects = processor
+            .tick(Event::DataReceived(notification(token, 12, Some(6))))
+            .unwrap()
+            .into_vec();
+        assert_eq!(
+            vec![Effect::Transmit(
+                Acknowledgement::new(MessageId::from_value(12)).encode()
+            )],
+            effects
+        );
+
+        let effects = processor
+            .tick(Event::DataReceived(notification(token, 13, None)))
+            .unwrap()
+            .into_vec();
+        assert!(matches!(
+            &notified(effects)[..],
+            [Effect::NotificationReceived(notified_token, response)]
+                if *notified_token == token && !response.continues_observation()
+        ));
+
+        assert_eq!(
+            Ok(vec![Effect::Transmit(
+                Reset::from_message_id(MessageId::from_value(14)).encode()
+            )]),
+            processor
+                .tick(Event::DataReceived(notification(token, 14, Some(8))))
+                .map(Effects::into_vec)
+        );
+    }
+
+    #[rstest]
+    fn observation_canceled() {
+        let mut processor = new_proccessor();
+        let token = Token::from_value(vec![1]).unwrap();
+        processor
+            .tick(Event::TransactionRequested(
+                NewRequest::Get(Get {
+                    options: GetOptions::new(),
+                    reliability: Reliability::NonConfirmable(NonConfirmableParameters::default()),
+                }),
+                token,
+            ))
+            .unwrap();
+        processor
+            .tick(Event::DataReceived(notification(token, 10, Some(5))))
+            .unwrap();
+
+        assert_eq!(
+            Ok(vec![Effect::TransactionCanceled(token)]),
+            processor
+                .tick(Event::TransactionCanceled(token))
+                .map(Effects::into_vec)
+        );
+        assert_eq!(
+            Ok(vec![Effect::Transmit(
+                Reset::from_message_id(MessageId::from_value(11)).encode()
+            )]),
+            processor
+                .tick(Event::DataReceived(notification(token, 11, Some(6))))
+                .map(Effects::into_vec)
+        );
+    }
+
+    #[rstest]
+    fn transaction_canceled() {
+        let mut processor = new_proccessor();
+        let request = NewRequest::Get(Get {
+            options: GetOptions::new(),
+            reliability: Reliability::NonConfirmable(NonConfirmableParameters::default()),
+        });
+        let in_flight = Token::from_value(vec![1]).unwrap();
+        let queued = Token::from_value(vec![2]).unwrap();
+
+        processor
+            .tick(Event::TransactionRequested(request.clone(), in_flight))
+            .unwrap();
+        processor
+            .tick(Event::TransactionRequested(request, queued))
+            .unwrap();
+
+        assert_eq!(
+            Ok(vec![Effect::TransactionCanceled(queued)]),
+            processor
+                .tick(Event::TransactionCanceled(queued))
+                .map(Effects::into_vec)
+        );
+        assert_eq!(
+            Ok(vec![Effect::TransactionCanceled(in_flight)]),
+            processor
+                .tick(Event::TransactionCanceled(in_flight))
+                .map(Effects::into_vec)
+        );
+        assert_eq!(
+            Ok(vec![]),
+            processor
+                .tick(Event::TransactionCanceled(in_flight))
+                .map(Effects::into_vec)
+        );
+        assert_eq!(
+            Stats {
+                claimed_message_ids: 1,
+                duplicate_non_responses: 0,
+                in_flight: 0,
+                malformed_datagrams: 0,
+                queued: 0,
+                truncated_datagrams: 0,
+            },
+            processor.stats()
+        );
+    }
+
+    #[rstest]
+    fn ping_received() {
+        let mut processor = new_proccessor();
+
+        let ping = message::Ping::from_message_id(MessageId::from_value(7));
+        let effects = processor
+            .tick(Event::DataReceived(ping.encode()))
+            .unwrap()
+            .into_vec();
+
+        assert_eq!(
+            vec![Effect::Transmit(
+                Reset::from_message_id(MessageId::from_value(7)).encode()
+            )],
+            effects
+        );
+    }
+
+    #[rstest]
+    #[case(ReservedPolicy::Ignore, vec![])]
+    #[case(
+        ReservedPolicy::Reset,
+        vec![Effect::Transmit(Reset::from_message_id(MessageId::from_value(7)).encode())]
+    )]
+    #[case(
+        ReservedPolicy::Deliver,
+        vec![Effect::UnknownMessageReceived(vec![0x40, 0xe0, 0x00, 0x07])]
+    )]
+    fn reserved_received(#[case] policy: ReservedPolicy, #[case] expected: Vec<Effect>) {
+        let mut processor = new_proccessor().with_reserved_policy(policy);
+
+        let effects = processor
+            .tick(Event::DataReceived(vec![0x40, 0xe0, 0x00, 0x07]))
+            .unwrap()
+            .into_vec();
+
+        assert_eq!(expected, effects);
+    }
+
+    #[rstest]
+    #[case(MalformedPolicy::Drop, vec![])]
+    #[case(MalformedPolicy::Deliver, vec![Effect::MalformedDatagramReceived(vec![0xff, 0x00])])]
+    fn malformed_received(#[case] policy: MalformedPolicy, #[case] expected: Vec<Effect>) {
+        let mut processor = new_proccessor().with_malformed_policy(policy);
+
+        let effects = processor
+            .tick(Event::DataReceived(vec![0xff, 0x00]))
+            .unwrap()
+            .into_vec();
+
+        assert_eq!(expected, effects);
+        assert_eq!(1, processor.stats().malformed_datagrams);
+    }
+
+    #[rstest]
+    fn empty_message_requested() {
+        let mut processor = new_proccessor();
+
+        let event = Event::EmptyMessageRequested(EmptyMessage::Reset(MessageId::from_value(7)));
+        let effects = processor.tick(event).unwrap().into_vec();
+
+        assert_eq!(
+            vec![Effect::Transmit(
+                Reset::from_message_id(MessageId::from_value(7)).encode()
+            )],
+            effects
+        );
+    }
+
+    #[rstest]
+    fn confirmable_message_sent_then_receives_acknowledgement() {
+        let mut processor = new_proccessor();
+
+        let message_id = MessageId::from_value(0);
+        let token = Token::new().unwrap();
+        let request = NewRequest::Get(Get {
+            options: GetOptions::new(),
+            reliability: Reliability::Confirmable(ConfirmableParameters::default(
+                InitialRetransmissionFactor::new(0.5).unwrap(),
+            )),
+        });
+
+        let event = Event::TransactionRequested(request.clone(), token);
+        processor.tick(event).unwrap();
+
+        let transaction = processor.transaction_store.find_by_token(&token).unwrap();
+        assert_eq!(false, transaction.is_acknowledged());
+
+        let acknowledgement = Acknowledgement::new(message_id);
+        let event = Event::DataReceived(acknowledgement.encode());
+        let effects = processor.tick(event).unwrap().into_vec();
+
+        let transaction = processor.transaction_store.find_by_token(&token).unwrap();
+        assert_eq!(Vec::<Effect>::new(), effects);
+        assert_eq!(true, transaction.is_acknowledged());
+    }
+
+    #[rstest]
+    fn confirmable_message_acknowledged_then_receives_response() {
+        let mut processor = new_proccessor();
+
+        let message_id = MessageId::from_value(0);
+        let token = Token::new().unwrap();
+        let request = NewRequest::Get(Get {
+            options: GetOptions::new(),
+            reliability: Reliability::Confirmable(ConfirmableParameters::default(
+                InitialRetransmissionFactor::new(0.5).unwrap(),
+            )),
+        });
+
+        let event = Event::TransactionRequested(request.clone(), token);
+        processor.tick(event).unwrap();
+
+        let acknowledgement = Acknowledgement::new(message_id);
+        let event = Event::DataReceived(acknowledgement.encode());
+        processor.tick(event).unwrap();
+
+        let response = Response::new(
+            message::Reliability::Confirmable,
+            token,
+            ResponseCode::Success(Success::Content),
+            MessageId::from_value(5),
+            Options::new(),
+            Payload::empty(),
+        );
+        let event = Event::DataReceived(response.clone().encode());
+        let effects = without_metrics(processor.tick(event).unwrap().into_vec());
+
+        let response = self::response::Response {
+            options: Options::new(),
+            response_code: response.response_code(),
+            payload: response.payload().clone(),
+            metrics: Default::default(),
+            warnings: vec![],
+        };
+        let acknowledgement = Acknowledgement::new(MessageId::from_value(5));
+        let expected_effects = vec![
+            Effect::Transmit(acknowledgement.encode()),
+            Effect::TransactionResolved(token, Ok(response)),
+        ];
+        assert_eq!(0, processor.transaction_store.count());
+        assert_eq!(expected_effects, effects);
+    }
+
+    #[rstest]
+    fn confirmable_message_sent_then_receives_reset() {
+        let mut processor = new_proccessor();
+
+        let message_id = MessageId::from_value(0);
+        let token = Token::new().unwrap();
+        let request = NewRequest::Get(Get {
+            options: GetOptions::new(),
+            reliability: Reliability::Confirmable(ConfirmableParameters::default(
+                InitialRetransmissionFactor::new(0.5).unwrap(),
+            )),
+        });
+
+        let event = Event::TransactionRequested(request.clone(), token);
+        processor.tick(event).unwrap();
+
+        let reset = Reset::from_message_id(message_id);
+        let event = Event::DataReceived(reset.encode());
+        let effects = processor.tick(event).unwrap().into_vec();
+        let expected_effects = vec![Effect::TransactionResolved(
+            token,
+            Err(response::Error::Reset),
+        )];
+        assert_eq!(expected_effects, effects);
+    }
+
+    #[rstest]
+    fn confirmable_message_sent_then_receives_piggyback_response() {
+        let mut processor = new_proccessor();
+
+        let message_id = MessageId::from_value(0);
+        let token = Token::new().unwrap();
+        let request = NewRequest::Get(Get {
+            options: GetOptions::new(),
+            reliability: Reliability::Confirmable(ConfirmableParameters::default(
+                InitialRetransmissionFactor::new(0.5).unwrap(),
+            )),
+        });
+
+        let event = Event::TransactionRequested(request.clone(), token);
+        processor.tick(event).unwrap();
+
+        let piggyback = Piggyback::new(
+            token,
+            ResponseCode::Success(Success::Content),
+            message_id,
+            Options::new(),
+            Payload::empty(),
+        );
+        let event = Event::DataReceived(piggyback.encode());
+        let effects = without_metrics(processor.tick(event).unwrap().into_vec());
+        let response = self::response::Response {
+            response_code: ResponseCode::Success(Success::Content),
+            options: Options::new(),
+            payload: Payload::empty(),
+            metrics: Default::default(),
+            warnings: vec![],
+        };
+        let expected_effects = vec![Effect::TransactionResolved(token, Ok(response))];
+        assert_eq!(0, processor.transaction_store.count());
+        assert_eq!(expected_effects, effects);
+    }
+
+    #[rstest]
+    fn confirmable_message_sent_then_piggyback_with_other_token_is_reset() {
+        let mut processor = new_proccessor();
+
+        let message_id = MessageId::from_value(0);
+        let token = Token::from_value(vec![1]).unwrap();
+        let request = NewRequest::Get(Get {
+            options: GetOptions::new(),
+            reliability: Reliability::Confirmable(ConfirmableParameters::default(
+                InitialRetransmissionFactor::new(0.5).unwrap(),
+            )),
+        });
+
+        let event = Event::TransactionRequested(request.clone(), token);
+        processor.tick(event).unwrap();
+
+        let piggyback = Piggyback::new(
+            Token::from_value(vec![2]).unwrap(),
+            ResponseCode::Success(Success::Content),
+            message_id,
+            Options::new(),
+            Payload::empty(),
+        );
+        let event = Event::DataReceived(piggyback.encode());
+        let effects = processor.tick(event).unwrap().into_vec();
+        let expected_effects = vec![Effect::Transmit(
+            Reset::from_message_id(message_id).encode(),
+        )];
+        assert_eq!(1, processor.transaction_store.count());
+        assert_eq!(expected_effects, effects);
+    }
+
+    #[rstest]
+    fn confirmable_message_sent_then_piggyback_with_other_message_id_is_ignored() {
+        let mut processor = new_proccessor();
+
+        let token = Token::from_value(vec![1]).unwrap();
+        let request = NewRequest::Get(Get {
+            options: GetOptions::new(),
+            reliability: Reliability::Confirmable(ConfirmableParameters::default(
+                InitialRetransmissionFactor::new(0.5).unwrap(),
+            )),
+        });
+
+        let event = Event::TransactionRequested(request.clone(), token);
+        processor.tick(event).unwrap();
+
+        let piggyback = Piggyback::new(
+            token,
+            ResponseCode::Success(Success::Content),
+            MessageId::from_value(7),
+            Options::new(),
+            Payload::empty(),
+        );
+        let event = Event::DataReceived(piggyback.encode());
+        let effects = processor.tick(event).unwrap().into_vec();
+        assert_eq!(1, processor.transaction_store.count());
+        assert_eq!(Vec::<Effect>::new(), effects);
+    }
+
+    #[rstest]
+    fn confirmable_message_sent_then_is_timed_out_based_on_max_transmit_wait() {
+        let mut processor = new_proccessor();
+
+        let message_id = MessageId::from_value(0);
+        let token = Token::new().unwrap();
+        let request = NewRequest::Get(Get {
+            options: GetOptions::new(),
+            reliability: Reliability::Confirmable(ConfirmableParameters::default(
+                InitialRetransmissionFactor::new(0.5).unwrap(),
+            )),
+        });
+
+        let event = Event::TransactionRequested(request.clone(), token);
+        processor.tick(event).unwrap();
+
+        let event = MaxTransmitWaitTimeout::new(
+            &message_id,
+            &ConfirmableParameters::default(InitialRetransmissionFactor::new(0.5).unwrap()),
+        )
+        .into();
+        let effects = processor.tick(event).unwrap().into_vec();
+        let expected_effects = vec![Effect::TransactionResolved(
+            token,
+            Err(response::Error::Timeout),
+        )];
+        assert_eq!(0, processor.transaction_store.count());
+        assert_eq!(expected_effects, effects);
+    }
+
+    #[rstest]
+    fn confirmable_message_acknowledged_then_is_timed_out_based_on_max_transmit_wait() {
+        let mut processor = new_proccessor();
+
+        let message_id = MessageId::from_value(0);
+        let token = Token::new().unwrap();
+        let request = NewRequest::Get(Get {
+            options: GetOptions::new(),
+            reliability: Reliability::Confirmable(ConfirmableParameters::default(
+                InitialRetransmissionFactor::new(0.5).unwrap(),
+            )),
+        });
+
+        let event = Event::TransactionRequested(request.clone(), token);
+        processor.tick(event).unwrap();
+
+        let event = Event::DataReceived(Acknowledgement::new(message_id).encode());
+        processor.tick(event).unwrap();
+        assert_eq!(
+            true,
+            processor
+                .transaction_store
+                .find_by_token(&token)
+                .unwrap()
+                .is_acknowledged()
+        );
+
+        let event = MaxTransmitWaitTimeout::new(
+            &message_id,
+            &ConfirmableParameters::default(InitialRetransmissionFactor::new(0.5).unwrap()),
+        )
+        .into();
+        let effects = processor.tick(event).unwrap().into_vec();
+        assert_eq!(1, processor.transaction_store.count());
+        assert_eq!(Vec::<Effect>::new(), effects);
+    }
+
+    #[rstest]
+    fn confirmable_message_sent_then_is_timed_out_based_on_exchange_lifetime() {
+        let mut processor = new_proccessor();
+
+        let message_id = MessageId::from_value(0);
+        let token = Token::new().unwrap();
+        let request = NewRequest::Get(Get {
+            options: GetOptions::new(),
+            reliability: Reliability::Confirmable(ConfirmableParameters::default(
+                InitialRetransmissionFactor::new(0.5).unwrap(),
+            )),
+        });
+
+        let event = Event::TransactionRequested(request.clone(), token);
+        processor.tick(event).unwrap();
+
+        let event = Event::TimeoutReached(
+            ExchangeLifetimeTimeout::new(
+                message_id,
+                &ConfirmableParameters::default(InitialRetransmissionFactor::new(0.0).unwrap()),
+            )
+            .into(),
+        );
+        let effects = processor.tick(event).unwrap().into_vec();
+        let expected_effects = vec![Effect::TransactionResolved(
+            token,
+            Err(response::Error::Timeout),
+        )];
+        assert_eq!(0, processor.transaction_store.count());
+        assert_eq!(expected_effects, effects);
+    }
+
+    #[rstest]
+    fn confirmable_message_acknowledged_then_is_timed_out_based_on_exchange_lifetime() {
+        let mut processor = new_proccessor();
+
+        let message_id = MessageId::from_value(0);
+        let token = Token::new().unwrap();
+        let request = NewRequest::Get(Get {
+            options: GetOptions::new(),
+            reliability: Reliability::Confirmable(ConfirmableParameters::default(
+                InitialRetransmissionFactor::new(0.5).unwrap(),
+            )),
+        });
+
+        let event = Event::TransactionRequested(request.clone(), token);
+        processor.tick(event).unwrap();
+
+        let event = Event::DataReceived(Acknowledgement::new(message_id).encode());
+        processor.tick(event).unwrap();
+        assert_eq!(
+            true,
+            processor
+                .transaction_store
+                .find_by_token(&token)
+                .unwrap()
+                .is_acknowledged()
+        );
+
+        let event = Event::TimeoutReached(
+            ExchangeLifetimeTimeout::new(
+                message_id,
+                &ConfirmableParameters::default(InitialRetransmissionFactor::new(0.0).unwrap()),
+            )
+            .into(),
+        );
+        let effects = processor.tick(event).unwrap().into_vec();
+        let expected_effects = vec![Effect::TransactionResolved(
+            token,
+            Err(response::Error::Timeout),
+        )];
+        assert_eq!(0, processor.transaction_store.count());
+        assert_eq!(expected_effects, effects);
+    }
+
+    #[rstest]
+    fn confirmable_message_sent_then_is_timed_out_based_on_transmission_counter() {
+        let mut processor = new_proccessor();
+
+        let message_id = MessageId::from_value(0);
+        let token = Token::new().unwrap();
+        let request = NewRequest::Get(Get {
+            options: GetOptions::new(),
+            reliability: Reliability::Confirmable(ConfirmableParameters::default(
+                InitialRetransmissionFactor::new(0.5).unwrap(),
+            )),
+        });
+
+        let event = Event::TransactionRequested(request.clone(), token);
+        processor.tick(event).unwrap();
+
+        let confirmable_parameters =
+            ConfirmableParameters::default(InitialRetransmissionFactor::new(0.0).unwrap());
+        let initial_retransmission_timeout =
+            RetransmissionTimeout::new(message_id, &confirmable_parameters);
+
+        let event = Event::TimeoutReached(initial_retransmission_timeout.into());
+        processor.tick(event).unwrap();
+
+        let next_retranmission_timeout =
+            RetransmissionTimeout::from_previous(initial_retransmission_timeout);
+        let event = Event::TimeoutReached(next_retranmission_timeout.into());
+        processor.tick(event).unwrap();
+
+        let next_retranmission_timeout =
+            RetransmissionTimeout::from_previous(next_retranmission_timeout);
+        let event = Event::TimeoutReached(next_retranmission_timeout.into());
+        processor.tick(event).unwrap();
+
+        let next_retranmission_timeout =
+            RetransmissionTimeout::from_previous(next_retranmission_timeout);
+        let event = Event::TimeoutReached(next_retranmission_timeout.into());
+        processor.tick(event).unwrap();
+
+        let next_retranmission_timeout =
+            RetransmissionTimeout::from_previous(next_retranmission_timeout);
+        let event = Event::TimeoutReached(next_retranmission_timeout.into());
+        let effects = processor.tick(event).unwrap().into_vec();
+        let expected_effects = vec![Effect::TransactionResolved(
             token,
             Err(response::Error::Timeout),
         )];
@@ -972,6 +2498,80 @@ mod tests {
         assert_eq!(expected_effects, effects);
     }
 
+    #[rstest]
+    fn request_larger_than_max_message_size_then_resolved_with_error() {
+        let mut processor = new_proccessor().with_max_message_size(64);
+
+        let token = Token::new().unwrap();
+        let request = NewRequest::Post(Post {
+            options: PostOptions::new(),
+            reliability: Reliability::Confirmable(ConfirmableParameters::default(
+                InitialRetransmissionFactor::new(0.5).unwrap(),
+            )),
+            payload: Payload::from_value(vec![0; 64]),
+        });
+        let size = request
+            .clone()
+            .encode(MessageId::from_value(0), token)
+            .len();
+
+        let event = Event::TransactionRequested(request, token);
+        let effects = processor.tick(event).unwrap().into_vec();
+        let expected_effects = vec![Effect::TransactionResolved(
+            token,
+            Err(response::Error::MessageTooLarge { size, limit: 64 }),
+        )];
+        assert_eq!(expected_effects, effects);
+        assert_eq!(0, processor.transaction_store.count());
+        assert_eq!(
+            false,
+            processor
+                .message_id_store
+                .is_claimed(&MessageId::from_value(0))
+        );
+    }
+
+    #[derive(Debug)]
+    struct ConstantBackoff;
+
+    impl BackoffStrategy for ConstantBackoff {
+        fn initial(&self, _parameters: &ConfirmableParameters) -> Duration {
+            Duration::from_secs(3)
+        }
+
+        fn next(&self, timeout: Duration, _attempt: u8) -> Duration {
+            timeout
+        }
+    }
+
+    #[rstest]
+    fn confirmable_message_retransmitted_with_backoff_strategy() {
+        let mut processor = new_proccessor().with_backoff_strategy(ConstantBackoff);
+
+        let message_id = MessageId::from_value(0);
+        let token = Token::new().unwrap();
+        let confirmable_parameters =
+            ConfirmableParameters::default(InitialRetransmissionFactor::new(0.5).unwrap());
+        let request = NewRequest::Get(Get {
+            options: GetOptions::new(),
+            reliability: Reliability::Confirmable(confirmable_parameters),
+        });
+
+        let event = Event::TransactionRequested(request.clone(), token);
+        let effects = processor.tick(event).unwrap().into_vec();
+        let retransmission_timeout = RetransmissionTimeout::with_strategy(
+            message_id,
+            &confirmable_parameters,
+            &ConstantBackoff,
+        );
+        assert_eq!(Duration::from_secs(3), *retransmission_timeout.timeout());
+        assert_eq!(true, effects.contains(&retransmission_timeout.into()));
+
+        let event = Event::TimeoutReached(retransmission_timeout.into());
+        let effects = processor.tick(event).unwrap().into_vec();
+        assert_eq!(Effect::from(retransmission_timeout), effects[0]);
+    }
+
     #[rstest]
     fn confirmable_message_acknowledged_then_ignore_retransmission() {
         let mut processor = new_proccessor();
@@ -985,7 +2585,7 @@ mod tests {
             )),
         });
 
-        let event = Event::TransactionRequested(request.clone(), token.clone());
+        let event = Event::TransactionRequested(request.clone(), token);
         processor.tick(event).unwrap();
 
         let event = Event::DataReceived(Acknowledgement::new(message_id).encode());
@@ -1006,7 +2606,7 @@ mod tests {
             )
             .into(),
         );
-        let effects = processor.tick(event).unwrap();
+        let effects = processor.tick(event).unwrap().into_vec();
         assert_eq!(Vec::<Effect>::new(), effects);
     }
 
@@ -1022,7 +2622,7 @@ mod tests {
             )),
         });
 
-        let event = Event::TransactionRequested(request.clone(), token.clone());
+        let event = Event::TransactionRequested(request.clone(), token);
         processor.tick(event).unwrap();
         assert_eq!(
             false,
@@ -1035,19 +2635,21 @@ mod tests {
 
         let response = Response::new(
             message::Reliability::Confirmable,
-            token.clone(),
+            token,
             ResponseCode::Success(Success::Content),
             MessageId::from_value(5),
             Options::new(),
             Payload::empty(),
         );
         let event = Event::DataReceived(response.clone().encode());
-        let effects = processor.tick(event).unwrap();
+        let effects = without_metrics(processor.tick(event).unwrap().into_vec());
 
         let response = self::response::Response {
             options: Options::new(),
             response_code: response.response_code(),
             payload: response.payload().clone(),
+            metrics: Default::default(),
+            warnings: vec![],
         };
         let acknowledgement = Acknowledgement::new(MessageId::from_value(5));
         let expected_effects = vec![
@@ -1071,7 +2673,7 @@ mod tests {
             )),
         });
 
-        let event = Event::TransactionRequested(request.clone(), token.clone());
+        let event = Event::TransactionRequested(request.clone(), token);
         processor.tick(event).unwrap();
 
         let event = Event::DataReceived(Acknowledgement::new(message_id).encode());
@@ -1079,19 +2681,21 @@ mod tests {
 
         let response = Response::new(
             message::Reliability::NonConfirmable,
-            token.clone(),
+            token,
             ResponseCode::Success(Success::Content),
             MessageId::from_value(5),
             Options::new(),
             Payload::empty(),
         );
         let event = Event::DataReceived(response.clone().encode());
-        let effects = processor.tick(event).unwrap();
+        let effects = without_metrics(processor.tick(event).unwrap().into_vec());
 
         let response = self::response::Response {
             options: Options::new(),
             response_code: response.response_code(),
             payload: response.payload().clone(),
+            metrics: Default::default(),
+            warnings: vec![],
         };
         let expected_effects = vec![Effect::TransactionResolved(token, Ok(response))];
         assert_eq!(0, processor.transaction_store.count());
@@ -1111,7 +2715,7 @@ mod tests {
             )),
         });
 
-        let event = Event::TransactionRequested(request.clone(), token.clone());
+        let event = Event::TransactionRequested(request.clone(), token);
         processor.tick(event).unwrap();
 
         let event = Event::DataReceived(Acknowledgement::new(message_id).encode());
@@ -1119,7 +2723,7 @@ mod tests {
 
         let response = Response::new(
             message::Reliability::NonConfirmable,
-            token.clone(),
+            token,
             ResponseCode::Success(Success::Content),
             MessageId::from_value(5),
             Options::new(),
@@ -1145,7 +2749,7 @@ mod tests {
             )),
         });
 
-        let event = Event::TransactionRequested(request.clone(), token.clone());
+        let event = Event::TransactionRequested(request.clone(), token);
         processor.tick(event).unwrap();
 
         let event = Event::DataReceived(Acknowledgement::new(message_id).encode());
@@ -1153,7 +2757,7 @@ mod tests {
 
         let response = Response::new(
             message::Reliability::NonConfirmable,
-            token.clone(),
+            token,
             ResponseCode::Success(Success::Content),
             MessageId::from_value(5),
             Options::new(),
@@ -1174,4 +2778,575 @@ mod tests {
         assert_eq!(0, processor.transaction_store.count());
         assert_eq!(false, processor.message_id_store.is_claimed(&message_id));
     }
+
+    #[rstest]
+    fn non_duplicates_are_recognized_per_source() {
+        let mut processor =
+            new_proccessor().with_profile(TransmissionProfile::default().with_nstart(2));
+        let first: std::net::SocketAddr = "192.0.2.1:5683".parse().unwrap();
+        let second: std::net::SocketAddr = "192.0.2.2:5683".parse().unwrap();
+        let tokens = [Token::new().unwrap(), Token::new().unwrap()];
+        for token in tokens {
+            let request = NewRequest::Get(Get {
+                options: GetOptions::new(),
+                reliability: Reliability::NonConfirmable(NonConfirmableParameters::default()),
+            });
+            processor
+                .tick(Event::TransactionRequested(request, token))
+                .unwrap();
+        }
+
+        let response = |token| {
+            Response::new(
+                message::Reliability::NonConfirmable,
+                token,
+                ResponseCode::Success(Success::Content),
+                MessageId::from_value(5),
+                Options::new(),
+                Payload::empty(),
+            )
+            .encode()
+        };
+        let resolved = |effects: Effects| {
+            effects
+                .into_iter()
+                .filter_map(|effect| match effect {
+                    Effect::TransactionResolved(token, Ok(_)) => Some(token),
+                    _ => None,
+                })
+                .collect::<Vec<_>>()
+        };
+
+        let effects = processor
+            .tick(Event::DataReceivedFrom(first, response(tokens[0])))
+            .unwrap();
+        assert_eq!(vec![tokens[0]], resolved(effects));
+
+        let effects = processor
+            .tick(Event::DataReceivedFrom(second, response(tokens[1])))
+            .unwrap();
+        assert_eq!(vec![tokens[1]], resolved(effects));
+
+        let effects = processor
+            .tick(Event::DataReceivedFrom(first, response(tokens[0])))
+            .unwrap();
+        assert_eq!(Vec::<Token>::new(), resolved(effects));
+        assert_eq!(1, processor.stats().duplicate_non_responses);
+    }
+
+    fn piggyback_event(token: Token, response_code: ResponseCode) -> Vec<Event> {
+        vec![Event::DataReceived(
+            Piggyback::new(
+                token,
+                response_code,
+                MessageId::from_value(0),
+                Options::new(),
+                Payload::empty(),
+            )
+            .encode(),
+        )]
+    }
+
+    fn separate_response_events(token: Token) -> Vec<Event> {
+        vec![
+            Event::DataReceived(Acknowledgement::new(MessageId::from_value(0)).encode()),
+            Event::DataReceived(
+                Response::new(
+                    message::Reliability::NonConfirmable,
+                    token,
+                    ResponseCode::Success(Success::Content),
+                    MessageId::from_value(5),
+                    Options::new(),
+                    Payload::empty(),
+                )
+                .encode(),
+            ),
+        ]
+    }
+
+    #[rstest]
+    #[case::piggybacked_error(piggyback_event(Token::from_value(vec![1]).unwrap(), ResponseCode::ClientError(ClientError::NotFound)))]
+    #[case::separate_response(separate_response_events(Token::from_value(vec![1]).unwrap()))]
+    #[case::reset(vec![Event::DataReceived(Reset::from_message_id(MessageId::from_value(0)).encode())])]
+    #[case::canceled(vec![Event::TransactionCanceled(Token::from_value(vec![1]).unwrap())])]
+    #[case::timed_out(vec![MaxTransmitWaitTimeout::new(&MessageId::from_value(0), &ConfirmableParameters::default(InitialRetransmissionFactor::new(0.5).unwrap())).into()])]
+    fn message_id_quarantined_when_transaction_ends_then_released(#[case] events: Vec<Event>) {
+        let mut processor = new_proccessor();
+        let message_id = MessageId::from_value(0);
+        let parameters =
+            ConfirmableParameters::default(InitialRetransmissionFactor::new(0.5).unwrap());
+
+        let request = NewRequest::Get(Get {
+            options: GetOptions::new(),
+            reliability: Reliability::Confirmable(parameters),
+        });
+        processor
+            .tick(Event::TransactionRequested(
+                request,
+                Token::from_value(vec![1]).unwrap(),
+            ))
+            .unwrap();
+        assert_eq!(
+            MessageIdState::InFlight,
+            processor.message_id_store.state(&message_id)
+        );
+
+        for event in events {
+            processor.tick(event).unwrap();
+        }
+        assert_eq!(0, processor.transaction_store.count());
+        assert_eq!(
+            MessageIdState::Quarantined,
+            processor.message_id_store.state(&message_id)
+        );
+
+        let event = ExchangeLifetimeTimeout::new(message_id, &parameters).into();
+        processor.tick(event).unwrap();
+        assert_eq!(
+            MessageIdState::Free,
+            processor.message_id_store.state(&message_id)
+        );
+    }
+
+    #[derive(Debug)]
+    struct PathInterceptor;
+
+    impl RequestInterceptor for PathInterceptor {
+        fn before_encode(&mut self, request: &mut NewRequest) {
+            if let NewRequest::Get(get) = request {
+                get.options.set_uri_path("auth".try_into().unwrap());
+            }
+        }
+
+        fn after_decode(&mut self, response: &mut self::response::Response) {
+            response.payload = Payload::from_value(vec![1]);
+        }
+    }
+
+    #[rstest]
+    fn interceptor_rewrites_request_and_response() {
+        let mut processor = new_proccessor().with_interceptor(PathInterceptor);
+
+        let message_id = MessageId::from_value(0);
+        let token = Token::new().unwrap();
+        let request = NewRequest::Get(Get {
+            options: GetOptions::new(),
+            reliability: Reliability::Confirmable(ConfirmableParameters::default(
+                InitialRetransmissionFactor::new(0.5).unwrap(),
+            )),
+        });
+        let mut intercepted_request = request.clone();
+        PathInterceptor.before_encode(&mut intercepted_request);
+
+        let event = Event::TransactionRequested(request, token);
+        let effects = processor.tick(event).unwrap().into_vec();
+        assert_eq!(
+            true,
+            effects.contains(&Effect::Transmit(
+                intercepted_request.encode(message_id, token)
+            ))
+        );
+
+        let piggyback = Piggyback::new(
+            token,
+            ResponseCode::Success(Success::Content),
+            message_id,
+            Options::new(),
+            Payload::empty(),
+        );
+        let event = Event::DataReceived(piggyback.encode());
+        let effects = without_metrics(processor.tick(event).unwrap().into_vec());
+        let response = self::response::Response {
+            response_code: ResponseCode::Success(Success::Content),
+            options: Options::new(),
+            payload: Payload::from_value(vec![1]),
+            metrics: Default::default(),
+            warnings: vec![],
+        };
+        assert_eq!(
+            vec![Effect::TransactionResolved(token, Ok(response))],
+            effects
+        );
+    }
+
+    #[rstest]
+    fn unauthorized_with_echo_is_sent_again_once() {
+        let mut processor = new_proccessor();
+
+        let token = Token::new().unwrap();
+        let request = NewRequest::Get(Get {
+            options: GetOptions::new(),
+            reliability: Reliability::Confirmable(ConfirmableParameters::default(
+                InitialRetransmissionFactor::new(0.5).unwrap(),
+            )),
+        });
+        processor
+            .tick(Event::TransactionRequested(request.clone(), token))
+            .unwrap();
+
+        let echo = Echo::from_value(vec![0xec, 0x40]).unwrap();
+        let unauthorized = |message_id| {
+            let mut options = Options::new();
+            options.set_echo(echo.clone());
+            Piggyback::new(
+                token,
+                ResponseCode::ClientError(ClientError::Unauthorized),
+                MessageId::from_value(message_id),
+                options,
+                Payload::empty(),
+            )
+        };
+
+        let effects = processor
+            .tick(Event::DataReceived(unauthorized(0).encode()))
+            .unwrap()
+            .into_vec();
+        let mut echoed_request = request;
+        echoed_request.set_echo(echo.clone());
+        assert!(effects.contains(&Effect::Transmit(
+            echoed_request.encode(MessageId::from_value(1), token)
+        )));
+        assert!(!effects
+            .iter()
+            .any(|effect| matches!(effect, Effect::TransactionResolved(..))));
+
+        let effects = without_metrics(
+            processor
+                .tick(Event::DataReceived(unauthorized(1).encode()))
+                .unwrap()
+                .into_vec(),
+        );
+        let mut options = Options::new();
+        options.set_echo(echo);
+        let response = self::response::Response {
+            response_code: ResponseCode::ClientError(ClientError::Unauthorized),
+            options,
+            payload: Payload::empty(),
+            metrics: Default::default(),
+            warnings: vec![],
+        };
+        assert_eq!(
+            vec![Effect::TransactionResolved(token, Ok(response))],
+            effects
+        );
+    }
+
+    fn large_put() -> NewRequest {
+        NewRequest::Put(Put {
+            options: PutOptions::new(),
+            reliability: Reliability::Confirmable(ConfirmableParameters::default(
+                InitialRetransmissionFactor::new(0.5).unwrap(),
+            )),
+            payload: Payload::from_value((0..40).collect()),
+        })
+    }
+
+    fn entity_too_large(token: Token) -> Vec<u8> {
+        let mut options = Options::new();
+        options.set_size1(Size1::new(16));
+        Piggyback::new(
+            token,
+            ResponseCode::REQUEST_ENTITY_TOO_LARGE,
+            MessageId::from_value(0),
+            options,
+            Payload::empty(),
+        )
+        .encode()
+    }
+
+    #[rstest]
+    fn entity_too_large_resolves_by_default() {
+        let mut processor = new_proccessor();
+        let token = Token::new().unwrap();
+        processor
+            .tick(Event::TransactionRequested(large_put(), token))
+            .unwrap();
+
+        let effects = processor
+            .tick(Event::DataReceived(entity_too_large(token)))
+            .unwrap()
+            .into_vec();
+
+        assert!(effects.iter().any(|effect| matches!(
+            effect,
+            Effect::TransactionResolved(_, Ok(response))
+                if response.response_code == ResponseCode::REQUEST_ENTITY_TOO_LARGE
+        )));
+    }
+
+    #[rstest]
+    fn entity_too_large_restarts_block_wise() {
+        let mut processor =
+            new_proccessor().with_entity_too_large_policy(EntityTooLargePolicy::AutoBlockwise);
+        let token = Token::new().unwrap();
+        let request = large_put();
+        processor
+            .tick(Event::TransactionRequested(request.clone(), token))
+            .unwrap();
+
+        let mut upload = BlockUpload::new(request, 0)
+            .unwrap()
+            .with_request_tag(RequestTag::from_value(vec![1]).unwrap());
+        let effects = processor
+            .tick(Event::DataReceived(entity_too_large(token)))
+            .unwrap()
+            .into_vec();
+        assert!(effects.contains(&Effect::Transmit(
+            upload
+                .start()
+                .unwrap()
+                .encode(MessageId::from_value(1), token)
+        )));
+
+        let piggyback = |message_id, response_code, num, more| {
+            let mut options = Options::new();
+            options.set_block1(Block1::new(num, more, 0).unwrap());
+            Piggyback::new(
+                token,
+                response_code,
+                MessageId::from_value(message_id),
+                options,
+                Payload::empty(),
+            )
+            .encode()
+        };
+        for num in 0..2 {
+            let effects = processor
+                .tick(Event::DataReceived(piggyback(
+                    num as u16 + 1,
+                    ResponseCode::CONTINUE,
+                    num,
+                    true,
+                )))
+                .unwrap()
+                .into_vec();
+            let block = upload.next(&Options::new()).unwrap().unwrap();
+            assert!(effects.contains(&Effect::Transmit(
+                block.encode(MessageId::from_value(num as u16 + 2), token)
+            )));
+            assert!(!effects
+                .iter()
+                .any(|effect| matches!(effect, Effect::TransactionResolved(..))));
+        }
+
+        let effects = processor
+            .tick(Event::DataReceived(piggyback(
+                3,
+                ResponseCode::CHANGED,
+                2,
+                false,
+            )))
+            .unwrap()
+            .into_vec();
+        assert!(effects.iter().any(|effect| matches!(
+            effect,
+            Effect::TransactionResolved(_, Ok(response))
+                if response.response_code == ResponseCode::CHANGED
+        )));
+    }
+
+    #[rstest]
+    fn upload_reads_body_block_by_block() {
+        let mut processor = new_proccessor().with_max_message_size(100);
+        let token = Token::new().unwrap();
+        let read = std::sync::Arc::new(std::sync::Mutex::new(0));
+        let body = Body::from_generator({
+            let read = read.clone();
+            move |size| {
+                let mut read = read.lock().unwrap();
+                let chunk = vec![7; size.min(200 - *read)];
+                *read += chunk.len();
+                Ok(chunk)
+            }
+        });
+
+        let effects = processor
+            .tick(Event::UploadRequested(large_put(), body, token))
+            .unwrap()
+            .into_vec();
+
+        let Some(data) = effects.iter().find_map(|effect| match effect {
+            Effect::Transmit(data) => Some(data),
+            _ => None,
+        }) else {
+            panic!("First block should be sent");
+        };
+        let Ok(message::Message::Request(message::Request::Put(put))) =
+            message::Message::decode(data)
+        else {
+            panic!("First block should be a PUT");
+        };
+        assert_eq!(
+            Some(&Block1::new(0, true, 2).unwrap()),
+            put.options().block1()
+        );
+        assert_eq!(
+            Some(&RequestTag::from_value(vec![1]).unwrap()),
+            put.options().request_tag()
+        );
+        assert_eq!(vec![7; 64], put.payload().value());
+        assert_eq!(65, *read.lock().unwrap());
+    }
+
+    #[rstest]
+    fn resolved_response_warns_about_content_format_mismatch() {
+        let mut processor = new_proccessor();
+
+        let message_id = MessageId::from_value(0);
+        let token = Token::new().unwrap();
+        let mut options = GetOptions::new();
+        options.set_accept(MediaType::APPLICATION_JSON.into());
+        let request = NewRequest::Get(Get {
+            options,
+            reliability: Reliability::Confirmable(ConfirmableParameters::default(
+                InitialRetransmissionFactor::new(0.5).unwrap(),
+            )),
+        });
+        processor
+            .tick(Event::TransactionRequested(request, token))
+            .unwrap();
+
+        let mut options = Options::new();
+        options.set_content_format(MediaType::TextPlain.into());
+        let piggyback = Piggyback::new(
+            token,
+            ResponseCode::Success(Success::Content),
+            message_id,
+            options,
+            Payload::from_value(b"ok".to_vec()),
+        )
+        .encode();
+        let effects = processor
+            .tick(Event::DataReceived(piggyback))
+            .unwrap()
+            .into_vec();
+
+        let [Effect::TransactionResolved(_, Ok(response))] = effects.as_slice() else {
+            panic!("Expected a resolved transaction, got {effects:?}");
+        };
+        assert_eq!(
+            &[Warning::ContentFormatMismatch {
+                accept: MediaType::ApplicationJson,
+                content_format: Some(MediaType::TextPlain),
+            }],
+            response.warnings()
+        );
+    }
+
+    #[rstest]
+    #[case(ResponseCodePolicy::Accept, Success::Content, Ok(vec![]))]
+    #[case(ResponseCodePolicy::Warn, Success::Content, Ok(vec![]))]
+    #[case(ResponseCodePolicy::Reject, Success::Content, Ok(vec![]))]
+    #[case(ResponseCodePolicy::Accept, Success::Created, Ok(vec![]))]
+    #[case(
+        ResponseCodePolicy::Warn,
+        Success::Created,
+        Ok(vec![Warning::ProtocolViolation {
+            method: MethodCode::Get,
+            response_code: ResponseCode::Success(Success::Created),
+        }])
+    )]
+    #[case(
+        ResponseCodePolicy::Reject,
+        Success::Created,
+        Err(response::Error::ProtocolViolation {
+            method: MethodCode::Get,
+            response_code: ResponseCode::Success(Success::Created),
+        })
+    )]
+    fn resolved_response_checks_response_code_against_method(
+        #[case] policy: ResponseCodePolicy,
+        #[case] success: Success,
+        #[case] expected: Result<Vec<Warning>, response::Error>,
+    ) {
+        let mut processor = new_proccessor().with_response_code_policy(policy);
+
+        let token = Token::new().unwrap();
+        let request = NewRequest::Get(Get {
+            options: GetOptions::new(),
+            reliability: Reliability::Confirmable(ConfirmableParameters::default(
+                InitialRetransmissionFactor::new(0.5).unwrap(),
+            )),
+        });
+        processor
+            .tick(Event::TransactionRequested(request, token))
+            .unwrap();
+
+        let piggyback = Piggyback::new(
+            token,
+            ResponseCode::Success(success),
+            MessageId::from_value(0),
+            Options::new(),
+            Payload::empty(),
+        )
+        .encode();
+        let effects = processor
+            .tick(Event::DataReceived(piggyback))
+            .unwrap()
+            .into_vec();
+
+        let [Effect::TransactionResolved(resolved_token, result)] = effects.as_slice() else {
+            panic!("Expected a resolved transaction, got {effects:?}");
+        };
+        assert_eq!(&token, resolved_token);
+        assert_eq!(
+            expected,
+            result
+                .as_ref()
+                .map(|response| response.warnings().to_vec())
+                .map_err(|error| *error)
+        );
+    }
+
+    #[rstest]
+    fn resolved_response_contains_metrics() {
+        let mut processor = new_proccessor();
+
+        let message_id = MessageId::from_value(0);
+        let token = Token::new().unwrap();
+        let request = NewRequest::Get(Get {
+            options: GetOptions::new(),
+            reliability: Reliability::Confirmable(ConfirmableParameters::default(
+                InitialRetransmissionFactor::new(0.5).unwrap(),
+            )),
+        });
+        let request_data = request.clone().encode(message_id, token);
+
+        let event = Event::TransactionRequested(request, token);
+        processor.tick(event).unwrap();
+
+        let retransmission_timeout = RetransmissionTimeout::new(
+            message_id,
+            &ConfirmableParameters::default(InitialRetransmissionFactor::new(0.5).unwrap()),
+        );
+        processor.tick(retransmission_timeout.into()).unwrap();
+
+        let piggyback = Piggyback::new(
+            token,
+            ResponseCode::Success(Success::Content),
+            message_id,
+            Options::new(),
+            Payload::empty(),
+        )
+        .encode();
+        let effects = processor
+            .tick(Event::DataReceived(piggyback.clone()))
+            .unwrap()
+            .into_vec();
+
+        let [Effect::TransactionResolved(resolved_token, Ok(response))] = effects.as_slice() else {
+            panic!("Expected a resolved transaction, got {effects:?}");
+        };
+        assert_eq!(&token, resolved_token);
+        assert_eq!(1, response.metrics.retransmissions);
+        assert_eq!(request_data.len() * 2, response.metrics.bytes_sent);
+        assert_eq!(piggyback.len(), response.metrics.bytes_received);
+        assert_eq!(
+            Some(true),
+            response
+                .metrics
+                .time_to_acknowledgement
+                .map(|duration| duration <= response.metrics.round_trip_time)
+        );
+    }
 }
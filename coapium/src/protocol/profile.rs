@@ -0,0 +1,214 @@
+use std::time::Duration;
+
+use super::transmission_parameters::{
+    AckRandomFactor, AckTimeout, ConfirmableParameters, InitialRetransmissionFactor, MaxRetransmit,
+    NonConfirmableParameters, ProbingRatePerSecond, DEFAULT_MAX_LATENCY,
+};
+
+/// Transmission parameters of
+/// [RFC 7252 §4.8](https://datatracker.ietf.org/doc/html/rfc7252#section-4.8) a client applies,
+/// along with the time values derived from them in §4.8.2.
+///
+/// The default is the values of the RFC, the presets suit networks far from its assumptions.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct TransmissionProfile {
+    ack_random_factor: AckRandomFactor,
+    ack_timeout: AckTimeout,
+    default_leisure: Duration,
+    max_latency: Duration,
+    max_retransmit: MaxRetransmit,
+    nstart: usize,
+    probing_rate: ProbingRatePerSecond,
+}
+
+impl TransmissionProfile {
+    pub fn rfc7252() -> Self {
+        Self {
+            ack_random_factor: AckRandomFactor::default(),
+            ack_timeout: AckTimeout::default(),
+            default_leisure: Duration::from_secs(5),
+            max_latency: DEFAULT_MAX_LATENCY,
+            max_retransmit: MaxRetransmit::default(),
+            nstart: 1,
+            probing_rate: ProbingRatePerSecond::default(),
+        }
+    }
+
+    /// Low-power wide-area networks like NB-IoT or LoRaWAN, where round trips take seconds and
+    /// messages are often lost. Retransmissions back off from 10 seconds and give up after 5.
+    pub fn lossy_lpwan() -> Self {
+        Self {
+            ack_timeout: AckTimeout::new(Duration::from_secs(10)).unwrap(),
+            default_leisure: Duration::from_secs(30),
+            max_latency: Duration::from_secs(300),
+            max_retransmit: MaxRetransmit::new(5),
+            probing_rate: ProbingRatePerSecond::new(0.1),
+            ..Self::rfc7252()
+        }
+    }
+
+    /// Wired or otherwise reliable local networks, where an unanswered message is lost rather than
+    /// late. Gives up within seconds and keeps more exchanges in flight.
+    pub fn lan_fast() -> Self {
+        Self {
+            ack_timeout: AckTimeout::new(Duration::from_secs(1)).unwrap(),
+            default_leisure: Duration::from_secs(1),
+            max_latency: Duration::from_secs(1),
+            max_retransmit: MaxRetransmit::new(2),
+            nstart: 4,
+            probing_rate: ProbingRatePerSecond::new(10.0),
+            ..Self::rfc7252()
+        }
+    }
+
+    pub fn with_ack_random_factor(mut self, ack_random_factor: AckRandomFactor) -> Self {
+        self.ack_random_factor = ack_random_factor;
+        self
+    }
+
+    pub fn with_ack_timeout(mut self, ack_timeout: AckTimeout) -> Self {
+        self.ack_timeout = ack_timeout;
+        self
+    }
+
+    /// How long a server may wait before answering a multicast request, and so how long a
+    /// multicast request waits for answers.
+    pub fn with_default_leisure(mut self, default_leisure: Duration) -> Self {
+        self.default_leisure = default_leisure;
+        self
+    }
+
+    pub fn with_max_latency(mut self, max_latency: Duration) -> Self {
+        self.max_latency = max_latency;
+        self
+    }
+
+    pub fn with_max_retransmit(mut self, max_retransmit: MaxRetransmit) -> Self {
+        self.max_retransmit = max_retransmit;
+        self
+    }
+
+    /// Exchanges in flight with the peer at once, at least 1.
+    pub fn with_nstart(mut self, nstart: usize) -> Self {
+        self.nstart = nstart.max(1);
+        self
+    }
+
+    pub fn with_probing_rate(mut self, probing_rate: ProbingRatePerSecond) -> Self {
+        self.probing_rate = probing_rate;
+        self
+    }
+
+    /// Parameters of a confirmable request sent with the profile.
+    pub fn confirmable_parameters(
+        &self,
+        initial_retransmission_factor: InitialRetransmissionFactor,
+    ) -> ConfirmableParameters {
+        ConfirmableParameters::new(
+            self.ack_timeout,
+            self.ack_random_factor,
+            initial_retransmission_factor,
+            self.max_retransmit,
+        )
+    }
+
+    /// Parameters of a non-confirmable request sent with the profile, retransmitted at the
+    /// probing rate.
+    pub fn non_confirmable_parameters(&self) -> NonConfirmableParameters {
+        NonConfirmableParameters::new(
+            self.ack_timeout,
+            self.ack_random_factor,
+            self.max_retransmit,
+            Some(self.probing_rate),
+        )
+    }
+
+    pub fn ack_random_factor(&self) -> f32 {
+        self.ack_random_factor.value()
+    }
+
+    pub fn ack_timeout(&self) -> Duration {
+        self.ack_timeout.value()
+    }
+
+    pub fn default_leisure(&self) -> Duration {
+        self.default_leisure
+    }
+
+    pub fn max_latency(&self) -> Duration {
+        self.max_latency
+    }
+
+    pub fn max_retransmit(&self) -> u8 {
+        self.max_retransmit.value()
+    }
+
+    pub fn nstart(&self) -> usize {
+        self.nstart
+    }
+
+    pub fn probing_rate(&self) -> ProbingRatePerSecond {
+        self.probing_rate
+    }
+
+    pub fn max_transmit_span(&self) -> Duration {
+        self.confirmable_parameters(InitialRetransmissionFactor::new(0.0).unwrap())
+            .max_transmit_span()
+    }
+
+    pub fn max_transmit_wait(&self) -> Duration {
+        self.confirmable_parameters(InitialRetransmissionFactor::new(0.0).unwrap())
+            .max_transmit_wait()
+    }
+
+    pub fn processing_delay(&self) -> Duration {
+        self.ack_timeout()
+    }
+
+    pub fn max_rtt(&self) -> Duration {
+        2 * self.max_latency + self.processing_delay()
+    }
+
+    pub fn exchange_lifetime(&self) -> Duration {
+        self.max_transmit_span() + 2 * self.max_latency + self.processing_delay()
+    }
+
+    pub fn non_lifetime(&self) -> Duration {
+        self.max_transmit_span() + self.max_latency
+    }
+}
+
+impl Default for TransmissionProfile {
+    fn default() -> Self {
+        Self::rfc7252()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use pretty_assertions::assert_eq;
+    use rstest::rstest;
+
+    use super::TransmissionProfile;
+
+    #[rstest]
+    fn rfc7252_derived_values() {
+        let profile = TransmissionProfile::default();
+
+        assert_eq!(Duration::from_secs(45), profile.max_transmit_span());
+        assert_eq!(Duration::from_secs(93), profile.max_transmit_wait());
+        assert_eq!(Duration::from_secs(202), profile.max_rtt());
+        assert_eq!(Duration::from_secs(247), profile.exchange_lifetime());
+        assert_eq!(Duration::from_secs(145), profile.non_lifetime());
+    }
+
+    #[rstest]
+    #[case(TransmissionProfile::lossy_lpwan())]
+    #[case(TransmissionProfile::lan_fast())]
+    fn presets_keep_lifetimes_ordered(#[case] profile: TransmissionProfile) {
+        assert!(profile.max_transmit_span() < profile.max_transmit_wait());
+        assert!(profile.non_lifetime() < profile.exchange_lifetime());
+    }
+}
@@ -0,0 +1,154 @@
+use std::collections::BTreeMap;
+
+use crate::codec::{
+    option::{Block2, ETag, QBlock2},
+    Options, Payload,
+};
+
+/// Puts together a representation sent as a burst of Q-Block2 responses and tells which blocks
+/// never arrived, see [RFC 9177](https://datatracker.ietf.org/doc/html/rfc9177#section-4.4).
+///
+/// The Q-Block2 option from [`QuickBlockReassembly::missing`] goes into a GET request, together
+/// with the ETag of the representation, to have the server send the lost blocks again. A block
+/// with another ETag means the representation changed and starts the reassembly over.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct QuickBlockReassembly {
+    blocks: BTreeMap<u32, Vec<u8>>,
+    etag: Option<ETag>,
+    last: Option<u32>,
+    szx: u8,
+}
+
+impl QuickBlockReassembly {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn etag(&self) -> Option<&ETag> {
+        self.etag.as_ref()
+    }
+
+    /// Adds the block of a response, responses without Q-Block2 are ignored.
+    pub fn insert(&mut self, options: &Options, payload: &Payload) {
+        let Some(block) = options
+            .q_block2()
+            .and_then(|q_block2| q_block2.blocks().first())
+        else {
+            return;
+        };
+
+        if options.etag() != self.etag.as_ref() {
+            *self = Self::new();
+            self.etag = options.etag().cloned();
+        }
+
+        if !block.more() {
+            self.last = Some(block.num());
+        }
+        self.szx = block.szx();
+        self.blocks.insert(block.num(), payload.value().to_vec());
+    }
+
+    /// Blocks to ask for again, or `None` if nothing is missing or no block arrived yet.
+    ///
+    /// When the last block hasn't arrived, the block after the highest one received is asked for
+    /// with the M bit set, so the server sends it and everything after it.
+    pub fn missing(&self) -> Option<QBlock2> {
+        let highest = *self.blocks.keys().next_back()?;
+
+        let mut blocks: Vec<Block2> = (0..highest)
+            .filter(|num| !self.blocks.contains_key(num))
+            .filter_map(|num| Block2::new(num, false, self.szx).ok())
+            .collect();
+        if self.last.is_none() {
+            blocks.extend(Block2::new(highest + 1, true, self.szx).ok());
+        }
+
+        QBlock2::from_blocks(blocks).ok()
+    }
+
+    /// The full representation, once every block arrived.
+    pub fn payload(&self) -> Option<Payload> {
+        let last = self.last?;
+        if self.blocks.len() != last as usize + 1 {
+            return None;
+        }
+
+        Some(Payload::from_value(
+            self.blocks.values().flatten().copied().collect(),
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+    use rstest::rstest;
+
+    use crate::codec::{
+        option::{Block2, ETag, QBlock2},
+        Options, Payload,
+    };
+
+    use super::QuickBlockReassembly;
+
+    fn block(num: u32, more: bool, etag: u8) -> (Options, Payload) {
+        let mut options = Options::new();
+        options.set_q_block2(QBlock2::new(num, more, 0).unwrap());
+        options.set_etag(ETag::from_values(vec![vec![etag]]).unwrap());
+        (options, Payload::from_value(vec![num as u8; 16]))
+    }
+
+    fn reassembly(blocks: Vec<(u32, bool, u8)>) -> QuickBlockReassembly {
+        let mut reassembly = QuickBlockReassembly::new();
+        for (num, more, etag) in blocks {
+            let (options, payload) = block(num, more, etag);
+            reassembly.insert(&options, &payload);
+        }
+        reassembly
+    }
+
+    fn blocks(blocks: Vec<(u32, bool)>) -> QBlock2 {
+        QBlock2::from_blocks(
+            blocks
+                .into_iter()
+                .map(|(num, more)| Block2::new(num, more, 0).unwrap())
+                .collect(),
+        )
+        .unwrap()
+    }
+
+    #[rstest]
+    #[case(vec![],                                               None)]
+    #[case(vec![(0, true, 1), (1, true, 1), (2, false, 1)],      None)]
+    #[case(vec![(0, true, 1), (2, true, 1), (4, false, 1)],      Some(blocks(vec![(1, false), (3, false)])))]
+    #[case(vec![(0, true, 1), (2, true, 1)],                     Some(blocks(vec![(1, false), (3, true)])))]
+    #[case(vec![(0, true, 1), (1, true, 1), (2, false, 2)],      Some(blocks(vec![(0, false), (1, false)])))]
+    fn missing(#[case] received: Vec<(u32, bool, u8)>, #[case] expected: Option<QBlock2>) {
+        assert_eq!(expected, reassembly(received).missing());
+    }
+
+    #[rstest]
+    fn payload_once_every_block_arrived() {
+        let mut reassembly = reassembly(vec![(2, false, 1), (0, true, 1)]);
+        assert_eq!(None, reassembly.payload());
+
+        let (options, payload) = block(1, true, 1);
+        reassembly.insert(&options, &payload);
+
+        assert_eq!(
+            Some(Payload::from_value(
+                [vec![0; 16], vec![1; 16], vec![2; 16]].concat()
+            )),
+            reassembly.payload()
+        );
+    }
+
+    #[rstest]
+    fn responses_without_q_block2_are_ignored() {
+        let mut reassembly = QuickBlockReassembly::new();
+        reassembly.insert(&Options::new(), &Payload::from_value(vec![1]));
+
+        assert_eq!(QuickBlockReassembly::new(), reassembly);
+    }
+}
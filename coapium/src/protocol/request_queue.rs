@@ -0,0 +1,163 @@
+use std::collections::VecDeque;
+
+use crate::codec::Token;
+
+use super::new_request::NewRequest;
+
+/// Order in which queued requests are sent once there is capacity, requests of the same priority
+/// are sent in the order they were queued.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub enum Priority {
+    High,
+    #[default]
+    Normal,
+    Low,
+}
+
+/// Requests waiting for capacity, optionally limited to a maximum number of requests.
+#[derive(Debug, Default)]
+pub struct RequestQueue {
+    high: VecDeque<(NewRequest, Token)>,
+    normal: VecDeque<(NewRequest, Token)>,
+    low: VecDeque<(NewRequest, Token)>,
+    max_depth: Option<usize>,
+}
+
+impl RequestQueue {
+    pub fn new(max_depth: Option<usize>) -> Self {
+        Self {
+            max_depth,
+            ..Default::default()
+        }
+    }
+
+    pub fn is_full(&self) -> bool {
+        self.max_depth
+            .is_some_and(|max_depth| self.len() >= max_depth)
+    }
+
+    pub fn len(&self) -> usize {
+        self.high.len() + self.normal.len() + self.low.len()
+    }
+
+    pub fn contains(&self, token: &Token) -> bool {
+        [&self.high, &self.normal, &self.low]
+            .into_iter()
+            .any(|queue| queue.iter().any(|(_, t)| t == token))
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    pub fn max_depth(&self) -> Option<usize> {
+        self.max_depth
+    }
+
+    /// Takes the request that has waited the longest among those with the highest priority.
+    pub fn pop(&mut self) -> Option<(Priority, NewRequest, Token)> {
+        [Priority::High, Priority::Normal, Priority::Low]
+            .into_iter()
+            .find_map(|priority| {
+                self.by_priority(priority)
+                    .pop_front()
+                    .map(|(request, token)| (priority, request, token))
+            })
+    }
+
+    /// Queues the request, or hands its token back if the queue is full.
+    pub fn push(
+        &mut self,
+        priority: Priority,
+        request: NewRequest,
+        token: Token,
+    ) -> Result<(), Token> {
+        if self.is_full() {
+            return Err(token);
+        }
+
+        self.by_priority(priority).push_back((request, token));
+        Ok(())
+    }
+
+    /// Removes the request with the token, returns whether it was queued.
+    pub fn remove(&mut self, token: &Token) -> bool {
+        [&mut self.high, &mut self.normal, &mut self.low]
+            .into_iter()
+            .any(|queue| {
+                let Some(position) = queue.iter().position(|(_, t)| t == token) else {
+                    return false;
+                };
+                queue.remove(position);
+                true
+            })
+    }
+
+    fn by_priority(&mut self, priority: Priority) -> &mut VecDeque<(NewRequest, Token)> {
+        match priority {
+            Priority::High => &mut self.high,
+            Priority::Normal => &mut self.normal,
+            Priority::Low => &mut self.low,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+    use rstest::rstest;
+
+    use crate::codec::{message::GetOptions, Token};
+    use crate::protocol::{
+        get::Get, new_request::NewRequest, reliability::Reliability,
+        transmission_parameters::NonConfirmableParameters,
+    };
+
+    use super::{Priority, RequestQueue};
+
+    fn request() -> NewRequest {
+        NewRequest::Get(Get {
+            options: GetOptions::new(),
+            reliability: Reliability::NonConfirmable(NonConfirmableParameters::default()),
+        })
+    }
+
+    fn token(value: u8) -> Token {
+        Token::from_value(vec![value]).unwrap()
+    }
+
+    #[rstest]
+    fn pop_by_priority_then_insertion_order() {
+        let mut queue = RequestQueue::new(None);
+        queue.push(Priority::Low, request(), token(1)).unwrap();
+        queue.push(Priority::Normal, request(), token(2)).unwrap();
+        queue.push(Priority::High, request(), token(3)).unwrap();
+        queue.push(Priority::Normal, request(), token(4)).unwrap();
+
+        let popped = std::iter::from_fn(|| queue.pop())
+            .map(|(priority, _, token)| (priority, token))
+            .collect::<Vec<_>>();
+
+        assert_eq!(
+            vec![
+                (Priority::High, token(3)),
+                (Priority::Normal, token(2)),
+                (Priority::Normal, token(4)),
+                (Priority::Low, token(1)),
+            ],
+            popped
+        );
+    }
+
+    #[rstest]
+    fn push_when_full() {
+        let mut queue = RequestQueue::new(Some(1));
+        queue.push(Priority::Low, request(), token(1)).unwrap();
+
+        assert_eq!(
+            Err(token(2)),
+            queue.push(Priority::High, request(), token(2))
+        );
+        assert_eq!(1, queue.len());
+    }
+}
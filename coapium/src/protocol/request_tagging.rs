@@ -0,0 +1,145 @@
+use std::collections::HashMap;
+
+use crate::codec::option::{Block1, RequestTag, UriPath};
+
+use super::{interceptor::RequestInterceptor, new_request::NewRequest};
+
+/// Interceptor that gives the blocks of a block-wise POST or PUT a Request-Tag, so that blocks of
+/// one operation can't be mixed up with those of another, see
+/// [RFC 9175](https://datatracker.ietf.org/doc/html/rfc9175#section-3).
+///
+/// Sending block 0 starts an operation with a new tag, the blocks after it reuse the tag of the
+/// operation on the same path. The operation is forgotten once its last block is sent. Requests
+/// without a Block1 option or with a Request-Tag of their own are left as they are, and so are
+/// GETs, which have no body to mix up.
+#[derive(Debug, Default)]
+pub struct RequestTagging {
+    counter: u64,
+    operations: HashMap<Vec<String>, RequestTag>,
+}
+
+impl RequestTagging {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Tag no other operation of this tagging has had.
+    pub fn next_tag(&mut self) -> RequestTag {
+        self.counter += 1;
+        let bytes = self.counter.to_be_bytes();
+        let leading_zeros = (self.counter.leading_zeros() / 8) as usize;
+
+        RequestTag::from_value(bytes[leading_zeros..].to_vec())
+            .expect("Request-Tag of a u64 should be at most 8 bytes")
+    }
+}
+
+impl RequestInterceptor for RequestTagging {
+    fn before_encode(&mut self, request: &mut NewRequest) {
+        if request.request_tag().is_some() {
+            return;
+        }
+        let (block1, path) = match &*request {
+            NewRequest::Post(post) => (post.options.block1(), post.options.uri_path()),
+            NewRequest::Put(put) => (put.options.block1(), put.options.uri_path()),
+            _ => return,
+        };
+        let Some(block) = block1.map(Block1::block) else {
+            return;
+        };
+
+        let (num, more) = (block.num(), block.more());
+        let path = path.map(UriPath::segments).unwrap_or_default();
+        let request_tag = match self.operations.remove(&path) {
+            Some(request_tag) if num != 0 => request_tag,
+            _ => self.next_tag(),
+        };
+        if more {
+            self.operations.insert(path, request_tag.clone());
+        }
+
+        request.set_request_tag(request_tag);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+    use rstest::rstest;
+
+    use crate::codec::{
+        message::{GetOptions, PutOptions},
+        option::{Block1, Block2, RequestTag},
+        Payload,
+    };
+    use crate::protocol::{
+        get::Get, interceptor::RequestInterceptor, new_request::NewRequest, put::Put,
+        reliability::Reliability, transmission_parameters::NonConfirmableParameters,
+    };
+
+    use super::RequestTagging;
+
+    fn request(path: &str, block: std::option::Option<(u32, bool)>) -> NewRequest {
+        let mut options = PutOptions::new();
+        options.set_uri_path(path.try_into().unwrap());
+        if let Some((num, more)) = block {
+            options.set_block1(Block1::new(num, more, 2).unwrap());
+        }
+
+        NewRequest::Put(Put {
+            options,
+            reliability: Reliability::NonConfirmable(NonConfirmableParameters::default()),
+            payload: Payload::from_value(vec![0; 64]),
+        })
+    }
+
+    fn tag(
+        tagging: &mut RequestTagging,
+        mut request: NewRequest,
+    ) -> std::option::Option<RequestTag> {
+        tagging.before_encode(&mut request);
+        request.request_tag().cloned()
+    }
+
+    #[rstest]
+    fn blocks_of_an_operation_share_a_tag() {
+        let mut tagging = RequestTagging::new();
+
+        let first = tag(&mut tagging, request("firmware", Some((0, true))));
+        let second = tag(&mut tagging, request("firmware", Some((1, true))));
+        let other = tag(&mut tagging, request("config", Some((0, true))));
+        let restarted = tag(&mut tagging, request("firmware", Some((0, true))));
+
+        assert_eq!(Some(RequestTag::from_value(vec![1]).unwrap()), first);
+        assert_eq!(first, second);
+        assert_eq!(Some(RequestTag::from_value(vec![2]).unwrap()), other);
+        assert_eq!(Some(RequestTag::from_value(vec![3]).unwrap()), restarted);
+    }
+
+    #[rstest]
+    fn last_block_ends_operation() {
+        let mut tagging = RequestTagging::new();
+
+        let first = tag(&mut tagging, request("firmware", Some((0, true))));
+        let last = tag(&mut tagging, request("firmware", Some((1, false))));
+        let single = tag(&mut tagging, request("config", Some((0, false))));
+
+        assert_eq!(first, last);
+        assert_eq!(Some(RequestTag::from_value(vec![2]).unwrap()), single);
+        assert!(tagging.operations.is_empty());
+    }
+
+    #[rstest]
+    fn requests_without_block1_are_not_tagged() {
+        let mut tagging = RequestTagging::new();
+        let mut options = GetOptions::new();
+        options.set_block2(Block2::new(0, false, 2).unwrap());
+        let get = NewRequest::Get(Get {
+            options,
+            reliability: Reliability::NonConfirmable(NonConfirmableParameters::default()),
+        });
+
+        assert_eq!(None, tag(&mut tagging, request("firmware", None)));
+        assert_eq!(None, tag(&mut tagging, get));
+    }
+}
@@ -0,0 +1,240 @@
+use crate::codec::{
+    option::{uri_query, UriPath, UriQuery},
+    Payload,
+};
+
+use super::{new_request::NewRequest, reliability::Reliability};
+
+/// Request with defaults to stamp out many requests from, like the same reading polled from a
+/// device over and over with one query parameter changing.
+///
+/// Overrides are applied to a clone of the template, the template itself is left as it is.
+///
+/// ```
+/// use coapium::{
+///     codec::message::GetOptions,
+///     protocol::{
+///         get::Get, new_request::NewRequest, reliability::Reliability,
+///         request_template::RequestTemplate, transmission_parameters::NonConfirmableParameters,
+///     },
+/// };
+///
+/// let mut options = GetOptions::new();
+/// options.set_uri_path("sensors/temperature".try_into().unwrap());
+/// let template = RequestTemplate::new(NewRequest::Get(Get {
+///     options,
+///     reliability: Reliability::NonConfirmable(NonConfirmableParameters::default()),
+/// }));
+///
+/// for sensor in ["1", "2"] {
+///     let request: NewRequest = template
+///         .clone()
+///         .with_query_parameter("sensor", sensor)
+///         .unwrap()
+///         .into();
+/// }
+/// ```
+#[derive(Clone, Debug, PartialEq)]
+pub struct RequestTemplate {
+    request: NewRequest,
+}
+
+impl RequestTemplate {
+    pub fn new(request: NewRequest) -> Self {
+        Self { request }
+    }
+
+    /// Request with the defaults of the template.
+    pub fn request(&self) -> NewRequest {
+        self.request.clone()
+    }
+
+    /// Replaces the payload, requests without one are left as they are.
+    pub fn with_payload(mut self, payload: Payload) -> Self {
+        match &mut self.request {
+            NewRequest::Custom(custom) => custom.payload = payload,
+            NewRequest::Post(post) => post.payload = payload,
+            NewRequest::Put(put) => put.payload = payload,
+            NewRequest::Delete(_) | NewRequest::Get(_) | NewRequest::Ping(_) => {}
+        }
+        self
+    }
+
+    /// Replaces the reliability, a ping is always confirmable and only takes confirmable
+    /// parameters.
+    pub fn with_reliability(mut self, reliability: Reliability) -> Self {
+        match &mut self.request {
+            NewRequest::Custom(custom) => custom.reliability = reliability,
+            NewRequest::Delete(delete) => delete.reliability = reliability,
+            NewRequest::Get(get) => get.reliability = reliability,
+            NewRequest::Ping(ping) => {
+                if let Reliability::Confirmable(confirmable_parameters) = reliability {
+                    ping.confirmable_parameters = confirmable_parameters;
+                }
+            }
+            NewRequest::Post(post) => post.reliability = reliability,
+            NewRequest::Put(put) => put.reliability = reliability,
+        }
+        self
+    }
+
+    /// Replaces the Uri-Path option, pings have no options and are left as they are.
+    pub fn with_uri_path(mut self, path: UriPath) -> Self {
+        match &mut self.request {
+            NewRequest::Custom(custom) => custom.options.set_uri_path(path),
+            NewRequest::Delete(delete) => delete.options.set_uri_path(path),
+            NewRequest::Get(get) => get.options.set_uri_path(path),
+            NewRequest::Ping(_) => {}
+            NewRequest::Post(post) => post.options.set_uri_path(path),
+            NewRequest::Put(put) => put.options.set_uri_path(path),
+        }
+        self
+    }
+
+    /// Replaces the whole Uri-Query option, pings have no options and are left as they are.
+    pub fn with_uri_query(mut self, query: UriQuery) -> Self {
+        match &mut self.request {
+            NewRequest::Custom(custom) => custom.options.set_uri_query(query),
+            NewRequest::Delete(delete) => delete.options.set_uri_query(query),
+            NewRequest::Get(get) => get.options.set_uri_query(query),
+            NewRequest::Ping(_) => {}
+            NewRequest::Post(post) => post.options.set_uri_query(query),
+            NewRequest::Put(put) => put.options.set_uri_query(query),
+        }
+        self
+    }
+
+    /// Sets `key=value` in the Uri-Query option, keeping the other query parameters of the
+    /// template, see [`UriQuery::set_key_value`].
+    pub fn with_query_parameter(self, key: &str, value: &str) -> Result<Self, uri_query::Error> {
+        let mut query = self.uri_query().cloned().unwrap_or_else(UriQuery::new);
+        query.set_key_value(key, value)?;
+
+        Ok(self.with_uri_query(query))
+    }
+
+    fn uri_query(&self) -> Option<&UriQuery> {
+        match &self.request {
+            NewRequest::Custom(custom) => custom.options.uri_query(),
+            NewRequest::Delete(delete) => delete.options.uri_query(),
+            NewRequest::Get(get) => get.options.uri_query(),
+            NewRequest::Ping(_) => None,
+            NewRequest::Post(post) => post.options.uri_query(),
+            NewRequest::Put(put) => put.options.uri_query(),
+        }
+    }
+}
+
+impl From<NewRequest> for RequestTemplate {
+    fn from(request: NewRequest) -> Self {
+        Self::new(request)
+    }
+}
+
+impl From<RequestTemplate> for NewRequest {
+    fn from(template: RequestTemplate) -> Self {
+        template.request
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+    use rstest::rstest;
+
+    use crate::{
+        codec::{
+            message::{GetOptions, PostOptions},
+            option::UriQuery,
+            Payload,
+        },
+        protocol::{
+            get::Get,
+            new_request::NewRequest,
+            ping::Ping,
+            post::Post,
+            reliability::Reliability,
+            transmission_parameters::{
+                ConfirmableParameters, InitialRetransmissionFactor, NonConfirmableParameters,
+            },
+        },
+    };
+
+    use super::RequestTemplate;
+
+    fn confirmable_parameters() -> ConfirmableParameters {
+        ConfirmableParameters::default(InitialRetransmissionFactor::new(0.5).unwrap())
+    }
+
+    fn get(query: &[(&str, &str)]) -> NewRequest {
+        let mut options = GetOptions::new();
+        options.set_uri_path("sensors".try_into().unwrap());
+        if !query.is_empty() {
+            let mut uri_query = UriQuery::new();
+            for (key, value) in query {
+                uri_query.add_key_value(key, value).unwrap();
+            }
+            options.set_uri_query(uri_query);
+        }
+
+        NewRequest::Get(Get {
+            options,
+            reliability: Reliability::NonConfirmable(NonConfirmableParameters::default()),
+        })
+    }
+
+    #[rstest]
+    #[case(get(&[]),                       get(&[("id", "2")]))]
+    #[case(get(&[("id", "1"), ("u", "c")]), get(&[("id", "2"), ("u", "c")]))]
+    fn with_query_parameter(#[case] defaults: NewRequest, #[case] expected: NewRequest) {
+        let template = RequestTemplate::new(defaults.clone());
+
+        let request: NewRequest = template
+            .clone()
+            .with_query_parameter("id", "2")
+            .unwrap()
+            .into();
+
+        assert_eq!(expected, request);
+        assert_eq!(defaults, template.request());
+    }
+
+    #[rstest]
+    fn with_payload_and_reliability() {
+        let template = RequestTemplate::new(NewRequest::Post(Post {
+            options: PostOptions::new(),
+            reliability: Reliability::NonConfirmable(NonConfirmableParameters::default()),
+            payload: Payload::from_value(vec![1]),
+        }));
+
+        let request: NewRequest = template
+            .with_payload(Payload::from_value(vec![2]))
+            .with_reliability(Reliability::Confirmable(confirmable_parameters()))
+            .into();
+
+        assert_eq!(
+            NewRequest::Post(Post {
+                options: PostOptions::new(),
+                reliability: Reliability::Confirmable(confirmable_parameters()),
+                payload: Payload::from_value(vec![2]),
+            }),
+            request
+        );
+    }
+
+    #[rstest]
+    fn ping_stays_confirmable() {
+        let ping = NewRequest::Ping(Ping {
+            confirmable_parameters: confirmable_parameters(),
+        });
+
+        let request: NewRequest = RequestTemplate::new(ping.clone())
+            .with_reliability(Reliability::NonConfirmable(
+                NonConfirmableParameters::default(),
+            ))
+            .with_payload(Payload::from_value(vec![1]))
+            .into();
+
+        assert_eq!(ping, request);
+    }
+}
@@ -1,11 +1,68 @@
-use crate::codec::{self, Options, Payload, ResponseCode};
+use std::{io, net::SocketAddr, time::Duration};
+
+use crate::codec::{
+    self,
+    code::response_code::ClientError,
+    option::{ETag, LocationPath},
+    Code, MediaType, MethodCode, Options, Payload, ResponseCode,
+};
 
 #[derive(Clone, Copy, Debug, PartialEq)]
 pub enum Error {
     AcknowledgementTimeout,
+    /// Reading the next block of an uploaded body failed.
+    Body(io::ErrorKind),
     Codec(codec::Error),
+    /// A bug left the request unresolved, like a transaction `"leaked"` past its lifetime.
+    Internal(&'static str),
+    MessageIdExhausted,
+    MessageTooLarge {
+        size: usize,
+        limit: usize,
+    },
+    /// The response code isn't defined for the method of the request, see
+    /// [`ResponseCodePolicy`](super::processor::ResponseCodePolicy).
+    ProtocolViolation {
+        method: MethodCode,
+        response_code: ResponseCode,
+    },
+    QueueFull,
     Reset,
+    Shutdown,
     Timeout,
+    TokenGeneration,
+    TransactionLimit,
+    /// Sending the request or receiving from the peer failed.
+    Transport(io::ErrorKind),
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct Metrics {
+    pub bytes_received: usize,
+    pub bytes_sent: usize,
+    /// Address that answered, filled in by the client since the processor doesn't know about
+    /// addresses.
+    pub endpoint: Option<SocketAddr>,
+    pub retransmissions: u8,
+    pub round_trip_time: Duration,
+    pub time_to_acknowledgement: Option<Duration>,
+}
+
+/// Something off about a response that still resolved the request.
+#[derive(Clone, Debug, PartialEq)]
+pub enum Warning {
+    /// The server answered with a Content-Format other than the one the request asked for with
+    /// Accept, or didn't tag the payload at all.
+    ContentFormatMismatch {
+        accept: MediaType,
+        content_format: Option<MediaType>,
+    },
+    /// The response code isn't defined for the method of the request, like 2.01 (Created) in
+    /// reply to a GET.
+    ProtocolViolation {
+        method: MethodCode,
+        response_code: ResponseCode,
+    },
 }
 
 #[derive(Clone, Debug, PartialEq)]
@@ -13,6 +70,93 @@ pub struct Response {
     pub response_code: ResponseCode,
     pub options: Options, // ResponseOptions
     pub payload: Payload,
+    pub metrics: Metrics,
+    pub(crate) warnings: Vec<Warning>,
+}
+
+/// Response to a request with content negotiation, tagged with the content format the server
+/// picked.
+#[derive(Clone, Debug, PartialEq)]
+pub struct TypedResponse {
+    pub content_format: Option<MediaType>,
+    pub response: Response,
+}
+
+impl Response {
+    /// Human-readable text carried by a client or server error response, like the options a 4.02
+    /// (Bad Option) complains about, `None` if the payload isn't diagnostic text.
+    pub fn diagnostic(&self) -> Option<&str> {
+        codec::message::response::diagnostic(self.response_code, &self.options, &self.payload)
+    }
+
+    /// Whether notifications follow the response, as it's a success carrying an Observe option,
+    /// RFC 7641 §3.2.
+    pub fn continues_observation(&self) -> bool {
+        self.response_code.is_success() && self.options.observe().is_some()
+    }
+
+    /// Media type of the payload, `None` when the response carries no Content-Format.
+    pub fn content_format(&self) -> Option<MediaType> {
+        self.options.content_media_type()
+    }
+
+    pub fn etag(&self) -> Option<&ETag> {
+        self.options.etag()
+    }
+
+    pub fn is_not_acceptable(&self) -> bool {
+        self.response_code == ResponseCode::ClientError(ClientError::NotAcceptable)
+    }
+
+    /// Location-Path and Location-Query of a 2.01 (Created) joined into a relative URI, e.g.
+    /// `/rd/4521`.
+    pub fn location(&self) -> Option<String> {
+        self.options.location()
+    }
+
+    pub fn location_path(&self) -> Option<&LocationPath> {
+        self.options.location_path()
+    }
+
+    /// How long the response may be cached, `None` when the server left out Max-Age and its
+    /// default of 60 seconds applies.
+    pub fn max_age(&self) -> Option<Duration> {
+        self.options.max_age_duration()
+    }
+
+    /// Payload deserialized from CBOR, regardless of the Content-Format the server tagged it with.
+    #[cfg(feature = "cbor")]
+    pub fn payload_as_cbor<T: serde::de::DeserializeOwned>(
+        &self,
+    ) -> Result<T, codec::payload::Error> {
+        self.payload.to_cbor()
+    }
+
+    /// Size hint sent with 4.13 (Request Entity Too Large), the largest request body the server is
+    /// able to process.
+    pub fn size1(&self) -> Option<u32> {
+        self.options.size1().map(|size1| size1.value())
+    }
+
+    pub fn warnings(&self) -> &[Warning] {
+        &self.warnings
+    }
+
+    /// Warns when a successful response carries a payload in another content format than
+    /// `accept`, the server is free to do so but the caller likely can't handle it.
+    pub(crate) fn check_accept(&mut self, accept: MediaType) {
+        if !matches!(self.response_code, ResponseCode::Success(_)) || self.payload.is_empty() {
+            return;
+        }
+
+        let content_format = self.options.content_media_type();
+        if content_format.as_ref() != Some(&accept) {
+            self.warnings.push(Warning::ContentFormatMismatch {
+                accept,
+                content_format,
+            });
+        }
+    }
 }
 
 impl From<codec::Response> for Response {
@@ -21,6 +165,194 @@ impl From<codec::Response> for Response {
             response_code: value.response_code(),
             options: value.options().clone(),
             payload: value.payload().clone(),
+            metrics: Metrics::default(),
+            warnings: vec![],
         }
     }
 }
+
+impl From<Response> for TypedResponse {
+    fn from(response: Response) -> Self {
+        Self {
+            content_format: response.content_format(),
+            response,
+        }
+    }
+}
+
+impl std::fmt::Display for Error {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Error::AcknowledgementTimeout => write!(f, "timed out waiting for acknowledgement"),
+            Error::Body(kind) => write!(f, "failed to read the body: {kind}"),
+            Error::Codec(error) => write!(f, "failed to decode response: {error}"),
+            Error::Internal(reason) => write!(f, "internal error: {reason}"),
+            Error::MessageIdExhausted => write!(f, "every message id is in use"),
+            Error::MessageTooLarge { size, limit } => {
+                write!(
+                    f,
+                    "message of {size} bytes exceeds the limit of {limit} bytes"
+                )
+            }
+            Error::ProtocolViolation {
+                method,
+                response_code,
+            } => write!(
+                f,
+                "{} response is not defined for a {} request",
+                Code::Response(*response_code),
+                Code::Request(*method)
+            ),
+            Error::QueueFull => write!(f, "too many requests are waiting to be sent"),
+            Error::Reset => write!(f, "request was reset by the server"),
+            Error::Shutdown => write!(f, "client has shut down"),
+            Error::Timeout => write!(f, "request timed out"),
+            Error::TokenGeneration => write!(f, "failed to generate a token for the request"),
+            Error::TransactionLimit => write!(f, "too many requests are in flight"),
+            Error::Transport(kind) => write!(f, "transport failed: {kind}"),
+        }
+    }
+}
+
+impl std::error::Error for Error {}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use pretty_assertions::assert_eq;
+    use rstest::rstest;
+
+    use crate::codec::{
+        self,
+        code::response_code::{ClientError, Success},
+        option::{ETag, LocationPath, MaxAge, Number},
+        MediaType, Options, Payload, ResponseCode,
+    };
+
+    use super::{Metrics, Response, TypedResponse, Warning};
+
+    fn response(response_code: ResponseCode, options: Options) -> Response {
+        Response {
+            response_code,
+            options,
+            payload: Payload::empty(),
+            metrics: Metrics::default(),
+            warnings: vec![],
+        }
+    }
+
+    #[rstest]
+    fn diagnostic() {
+        let bad_option =
+            Response::from(codec::Response::bad_option(&[Number::from_value_or_panic(
+                65001,
+            )]));
+
+        assert_eq!(Some("Unrecognized options: 65001"), bad_option.diagnostic());
+        assert_eq!(
+            None,
+            response(
+                ResponseCode::ClientError(ClientError::BadOption),
+                Options::new()
+            )
+            .diagnostic()
+        );
+    }
+
+    #[rstest]
+    fn option_helpers() {
+        let mut options = Options::new();
+        options.set_content_format(MediaType::ApplicationJson.into());
+        options.set_etag(ETag::from_values(vec![vec![1, 2]]).unwrap());
+        options.set_location_path(LocationPath::from_value("/rd/4521").unwrap());
+        options.set_max_age(MaxAge::from(30));
+        let response = response(ResponseCode::Success(Success::Created), options.clone());
+
+        assert_eq!(Some(MediaType::ApplicationJson), response.content_format());
+        assert_eq!(options.etag(), response.etag());
+        assert_eq!(Some("/rd/4521".to_string()), response.location());
+        assert_eq!(options.location_path(), response.location_path());
+        assert_eq!(Some(Duration::from_secs(30)), response.max_age());
+    }
+
+    #[rstest]
+    fn option_helpers_without_options() {
+        let response = response(ResponseCode::Success(Success::Content), Options::new());
+
+        assert_eq!(None, response.content_format());
+        assert_eq!(None, response.etag());
+        assert_eq!(None, response.location());
+        assert_eq!(None, response.max_age());
+    }
+
+    #[rstest]
+    #[case(ResponseCode::ClientError(ClientError::NotAcceptable), true)]
+    #[case(ResponseCode::ClientError(ClientError::NotFound), false)]
+    #[case(ResponseCode::Success(Success::Content), false)]
+    fn is_not_acceptable(#[case] response_code: ResponseCode, #[case] expected: bool) {
+        assert_eq!(
+            expected,
+            response(response_code, Options::new()).is_not_acceptable()
+        );
+    }
+
+    #[rstest]
+    #[case(Options::new(), None)]
+    #[case(
+        {
+            let mut options = Options::new();
+            options.set_content_format(MediaType::ApplicationJson.into());
+            options
+        },
+        Some(MediaType::ApplicationJson)
+    )]
+    fn typed_response_from_response(#[case] options: Options, #[case] expected: Option<MediaType>) {
+        let response = response(ResponseCode::Success(Success::Content), options);
+
+        assert_eq!(
+            TypedResponse {
+                content_format: expected,
+                response: response.clone(),
+            },
+            response.into()
+        );
+    }
+
+    #[cfg(feature = "cbor")]
+    #[rstest]
+    fn payload_as_cbor() {
+        let response = Response {
+            payload: Payload::from_cbor(&vec![1u8, 2, 3]).unwrap(),
+            ..response(ResponseCode::Success(Success::Content), Options::new())
+        };
+
+        assert_eq!(Ok(vec![1u8, 2, 3]), response.payload_as_cbor());
+    }
+
+    #[rstest]
+    #[case(ResponseCode::Success(Success::Content), Some(MediaType::ApplicationJson), b"{}".to_vec(), vec![])]
+    #[case(ResponseCode::Success(Success::Content), Some(MediaType::TextPlain), b"{}".to_vec(), vec![Warning::ContentFormatMismatch { accept: MediaType::ApplicationJson, content_format: Some(MediaType::TextPlain) }])]
+    #[case(ResponseCode::Success(Success::Content), None, b"{}".to_vec(), vec![Warning::ContentFormatMismatch { accept: MediaType::ApplicationJson, content_format: None }])]
+    #[case(ResponseCode::Success(Success::Changed), None, vec![], vec![])]
+    #[case(ResponseCode::ClientError(ClientError::NotFound), Some(MediaType::TextPlain), b"gone".to_vec(), vec![])]
+    fn check_accept(
+        #[case] response_code: ResponseCode,
+        #[case] content_format: Option<MediaType>,
+        #[case] payload: Vec<u8>,
+        #[case] expected: Vec<Warning>,
+    ) {
+        let mut options = Options::new();
+        if let Some(content_format) = content_format {
+            options.set_content_format(content_format.into());
+        }
+        let mut response = Response {
+            payload: Payload::from_value(payload),
+            ..response(response_code, options)
+        };
+
+        response.check_accept(MediaType::ApplicationJson);
+
+        assert_eq!(expected, response.warnings());
+    }
+}
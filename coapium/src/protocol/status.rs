@@ -0,0 +1,45 @@
+use std::time::{Duration, Instant};
+
+/// Where a request stands on its way to being resolved.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum State {
+    /// Waiting for capacity or a message id before it's sent.
+    Queued,
+    /// Sent `attempt` times, 1 being the first transmission, and not acknowledged yet.
+    Transmitted { attempt: u8 },
+    /// Acknowledged by the peer, the response is yet to come.
+    Acknowledged,
+    /// Resolved, or never requested.
+    Resolved,
+}
+
+/// Snapshot of a request, for showing the progress of a request that may take minutes to resolve.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Status {
+    pub state: State,
+    /// When the request is due to be sent again, or given up on after the last retransmission.
+    /// `None` when it isn't sent again, like once it's acknowledged.
+    pub next_retransmission: Option<Instant>,
+}
+
+impl Status {
+    pub fn queued() -> Self {
+        Self {
+            state: State::Queued,
+            next_retransmission: None,
+        }
+    }
+
+    pub fn resolved() -> Self {
+        Self {
+            state: State::Resolved,
+            next_retransmission: None,
+        }
+    }
+
+    /// Time left until the next retransmission, zero if it's overdue.
+    pub fn time_to_retransmission(&self, now: Instant) -> Option<Duration> {
+        self.next_retransmission
+            .map(|next_retransmission| next_retransmission.saturating_duration_since(now))
+    }
+}
@@ -2,8 +2,11 @@ use std::time::Duration;
 
 use crate::codec::MessageId;
 
-use super::transmission_parameters::{
-    ConfirmableParameters, NonConfirmableParameters, ProbingRatePerSecond,
+use super::{
+    backoff::{BackoffStrategy, ExponentialBackoff},
+    transmission_parameters::{
+        ConfirmableParameters, NonConfirmableParameters, ProbingRatePerSecond,
+    },
 };
 
 #[derive(Clone, Copy, Debug, PartialEq)]
@@ -33,15 +36,16 @@ pub struct RetransmissionTimeout {
 
 impl RetransmissionTimeout {
     pub fn new(message_id: MessageId, confirmable_parameters: &ConfirmableParameters) -> Self {
-        let variable_range =
-            confirmable_parameters.max_ack_timeout() - confirmable_parameters.min_ack_timeout();
-
-        let range = variable_range.mul_f32(confirmable_parameters.initial_retransmission_factor());
-
-        let timeout = confirmable_parameters.min_ack_timeout() + range;
+        Self::with_strategy(message_id, confirmable_parameters, &ExponentialBackoff)
+    }
 
+    pub fn with_strategy(
+        message_id: MessageId,
+        confirmable_parameters: &ConfirmableParameters,
+        strategy: &dyn BackoffStrategy,
+    ) -> Self {
         Self {
-            timeout,
+            timeout: strategy.initial(confirmable_parameters),
             message_id,
         }
     }
@@ -54,8 +58,12 @@ impl RetransmissionTimeout {
     }
 
     pub fn next(self) -> Self {
+        self.next_with(&ExponentialBackoff, 0)
+    }
+
+    pub fn next_with(self, strategy: &dyn BackoffStrategy, attempt: u8) -> Self {
         Self {
-            timeout: self.timeout * 2,
+            timeout: strategy.next(self.timeout, attempt),
             ..self
         }
     }
@@ -105,7 +113,7 @@ impl NonRetransmissionTimeout {
         probing_rate_per_second: &ProbingRatePerSecond,
     ) -> Self {
         Self {
-            timeout: Duration::from_secs_f32(probing_rate_per_second.value() * data_len as f32),
+            timeout: Duration::from_secs_f32(data_len as f32 / probing_rate_per_second.value()),
             message_id: *message_id,
         }
     }
@@ -1,8 +1,11 @@
 use std::time::Instant;
 
+use smallvec::smallvec;
+
 use crate::{
     codec::{MessageId, Token},
     protocol::{
+        backoff::BackoffStrategy,
         effect::{Effect, Effects},
         new_request::NewRequest,
         response,
@@ -13,9 +16,11 @@ use crate::{
 
 #[derive(Clone, Debug, PartialEq)]
 pub struct ConfirmableTransaction {
-    pub acknowledged: bool,
+    pub acknowledged_at: Option<Instant>,
     pub created_at: Instant,
     pub message_id: MessageId,
+    /// When the retransmission timeout last scheduled is due.
+    pub next_retransmission: Option<Instant>,
     pub request_data: Vec<u8>,
     pub retransmission_counter: u8,
     pub token: Token,
@@ -28,47 +33,46 @@ impl ConfirmableTransaction {
         token: Token,
         request: NewRequest,
         parameters: ConfirmableParameters,
+        now: Instant,
     ) -> Self {
         Self {
-            acknowledged: false,
-            created_at: Instant::now(),
+            acknowledged_at: None,
+            created_at: now,
             message_id,
-            request_data: request.encode(message_id, token.clone()),
+            next_retransmission: None,
+            request_data: request.encode(message_id, token),
             retransmission_counter: 0,
             token,
             transaction_parameters: parameters,
         }
     }
 
-    pub fn on_max_transmit_wait(&self) -> Result<Effects, Effects> {
-        if self.acknowledged {
-            return Ok(vec![]);
+    /// Gives up with [`response::Error::Timeout`] unless the request was acknowledged.
+    pub fn on_max_transmit_wait(&self) -> Result<Effects, response::Error> {
+        if self.is_acknowledged() {
+            return Ok(smallvec![]);
         }
 
-        Err(vec![Effect::TransactionResolved(
-            self.token.clone(),
-            Err(response::Error::Timeout),
-        )])
+        Err(response::Error::Timeout)
     }
 
     pub fn retransmit(
         &mut self,
         timeout: RetransmissionTimeout,
-    ) -> Result<Vec<Effect>, Vec<Effect>> {
-        if self.acknowledged {
-            return Ok(vec![]);
+        strategy: &dyn BackoffStrategy,
+    ) -> Result<Effects, response::Error> {
+        if self.is_acknowledged() {
+            return Ok(smallvec![]);
         }
 
         if !self.can_retransmit() {
-            return Err(vec![Effect::TransactionResolved(
-                self.token.clone(),
-                Err(response::Error::Timeout),
-            )]);
+            return Err(response::Error::Timeout);
         }
 
+        let next_timeout = timeout.next_with(strategy, self.retransmission_counter);
         self.retransmission_counter += 1;
-        Ok(vec![
-            timeout.next().into(),
+        Ok(smallvec![
+            next_timeout.into(),
             Effect::Transmit(self.request_data.clone()),
         ])
     }
@@ -77,20 +81,29 @@ impl ConfirmableTransaction {
         self.retransmission_counter < self.transaction_parameters.max_retransmit()
     }
 
-    pub fn acknowledged(&mut self) {
-        self.acknowledged = true
+    pub fn acknowledged(&mut self, now: Instant) {
+        if self.acknowledged_at.is_none() {
+            self.acknowledged_at = Some(now)
+        }
     }
 
-    pub fn initial_effects(&self) -> Effects {
-        let retransmission_timeout =
-            RetransmissionTimeout::new(self.message_id, &self.transaction_parameters);
+    pub fn is_acknowledged(&self) -> bool {
+        self.acknowledged_at.is_some()
+    }
+
+    pub fn initial_effects(&self, strategy: &dyn BackoffStrategy) -> Effects {
+        let retransmission_timeout = RetransmissionTimeout::with_strategy(
+            self.message_id,
+            &self.transaction_parameters,
+            strategy,
+        );
 
         let exchange_lifetime_timeout =
             ExchangeLifetimeTimeout::new(self.message_id, &self.transaction_parameters);
 
         let transmit = Effect::Transmit(self.request_data.clone());
 
-        vec![
+        smallvec![
             exchange_lifetime_timeout.into(),
             retransmission_timeout.into(),
             transmit,
@@ -101,6 +114,7 @@ impl ConfirmableTransaction {
 // TODO: Review these tests, muddy test cases
 #[cfg(test)]
 mod tests {
+    use std::time::Instant;
 
     use pretty_assertions::assert_eq;
     use rstest::rstest;
@@ -108,6 +122,7 @@ mod tests {
     use crate::{
         codec::{message::GetOptions, Token},
         protocol::{
+            backoff::ExponentialBackoff,
             effect::Effect,
             get::Get,
             new_request::NewRequest,
@@ -134,9 +149,10 @@ mod tests {
                 )),
             }),
             ConfirmableParameters::default(InitialRetransmissionFactor::new(0.5).unwrap()),
+            Instant::now(),
         );
 
-        let effects = transaction.initial_effects();
+        let effects = transaction.initial_effects(&ExponentialBackoff).into_vec();
         let expected_effects = vec![
             ExchangeLifetimeTimeout::new(transaction.message_id, &confirmable_parameters).into(),
             RetransmissionTimeout::new(transaction.message_id, &confirmable_parameters).into(),
@@ -160,9 +176,10 @@ mod tests {
                 )),
             }),
             ConfirmableParameters::default(InitialRetransmissionFactor::new(0.5).unwrap()),
+            Instant::now(),
         );
 
-        let effects = transaction.initial_effects();
+        let effects = transaction.initial_effects(&ExponentialBackoff).into_vec();
         let expected_effects = vec![
             ExchangeLifetimeTimeout::new(transaction.message_id, &confirmable_parameters).into(),
             RetransmissionTimeout::new(transaction.message_id, &confirmable_parameters).into(),
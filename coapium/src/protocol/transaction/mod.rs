@@ -3,32 +3,34 @@ pub mod non_con;
 
 use std::time::{Duration, Instant};
 
-use crate::codec::{MessageId, Token};
+use crate::codec::{message::Message, option::Accept, Code, Header, MessageId, MethodCode, Token};
 
 use self::{con::ConfirmableTransaction, non_con::NonConfirmableTransacation};
 
 use super::{
-    effect::{Effect, Effects},
+    backoff::BackoffStrategy,
+    effect::{Effect, Effects, Timeout},
     new_request::NewRequest,
     reliability::Reliability,
-    response,
+    response::{self, Metrics},
+    status::{State, Status},
 };
 
-pub const ACK_RANDOM_FACTOR: f32 = 1.5;
-pub const ACK_TIMEOUT: Duration = Duration::from_secs(2);
-pub const DEFAULT_LEISURE: Duration = Duration::from_secs(5);
-pub const EXCHANGE_LIFETIME: Duration = Duration::from_secs(247);
-pub const MAX_LATENCY: Duration = Duration::from_secs(100);
-pub const MAX_RETRANSMIT: u8 = 4;
-pub const MAX_RTT: Duration = Duration::from_secs(202);
-pub const MAX_TRANSMIT: u8 = 4;
-pub const MAX_TRANSMIT_SPAN: Duration = Duration::from_secs(45);
-pub const MAX_TRANSMIT_WAIT: Duration = Duration::from_secs(93);
-pub const NON_LIFETIME: Duration = Duration::from_secs(145);
-pub const NSTART: usize = 1;
 pub const PATH_MTU: usize = 1152;
-pub const PROBING_RATE_PER_SECOND: u8 = 1;
-pub const PROCESSING_DELAY: Duration = Duration::from_secs(2);
+/// Largest payload of a UDP datagram, over IPv6.
+pub const MAX_DATAGRAM_SIZE: usize = 65_527;
+/// How long a transaction may outlive its lifetime before it's evicted as leaked.
+pub const LEAK_MARGIN: Duration = Duration::from_secs(60);
+
+/// When the retransmission timeout among `effects` is due, `None` if there is none.
+pub fn next_retransmission(effects: &Effects, now: Instant) -> Option<Instant> {
+    effects.iter().find_map(|effect| match effect {
+        Effect::CreateTimeout(
+            timeout @ (Timeout::Retransmission(_) | Timeout::NonRetransmission(_)),
+        ) => Some(now + *timeout.duration()),
+        _ => None,
+    })
+}
 
 #[derive(Debug)]
 pub enum Transaction {
@@ -37,13 +39,13 @@ pub enum Transaction {
 }
 
 impl Transaction {
-    pub fn new(message_id: MessageId, token: Token, request: NewRequest) -> Self {
+    pub fn new(message_id: MessageId, token: Token, request: NewRequest, now: Instant) -> Self {
         match request.reliability() {
             Reliability::Confirmable(parameters) => Transaction::Confirmable(
-                ConfirmableTransaction::new(message_id, token, request, parameters),
+                ConfirmableTransaction::new(message_id, token, request, parameters, now),
             ),
             Reliability::NonConfirmable(parameters) => Transaction::NonConfirmable(
-                NonConfirmableTransacation::new(message_id, token, request, parameters),
+                NonConfirmableTransacation::new(message_id, token, request, parameters, now),
             ),
         }
     }
@@ -51,7 +53,7 @@ impl Transaction {
     pub fn increment_retransmit_counter(&mut self) {
         match self {
             Transaction::Confirmable(t) => t.retransmission_counter += 1,
-            Transaction::NonConfirmable(_t) => {}
+            Transaction::NonConfirmable(t) => t.retransmission_counter += 1,
         }
     }
 
@@ -62,6 +64,15 @@ impl Transaction {
         }
     }
 
+    /// How long after it was created the transaction is resolved at the latest, by its exchange or
+    /// non-lifetime timeout.
+    pub fn lifetime(&self) -> Duration {
+        match self {
+            Transaction::Confirmable(t) => t.transaction_parameters.exchange_lifetime(),
+            Transaction::NonConfirmable(t) => t.transaction_parameters.non_lifetime(),
+        }
+    }
+
     pub fn request_data(&self) -> &[u8] {
         match self {
             Transaction::Confirmable(t) => &t.request_data,
@@ -71,7 +82,7 @@ impl Transaction {
 
     pub fn is_acknowledged(&self) -> bool {
         match self {
-            Transaction::Confirmable(t) => t.acknowledged,
+            Transaction::Confirmable(t) => t.is_acknowledged(),
             Transaction::NonConfirmable(_) => false,
         }
     }
@@ -82,16 +93,101 @@ impl Transaction {
         }
     }
 
+    pub fn metrics(&self, bytes_received: usize, now: Instant) -> Metrics {
+        let time_to_acknowledgement = match self {
+            Transaction::Confirmable(t) => t
+                .acknowledged_at
+                .map(|acknowledged_at| acknowledged_at.duration_since(t.created_at)),
+            Transaction::NonConfirmable(_) => None,
+        };
+
+        Metrics {
+            bytes_received,
+            bytes_sent: self.request_data().len() * (1 + self.retransmit_counter() as usize),
+            endpoint: None,
+            retransmissions: self.retransmit_counter(),
+            round_trip_time: now.duration_since(self.created_at()),
+            time_to_acknowledgement,
+        }
+    }
+
     pub fn message_id(&self) -> MessageId {
         match self {
             Transaction::Confirmable(t) => t.message_id,
             Transaction::NonConfirmable(t) => t.message_id,
         }
     }
+    /// Accept option of the request as it was sent.
+    pub fn accept(&self) -> Option<Accept> {
+        let Ok(Message::Request(request)) = Message::decode(self.request_data()) else {
+            return None;
+        };
+
+        request.accept().cloned()
+    }
+
+    /// Method of the request as it was sent, `None` for pings.
+    pub fn method(&self) -> Option<MethodCode> {
+        match Header::parse(self.request_data()) {
+            Ok((_, header)) => match header.code() {
+                Code::Request(method) => Some(method),
+                _ => None,
+            },
+            Err(_) => None,
+        }
+    }
+
+    /// The request as it was sent, decoded from the request data. `None` for pings.
+    pub fn request(&self) -> Option<NewRequest> {
+        let Ok(Message::Request(request)) = Message::decode(self.request_data()) else {
+            return None;
+        };
+
+        let reliability = match self {
+            Transaction::Confirmable(t) => Reliability::Confirmable(t.transaction_parameters),
+            Transaction::NonConfirmable(t) => Reliability::NonConfirmable(t.transaction_parameters),
+        };
+
+        Some(NewRequest::from_request_with_reliability(
+            request,
+            reliability,
+        ))
+    }
+
+    /// Remembers when the retransmission timeout among `effects`, if any, is due.
+    pub fn schedule_retransmission(&mut self, effects: &Effects, now: Instant) {
+        let next_retransmission = next_retransmission(effects, now);
+        match self {
+            Transaction::Confirmable(t) => t.next_retransmission = next_retransmission,
+            Transaction::NonConfirmable(t) => t.next_retransmission = next_retransmission,
+        }
+    }
+
+    pub fn status(&self) -> Status {
+        if self.is_acknowledged() {
+            return Status {
+                state: State::Acknowledged,
+                next_retransmission: None,
+            };
+        }
+
+        let next_retransmission = match self {
+            Transaction::Confirmable(t) => t.next_retransmission,
+            Transaction::NonConfirmable(t) => t.next_retransmission,
+        };
+
+        Status {
+            state: State::Transmitted {
+                attempt: self.retransmit_counter() + 1,
+            },
+            next_retransmission,
+        }
+    }
+
     pub fn retransmit_counter(&self) -> u8 {
         match self {
             Transaction::Confirmable(t) => t.retransmission_counter,
-            Transaction::NonConfirmable(_t) => 0,
+            Transaction::NonConfirmable(t) => t.retransmission_counter,
         }
     }
 
@@ -111,16 +207,16 @@ impl Transaction {
         Effect::TransactionResolved(token, Err(response::Error::Timeout))
     }
 
-    pub fn acknowledged(&mut self) {
+    pub fn acknowledged(&mut self, now: Instant) {
         match self {
-            Self::Confirmable(transcation) => transcation.acknowledged(),
+            Self::Confirmable(transcation) => transcation.acknowledged(now),
             _ => {}
         }
     }
 
-    pub fn initial_effects(&self) -> Effects {
+    pub fn initial_effects(&self, strategy: &dyn BackoffStrategy) -> Effects {
         match self {
-            Self::Confirmable(transaction) => transaction.initial_effects(),
+            Self::Confirmable(transaction) => transaction.initial_effects(strategy),
             Self::NonConfirmable(transaction) => transaction.initial_effects(),
         }
     }
@@ -1,10 +1,13 @@
 use crate::protocol::{
     new_request::NewRequest,
+    response,
     timeout::{NonLifetimeTimeout, NonRetransmissionTimeout},
     transmission_parameters::NonConfirmableParameters,
 };
 use std::time::Instant;
 
+use smallvec::smallvec;
+
 use crate::{
     codec::{MessageId, Token},
     protocol::effect::{Effect, Effects},
@@ -15,7 +18,10 @@ pub struct NonConfirmableTransacation {
     pub created_at: Instant,
     pub token: Token,
     pub message_id: MessageId,
+    /// When the retransmission timeout last scheduled is due.
+    pub next_retransmission: Option<Instant>,
     pub request_data: Vec<u8>,
+    pub retransmission_counter: u8,
     pub transaction_parameters: NonConfirmableParameters,
 }
 
@@ -25,26 +31,39 @@ impl NonConfirmableTransacation {
         token: Token,
         request: NewRequest,
         transaction_parameters: NonConfirmableParameters,
+        now: Instant,
     ) -> Self {
         Self {
-            created_at: Instant::now(),
+            created_at: now,
             message_id,
-            request_data: request.encode(message_id, token.clone()),
+            next_retransmission: None,
+            request_data: request.encode(message_id, token),
+            retransmission_counter: 0,
             token,
             transaction_parameters,
         }
     }
 
-    pub fn retransmit(&mut self) -> Result<Vec<Effect>, Vec<Effect>> {
-        if let Some(timeout) = self.timeout() {
-            Ok(vec![timeout.into()])
-        } else {
-            Ok(vec![])
+    /// Sends the request again, paced by the probing rate, until it has been sent `MAX_RETRANSMIT`
+    /// more times. Resolves the transaction with a timeout after the last retransmission.
+    pub fn retransmit(&mut self) -> Result<Effects, response::Error> {
+        let Some(timeout) = self.timeout() else {
+            return Ok(smallvec![]);
+        };
+
+        if self.retransmission_counter >= self.transaction_parameters.max_retransmit() {
+            return Err(response::Error::Timeout);
         }
+
+        self.retransmission_counter += 1;
+        Ok(smallvec![
+            timeout.into(),
+            Effect::Transmit(self.request_data.clone()),
+        ])
     }
 
     pub fn initial_effects(&self) -> Effects {
-        let mut effects = vec![];
+        let mut effects = smallvec![];
 
         effects
             .push(NonLifetimeTimeout::new(&self.message_id, &self.transaction_parameters).into());
@@ -1,72 +1,156 @@
+use std::{
+    collections::{BTreeSet, HashMap},
+    fmt::Debug,
+    time::Instant,
+};
+
+use log::warn;
+
 use crate::codec::{MessageId, Token};
 
-use super::transaction::{Transaction, NSTART};
+use super::{
+    profile::TransmissionProfile,
+    transaction::{Transaction, LEAK_MARGIN},
+};
+
+/// Told about every transaction evicted as leaked, see [`TransactionStore::evict_leaked`].
+pub trait EvictionHook: Debug + Send {
+    fn on_evicted(&mut self, transaction: &Transaction);
+}
 
+/// In-flight transactions, indexed by token and by message id.
 #[derive(Debug)]
 pub struct TransactionStore {
+    eviction_hooks: Vec<Box<dyn EvictionHook>>,
+    /// Message ids by the time their transaction counts as leaked, earliest first, so finding the
+    /// leaked ones doesn't take a look at every transaction.
+    leak_deadlines: BTreeSet<(Instant, u16)>,
     nstart: usize,
-    transactions: Vec<Transaction>,
+    tokens: HashMap<MessageId, Token>,
+    transactions: HashMap<Token, Transaction>,
 }
 
 impl TransactionStore {
     pub fn new(nstart: usize) -> Self {
         Self {
+            eviction_hooks: vec![],
+            leak_deadlines: BTreeSet::new(),
             nstart,
-            transactions: vec![],
+            tokens: HashMap::new(),
+            transactions: HashMap::new(),
         }
     }
 
+    /// Changes how many exchanges may be in flight at once, transactions already in flight stay.
+    pub fn set_nstart(&mut self, nstart: usize) {
+        self.nstart = nstart;
+    }
+
     pub fn count(&self) -> usize {
         self.transactions.len()
     }
 
+    /// Adds the transaction, replacing any transaction with the same token.
     pub fn add(&mut self, transaction: Transaction) {
-        self.transactions.push(transaction);
+        let token = *transaction.token();
+        let message_id = transaction.message_id();
+        self.leak_deadlines
+            .insert(Self::leak_deadline(&transaction));
+        if let Some(replaced) = self.transactions.insert(token, transaction) {
+            self.tokens.remove(&replaced.message_id());
+            self.leak_deadlines.remove(&Self::leak_deadline(&replaced));
+        }
+        self.tokens.insert(message_id, token);
+    }
+
+    pub fn add_eviction_hook(&mut self, hook: Box<dyn EvictionHook>) {
+        self.eviction_hooks.push(hook);
+    }
+
+    /// Removes the transactions that outlived their lifetime by more than [`LEAK_MARGIN`]. Those
+    /// should have been resolved by their lifetime timeout, so they are leaked by a bug and would
+    /// otherwise be held on to for good.
+    ///
+    /// Only the transactions past their deadline are looked at, so it's cheap to call on every
+    /// event.
+    pub fn evict_leaked(&mut self, now: Instant) -> Vec<Transaction> {
+        let mut leaked = vec![];
+        while let Some(&(deadline, message_id)) = self.leak_deadlines.first() {
+            if deadline >= now {
+                break;
+            }
+            self.leak_deadlines.pop_first();
+            leaked.extend(self.remove_by_message_id(&MessageId::from_value(message_id)));
+        }
+
+        for transaction in &leaked {
+            warn!(
+                "Evicting leaked transaction with message id {} created {:?} ago",
+                transaction.message_id().value(),
+                now.saturating_duration_since(transaction.created_at())
+            );
+            for hook in &mut self.eviction_hooks {
+                hook.on_evicted(transaction);
+            }
+        }
+
+        leaked
+    }
+
+    /// Gives memory held on to after a burst of traffic back to the allocator.
+    pub fn compact(&mut self) {
+        self.tokens.shrink_to_fit();
+        self.transactions.shrink_to_fit();
     }
 
     pub fn find_by_message_id(&mut self, message_id: &MessageId) -> Option<&Transaction> {
-        self.transactions
-            .iter()
-            .find(|t| t.message_id() == *message_id)
+        let token = self.tokens.get(message_id)?;
+        self.transactions.get(token)
     }
 
     pub fn find_mut_by_message_id(&mut self, message_id: &MessageId) -> Option<&mut Transaction> {
-        self.transactions
-            .iter_mut()
-            .find(|t| t.message_id() == *message_id)
+        let token = self.tokens.get(message_id)?;
+        self.transactions.get_mut(token)
     }
 
-    pub fn find_by_token(&mut self, token: &Token) -> Option<&Transaction> {
-        self.transactions.iter().find(|t| t.token() == token)
+    pub fn find_by_token(&self, token: &Token) -> Option<&Transaction> {
+        self.transactions.get(token)
     }
 
     pub fn exists_by_token(&mut self, token: &Token) -> bool {
-        self.find_by_token(token).is_some()
+        self.transactions.contains_key(token)
     }
 
-    pub fn remove_by_message_id(&mut self, message_id: &MessageId) -> Option<Transaction> {
-        let Some(position) = self
-            .transactions
-            .iter()
-            .position(Self::compare_message_id(message_id))
-        else {
-            return None;
-        };
+    pub fn message_ids(&self) -> Vec<MessageId> {
+        self.tokens.keys().copied().collect()
+    }
 
-        Some(self.transactions.swap_remove(position))
+    pub fn remove_by_message_id(&mut self, message_id: &MessageId) -> Option<Transaction> {
+        let token = self.tokens.remove(message_id)?;
+        let transaction = self.transactions.remove(&token)?;
+        self.leak_deadlines
+            .remove(&Self::leak_deadline(&transaction));
+        Some(transaction)
     }
 
     pub fn remove_by_token(&mut self, token: &Token) -> Option<Transaction> {
-        let Some(position) = self.transactions.iter().position(|t| t.token() == token) else {
-            return None;
-        };
+        let transaction = self.transactions.remove(token)?;
+        self.tokens.remove(&transaction.message_id());
+        self.leak_deadlines
+            .remove(&Self::leak_deadline(&transaction));
+        Some(transaction)
+    }
 
-        Some(self.transactions.swap_remove(position))
+    fn leak_deadline(transaction: &Transaction) -> (Instant, u16) {
+        (
+            transaction.created_at() + transaction.lifetime() + LEAK_MARGIN,
+            transaction.message_id().value(),
+        )
     }
 
     pub fn current_nstart(&self) -> usize {
         self.transactions
-            .iter()
+            .values()
             .filter(|t| t.is_non_confirmable() || t.is_acknowledged())
             .count()
     }
@@ -74,14 +158,162 @@ impl TransactionStore {
     pub fn at_max_inflight_capacity(&self) -> bool {
         self.current_nstart() >= self.nstart
     }
-
-    fn compare_message_id<'a>(right: &'a MessageId) -> impl FnMut(&'a Transaction) -> bool {
-        move |left| left.message_id() == *right
-    }
 }
 
 impl Default for TransactionStore {
     fn default() -> Self {
-        Self::new(NSTART)
+        Self::new(TransmissionProfile::default().nstart())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        sync::{Arc, Mutex},
+        time::{Duration, Instant},
+    };
+
+    use pretty_assertions::assert_eq;
+    use rstest::rstest;
+
+    use crate::codec::{message::GetOptions, MessageId, Token};
+    use crate::protocol::{
+        get::Get,
+        new_request::NewRequest,
+        profile::TransmissionProfile,
+        reliability::Reliability,
+        transaction::{Transaction, LEAK_MARGIN},
+        transmission_parameters::NonConfirmableParameters,
+    };
+
+    use super::{EvictionHook, TransactionStore};
+
+    #[derive(Debug, Default)]
+    struct Evicted(Arc<Mutex<Vec<MessageId>>>);
+
+    impl EvictionHook for Evicted {
+        fn on_evicted(&mut self, transaction: &Transaction) {
+            self.0.lock().unwrap().push(transaction.message_id());
+        }
+    }
+
+    fn transaction(message_id: u16, token: u8) -> Transaction {
+        Transaction::new(
+            MessageId::from_value(message_id),
+            Token::from_value(vec![token]).unwrap(),
+            NewRequest::Get(Get {
+                options: GetOptions::new(),
+                reliability: Reliability::NonConfirmable(NonConfirmableParameters::default()),
+            }),
+            Instant::now(),
+        )
+    }
+
+    #[rstest]
+    fn find_by_token_and_message_id() {
+        let mut store = TransactionStore::default();
+        store.add(transaction(1, 10));
+        store.add(transaction(2, 20));
+
+        let token = Token::from_value(vec![20]).unwrap();
+        assert_eq!(
+            Some(MessageId::from_value(2)),
+            store.find_by_token(&token).map(Transaction::message_id)
+        );
+        assert_eq!(
+            Some(token),
+            store
+                .find_by_message_id(&MessageId::from_value(2))
+                .map(|t| *t.token())
+        );
+        assert_eq!(2, store.count());
+    }
+
+    #[rstest]
+    fn remove_drops_both_indexes() {
+        let mut store = TransactionStore::default();
+        store.add(transaction(1, 10));
+        store.add(transaction(2, 20));
+
+        assert!(store
+            .remove_by_token(&Token::from_value(vec![10]).unwrap())
+            .is_some());
+        assert!(store
+            .find_by_message_id(&MessageId::from_value(1))
+            .is_none());
+
+        assert!(store
+            .remove_by_message_id(&MessageId::from_value(2))
+            .is_some());
+        assert!(!store.exists_by_token(&Token::from_value(vec![20]).unwrap()));
+        assert_eq!(0, store.count());
+    }
+
+    #[rstest]
+    fn evict_leaked() {
+        let evicted = Arc::new(Mutex::new(vec![]));
+        let mut store = TransactionStore::default();
+        store.add_eviction_hook(Box::new(Evicted(evicted.clone())));
+        let transaction = transaction(1, 10);
+        let created_at = transaction.created_at();
+        store.add(transaction);
+
+        assert!(store
+            .evict_leaked(created_at + TransmissionProfile::default().non_lifetime() + LEAK_MARGIN)
+            .is_empty());
+        assert_eq!(1, store.count());
+
+        let leaked = store.evict_leaked(
+            created_at
+                + TransmissionProfile::default().non_lifetime()
+                + LEAK_MARGIN
+                + Duration::from_millis(1),
+        );
+
+        assert_eq!(
+            vec![MessageId::from_value(1)],
+            leaked
+                .iter()
+                .map(Transaction::message_id)
+                .collect::<Vec<_>>()
+        );
+        assert_eq!(vec![MessageId::from_value(1)], *evicted.lock().unwrap());
+        assert_eq!(0, store.count());
+    }
+
+    #[rstest]
+    fn add_with_same_token_replaces() {
+        let mut store = TransactionStore::default();
+        store.add(transaction(1, 10));
+        store.add(transaction(2, 10));
+
+        assert_eq!(1, store.count());
+        assert!(store
+            .find_by_message_id(&MessageId::from_value(1))
+            .is_none());
+        assert!(store
+            .find_by_message_id(&MessageId::from_value(2))
+            .is_some());
+    }
+
+    #[rstest]
+    fn removed_transactions_are_not_evicted() {
+        let mut store = TransactionStore::default();
+        store.add(transaction(1, 10));
+        store.add(transaction(2, 20));
+        store.add(transaction(3, 30));
+        store.remove_by_token(&Token::from_value(vec![10]).unwrap());
+        store.remove_by_message_id(&MessageId::from_value(2));
+
+        let leaked = store.evict_leaked(Instant::now() + Duration::from_secs(24 * 60 * 60));
+
+        assert_eq!(
+            vec![MessageId::from_value(3)],
+            leaked
+                .iter()
+                .map(Transaction::message_id)
+                .collect::<Vec<_>>()
+        );
+        assert!(store.leak_deadlines.is_empty());
     }
 }
@@ -1,5 +1,19 @@
 use std::time::Duration;
 
+use rand::{thread_rng, Rng};
+
+/// MAX_LATENCY of RFC 7252, the longest a datagram takes from one endpoint to another.
+pub(crate) const DEFAULT_MAX_LATENCY: Duration = Duration::from_secs(100);
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Error {
+    AckRandomFactor(f32),
+    AckTimeout(Duration),
+    InitialRetransmissionFactor(f32),
+    MaxRetransmit(u8),
+    ProbingRatePerSecond(f32),
+}
+
 #[derive(Clone, Copy, Debug, PartialEq)]
 pub enum TransmissionParamters {
     Confirmable(ConfirmableParameters),
@@ -19,6 +33,10 @@ impl AckRandomFactor {
 
         Ok(Self { value })
     }
+
+    pub fn value(&self) -> f32 {
+        self.value
+    }
 }
 
 impl Default for AckRandomFactor {
@@ -40,6 +58,10 @@ impl AckTimeout {
 
         Ok(Self { value })
     }
+
+    pub fn value(&self) -> Duration {
+        self.value
+    }
 }
 
 impl Default for AckTimeout {
@@ -57,6 +79,10 @@ impl MaxRetransmit {
     pub fn new(value: u8) -> Self {
         Self { value }
     }
+
+    pub fn value(&self) -> u8 {
+        self.value
+    }
 }
 
 impl Default for MaxRetransmit {
@@ -74,6 +100,10 @@ pub struct ConfirmableParameters {
 }
 
 impl ConfirmableParameters {
+    pub fn builder() -> ConfirmableParametersBuilder {
+        ConfirmableParametersBuilder::new()
+    }
+
     pub fn new(
         ack_timeout: AckTimeout,
         ack_random_factor: AckRandomFactor,
@@ -99,7 +129,7 @@ impl ConfirmableParameters {
 
     pub fn max_transmit_wait(&self) -> Duration {
         self.ack_timeout().mul_f32(self.ack_random_factor())
-            * ((self.max_retransmit() + 1).pow(2) - 1).into()
+            * (2u32.pow((self.max_retransmit() + 1).into()) - 1)
     }
 
     pub fn ack_timeout(&self) -> Duration {
@@ -142,11 +172,11 @@ impl ConfirmableParameters {
 
     pub fn max_transmit_span(&self) -> Duration {
         self.ack_timeout().mul_f32(self.ack_random_factor())
-            * (self.max_retransmit().pow(2) - 1).into()
+            * (2u32.pow(self.max_retransmit().into()) - 1)
     }
 
     pub fn max_latency(&self) -> Duration {
-        Duration::from_secs(100)
+        DEFAULT_MAX_LATENCY
     }
 
     pub fn processing_delay(&self) -> Duration {
@@ -154,6 +184,60 @@ impl ConfirmableParameters {
     }
 }
 
+#[derive(Clone, Copy, Debug, Default)]
+pub struct ConfirmableParametersBuilder {
+    ack_timeout: Option<Duration>,
+    ack_random_factor: Option<f32>,
+    initial_retransmission_factor: Option<f32>,
+    max_retransmit: Option<u8>,
+}
+
+impl ConfirmableParametersBuilder {
+    pub fn ack_random_factor(mut self, ack_random_factor: f32) -> Self {
+        self.ack_random_factor = Some(ack_random_factor);
+        self
+    }
+
+    pub fn ack_timeout(mut self, ack_timeout: Duration) -> Self {
+        self.ack_timeout = Some(ack_timeout);
+        self
+    }
+
+    /// Builds the parameters, any value not set falls back to the default from
+    /// [RFC 7252](https://datatracker.ietf.org/doc/html/rfc7252#section-4.8).
+    ///
+    /// The initial retransmission factor is picked at random when not set.
+    pub fn build(self) -> Result<ConfirmableParameters, Error> {
+        let initial_retransmission_factor = self
+            .initial_retransmission_factor
+            .unwrap_or_else(|| thread_rng().gen_range(0.0..1.0));
+
+        Ok(ConfirmableParameters::new(
+            build_ack_timeout(self.ack_timeout)?,
+            build_ack_random_factor(self.ack_random_factor)?,
+            InitialRetransmissionFactor::new(initial_retransmission_factor)
+                .map_err(|_| Error::InitialRetransmissionFactor(initial_retransmission_factor))?,
+            build_max_retransmit(self.max_retransmit)?,
+        ))
+    }
+
+    pub fn initial_retransmission_factor(mut self, initial_retransmission_factor: f32) -> Self {
+        self.initial_retransmission_factor = Some(initial_retransmission_factor);
+        self
+    }
+
+    pub fn max_retransmit(mut self, max_retransmit: u8) -> Self {
+        self.max_retransmit = Some(max_retransmit);
+        self
+    }
+
+    pub fn new() -> Self {
+        Self {
+            ..Default::default()
+        }
+    }
+}
+
 #[derive(Clone, Copy, Debug, PartialEq)]
 pub struct InitialRetransmissionFactor {
     value: f32,
@@ -209,6 +293,10 @@ pub struct NonConfirmableParameters {
 }
 
 impl NonConfirmableParameters {
+    pub fn builder() -> NonConfirmableParametersBuilder {
+        NonConfirmableParametersBuilder::new()
+    }
+
     pub fn default() -> Self {
         Self {
             ack_timeout: AckTimeout::default(),
@@ -242,11 +330,11 @@ impl NonConfirmableParameters {
 
     pub fn max_transmit_span(&self) -> Duration {
         self.ack_timeout().mul_f32(self.ack_random_factor())
-            * (self.max_retransmit().pow(2) - 1).into()
+            * (2u32.pow(self.max_retransmit().into()) - 1)
     }
 
     fn max_latency(&self) -> Duration {
-        Duration::from_secs(100)
+        DEFAULT_MAX_LATENCY
     }
 
     pub fn ack_timeout(&self) -> Duration {
@@ -269,3 +357,197 @@ impl NonConfirmableParameters {
         self.max_retransmit.value
     }
 }
+
+#[derive(Clone, Copy, Debug, Default)]
+pub struct NonConfirmableParametersBuilder {
+    ack_timeout: Option<Duration>,
+    ack_random_factor: Option<f32>,
+    max_retransmit: Option<u8>,
+    probing_rate_per_second: Option<f32>,
+}
+
+impl NonConfirmableParametersBuilder {
+    pub fn ack_random_factor(mut self, ack_random_factor: f32) -> Self {
+        self.ack_random_factor = Some(ack_random_factor);
+        self
+    }
+
+    pub fn ack_timeout(mut self, ack_timeout: Duration) -> Self {
+        self.ack_timeout = Some(ack_timeout);
+        self
+    }
+
+    /// Builds the parameters, any value not set falls back to the default from
+    /// [RFC 7252](https://datatracker.ietf.org/doc/html/rfc7252#section-4.8).
+    ///
+    /// Retransmission of non-confirmable messages is only enabled if a probing rate was set.
+    pub fn build(self) -> Result<NonConfirmableParameters, Error> {
+        let probing_rate_per_second = match self.probing_rate_per_second {
+            Some(value) if !value.is_finite() || value <= 0.0 => {
+                return Err(Error::ProbingRatePerSecond(value))
+            }
+            value => value.map(ProbingRatePerSecond::new),
+        };
+
+        Ok(NonConfirmableParameters::new(
+            build_ack_timeout(self.ack_timeout)?,
+            build_ack_random_factor(self.ack_random_factor)?,
+            build_max_retransmit(self.max_retransmit)?,
+            probing_rate_per_second,
+        ))
+    }
+
+    pub fn max_retransmit(mut self, max_retransmit: u8) -> Self {
+        self.max_retransmit = Some(max_retransmit);
+        self
+    }
+
+    pub fn new() -> Self {
+        Self {
+            ..Default::default()
+        }
+    }
+
+    pub fn probing_rate_per_second(mut self, probing_rate_per_second: f32) -> Self {
+        self.probing_rate_per_second = Some(probing_rate_per_second);
+        self
+    }
+}
+
+fn build_ack_random_factor(value: Option<f32>) -> Result<AckRandomFactor, Error> {
+    match value {
+        Some(value) if !value.is_finite() => Err(Error::AckRandomFactor(value)),
+        Some(value) => AckRandomFactor::new(value).map_err(|_| Error::AckRandomFactor(value)),
+        None => Ok(AckRandomFactor::default()),
+    }
+}
+
+fn build_ack_timeout(value: Option<Duration>) -> Result<AckTimeout, Error> {
+    match value {
+        Some(value) => AckTimeout::new(value).map_err(|_| Error::AckTimeout(value)),
+        None => Ok(AckTimeout::default()),
+    }
+}
+
+// The derived timeouts double with every retransmission, keep them within range.
+fn build_max_retransmit(value: Option<u8>) -> Result<MaxRetransmit, Error> {
+    match value {
+        Some(value) if !(1..=14).contains(&value) => Err(Error::MaxRetransmit(value)),
+        Some(value) => Ok(MaxRetransmit::new(value)),
+        None => Ok(MaxRetransmit::default()),
+    }
+}
+
+impl std::fmt::Display for Error {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Error::AckRandomFactor(value) => {
+                write!(f, "ACK_RANDOM_FACTOR {value} must be at least 1.0")
+            }
+            Error::AckTimeout(value) => write!(f, "ACK_TIMEOUT {value:?} must be at least 1s"),
+            Error::InitialRetransmissionFactor(value) => write!(
+                f,
+                "initial retransmission factor {value} must be within 0.0..=1.0"
+            ),
+            Error::MaxRetransmit(value) => {
+                write!(f, "MAX_RETRANSMIT {value} must be within 1..=14")
+            }
+            Error::ProbingRatePerSecond(value) => {
+                write!(f, "PROBING_RATE {value} must be a positive number")
+            }
+        }
+    }
+}
+
+impl std::error::Error for Error {}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use pretty_assertions::assert_eq;
+    use rstest::rstest;
+
+    use super::{
+        AckRandomFactor, AckTimeout, ConfirmableParameters, ConfirmableParametersBuilder, Error,
+        InitialRetransmissionFactor, MaxRetransmit, NonConfirmableParameters, ProbingRatePerSecond,
+    };
+
+    #[rstest]
+    fn confirmable_builder() {
+        let parameters = ConfirmableParameters::builder()
+            .ack_timeout(Duration::from_secs(3))
+            .ack_random_factor(2.0)
+            .initial_retransmission_factor(0.25)
+            .max_retransmit(2)
+            .build();
+
+        assert_eq!(
+            Ok(ConfirmableParameters::new(
+                AckTimeout::new(Duration::from_secs(3)).unwrap(),
+                AckRandomFactor::new(2.0).unwrap(),
+                InitialRetransmissionFactor::new(0.25).unwrap(),
+                MaxRetransmit::new(2),
+            )),
+            parameters
+        );
+    }
+
+    #[rstest]
+    fn confirmable_builder_defaults() {
+        let parameters = ConfirmableParameters::builder()
+            .initial_retransmission_factor(0.5)
+            .build();
+
+        assert_eq!(
+            Ok(ConfirmableParameters::default(
+                InitialRetransmissionFactor::new(0.5).unwrap()
+            )),
+            parameters
+        );
+    }
+
+    #[rstest]
+    #[case(ConfirmableParameters::builder().ack_timeout(Duration::from_millis(500)), Error::AckTimeout(Duration::from_millis(500)))]
+    #[case(ConfirmableParameters::builder().ack_random_factor(0.9), Error::AckRandomFactor(0.9))]
+    #[case(ConfirmableParameters::builder().ack_random_factor(f32::INFINITY), Error::AckRandomFactor(f32::INFINITY))]
+    #[case(ConfirmableParameters::builder().initial_retransmission_factor(1.5), Error::InitialRetransmissionFactor(1.5))]
+    #[case(ConfirmableParameters::builder().max_retransmit(0), Error::MaxRetransmit(0))]
+    #[case(ConfirmableParameters::builder().max_retransmit(15), Error::MaxRetransmit(15))]
+    fn confirmable_builder_invalid(
+        #[case] builder: ConfirmableParametersBuilder,
+        #[case] expected: Error,
+    ) {
+        assert_eq!(Err(expected), builder.build());
+    }
+
+    #[rstest]
+    fn non_confirmable_builder() {
+        let parameters = NonConfirmableParameters::builder()
+            .ack_timeout(Duration::from_secs(3))
+            .probing_rate_per_second(2.0)
+            .build();
+
+        assert_eq!(
+            Ok(NonConfirmableParameters::new(
+                AckTimeout::new(Duration::from_secs(3)).unwrap(),
+                AckRandomFactor::default(),
+                MaxRetransmit::default(),
+                Some(ProbingRatePerSecond::new(2.0)),
+            )),
+            parameters
+        );
+    }
+
+    #[rstest]
+    #[case(0.0)]
+    #[case(-1.0)]
+    #[case(f32::NAN)]
+    fn non_confirmable_builder_invalid_probing_rate(#[case] probing_rate_per_second: f32) {
+        let result = NonConfirmableParameters::builder()
+            .probing_rate_per_second(probing_rate_per_second)
+            .build();
+
+        assert!(matches!(result, Err(Error::ProbingRatePerSecond(_))));
+    }
+}
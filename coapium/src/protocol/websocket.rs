@@ -0,0 +1,206 @@
+use std::collections::VecDeque;
+
+use crate::codec::{Code, Header, MessageId, MessageType, TokenLength};
+
+/// Signaling code 7.01, Capabilities and Settings Message.
+pub const CSM: u8 = 0xE1;
+
+/// Signaling code 7.02.
+pub const PING: u8 = 0xE2;
+
+/// Signaling code 7.03.
+pub const PONG: u8 = 0xE3;
+
+/// Translates between the datagrams of the processor and CoAP over WebSocket frames, see
+/// [RFC 8323](https://datatracker.ietf.org/doc/html/rfc8323#section-4).
+///
+/// A WebSocket is reliable, so frames have neither type nor message ID. Confirmable requests are
+/// acknowledged by the bridge as soon as they are framed, which leaves the processor waiting for a
+/// separate response. Responses are handed to the processor as non-confirmable messages, which
+/// doesn't need an acknowledgement in return. A ping becomes a 7.02 Ping and the 7.03 Pong that
+/// answers it becomes the reset the processor expects.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct WebSocketBridge {
+    message_id: u16,
+    pings: VecDeque<MessageId>,
+}
+
+/// What to do with a datagram or a frame after bridging it.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Bridged {
+    /// Datagram to hand to the processor as if it was received.
+    pub datagram: Option<Vec<u8>>,
+    /// Frame to send over the WebSocket.
+    pub frame: Option<Vec<u8>>,
+}
+
+impl WebSocketBridge {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// The empty Capabilities and Settings Message each side sends first.
+    pub fn csm() -> Vec<u8> {
+        vec![0x00, CSM]
+    }
+
+    /// Bridges a datagram sent by the processor, anything not a request or a ping is dropped.
+    pub fn outgoing(&mut self, datagram: &[u8]) -> Bridged {
+        let Ok((rest, header)) = Header::parse(datagram) else {
+            return Bridged::default();
+        };
+
+        match (header.message_type(), header.code()) {
+            (MessageType::Confirmable, Code::Empty) => {
+                self.pings.push_back(header.message_id());
+                Bridged {
+                    datagram: None,
+                    frame: Some(vec![0x00, PING]),
+                }
+            }
+            (MessageType::Confirmable, Code::Request(_)) => Bridged {
+                datagram: Some(empty(MessageType::Acknowledgement, header.message_id())),
+                frame: Some(frame(&header, rest)),
+            },
+            (MessageType::NonConfirmable, Code::Request(_)) => Bridged {
+                datagram: None,
+                frame: Some(frame(&header, rest)),
+            },
+            _ => Bridged::default(),
+        }
+    }
+
+    /// Bridges a frame received over the WebSocket, signals other than ping and pong are dropped.
+    pub fn incoming(&mut self, frame: &[u8]) -> Bridged {
+        let [length_and_token_length, code, ..] = *frame else {
+            return Bridged::default();
+        };
+        // Messages larger than 12 bytes carry an extended length, which a WebSocket doesn't need.
+        if length_and_token_length >> 4 != 0 {
+            return Bridged::default();
+        }
+        let Ok(token_length) =
+            TokenLength::from_value(TokenLength::decode(length_and_token_length).value())
+        else {
+            return Bridged::default();
+        };
+
+        match code {
+            PING => Bridged {
+                datagram: None,
+                frame: Some(vec![0x00, PONG]),
+            },
+            PONG => Bridged {
+                datagram: self
+                    .pings
+                    .pop_front()
+                    .map(|message_id| empty(MessageType::Reset, message_id)),
+                frame: None,
+            },
+            code if Code::decode(code).is_response() => {
+                let header = Header::new(
+                    MessageType::NonConfirmable,
+                    token_length,
+                    Code::decode(code),
+                    self.next_message_id(),
+                );
+                Bridged {
+                    datagram: Some([header.encode(), frame[2..].to_vec()].concat()),
+                    frame: None,
+                }
+            }
+            _ => Bridged::default(),
+        }
+    }
+
+    fn next_message_id(&mut self) -> MessageId {
+        self.message_id = self.message_id.wrapping_add(1);
+        MessageId::from_value(self.message_id)
+    }
+}
+
+fn empty(message_type: MessageType, message_id: MessageId) -> Vec<u8> {
+    Header::new(
+        message_type,
+        TokenLength::zero_length(),
+        Code::Empty,
+        message_id,
+    )
+    .encode()
+}
+
+/// Frames without extended length, as the WebSocket already delimits messages.
+fn frame(header: &Header, rest: &[u8]) -> Vec<u8> {
+    [
+        vec![header.token_length().encode(), header.code().encode()],
+        rest.to_vec(),
+    ]
+    .concat()
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+    use rstest::rstest;
+
+    use super::{Bridged, WebSocketBridge};
+
+    #[rstest]
+    // CON GET, token 0xAB, Uri-Path "a"
+    #[case(vec![0x41, 0x01, 0x12, 0x34, 0xAB, 0xB1, b'a'], Bridged { datagram: Some(vec![0x60, 0x00, 0x12, 0x34]), frame: Some(vec![0x01, 0x01, 0xAB, 0xB1, b'a']) })]
+    // NON POST with payload
+    #[case(vec![0x50, 0x02, 0x00, 0x01, 0xFF, 0x01], Bridged { datagram: None, frame: Some(vec![0x00, 0x02, 0xFF, 0x01]) })]
+    // Ping
+    #[case(vec![0x40, 0x00, 0x00, 0x07], Bridged { datagram: None, frame: Some(vec![0x00, 0xE2]) })]
+    // ACK
+    #[case(vec![0x60, 0x00, 0x00, 0x07], Bridged::default())]
+    // RST
+    #[case(vec![0x70, 0x00, 0x00, 0x07], Bridged::default())]
+    #[case(vec![0x40],                   Bridged::default())]
+    fn outgoing(#[case] datagram: Vec<u8>, #[case] expected: Bridged) {
+        assert_eq!(expected, WebSocketBridge::new().outgoing(&datagram));
+    }
+
+    #[rstest]
+    // 2.05 Content, token 0xAB, payload "ok"
+    #[case(vec![0x01, 0x45, 0xAB, 0xFF, b'o', b'k'], Bridged { datagram: Some(vec![0x51, 0x45, 0x00, 0x01, 0xAB, 0xFF, b'o', b'k']), frame: None })]
+    // Ping from the server
+    #[case(vec![0x00, 0xE2],                         Bridged { datagram: None, frame: Some(vec![0x00, 0xE3]) })]
+    // Pong without a ping
+    #[case(vec![0x00, 0xE3],                         Bridged::default())]
+    // CSM
+    #[case(vec![0x00, 0xE1],                         Bridged::default())]
+    // Request
+    #[case(vec![0x00, 0x01],                         Bridged::default())]
+    // Extended length
+    #[case(vec![0xD0, 0x00, 0x45],                   Bridged::default())]
+    #[case(vec![0x00],                               Bridged::default())]
+    fn incoming(#[case] frame: Vec<u8>, #[case] expected: Bridged) {
+        assert_eq!(expected, WebSocketBridge::new().incoming(&frame));
+    }
+
+    #[rstest]
+    fn pong_resets_the_oldest_ping() {
+        let mut bridge = WebSocketBridge::new();
+        bridge.outgoing(&[0x40, 0x00, 0x00, 0x07]);
+        bridge.outgoing(&[0x40, 0x00, 0x00, 0x08]);
+
+        assert_eq!(
+            Some(vec![0x70, 0x00, 0x00, 0x07]),
+            bridge.incoming(&[0x00, 0xE3]).datagram
+        );
+        assert_eq!(
+            Some(vec![0x70, 0x00, 0x00, 0x08]),
+            bridge.incoming(&[0x00, 0xE3]).datagram
+        );
+    }
+
+    #[rstest]
+    fn responses_get_distinct_message_ids() {
+        let mut bridge = WebSocketBridge::new();
+        let first = bridge.incoming(&[0x00, 0x45]).datagram.unwrap();
+        let second = bridge.incoming(&[0x00, 0x45]).datagram.unwrap();
+
+        assert_ne!(first[2..4], second[2..4]);
+    }
+}
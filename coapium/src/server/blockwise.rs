@@ -0,0 +1,488 @@
+use std::{
+    collections::hash_map::DefaultHasher,
+    hash::{Hash, Hasher},
+    time::{Duration, Instant},
+};
+
+use crate::{
+    codec::{
+        message::GetOptions,
+        option::{Block2, ETag, QBlock2},
+        Options, Payload,
+    },
+    protocol::profile::TransmissionProfile,
+};
+
+/// A single block of a representation, with the options that describe it.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Slice {
+    pub options: Options,
+    pub payload: Payload,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Error {
+    /// The requested block starts beyond the end of the representation, the request should be
+    /// answered with 4.02 (Bad Option).
+    OutOfRange { num: u32 },
+}
+
+/// How [`Blockwise`] transfers representations that don't fit in a single block.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub enum TransferStrategy {
+    /// One Block2 block per request, as described in
+    /// [RFC 7959](https://datatracker.ietf.org/doc/html/rfc7959#section-2.4).
+    #[default]
+    Block,
+    /// All Q-Block2 blocks at once, meant to be sent as a burst of non-confirmable responses.
+    /// Clients recover lost blocks by asking for the missing ones, as described in
+    /// [RFC 9177](https://datatracker.ietf.org/doc/html/rfc9177#section-4.4).
+    QuickBlock,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+struct Representation {
+    etag: ETag,
+    expires_at: Instant,
+    payload: Vec<u8>,
+}
+
+/// Slices responses larger than the block size into Block2 blocks as described in
+/// [RFC 7959](https://datatracker.ietf.org/doc/html/rfc7959#section-2.4), so handlers can return
+/// the full payload.
+///
+/// Every sliced representation is cached by its ETag for the exchange lifetime, a follow up
+/// request carrying the ETag is served from the cache instead of the handler.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Blockwise {
+    lifetime: Duration,
+    representations: Vec<Representation>,
+    strategy: TransferStrategy,
+    szx: u8,
+}
+
+impl Blockwise {
+    pub fn new() -> Self {
+        Self {
+            lifetime: TransmissionProfile::default().exchange_lifetime(),
+            representations: vec![],
+            strategy: TransferStrategy::default(),
+            szx: Block2::MAX_SZX,
+        }
+    }
+
+    /// Largest block size the server sends, as the exponent SZX where the size is
+    /// `2^(SZX + 4)` bytes. Clients can only ask for smaller blocks.
+    pub fn with_szx(mut self, szx: u8) -> Self {
+        self.szx = szx.min(Block2::MAX_SZX);
+        self
+    }
+
+    pub fn with_lifetime(mut self, lifetime: Duration) -> Self {
+        self.lifetime = lifetime;
+        self
+    }
+
+    pub fn with_transfer_strategy(mut self, strategy: TransferStrategy) -> Self {
+        self.strategy = strategy;
+        self
+    }
+
+    /// Responds with the block asked for in the request, `payload` is only called when the
+    /// representation is not cached.
+    ///
+    /// With [`TransferStrategy::QuickBlock`] this is the first of the blocks
+    /// [`Blockwise::respond_all`] responds with.
+    pub fn respond(
+        &mut self,
+        now: Instant,
+        options: &GetOptions,
+        payload: impl FnOnce() -> Payload,
+    ) -> Result<Slice, Error> {
+        self.respond_all(now, options, payload).map(|slices| {
+            slices
+                .into_iter()
+                .next()
+                .expect("There should always be at least one slice")
+        })
+    }
+
+    /// Responds with every block asked for in the request, `payload` is only called when the
+    /// representation is not cached.
+    ///
+    /// With [`TransferStrategy::Block`] that is the single block asked for with Block2. With
+    /// [`TransferStrategy::QuickBlock`] it is every block of the representation, unless the
+    /// request lists the blocks it is missing with Q-Block2. A listed block with the M bit set
+    /// asks for that block and all blocks after it.
+    pub fn respond_all(
+        &mut self,
+        now: Instant,
+        options: &GetOptions,
+        payload: impl FnOnce() -> Payload,
+    ) -> Result<Vec<Slice>, Error> {
+        self.representations
+            .retain(|representation| representation.expires_at > now);
+
+        let requested = match self.strategy {
+            TransferStrategy::Block => options.block2().cloned().into_iter().collect(),
+            TransferStrategy::QuickBlock => options
+                .q_block2()
+                .map_or(vec![], |q_block2| q_block2.blocks().to_vec()),
+        };
+        let szx = requested
+            .first()
+            .map_or(self.szx, |block| block.szx().min(self.szx));
+
+        let cached = options.etag().and_then(|etag| {
+            self.representations
+                .iter()
+                .find(|representation| representation.etag == *etag)
+        });
+
+        let (etag, payload) = match cached {
+            Some(representation) => (representation.etag.clone(), representation.payload.clone()),
+            None => {
+                let payload = payload().value().to_vec();
+                if requested.is_empty() && payload.len() <= block_size(szx) {
+                    return Ok(vec![Slice {
+                        options: Options::new(),
+                        payload: Payload::from_value(payload),
+                    }]);
+                }
+
+                let etag = etag(&payload);
+                self.cache(now, etag.clone(), payload.clone());
+                (etag, payload)
+            }
+        };
+
+        self.offsets(&requested, payload.len(), szx)
+            .into_iter()
+            .map(|offset| slice(etag.clone(), &payload, offset, szx, self.strategy))
+            .collect()
+    }
+
+    fn offsets(&self, requested: &[Block2], length: usize, szx: u8) -> Vec<usize> {
+        let size = block_size(szx);
+        let end = length.max(1);
+
+        if self.strategy == TransferStrategy::Block {
+            return vec![requested.first().map_or(0, Block2::offset)];
+        }
+
+        if requested.is_empty() {
+            return (0..end).step_by(size).collect();
+        }
+
+        let mut offsets: Vec<usize> = requested
+            .iter()
+            .flat_map(|block| {
+                let last = match block.more() {
+                    true => end.max(block.offset() + 1),
+                    false => block.offset() + 1,
+                };
+                (block.offset()..last).step_by(size)
+            })
+            .collect();
+        offsets.sort();
+        offsets.dedup();
+        offsets
+    }
+
+    fn cache(&mut self, now: Instant, etag: ETag, payload: Vec<u8>) {
+        self.representations
+            .retain(|representation| representation.etag != etag);
+        self.representations.push(Representation {
+            etag,
+            expires_at: now + self.lifetime,
+            payload,
+        });
+    }
+}
+
+impl Default for Blockwise {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+fn block_size(szx: u8) -> usize {
+    1 << (szx + 4)
+}
+
+fn etag(payload: &[u8]) -> ETag {
+    let mut hasher = DefaultHasher::new();
+    payload.hash(&mut hasher);
+
+    ETag::from_values(vec![hasher.finish().to_be_bytes().to_vec()])
+        .expect("ETag of a hash should be 8 bytes")
+}
+
+fn slice(
+    etag: ETag,
+    payload: &[u8],
+    offset: usize,
+    szx: u8,
+    strategy: TransferStrategy,
+) -> Result<Slice, Error> {
+    let size = block_size(szx);
+    let num = (offset / size) as u32;
+
+    if offset > 0 && offset >= payload.len() {
+        return Err(Error::OutOfRange { num });
+    }
+
+    let end = payload.len().min(offset + size);
+    let more = end < payload.len();
+
+    let mut options = Options::new();
+    match strategy {
+        TransferStrategy::Block => {
+            options.set_block2(Block2::new(num, more, szx).map_err(|_| Error::OutOfRange { num })?)
+        }
+        TransferStrategy::QuickBlock => options
+            .set_q_block2(QBlock2::new(num, more, szx).map_err(|_| Error::OutOfRange { num })?),
+    }
+    options.set_etag(etag);
+
+    Ok(Slice {
+        options,
+        payload: Payload::from_value(payload[offset..end].to_vec()),
+    })
+}
+
+impl std::fmt::Display for Error {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Error::OutOfRange { num } => {
+                write!(f, "block {num} is beyond the end of the representation")
+            }
+        }
+    }
+}
+
+impl std::error::Error for Error {}
+
+#[cfg(test)]
+mod tests {
+    use std::time::{Duration, Instant};
+
+    use pretty_assertions::assert_eq;
+    use rstest::rstest;
+
+    use crate::codec::{
+        message::GetOptions,
+        option::{Block2, QBlock2},
+        Options, Payload,
+    };
+
+    use super::{Blockwise, Error, TransferStrategy};
+
+    fn payload() -> Payload {
+        Payload::from_value((0..40).collect())
+    }
+
+    fn request(block2: std::option::Option<Block2>, options: &Options) -> GetOptions {
+        let mut request = GetOptions::new();
+        if let Some(block2) = block2 {
+            request.set_block2(block2);
+        }
+        if let Some(etag) = options.etag() {
+            request.set_etag(etag.clone());
+        }
+        request
+    }
+
+    fn quick_request(blocks: Vec<(u32, bool)>, options: &Options) -> GetOptions {
+        let mut request = request(None, options);
+        request.set_q_block2(
+            QBlock2::from_blocks(
+                blocks
+                    .into_iter()
+                    .map(|(num, more)| Block2::new(num, more, 0).unwrap())
+                    .collect(),
+            )
+            .unwrap(),
+        );
+        request
+    }
+
+    fn quick_blocks(slices: &[super::Slice]) -> Vec<(u32, bool)> {
+        slices
+            .iter()
+            .flat_map(|slice| slice.options.q_block2().unwrap().blocks())
+            .map(|block| (block.num(), block.more()))
+            .collect()
+    }
+
+    #[rstest]
+    fn small_payload_is_not_sliced() {
+        let mut blockwise = Blockwise::new();
+
+        let slice = blockwise
+            .respond(Instant::now(), &GetOptions::new(), payload)
+            .unwrap();
+
+        assert_eq!(Options::new(), slice.options);
+        assert_eq!(payload(), slice.payload);
+    }
+
+    #[rstest]
+    #[case(None,                                   0, true,  0..16)]
+    #[case(Some(Block2::new(2, false, 0).unwrap()), 2, false, 32..40)]
+    #[case(Some(Block2::new(1, false, 1).unwrap()), 2, false, 32..40)]
+    fn slices_requested_block(
+        #[case] block2: std::option::Option<Block2>,
+        #[case] num: u32,
+        #[case] more: bool,
+        #[case] range: std::ops::Range<u8>,
+    ) {
+        let mut blockwise = Blockwise::new().with_szx(0);
+
+        let slice = blockwise
+            .respond(Instant::now(), &request(block2, &Options::new()), payload)
+            .unwrap();
+
+        assert_eq!(
+            Some(&Block2::new(num, more, 0).unwrap()),
+            slice.options.block2()
+        );
+        assert_eq!(Payload::from_value(range.collect()), slice.payload);
+    }
+
+    #[rstest]
+    fn follow_up_with_etag_is_served_from_cache() {
+        let now = Instant::now();
+        let mut blockwise = Blockwise::new().with_szx(0);
+        let first = blockwise.respond(now, &GetOptions::new(), payload).unwrap();
+
+        let second = blockwise
+            .respond(
+                now,
+                &request(Some(Block2::new(1, false, 0).unwrap()), &first.options),
+                || panic!("Expected the cached representation"),
+            )
+            .unwrap();
+
+        assert_eq!(first.options.etag(), second.options.etag());
+        assert_eq!(Payload::from_value((16..32).collect()), second.payload);
+    }
+
+    #[rstest]
+    fn expired_representation_is_recreated() {
+        let now = Instant::now();
+        let mut blockwise = Blockwise::new()
+            .with_szx(0)
+            .with_lifetime(Duration::from_secs(1));
+        let first = blockwise.respond(now, &GetOptions::new(), payload).unwrap();
+
+        let mut called = false;
+        blockwise
+            .respond(
+                now + Duration::from_secs(1),
+                &request(Some(Block2::new(1, false, 0).unwrap()), &first.options),
+                || {
+                    called = true;
+                    payload()
+                },
+            )
+            .unwrap();
+
+        assert_eq!(true, called);
+    }
+
+    #[rstest]
+    fn block_beyond_end_is_rejected() {
+        let mut blockwise = Blockwise::new().with_szx(0);
+
+        assert_eq!(
+            Err(Error::OutOfRange { num: 3 }),
+            blockwise.respond(
+                Instant::now(),
+                &request(Some(Block2::new(3, false, 0).unwrap()), &Options::new()),
+                payload
+            )
+        );
+    }
+
+    #[rstest]
+    fn quick_block_responds_with_every_block() {
+        let mut blockwise = Blockwise::new()
+            .with_szx(0)
+            .with_transfer_strategy(TransferStrategy::QuickBlock);
+
+        let slices = blockwise
+            .respond_all(Instant::now(), &GetOptions::new(), payload)
+            .unwrap();
+
+        assert_eq!(
+            vec![(0, true), (1, true), (2, false)],
+            quick_blocks(&slices)
+        );
+        assert_eq!(
+            payload().value(),
+            slices
+                .iter()
+                .flat_map(|slice| slice.payload.value().to_vec())
+                .collect::<Vec<_>>()
+        );
+        assert_eq!(None, slices[0].options.block2());
+    }
+
+    #[rstest]
+    #[case(vec![(1, false)],            vec![(1, true)])]
+    #[case(vec![(2, false), (0, false)], vec![(0, true), (2, false)])]
+    #[case(vec![(1, true)],             vec![(1, true), (2, false)])]
+    #[case(vec![(1, true), (2, false)], vec![(1, true), (2, false)])]
+    fn quick_block_responds_with_missing_blocks(
+        #[case] missing: Vec<(u32, bool)>,
+        #[case] expected: Vec<(u32, bool)>,
+    ) {
+        let now = Instant::now();
+        let mut blockwise = Blockwise::new()
+            .with_szx(0)
+            .with_transfer_strategy(TransferStrategy::QuickBlock);
+        let burst = blockwise
+            .respond_all(now, &GetOptions::new(), payload)
+            .unwrap();
+
+        let slices = blockwise
+            .respond_all(now, &quick_request(missing, &burst[0].options), || {
+                panic!("Expected the cached representation")
+            })
+            .unwrap();
+
+        assert_eq!(expected, quick_blocks(&slices));
+    }
+
+    #[rstest]
+    fn quick_block_missing_block_beyond_end_is_rejected() {
+        let mut blockwise = Blockwise::new()
+            .with_szx(0)
+            .with_transfer_strategy(TransferStrategy::QuickBlock);
+
+        assert_eq!(
+            Err(Error::OutOfRange { num: 3 }),
+            blockwise.respond_all(
+                Instant::now(),
+                &quick_request(vec![(3, false)], &Options::new()),
+                payload
+            )
+        );
+    }
+
+    #[rstest]
+    fn block_strategy_responds_with_single_block() {
+        let mut blockwise = Blockwise::new().with_szx(0);
+
+        let slices = blockwise
+            .respond_all(Instant::now(), &GetOptions::new(), payload)
+            .unwrap();
+
+        assert_eq!(1, slices.len());
+        assert_eq!(
+            Some(&Block2::new(0, true, 0).unwrap()),
+            slices[0].options.block2()
+        );
+    }
+}
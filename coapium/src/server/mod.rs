@@ -0,0 +1,5 @@
+pub mod blockwise;
+pub mod observable;
+
+pub use blockwise::{Blockwise, TransferStrategy};
+pub use observable::Observable;
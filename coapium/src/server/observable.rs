@@ -0,0 +1,376 @@
+use std::net::SocketAddr;
+
+use crate::codec::{
+    code::response_code::Success,
+    message::{Get, Reliability},
+    option::Observe,
+    MessageId, Options, Payload, Piggyback, Response, ResponseCode, Token,
+};
+
+/// A client observing a resource, identified by its endpoint and the token of its registration.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Observer {
+    endpoint: SocketAddr,
+    last_message_id: Option<MessageId>,
+    notifications: u32,
+    token: Token,
+}
+
+impl Observer {
+    pub fn endpoint(&self) -> &SocketAddr {
+        &self.endpoint
+    }
+
+    pub fn token(&self) -> &Token {
+        &self.token
+    }
+}
+
+/// Encoded message to send to an endpoint.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Notification {
+    pub data: Vec<u8>,
+    pub endpoint: SocketAddr,
+    pub message_id: MessageId,
+    pub reliability: Reliability,
+}
+
+/// Resource state that clients can observe as described in
+/// [RFC 7641](https://datatracker.ietf.org/doc/html/rfc7641).
+///
+/// The observable is sans-IO, it hands back the notifications to send and expects resets received
+/// from observers to be passed to [Observable::on_reset].
+#[derive(Clone, Debug, PartialEq)]
+pub struct Observable<T> {
+    confirmable_every: u32,
+    next_message_id: MessageId,
+    observers: Vec<Observer>,
+    sequence: u32,
+    state: T,
+}
+
+impl<T: Clone + Into<Payload>> Observable<T> {
+    pub const DEFAULT_CONFIRMABLE_EVERY: u32 = 5;
+
+    pub fn new(state: T, initial_message_id: MessageId) -> Self {
+        Self {
+            confirmable_every: Self::DEFAULT_CONFIRMABLE_EVERY,
+            next_message_id: initial_message_id,
+            observers: vec![],
+            sequence: 0,
+            state,
+        }
+    }
+
+    /// Sends every Nth notification to an observer as a confirmable message, to find out whether
+    /// the observer is still interested, see
+    /// [RFC 7641](https://datatracker.ietf.org/doc/html/rfc7641#section-4.5).
+    pub fn with_confirmable_every(mut self, confirmable_every: u32) -> Self {
+        self.confirmable_every = confirmable_every.max(1);
+        self
+    }
+
+    pub fn deregister(&mut self, endpoint: &SocketAddr, token: &Token) -> bool {
+        let Some(position) = self
+            .observers
+            .iter()
+            .position(|observer| observer.endpoint == *endpoint && observer.token == *token)
+        else {
+            return false;
+        };
+
+        self.observers.swap_remove(position);
+        true
+    }
+
+    /// Updates the state and creates a notification for every observer.
+    pub fn notify(&mut self, state: T) -> Vec<Notification> {
+        self.state = state;
+        self.sequence = (self.sequence + 1) & Observe::MAX;
+
+        let mut observers = std::mem::take(&mut self.observers);
+        let notifications = observers
+            .iter_mut()
+            .map(|observer| self.notification(observer))
+            .collect();
+        self.observers = observers;
+
+        notifications
+    }
+
+    pub fn observers(&self) -> &[Observer] {
+        &self.observers
+    }
+
+    /// Removes the observer that rejected a notification, returns `false` if no notification
+    /// matches.
+    pub fn on_reset(&mut self, endpoint: &SocketAddr, message_id: MessageId) -> bool {
+        let Some(position) = self.observers.iter().position(|observer| {
+            observer.endpoint == *endpoint && observer.last_message_id == Some(message_id)
+        }) else {
+            return false;
+        };
+
+        self.observers.swap_remove(position);
+        true
+    }
+
+    /// Handles a GET for the resource, registering or deregistering the requester depending on
+    /// the Observe option, and responds with the current state.
+    pub fn register(&mut self, endpoint: SocketAddr, request: &Get) -> Notification {
+        let token = *request.token();
+
+        let observe = match request.options().observe() {
+            Some(observe) if observe.is_register() => {
+                self.deregister(&endpoint, &token);
+                self.observers.push(Observer {
+                    endpoint,
+                    last_message_id: None,
+                    notifications: 0,
+                    token,
+                });
+                Some(Observe::from_value(self.sequence))
+            }
+            Some(observe) if observe.is_deregister() => {
+                self.deregister(&endpoint, &token);
+                None
+            }
+            _ => None,
+        };
+
+        let mut options = Options::new();
+        if let Some(observe) = observe {
+            options.set_observe(observe);
+        }
+        let payload = self.state.clone().into();
+
+        match request.reliability() {
+            Reliability::Confirmable => Notification {
+                data: Piggyback::new(token, content(), request.message_id(), options, payload)
+                    .encode(),
+                endpoint,
+                message_id: request.message_id(),
+                reliability: Reliability::Confirmable,
+            },
+            Reliability::NonConfirmable => {
+                let message_id = self.claim_message_id();
+                Notification {
+                    data: Response::new(
+                        Reliability::NonConfirmable,
+                        token,
+                        content(),
+                        message_id,
+                        options,
+                        payload,
+                    )
+                    .encode(),
+                    endpoint,
+                    message_id,
+                    reliability: Reliability::NonConfirmable,
+                }
+            }
+        }
+    }
+
+    pub fn state(&self) -> &T {
+        &self.state
+    }
+
+    fn claim_message_id(&mut self) -> MessageId {
+        let message_id = self.next_message_id;
+        self.next_message_id = message_id.next();
+        message_id
+    }
+
+    fn notification(&mut self, observer: &mut Observer) -> Notification {
+        observer.notifications += 1;
+        let reliability = if observer
+            .notifications
+            .is_multiple_of(self.confirmable_every)
+        {
+            Reliability::Confirmable
+        } else {
+            Reliability::NonConfirmable
+        };
+
+        let message_id = self.claim_message_id();
+        observer.last_message_id = Some(message_id);
+
+        let mut options = Options::new();
+        options.set_observe(Observe::from_value(self.sequence));
+
+        Notification {
+            data: Response::new(
+                reliability,
+                observer.token,
+                content(),
+                message_id,
+                options,
+                self.state.clone().into(),
+            )
+            .encode(),
+            endpoint: observer.endpoint,
+            message_id,
+            reliability,
+        }
+    }
+}
+
+fn content() -> ResponseCode {
+    ResponseCode::Success(Success::Content)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::net::{Ipv4Addr, SocketAddr};
+
+    use pretty_assertions::assert_eq;
+    use rstest::rstest;
+
+    use crate::codec::{
+        code::response_code::Success,
+        message::{Get, GetOptions, Reliability},
+        option::Observe,
+        Message, MessageId, Options, Payload, ResponseCode, Token,
+    };
+
+    use super::Observable;
+
+    #[derive(Clone, Debug, PartialEq)]
+    struct Temperature(u8);
+
+    impl From<Temperature> for Payload {
+        fn from(value: Temperature) -> Self {
+            Payload::from_value(vec![value.0])
+        }
+    }
+
+    fn endpoint() -> SocketAddr {
+        SocketAddr::from((Ipv4Addr::LOCALHOST, 5683))
+    }
+
+    fn request(reliability: Reliability, observe: Option<Observe>) -> Get {
+        let mut options = GetOptions::new();
+        if let Some(observe) = observe {
+            options.set_observe(observe);
+        }
+        Get::new(
+            MessageId::from_value(100),
+            reliability,
+            Token::from_value(vec![1]).unwrap(),
+            options,
+        )
+    }
+
+    fn observable() -> Observable<Temperature> {
+        Observable::new(Temperature(20), MessageId::from_value(0))
+    }
+
+    #[rstest]
+    fn register_responds_with_piggybacked_state() {
+        let mut observable = observable();
+
+        let notification = observable.register(
+            endpoint(),
+            &request(Reliability::Confirmable, Some(Observe::register())),
+        );
+
+        let Ok(Message::Piggyback(piggyback)) = Message::decode(&notification.data) else {
+            panic!("Expected a piggybacked response");
+        };
+        assert_eq!(MessageId::from_value(100), piggyback.message_id());
+        let response = crate::codec::Response::from(piggyback);
+        assert_eq!(Some(&Observe::from_value(0)), response.options().observe());
+        assert_eq!(&Payload::from_value(vec![20]), response.payload());
+        assert_eq!(1, observable.observers().len());
+    }
+
+    #[rstest]
+    #[case(None)]
+    #[case(Some(Observe::deregister()))]
+    fn get_without_register_does_not_observe(#[case] observe: Option<Observe>) {
+        let mut observable = observable();
+        observable.register(
+            endpoint(),
+            &request(Reliability::Confirmable, Some(Observe::register())),
+        );
+
+        let observing = observe.is_none();
+        observable.register(endpoint(), &request(Reliability::Confirmable, observe));
+
+        assert_eq!(observing, !observable.observers().is_empty());
+    }
+
+    #[rstest]
+    fn notify_sends_every_nth_notification_as_confirmable() {
+        let mut observable = observable().with_confirmable_every(2);
+        observable.register(
+            endpoint(),
+            &request(Reliability::NonConfirmable, Some(Observe::register())),
+        );
+
+        let reliabilities = (21..25)
+            .flat_map(|temperature| observable.notify(Temperature(temperature)))
+            .map(|notification| notification.reliability)
+            .collect::<Vec<_>>();
+
+        assert_eq!(
+            vec![
+                Reliability::NonConfirmable,
+                Reliability::Confirmable,
+                Reliability::NonConfirmable,
+                Reliability::Confirmable,
+            ],
+            reliabilities
+        );
+        assert_eq!(&Temperature(24), observable.state());
+    }
+
+    #[rstest]
+    fn notify_encodes_sequence_number() {
+        let mut observable = observable();
+        observable.register(
+            endpoint(),
+            &request(Reliability::Confirmable, Some(Observe::register())),
+        );
+
+        let notification = observable.notify(Temperature(21)).remove(0);
+
+        let Ok(Message::Response(response)) = Message::decode(&notification.data) else {
+            panic!("Expected a response");
+        };
+        let mut options = Options::new();
+        options.set_observe(Observe::from_value(1));
+        assert_eq!(
+            crate::codec::Response::new(
+                Reliability::NonConfirmable,
+                Token::from_value(vec![1]).unwrap(),
+                ResponseCode::Success(Success::Content),
+                notification.message_id,
+                options,
+                Payload::from_value(vec![21]),
+            ),
+            response
+        );
+    }
+
+    #[rstest]
+    fn reset_deregisters_observer() {
+        let mut observable = observable();
+        observable.register(
+            endpoint(),
+            &request(Reliability::Confirmable, Some(Observe::register())),
+        );
+        let notification = observable.notify(Temperature(21)).remove(0);
+
+        assert_eq!(
+            false,
+            observable.on_reset(&endpoint(), notification.message_id.next())
+        );
+        assert_eq!(
+            true,
+            observable.on_reset(&endpoint(), notification.message_id)
+        );
+        assert_eq!(0, observable.observers().len());
+    }
+}
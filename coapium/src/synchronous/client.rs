@@ -1,17 +1,37 @@
 use std::{
-    net::UdpSocket,
-    sync::mpsc::{channel, Sender},
-    thread::spawn,
+    net::SocketAddr,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        mpsc::{channel, Receiver, RecvTimeoutError, Sender},
+        Arc, Mutex,
+    },
+    thread::{sleep, spawn},
+    time::Duration,
 };
 
+use log::warn;
+
 use crate::{
-    codec::{url::Endpoint, MessageId},
+    client::{
+        capture::Capture,
+        progress::Progress,
+        resolve::{Resolve, SystemResolver},
+        session_stats::SessionStats,
+        socket_config::{self, SocketConfig},
+        Error,
+    },
+    codec::{url::Endpoint, MessageId, Token},
     protocol::{
+        empty_message::EmptyMessage,
+        interceptor::RequestInterceptor,
+        keep_alive::{ConnectionState, KeepAlive, Liveness},
         message_id_store::MessageIdStore,
         new_request::NewRequest,
         ping::{self, Ping},
-        processor::Processor,
+        processor::{Processor, ReservedPolicy},
+        profile::TransmissionProfile,
         response::{self, Response},
+        status::Status,
     },
     synchronous::system,
 };
@@ -20,48 +40,278 @@ use super::system::{Command, System};
 
 #[derive(Debug, Clone)]
 pub struct Client {
+    profile: TransmissionProfile,
+    progress: Arc<Mutex<Progress>>,
     request_sender: Sender<Command>,
+    stats: Arc<Mutex<SessionStats>>,
+    unknown_messages: Arc<Mutex<Option<Receiver<Vec<u8>>>>>,
+}
+
+#[derive(Debug)]
+pub struct ResponseHandle {
+    token: Token,
+    receiver: Receiver<Result<Response, response::Error>>,
+}
+
+/// Handle to the keep-alive pings of a client, the pings stop once the handle is dropped.
+#[derive(Debug)]
+pub struct KeepAliveHandle {
+    liveness: Arc<Mutex<Liveness>>,
+    stopped: Arc<AtomicBool>,
 }
 
-fn run_loop(mut system: System, message_id_store: MessageIdStore) -> Result<(), ()> {
-    let mut processor = Processor::new(message_id_store);
+impl KeepAliveHandle {
+    pub fn missed_pongs(&self) -> u32 {
+        self.liveness.lock().unwrap().missed_pongs()
+    }
+
+    pub fn state(&self) -> ConnectionState {
+        self.liveness.lock().unwrap().state()
+    }
+}
+
+impl Drop for KeepAliveHandle {
+    fn drop(&mut self) {
+        self.stopped.store(true, Ordering::Relaxed);
+    }
+}
+
+impl ResponseHandle {
+    pub fn token(&self) -> &Token {
+        &self.token
+    }
+
+    pub fn wait(self) -> Result<Response, response::Error> {
+        self.receiver
+            .recv()
+            .expect("Failed to receive from response from system")
+    }
+
+    pub fn wait_timeout(&self, timeout: Duration) -> Option<Result<Response, response::Error>> {
+        match self.receiver.recv_timeout(timeout) {
+            Ok(result) => Some(result),
+            Err(RecvTimeoutError::Timeout) => None,
+            Err(RecvTimeoutError::Disconnected) => {
+                panic!("Failed to receive from response from system")
+            }
+        }
+    }
+}
+
+fn run_loop(
+    mut system: System,
+    mut processor: Processor,
+    stats: Arc<Mutex<SessionStats>>,
+    progress: Arc<Mutex<Progress>>,
+) -> Result<(), ()> {
     loop {
         let events = system.poll()?;
-        let effects = events
-            .into_iter()
-            .map(|event| processor.tick(event))
-            .collect::<Result<Vec<_>, _>>()
-            .map_err(|_| ())?;
+        let effects = {
+            let mut stats = stats.lock().unwrap();
+            events
+                .into_iter()
+                .map(|event| stats.tick(&mut processor, event))
+                .collect::<Result<Vec<_>, _>>()
+                .map_err(|_| ())?
+        };
 
         let effects = effects.into_iter().flatten().collect();
 
+        let endpoint = system.current_endpoint();
         system.dispatch(effects)?;
+        if system.current_endpoint() != endpoint {
+            processor.clear_duplicate_window();
+        }
+        progress.lock().unwrap().update(&processor);
     }
 }
 
 impl Client {
-    pub fn new(endpoint: Endpoint) -> Self {
-        let socket = UdpSocket::bind("0.0.0.0:0").unwrap();
-        socket.set_nonblocking(true).unwrap();
-        let connect_address = format!(
-            "{}:{}",
-            endpoint.host,
-            endpoint
-                .port
-                .map(|p| p.value())
-                .unwrap_or(Default::default())
-        );
-        socket.connect(&connect_address).unwrap();
+    /// Creates a client, which fails when the socket can't be bound as `socket_config` says or
+    /// `endpoint` has no address the socket can send to.
+    pub fn new(endpoint: Endpoint, socket_config: SocketConfig) -> Result<Self, Error> {
+        Self::with_interceptors(endpoint, socket_config, vec![])
+    }
+
+    /// Creates a client where every request and response passes through the interceptors, in
+    /// order.
+    pub fn with_interceptors(
+        endpoint: Endpoint,
+        socket_config: SocketConfig,
+        interceptors: Vec<Box<dyn RequestInterceptor>>,
+    ) -> Result<Self, Error> {
+        Self::with_failover(vec![endpoint], socket_config, interceptors)
+    }
+
+    /// Creates a client that sends to the first of `endpoints`. A request that times out, or fails
+    /// since the endpoint is unreachable, is sent again to the next endpoint, which every request
+    /// after it goes to as well, until each endpoint had a go.
+    /// [`Metrics::endpoint`](crate::protocol::response::Metrics::endpoint) of a response tells
+    /// which endpoint answered.
+    pub fn with_failover(
+        endpoints: Vec<Endpoint>,
+        socket_config: SocketConfig,
+        interceptors: Vec<Box<dyn RequestInterceptor>>,
+    ) -> Result<Self, Error> {
+        Self::with_resolver(endpoints, socket_config, interceptors, SystemResolver)
+    }
+
+    /// Creates a client like [`with_failover`](Client::with_failover), where `resolver` turns the
+    /// endpoints into addresses. Every address of the local address family is a failover
+    /// candidate, in the order the resolver returned them.
+    pub fn with_resolver(
+        endpoints: Vec<Endpoint>,
+        socket_config: SocketConfig,
+        interceptors: Vec<Box<dyn RequestInterceptor>>,
+        resolver: impl Resolve,
+    ) -> Result<Self, Error> {
+        Self::with_profile(
+            endpoints,
+            socket_config,
+            interceptors,
+            resolver,
+            TransmissionProfile::default(),
+        )
+    }
+
+    /// Creates a client like [`with_resolver`](Client::with_resolver) that applies `profile`,
+    /// see [`Processor::with_profile`].
+    pub fn with_profile(
+        endpoints: Vec<Endpoint>,
+        socket_config: SocketConfig,
+        interceptors: Vec<Box<dyn RequestInterceptor>>,
+        resolver: impl Resolve,
+        profile: TransmissionProfile,
+    ) -> Result<Self, Error> {
+        Self::create(
+            endpoints,
+            socket_config,
+            interceptors,
+            resolver,
+            profile,
+            None,
+        )
+    }
+
+    /// Creates a client like [`with_profile`](Client::with_profile) that hands every datagram it
+    /// sends or receives to `capture`.
+    pub fn with_capture(
+        endpoints: Vec<Endpoint>,
+        socket_config: SocketConfig,
+        interceptors: Vec<Box<dyn RequestInterceptor>>,
+        resolver: impl Resolve,
+        profile: TransmissionProfile,
+        capture: Box<dyn Capture>,
+    ) -> Result<Self, Error> {
+        Self::create(
+            endpoints,
+            socket_config,
+            interceptors,
+            resolver,
+            profile,
+            Some(capture),
+        )
+    }
+
+    fn create(
+        endpoints: Vec<Endpoint>,
+        socket_config: SocketConfig,
+        interceptors: Vec<Box<dyn RequestInterceptor>>,
+        resolver: impl Resolve,
+        profile: TransmissionProfile,
+        capture: Option<Box<dyn Capture>>,
+    ) -> Result<Self, Error> {
+        let socket = socket_config.bind()?;
+        let local_address = socket
+            .local_addr()
+            .map_err(|e| socket_config::Error::Bind(e.kind()))?;
+
+        let mut addresses: Vec<SocketAddr> = vec![];
+        for endpoint in &endpoints {
+            let resolved = resolver
+                .resolve(endpoint)
+                .map_err(|e| Error::Resolve(e.kind()))?;
+            addresses.extend(
+                resolved
+                    .into_iter()
+                    .filter(|address| address.is_ipv4() == local_address.is_ipv4()),
+            );
+        }
+        let Some(&peer) = addresses.first() else {
+            return Err(Error::NoAddress);
+        };
+        if !socket_config.accepts_any_source() {
+            socket.connect(peer).map_err(|e| Error::Connect(e.kind()))?;
+        }
 
         let initial_message_id = MessageId::from_value(rand::random());
         let message_id_store = MessageIdStore::new(initial_message_id);
+        let processor = interceptors.into_iter().fold(
+            Processor::new(message_id_store)
+                .with_profile(profile)
+                .with_entity_too_large_policy(socket_config.entity_too_large())
+                .with_reserved_policy(socket_config.reserved()),
+            Processor::with_boxed_interceptor,
+        );
 
-        let system = System::new(socket);
+        let mut system = System::new(socket)
+            .with_failover(addresses)
+            .with_max_datagram_size(socket_config.receive_size())
+            .with_token_length(socket_config.tokens());
+        if socket_config.accepts_any_source() {
+            warn!(
+                "Taking responses to requests for {peer} from any address, matched by token alone"
+            );
+            system = system.with_any_source(peer);
+        }
+        if let Some(capture) = capture {
+            system = system.with_capture(capture);
+        }
+        let mut unknown_messages = None;
+        if socket_config.reserved() == ReservedPolicy::Deliver {
+            let (sender, receiver) = channel();
+            system = system.with_unknown_messages(sender);
+            unknown_messages = Some(receiver);
+        }
         let request_sender = system.get_sender();
+        let progress = system.progress();
+        let stats = Arc::new(Mutex::new(SessionStats::new()));
 
-        spawn(|| run_loop(system, message_id_store));
+        let loop_stats = stats.clone();
+        let loop_progress = progress.clone();
+        spawn(|| run_loop(system, processor, loop_stats, loop_progress));
 
-        Self { request_sender }
+        Ok(Self {
+            profile,
+            progress,
+            request_sender,
+            stats,
+            unknown_messages: Arc::new(Mutex::new(unknown_messages)),
+        })
+    }
+
+    /// Transmission parameters the client was created with, to build the parameters of requests
+    /// from.
+    pub fn profile(&self) -> &TransmissionProfile {
+        &self.profile
+    }
+
+    /// Messages with a reserved code, as whole datagrams, when the client was created with
+    /// [`ReservedPolicy::Deliver`], see [`SocketConfig::reserved_policy`]. Only the first call
+    /// gets them, the messages are kept until they are received.
+    pub fn unknown_messages(&self) -> Option<Receiver<Vec<u8>>> {
+        self.unknown_messages.lock().unwrap().take()
+    }
+
+    /// Snapshot of the counters since the client was created.
+    pub fn stats(&self) -> SessionStats {
+        *self.stats.lock().unwrap()
+    }
+
+    /// Where the request of `token` stands, see [`ResponseHandle::token`]. The tokens of resolved
+    /// requests and unknown tokens are [`State::Resolved`](crate::protocol::status::State).
+    pub fn status(&self, token: &Token) -> Status {
+        self.progress.lock().unwrap().status(token)
     }
 
     pub fn ping(&self, ping: Ping) -> Result<(), ping::Error> {
@@ -82,14 +332,59 @@ impl Client {
             .expect("Failed to receive from response from system")
     }
 
-    pub fn execute(&self, request: NewRequest) -> Result<Response, response::Error> {
+    /// Pings the peer every `interval` until the returned handle is dropped, the handle flips to
+    /// [`ConnectionState::Disconnected`] once too many pings in a row went unanswered.
+    pub fn keep_alive(&self, keep_alive: KeepAlive) -> KeepAliveHandle {
+        let liveness = Arc::new(Mutex::new(Liveness::new(keep_alive.max_missed_pongs)));
+        let stopped = Arc::new(AtomicBool::new(false));
+
+        let client = self.clone();
+        let thread_liveness = liveness.clone();
+        let thread_stopped = stopped.clone();
+        spawn(move || loop {
+            sleep(keep_alive.interval);
+            if thread_stopped.load(Ordering::Relaxed) {
+                return;
+            }
+
+            let result = client.ping(keep_alive.ping.clone());
+
+            let mut liveness = thread_liveness.lock().unwrap();
+            let previous = liveness.state();
+            let state = liveness.on_ping_result(&result);
+            if state != previous {
+                warn!("Keep-alive connection state changed to {state:?}");
+            }
+        });
+
+        KeepAliveHandle { liveness, stopped }
+    }
+
+    /// Sends an empty acknowledgement for `message_id` without waiting for anything in return.
+    pub fn send_acknowledgement(&self, message_id: MessageId) {
+        self.send(EmptyMessage::Acknowledgement(message_id))
+    }
+
+    /// Sends a reset for `message_id`, e.g. to reject a notification, without waiting for anything
+    /// in return.
+    pub fn send_reset(&self, message_id: MessageId) {
+        self.send(EmptyMessage::Reset(message_id))
+    }
+
+    fn send(&self, empty_message: EmptyMessage) {
+        self.request_sender
+            .send(Command::Send(empty_message))
+            .expect("Failed to send to system");
+    }
+
+    pub fn execute(&self, request: NewRequest) -> ResponseHandle {
         let (sender, receiver) = System::new_request_channel();
         self.request_sender
             .send(Command::Request(request, sender))
             .expect("Failed to send to system");
 
         use system::Request::*;
-        let (_token, receiver) = match receiver
+        let (token, receiver) = match receiver
             .recv()
             .expect("Failed to receive request accepted from system")
         {
@@ -97,8 +392,203 @@ impl Client {
             _ => unreachable!(),
         };
 
-        receiver
-            .recv()
-            .expect("Failed to receive from response from system")
+        ResponseHandle { token, receiver }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        collections::HashSet,
+        io,
+        net::{Ipv4Addr, Ipv6Addr, SocketAddr, UdpSocket},
+        time::Duration,
+    };
+
+    use pretty_assertions::assert_eq;
+    use rstest::rstest;
+
+    use crate::{
+        client::{
+            socket_config::{self, SocketConfig},
+            Error,
+        },
+        codec::{
+            message::{GetOptions, Message, PutOptions, Request},
+            option::{Block1, Size1},
+            url::Endpoint,
+            MessageId, Options, Payload, Piggyback, ResponseCode, TokenLength,
+        },
+        protocol::{
+            get::Get,
+            new_request::NewRequest,
+            processor::{EntityTooLargePolicy, ReservedPolicy},
+            put::Put,
+            reliability::Reliability,
+            transmission_parameters::{ConfirmableParameters, InitialRetransmissionFactor},
+        },
+    };
+
+    use super::Client;
+
+    fn endpoint() -> Endpoint {
+        Endpoint::from_str("coap://127.0.0.1").unwrap()
+    }
+
+    #[rstest]
+    fn new_rejects_invalid_dscp() {
+        assert_eq!(
+            Some(Error::Socket(socket_config::Error::Dscp(64))),
+            Client::new(endpoint(), SocketConfig::new().dscp(64)).err()
+        );
+    }
+
+    #[rstest]
+    fn with_resolver_returns_resolve_error() {
+        let resolver = |_: &Endpoint| Err(io::ErrorKind::NotFound.into());
+
+        assert_eq!(
+            Some(Error::Resolve(io::ErrorKind::NotFound)),
+            Client::with_resolver(vec![endpoint()], SocketConfig::new(), vec![], resolver).err()
+        );
+    }
+
+    #[rstest]
+    fn with_resolver_rejects_addresses_of_other_family() {
+        let resolver = |_: &Endpoint| Ok(vec![SocketAddr::from((Ipv6Addr::LOCALHOST, 5683))]);
+
+        assert_eq!(
+            Some(Error::NoAddress),
+            Client::with_resolver(vec![endpoint()], SocketConfig::new(), vec![], resolver).err()
+        );
+    }
+
+    #[rstest]
+    fn delivers_unknown_messages() {
+        let server = UdpSocket::bind((Ipv4Addr::LOCALHOST, 0)).unwrap();
+        server
+            .set_read_timeout(Some(Duration::from_secs(5)))
+            .unwrap();
+        let resolver = {
+            let address = server.local_addr().unwrap();
+            move |_: &Endpoint| Ok(vec![address])
+        };
+        let client = Client::with_resolver(
+            vec![endpoint()],
+            SocketConfig::new().reserved_policy(ReservedPolicy::Deliver),
+            vec![],
+            resolver,
+        )
+        .unwrap();
+        let unknown_messages = client.unknown_messages().unwrap();
+
+        client.send_reset(MessageId::from_value(1));
+        let (_, client_address) = server.recv_from(&mut [0; 16]).unwrap();
+        server
+            .send_to(&[0x40, 0xe0, 0x00, 0x07], client_address)
+            .unwrap();
+
+        assert_eq!(
+            Ok(vec![0x40, 0xe0, 0x00, 0x07]),
+            unknown_messages.recv_timeout(Duration::from_secs(5))
+        );
+        assert!(client.unknown_messages().is_none());
+    }
+
+    #[rstest]
+    fn unknown_messages_without_deliver_policy() {
+        let client = Client::new(endpoint(), SocketConfig::new()).unwrap();
+
+        assert!(client.unknown_messages().is_none());
+    }
+
+    #[rstest]
+    fn entity_too_large_policy_applies() {
+        let server = UdpSocket::bind((Ipv4Addr::LOCALHOST, 0)).unwrap();
+        server
+            .set_read_timeout(Some(Duration::from_secs(5)))
+            .unwrap();
+        let resolver = {
+            let address = server.local_addr().unwrap();
+            move |_: &Endpoint| Ok(vec![address])
+        };
+        let client = Client::with_resolver(
+            vec![endpoint()],
+            SocketConfig::new().entity_too_large_policy(EntityTooLargePolicy::AutoBlockwise),
+            vec![],
+            resolver,
+        )
+        .unwrap();
+        let mut buffer = [0; 128];
+        let mut receive_put = || {
+            let (read, client_address) = server.recv_from(&mut buffer).unwrap();
+            match Message::decode(&buffer[..read]).unwrap() {
+                Message::Request(Request::Put(put)) => (put, client_address),
+                message => panic!("expected a PUT, got {message:?}"),
+            }
+        };
+
+        let _handle = client.execute(NewRequest::Put(Put {
+            options: PutOptions::new(),
+            reliability: Reliability::Confirmable(ConfirmableParameters::default(
+                InitialRetransmissionFactor::new(0.5).unwrap(),
+            )),
+            payload: Payload::from_value((0..40).collect()),
+        }));
+        let (put, client_address) = receive_put();
+        let mut options = Options::new();
+        options.set_size1(Size1::new(16));
+        let entity_too_large = Piggyback::new(
+            *put.token(),
+            ResponseCode::REQUEST_ENTITY_TOO_LARGE,
+            put.message_id(),
+            options,
+            Payload::empty(),
+        );
+        server
+            .send_to(&entity_too_large.encode(), client_address)
+            .unwrap();
+
+        let (put, _) = receive_put();
+        assert_eq!(
+            Some(&Block1::new(0, true, 0).unwrap()),
+            put.options().block1()
+        );
+        assert_eq!(16, put.payload().value().len());
+    }
+
+    #[rstest]
+    fn tokens_in_flight_are_not_handed_out_again() {
+        let server = UdpSocket::bind((Ipv4Addr::LOCALHOST, 0)).unwrap();
+        let resolver = {
+            let address = server.local_addr().unwrap();
+            move |_: &Endpoint| Ok(vec![address])
+        };
+        let client = Client::with_resolver(
+            vec![endpoint()],
+            SocketConfig::new().token_length(TokenLength::from_value(1).unwrap()),
+            vec![],
+            resolver,
+        )
+        .unwrap();
+
+        // Among 32 random single byte tokens two are alike more often than not.
+        let handles = (0..32)
+            .map(|_| {
+                client.execute(NewRequest::Get(Get {
+                    options: GetOptions::new(),
+                    reliability: Reliability::Confirmable(ConfirmableParameters::default(
+                        InitialRetransmissionFactor::new(0.5).unwrap(),
+                    )),
+                }))
+            })
+            .collect::<Vec<_>>();
+
+        let tokens = handles
+            .iter()
+            .map(|handle| *handle.token())
+            .collect::<HashSet<_>>();
+        assert_eq!(32, tokens.len());
+        assert!(tokens.iter().all(|token| token.as_bytes().len() == 1));
     }
 }
@@ -0,0 +1,144 @@
+use std::{
+    collections::HashMap,
+    io,
+    net::{IpAddr, Ipv4Addr, Ipv6Addr, SocketAddr, UdpSocket},
+    time::{Duration, Instant},
+};
+
+use log::debug;
+
+use crate::{
+    codec::{
+        link_format::{self, Link},
+        message::{GetOptions, Message},
+        Acknowledgement, MessageId, Token,
+    },
+    protocol::{
+        get::Get, new_request::NewRequest, reliability::Reliability,
+        transmission_parameters::NonConfirmableParameters,
+    },
+};
+
+pub const ALL_COAP_NODES_V4: Ipv4Addr = Ipv4Addr::new(224, 0, 1, 187);
+pub const ALL_COAP_NODES_LINK_LOCAL: Ipv6Addr = Ipv6Addr::new(0xff02, 0, 0, 0, 0, 0, 0, 0xfd);
+pub const ALL_COAP_NODES_SITE_LOCAL: Ipv6Addr = Ipv6Addr::new(0xff05, 0, 0, 0, 0, 0, 0, 0xfd);
+pub const DEFAULT_PORT: u16 = 5683;
+
+const POLL_INTERVAL: Duration = Duration::from_millis(50);
+
+/// Resources of every endpoint that answered, by the address it answered from.
+pub type Endpoints = HashMap<SocketAddr, Vec<Link>>;
+
+/// Multicasts a non-confirmable GET `/.well-known/core` to the All CoAP Nodes addresses and
+/// collects the link-format answers that arrive within `timeout`.
+///
+/// The request goes out on the interface the operating system picks for each address, as the
+/// standard library can't list interfaces. An address family that can't be used, like IPv6 on a
+/// host without it, is skipped, only when no request could be sent at all is it an error.
+/// Answers that aren't success responses or aren't link-format are left out.
+pub fn discover(timeout: Duration) -> io::Result<Endpoints> {
+    let token = Token::new().map_err(|_| io::Error::other("failed to generate a token"))?;
+    let request = request(token);
+
+    let sockets = [
+        send(
+            (Ipv4Addr::UNSPECIFIED, 0).into(),
+            &[ALL_COAP_NODES_V4.into()],
+            &request,
+        ),
+        send(
+            (Ipv6Addr::UNSPECIFIED, 0).into(),
+            &[
+                ALL_COAP_NODES_LINK_LOCAL.into(),
+                ALL_COAP_NODES_SITE_LOCAL.into(),
+            ],
+            &request,
+        ),
+    ];
+    let mut last_error = None;
+    let sockets = sockets
+        .into_iter()
+        .filter_map(|socket| socket.map_err(|e| last_error = Some(e)).ok())
+        .collect::<Vec<_>>();
+    if sockets.is_empty() {
+        return Err(last_error.unwrap_or_else(|| io::ErrorKind::AddrNotAvailable.into()));
+    }
+
+    let mut endpoints = Endpoints::new();
+    let deadline = Instant::now() + timeout;
+    let mut buffer = [0; 1500];
+    while let Some(remaining) = deadline.checked_duration_since(Instant::now()) {
+        for socket in &sockets {
+            socket.set_read_timeout(Some(
+                remaining.min(POLL_INTERVAL).max(Duration::from_millis(1)),
+            ))?;
+            let Ok((size, source)) = socket.recv_from(&mut buffer) else {
+                continue;
+            };
+            if let Some(links) = links(socket, &buffer[..size], source, &token) {
+                endpoints.entry(source).or_default().extend(links);
+            }
+        }
+    }
+
+    Ok(endpoints)
+}
+
+fn request(token: Token) -> Vec<u8> {
+    let mut options = GetOptions::new();
+    options.set_uri_path(
+        ".well-known/core"
+            .try_into()
+            .expect("well-known core path is a valid Uri-Path"),
+    );
+
+    NewRequest::Get(Get {
+        options,
+        reliability: Reliability::NonConfirmable(NonConfirmableParameters::default()),
+    })
+    .encode(MessageId::from_value(rand::random()), token)
+}
+
+/// Binds a socket of the family of `local_address` and sends `request` to each of `groups`,
+/// the socket is kept as long as one of them could be sent to.
+fn send(local_address: SocketAddr, groups: &[IpAddr], request: &[u8]) -> io::Result<UdpSocket> {
+    let socket = UdpSocket::bind(local_address)?;
+
+    let mut result = Err(io::ErrorKind::AddrNotAvailable.into());
+    for group in groups {
+        let group = SocketAddr::new(*group, DEFAULT_PORT);
+        match socket.send_to(request, group) {
+            Ok(_) => result = Ok(()),
+            Err(e) => {
+                debug!("Failed to send discovery request to {group}: {e}");
+                result = result.or(Err(e));
+            }
+        }
+    }
+
+    result.map(|()| socket)
+}
+
+/// Links of a response to the discovery request, acknowledging it if it's confirmable.
+fn links(socket: &UdpSocket, data: &[u8], source: SocketAddr, token: &Token) -> Option<Vec<Link>> {
+    let Ok(Message::Response(response)) = Message::decode(data) else {
+        return None;
+    };
+    if response.token() != token {
+        return None;
+    }
+    if response.reliability().is_confirmable() {
+        let _ = socket.send_to(
+            &Acknowledgement::new(response.message_id()).encode(),
+            source,
+        );
+    }
+    if !response.response_code().is_success() {
+        return None;
+    }
+
+    let links = std::str::from_utf8(response.payload().value()).ok()?;
+    link_format::parse(links)
+        .map_err(|e| debug!("Ignoring discovery response from {source}: {e}"))
+        .ok()
+}
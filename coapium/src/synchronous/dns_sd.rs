@@ -0,0 +1,471 @@
+//! Browsing for CoAP services with DNS-SD over mDNS, RFC 6763 and RFC 6762.
+//!
+//! Unlike [`discovery`](super::discovery), which asks CoAP endpoints for their resources, this
+//! finds the services announced on the local link along with their host, port and TXT record.
+//! Only what the responders send within the timeout is used, a host whose addresses aren't among
+//! the answers is left for a [`Resolve`](crate::client::resolve::Resolve) to look up.
+
+use std::{
+    collections::HashMap,
+    io,
+    net::{IpAddr, Ipv4Addr, Ipv6Addr, SocketAddr, UdpSocket},
+    time::{Duration, Instant},
+};
+
+use log::debug;
+
+use crate::client::url::{self, Url};
+
+pub const MDNS_V4: Ipv4Addr = Ipv4Addr::new(224, 0, 0, 251);
+pub const MDNS_V6: Ipv6Addr = Ipv6Addr::new(0xff02, 0, 0, 0, 0, 0, 0, 0xfb);
+pub const MDNS_PORT: u16 = 5353;
+
+const POLL_INTERVAL: Duration = Duration::from_millis(50);
+
+const TYPE_A: u16 = 1;
+const TYPE_PTR: u16 = 12;
+const TYPE_TXT: u16 = 16;
+const TYPE_AAAA: u16 = 28;
+const TYPE_SRV: u16 = 33;
+const CLASS_IN: u16 = 1;
+/// Compression pointers followed in one name at most, more means the pointers loop.
+const MAX_POINTERS: usize = 16;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum ServiceType {
+    /// `_coap._udp`
+    Coap,
+    /// `_coaps._udp`
+    Coaps,
+}
+
+impl ServiceType {
+    pub fn name(&self) -> &'static str {
+        match self {
+            ServiceType::Coap => "_coap._udp.local",
+            ServiceType::Coaps => "_coaps._udp.local",
+        }
+    }
+
+    fn scheme(&self) -> &'static str {
+        match self {
+            ServiceType::Coap => "coap",
+            ServiceType::Coaps => "coaps",
+        }
+    }
+}
+
+/// Instance of a service found by [`browse`].
+#[derive(Clone, Debug, PartialEq)]
+pub struct Service {
+    /// Full name of the instance, like `Kitchen lamp._coap._udp.local`.
+    pub instance: String,
+    pub service_type: ServiceType,
+    /// Target of the SRV record, like `lamp.local`.
+    pub host: String,
+    pub port: u16,
+    /// Addresses of the host among the answers, empty when none were sent along.
+    pub addresses: Vec<IpAddr>,
+    /// Entries of the TXT record, `key=value` or a lone `key`.
+    pub txt: Vec<String>,
+}
+
+impl Service {
+    /// Value of the TXT entry `key`, empty for a lone key. Keys are case insensitive, RFC 6763
+    /// §6.4.
+    pub fn txt(&self, key: &str) -> Option<&str> {
+        self.txt.iter().find_map(|entry| {
+            let (entry_key, value) = entry.split_once('=').unwrap_or((entry, ""));
+            entry_key.eq_ignore_ascii_case(key).then_some(value)
+        })
+    }
+
+    /// Url of `path` on the service, so it can be queried right away. It's on the first address
+    /// of the service, or on the host when there is none.
+    pub fn url(&self, path: &str) -> Result<Url, url::Error> {
+        let host = match self.addresses.first() {
+            Some(IpAddr::V4(address)) => address.to_string(),
+            Some(IpAddr::V6(address)) => format!("[{address}]"),
+            None => self.host.clone(),
+        };
+        let path = path.trim_start_matches('/');
+
+        format!(
+            "{}://{host}:{}/{path}",
+            self.service_type.scheme(),
+            self.port
+        )
+        .try_into()
+    }
+}
+
+/// Records of the mDNS answers, by lowercase owner name since DNS names are case insensitive.
+#[derive(Debug, Default)]
+struct Records {
+    instances: Vec<(String, String)>,
+    targets: HashMap<String, (String, u16)>,
+    txt: HashMap<String, Vec<String>>,
+    addresses: HashMap<String, Vec<IpAddr>>,
+}
+
+/// Multicasts a PTR query for each of `service_types` to the mDNS addresses and collects the
+/// instances announced within `timeout`.
+///
+/// The query is sent from an ephemeral port, so responders answer it with unicast, RFC 6762
+/// §6.7. Like [`discover`](super::discovery::discover), an address family that can't be used is
+/// skipped and only when no query could be sent at all is it an error. Instances without an SRV
+/// record among the answers are left out.
+pub fn browse(service_types: &[ServiceType], timeout: Duration) -> io::Result<Vec<Service>> {
+    let query = query(service_types);
+
+    let sockets = [
+        send((Ipv4Addr::UNSPECIFIED, 0).into(), MDNS_V4.into(), &query),
+        send((Ipv6Addr::UNSPECIFIED, 0).into(), MDNS_V6.into(), &query),
+    ];
+    let mut last_error = None;
+    let sockets = sockets
+        .into_iter()
+        .filter_map(|socket| socket.map_err(|e| last_error = Some(e)).ok())
+        .collect::<Vec<_>>();
+    if sockets.is_empty() {
+        return Err(last_error.unwrap_or_else(|| io::ErrorKind::AddrNotAvailable.into()));
+    }
+
+    let mut records = Records::default();
+    let deadline = Instant::now() + timeout;
+    let mut buffer = [0; 9000];
+    while let Some(remaining) = deadline.checked_duration_since(Instant::now()) {
+        for socket in &sockets {
+            socket.set_read_timeout(Some(
+                remaining.min(POLL_INTERVAL).max(Duration::from_millis(1)),
+            ))?;
+            let Ok((size, source)) = socket.recv_from(&mut buffer) else {
+                continue;
+            };
+            if parse(&buffer[..size], &mut records).is_none() {
+                debug!("Ignoring malformed mDNS response from {source}");
+            }
+        }
+    }
+
+    Ok(services(&records, service_types))
+}
+
+fn send(local_address: SocketAddr, group: IpAddr, query: &[u8]) -> io::Result<UdpSocket> {
+    let socket = UdpSocket::bind(local_address)?;
+    let group = SocketAddr::new(group, MDNS_PORT);
+    if let Err(e) = socket.send_to(query, group) {
+        debug!("Failed to send mDNS query to {group}: {e}");
+        return Err(e);
+    }
+
+    Ok(socket)
+}
+
+fn services(records: &Records, service_types: &[ServiceType]) -> Vec<Service> {
+    let mut services = Vec::<Service>::new();
+    for (owner, instance) in &records.instances {
+        let Some(service_type) = service_types
+            .iter()
+            .find(|service_type| service_type.name().eq_ignore_ascii_case(owner))
+        else {
+            continue;
+        };
+        if services
+            .iter()
+            .any(|service| service.instance.eq_ignore_ascii_case(instance))
+        {
+            continue;
+        }
+        let key = instance.to_ascii_lowercase();
+        let Some((host, port)) = records.targets.get(&key) else {
+            continue;
+        };
+
+        services.push(Service {
+            instance: instance.clone(),
+            service_type: *service_type,
+            host: host.clone(),
+            port: *port,
+            addresses: records
+                .addresses
+                .get(&host.to_ascii_lowercase())
+                .cloned()
+                .unwrap_or_default(),
+            txt: records.txt.get(&key).cloned().unwrap_or_default(),
+        });
+    }
+
+    services
+}
+
+fn query(service_types: &[ServiceType]) -> Vec<u8> {
+    let mut query = vec![0; 12];
+    query[4..6].copy_from_slice(&(service_types.len() as u16).to_be_bytes());
+
+    for service_type in service_types {
+        for label in service_type.name().split('.') {
+            query.push(label.len() as u8);
+            query.extend_from_slice(label.as_bytes());
+        }
+        query.push(0);
+        query.extend_from_slice(&TYPE_PTR.to_be_bytes());
+        query.extend_from_slice(&CLASS_IN.to_be_bytes());
+    }
+
+    query
+}
+
+/// Adds the records of a response to `records`, `None` if it's malformed. Records of every
+/// section are used, responders put the SRV, TXT and address records in the additional one.
+fn parse(data: &[u8], records: &mut Records) -> Option<()> {
+    let header = data.get(..12)?;
+    if header[2] & 0x80 == 0 {
+        return Some(());
+    }
+    let count = |index: usize| u16::from_be_bytes([header[index], header[index + 1]]) as usize;
+    let questions = count(4);
+    let answers = count(6) + count(8) + count(10);
+
+    let mut offset = 12;
+    for _ in 0..questions {
+        offset = name(data, offset)?.1 + 4;
+    }
+
+    for _ in 0..answers {
+        let (owner, end) = name(data, offset)?;
+        let fixed = data.get(end..end + 10)?;
+        let record_type = u16::from_be_bytes([fixed[0], fixed[1]]);
+        let length = u16::from_be_bytes([fixed[8], fixed[9]]) as usize;
+        let start = end + 10;
+        let rdata = data.get(start..start + length)?;
+        offset = start + length;
+
+        let owner_key = owner.to_ascii_lowercase();
+        match record_type {
+            TYPE_PTR => records.instances.push((owner, name(data, start)?.0)),
+            TYPE_SRV => {
+                let port = u16::from_be_bytes([*rdata.get(4)?, *rdata.get(5)?]);
+                let (target, _) = name(data, start + 6)?;
+                records.targets.insert(owner_key, (target, port));
+            }
+            TYPE_TXT => {
+                records.txt.insert(owner_key, txt(rdata)?);
+            }
+            TYPE_A => {
+                let address: [u8; 4] = rdata.try_into().ok()?;
+                add_address(records, owner_key, Ipv4Addr::from(address).into());
+            }
+            TYPE_AAAA => {
+                let address: [u8; 16] = rdata.try_into().ok()?;
+                add_address(records, owner_key, Ipv6Addr::from(address).into());
+            }
+            _ => {}
+        }
+    }
+
+    Some(())
+}
+
+fn add_address(records: &mut Records, host: String, address: IpAddr) {
+    let addresses = records.addresses.entry(host).or_default();
+    if !addresses.contains(&address) {
+        addresses.push(address);
+    }
+}
+
+/// Entries of a TXT record. An empty record is sent as a single empty string, RFC 6763 §6.1, and
+/// has no entries.
+fn txt(mut rdata: &[u8]) -> Option<Vec<String>> {
+    let mut entries = vec![];
+    while let Some((&length, rest)) = rdata.split_first() {
+        let entry = rest.get(..length as usize)?;
+        if !entry.is_empty() {
+            entries.push(String::from_utf8_lossy(entry).into_owned());
+        }
+        rdata = &rest[length as usize..];
+    }
+
+    Some(entries)
+}
+
+/// Name at `offset` and the offset right after it, following compression pointers, RFC 1035
+/// §4.1.4.
+fn name(data: &[u8], mut offset: usize) -> Option<(String, usize)> {
+    let mut labels = vec![];
+    let mut end = None;
+    let mut pointers = 0;
+
+    loop {
+        let length = *data.get(offset)?;
+        match length {
+            0 => break,
+            length if length & 0xc0 == 0xc0 => {
+                pointers += 1;
+                if pointers > MAX_POINTERS {
+                    return None;
+                }
+                end.get_or_insert(offset + 2);
+                offset = u16::from_be_bytes([length & 0x3f, *data.get(offset + 1)?]) as usize;
+            }
+            length => {
+                let label = data.get(offset + 1..offset + 1 + length as usize)?;
+                labels.push(String::from_utf8_lossy(label).into_owned());
+                offset += 1 + length as usize;
+            }
+        }
+    }
+
+    Some((labels.join("."), end.unwrap_or(offset + 1)))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::net::{IpAddr, Ipv4Addr, Ipv6Addr};
+
+    use pretty_assertions::assert_eq;
+    use rstest::rstest;
+
+    use super::{
+        parse, query, services, Records, Service, ServiceType, TYPE_A, TYPE_PTR, TYPE_SRV, TYPE_TXT,
+    };
+
+    /// Answer of a responder to a PTR query for `_coap._udp.local`, with the SRV, TXT, A and AAAA
+    /// records in the additional section and names compressed.
+    const CAPTURED: &[u8] = &[
+        0x00, 0x00, 0x84, 0x00, 0x00, 0x00, 0x00, 0x01, 0x00, 0x00, 0x00, 0x04, 0x05, 0x5f, 0x63,
+        0x6f, 0x61, 0x70, 0x04, 0x5f, 0x75, 0x64, 0x70, 0x05, 0x6c, 0x6f, 0x63, 0x61, 0x6c, 0x00,
+        0x00, 0x0c, 0x00, 0x01, 0x00, 0x00, 0x00, 0x78, 0x00, 0x0f, 0x0c, 0x4b, 0x69, 0x74, 0x63,
+        0x68, 0x65, 0x6e, 0x20, 0x6c, 0x61, 0x6d, 0x70, 0xc0, 0x0c, 0xc0, 0x28, 0x00, 0x21, 0x80,
+        0x01, 0x00, 0x00, 0x00, 0x78, 0x00, 0x0d, 0x00, 0x00, 0x00, 0x00, 0x16, 0x33, 0x04, 0x6c,
+        0x61, 0x6d, 0x70, 0xc0, 0x17, 0xc0, 0x28, 0x00, 0x10, 0x80, 0x01, 0x00, 0x00, 0x00, 0x78,
+        0x00, 0x0a, 0x06, 0x72, 0x74, 0x3d, 0x6c, 0x75, 0x78, 0x02, 0x69, 0x66, 0xc0, 0x49, 0x00,
+        0x01, 0x80, 0x01, 0x00, 0x00, 0x00, 0x78, 0x00, 0x04, 0xc0, 0xa8, 0x01, 0x14, 0xc0, 0x49,
+        0x00, 0x1c, 0x80, 0x01, 0x00, 0x00, 0x00, 0x78, 0x00, 0x10, 0xfe, 0x80, 0x00, 0x00, 0x00,
+        0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x01,
+    ];
+
+    fn encode_name(name: &str) -> Vec<u8> {
+        let mut bytes = vec![];
+        for label in name.split('.') {
+            bytes.push(label.len() as u8);
+            bytes.extend_from_slice(label.as_bytes());
+        }
+        bytes.push(0);
+        bytes
+    }
+
+    fn record(owner: &str, record_type: u16, rdata: Vec<u8>) -> Vec<u8> {
+        let mut bytes = encode_name(owner);
+        bytes.extend_from_slice(&record_type.to_be_bytes());
+        bytes.extend_from_slice(&[0x80, 0x01, 0, 0, 0, 120]);
+        bytes.extend_from_slice(&(rdata.len() as u16).to_be_bytes());
+        bytes.extend_from_slice(&rdata);
+        bytes
+    }
+
+    fn srv(port: u16, target: &str) -> Vec<u8> {
+        let mut rdata = vec![0, 0, 0, 0];
+        rdata.extend_from_slice(&port.to_be_bytes());
+        rdata.extend_from_slice(&encode_name(target));
+        rdata
+    }
+
+    fn response(records: &[Vec<u8>]) -> Vec<u8> {
+        let mut bytes = vec![0, 0, 0x84, 0, 0, 0, 0, records.len() as u8, 0, 0, 0, 0];
+        for record in records {
+            bytes.extend_from_slice(record);
+        }
+        bytes
+    }
+
+    fn lamp(addresses: Vec<IpAddr>, txt: Vec<&str>) -> Service {
+        Service {
+            instance: "Kitchen lamp._coap._udp.local".to_string(),
+            service_type: ServiceType::Coap,
+            host: "lamp.local".to_string(),
+            port: 5683,
+            addresses,
+            txt: txt.into_iter().map(String::from).collect(),
+        }
+    }
+
+    #[rstest]
+    #[case::captured(
+        CAPTURED.to_vec(),
+        Some(vec![lamp(
+            vec![
+                Ipv4Addr::new(192, 168, 1, 20).into(),
+                Ipv6Addr::new(0xfe80, 0, 0, 0, 0, 0, 0, 1).into(),
+            ],
+            vec!["rt=lux", "if"],
+        )])
+    )]
+    #[case::case_insensitive_owners(
+        response(&[
+            record("_COAP._UDP.local", TYPE_PTR, encode_name("Kitchen lamp._coap._udp.local")),
+            record("KITCHEN LAMP._coap._udp.LOCAL", TYPE_SRV, srv(5683, "lamp.local")),
+            record("kitchen lamp._coap._udp.local", TYPE_TXT, vec![6, b'r', b't', b'=', b'l', b'u', b'x']),
+            record("LAMP.local", TYPE_A, vec![192, 168, 1, 20]),
+        ]),
+        Some(vec![lamp(vec![Ipv4Addr::new(192, 168, 1, 20).into()], vec!["rt=lux"])])
+    )]
+    #[case::empty_txt(
+        response(&[
+            record("_coap._udp.local", TYPE_PTR, encode_name("Kitchen lamp._coap._udp.local")),
+            record("Kitchen lamp._coap._udp.local", TYPE_SRV, srv(5683, "lamp.local")),
+            record("Kitchen lamp._coap._udp.local", TYPE_TXT, vec![0]),
+        ]),
+        Some(vec![lamp(vec![], vec![])])
+    )]
+    #[case::without_srv(
+        response(&[
+            record("_coap._udp.local", TYPE_PTR, encode_name("Kitchen lamp._coap._udp.local")),
+        ]),
+        Some(vec![])
+    )]
+    #[case::pointer_to_itself(vec![0, 0, 0x84, 0, 0, 0, 0, 1, 0, 0, 0, 0, 0xc0, 0x0c], None)]
+    #[case::pointers_loop(
+        vec![0, 0, 0x84, 0, 0, 0, 0, 1, 0, 0, 0, 0, 0x01, b'a', 0xc0, 0x10, 0x01, b'b', 0xc0, 0x0c],
+        None
+    )]
+    #[case::truncated_rdata(CAPTURED[..CAPTURED.len() - 4].to_vec(), None)]
+    #[case::truncated_srv(
+        response(&[record("Kitchen lamp._coap._udp.local", TYPE_SRV, vec![0, 0, 0, 0, 0x16])]),
+        None
+    )]
+    #[case::truncated_txt_entry(
+        response(&[record("Kitchen lamp._coap._udp.local", TYPE_TXT, vec![6, b'r', b't'])]),
+        None
+    )]
+    #[case::truncated_header(CAPTURED[..11].to_vec(), None)]
+    fn parse_response(#[case] data: Vec<u8>, #[case] expected: Option<Vec<Service>>) {
+        let mut records = Records::default();
+
+        assert_eq!(
+            expected,
+            parse(&data, &mut records).map(|()| services(&records, &[ServiceType::Coap]))
+        );
+    }
+
+    #[rstest]
+    fn queries_are_ignored() {
+        let mut query = CAPTURED.to_vec();
+        query[2] = 0;
+        let mut records = Records::default();
+
+        assert_eq!(Some(()), parse(&query, &mut records));
+        assert!(records.instances.is_empty());
+    }
+
+    #[rstest]
+    fn query_asks_for_ptr_records() {
+        let mut expected = vec![0, 0, 0, 0, 0, 2, 0, 0, 0, 0, 0, 0];
+        for name in ["_coap._udp.local", "_coaps._udp.local"] {
+            expected.extend_from_slice(&encode_name(name));
+            expected.extend_from_slice(&[0, 12, 0, 1]);
+        }
+
+        assert_eq!(expected, query(&[ServiceType::Coap, ServiceType::Coaps]));
+    }
+}
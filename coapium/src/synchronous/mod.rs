@@ -1,14 +1,16 @@
 pub mod client;
+pub mod discovery;
+pub mod dns_sd;
 pub mod system;
 
-use rand::{thread_rng, Rng};
+use std::io;
 
 use crate::{
-    client::url::Url,
+    client::{socket_config::SocketConfig, url::Url},
     codec::{
         message::{DeleteOptions, GetOptions, PostOptions, PutOptions},
-        option::ContentFormat,
-        Payload,
+        option::{self, Accept, ContentFormat},
+        MediaType, Payload,
     },
     protocol::{
         delete::Delete,
@@ -16,40 +18,81 @@ use crate::{
         new_request::NewRequest,
         ping::{self, Ping},
         post::Post,
+        profile::TransmissionProfile,
         put::Put,
         reliability::Reliability,
         request::Method,
-        response::{self, Response},
-        transmission_parameters::{ConfirmableParameters, InitialRetransmissionFactor},
+        response::{self, Response, TypedResponse},
+        transmission_parameters::ConfirmableParameters,
     },
-    synchronous::client::Client,
+    synchronous::{client::Client, discovery::Endpoints},
 };
 
 pub fn default_parameters() -> ConfirmableParameters {
-    ConfirmableParameters::new(
-        Default::default(),
-        Default::default(),
-        initial_retransmission_factor(),
-        Default::default(),
-    )
+    ConfirmableParameters::builder()
+        .build()
+        .expect("Default parameters should be valid")
 }
 
 pub fn default_reliability() -> Reliability {
     Reliability::Confirmable(default_parameters())
 }
 
+/// Resources of the CoAP endpoints on the local network, found by multicasting a GET
+/// `/.well-known/core` and waiting for answers for the default leisure of 5 seconds, see
+/// [`discovery::discover`].
+pub fn discover_local() -> io::Result<Endpoints> {
+    discovery::discover(TransmissionProfile::default().default_leisure())
+}
+
 pub fn get(url: Url) -> Result<Response, response::Error> {
     request(Method::Get, url)
 }
 
-fn initial_retransmission_factor() -> InitialRetransmissionFactor {
-    InitialRetransmissionFactor::new(thread_rng().gen_range(0.0..1.0)).unwrap()
+/// GET with content negotiation, the media types are requested in order of preference until the
+/// server stops answering with 4.06 (Not Acceptable).
+///
+/// When every media type is rejected the last 4.06 response is returned, when no media types are
+/// given the request is sent without an Accept option.
+pub fn get_as(url: Url, media_types: &[MediaType]) -> Result<TypedResponse, response::Error> {
+    let Some((last, preferred)) = media_types.split_last() else {
+        return get(url).map(Into::into);
+    };
+
+    let client = client(&url)?;
+
+    for media_type in preferred {
+        let response = client.execute(negotiated_get(&url, media_type)?).wait()?;
+        if !response.is_not_acceptable() {
+            return Ok(response.into());
+        }
+    }
+
+    client
+        .execute(negotiated_get(&url, last)?)
+        .wait()
+        .map(Into::into)
+}
+
+fn negotiated_get(url: &Url, media_type: &MediaType) -> Result<NewRequest, response::Error> {
+    let accept = Accept::try_from(media_type.clone())
+        .map_err(|error| response::Error::Codec(option::Error::from(error).into()))?;
+
+    let mut options = GetOptions::from(url);
+    options.set_accept(accept);
+
+    Ok(NewRequest::Get(Get {
+        options,
+        reliability: default_reliability(),
+    }))
 }
 
 pub fn ping(url: Url) -> Result<(), ping::Error> {
-    Client::new(url.clone().into()).ping(Ping {
-        confirmable_parameters: default_parameters(),
-    })
+    Client::new(url.clone().into(), SocketConfig::default())
+        .map_err(|error| ping::Error::Transport(error.kind()))?
+        .ping(Ping {
+            confirmable_parameters: default_parameters(),
+        })
 }
 
 pub fn post(url: Url) -> Result<Response, response::Error> {
@@ -61,13 +104,11 @@ pub fn post_payload(
     content_format: ContentFormat,
     payload: Payload,
 ) -> Result<Response, response::Error> {
-    let client = Client::new(url.clone().into());
+    let client = client(&url)?;
 
     let reliability = default_reliability();
 
-    let mut options = PostOptions::new();
-    options.set_uri_path(url.path);
-    options.set_uri_query(url.query);
+    let mut options = PostOptions::from(&url);
     options.set_content_format(content_format);
 
     let request = NewRequest::Post(Post {
@@ -76,7 +117,7 @@ pub fn post_payload(
         payload,
     });
 
-    client.execute(request)
+    client.execute(request).wait()
 }
 
 pub fn put(url: Url) -> Result<Response, response::Error> {
@@ -88,13 +129,11 @@ pub fn put_payload(
     content_format: ContentFormat,
     payload: Payload,
 ) -> Result<Response, response::Error> {
-    let client = Client::new(url.clone().into());
+    let client = client(&url)?;
 
     let reliability = default_reliability();
 
-    let mut options = PutOptions::new();
-    options.set_uri_path(url.path);
-    options.set_uri_query(url.query);
+    let mut options = PutOptions::from(&url);
     options.set_content_format(content_format);
 
     let request = NewRequest::Put(Put {
@@ -103,23 +142,26 @@ pub fn put_payload(
         payload,
     });
 
-    client.execute(request)
+    client.execute(request).wait()
 }
 
 pub fn delete(url: Url) -> Result<Response, response::Error> {
     request(Method::Delete, url)
 }
 
+fn client(url: &Url) -> Result<Client, response::Error> {
+    Client::new(url.clone().into(), SocketConfig::default())
+        .map_err(|error| response::Error::Transport(error.kind()))
+}
+
 pub fn request(method: Method, url: Url) -> Result<Response, response::Error> {
-    let client = Client::new(url.clone().into());
+    let client = client(&url)?;
 
     let reliability = default_reliability();
 
     let request = match method {
         Method::Get => {
-            let mut options = GetOptions::new();
-            options.set_uri_path(url.path);
-            options.set_uri_query(url.query);
+            let options = GetOptions::from(&url);
 
             NewRequest::Get(Get {
                 options,
@@ -127,9 +169,7 @@ pub fn request(method: Method, url: Url) -> Result<Response, response::Error> {
             })
         }
         Method::Post => {
-            let mut options = PostOptions::new();
-            options.set_uri_path(url.path);
-            options.set_uri_query(url.query);
+            let options = PostOptions::from(&url);
 
             NewRequest::Post(Post {
                 options,
@@ -138,9 +178,7 @@ pub fn request(method: Method, url: Url) -> Result<Response, response::Error> {
             })
         }
         Method::Put => {
-            let mut options = PutOptions::new();
-            options.set_uri_path(url.path);
-            options.set_uri_query(url.query);
+            let options = PutOptions::from(&url);
 
             NewRequest::Put(Put {
                 options,
@@ -149,9 +187,7 @@ pub fn request(method: Method, url: Url) -> Result<Response, response::Error> {
             })
         }
         Method::Delete => {
-            let mut options = DeleteOptions::new();
-            options.set_uri_path(url.path);
-            options.set_uri_query(url.query);
+            let options = DeleteOptions::from(&url);
 
             NewRequest::Delete(Delete {
                 options,
@@ -160,5 +196,5 @@ pub fn request(method: Method, url: Url) -> Result<Response, response::Error> {
         }
     };
 
-    client.execute(request)
+    client.execute(request).wait()
 }
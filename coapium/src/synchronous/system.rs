@@ -1,19 +1,24 @@
 use std::{
     io::ErrorKind,
-    net::UdpSocket,
+    net::{SocketAddr, UdpSocket},
     sync::{
         mpsc::{channel, Receiver, Sender},
-        Arc,
+        Arc, Mutex,
     },
     time::Instant,
 };
 
-use log::error;
+use log::{debug, error, warn};
 
 use crate::{
-    codec::Token,
+    client::{
+        capture::{Capture, Direction},
+        progress::Progress,
+    },
+    codec::{Token, TokenLength},
     protocol::{
-        effect::{Effect, Effects, Timeout},
+        effect::{prioritize, Effect, Effects, Timeout},
+        empty_message::EmptyMessage,
         event::{Event, Events},
         new_request::NewRequest,
         ping::{self, Ping},
@@ -38,19 +43,46 @@ pub enum RequestSender {
 pub enum Command {
     Request(NewRequest, Sender<Request>),
     Cancel(Token),
+    Send(EmptyMessage),
     Ping(
         Ping,
         Sender<Result<(Token, Receiver<Result<(), ping::Error>>), ()>>,
     ),
 }
 
+/// Tokens generated for a request before giving up on finding one that isn't in use.
+const MAX_TOKEN_ATTEMPTS: usize = 16;
+
+/// Addresses to fail over to, in order, once a request times out or the transport fails against
+/// the current one.
+#[derive(Debug, Default)]
+struct Failover {
+    endpoints: Vec<SocketAddr>,
+    current: usize,
+    /// Requests that may be sent again, with the index of the endpoint they were last sent to and
+    /// the number of endpoints they were sent to.
+    requests: Vec<(Token, NewRequest, usize, usize)>,
+}
+
 #[derive(Debug)]
 pub struct System {
+    /// One byte larger than the largest datagram to receive, so a larger one fills it up.
+    buffer: Vec<u8>,
+    capture: Option<Box<dyn Capture>>,
     requests: Vec<(Token, RequestSender)>,
     command_sender: Sender<Command>,
     command_receiver: Receiver<Command>,
+    failover: Failover,
+    /// Events raised while dispatching effects, handed out by the next poll.
+    pending: Events,
+    /// Address requests are sent to on an unconnected socket, see [`System::with_any_source`].
+    peer: Option<SocketAddr>,
+    progress: Arc<Mutex<Progress>>,
     udp_socket: Arc<UdpSocket>,
     timeouts: Vec<(Instant, Timeout)>,
+    token_length: TokenLength,
+    /// Receives the messages with a reserved code the processor hands over.
+    unknown_messages: Option<Sender<Vec<u8>>>,
 }
 
 impl System {
@@ -64,46 +96,114 @@ impl System {
         let (command_sender, command_receiver) = channel();
 
         Self {
+            buffer: vec![0; PATH_MTU + 1],
+            capture: None,
             udp_socket,
             command_sender,
             command_receiver,
+            failover: Failover::default(),
+            pending: vec![],
+            peer: None,
+            progress: Default::default(),
             requests: Default::default(),
             timeouts: vec![],
+            token_length: TokenLength::decode(TokenLength::DEFAULT),
+            unknown_messages: None,
         }
     }
 
+    /// Sends requests that time out again to the next of `endpoints`, the socket has to be
+    /// connected to the first one.
+    pub fn with_failover(mut self, endpoints: Vec<SocketAddr>) -> Self {
+        self.failover.endpoints = endpoints;
+        self
+    }
+
+    /// Largest datagram received in full, [`PATH_MTU`] by default. Larger ones are cut off by the
+    /// socket and handed over as [`Event::TruncatedDataReceived`].
+    pub fn with_max_datagram_size(mut self, max_datagram_size: usize) -> Self {
+        self.buffer = vec![0; max_datagram_size + 1];
+        self
+    }
+
+    /// Length of the tokens generated for requests, [`TokenLength::DEFAULT`] by default. A token
+    /// already used by a request in flight is generated again.
+    pub fn with_token_length(mut self, token_length: TokenLength) -> Self {
+        self.token_length = token_length;
+        self
+    }
+
+    /// Sends to `peer` on an unconnected socket and takes datagrams from any address, so responses
+    /// are matched by token alone, see
+    /// [`SocketConfig::any_source`](crate::client::socket_config::SocketConfig::any_source).
+    pub fn with_any_source(mut self, peer: SocketAddr) -> Self {
+        self.peer = Some(peer);
+        self
+    }
+
+    /// Hands every datagram sent or received to `capture`.
+    pub fn with_capture(mut self, capture: Box<dyn Capture>) -> Self {
+        self.capture = Some(capture);
+        self
+    }
+
+    /// Sends the datagrams of [`Effect::UnknownMessageReceived`] to `sender` instead of dropping
+    /// them.
+    pub fn with_unknown_messages(mut self, sender: Sender<Vec<u8>>) -> Self {
+        self.unknown_messages = Some(sender);
+        self
+    }
+
+    /// Endpoint requests are sent to, `None` without failover.
+    pub fn current_endpoint(&self) -> Option<SocketAddr> {
+        self.failover.endpoints.get(self.failover.current).copied()
+    }
+
     pub fn get_sender(&self) -> Sender<Command> {
         self.command_sender.clone()
     }
 
+    /// Status of the requests yet to be resolved, to be updated after each tick.
+    pub fn progress(&self) -> Arc<Mutex<Progress>> {
+        self.progress.clone()
+    }
+
     fn on_command(&mut self, command: Command) -> Result<Event, ()> {
         match command {
             Command::Request(request, sender) => self.handle_request(request, sender),
             Command::Cancel(token) => self.handle_cancel(token),
+            Command::Send(empty_message) => Ok(Event::EmptyMessageRequested(empty_message)),
             Command::Ping(ping, sender) => self.ping(ping, sender),
         }
     }
 
     fn handle_cancel(&mut self, token: Token) -> Result<Event, ()> {
-        self.requests.retain(|(t, _)| *t == token);
         Ok(Event::TransactionCanceled(token))
     }
 
+    /// Token for a new request that no request in flight uses.
+    fn token(&self) -> Option<Token> {
+        (0..MAX_TOKEN_ATTEMPTS)
+            .map(|_| Token::random(self.token_length))
+            .find(|token| self.requests.iter().all(|(used, _)| used != token))
+    }
+
     fn ping(
         &mut self,
         ping: Ping,
         sender: Sender<Result<(Token, Receiver<Result<(), ping::Error>>), ()>>,
     ) -> Result<Event, ()> {
-        let token = Token::new().map_err(|_| ())?;
+        let token = self.token().ok_or(())?;
 
+        self.progress.lock().unwrap().accepted(token);
         let (result_sender, result_receiver) = channel();
-        if let Err(e) = sender.send(Ok((token.clone(), result_receiver))) {
+        if let Err(e) = sender.send(Ok((token, result_receiver))) {
             error!("Failed to send Request::Accepted to client: {e:?}");
             return Err(());
         }
 
         self.requests
-            .push((token.clone(), RequestSender::Ping(result_sender)));
+            .push((token, RequestSender::Ping(result_sender)));
 
         Ok(Event::TransactionRequested(NewRequest::Ping(ping), token))
     }
@@ -113,34 +213,60 @@ impl System {
         request: NewRequest,
         sender: Sender<Request>,
     ) -> Result<Event, ()> {
-        let token = Token::new().map_err(|_| ())?;
+        let token = self.token().ok_or(())?;
 
+        self.progress.lock().unwrap().accepted(token);
         let (result_sender, result_receiver) = channel();
-        if let Err(e) = sender.send(Request::Accepted(token.clone(), result_receiver)) {
+        if let Err(e) = sender.send(Request::Accepted(token, result_receiver)) {
             error!("Failed to send Request::Accepted to client: {e:?}");
             return Err(());
         }
 
         self.requests
-            .push((token.clone(), RequestSender::Request(result_sender)));
+            .push((token, RequestSender::Request(result_sender)));
+        if self.failover.endpoints.len() > 1 {
+            self.failover
+                .requests
+                .push((token, request.clone(), self.failover.current, 1));
+        }
 
         Ok(Event::TransactionRequested(request, token))
     }
 
     pub fn poll(&mut self) -> Result<Events, ()> {
-        let mut events = vec![];
+        let mut events = std::mem::take(&mut self.pending);
 
-        let mut buffer = [0u8; PATH_MTU];
-        let read = self.udp_socket.recv(&mut buffer);
+        let read = self.udp_socket.recv_from(&mut self.buffer);
+        if let Ok((read, source)) = read {
+            if self.peer.is_some_and(|peer| peer != source) {
+                debug!("Taking datagram from {source}, requests are sent to another address");
+            }
+            Self::capture(
+                &mut self.capture,
+                &self.udp_socket,
+                Direction::Received,
+                source,
+                &self.buffer[..read],
+            );
+        }
 
         match read {
-            Ok(read) => {
-                events.push(Event::DataReceived(buffer[..read].to_vec()));
+            Ok((read, _)) if read == self.buffer.len() => {
+                warn!("Dropping datagram larger than {} bytes", read - 1);
+                events.push(Event::TruncatedDataReceived(self.buffer.clone()));
             }
+            Ok((read, source)) => {
+                events.push(Event::DataReceivedFrom(
+                    source,
+                    self.buffer[..read].to_vec(),
+                ));
+            }
+            Err(e) if matches!(e.kind(), ErrorKind::WouldBlock | ErrorKind::TimedOut) => {}
+            // A connected socket reports an unreachable peer on the next receive, which fails
+            // the requests in flight and fails over like a timeout does.
             Err(e) => {
-                if e.kind() != ErrorKind::WouldBlock {
-                    return Err(());
-                }
+                debug!("Failed to receive on udp socket: {e:?}");
+                events.push(Event::TransportFailed(e.kind()));
             }
         }
 
@@ -167,12 +293,26 @@ impl System {
         Ok(events)
     }
 
+    /// Hands a message with a reserved code to the receiver of unknown messages, if there's
+    /// anyone to receive it.
+    fn on_unknown_message(&mut self, data: Vec<u8>) {
+        let Some(sender) = &self.unknown_messages else {
+            debug!("Ignoring message with reserved code: {data:?}");
+            return;
+        };
+        if let Err(e) = sender.send(data) {
+            debug!("Ignoring message with reserved code: {:?}", e.0);
+        }
+    }
+
     fn on_create_timeout(&mut self, timeout: Timeout) {
         let timeout_at = Instant::now() + *timeout.duration();
         self.timeouts.push((timeout_at, timeout))
     }
 
     fn remove_request_by_token(&mut self, token: &Token) -> Option<RequestSender> {
+        self.progress.lock().unwrap().resolved(token);
+
         let Some(position) = self
             .requests
             .iter()
@@ -184,7 +324,44 @@ impl System {
         Some(self.requests.swap_remove(position).1)
     }
 
-    fn on_transaction_resolved(&mut self, token: Token, result: Result<Response, response::Error>) {
+    fn on_transaction_resolved(
+        &mut self,
+        token: Token,
+        mut result: Result<Response, response::Error>,
+    ) {
+        let failover = self
+            .failover
+            .requests
+            .iter()
+            .position(|(request_token, ..)| *request_token == token)
+            .map(|position| self.failover.requests.swap_remove(position));
+        if let (
+            Some((token, request, endpoint, attempts)),
+            Err(
+                response::Error::Timeout
+                | response::Error::AcknowledgementTimeout
+                | response::Error::Transport(_),
+            ),
+        ) = (failover, &result)
+        {
+            if attempts < self.failover.endpoints.len() {
+                self.fail_over(endpoint);
+                self.failover.requests.push((
+                    token,
+                    request.clone(),
+                    self.failover.current,
+                    attempts + 1,
+                ));
+                self.pending
+                    .push(Event::TransactionRequested(request, token));
+                return;
+            }
+        }
+
+        if let Ok(response) = &mut result {
+            response.metrics.endpoint = self.peer_addr();
+        }
+
         let Some(request) = self.remove_request_by_token(&token) else {
             return;
         };
@@ -195,6 +372,22 @@ impl System {
         }
     }
 
+    /// Moves on to the endpoint after `endpoint`, unless another request already did.
+    fn fail_over(&mut self, endpoint: usize) {
+        if endpoint != self.failover.current {
+            return;
+        }
+
+        self.failover.current = (endpoint + 1) % self.failover.endpoints.len();
+        let address = self.failover.endpoints[self.failover.current];
+        warn!("Failing over to {address}");
+        if let Some(peer) = &mut self.peer {
+            *peer = address;
+        } else if let Err(e) = self.udp_socket.connect(address) {
+            error!("Failed to connect udp socket to {address}: {e:?}");
+        }
+    }
+
     fn on_request_resolved(
         sender: Sender<Result<Response, response::Error>>,
         result: Result<Response, response::Error>,
@@ -213,17 +406,71 @@ impl System {
         }
     }
 
+    /// Address requests are sent to.
+    fn peer_addr(&self) -> Option<SocketAddr> {
+        self.peer.or_else(|| self.udp_socket.peer_addr().ok())
+    }
+
     fn on_transmit(&mut self, data: Vec<u8>) {
-        if let Err(e) = self.udp_socket.send(&data) {
-            println!("Failed to send on udp socket: {e:?}");
+        let sent = match self.peer {
+            Some(peer) => self.udp_socket.send_to(&data, peer),
+            None => self.udp_socket.send(&data),
+        };
+
+        match (sent, self.peer_addr()) {
+            (Ok(_), Some(peer)) => Self::capture(
+                &mut self.capture,
+                &self.udp_socket,
+                Direction::Sent,
+                peer,
+                &data,
+            ),
+            (Ok(_), None) => {}
+            (Err(e), _) => {
+                debug!("Failed to send on udp socket: {e:?}");
+                self.pending.push(Event::TransmitFailed(data, e.kind()));
+            }
         }
     }
 
-    pub fn dispatch(&mut self, effects: Effects) -> Result<(), ()> {
+    fn capture(
+        capture: &mut Option<Box<dyn Capture>>,
+        udp_socket: &UdpSocket,
+        direction: Direction,
+        peer: SocketAddr,
+        data: &[u8],
+    ) {
+        let Some(capture) = capture else {
+            return;
+        };
+
+        match udp_socket.local_addr() {
+            Ok(local) => capture.datagram(direction, local, peer, data),
+            Err(e) => debug!("Failed to capture datagram: {e:?}"),
+        }
+    }
+
+    /// Carries out `effects`, acknowledgements and resets first, see [`prioritize`].
+    pub fn dispatch(&mut self, mut effects: Effects) -> Result<(), ()> {
+        prioritize(&mut effects);
         for effect in effects {
             match effect {
                 Effect::CreateTimeout(timeout) => self.on_create_timeout(timeout),
+                Effect::TransactionCanceled(token) => {
+                    self.remove_request_by_token(&token);
+                    self.failover
+                        .requests
+                        .retain(|(request_token, ..)| *request_token != token);
+                }
+                Effect::NotificationReceived(token, _) => {
+                    debug!("Canceling observation nobody receives notifications of: {token:?}");
+                    self.pending.push(Event::TransactionCanceled(token));
+                }
                 Effect::Transmit(data) => self.on_transmit(data),
+                Effect::UnknownMessageReceived(data) => self.on_unknown_message(data),
+                Effect::MalformedDatagramReceived(data) => {
+                    debug!("Ignoring malformed datagram: {data:?}");
+                }
                 Effect::TransactionResolved(token, result) => {
                     self.on_transaction_resolved(token, result);
                 }